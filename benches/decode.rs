@@ -0,0 +1,136 @@
+//! Decoding benchmarks, on the unstable `test` crate:
+//!
+//! ```text
+//! cargo +nightly bench --features nightly,testgen
+//! ```
+//!
+//! The file benchmarks need `make testdata` first.
+#![feature(test)]
+
+extern crate garminfit as fit;
+extern crate test;
+
+use fit::{
+    crc::CrcWriter,
+    messages::{
+        FileId,
+        Message,
+    },
+    profile::types::Manufacturer,
+    slice::FitSliceReader,
+    testgen::{
+        self,
+        Shape,
+    },
+    File,
+};
+use std::{
+    fs,
+    io::{
+        Cursor,
+        Write,
+    },
+    path::Path,
+};
+use test::Bencher;
+
+// Benchmark FIT file decoding. Takes the std `Bencher` and
+// the path to a `.fit` file to decode. Reads the file once
+// then puts it behind a cursor for benchmarking.
+fn bench_file_decoding(b: &mut Bencher, file: &Path) {
+    let data = Cursor::new(fs::read(file).unwrap());
+    // NOTE: is it an issue that this benchmarks the clone?
+    b.iter(|| File::decode(&mut data.clone()).unwrap());
+}
+
+macro_rules! bench_file {
+    ($name:ident, $path:expr) => {
+        #[bench]
+        fn $name(b: &mut Bencher) {
+            bench_file_decoding(
+                b,
+                &Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+            );
+        }
+    };
+}
+
+bench_file!(bench_python_fitparse_1, "testdata/python_fitparse_1.fit");
+bench_file!(bench_python_fitparse_2, "testdata/python_fitparse_2.fit");
+
+/// 10,000 one second records with every optional field.
+fn record_file() -> Vec<u8> {
+    let shape = Shape {
+        records: 10_000,
+        ..Shape::default()
+    };
+    testgen::generate(&shape, 1)
+}
+
+// Owning and borrowing decoders over the same records. The
+// borrowing one doesn't allocate at all; see `slice::tests`
+// for the counts.
+#[bench]
+fn bench_owned_records(b: &mut Bencher) {
+    let bytes = record_file();
+    b.iter(|| File::from_bytes(&bytes).unwrap());
+}
+
+#[bench]
+fn bench_borrowed_records(b: &mut Bencher) {
+    let bytes = record_file();
+    b.iter(|| {
+        FitSliceReader::new(&bytes)
+            .unwrap()
+            .map(|message| message.unwrap())
+            .count()
+    });
+}
+
+/// 1024 `FileId` messages holding only a manufacturer, a
+/// spread over the range the profile uses with a quarter or
+/// so unknown.
+fn manufacturer_file() -> Vec<u8> {
+    let mut records = vec![
+        // Definition: local 0, little endian, `FileId`, one
+        // uint16 field
+        0x40, 0, 0, 0, 0, 1, 1, 2, 0x84,
+    ];
+    for v in 0..1024u16 {
+        records.push(0);
+        records.extend_from_slice(&(v * 7 % 400).to_le_bytes());
+    }
+
+    let mut w = CrcWriter::new(Vec::new());
+    w.write_all(&[12, 0x20, 0, 0]).unwrap();
+    w.write_all(&(records.len() as u32).to_le_bytes()).unwrap();
+    w.write_all(b".FIT").unwrap();
+    w.write_all(&records).unwrap();
+    let crc = w.crc();
+    let mut bytes = w.into_inner();
+    bytes.extend_from_slice(&crc.to_le_bytes());
+    bytes
+}
+
+// Enum decoding, which goes through the lookup tables in
+// `profile::table` for the large enums. Most of the time
+// here is the rest of the message decode; the change to
+// watch for is relative.
+#[bench]
+fn bench_enum_decode(b: &mut Bencher) {
+    let bytes = manufacturer_file();
+    b.iter(|| {
+        File::from_bytes(&bytes)
+            .unwrap()
+            .messages()
+            .filter(|data| {
+                data.0.iter().any(|message| match message {
+                    Message::FileId(FileId::Manufacturer(field)) => {
+                        !matches!(field.raw_value, Manufacturer::Unknown)
+                    },
+                    _ => false,
+                })
+            })
+            .count()
+    });
+}
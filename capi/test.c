@@ -0,0 +1,83 @@
+/* Decodes a small FIT file through include/garminfit.h.
+ *
+ *   cargo build --features capi
+ *   cc -Wall -Werror -Iinclude capi/test.c -Ltarget/debug -lgarminfit -lm -o target/capi-test
+ *   LD_LIBRARY_PATH=target/debug target/capi-test
+ */
+#include <assert.h>
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "garminfit.h"
+
+static uint16_t crc(const uint8_t *bytes, size_t len) {
+    static const uint16_t table[16] = {
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401,
+        0xA001, 0x6C00, 0x7800, 0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    };
+    uint16_t crc = 0;
+    for (size_t i = 0; i < len; i++) {
+        uint16_t tmp = table[crc & 0xF];
+        crc = (crc >> 4) & 0x0FFF;
+        crc = crc ^ tmp ^ table[bytes[i] & 0xF];
+        tmp = table[crc & 0xF];
+        crc = (crc >> 4) & 0x0FFF;
+        crc = crc ^ tmp ^ table[(bytes[i] >> 4) & 0xF];
+    }
+    return crc;
+}
+
+int main(void) {
+    uint8_t file[64] = {
+        /* Header, without a header CRC; the data size is filled in below */
+        12, 0x20, 0x52, 0x08, 0, 0, 0, 0, '.', 'F', 'I', 'T',
+        /* Definition of local 0 as FileId: type, manufacturer */
+        0x40, 0, 0, 0, 0, 2, 0, 1, 0x00, 1, 2, 0x84,
+        /* FileId: activity, Garmin */
+        0x00, 4, 1, 0,
+        /* Definition of local 1 as Record: timestamp, speed */
+        0x41, 0, 0, 20, 0, 2, 253, 4, 0x86, 6, 2, 0x84,
+        /* Record: 1000 s, 5.25 m/s */
+        0x01, 0xE8, 0x03, 0, 0, 0x82, 0x14,
+    };
+    size_t data_size = 12 + 4 + 12 + 7;
+    file[4] = (uint8_t)data_size;
+    size_t len = 12 + data_size;
+    uint16_t file_crc = crc(file, len);
+    file[len++] = file_crc & 0xFF;
+    file[len++] = file_crc >> 8;
+
+    FitDecoder *decoder = fit_decoder_new(file, len);
+    FitMessage mesg;
+
+    assert(fit_decoder_next(decoder, &mesg) == 1);
+    assert(mesg.mesg_num == 0 && mesg.field_count == 2);
+    assert(mesg.fields[0].kind == FIT_FIELD_KIND_NUMBER);
+    assert(mesg.fields[0].value == 4.0);
+
+    assert(fit_decoder_next(decoder, &mesg) == 1);
+    assert(mesg.mesg_num == 20 && mesg.field_count == 2);
+    assert(mesg.fields[0].value == 1000.0);
+    assert(fabs(mesg.fields[1].value - 5.25) < 1e-9);
+
+    size_t error_len;
+    assert(fit_decoder_next(decoder, &mesg) == 0);
+    assert(fit_decoder_error(decoder, &error_len) == NULL);
+    fit_decoder_free(decoder);
+
+    /* A corrupt file CRC fails the decoder, and keeps it failed */
+    file[len - 1] ^= 0xFF;
+    decoder = fit_decoder_new(file, len);
+    while (fit_decoder_next(decoder, &mesg) == 1) {
+    }
+    assert(fit_decoder_next(decoder, &mesg) == -1);
+    const uint8_t *error = fit_decoder_error(decoder, &error_len);
+    assert(error != NULL && error_len > 0);
+    printf("corrupt file: %.*s\n", (int)error_len, (const char *)error);
+    fit_decoder_free(decoder);
+
+    fit_decoder_free(NULL);
+    puts("ok");
+    return 0;
+}
@@ -1,5 +1,4 @@
 extern crate clap;
-extern crate failure;
 extern crate garminfit as fit;
 
 use std::{
@@ -42,19 +41,6 @@ fn main() {
                 }
             }
         },
-        Err(err) => eprintln!("{}", pretty_error(&err.into())),
+        Err(err) => eprintln!("{}", err.describe()),
     };
 }
-
-/// Return a prettily formatted error, including its entire
-/// causal chain.
-fn pretty_error(err: &failure::Error) -> String {
-    let mut pretty = err.to_string();
-    let mut prev = err.as_fail();
-    while let Some(next) = prev.cause() {
-        pretty.push_str(": ");
-        pretty.push_str(&next.to_string());
-        prev = next;
-    }
-    pretty
-}
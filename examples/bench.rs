@@ -0,0 +1,327 @@
+extern crate clap;
+extern crate fitparser;
+extern crate garminfit as fit;
+extern crate libloading;
+
+use fit::testgen::{
+    self,
+    Shape,
+};
+use std::{
+    env,
+    fs,
+    os::raw::{
+        c_long,
+        c_uchar,
+        c_ulong,
+    },
+    path::Path,
+    process::Command,
+    time::Instant,
+};
+
+/// Points at a shared library built from
+/// `examples/bench_sdk_shim.c`, to include the FIT SDK's C
+/// decoder.
+const SDK_ENV: &str = "GARMINFIT_BENCH_SDK";
+
+const DECODERS: &[&str] = &["garminfit", "fitparser", "sdk"];
+
+type SdkCount = unsafe extern "C" fn(*const c_uchar, c_ulong) -> c_long;
+
+/// The messages a decoder finds in a file, `None` if it
+/// fails.
+type Count = dyn Fn(&[u8]) -> Option<u64>;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: bench example")
+        .about(
+            "decode the same fixtures with garminfit, the fitparser crate \
+             and, if GARMINFIT_BENCH_SDK names the shim library, the FIT \
+             SDK's C decoder; report the median messages per second and peak \
+             RSS of each",
+        )
+        .arg(
+            clap::Arg::with_name("runs")
+                .long("runs")
+                .takes_value(true)
+                .default_value("11")
+                .help("timed runs per decoder, of which the median counts"),
+        )
+        .arg(
+            clap::Arg::with_name("warmup")
+                .long("warmup")
+                .takes_value(true)
+                .default_value("3")
+                .help("untimed runs before those"),
+        )
+        .arg(
+            clap::Arg::with_name("smoke")
+                .long("smoke")
+                .help("one run of small fixtures, to check it all works"),
+        )
+        .arg(
+            clap::Arg::with_name("decoder")
+                .long("decoder")
+                .takes_value(true)
+                .possible_values(DECODERS)
+                .help("measure only this decoder, in this process"),
+        )
+        .arg(
+            clap::Arg::with_name("FILES")
+                .multiple(true)
+                .help("fixtures to add to the generated ones"),
+        )
+        .get_matches();
+
+    let smoke = matches.is_present("smoke");
+    let (runs, warmup) = if smoke {
+        (1, 0)
+    }
+    else {
+        (number(&matches, "runs").max(1), number(&matches, "warmup"))
+    };
+    let files: Vec<&str> =
+        matches.values_of("FILES").map(Iterator::collect).unwrap_or_default();
+    let fixtures = fixtures(smoke, &files);
+
+    if let Some(decoder) = matches.value_of("decoder") {
+        let result = measure(decoder, &fixtures, runs, warmup);
+        println!(
+            "{} {} {} {} {}",
+            decoder,
+            result.messages,
+            result.failures,
+            result.median_secs,
+            peak_rss_kb().map_or_else(|| "-".to_owned(), |kb| kb.to_string()),
+        );
+        return;
+    }
+
+    // Peak RSS is per process, so each decoder gets its own
+    let bytes: usize = fixtures.iter().map(|(_, b)| b.len()).sum();
+    println!(
+        "{} fixtures, {} bytes; median of {} runs after {} warmup\n",
+        fixtures.len(),
+        bytes,
+        runs,
+        warmup
+    );
+    println!(
+        "| decoder | messages | failed fixtures | median ms | messages/s | \
+         peak RSS |"
+    );
+    println!("|---|--:|--:|--:|--:|--:|");
+    for decoder in DECODERS {
+        if *decoder == "sdk" && env::var_os(SDK_ENV).is_none() {
+            eprintln!("skipping the FIT SDK: {} isn't set", SDK_ENV);
+            continue;
+        }
+        let mut child = Command::new(env::current_exe().unwrap());
+        child.args(["--decoder", decoder]);
+        child.args(["--runs", &runs.to_string()]);
+        child.args(["--warmup", &warmup.to_string()]);
+        if smoke {
+            child.arg("--smoke");
+        }
+        child.args(&files);
+        let output = child.output().expect("to run the decoder");
+        if !output.status.success() {
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            panic!("{} failed", decoder);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let columns: Vec<&str> = stdout.split_whitespace().collect();
+        let messages: f64 = columns[1].parse().unwrap();
+        let secs: f64 = columns[3].parse().unwrap();
+        println!(
+            "| {} | {} | {} | {:.2} | {:.0} | {} |",
+            decoder,
+            columns[1],
+            columns[2],
+            secs * 1000.0,
+            messages / secs,
+            if columns[4] == "-" {
+                "-".to_owned()
+            }
+            else {
+                format!("{} KiB", columns[4])
+            },
+        );
+    }
+}
+
+struct Measurement {
+    /// Data messages decoded in one pass over the fixtures.
+    messages:    u64,
+    /// Fixtures the decoder returned an error for.
+    failures:    u64,
+    median_secs: f64,
+}
+
+/// Decode every fixture `warmup + runs` times, timing each
+/// of the last `runs` passes.
+fn measure(
+    decoder: &str,
+    fixtures: &[(String, Vec<u8>)],
+    runs: usize,
+    warmup: usize,
+) -> Measurement {
+    let count: Box<Count> = match decoder {
+        "garminfit" => {
+            Box::new(|bytes: &[u8]| {
+                fit::reader::from_bytes(bytes).ok().map(|m| m.len() as u64)
+            })
+        },
+        "fitparser" => {
+            Box::new(|bytes: &[u8]| {
+                fitparser::from_bytes(bytes).ok().map(|m| m.len() as u64)
+            })
+        },
+        _ => {
+            let path = env::var_os(SDK_ENV).expect("GARMINFIT_BENCH_SDK");
+            // The shim has no initialisers with side effects, and
+            // the library lives as long as the process
+            let library = Box::leak(Box::new(unsafe {
+                libloading::Library::new(&path).expect("to load the shim")
+            }));
+            let sdk_count: libloading::Symbol<SdkCount> = unsafe {
+                library
+                    .get(b"fit_sdk_count_messages\0")
+                    .expect("the shim to export fit_sdk_count_messages")
+            };
+            let sdk_count = *sdk_count;
+            Box::new(move |bytes: &[u8]| {
+                let n = unsafe { sdk_count(bytes.as_ptr(), bytes.len() as _) };
+                if n < 0 {
+                    None
+                }
+                else {
+                    Some(n as u64)
+                }
+            })
+        },
+    };
+
+    let pass = || {
+        let (mut messages, mut failures) = (0, 0);
+        for (_, bytes) in fixtures {
+            match count(bytes) {
+                Some(n) => messages += n,
+                None => failures += 1,
+            }
+        }
+        (messages, failures)
+    };
+    for _ in 0..warmup {
+        pass();
+    }
+    let mut times = Vec::with_capacity(runs);
+    let mut result = (0, 0);
+    for _ in 0..runs {
+        let start = Instant::now();
+        result = pass();
+        times.push(start.elapsed().as_secs_f64());
+    }
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    Measurement {
+        messages:    result.0,
+        failures:    result.1,
+        median_secs: times[times.len() / 2],
+    }
+}
+
+/// Generated activities with fixed seeds, the files in
+/// `testdata` from `make testdata`, and `files`.
+fn fixtures(smoke: bool, files: &[&str]) -> Vec<(String, Vec<u8>)> {
+    let scale = if smoke { 10 } else { 1 };
+    let shapes = [
+        (
+            "ten minutes",
+            Shape {
+                records: 600 / scale,
+                ..Shape::default()
+            },
+        ),
+        (
+            "ten hours",
+            Shape {
+                records: 36_000 / scale,
+                laps: 10,
+                ..Shape::default()
+            },
+        ),
+        (
+            "smart recording",
+            Shape {
+                records: 6_000 / scale,
+                smart_recording: true,
+                ..Shape::default()
+            },
+        ),
+        (
+            "developer fields",
+            Shape {
+                records: 6_000 / scale,
+                developer_fields: true,
+                ..Shape::default()
+            },
+        ),
+        (
+            "big endian",
+            Shape {
+                records: 6_000 / scale,
+                big_endian: true,
+                ..Shape::default()
+            },
+        ),
+    ];
+    let mut fixtures: Vec<_> = shapes
+        .iter()
+        .enumerate()
+        .map(|(seed, (name, shape))| {
+            (name.to_string(), testgen::generate(shape, seed as u64))
+        })
+        .collect();
+
+    let mut paths: Vec<_> = if smoke {
+        Vec::new()
+    }
+    else {
+        fs::read_dir("testdata")
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|e| e == "fit"))
+            .collect()
+    };
+    paths.sort();
+    paths.extend(files.iter().map(|file| Path::new(file).to_owned()));
+    for path in paths {
+        let bytes = fs::read(&path).expect("provided file to exist");
+        fixtures.push((path.display().to_string(), bytes));
+    }
+    fixtures
+}
+
+fn number(matches: &clap::ArgMatches, name: &str) -> usize {
+    matches
+        .value_of(name)
+        .unwrap()
+        .parse()
+        .unwrap_or_else(|_| panic!("--{} to be a number", name))
+}
+
+/// The most memory this process has had resident, on Linux.
+fn peak_rss_kb() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find(|line| line.starts_with("VmHWM:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,37 @@
+/*
+ * The FIT SDK's C decoder behind the one function examples/bench.rs
+ * loads, so it can be timed on the same fixtures as the Rust decoders.
+ *
+ * Build it against the c/ directory of an SDK release, e.g.
+ *
+ *   cc -O2 -shared -fPIC -Isdk/FitSDKRelease_20.66.00/c \
+ *       examples/bench_sdk_shim.c \
+ *       sdk/FitSDKRelease_20.66.00/c/fit.c \
+ *       sdk/FitSDKRelease_20.66.00/c/fit_convert.c \
+ *       sdk/FitSDKRelease_20.66.00/c/fit_crc.c \
+ *       sdk/FitSDKRelease_20.66.00/c/fit_example.c \
+ *       -o target/libfit_sdk_shim.so
+ *
+ * and run the benchmark with GARMINFIT_BENCH_SDK=target/libfit_sdk_shim.so.
+ */
+#include "fit_convert.h"
+
+/*
+ * The number of data messages in the FIT file `data`, or -1 if the
+ * decoder gave up on it.
+ *
+ * The SDK decodes one file per FitConvert_Init, so only the first of
+ * chained files is counted.
+ */
+long fit_sdk_count_messages(const unsigned char *data, unsigned long size)
+{
+    FIT_CONVERT_RETURN ret;
+    long messages = 0;
+
+    FitConvert_Init(FIT_TRUE);
+    while ((ret = FitConvert_Read(data, (FIT_UINT32)size)) ==
+           FIT_CONVERT_MESSAGE_AVAILABLE)
+        messages++;
+
+    return ret == FIT_CONVERT_END_OF_FILE ? messages : -1;
+}
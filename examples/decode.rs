@@ -0,0 +1,42 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::prelude::*;
+use std::fs;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: decode example")
+        .about("summarise a fit file: its type, devices and laps")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let bytes = fs::read(matches.value_of("INPUT").unwrap())
+        .expect("provided file to exist");
+
+    let file = match File::from_bytes(&bytes) {
+        Ok(file) => file,
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+
+    println!("type: {:?}", file.file_type());
+    for device in file.contributing_devices() {
+        println!("device: {}", fit::devices::device_display_name(&device));
+    }
+    for (i, lap) in file.messages().filter_map(Lap::from_message).enumerate() {
+        println!(
+            "lap {}: {:.0} m in {:.0} s",
+            i + 1,
+            lap.total_distance.unwrap_or(0.0),
+            lap.total_elapsed_time.unwrap_or(0.0),
+        );
+    }
+}
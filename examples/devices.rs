@@ -0,0 +1,84 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    devices::{
+        self,
+        SensorTimeline,
+    },
+    prelude::*,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: devices example")
+        .about("list the device that recorded a fit file and its sensors")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => print!("{}", device_list(&messages)),
+        Err(err) => eprintln!("{}", err.describe()),
+    }
+}
+
+/// The recording device from the `FileId`, then every
+/// sensor by device index, with when it was paired if that
+/// changed during the file.
+fn device_list(messages: &[Data]) -> String {
+    let product = messages.iter().find_map(devices::file_product_id);
+    let product = match product {
+        Some(product) => {
+            product.name().unwrap_or_else(|| format!("{:?}", product))
+        },
+        None => "?".to_owned(),
+    };
+    let mut list = format!("recorded by: {}\n", product);
+    for sensor in SensorTimeline::from_messages(messages).sensors {
+        let name = sensor.device.as_ref().map(devices::device_display_name);
+        list.push_str(&format!(
+            "device {}: {}",
+            sensor.device_index.0,
+            name.unwrap_or_else(|| "?".to_owned())
+        ));
+        if let Some(number) = sensor.ant_device_number() {
+            list.push_str(&format!(", ANT {}", number));
+        }
+        if sensor.from.is_some() || sensor.until.is_some() {
+            let time = |t: Option<u32>| t.map_or("-".to_owned(), clock_time);
+            let (from, until) = (time(sensor.from), time(sensor.until));
+            list.push_str(&format!(", {} to {}", from, until));
+        }
+        list.push('\n');
+    }
+    list
+}
+
+fn clock_time(timestamp: u32) -> String {
+    DateTime(timestamp)
+        .to_utc()
+        .map_or_else(|| timestamp.to_string(), |t| t.format("%T").to_string())
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn recording_device() {
+        let messages =
+            fit::from_bytes(&generate(&Shape::default(), 1)).unwrap();
+        let list = device_list(&messages);
+        assert!(list.starts_with("recorded by: "), "{}", list);
+        assert!(!list.starts_with("recorded by: ?"), "{}", list);
+    }
+}
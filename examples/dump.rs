@@ -1,11 +1,7 @@
 extern crate clap;
-extern crate failure;
 extern crate garminfit as fit;
 
-use std::{
-    fs::File,
-    io::BufReader,
-};
+use std::fs;
 
 fn main() {
     // Create the tiny CLI
@@ -19,30 +15,39 @@ fn main() {
         )
         .get_matches();
 
-    let file = File::open(matches.value_of("INPUT").unwrap())
+    let bytes = fs::read(matches.value_of("INPUT").unwrap())
         .expect("provided file to exist");
 
-    let mut reader = BufReader::new(file);
-
-    match fit::File::decode(&mut reader) {
-        Ok(decoded) => {
-            for record in decoded.records {
-                println!("Record: {:?}", record);
-            }
-        },
-        Err(err) => eprintln!("{}", pretty_error(&err.into())),
+    match fit::File::from_bytes(&bytes) {
+        Ok(decoded) => print!("{}", dump(&decoded)),
+        Err(err) => eprintln!("{}", err.describe()),
     };
 }
 
-/// Return a prettily formatted error, including its entire
-/// causal chain.
-fn pretty_error(err: &failure::Error) -> String {
-    let mut pretty = err.to_string();
-    let mut prev = err.as_fail();
-    while let Some(next) = prev.cause() {
-        pretty.push_str(": ");
-        pretty.push_str(&next.to_string());
-        prev = next;
+/// Every record, definitions included, a line each.
+fn dump(file: &fit::File) -> String {
+    file.records
+        .iter()
+        .map(|record| format!("Record: {:?}\n", record))
+        .collect()
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn line_per_record() {
+        let shape = Shape {
+            records: 10,
+            ..Shape::default()
+        };
+        let file = fit::File::from_bytes(&generate(&shape, 1)).unwrap();
+        assert_eq!(dump(&file).lines().count(), file.records.len());
+        assert!(dump(&file).starts_with("Record: Record { header: "));
     }
-    pretty
 }
@@ -0,0 +1,84 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    messages::{
+        field_name,
+        field_units,
+    },
+    prelude::*,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: filter example")
+        .about("print the messages of one type in a fit file, field by field")
+        .arg(
+            clap::Arg::with_name("MESSAGE")
+                .help("the message type, by name (e.g. DeviceInfo) or number")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(2),
+        )
+        .get_matches();
+
+    let message = matches.value_of("MESSAGE").unwrap();
+    let mesg_num = match message.parse::<MesgNum>() {
+        Ok(mesg_num) => mesg_num.value(),
+        Err(()) => message.parse().expect("a message name or number"),
+    };
+    match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => print!("{}", filter(&messages, mesg_num)),
+        Err(err) => eprintln!("{}", err.describe()),
+    }
+}
+
+/// The `mesg_num` messages, a line each of their valid
+/// fields with units.
+fn filter(messages: &[Data], mesg_num: u16) -> String {
+    let mut lines = String::new();
+    for mesg in messages.iter().filter(|m| m.mesg_num() == Some(mesg_num)) {
+        let fields: Vec<String> = mesg
+            .0
+            .iter()
+            .filter(|field| field.value() != Value::Invalid)
+            .map(|field| {
+                let num = field.field_def_num();
+                let name = field_name(mesg_num, num)
+                    .map_or_else(|| format!("field_{}", num), str::to_owned);
+                let units = field_units(mesg_num, num).unwrap_or("");
+                format!("{}={:?}{}", name, field.value(), units)
+            })
+            .collect();
+        lines.push_str(&fields.join(" "));
+        lines.push('\n');
+    }
+    lines
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn laps_only() {
+        let shape = Shape {
+            laps: 4,
+            ..Shape::default()
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        let laps = filter(&messages, MesgNum::Lap.value());
+        assert_eq!(laps.lines().count(), 4);
+        assert!(laps.starts_with("message_index=Number(0.0) timestamp="));
+        assert_eq!(filter(&messages, MesgNum::Hrv.value()), "");
+    }
+}
@@ -0,0 +1,107 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    altitude::{
+        self,
+        Policy,
+    },
+    prelude::*,
+    strings::escape_xml,
+    workout::Workout,
+};
+use std::{
+    fmt::Write,
+    path::Path,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: gpx example")
+        .about("write the track of a fit file as gpx to stdout")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let input = matches.value_of("INPUT").unwrap();
+    let messages = match fit::from_file(input) {
+        Ok(messages) => messages,
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+    // The workout's name, or else the file's
+    let name = messages
+        .iter()
+        .filter_map(Workout::from_message)
+        .find_map(|workout| workout.name)
+        .unwrap_or_else(|| {
+            let stem = Path::new(input).file_stem().unwrap_or_default();
+            stem.to_string_lossy().into_owned()
+        });
+    print!("{}", gpx(&messages, &name));
+}
+
+/// The records with positions as a GPX track called
+/// `name`.
+fn gpx(messages: &[Data], name: &str) -> String {
+    let records = RecordTimeSeries::from_messages(messages);
+    let altitude = altitude::select(messages, Policy::Auto);
+
+    // Writing to a string can't fail
+    let mut gpx = String::new();
+    writeln!(gpx, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        gpx,
+        r#"<gpx version="1.1" creator="garminfit" xmlns="http://www.topografix.com/GPX/1/1">"#
+    )
+    .unwrap();
+    writeln!(gpx, "<trk><name>{}</name><trkseg>", escape_xml(name)).unwrap();
+    for point in records.iter() {
+        let position = match point.position() {
+            Some(position) => position,
+            None => continue,
+        };
+        let (lat, lon) = (position.lat, position.lon);
+        writeln!(gpx, r#"<trkpt lat="{}" lon="{}">"#, lat, lon).unwrap();
+        let elevation = altitude.at(point.timestamp);
+        if elevation.is_finite() {
+            writeln!(gpx, "<ele>{:.1}</ele>", elevation).unwrap();
+        }
+        if let Some(time) = DateTime(point.timestamp).to_utc() {
+            let time = time.format("%Y-%m-%dT%H:%M:%SZ");
+            writeln!(gpx, "<time>{}</time>", time).unwrap();
+        }
+        writeln!(gpx, "</trkpt>").unwrap();
+    }
+    writeln!(gpx, "</trkseg></trk>\n</gpx>").unwrap();
+    gpx
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn track_point_per_record() {
+        let shape = Shape {
+            records: 60,
+            ..Shape::default()
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        let gpx = gpx(&messages, "Morning & evening");
+        assert!(gpx.contains("<name>Morning &amp; evening</name>"));
+        assert_eq!(gpx.matches("<trkpt ").count(), 60);
+        assert_eq!(gpx.matches("<ele>").count(), 60);
+        assert!(gpx.ends_with("</trkpt>\n</trkseg></trk>\n</gpx>\n"));
+    }
+}
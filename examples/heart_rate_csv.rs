@@ -0,0 +1,70 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::prelude::*;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: heart rate csv example")
+        .about(
+            "write the heart rate of an activity over time as csv to stdout, \
+             ready to plot",
+        )
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => print!("{}", heart_rate_csv(&messages)),
+        Err(err) => eprintln!("{}", err.describe()),
+    }
+}
+
+/// Seconds since the first record and the heart rate then,
+/// leaving out records without one.
+fn heart_rate_csv(messages: &[Data]) -> String {
+    let records = RecordTimeSeries::from_messages(messages);
+    let start = records.iter().map(|point| point.timestamp).min();
+    let mut csv = String::from("seconds,heart_rate\n");
+    for point in records.iter() {
+        if let (Some(start), Some(heart_rate)) = (start, point.heart_rate) {
+            let seconds = point.timestamp - start;
+            csv.push_str(&format!("{},{}\n", seconds, heart_rate));
+        }
+    }
+    csv
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn one_row_per_record() {
+        let shape = Shape {
+            records: 60,
+            ..Shape::default()
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        let csv = heart_rate_csv(&messages);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 1 + 60);
+        assert!(rows[1].starts_with("0,"));
+        assert!(rows[60].starts_with("59,"));
+
+        let shape = Shape {
+            heart_rate: false,
+            ..shape
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        assert_eq!(heart_rate_csv(&messages), "seconds,heart_rate\n");
+    }
+}
@@ -0,0 +1,290 @@
+extern crate clap;
+#[macro_use]
+extern crate crossterm;
+extern crate garminfit as fit;
+
+use crossterm::{
+    cursor::{
+        Hide,
+        MoveTo,
+        Show,
+    },
+    event::{
+        self,
+        Event,
+        KeyCode,
+        KeyEventKind,
+    },
+    style::Print,
+    terminal::{
+        self,
+        Clear,
+        ClearType,
+        EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use fit::inspect::{
+    Node,
+    Raw,
+    Tree,
+};
+use std::{
+    collections::HashSet,
+    io::{
+        self,
+        Write,
+    },
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: inspect example")
+        .about("browse the messages and fields of a file in the terminal")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let messages = match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => messages,
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+    let mut inspector = Inspector::new(Tree::build(&messages, Raw::Keep));
+    if let Err(err) = run(&mut inspector) {
+        eprintln!("{}", err);
+    }
+}
+
+/// What's expanded, and where the cursor and search are.
+struct Inspector {
+    tree:     Tree,
+    expanded: HashSet<Node>,
+    cursor:   usize,
+    /// Being typed, or the last one run.
+    search:   String,
+    typing:   bool,
+}
+
+impl Inspector {
+    fn new(tree: Tree) -> Inspector {
+        Inspector {
+            tree,
+            expanded: [Node::Root].iter().cloned().collect(),
+            cursor: 0,
+            search: String::new(),
+            typing: false,
+        }
+    }
+
+    /// The nodes on show, with how deep each is.
+    fn rows(&self) -> Vec<(usize, Node)> {
+        let mut rows = Vec::new();
+        let mut stack = vec![(0, Node::Root)];
+        while let Some((depth, node)) = stack.pop() {
+            rows.push((depth, node));
+            if self.expanded.contains(&node) {
+                let children = self.tree.children(node);
+                stack
+                    .extend(children.into_iter().rev().map(|c| (depth + 1, c)));
+            }
+        }
+        rows
+    }
+
+    fn selected(&self) -> Node {
+        self.rows()[self.cursor].1
+    }
+
+    fn select(&mut self, node: Node) {
+        let mut parent = self.tree.parent(node);
+        while let Some(node) = parent {
+            self.expanded.insert(node);
+            parent = self.tree.parent(node);
+        }
+        self.cursor =
+            self.rows().iter().position(|&(_, n)| n == node).unwrap_or(0);
+    }
+
+    /// Move to the next field matching the search after the
+    /// cursor, wrapping around.
+    fn next_match(&mut self) {
+        let matches = self.tree.search(&self.search);
+        let after = match self.selected() {
+            Node::Field(field) => field + 1,
+            _ => 0,
+        };
+        let next = matches
+            .iter()
+            .find(|node| matches!(node, Node::Field(field) if *field >= after))
+            .or_else(|| matches.first());
+        if let Some(&node) = next {
+            self.select(node);
+        }
+    }
+
+    /// Handle a key, returning whether to carry on.
+    fn key(&mut self, code: KeyCode) -> bool {
+        if self.typing {
+            match code {
+                KeyCode::Char(c) => self.search.push(c),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                },
+                KeyCode::Enter => {
+                    self.typing = false;
+                    self.next_match();
+                },
+                KeyCode::Esc => self.typing = false,
+                _ => (),
+            }
+            return true;
+        }
+
+        let last = self.rows().len() - 1;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.cursor = self.cursor.saturating_sub(1)
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.cursor = (self.cursor + 1).min(last)
+            },
+            KeyCode::Right | KeyCode::Enter | KeyCode::Char('l') => {
+                self.expanded.insert(self.selected());
+            },
+            KeyCode::Left | KeyCode::Char('h') => {
+                let node = self.selected();
+                if !self.expanded.remove(&node) {
+                    if let Some(parent) = self.tree.parent(node) {
+                        self.expanded.remove(&parent);
+                        self.select(parent);
+                    }
+                }
+            },
+            KeyCode::Char('/') => {
+                self.typing = true;
+                self.search.clear();
+            },
+            KeyCode::Char('n') => self.next_match(),
+            _ => (),
+        }
+        true
+    }
+
+    /// The lines for a screen `height` rows high, the
+    /// cursor's in view and marked.
+    fn lines(&self, height: usize) -> Vec<String> {
+        let rows = self.rows();
+        let height = height.saturating_sub(1).max(1);
+        let top = (self.cursor + 1).saturating_sub(height);
+        let mut lines: Vec<String> = rows
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(height)
+            .map(|(i, &(depth, node))| {
+                let marker = if i == self.cursor { ">" } else { " " };
+                let fold = match node {
+                    Node::Field(_) => " ",
+                    _ if self.expanded.contains(&node) => "-",
+                    _ => "+",
+                };
+                let label = self.tree.label(node);
+                format!("{}{}{} {}", marker, "  ".repeat(depth), fold, label)
+            })
+            .collect();
+        lines.push(
+            if self.typing {
+                format!("/{}", self.search)
+            }
+            else {
+                "arrows to move, / to search, n for the next match, q to quit"
+                    .to_owned()
+            },
+        );
+        lines
+    }
+}
+
+fn run(inspector: &mut Inspector) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen, Hide)?;
+    let result = (|| -> io::Result<()> {
+        loop {
+            let (width, height) = terminal::size()?;
+            queue!(out, Clear(ClearType::All))?;
+            for (row, line) in
+                inspector.lines(usize::from(height)).iter().enumerate()
+            {
+                let line: String =
+                    line.chars().take(usize::from(width)).collect();
+                queue!(out, MoveTo(0, row as u16), Print(line))?;
+            }
+            out.flush()?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !inspector.key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+    execute!(out, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    fn inspector() -> Inspector {
+        let shape = Shape {
+            records: 5,
+            ..Shape::default()
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        Inspector::new(Tree::build(&messages, Raw::Keep))
+    }
+
+    #[test]
+    fn browse_and_search() {
+        let mut inspector = inspector();
+        let groups = inspector.tree.groups.len();
+        assert_eq!(inspector.rows().len(), 1 + groups);
+
+        // Down to the first group and open it
+        inspector.key(KeyCode::Down);
+        inspector.key(KeyCode::Enter);
+        let messages = inspector.tree.groups[0].messages.len();
+        assert_eq!(inspector.rows().len(), 1 + groups + messages);
+        inspector.key(KeyCode::Left);
+        assert_eq!(inspector.rows().len(), 1 + groups);
+
+        for c in "/heart_rate".chars() {
+            inspector.key(KeyCode::Char(c));
+        }
+        inspector.key(KeyCode::Enter);
+        let first = inspector.selected();
+        assert!(inspector.tree.label(first).starts_with("heart_rate: "));
+        inspector.key(KeyCode::Char('n'));
+        assert!(inspector.selected() != first);
+
+        let lines = inspector.lines(10);
+        assert_eq!(lines.len(), 10);
+        assert!(lines.iter().any(|line| line.starts_with(">")));
+        assert!(!inspector.key(KeyCode::Char('q')));
+    }
+}
@@ -0,0 +1,80 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::prelude::*;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: laps example")
+        .about("print a table of the laps of an activity")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => print!("{}", lap_table(&messages)),
+        Err(err) => eprintln!("{}", err.describe()),
+    }
+}
+
+/// A row per lap of its time, distance, pace and averages,
+/// with `-` for what the lap doesn't have.
+fn lap_table(messages: &[Data]) -> String {
+    let mut table = format!(
+        "{:>3}  {:>12}  {:>8}  {:>7}  {:>4}  {:>5}\n",
+        "lap", "time", "km", "min/km", "bpm", "W"
+    );
+    let laps = messages.iter().filter_map(Lap::from_message);
+    for (i, lap) in laps.enumerate() {
+        let km = lap.total_distance.map(|m| m / 1000.0);
+        let pace = match (lap.timer_time(), km) {
+            (Some(time), Some(km)) if km > 0.0 => {
+                let secs = (time.as_secs_f64() / km).round() as u64;
+                format!("{}:{:02}", secs / 60, secs % 60)
+            },
+            _ => "-".to_owned(),
+        };
+        table.push_str(&format!(
+            "{:>3}  {:>12}  {:>8}  {:>7}  {:>4}  {:>5}\n",
+            i + 1,
+            or_dash(lap.timer_time()),
+            or_dash(km.map(|km| format!("{:.2}", km))),
+            pace,
+            or_dash(lap.avg_heart_rate),
+            or_dash(lap.avg_power),
+        ));
+    }
+    table
+}
+
+fn or_dash<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn row_per_lap() {
+        let shape = Shape {
+            records: 600,
+            laps: 3,
+            ..Shape::default()
+        };
+        let messages = fit::from_bytes(&generate(&shape, 1)).unwrap();
+        let table = lap_table(&messages);
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows.len(), 1 + 3);
+        assert!(rows[1].starts_with("  1   0:03:19.000"), "{}", rows[1]);
+        assert!(rows[1].ends_with("     -      -"), "{}", rows[1]);
+    }
+}
@@ -0,0 +1,101 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    prelude::*,
+    reader::ReadWarning,
+    record,
+};
+use std::fs;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: lenient example")
+        .about(
+            "decode as much of a damaged fit file as possible, reporting what \
+             was wrong with it and how much of it is missing",
+        )
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let bytes = fs::read(matches.value_of("INPUT").unwrap())
+        .expect("provided file to exist");
+    let (messages, warnings, error) = recover(&bytes);
+    for warning in warnings {
+        eprintln!("warning: {:?}", warning);
+    }
+    if let Some(err) = error {
+        eprintln!("stopped: {}", err.describe());
+    }
+    println!("recovered {} messages", messages.len());
+
+    if let Ok(report) = fit::diagnose::truncation_from_bytes(&bytes) {
+        for mode in &report.modes {
+            eprintln!("truncated: {:?}", mode);
+        }
+        if let (true, Some(completeness)) =
+            (report.is_truncated(), report.completeness())
+        {
+            println!("about {:.0} % of the activity", completeness * 100.0);
+        }
+    }
+}
+
+/// The data messages before the first error, what was
+/// recovered from on the way, and the error.
+///
+/// Zero padding is skipped and a CRC mismatch, reported as
+/// an error once everything else has been decoded, doesn't
+/// lose any messages.
+fn recover(bytes: &[u8]) -> (Vec<Data>, Vec<ReadWarning>, Option<Error>) {
+    let mut reader = match FitReader::new(bytes) {
+        Ok(reader) => reader.skip_zero_padding(),
+        Err(err) => return (Vec::new(), Vec::new(), Some(err)),
+    };
+    let mut messages = Vec::new();
+    let mut error = None;
+    for record in reader.by_ref() {
+        match record {
+            Ok(Record {
+                content: record::Message::Data(data), ..
+            }) => messages.push(data),
+            Ok(_) => (),
+            Err(err) => {
+                error = Some(err);
+                break;
+            },
+        }
+    }
+    (messages, reader.warnings().to_vec(), error)
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn keeps_what_decoded() {
+        let mut bytes = generate(&Shape::default(), 1);
+        let all = fit::from_bytes(&bytes).unwrap().len();
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        let (messages, _, error) = recover(&bytes);
+        assert_eq!(messages.len(), all);
+        assert!(error.unwrap().describe().contains("CRC"));
+
+        bytes.truncate(bytes.len() / 2);
+        let (messages, _, error) = recover(&bytes);
+        assert!(messages.len() > all / 3 && messages.len() < all);
+        assert!(error.is_some());
+    }
+}
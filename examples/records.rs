@@ -0,0 +1,59 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::prelude::*;
+use std::{
+    fs,
+    io::BufReader,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: records example")
+        .about("print the records of a fit file as they're read")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let file = fs::File::open(matches.value_of("INPUT").unwrap())
+        .expect("provided file to exist");
+
+    let reader = match FitReader::new(BufReader::new(file)) {
+        Ok(reader) => reader,
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+
+    // Records are read one at a time, so this starts
+    // printing straight away however big the file is
+    for record in reader {
+        let data = match record {
+            Ok(Record {
+                content: fit::record::Message::Data(data), ..
+            }) => data,
+            Ok(_) => continue,
+            Err(err) => {
+                eprintln!("{}", err.describe());
+                return;
+            },
+        };
+        if let Some(point) = RecordPoint::from_message(&data) {
+            println!(
+                "{} heart rate {:?} power {:?}",
+                point.timestamp, point.heart_rate, point.power
+            );
+        }
+        else {
+            // Any other message, field by field
+            for field in &data.0 {
+                println!("  {:?}", field.value());
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    monitoring,
+    prelude::*,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: steps example")
+        .about(
+            "print the steps taken each day in monitoring fit files, against \
+             the step goal",
+        )
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit files to use, e.g. a week of them")
+                .required(true)
+                .multiple(true)
+                .index(1),
+        )
+        .get_matches();
+
+    // A day's monitoring is spread over several files
+    let mut messages = Vec::new();
+    for input in matches.values_of("INPUT").unwrap() {
+        match fit::from_file(input) {
+            Ok(more) => messages.extend(more),
+            Err(err) => eprintln!("{}: {}", input, err.describe()),
+        }
+    }
+    print!("{}", steps_per_day(&messages));
+}
+
+/// A line per day of the steps taken against the goal, and
+/// the intensity minutes.
+fn steps_per_day(messages: &[Data]) -> String {
+    let mut lines = String::new();
+    // The goals are among the messages too
+    for day in monitoring::daily_goal_progress(messages, messages) {
+        let date = day.date.map_or("?".to_owned(), |date| date.to_string());
+        lines.push_str(&format!("{}: {} steps", date, day.steps_taken));
+        if day.step_goal > 0 {
+            lines.push_str(&format!(
+                " of {} ({:.0} %)",
+                day.step_goal, day.step_completion_pct
+            ));
+        }
+        lines.push_str(&format!(
+            ", {} intensity minutes\n",
+            day.intensity_minutes
+        ));
+    }
+    lines
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn activities_have_no_days() {
+        let messages =
+            fit::from_bytes(&generate(&Shape::default(), 1)).unwrap();
+        assert_eq!(steps_per_day(&messages), "");
+    }
+}
@@ -0,0 +1,80 @@
+extern crate clap;
+extern crate garminfit as fit;
+
+use fit::{
+    prelude::*,
+    sport::Canonical,
+};
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: summary example")
+        .about("print the sport, times, distance and averages of an activity")
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    let messages = match fit::from_file(matches.value_of("INPUT").unwrap()) {
+        Ok(messages) => messages,
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+    match summary(&messages) {
+        Some(summary) => print!("{}", summary),
+        None => eprintln!("no session with a start time, sport and totals"),
+    }
+}
+
+/// The first session, one quantity to a line.
+fn summary(messages: &[Data]) -> Option<String> {
+    let mut session = messages.iter().find_map(SessionSummary::from_message)?;
+    // Taking into account devices with their own ways
+    let kind = session.classify(&Canonical::default(), messages);
+    let mut lines = vec![
+        format!("sport: {:?}", session.sport),
+        format!("kind: {:?}", kind),
+    ];
+    if let Some(start) = DateTime(session.start_time).to_utc() {
+        lines.push(format!("start: {}", start.format("%Y-%m-%d %H:%M:%S UTC")));
+    }
+    lines.push(format!("elapsed: {}", session.elapsed()));
+    if let Some(timer) = session.timer() {
+        lines.push(format!("timer: {}", timer));
+    }
+    lines.push(format!("distance: {:.2} km", session.total_distance_m / 1e3));
+    if let Some(speed) = session.avg_speed_ms {
+        lines.push(format!("average speed: {:.1} km/h", speed * 3.6));
+    }
+    if let Some(heart_rate) = session.avg_heart_rate {
+        lines.push(format!("average heart rate: {} bpm", heart_rate));
+    }
+    if let Some(power) = session.avg_power {
+        lines.push(format!("average power: {} W", power));
+    }
+    Some(lines.iter().map(|line| format!("{}\n", line)).collect())
+}
+
+#[cfg(all(test, feature = "testgen"))]
+mod tests {
+    use super::*;
+    use fit::testgen::{
+        generate,
+        Shape,
+    };
+
+    #[test]
+    fn ten_minutes() {
+        let messages =
+            fit::from_bytes(&generate(&Shape::default(), 1)).unwrap();
+        let summary = summary(&messages).unwrap();
+        assert!(summary.starts_with("sport: "));
+        assert!(summary.contains("\nelapsed: 0:09:59.000\n"));
+        assert!(summary.contains("\ntimer: 0:09:59.000\n"));
+    }
+}
@@ -0,0 +1,53 @@
+extern crate clap;
+extern crate garminfit as fit;
+extern crate tracing_subscriber;
+
+use fit::reader::FitReader;
+use std::fs;
+use tracing_subscriber::EnvFilter;
+
+fn main() {
+    // Create the tiny CLI
+    let matches = clap::App::new("garminfit: trace example")
+        .about(
+            "decode a fit file, logging what the decoder does to stderr; \
+             filter with RUST_LOG, e.g. RUST_LOG=garminfit=trace",
+        )
+        .arg(
+            clap::Arg::with_name("INPUT")
+                .help("the .fit file to use")
+                .required(true)
+                .index(1),
+        )
+        .get_matches();
+
+    // Definitions, warnings and errors unless told otherwise
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("garminfit=debug"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let file = fs::File::open(matches.value_of("INPUT").unwrap())
+        .expect("provided file to exist");
+
+    let mut reader = match FitReader::new(file) {
+        Ok(reader) => reader.skip_zero_padding(),
+        Err(err) => {
+            eprintln!("{}", err.describe());
+            return;
+        },
+    };
+    let mut records = 0;
+    for record in reader.by_ref() {
+        match record {
+            Ok(_) => records += 1,
+            Err(err) => {
+                eprintln!("{}", err.describe());
+                break;
+            },
+        }
+    }
+    println!("{} records, {} warnings", records, reader.warnings().len());
+}
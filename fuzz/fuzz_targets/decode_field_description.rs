@@ -0,0 +1,55 @@
+//! Feed arbitrary bytes to the decoder right after a
+//! `FieldDescription` definition and a `Record` definition
+//! with developer fields, so the fuzzer starts out in
+//! developer field metadata rather than failing the header
+//! checks. That metadata is written by whichever Connect IQ
+//! app recorded the file, so deserves a closer look than
+//! the rest.
+//!
+//! Records are read one at a time and the file CRC, which
+//! the fuzzer would rarely get right, is never reached.
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate garminfit;
+
+use garminfit::FitReader;
+
+#[rustfmt::skip]
+const DEFINITIONS: &[u8] = &[
+    // Local 0: FieldDescription, with its developer data
+    // index, field number, base type, name, units and
+    // native message number
+    0x40, 0, 0, 206, 0, 6,
+    0, 1, 0x02,
+    1, 1, 0x02,
+    2, 1, 0x02,
+    3, 64, 0x07,
+    8, 16, 0x07,
+    14, 2, 0x84,
+    // Local 1: Record, with a timestamp and two developer
+    // fields of the first developer
+    0x61, 0, 0, 20, 0, 1,
+    253, 4, 0x86,
+    2,
+    0, 4, 0,
+    1, 255, 0,
+];
+
+fuzz_target!(|data: &[u8]| {
+    let data_size = (DEFINITIONS.len() + data.len()) as u32;
+    let mut bytes = vec![12, 0x20];
+    bytes.extend_from_slice(&2066u16.to_le_bytes());
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+    bytes.extend_from_slice(b".FIT");
+    bytes.extend_from_slice(DEFINITIONS);
+    bytes.extend_from_slice(data);
+
+    if let Ok(reader) = FitReader::new(&bytes[..]) {
+        for record in reader {
+            if record.is_err() {
+                break;
+            }
+        }
+    }
+});
@@ -0,0 +1,13 @@
+//! Feed arbitrary bytes to the decoder. It should only ever
+//! return `Ok` or `Err`, never panic.
+//!
+//! See FUZZING.md at the root of the repository for seeding
+//! the corpus and running with sanitizers.
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate garminfit;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = garminfit::File::from_bytes(data);
+});
@@ -0,0 +1,138 @@
+#ifndef GARMINFIT_H
+#define GARMINFIT_H
+
+/* Generated by cbindgen from src/capi.rs. Don't edit. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * What a [`FitField`] holds.
+ *
+ * [`FitField`]: struct.FitField.html
+ */
+typedef enum FitFieldKind {
+  /**
+   * `value` holds the scaled value.
+   */
+  FIT_FIELD_KIND_NUMBER,
+  /**
+   * `string` and `string_len` hold the value.
+   */
+  FIT_FIELD_KIND_STRING,
+  /**
+   * The field holds its base type's invalid value.
+   */
+  FIT_FIELD_KIND_INVALID,
+  /**
+   * Bytes, arrays and strings that aren't UTF-8, which
+   * aren't converted.
+   */
+  FIT_FIELD_KIND_OTHER,
+} FitFieldKind;
+
+/**
+ * Decodes a FIT file held in memory. Opaque to C.
+ */
+typedef struct FitDecoder FitDecoder;
+
+/**
+ * One field of a data message.
+ */
+typedef struct FitField {
+  /**
+   * The field definition number.
+   */
+  uint8_t num;
+  /**
+   * The base type number from the field definition.
+   */
+  uint8_t base_type;
+  enum FitFieldKind kind;
+  /**
+   * The value scaled as the profile says, or NaN unless
+   * `kind` is `Number`.
+   */
+  double value;
+  /**
+   * UTF-8 bytes, or null unless `kind` is `String`.
+   * Valid until the decoder is freed.
+   */
+  const uint8_t *string;
+  size_t string_len;
+} FitField;
+
+/**
+ * A data message. Definition messages are never returned.
+ */
+typedef struct FitMessage {
+  uint16_t mesg_num;
+  size_t field_count;
+  /**
+   * `field_count` fields, in record order, valid until
+   * the next call on the decoder.
+   */
+  const struct FitField *fields;
+} FitMessage;
+
+/**
+ * Start decoding a copy of the `len` bytes at `data`.
+ *
+ * Never returns null. If the file header can't be read,
+ * the decoder starts out failed: [`fit_decoder_next`]
+ * returns -1 and [`fit_decoder_error`] says why.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, or may be
+ * null if `len` is zero.
+ *
+ * [`fit_decoder_next`]: fn.fit_decoder_next.html
+ * [`fit_decoder_error`]: fn.fit_decoder_error.html
+ */
+struct FitDecoder *fit_decoder_new(const uint8_t *data, size_t len);
+
+/**
+ * Decode the next data message into `out`.
+ *
+ * Returns 1 if a message was decoded, 0 at the end of the
+ * file and -1 on error, after which the decoder stays
+ * failed.
+ *
+ * # Safety
+ *
+ * `decoder` must come from [`fit_decoder_new`] and not
+ * have been freed, and `out` must be writable.
+ *
+ * [`fit_decoder_new`]: fn.fit_decoder_new.html
+ */
+int fit_decoder_next(struct FitDecoder *decoder, struct FitMessage *out);
+
+/**
+ * Why the decoder failed, as UTF-8 with its length stored
+ * in `len`, or null if it hasn't. Valid until the decoder
+ * is freed.
+ *
+ * # Safety
+ *
+ * `decoder` must come from [`fit_decoder_new`] and not
+ * have been freed, and `len` must be writable.
+ *
+ * [`fit_decoder_new`]: fn.fit_decoder_new.html
+ */
+const uint8_t *fit_decoder_error(const struct FitDecoder *decoder, size_t *len);
+
+/**
+ * Free a decoder, and with it every message and string it
+ * returned. Does nothing if `decoder` is null.
+ *
+ * # Safety
+ *
+ * `decoder` must be null or come from [`fit_decoder_new`]
+ * and not have been freed already.
+ *
+ * [`fit_decoder_new`]: fn.fit_decoder_new.html
+ */
+void fit_decoder_free(struct FitDecoder *decoder);
+
+#endif  /* GARMINFIT_H */
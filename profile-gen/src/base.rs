@@ -8,3 +8,18 @@ lazy_static! {
         ].iter().cloned().map(String::from).collect()
     };
 }
+
+/// The invalid value of the integer base type `base_type`,
+/// as the SDK defines it, or `None` for other base types.
+pub fn invalid_value(base_type: &str) -> Option<&'static str> {
+    match base_type {
+        "enum" | "uint8" => Some("0xFF"),
+        "sint8" => Some("0x7F"),
+        "uint16" => Some("0xFFFF"),
+        "sint16" => Some("0x7FFF"),
+        "uint32" => Some("0xFFFFFFFF"),
+        "sint32" => Some("0x7FFFFFFF"),
+        "uint8z" | "uint16z" | "uint32z" => Some("0"),
+        _ => None,
+    }
+}
@@ -1,6 +1,3 @@
-#![feature(slice_patterns)]
-#![feature(stmt_expr_attributes)]
-#![feature(custom_attribute)]
 #![allow(dead_code)]
 #![allow(unused_attributes)]
 #![recursion_limit = "128"]
@@ -17,6 +14,7 @@ extern crate quote;
 
 pub mod base;
 pub mod error;
+pub mod supplement;
 pub mod util;
 pub mod worksheet;
 
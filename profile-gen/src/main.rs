@@ -16,6 +16,7 @@ use std::{
 use profile_gen::{
     error::Error,
     open_workbook,
+    supplement,
     worksheet,
     TokenStream,
     Xlsx,
@@ -76,7 +77,8 @@ fn generate_modules(
 ) -> Result<(TokenStream, TokenStream), failure::Error> {
     // Process the "Types" worksheet
     let types_sheet = worksheet::types::open_sheet(workbook)?;
-    let types = worksheet::types::extract(&types_sheet);
+    let mut types = worksheet::types::extract(&types_sheet);
+    supplement::extend_types(&mut types);
     let types_tokens =
         worksheet::types::generate_module(fit_sdk_version, &types)?;
 
@@ -94,11 +96,13 @@ fn generate_modules(
 
     // Process the "Messages" worksheet
     let mesgs_sheet = worksheet::messages::open_sheet(workbook)?;
-    let mesgs = worksheet::messages::extract(&mesgs_sheet);
+    let mut mesgs = worksheet::messages::extract(&mesgs_sheet);
+    supplement::extend_messages(&mut mesgs);
     let mesgs_tokens = worksheet::messages::generate_module(
         fit_sdk_version,
         &mesgs,
         mesg_nums,
+        &types,
     )?;
 
     // Ok, return token streams
@@ -128,7 +132,7 @@ impl<'a> From<clap::ArgMatches<'a>> for Options {
                 matches.value_of("types_module_path").unwrap_or("types.rs"),
             ),
             mesgs_module_path: PathBuf::from(
-                matches.value_of("mess_module_path").unwrap_or("messages.rs"),
+                matches.value_of("mesgs_module_path").unwrap_or("messages.rs"),
             ),
             fit_sdk_version:   matches
                 .value_of("fit_sdk_version")
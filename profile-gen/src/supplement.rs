@@ -0,0 +1,116 @@
+//! Profile entries that newer FIT SDK releases added, for
+//! generating from a `Profile.xlsx` that predates them.
+//!
+//! Each entry is only added when the worksheet doesn't
+//! already have it, so a newer profile takes precedence.
+
+use worksheet::{
+    messages::{
+        FieldRow,
+        Message,
+    },
+    types::{
+        Type,
+        Value,
+    },
+};
+
+/// Whole types, as `(type to follow, type, base type,
+/// values)`.
+static TYPES: &[(&str, &str, &str, &[(&str, u64)])] = &[(
+    "GarminProduct",
+    "FaveroProduct",
+    "uint16",
+    &[("AssiomaUno", 10), ("AssiomaDuo", 12)],
+)];
+
+/// Type values, as `(type, value name, value)`.
+static VALUES: &[(&str, &str, u64)] =
+    &[("Event", "RadarThreatAlert", 75), ("MesgNum", "Location", 29)];
+
+/// Whole messages, as `(message to follow, message,
+/// fields)`. `location` is what devices write to
+/// `Locations.fit`; the published profile leaves it out.
+static MESSAGES: &[(&str, &str, &[FieldRow])] = &[(
+    "course_point",
+    "location",
+    &[
+        (254, "message_index", "message_index", None, None, None),
+        (253, "timestamp", "date_time", None, None, Some("s")),
+        (0, "name", "string", None, None, None),
+        (1, "position_lat", "sint32", None, None, Some("semicircles")),
+        (2, "position_long", "sint32", None, None, Some("semicircles")),
+        (3, "symbol", "uint16", None, None, None),
+        (4, "altitude", "uint16", Some(5.0), Some(500.0), Some("m")),
+        (6, "description", "string", None, None, None),
+    ],
+)];
+
+/// Add the missing `TYPES` to `types`, and the missing
+/// `VALUES` before the first value that's larger, to keep
+/// the worksheet's order.
+pub fn extend_types(types: &mut Vec<Type>) {
+    for &(after, type_name, base_type, values) in TYPES {
+        if types.iter().any(|ty| ty.name == type_name) {
+            continue
+        }
+        let at = types
+            .iter()
+            .position(|ty| ty.name == after)
+            .map_or_else(|| types.len(), |i| i + 1);
+        types.insert(
+            at,
+            Type {
+                name:      type_name.to_string(),
+                base_type: base_type.to_string(),
+                values:    values
+                    .iter()
+                    .map(|&(name, value)| {
+                        Value {
+                            name: name.to_string(),
+                            value,
+                            comment: None,
+                        }
+                    })
+                    .collect(),
+                comment:   None,
+            },
+        );
+    }
+    for &(type_name, name, value) in VALUES {
+        let ty = match types.iter_mut().find(|ty| ty.name == type_name) {
+            Some(ty) => ty,
+            None => continue,
+        };
+        if ty.values.iter().any(|val| val.name == name || val.value == value) {
+            continue
+        }
+        let at = ty
+            .values
+            .iter()
+            .position(|val| val.value > value)
+            .unwrap_or_else(|| ty.values.len());
+        ty.values.insert(
+            at,
+            Value {
+                name: name.to_string(),
+                value,
+                comment: None,
+            },
+        );
+    }
+}
+
+/// Add the missing `MESSAGES` to `messages`.
+pub fn extend_messages(messages: &mut Vec<Message>) {
+    for &(after, name, fields) in MESSAGES {
+        if messages.iter().any(|mesg| mesg.profile_name() == name) {
+            continue
+        }
+        let at = messages
+            .iter()
+            .position(|mesg| mesg.profile_name() == after)
+            .map_or_else(|| messages.len(), |i| i + 1);
+        messages.insert(at, Message::new(name, fields));
+    }
+}
@@ -17,6 +17,11 @@ use std::{
     iter,
 };
 use util;
+use worksheet::types::{
+    self,
+    Kind,
+    Type,
+};
 
 /// The worksheet what we want.
 static WORKSHEET_NAME: &'static str = "Messages";
@@ -25,7 +30,7 @@ static WORKSHEET_NAME: &'static str = "Messages";
 pub struct Sheet(calamine::Range<calamine::DataType>);
 
 impl Sheet {
-    fn rows(&self) -> calamine::Rows<calamine::DataType> {
+    fn rows(&self) -> calamine::Rows<'_, calamine::DataType> {
         self.0.rows()
     }
 }
@@ -44,27 +49,214 @@ where
 
 #[derive(Clone)]
 pub struct Message {
-    name:    String,
-    fields:  Vec<Field>,
-    comment: Option<String>,
+    name:         String,
+    /// The name as the profile writes it, in snake case.
+    profile_name: String,
+    fields:       Vec<Field>,
+    comment:      Option<String>,
 }
 
 #[derive(Clone)]
 pub struct Field {
-    name:    String,
-    def_num: u8,
-    type_:   String, // Either<Type, BaseType>
-    scale:   Option<f64>,
-    offset:  Option<f64>,
-    units:   Option<String>,
-    refs:    Option<Vec<(String, String)>>,
-    comment: Option<String>,
+    name:         String,
+    /// The name as the profile writes it, in snake case.
+    profile_name: String,
+    def_num:      u8,
+    type_:        String, // Either<Type, BaseType>
+    scale:        Option<f64>,
+    offset:       Option<f64>,
+    units:        Option<String>,
+    refs:         Option<Vec<(String, String)>>,
+    comment:      Option<String>,
+    array:        bool,
 }
 
+/// A field as `(def_num, name, type, scale, offset,
+/// units)`, with the names and type as the worksheet writes
+/// them.
+pub type FieldRow<'a> =
+    (u8, &'a str, &'a str, Option<f64>, Option<f64>, Option<&'a str>);
+
+impl Message {
+    /// A message that isn't in the worksheet, named as the
+    /// worksheet would name it.
+    pub fn new(profile_name: &str, fields: &[FieldRow]) -> Self {
+        Message {
+            name:         profile_name.to_pascal_case(),
+            profile_name: profile_name.to_string(),
+            fields:       fields
+                .iter()
+                .map(|&(def_num, name, type_, scale, offset, units)| {
+                    Field {
+                        name: name.to_pascal_case(),
+                        profile_name: name.to_string(),
+                        def_num,
+                        type_: type_.to_string(),
+                        scale,
+                        offset,
+                        units: units.map(String::from),
+                        refs: None,
+                        comment: None,
+                        array: false,
+                    }
+                })
+                .collect(),
+            comment:      None,
+        }
+    }
+
+    /// The name as the profile writes it, in snake case.
+    pub fn profile_name(&self) -> &str {
+        &self.profile_name
+    }
+}
+
+impl Field {
+    /// The `profile` type of the field's values.
+    fn value_type(&self) -> TokenStream {
+        let namespace = if KNOWN_BASE_TYPES.contains(&self.type_) {
+            Ident::new("base", Span::call_site())
+        }
+        else {
+            Ident::new("types", Span::call_site())
+        };
+        let member = match KNOWN_BASE_TYPES.get(&self.type_).map(String::as_str)
+        {
+            // Handle this type belonging to the base types.
+            Some("string") => Ident::new("Utf8String", Span::call_site()),
+            Some("byte") => Ident::new("Bytes", Span::call_site()),
+            Some(ty) => {
+                Ident::new(&util::uppercase_first(ty), Span::call_site())
+            },
+            // Otherwise it belongs to sdk types.
+            None => Ident::new(&self.type_.to_pascal_case(), Span::call_site()),
+        };
+        quote! { profile::#namespace::#member }
+    }
+
+    /// The type the field decodes into, inside `Field`.
+    fn data_type(&self) -> TokenStream {
+        let value_type = self.value_type();
+        if self.array {
+            quote! { Vec<#value_type> }
+        }
+        else {
+            value_type
+        }
+    }
+
+    /// An expression for the `Value` of the field `f`.
+    fn value(&self, types: &[Type]) -> TokenStream {
+        let base_type = KNOWN_BASE_TYPES.get(&self.type_).map(String::as_str);
+        match (self.array, base_type) {
+            (true, Some("string")) => {
+                quote! {
+                    let texts = f.raw_value.iter().filter_map(|string| {
+                        match text(string) {
+                            Value::Text(string) => Some(string),
+                            _ => None,
+                        }
+                    });
+                    Value::Texts(texts.collect())
+                }
+            },
+            (true, Some(_)) => {
+                quote! {
+                    if f.is_valid() { Value::Numbers(f.value()) } else { Value::Invalid }
+                }
+            },
+            (true, None) => {
+                panic!("no array value for type: {}", self.type_)
+            },
+            (false, Some("string")) => quote! { text(&f.raw_value) },
+            (false, Some("bool")) => quote! { Value::Bool(f.raw_value.0) },
+            (false, Some("byte")) => {
+                quote! {
+                    if f.is_valid() { Value::Bytes(f.raw_value.0.clone()) } else { Value::Invalid }
+                }
+            },
+            (false, Some(_)) => {
+                quote! {
+                    if f.is_valid() { Value::Number(f.value()) } else { Value::Invalid }
+                }
+            },
+            (false, None) => {
+                let ty = types
+                    .iter()
+                    .find(|ty| ty.name == self.type_.to_pascal_case())
+                    .unwrap_or_else(|| panic!("unknown type: {}", self.type_));
+                match (ty.kind(), ty.name.as_str()) {
+                    (Kind::Prim, "DateTime")
+                    | (Kind::Prim, "LocalDateTime") => {
+                        let variant = Ident::new(&ty.name, Span::call_site());
+                        quote! {
+                            if f.raw_value.0 != u32::MAX { Value::#variant(f.raw_value) } else { Value::Invalid }
+                        }
+                    },
+                    (Kind::Prim, _) => {
+                        let prim_type = types::prim_type_ident(ty);
+                        quote! {
+                            if f.raw_value.0 != #prim_type::MAX {
+                                Value::Number(f64::from(f.raw_value.0))
+                            }
+                            else {
+                                Value::Invalid
+                            }
+                        }
+                    },
+                    (Kind::Struct, _) => {
+                        quote! {
+                            if f.is_valid() { Value::Number(f64::from(f.raw_value.0)) } else { Value::Invalid }
+                        }
+                    },
+                    (Kind::Enum, _) => {
+                        quote! { Value::Enum(format!("{:?}", f.raw_value)) }
+                    },
+                }
+            },
+        }
+    }
+
+    /// An expression decoding the field from `buffer`.
+    fn decode(&self) -> TokenStream {
+        let value_type = self.value_type();
+        if self.array {
+            quote! { #value_type::decode_array::<T>(buffer)? }
+        }
+        else {
+            quote! { #value_type::decode::<T>(buffer)? }
+        }
+    }
+}
+
+/// Messages with a getter for each field, which finds the
+/// field's last occurrence among a message's fields.
+static GETTER_MESSAGES: &[&str] =
+    &["FileId", "DeviceInfo", "Event", "Record", "Lap", "Session"];
+
+/// Fields that hold an array of values, as `(message,
+/// field)`. The profile marks many more fields as arrays,
+/// but most only ever hold one value, so arrays are opted
+/// into field by field.
+static ARRAY_FIELDS: &[(&str, &str)] = &[
+    ("FieldDescription", "FieldName"),
+    ("FieldDescription", "Units"),
+    ("GpsMetadata", "Velocity"),
+    ("SegmentPoint", "LeaderTime"),
+];
+
+/// Fields decoded as another type than the profile gives
+/// them, as `(message, field, type)`. `FileCapabilities`'
+/// `flags` are typed as `file_flags`, but hold several of
+/// its bits at once, which the enum can't name.
+static FIELD_TYPES: &[(&str, &str, &str)] =
+    &[("FileCapabilities", "Flags", "uint8z")];
+
 pub fn generate_module(
     sdk_version: &str,
     messages: &[Message],
     mesg_nums: HashMap<String, u64>,
+    types: &[Type],
 ) -> Result<TokenStream> {
     // Module header:
     let mut tokens = quote! {
@@ -74,27 +266,9 @@ pub fn generate_module(
         use byteorder::ByteOrder;
         use error;
         use profile;
-        use types;
-
-        /// The actual data of a `Message`.
-        #[derive(Debug)]
-        pub struct Field<T> {
-            value:  T,
-            scale:  Option<f64>,
-            offset: Option<f64>,
-            pub units:  Option<&'static str>,
-        }
-
-        // TODO: Finish these impls
-
-        impl types::field::Field for Field<profile::base::Float64> {
-            type Value = f64;
-
-            fn value(&self) -> Self::Value {
-                self.value.0 / self.scale.unwrap_or(1.0) - self.offset.unwrap_or(0.0)
-            }
-        }
+        use types::{self, field::Value};
     };
+    tokens.extend(generate_field(&messages));
 
     tokens.extend(generate_message_enum(&messages));
 
@@ -116,6 +290,15 @@ pub fn generate_module(
         }
     })?;
     tokens.extend(generate_message_decode_impl(&numbered_messages));
+    tokens.extend(generate_message_mesg_num_impl(&numbered_messages));
+    tokens.extend(generate_field_table(&numbered_messages));
+    tokens.extend(generate_message_name(&numbered_messages));
+    tokens.extend(generate_message_index_impl(&messages));
+    tokens.extend(generate_message_field_def_num_impl(&messages));
+    tokens.extend(generate_message_value_impl(&messages, types));
+    tokens.extend(generate_message_is_unknown_impl(&messages));
+    tokens.extend(generate_message_date_time_mut_impl(&messages));
+    tokens.extend(generate_message_encode_impl(&messages));
 
     for message in messages {
         tokens.extend(generate_message_inner(&message));
@@ -123,6 +306,206 @@ pub fn generate_module(
     Ok(tokens)
 }
 
+/// Base types whose fields have a numeric, scaled value.
+static NUMERIC_BASE_TYPES: &[&str] = &[
+    "Float32", "Float64", "Uint8", "Uint8z", "Sint8", "Uint16", "Uint16z",
+    "Sint16", "Uint32", "Uint32z", "Sint32", "Uint64", "Uint64z", "Sint64",
+];
+
+/// Integer base types with the Rust integer holding them.
+static INTEGER_BASE_TYPES: &[(&str, &str)] = &[
+    ("Uint8", "u8"),
+    ("Uint8z", "u8"),
+    ("Sint8", "i8"),
+    ("Uint16", "u16"),
+    ("Uint16z", "u16"),
+    ("Sint16", "i16"),
+    ("Uint32", "u32"),
+    ("Uint32z", "u32"),
+    ("Sint32", "i32"),
+    ("Uint64", "u64"),
+    ("Uint64z", "u64"),
+    ("Sint64", "i64"),
+];
+
+/// The 64-bit base types, whose values an `f64` can't hold
+/// exactly, with the accessor for their exact raw value,
+/// its integer, and the range beyond which `f64`s lose
+/// precision.
+static RAW_64_BIT_ACCESSORS: &[(&str, &str, &str, (&str, &str))] = &[
+    ("Uint64", "raw_u64", "u64", ("above", "2^53")),
+    ("Uint64z", "raw_u64", "u64", ("above", "2^53")),
+    ("Sint64", "raw_i64", "i64", ("beyond", "±2^53")),
+];
+
+/// The `Field` that every message field decodes into, and
+/// its numeric value for each base type and for the arrays
+/// in `messages`.
+fn generate_field(messages: &[Message]) -> TokenStream {
+    let mut tokens = quote! {
+        /// The actual data of a `Message`.
+        #[derive(Debug, Clone)]
+        pub struct Field<T> {
+            pub raw_value: T,
+            pub scale: Option<f64>,
+            pub offset: Option<f64>,
+            pub units: Option<&'static str>,
+        }
+
+        impl<T: profile::base::Valid> Field<T> {
+            pub fn is_valid(&self) -> bool {
+                self.raw_value.is_valid()
+            }
+        }
+
+        impl<T> Field<T> {
+            /// Decimals implied by the field's scale, or `None` if
+            /// the scale doesn't map onto a number of decimals.
+            pub fn decimals_hint(&self) -> Option<u8> {
+                types::field::decimals_for_scale(self.scale)
+            }
+        }
+
+        impl<T> Field<T>
+        where
+            Field<T>: types::field::Field<Value = f64>,
+        {
+            /// The scaled value rounded according to `precision`.
+            pub fn rounded_value(&self, precision: types::field::Precision) -> f64 {
+                use types::field::Field;
+                precision.round(self.value(), self.scale)
+            }
+        }
+    };
+
+    tokens.extend(quote! {
+        impl<T: profile::base::Valid> Field<T>
+        where
+            Field<T>: types::field::Field<Value = f64>,
+        {
+            /// The scaled value, or `None` if the field holds its
+            /// base type's invalid value or isn't a finite number.
+            ///
+            /// A scale or offset that can't be used, such as a
+            /// scale of zero, is left out as [`scale_value`]
+            /// describes, so this is never infinite or NaN.
+            ///
+            /// [`scale_value`]: ../../types/field/fn.scale_value.html
+            pub fn checked_value(&self) -> Option<f64> {
+                use types::field::Field;
+                Some(self.value()).filter(|value| self.is_valid() && value.is_finite())
+            }
+        }
+    });
+
+    tokens.extend(quote! {
+        /// The scaled value, with as many decimals as the
+        /// formatter's precision asks for, as in `{:.2}`, or
+        /// else as many as [`Precision::SpecImplied`] keeps for
+        /// the field's scale.
+        ///
+        /// [`Precision::SpecImplied`]: ../../types/field/enum.Precision.html#variant.SpecImplied
+        impl<T> std::fmt::Display for Field<T>
+        where
+            Field<T>: types::field::Field<Value = f64>,
+        {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                use types::field::{Field, Precision};
+                let value = self.value();
+                if f.precision().is_some() {
+                    return std::fmt::Display::fmt(&value, f)
+                }
+                f.pad(&Precision::SpecImplied.format(value, self.scale))
+            }
+        }
+    });
+
+    for &(base_type, int) in INTEGER_BASE_TYPES {
+        let base_type = Ident::new(base_type, Span::call_site());
+        let int = Ident::new(int, Span::call_site());
+        tokens.extend(quote! { raw_integer!(#base_type, #int); });
+    }
+
+    for &(base_type, accessor, int, range) in RAW_64_BIT_ACCESSORS {
+        let base_type = Ident::new(base_type, Span::call_site());
+        let accessor = Ident::new(accessor, Span::call_site());
+        let int = Ident::new(int, Span::call_site());
+        let comment = [
+            " The exact raw value, or `None` if it's invalid.".to_string(),
+            format!(" Unlike [`value`], it doesn't lose precision {}", range.0),
+            format!(" {}.", range.1),
+            "".to_string(),
+            " [`value`]: ../../types/field/trait.Field.html#tymethod.value"
+                .to_string(),
+        ];
+        tokens.extend(quote! {
+            impl Field<profile::base::#base_type> {
+                #(#[doc=#comment])*
+                pub fn #accessor(&self) -> Option<#int> {
+                    Some(self.raw_value.get()).filter(|_| self.is_valid())
+                }
+            }
+        });
+    }
+
+    for base_type in NUMERIC_BASE_TYPES {
+        let is_array =
+            messages.iter().flat_map(|mesg| &mesg.fields).any(|field| {
+                field.array && util::uppercase_first(&field.type_) == *base_type
+            });
+        let base_type = Ident::new(base_type, Span::call_site());
+        let raw_value = if base_type == "Float64" {
+            quote! { self.raw_value.0 }
+        }
+        else {
+            quote! { self.raw_value.0 as f64 }
+        };
+        let comment = RAW_64_BIT_ACCESSORS
+            .iter()
+            .find(|&&(name, ..)| base_type == name)
+            .map(|&(_, accessor, ..)| {
+                let comment = [
+                    " The scaled value as an `f64`, which can only hold"
+                        .to_string(),
+                    format!(
+                        " integers up to 2^53 exactly. Use [`{}`] for the",
+                        accessor
+                    ),
+                    " exact raw value.".to_string(),
+                    "".to_string(),
+                    format!(" [`{0}`]: struct.Field.html#method.{0}", accessor),
+                ];
+                quote! { #(#[doc=#comment])* }
+            });
+        tokens.extend(quote! {
+            #comment
+            impl types::field::Field for Field<profile::base::#base_type> {
+                type Value = f64;
+
+                fn value(&self) -> Self::Value {
+                    types::field::scale_value(#raw_value, self.scale, self.offset)
+                }
+            }
+        });
+
+        if is_array {
+            tokens.extend(quote! {
+                impl types::field::Field for Field<Vec<profile::base::#base_type>> {
+                    type Value = Vec<f64>;
+
+                    fn value(&self) -> Self::Value {
+                        self.raw_value
+                            .iter()
+                            .map(|x| types::field::scale_value(x.0 as f64, self.scale, self.offset))
+                            .collect()
+                    }
+                }
+            });
+        }
+    }
+    tokens
+}
+
 fn generate_message_enum(messages: &[Message]) -> TokenStream {
     let variants = messages.iter().map(|mesg| {
         let name = Ident::new(&mesg.name, Span::call_site());
@@ -131,7 +514,7 @@ fn generate_message_enum(messages: &[Message]) -> TokenStream {
 
     quote! {
         /// All the FIT message types.
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         pub enum Message {
             #(#variants,)*
             Unknown {
@@ -172,6 +555,345 @@ fn generate_message_decode_impl(
     }
 }
 
+fn generate_message_mesg_num_impl(
+    numbered_messages: &[(u64, Message)],
+) -> TokenStream {
+    let match_arms = numbered_messages.iter().map(|(n, mesg)| {
+        let num = Literal::u64_unsuffixed(*n);
+        let name = Ident::new(&mesg.name, Span::call_site());
+        quote! { Message::#name(_) => #num }
+    });
+
+    quote! {
+        impl Message {
+            /// The global message number this field belongs to.
+            pub fn mesg_num(&self) -> u16 {
+                match self {
+                    #(#match_arms,)*
+                    Message::Unknown { mesg_num, .. } => *mesg_num,
+                }
+            }
+        }
+    }
+}
+
+fn generate_message_index_impl(messages: &[Message]) -> TokenStream {
+    let patterns = messages.iter().flat_map(|mesg| {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        mesg.fields
+            .iter()
+            .filter(|field| {
+                field.def_num == 254 && field.type_ == "message_index"
+            })
+            .map(move |field| {
+                let field_name = Ident::new(&field.name, Span::call_site());
+                quote! { Message::#name(#name::#field_name(f)) }
+            })
+    });
+
+    quote! {
+        impl Message {
+            /// The value of this field if it is its message's
+            /// `message_index` (field 254).
+            pub fn message_index(&self) -> Option<profile::types::MessageIndex> {
+                match self {
+                    #(#patterns)|* => Some(f.raw_value),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+fn generate_field_table(numbered_messages: &[(u64, Message)]) -> TokenStream {
+    let mut fields = numbered_messages
+        .iter()
+        .flat_map(|(n, mesg)| mesg.fields.iter().map(move |field| (*n, field)))
+        .collect::<Vec<_>>();
+    fields.sort_by_key(|&(n, field)| (n, field.def_num));
+    let entries = fields.iter().map(|&(n, field)| {
+        let num = Literal::u64_unsuffixed(n);
+        let field_def_num = Literal::u8_unsuffixed(field.def_num);
+        let name = &field.profile_name;
+        let units = match field.units {
+            Some(ref units) => quote! { Some(#units) },
+            None => quote! { None },
+        };
+        let scale = optional_f64(field.scale);
+        let offset = optional_f64(field.offset);
+        quote! { (#num, #field_def_num, #name, #units, #scale, #offset) }
+    });
+
+    quote! {
+        /// A field as the profile gives it: its message and field
+        /// number, snake case name, units, scale and offset.
+        pub(crate) type FieldInfo = (
+            u16,
+            u8,
+            &'static str,
+            Option<&'static str>,
+            Option<f64>,
+            Option<f64>,
+        );
+
+        /// Every field in the profile, sorted by message and field
+        /// number.
+        pub(crate) const FIELDS: &[FieldInfo] = &[#(#entries,)*];
+
+        /// The `FIELDS` entry for field `field_def_num` of message
+        /// `mesg_num`.
+        fn field(mesg_num: u16, field_def_num: u8) -> Option<&'static FieldInfo> {
+            FIELDS
+                .binary_search_by_key(
+                    &(mesg_num, field_def_num),
+                    |&(mesg, field, ..)| (mesg, field),
+                )
+                .ok()
+                .map(|i| &FIELDS[i])
+        }
+
+        /// The snake case name of field `field_def_num` of message
+        /// `mesg_num`, `None` for unknown fields.
+        pub fn field_name(mesg_num: u16, field_def_num: u8) -> Option<&'static str> {
+            field(mesg_num, field_def_num).map(|&(_, _, name, ..)| name)
+        }
+
+        /// The units the profile gives field `field_def_num` of
+        /// message `mesg_num`, `None` for fields without units and
+        /// for unknown fields.
+        pub fn field_units(mesg_num: u16, field_def_num: u8) -> Option<&'static str> {
+            field(mesg_num, field_def_num).and_then(|&(_, _, _, units, ..)| units)
+        }
+
+        /// The scale and offset the profile gives field
+        /// `field_def_num` of message `mesg_num`, both `None` for
+        /// fields without either and for unknown fields.
+        ///
+        /// Scaled values are `raw / scale - offset`.
+        pub fn field_scale_offset(
+            mesg_num: u16,
+            field_def_num: u8,
+        ) -> (Option<f64>, Option<f64>) {
+            field(mesg_num, field_def_num)
+                .map_or((None, None), |&(.., scale, offset)| (scale, offset))
+        }
+    }
+}
+
+fn generate_message_name(numbered_messages: &[(u64, Message)]) -> TokenStream {
+    let match_arms = numbered_messages.iter().map(|(n, mesg)| {
+        let num = Literal::u64_unsuffixed(*n);
+        let name = &mesg.profile_name;
+        quote! { #num => Some(#name) }
+    });
+
+    quote! {
+        /// The snake case name of message `mesg_num`, `None` for
+        /// unknown messages.
+        pub fn message_name(mesg_num: u16) -> Option<&'static str> {
+            match mesg_num {
+                #(#match_arms,)*
+                _ => None,
+            }
+        }
+    }
+}
+
+/// `value` as an `Option<f64>` expression.
+fn optional_f64(value: Option<f64>) -> TokenStream {
+    match value {
+        Some(value) => {
+            let value = Literal::f64_unsuffixed(value);
+            quote! { Some(#value) }
+        },
+        None => quote! { None },
+    }
+}
+
+fn generate_message_field_def_num_impl(messages: &[Message]) -> TokenStream {
+    let mut def_nums = messages
+        .iter()
+        .flat_map(|mesg| mesg.fields.iter().map(|field| field.def_num))
+        .collect::<Vec<_>>();
+    def_nums.sort();
+    def_nums.dedup();
+    let match_arms = def_nums.iter().map(|&def_num| {
+        let patterns = messages.iter().flat_map(|mesg| {
+            let name = Ident::new(&mesg.name, Span::call_site());
+            mesg.fields
+                .iter()
+                .filter(move |field| field.def_num == def_num)
+                .map(move |field| {
+                    let field_name = Ident::new(&field.name, Span::call_site());
+                    quote! { Message::#name(#name::#field_name(_)) }
+                })
+        });
+        let field_def_num = Literal::u8_unsuffixed(def_num);
+        quote! { #(#patterns)|* => #field_def_num }
+    });
+    let unknown_patterns = messages.iter().map(|mesg| {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        quote! { Message::#name(#name::Unknown { field_def_num, .. }) }
+    });
+
+    quote! {
+        impl Message {
+            /// The field definition number of this field.
+            pub fn field_def_num(&self) -> u8 {
+                match self {
+                    #(#match_arms,)*
+                    Message::Unknown { field_def_num, .. }
+                    #(| #unknown_patterns)* => *field_def_num,
+                }
+            }
+        }
+    }
+}
+
+/// The patterns binding each field as `f`, grouped by the
+/// type of `f` so that each group can share an arm, in the
+/// order the types first appear, with the group's first
+/// field.
+fn group_by_data_type(messages: &[Message]) -> Vec<(&Field, Vec<TokenStream>)> {
+    let mut groups: Vec<(String, &Field, Vec<TokenStream>)> = Vec::new();
+    for mesg in messages {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        for field in &mesg.fields {
+            let field_name = Ident::new(&field.name, Span::call_site());
+            let pattern = quote! { Message::#name(#name::#field_name(f)) };
+            let data_type = field.data_type().to_string();
+            match groups.iter().position(|group| group.0 == data_type) {
+                Some(i) => groups[i].2.push(pattern),
+                None => groups.push((data_type, field, vec![pattern])),
+            }
+        }
+    }
+    groups.into_iter().map(|(_, field, patterns)| (field, patterns)).collect()
+}
+
+fn generate_message_value_impl(
+    messages: &[Message],
+    types: &[Type],
+) -> TokenStream {
+    let match_arms =
+        group_by_data_type(messages).into_iter().map(|(field, patterns)| {
+            let value = field.value(types);
+            quote! { #(#patterns)|* => { #value } }
+        });
+    let unknown_patterns = messages.iter().map(|mesg| {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        quote! { Message::#name(#name::Unknown { data, .. }) }
+    });
+
+    quote! {
+        impl Message {
+            /// The value of this field, scaled into its units.
+            /// Fields holding their invalid value, and strings
+            /// that are empty once trailing NULs are dropped, are
+            /// `Invalid`; unknown fields are their raw bytes.
+            pub fn value(&self) -> Value {
+                use types::field::Field;
+
+                match self {
+                    #(#match_arms,)*
+                    Message::Unknown { data, .. }
+                    #(| #unknown_patterns)* => Value::Bytes(data.clone()),
+                }
+            }
+        }
+
+        /// `string` without its trailing NULs, `Invalid` if that
+        /// leaves nothing.
+        fn text(string: &profile::base::Utf8String) -> Value {
+            let string = string.0.trim_end_matches('\0');
+            if string.is_empty() {
+                Value::Invalid
+            }
+            else {
+                Value::Text(string.to_string())
+            }
+        }
+    }
+}
+
+fn generate_message_is_unknown_impl(messages: &[Message]) -> TokenStream {
+    let patterns = messages.iter().map(|mesg| {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        quote! { Message::#name(#name::Unknown { .. }) }
+    });
+
+    quote! {
+        impl Message {
+            /// Whether this is a field, or a whole message, that
+            /// isn't in the profile.
+            pub fn is_unknown(&self) -> bool {
+                matches!(
+                    self,
+                    Message::Unknown { .. } #(| #patterns)*
+                )
+            }
+        }
+    }
+}
+
+fn generate_message_date_time_mut_impl(messages: &[Message]) -> TokenStream {
+    // The two types bind different `Field`s, so each has its
+    // own arm.
+    let match_arms = ["date_time", "local_date_time"].iter().map(|type_| {
+        let patterns = messages.iter().flat_map(|mesg| {
+            let name = Ident::new(&mesg.name, Span::call_site());
+            mesg.fields
+                .iter()
+                .filter(move |field| field.type_ == *type_ && !field.array)
+                .map(move |field| {
+                    let field_name = Ident::new(&field.name, Span::call_site());
+                    quote! { Message::#name(#name::#field_name(f)) }
+                })
+        });
+        quote! { #(#patterns)|* => Some(&mut f.raw_value.0) }
+    });
+
+    quote! {
+        impl Message {
+            /// The seconds of this field, for editing in place, if
+            /// it's a `date_time` or `local_date_time`.
+            pub(crate) fn date_time_mut(&mut self) -> Option<&mut u32> {
+                match self {
+                    #(#match_arms,)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+fn generate_message_encode_impl(messages: &[Message]) -> TokenStream {
+    let match_arms =
+        group_by_data_type(messages).into_iter().map(|(_, patterns)| {
+            quote! { #(#patterns)|* => f.raw_value.encode(out) }
+        });
+    let unknown_patterns = messages.iter().map(|mesg| {
+        let name = Ident::new(&mesg.name, Span::call_site());
+        quote! { Message::#name(#name::Unknown { data, .. }) }
+    });
+
+    quote! {
+        impl Message {
+            /// Append the little endian bytes this field decodes
+            /// from, as `Message::decode` reads them.
+            pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+                use profile::base::Encode;
+
+                match self {
+                    #(#match_arms,)*
+                    Message::Unknown { data, .. }
+                    #(| #unknown_patterns)* => out.extend_from_slice(data),
+                }
+            }
+        }
+    }
+}
+
 fn generate_message_inner(message: &Message) -> TokenStream {
     let name = Ident::new(&message.name, Span::call_site());
     let comment = match message.comment {
@@ -179,42 +901,30 @@ fn generate_message_inner(message: &Message) -> TokenStream {
         None => TokenStream::new(),
     };
 
-    let variants = message.fields.iter().filter_map(|field| {
+    let variants = message.fields.iter().map(|field| {
         let comment = match field.comment {
             Some(ref comment) => quote! { #[doc=#comment] },
             None => TokenStream::new(),
         };
         let variant = Ident::new(&field.name, Span::call_site());
-        let namespace = if KNOWN_BASE_TYPES.contains(&field.type_) {
-            Ident::new("base", Span::call_site())
-        }
-        else {
-            Ident::new("types", Span::call_site())
-        };
-        let member =
-            match KNOWN_BASE_TYPES.get(&field.type_).map(String::as_str) {
-                // Handle this type belonging to the base types.
-                Some("string") => Ident::new("Utf8String", Span::call_site()),
-                Some("byte") => Ident::new("Bytes", Span::call_site()),
-                Some(ty) => {
-                    Ident::new(&util::uppercase_first(ty), Span::call_site())
-                },
-                // Otherwise it belongs to sdk types.
-                None => {
-                    Ident::new(&field.type_.to_pascal_case(), Span::call_site())
-                },
-            };
-        Some(quote! {
+        let data_type = field.data_type();
+        quote! {
             #comment
-            #variant(Field<profile::#namespace::#member>)
-        })
+            #variant(Field<#data_type>)
+        }
     });
 
     let decode_impl = generate_message_inner_decode_impl(message);
+    let getters_impl = if GETTER_MESSAGES.contains(&message.name.as_str()) {
+        generate_message_inner_getters_impl(message)
+    }
+    else {
+        TokenStream::new()
+    };
 
     quote! {
         #comment
-        #[derive(Debug)]
+        #[derive(Debug, Clone)]
         pub enum #name {
             #(#variants,)*
             Unknown {
@@ -224,67 +934,57 @@ fn generate_message_inner(message: &Message) -> TokenStream {
         }
 
         #decode_impl
+
+        #getters_impl
     }
 }
 
-fn generate_message_inner_decode_impl(message: &Message) -> TokenStream {
+fn generate_message_inner_getters_impl(message: &Message) -> TokenStream {
     let message_name = Ident::new(&message.name, Span::call_site());
 
-    let match_arms = message.fields.iter().filter_map(|field| {
+    let getters = message.fields.iter().map(|field| {
         let field_name = Ident::new(&field.name, Span::call_site());
-        let field_def_num = Literal::u8_unsuffixed(field.def_num);
-
-        let value = {
-            let namespace = if KNOWN_BASE_TYPES.contains(&field.type_) {
-                Ident::new("base", Span::call_site())
+        let getter = Ident::new(
+            &match field.profile_name.as_str() {
+                "type" => "type_".to_string(),
+                name => name.to_string(),
+            },
+            Span::call_site(),
+        );
+        let comment = format!(
+            "The last `{}` field among `fields`, if any.",
+            field.profile_name
+        );
+        let data_type = field.data_type();
+        quote! {
+            #[doc=#comment]
+            pub fn #getter(fields: &[Message]) -> Option<&Field<#data_type>> {
+                fields.iter().rev().find_map(|field| match field {
+                    Message::#message_name(#message_name::#field_name(f)) => Some(f),
+                    _ => None,
+                })
             }
-            else {
-                Ident::new("types", Span::call_site())
-            };
+        }
+    });
 
-            let member = match KNOWN_BASE_TYPES
-                .get(&field.type_)
-                .map(String::as_str)
-            {
-                // Handle this type belonging to the base types.
-                Some("string") => Ident::new("Utf8String", Span::call_site()),
-                Some("byte") => Ident::new("Bytes", Span::call_site()),
-                Some(ty) => {
-                    Ident::new(&util::uppercase_first(ty), Span::call_site())
-                },
-                // Otherwise it belongs to sdk types.
-                None => {
-                    match String::as_str(&field.type_) {
-                        "bool" => return None, // NOTE: idk
-                        _ => {
-                            Ident::new(
-                                &field.type_.to_pascal_case(),
-                                Span::call_site(),
-                            )
-                        },
-                    }
-                },
-            };
+    quote! {
+        impl #message_name {
+            #(#getters)*
+        }
+    }
+}
 
-            quote! {
-                profile::#namespace::#member::decode::<T>(buffer)?
-            }
-        };
+fn generate_message_inner_decode_impl(message: &Message) -> TokenStream {
+    let message_name = Ident::new(&message.name, Span::call_site());
 
-        let scale = match field.scale {
-            Some(scale) => {
-                let scale_lit = Literal::f64_unsuffixed(scale);
-                quote! { Some(#scale_lit) }
-            },
-            None => quote! { None },
-        };
-        let offset = match field.offset {
-            Some(offset) => {
-                let offset_lit = Literal::f64_unsuffixed(offset);
-                quote! { Some(#offset_lit) }
-            },
-            None => quote! { None },
-        };
+    let match_arms = message.fields.iter().map(|field| {
+        let field_name = Ident::new(&field.name, Span::call_site());
+        let field_def_num = Literal::u8_unsuffixed(field.def_num);
+
+        let value = field.decode();
+
+        let scale = optional_f64(field.scale);
+        let offset = optional_f64(field.offset);
         let units = match field.units {
             Some(ref unit) => {
                 let unit_lit = Literal::string(unit);
@@ -293,14 +993,14 @@ fn generate_message_inner_decode_impl(message: &Message) -> TokenStream {
             None => quote! { None },
         };
 
-        Some(quote! {
+        quote! {
             #field_def_num => Ok(#message_name::#field_name(Field {
-                value: #value,
+                raw_value: #value,
                 scale: #scale,
                 offset: #offset,
                 units: #units,
             }))
-        })
+        }
     });
 
     quote! {
@@ -408,8 +1108,8 @@ impl<'a> Row<'a> {
 impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
     // NOTE: the logic below is very brittle. But at least
     // it is all contained here.
+    #[rustfmt::skip]
     fn from(row: &'a [calamine::DataType]) -> Self {
-        #[rustfmt_skip]
         match *row {
             // A group of messages is headed by a sortof banner row
             // which has a SHOUTING label in the fourth column.
@@ -417,7 +1117,7 @@ impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
             , calamine::DataType::Empty               // Field Definition Number
             , calamine::DataType::Empty               // Field Name
             , calamine::DataType::String(ref name)    // Field Type
-            , ref rest..
+            , ref rest @ ..
             ] if util::is_shouting(name) && util::all_empty(rest) => {
                 Row::GroupBanner {
                     name: name.to_pascal_case(),
@@ -441,7 +1141,7 @@ impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
             , ..
             ] => {
                 Row::Header {
-                    mesg_name: name.to_pascal_case(),
+                    mesg_name: name.trim().to_string(),
                     comment: match comment_cell {
                         calamine::DataType::String(comment) =>
                             Some(sanitize_comment(comment)),
@@ -469,7 +1169,7 @@ impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
             ] => {
                 Row::Field {
                     def_num: def_num as u8,
-                    name: name.to_pascal_case(),
+                    name: name.trim().to_string(),
                     type_: type_.to_string(),
                     scale: match *scale_cell {
                         calamine::DataType::Float(scale) =>
@@ -527,9 +1227,10 @@ impl<'a> From<Vec<Row<'a>>> for Message {
     fn from(rows: Vec<Row>) -> Self {
         rows.into_iter().fold(
             Message {
-                name:    String::new(),
-                fields:  Vec::new(),
-                comment: None,
+                name:         String::new(),
+                profile_name: String::new(),
+                fields:       Vec::new(),
+                comment:      None,
             },
             |mut acc, row| {
                 match row {
@@ -537,7 +1238,8 @@ impl<'a> From<Vec<Row<'a>>> for Message {
                         mesg_name,
                         comment,
                     } => {
-                        acc.name = mesg_name;
+                        acc.name = mesg_name.to_pascal_case();
+                        acc.profile_name = mesg_name;
                         acc.comment = comment;
                     },
                     Row::Field {
@@ -551,8 +1253,19 @@ impl<'a> From<Vec<Row<'a>>> for Message {
                         ref_field_values,
                         comment,
                     } => {
+                        let profile_name = name;
+                        let name = profile_name.to_pascal_case();
+                        let array = ARRAY_FIELDS
+                            .contains(&(acc.name.as_str(), name.as_str()));
+                        let type_ = FIELD_TYPES
+                            .iter()
+                            .find(|&&(mesg, field, _)| {
+                                mesg == acc.name && field == name
+                            })
+                            .map_or(type_, |&(.., ty)| ty.to_string());
                         let field = Field {
                             name,
+                            profile_name,
                             def_num,
                             type_,
                             scale,
@@ -566,6 +1279,7 @@ impl<'a> From<Vec<Row<'a>>> for Message {
                                 })
                             }),
                             comment,
+                            array,
                         };
                         acc.fields.push(field);
                     },
@@ -1,4 +1,7 @@
-use base::KNOWN_BASE_TYPES;
+use base::{
+    self,
+    KNOWN_BASE_TYPES,
+};
 use calamine;
 use error::{
     Error,
@@ -22,11 +25,32 @@ use util;
 /// The worksheet what we want.
 static WORKSHEET_NAME: &'static str = "Types";
 
+/// Enums with at least this many values decode through a
+/// lookup table rather than a match; see `profile::table`.
+const TABLE_MIN_VALUES: usize = 50;
+
+/// Types whose values only name some of the numbers the
+/// type holds, so they're generated as a struct around the
+/// number with a constant for each value.
+static OPEN_TYPES: &[&str] = &["DeviceIndex"];
+
+/// Derives beyond `Debug` and `Clone`, for the types that
+/// are compared, sorted or hashed.
+static EXTRA_DERIVES: &[(&str, &[&str])] = &[
+    ("DateTime", &["Copy", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash"]),
+    (
+        "LocalDateTime",
+        &["Copy", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+    ),
+    ("ExdLayout", &["Copy", "PartialEq", "Eq"]),
+    ("MesgNum", &["Copy", "PartialEq", "Eq", "Hash"]),
+];
+
 /// The sheet relevant to this module.
 pub struct Sheet(calamine::Range<calamine::DataType>);
 
 impl Sheet {
-    fn rows(&self) -> calamine::Rows<calamine::DataType> {
+    fn rows(&self) -> calamine::Rows<'_, calamine::DataType> {
         self.0.rows()
     }
 }
@@ -51,6 +75,30 @@ pub struct Type {
     pub comment:   Option<String>,
 }
 
+/// How a `Type` is generated.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    /// A struct around the number, without a `Valid` impl.
+    Prim,
+    /// A struct around the number, with constants for its
+    /// values and a `Valid` impl.
+    Struct,
+    /// An enum of the values.
+    Enum,
+}
+
+impl Type {
+    pub fn kind(&self) -> Kind {
+        match self.values.len() {
+            0 => Kind::Prim,
+            1 if self.values[0].name == "Min" => Kind::Prim,
+            _ if self.name == "MessageIndex" => Kind::Struct,
+            _ if OPEN_TYPES.contains(&self.name.as_str()) => Kind::Struct,
+            _ => Kind::Enum,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Value {
     pub name:    String,
@@ -68,16 +116,16 @@ pub fn generate_module(
 
         use byteorder::ByteOrder;
         use error;
+        use std::convert::TryFrom;
         use profile;
     };
 
     for ty in types {
-        tokens.extend(match ty.values.len() {
-            0 => generate_type_prim(&ty),
+        tokens.extend(match ty.kind() {
             // TODO: For now we just capture the comment associated with this
             // "Min" value, but it should really be represented in
             // the type.
-            1 if ty.values[0].name == "Min" => {
+            Kind::Prim if ty.values.len() == 1 => {
                 let more_comment =
                     ty.values[0].comment.clone().unwrap_or(String::new());
 
@@ -88,7 +136,12 @@ pub fn generate_module(
                     ..ty.clone()
                 })
             },
-            _ => generate_type_enum(&ty),
+            Kind::Prim => generate_type_prim(&ty),
+            Kind::Struct if ty.name == "MessageIndex" => {
+                generate_message_index(&ty)
+            },
+            Kind::Struct => generate_type_open(&ty),
+            Kind::Enum => generate_type_enum(&ty),
         });
     }
     Ok(tokens)
@@ -121,16 +174,69 @@ fn generate_type_enum(ty: &Type) -> TokenStream {
     });
 
     let decode_impl = generate_type_enum_decode_impl(ty);
+    let prim_type = prim_type_ident(ty);
+    // `Unknown` is written as the base type's invalid value.
+    let unknown: TokenStream = base::invalid_value(&ty.base_type)
+        .expect("enum types are integers")
+        .parse()
+        .expect("a literal");
+    let mesg_num_impl = if ty.name == "MesgNum" {
+        generate_mesg_num_impl()
+    }
+    else {
+        TokenStream::new()
+    };
+
+    let derives = derives(ty);
 
     quote! {
         #comment
-        #[derive(Debug)]
+        #derives
         pub enum #name {
             #(#variants,)*
             Unknown,
         }
 
         #decode_impl
+        encode_enum!(#name, #prim_type, #unknown);
+
+        #mesg_num_impl
+    }
+}
+
+/// `MesgNum`'s number and message name, and the conversion
+/// from the numbers the profile knows.
+fn generate_mesg_num_impl() -> TokenStream {
+    quote! {
+        impl MesgNum {
+            #[doc="The message number, `0xFFFF` for `Unknown`."]
+            pub fn value(&self) -> u16 {
+                *self as u16
+            }
+
+            #[doc="The snake case name of the message in the profile, as \
+                   `profile::messages::message_name` gives it. `pad` has no \
+                   message of its own, and the manufacturer range bounds and \
+                   `Unknown` have no name."]
+            pub fn name(&self) -> Option<&'static str> {
+                match *self {
+                    MesgNum::Pad => Some("pad"),
+                    MesgNum::MfgRangeMin | MesgNum::MfgRangeMax | MesgNum::Unknown => None,
+                    _ => profile::messages::message_name(self.value()),
+                }
+            }
+        }
+
+        #[doc="Fails for numbers that aren't in the profile's `mesg_num` list."]
+        impl TryFrom<u16> for MesgNum {
+            type Error = ();
+            fn try_from(value: u16) -> Result<Self, ()> {
+                match MesgNum::decode::<byteorder::LittleEndian>(&value.to_le_bytes()) {
+                    Ok(MesgNum::Unknown) | Err(_) => Err(()),
+                    Ok(mesg_num) => Ok(mesg_num),
+                }
+            }
+        }
     }
 }
 
@@ -141,39 +247,129 @@ fn generate_type_prim(ty: &Type) -> TokenStream {
         None => TokenStream::new(),
     };
 
-    let prim_type = Ident::new(
-        match ty.base_type.as_str() {
-            "uint8" => "u8",
-            "uint32" => "u32",
-            other => other,
-        },
-        Span::call_site(),
-    );
+    let prim_type = prim_type_ident(ty);
 
     let decode_impl = generate_type_prim_decode_impl(ty);
 
+    let derives = derives(ty);
+
     quote! {
         #comment
-        #[derive(Debug)]
+        #derives
         pub struct #name(pub #prim_type);
 
         #decode_impl
+        encode_number!(#name);
+    }
+}
+
+/// A struct around the number, like `generate_type_prim`,
+/// with the values as constants.
+fn generate_type_open(ty: &Type) -> TokenStream {
+    let name = Ident::new(&ty.name, Span::call_site());
+    let consts = ty.values.iter().map(|val| {
+        let const_name =
+            Ident::new(&val.name.to_screaming_snake_case(), Span::call_site());
+        let value = Literal::u64_unsuffixed(val.value);
+        let comment = match val.comment {
+            Some(ref comment) => quote! { #[doc=#comment] },
+            None => TokenStream::new(),
+        };
+        quote! {
+            #comment
+            pub const #const_name: #name = #name(#value);
+        }
+    });
+
+    generate_type_struct(ty, quote! { #(#consts)* }, TokenStream::new())
+}
+
+/// `MessageIndex`, whose values are the masks of its bit
+/// fields rather than values of its own.
+fn generate_message_index(ty: &Type) -> TokenStream {
+    let prim_type = prim_type_ident(ty);
+    let mut values = ty.values.clone();
+    values.sort_by_key(|val| val.value);
+    let consts = values.iter().map(|val| {
+        let const_name =
+            Ident::new(&val.name.to_screaming_snake_case(), Span::call_site());
+        let mask: TokenStream =
+            format!("0x{:04X}", val.value).parse().expect("a literal");
+        let comment = match val.comment {
+            Some(ref comment) => quote! { #[doc=#comment] },
+            None => TokenStream::new(),
+        };
+        quote! {
+            #comment
+            pub const #const_name: #prim_type = #mask;
+        }
+    });
+
+    let methods = quote! {
+        /// The index itself, without the selected and reserved bits.
+        pub fn index(&self) -> u16 {
+            self.0 & MessageIndex::MASK
+        }
+
+        /// Whether the selected bit is set.
+        pub fn is_selected(&self) -> bool {
+            self.0 & MessageIndex::SELECTED != 0
+        }
+    };
+
+    generate_type_struct(ty, quote! { #(#consts)* }, methods)
+}
+
+/// A struct around the number with `consts` and `methods`,
+/// and a `Valid` impl.
+fn generate_type_struct(
+    ty: &Type,
+    consts: TokenStream,
+    methods: TokenStream,
+) -> TokenStream {
+    let name = Ident::new(&ty.name, Span::call_site());
+    let comment = match ty.comment {
+        Some(ref comment) => quote! { #[doc=#comment] },
+        None => TokenStream::new(),
+    };
+    let prim_type = prim_type_ident(ty);
+    let base_type = base_type_ident(ty);
+    let invalid: TokenStream = base::invalid_value(&ty.base_type)
+        .expect("struct types are integers")
+        .parse()
+        .expect("a literal");
+
+    quote! {
+        #comment
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct #name(pub #prim_type);
+        impl #name {
+            #consts
+
+            pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
+                let base_value = profile::base::#base_type::decode::<T>(buffer)?;
+                Ok(#name(base_value.0))
+            }
+
+            #methods
+        }
+        encode_number!(#name);
+        impl profile::base::Valid for #name {
+            fn is_valid(&self) -> bool {
+                self.0 != #invalid
+            }
+        }
     }
 }
 
 fn generate_type_enum_decode_impl(ty: &Type) -> TokenStream {
     let type_name = Ident::new(&ty.name, Span::call_site());
 
-    let base_type = match KNOWN_BASE_TYPES
-        .get(&ty.base_type)
-        .map(String::as_str)
-    {
-        // Do we have a valid base type?
-        Some("string") => Ident::new("Utf8String", Span::call_site()),
-        Some("bytes") => Ident::new("Byte", Span::call_site()),
-        Some(ty) => Ident::new(&util::uppercase_first(ty), Span::call_site()),
-        None => panic!("unknown base type: {}", ty.base_type),
-    };
+    let base_type = base_type_ident(ty);
+
+    if ty.values.len() >= TABLE_MIN_VALUES {
+        return generate_type_enum_table_impl(ty, &base_type)
+    }
 
     let match_arms = ty.values.iter().map(|val| {
         let name = Ident::new(&val.name, Span::call_site());
@@ -196,26 +392,125 @@ fn generate_type_enum_decode_impl(ty: &Type) -> TokenStream {
     }
 }
 
-fn generate_type_prim_decode_impl(ty: &Type) -> TokenStream {
-    let type_name = Ident::new(&ty.name, Span::call_site());
-    let decode_body = match ty.base_type.as_str() {
-        "uint8" => {
-            quote! {
-                Ok(#type_name(buffer[0]))
-            }
+/// The `#[derive]` attribute for `ty`.
+fn derives(ty: &Type) -> TokenStream {
+    let extra = EXTRA_DERIVES
+        .iter()
+        .find(|&&(name, _)| name == ty.name)
+        .map_or(&[][..], |&(_, derives)| derives)
+        .iter()
+        .map(|derive| Ident::new(derive, Span::call_site()));
+    quote! { #[derive(Debug, Clone #(, #extra)*)] }
+}
+
+/// Whether `ty` derives `Copy`, through `EXTRA_DERIVES`.
+fn is_copy(ty: &Type) -> bool {
+    EXTRA_DERIVES
+        .iter()
+        .any(|&(name, derives)| name == ty.name && derives.contains(&"Copy"))
+}
+
+/// The Rust integer that holds a `ty`'s values.
+pub fn prim_type_ident(ty: &Type) -> Ident {
+    Ident::new(
+        match ty.base_type.as_str() {
+            "enum" | "uint8" | "uint8z" => "u8",
+            "uint16" | "uint16z" => "u16",
+            "uint32" | "uint32z" => "u32",
+            other => panic!("no primitive for base type: {}", other),
         },
-        "uint32" => {
-            quote! {
-                Ok(#type_name(T::read_u32(buffer)))
-            }
+        Span::call_site(),
+    )
+}
+
+/// The `profile::base` type that `ty` is decoded through.
+fn base_type_ident(ty: &Type) -> Ident {
+    match KNOWN_BASE_TYPES.get(&ty.base_type).map(String::as_str) {
+        // Do we have a valid base type?
+        Some("string") => Ident::new("Utf8String", Span::call_site()),
+        Some("bytes") => Ident::new("Byte", Span::call_site()),
+        Some(ty) => Ident::new(&util::uppercase_first(ty), Span::call_site()),
+        None => panic!("unknown base type: {}", ty.base_type),
+    }
+}
+
+/// Decode through a static `EnumTable`, sorted by value,
+/// with the names for `as_str` and `FromStr` alongside.
+fn generate_type_enum_table_impl(ty: &Type, base_type: &Ident) -> TokenStream {
+    let type_name = Ident::new(&ty.name, Span::call_site());
+    let table = Ident::new(&ty.name.to_screaming_snake_case(), Span::call_site());
+    let prim_type = Ident::new(
+        match base_type.to_string().as_str() {
+            "Enum" | "Uint8" | "Uint8z" => "u8",
+            "Uint16" | "Uint16z" => "u16",
+            "Uint32" | "Uint32z" => "u32",
+            other => panic!("no lookup table for base type: {}", other),
         },
-        _ => quote! { unimplemented!() },
+        Span::call_site(),
+    );
+
+    let mut values: Vec<&Value> =
+        ty.values.iter().filter(|val| val.name != "Unknown").collect();
+    values.sort_by_key(|val| val.value);
+    let entries = values.iter().map(|val| {
+        let name = Ident::new(&val.name, Span::call_site());
+        let value = Literal::u64_unsuffixed(val.value);
+        quote! { (#value, #type_name::#name) }
+    });
+    let names = values.iter().map(|val| val.name.as_str());
+    let mut by_name: Vec<usize> = (0..values.len()).collect();
+    by_name.sort_by_key(|&i| values[i].name.as_str());
+    let by_name = by_name.into_iter().map(|i| Literal::u16_unsuffixed(i as u16));
+    let this = if is_copy(ty) {
+        quote! { *self }
+    } else {
+        quote! { self.clone() }
     };
 
+    quote! {
+        impl #type_name {
+            pub(crate) fn decode<T: ByteOrder>(
+                buffer: &[u8],
+            ) -> error::Result<Self> {
+                let base_value = profile::base::#base_type::decode::<T>(buffer)?;
+                Ok(#table.get(base_value.0).unwrap_or(#type_name::Unknown))
+            }
+
+            #[doc="The variant's name, as `Debug` writes it."]
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    #type_name::Unknown => "Unknown",
+                    _ => #table.name(#this as #prim_type).unwrap_or("Unknown"),
+                }
+            }
+        }
+
+        #[doc="Fails for names that aren't variants, and for `Unknown`."]
+        impl std::str::FromStr for #type_name {
+            type Err = ();
+            fn from_str(name: &str) -> Result<Self, ()> {
+                #table.parse(name).ok_or(())
+            }
+        }
+
+        static #table: profile::table::EnumTable<#prim_type, #type_name> =
+            profile::table::EnumTable {
+                values: &[#(#entries,)*],
+                names: &[#(#names,)*],
+                by_name: &[#(#by_name,)*],
+            };
+    }
+}
+
+fn generate_type_prim_decode_impl(ty: &Type) -> TokenStream {
+    let type_name = Ident::new(&ty.name, Span::call_site());
+    let base_type = base_type_ident(ty);
+
     quote! {
         impl #type_name {
             pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-                #decode_body
+                let base_value = profile::base::#base_type::decode::<T>(buffer)?;
+                Ok(#type_name(base_value.0))
             }
         }
     }
@@ -294,8 +589,8 @@ impl<'a> Row<'a> {
 impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
     // NOTE: This logic is very brittle.
     // But at least it's all contained here.
+    #[rustfmt::skip]
     fn from(row: &'a [calamine::DataType]) -> Self {
-        #[rustfmt_skip]
         match *row {
 
             // Type Name | Base Type | Value Name | Value | Comment
@@ -303,7 +598,7 @@ impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
             // type_name | base_type |            |       | comment
             [ calamine::DataType::String(ref type_name)
             , calamine::DataType::String(ref base_type)
-            , ref rest..
+            , ref rest @ ..
             , ref comment
             ] if util::all_empty(rest) => {
                 Row::Header {
@@ -324,7 +619,7 @@ impl<'a> From<&'a [calamine::DataType]> for Row<'a> {
             , calamine::DataType::String(ref name)
             , ref value
             , ref comment
-            , _..
+            , ..
             ] => {
                 Row::Value {
                     name: VALUE_NAME_QUIRKS
@@ -399,7 +694,6 @@ impl<'a> From<Vec<Row<'a>>> for Type {
 lazy_static! {
     /// Rename value names that cant be valid identifiers.
     static ref VALUE_NAME_QUIRKS: HashMap<&'static str, &'static str> = {
-        #[rustfmt_skip]
         [(
             "30_degree_lat_pulldown",
             "thirty_degree_lat_pulldown",
@@ -0,0 +1,41 @@
+"""Time reading every record of a FIT file with garminfit and
+with the pure-Python fitparse.
+
+    maturin develop --release
+    pip install fitparse
+    python python/bench.py testdata/python_fitparse_1.fit
+"""
+import sys
+import timeit
+
+import garminfit
+
+
+def with_garminfit(path):
+    return garminfit.FitFile(path).records_dataframe()
+
+
+def with_fitparse(path):
+    import fitparse
+
+    fit = fitparse.FitFile(path)
+    return [record.get_values() for record in fit.get_messages("record")]
+
+
+def main(path, repeat=5):
+    runs = [("garminfit", with_garminfit)]
+    try:
+        import fitparse  # noqa: F401
+
+        runs.append(("fitparse", with_fitparse))
+    except ImportError:
+        print("fitparse isn't installed, timing garminfit alone")
+
+    records = len(with_garminfit(path))
+    for name, read in runs:
+        best = min(timeit.repeat(lambda: read(path), number=1, repeat=repeat))
+        print(f"{name:>10}: {best * 1000:8.1f} ms for {records} records")
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
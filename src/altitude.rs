@@ -0,0 +1,282 @@
+//! Choosing between barometric and GPS altitude.
+//!
+//! Devices with a barometer record its altitude in their
+//! records, and some log the GPS altitude alongside in
+//! `GpsMetadata` messages. The barometer follows small
+//! changes far better, but drifts with the weather, so the
+//! two usually disagree by a roughly constant offset. With
+//! [`Policy::Auto`] the barometric altitude is shifted onto
+//! the GPS one, using the median difference while neither
+//! is changing, when the GPS is most trustworthy.
+//!
+//! Altitude in vendor developer fields isn't read.
+//!
+//! [`Policy::Auto`]: enum.Policy.html#variant.Auto
+use gps;
+use series::{
+    self,
+    RecordTimeSeries,
+};
+use types::record::Data;
+
+/// Seconds either side of a GPS fix over which the
+/// barometric altitude must be steady for the fix to
+/// calibrate against.
+const STABLE_WINDOW_S: f64 = 5.0;
+/// Metres per second of climb below which the altitude is
+/// steady.
+const STABLE_RATE_MS: f64 = 0.2;
+/// Seconds between records beyond which the barometric
+/// altitude isn't interpolated.
+const MAX_GAP_S: f64 = 10.0;
+
+/// Which altitude [`select`] uses.
+///
+/// [`select`]: fn.select.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// The records' altitude, as recorded.
+    Barometric,
+    /// The `GpsMetadata` altitude, as recorded.
+    Gps,
+    /// The records' altitude, calibrated against the GPS
+    /// when there is any, or the GPS altitude without it.
+    Auto,
+}
+
+/// Where the altitude of an [`AltitudeSeries`] came from.
+///
+/// [`AltitudeSeries`]: struct.AltitudeSeries.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Barometric,
+    Gps,
+}
+
+/// Altitude over an activity, from a single source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AltitudeSeries {
+    /// `None` when the activity has no altitude at all.
+    pub source:   Option<Source>,
+    /// Metres added to every sample by calibration.
+    pub offset_m: f64,
+    /// Seconds since the FIT epoch, and metres, in time
+    /// order.
+    pub samples:  Vec<(u32, f64)>,
+}
+
+impl AltitudeSeries {
+    /// Metres, interpolated at `timestamp`, or NaN outside
+    /// the samples or in a gap in them.
+    pub fn at(&self, timestamp: u32) -> f64 {
+        self.resample(&[f64::from(timestamp)])[0]
+    }
+
+    fn resample(&self, at: &[f64]) -> Vec<f64> {
+        let samples: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .map(|&(t, altitude)| (f64::from(t), altitude))
+            .collect();
+        series::resample(&samples, at, MAX_GAP_S)
+    }
+
+    /// Total climb and descent in metres.
+    ///
+    /// Changes smaller than `threshold_m` are counted only
+    /// once they add up to it, so noise doesn't accumulate.
+    pub fn ascent_descent_m(&self, threshold_m: f64) -> (f64, f64) {
+        let (mut ascent, mut descent) = (0.0, 0.0);
+        let mut reference = match self.samples.first() {
+            Some(&(_, altitude)) => altitude,
+            None => return (0.0, 0.0),
+        };
+        for &(_, altitude) in &self.samples[1..] {
+            if altitude - reference >= threshold_m {
+                ascent += altitude - reference;
+                reference = altitude;
+            }
+            else if reference - altitude >= threshold_m {
+                descent += reference - altitude;
+                reference = altitude;
+            }
+        }
+        (ascent, descent)
+    }
+}
+
+/// The altitude of the activity in `messages`, from the
+/// source `policy` picks.
+pub fn select(messages: &[Data], policy: Policy) -> AltitudeSeries {
+    let barometric: Vec<(u32, f64)> = RecordTimeSeries::from_messages(messages)
+        .iter()
+        .filter_map(|point| {
+            let altitude = point.enhanced_altitude.or(point.altitude)?;
+            Some((point.timestamp, altitude))
+        })
+        .collect();
+    let fixes = gps::extract_gps_metadata(messages);
+    let gps = AltitudeSeries {
+        source:   Some(Source::Gps),
+        offset_m: 0.0,
+        samples:  fixes
+            .iter()
+            .filter(|fix| fix.enhanced_alt_m.is_finite())
+            .map(|fix| (fix.timestamp, fix.enhanced_alt_m))
+            .collect(),
+    };
+    let mut barometric = AltitudeSeries {
+        source:   Some(Source::Barometric),
+        offset_m: 0.0,
+        samples:  barometric,
+    };
+
+    let chosen = match policy {
+        Policy::Barometric => barometric,
+        Policy::Gps => gps,
+        Policy::Auto if barometric.samples.is_empty() => gps,
+        Policy::Auto => {
+            if let Some(offset) = calibration_offset(&barometric, &fixes) {
+                for sample in barometric.samples.iter_mut() {
+                    sample.1 += offset;
+                }
+                barometric.offset_m = offset;
+            }
+            barometric
+        },
+    };
+    if chosen.samples.is_empty() {
+        return AltitudeSeries::default();
+    }
+    chosen
+}
+
+/// Median of the GPS altitude less the barometric one,
+/// over the fixes where both are steady, or `None` if
+/// there are none.
+fn calibration_offset(
+    barometric: &AltitudeSeries,
+    fixes: &[gps::GpsMetaPoint],
+) -> Option<f64> {
+    let fixes: Vec<&gps::GpsMetaPoint> =
+        fixes.iter().filter(|fix| fix.enhanced_alt_m.is_finite()).collect();
+    let times: Vec<f64> =
+        fixes.iter().map(|f| f64::from(f.timestamp)).collect();
+    let shifted = |by: f64| -> Vec<f64> {
+        barometric.resample(&times.iter().map(|t| t + by).collect::<Vec<_>>())
+    };
+    let (before, now, after) =
+        (shifted(-STABLE_WINDOW_S), shifted(0.0), shifted(STABLE_WINDOW_S));
+
+    let mut differences: Vec<f64> = fixes
+        .iter()
+        .enumerate()
+        .filter(|&(i, fix)| {
+            let rate = (after[i] - before[i]).abs() / (2.0 * STABLE_WINDOW_S);
+            // NaN rates, at the ends or in gaps, aren't steady,
+            // but a fix without a vertical speed may be
+            let climb = fix.vertical_speed_ms().abs();
+            rate < STABLE_RATE_MS && (climb.is_nan() || climb < STABLE_RATE_MS)
+        })
+        .map(|(i, fix)| fix.enhanced_alt_m - now[i])
+        .filter(|difference| difference.is_finite())
+        .collect();
+    if differences.is_empty() {
+        return None;
+    }
+    differences.sort_by(f64::total_cmp);
+    let mid = differences.len() / 2;
+    Some(
+        if differences.len() % 2 == 1 {
+            differences[mid]
+        }
+        else {
+            (differences[mid - 1] + differences[mid]) / 2.0
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    /// Metres in the units of `enhanced_altitude`.
+    fn raw(altitude: f64) -> u32 {
+        ((altitude + 500.0) * 5.0).round() as u32
+    }
+
+    /// A minute level at 100 m then a minute climbing at
+    /// 1 m/s by the barometer, with the GPS 25 m higher,
+    /// and lagging 8 s behind on the climb.
+    fn activity() -> Vec<Data> {
+        let mut messages = Vec::new();
+        for t in 0..120u32 {
+            let baro = 100.0 + f64::from(t.saturating_sub(60));
+            let gps = 125.0 + f64::from(t.saturating_sub(68));
+            messages.push(mesg(20).u32(253, t).u32(78, raw(baro)).build());
+            messages.push(
+                mesg(160)
+                    .u32(253, t)
+                    .i32(1, 1 << 29)
+                    .i32(2, 1 << 28)
+                    .u32(3, raw(gps))
+                    .build(),
+            );
+        }
+        messages
+    }
+
+    #[test]
+    fn auto_calibrates_against_steady_gps() {
+        let messages = activity();
+
+        let baro = select(&messages, Policy::Barometric);
+        assert_eq!(baro.source, Some(Source::Barometric));
+        assert_eq!((baro.offset_m, baro.samples.len()), (0.0, 120));
+        assert_eq!(baro.at(119), 159.0);
+
+        let gps = select(&messages, Policy::Gps);
+        assert_eq!(gps.source, Some(Source::Gps));
+        assert_eq!(gps.at(0), 125.0);
+
+        // Over every fix, the lag on the climb would drag
+        // the offset down towards 17 m
+        let auto = select(&messages, Policy::Auto);
+        assert_eq!(auto.source, Some(Source::Barometric));
+        assert_eq!(auto.offset_m, 25.0);
+        assert_eq!(auto.at(0), 125.0);
+        assert_eq!(auto.at(119), 184.0);
+        assert_eq!(auto.ascent_descent_m(1.0), (59.0, 0.0));
+    }
+
+    #[test]
+    fn falls_back_without_a_source() {
+        let gps_only: Vec<Data> = activity()
+            .into_iter()
+            .filter(|m| gps::GpsMetaPoint::from_message(m).is_some())
+            .collect();
+        let auto = select(&gps_only, Policy::Auto);
+        assert_eq!((auto.source, auto.offset_m), (Some(Source::Gps), 0.0));
+        assert!(select(&gps_only, Policy::Barometric).source.is_none());
+
+        let auto = select(&[], Policy::Auto);
+        assert_eq!(auto, AltitudeSeries::default());
+        assert_eq!(auto.ascent_descent_m(1.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn ascent_ignores_noise_below_threshold() {
+        let series = AltitudeSeries {
+            source:   Some(Source::Barometric),
+            offset_m: 0.0,
+            samples:  [0.0, 0.5, 0.0, 0.5, 3.0, 2.5, 3.0, 0.0]
+                .iter()
+                .enumerate()
+                .map(|(t, &a)| (t as u32, a))
+                .collect(),
+        };
+        assert_eq!(series.ascent_descent_m(1.0), (3.0, 3.0));
+        assert_eq!(series.ascent_descent_m(0.4), (4.0, 4.0));
+    }
+}
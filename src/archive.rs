@@ -0,0 +1,242 @@
+//! Decoding FIT files wrapped in gzip or zip containers, as
+//! delivered by Garmin Connect exports.
+//!
+//! Inputs are sniffed by their magic bytes rather than
+//! trusted by file name, so a `.fit.gz` that is really a
+//! plain FIT file decodes just the same.
+use consts::{
+    DATA_TYPE,
+    HEADER_SIZE_NO_CRC,
+};
+use error::{
+    Error,
+    Result,
+};
+#[cfg(feature = "gzip")]
+use flate2::read::GzDecoder;
+use reader::FitReader;
+use std::io::{
+    self,
+    BufRead,
+    BufReader,
+    Read,
+};
+#[cfg(feature = "zip")]
+use std::{
+    fs,
+    io::{
+        Cursor,
+        Seek,
+    },
+    path::Path,
+};
+use types::record::Data;
+#[cfg(feature = "zip")]
+use zip::ZipArchive;
+
+/// The first two bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Whether `bytes` starts like a gzip stream.
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&GZIP_MAGIC)
+}
+
+/// Whether `bytes` starts like a FIT file header.
+pub fn is_fit(bytes: &[u8]) -> bool {
+    let size = usize::from(HEADER_SIZE_NO_CRC);
+    bytes.len() >= size && &bytes[size - DATA_TYPE.len()..size] == DATA_TYPE
+}
+
+/// A reader that transparently decompresses its input if it
+/// turned out to be gzipped.
+#[cfg(feature = "gzip")]
+pub enum MaybeGzip<R> {
+    Plain(BufReader<R>),
+    Gzip(GzDecoder<BufReader<R>>),
+}
+
+#[cfg(feature = "gzip")]
+impl<R: Read> MaybeGzip<R> {
+    /// Peek at the start of `reader` and wrap it in a gzip
+    /// decoder if needed.
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut buffered = BufReader::new(reader);
+        if is_gzip(buffered.fill_buf()?) {
+            Ok(MaybeGzip::Gzip(GzDecoder::new(buffered)))
+        }
+        else {
+            Ok(MaybeGzip::Plain(buffered))
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<R: Read> Read for MaybeGzip<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            MaybeGzip::Plain(r) => r.read(buf),
+            MaybeGzip::Gzip(r) => r.read(buf),
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<R: Read> FitReader<MaybeGzip<R>> {
+    /// Decode a possibly gzipped FIT file, decompressing on
+    /// the fly.
+    pub fn from_gzip(reader: R) -> Result<Self> {
+        let reader =
+            MaybeGzip::new(reader).map_err(Error::reading("gzip stream"))?;
+        FitReader::new(reader)
+    }
+}
+
+/// Iterates over the FIT files inside a zip archive,
+/// yielding each entry's name and decoded data messages.
+///
+/// Entries are decoded one at a time straight out of the
+/// archive. Entries that are neither FIT nor gzip (e.g. the
+/// JSON summaries in a Connect export) are skipped.
+#[cfg(feature = "zip")]
+pub struct ZipMessages<R> {
+    archive: ZipArchive<R>,
+    index:   usize,
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> ZipMessages<R> {
+    /// Open a zip archive from a seekable reader.
+    pub fn new(reader: R) -> Result<Self> {
+        let archive =
+            ZipArchive::new(reader).map_err(Error::reading("zip archive"))?;
+        Ok(ZipMessages {
+            archive,
+            index: 0,
+        })
+    }
+}
+
+#[cfg(feature = "zip")]
+impl<R: Read + Seek> Iterator for ZipMessages<R> {
+    type Item = (String, Result<Vec<Data>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.archive.len() {
+            let index = self.index;
+            self.index += 1;
+
+            let mut entry = match self.archive.by_index(index) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    let name = format!("#{}", index);
+                    return Some((name, Err(Error::reading("zip entry")(err))))
+                },
+            };
+            if entry.is_dir() {
+                continue
+            }
+            let name = entry.name().to_string();
+
+            // Sniff the entry before committing to decoding it
+            let size = HEADER_SIZE_NO_CRC;
+            let mut head = Vec::with_capacity(usize::from(size));
+            if let Err(err) =
+                entry.by_ref().take(u64::from(size)).read_to_end(&mut head)
+            {
+                return Some((name, Err(Error::reading("zip entry")(err))))
+            }
+            if !is_gzip(&head) && !is_fit(&head) {
+                continue
+            }
+
+            let messages = FitReader::from_gzip(Cursor::new(head).chain(entry))
+                .and_then(|mut reader| reader.read_messages());
+            return Some((name, messages))
+        }
+        None
+    }
+}
+
+/// Open the zip archive at `path` and iterate over the FIT
+/// files inside it.
+#[cfg(feature = "zip")]
+pub fn iter_zip<P: AsRef<Path>>(path: P) -> Result<ZipMessages<fs::File>> {
+    let file = fs::File::open(path).map_err(Error::reading("zip archive"))?;
+    ZipMessages::new(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{
+        write::GzEncoder,
+        Compression,
+    };
+    use std::io::{
+        Cursor,
+        Write,
+    };
+    use testutil::FitBuilder;
+
+    fn activity(records: u32) -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        for i in 0..records {
+            fit.data(0, &[&(1000 + i).to_le_bytes()[..], &[140]]);
+        }
+        fit.build()
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn sniffs_gzip_and_plain() {
+        let plain = activity(10);
+        let gzipped = gzip(&plain);
+        assert!(is_fit(&plain));
+        assert!(is_gzip(&gzipped));
+
+        for bytes in vec![plain, gzipped] {
+            let messages = FitReader::from_gzip(Cursor::new(bytes))
+                .and_then(|mut reader| reader.read_messages())
+                .unwrap();
+            assert_eq!(messages.len(), 10);
+        }
+    }
+
+    #[cfg(feature = "zip")]
+    #[test]
+    fn iterates_zip_entries() {
+        use zip::{
+            write::FileOptions,
+            ZipWriter,
+        };
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+        writer.start_file("a.fit", options).unwrap();
+        writer.write_all(&activity(3)).unwrap();
+        writer.start_file("summary.json", options).unwrap();
+        writer.write_all(b"{}").unwrap();
+        writer.start_file("b.fit.gz", options).unwrap();
+        writer.write_all(&gzip(&activity(5))).unwrap();
+        writer.start_file("broken.fit", options).unwrap();
+        writer.write_all(&activity(5)[..40]).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let entries: Vec<(String, Result<Vec<Data>>)> =
+            ZipMessages::new(Cursor::new(bytes)).unwrap().collect();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0, "a.fit");
+        assert_eq!(entries[0].1.as_ref().unwrap().len(), 3);
+        assert_eq!(entries[1].0, "b.fit.gz");
+        assert_eq!(entries[1].1.as_ref().unwrap().len(), 5);
+        assert_eq!(entries[2].0, "broken.fit");
+        assert!(entries[2].1.is_err());
+    }
+}
@@ -0,0 +1,244 @@
+//! Aircraft attitude from the `AviationAttitude` messages
+//! avionics with FIT recording log, and how far it strayed
+//! from normal flight.
+use profile::messages::{
+    AviationAttitude,
+    Message,
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// Standard gravity in metres per second squared.
+const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// A single `AviationAttitude` message, scaled into natural
+/// units.
+///
+/// Fields missing from the message are NaN.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttitudePoint {
+    /// Seconds since the FIT epoch.
+    pub timestamp: u32,
+    /// Degrees, nose up positive.
+    pub pitch_deg: f64,
+    /// Degrees, right wing down positive.
+    pub roll_deg:  f64,
+    /// Normal acceleration in multiples of standard
+    /// gravity, 1 in level flight.
+    pub g_load:    f64,
+}
+
+impl AttitudePoint {
+    /// Flatten a decoded `AviationAttitude` data message.
+    ///
+    /// Returns `None` if `mesg` isn't an `AviationAttitude`
+    /// message or has no valid timestamp.
+    pub fn from_message(mesg: &Data) -> Option<AttitudePoint> {
+        let mut timestamp = None;
+        let mut point = AttitudePoint {
+            timestamp: 0,
+            pitch_deg: f64::NAN,
+            roll_deg:  f64::NAN,
+            g_load:    f64::NAN,
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::AviationAttitude(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                AviationAttitude::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                AviationAttitude::Pitch(f) if f.is_valid() => {
+                    point.pitch_deg = f.value().to_degrees()
+                },
+                AviationAttitude::Roll(f) if f.is_valid() => {
+                    point.roll_deg = f.value().to_degrees()
+                },
+                AviationAttitude::AccelNormal(f) if f.is_valid() => {
+                    point.g_load = f.value() / STANDARD_GRAVITY
+                },
+                _ => (),
+            }
+        }
+
+        Some(AttitudePoint {
+            timestamp: timestamp?,
+            ..point
+        })
+    }
+}
+
+/// Every `AviationAttitude` message with a timestamp, in
+/// file order.
+pub fn extract_attitude(messages: &[Data]) -> Vec<AttitudePoint> {
+    messages.iter().filter_map(AttitudePoint::from_message).collect()
+}
+
+/// Where an attitude stops being normal flight.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttitudeLimits {
+    /// Degrees of bank either way.
+    pub max_roll_deg:  f64,
+    /// Degrees nose up or down.
+    pub max_pitch_deg: f64,
+    pub max_g_load:    f64,
+}
+
+impl Default for AttitudeLimits {
+    /// The usual definition of an unusual attitude: more
+    /// than 60° of bank, more than 30° of pitch or more
+    /// than 2 g.
+    fn default() -> Self {
+        AttitudeLimits {
+            max_roll_deg:  60.0,
+            max_pitch_deg: 30.0,
+            max_g_load:    2.0,
+        }
+    }
+}
+
+impl AttitudeLimits {
+    /// Whether `point` is beyond any of the limits. Missing
+    /// values never are.
+    pub fn exceeded_by(&self, point: &AttitudePoint) -> bool {
+        point.roll_deg.abs() > self.max_roll_deg
+            || point.pitch_deg.abs() > self.max_pitch_deg
+            || point.g_load > self.max_g_load
+    }
+}
+
+/// The extremes of a flight's attitude.
+///
+/// Extremes are NaN when no point had the value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttitudeEnvelope {
+    pub max_pitch_deg:    f64,
+    pub min_pitch_deg:    f64,
+    pub max_roll_deg:     f64,
+    pub min_roll_deg:     f64,
+    pub max_g_load:       f64,
+    /// Points beyond the limits.
+    pub exceedance_count: usize,
+}
+
+/// The attitude extremes of `points`, counting exceedances
+/// of the default [`AttitudeLimits`].
+///
+/// [`AttitudeLimits`]: struct.AttitudeLimits.html
+pub fn attitude_envelope(points: &[AttitudePoint]) -> AttitudeEnvelope {
+    attitude_envelope_with_limits(points, &AttitudeLimits::default())
+}
+
+/// The attitude extremes of `points`, counting exceedances
+/// of `limits`.
+pub fn attitude_envelope_with_limits(
+    points: &[AttitudePoint],
+    limits: &AttitudeLimits,
+) -> AttitudeEnvelope {
+    // `f64::max` and `min` skip NaN, so missing values
+    // never become extremes
+    let mut envelope = AttitudeEnvelope {
+        max_pitch_deg:    f64::NAN,
+        min_pitch_deg:    f64::NAN,
+        max_roll_deg:     f64::NAN,
+        min_roll_deg:     f64::NAN,
+        max_g_load:       f64::NAN,
+        exceedance_count: 0,
+    };
+    for point in points {
+        envelope.max_pitch_deg = envelope.max_pitch_deg.max(point.pitch_deg);
+        envelope.min_pitch_deg = envelope.min_pitch_deg.min(point.pitch_deg);
+        envelope.max_roll_deg = envelope.max_roll_deg.max(point.roll_deg);
+        envelope.min_roll_deg = envelope.min_roll_deg.min(point.roll_deg);
+        envelope.max_g_load = envelope.max_g_load.max(point.g_load);
+        if limits.exceeded_by(point) {
+            envelope.exceedance_count += 1;
+        }
+    }
+    envelope
+}
+
+/// Seconds spent beyond the default [`AttitudeLimits`],
+/// taking each point to last `dt_s` seconds.
+///
+/// [`AttitudeLimits`]: struct.AttitudeLimits.html
+pub fn time_in_unusual_attitude_s(points: &[AttitudePoint], dt_s: f64) -> f64 {
+    let limits = AttitudeLimits::default();
+    let unusual = points.iter().filter(|point| limits.exceeded_by(point));
+    unusual.count() as f64 * dt_s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn roll(roll_deg: f64) -> AttitudePoint {
+        AttitudePoint {
+            timestamp: 1000,
+            pitch_deg: 2.0,
+            roll_deg,
+            g_load: 1.0,
+        }
+    }
+
+    #[test]
+    fn roll_sweep() {
+        let sweep: Vec<AttitudePoint> =
+            (-90..=90).map(|deg| roll(f64::from(deg))).collect();
+
+        let envelope = attitude_envelope(&sweep);
+        assert_eq!(
+            (envelope.min_roll_deg, envelope.max_roll_deg),
+            (-90.0, 90.0)
+        );
+        assert_eq!(envelope.max_pitch_deg, 2.0);
+        assert_eq!(envelope.max_g_load, 1.0);
+        // -90 to -61 and 61 to 90
+        assert_eq!(envelope.exceedance_count, 60);
+        assert_eq!(time_in_unusual_attitude_s(&sweep, 0.1), 6.0);
+
+        let steep = AttitudeLimits {
+            max_roll_deg: 45.0,
+            ..AttitudeLimits::default()
+        };
+        let envelope = attitude_envelope_with_limits(&sweep, &steep);
+        assert_eq!(envelope.exceedance_count, 90);
+
+        let pull = AttitudePoint {
+            g_load: 3.5,
+            ..roll(0.0)
+        };
+        assert_eq!(attitude_envelope(&[pull]).exceedance_count, 1);
+        assert!(attitude_envelope(&[]).max_roll_deg.is_nan());
+    }
+
+    #[test]
+    fn from_message() {
+        let messages = vec![
+            mesg(178)
+                .u32(253, 1000)
+                // 0.5 and -0.25 radians
+                .i16(2, 5215)
+                .i16(3, -2608)
+                .i16(5, 1961)
+                .build(),
+            mesg(178).u32(253, 1001).i16(3, 0x7FFF).build(),
+            mesg(20).u32(253, 1001).build(),
+        ];
+
+        let points = extract_attitude(&messages);
+        assert_eq!(points.len(), 2);
+        assert!((points[0].pitch_deg - 28.648).abs() < 0.01);
+        assert!((points[0].roll_deg + 14.325).abs() < 0.01);
+        assert!((points[0].g_load - 2.0).abs() < 0.001);
+        assert!(points[1].roll_deg.is_nan());
+        assert!(!AttitudeLimits::default().exceeded_by(&points[1]));
+    }
+}
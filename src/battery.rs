@@ -0,0 +1,237 @@
+//! The battery of the recording device over the course of a
+//! file, for telling whether it ran out mid activity.
+//!
+//! Devices report on their battery in two places:
+//! `DeviceInfo` messages, with the voltage in 1/256 V and a
+//! status, and `Battery` events, with the voltage in mV.
+//! Both come out in volts here.
+use devices::DeviceInfo as DeviceSummary;
+use events::{
+    self,
+    Parsed,
+};
+use profile::{
+    messages::{
+        DeviceInfo,
+        Message,
+    },
+    types::{
+        BatteryStatus,
+        DateTime,
+        DeviceIndex,
+        Event,
+        EventType,
+    },
+};
+use series::RecordPoint;
+use types::record::Data;
+
+/// A reading of the recording device's battery.
+#[derive(Debug, Clone, Default)]
+pub struct BatteryState {
+    /// Volts.
+    pub voltage_v: Option<f64>,
+    pub status:    Option<BatteryStatus>,
+}
+
+/// Every reading of the recording device's battery in
+/// `messages`, in time order.
+///
+/// Readings come from the creator's `DeviceInfo` messages,
+/// ignoring sensors, and from `Battery` and `BatteryLow`
+/// events. A `BatteryLow` event reads as a `Low` status.
+/// Readings without a timestamp are skipped.
+pub fn timeline(messages: &[Data]) -> Vec<(DateTime, BatteryState)> {
+    let mut timeline: Vec<(DateTime, BatteryState)> =
+        messages.iter().filter_map(reading).collect();
+    // Stable, so readings at the same time stay in file order
+    timeline.sort_by_key(|&(timestamp, _)| timestamp);
+    timeline
+}
+
+/// When the recording stopped, if it looks like the battery
+/// ran out: the last reading is `Critical` and the timer
+/// was never stopped after it.
+///
+/// That's the time of the last record, or of the last
+/// reading if it came later.
+pub fn drained_at(messages: &[Data]) -> Option<DateTime> {
+    let timeline = timeline(messages);
+    let &(last_reading, ref last) = timeline.last()?;
+    if !matches!(last.status, Some(BatteryStatus::Critical)) {
+        return None;
+    }
+
+    let stopped = messages.iter().any(|mesg| {
+        match (events::parse(mesg), events::timestamp(mesg)) {
+            (Some(Parsed::Timer(event_type)), Some(timestamp)) => {
+                is_stop(&event_type) && timestamp >= last_reading.0
+            },
+            _ => false,
+        }
+    });
+    if stopped {
+        return None;
+    }
+
+    let last_record = messages
+        .iter()
+        .filter_map(RecordPoint::from_message)
+        .map(|point| DateTime(point.timestamp))
+        .max();
+    Some(last_record.map_or(last_reading, |last| last.max(last_reading)))
+}
+
+fn is_stop(event_type: &EventType) -> bool {
+    matches!(
+        event_type,
+        EventType::Stop
+            | EventType::StopAll
+            | EventType::StopDisable
+            | EventType::StopDisableAll
+    )
+}
+
+/// The battery reading in `mesg`, if it has one.
+fn reading(mesg: &Data) -> Option<(DateTime, BatteryState)> {
+    match mesg.0.first()? {
+        Message::DeviceInfo(_) => device_reading(mesg),
+        Message::Event(_) => {
+            let timestamp = events::timestamp(mesg)?;
+            if timestamp == u32::MAX {
+                return None;
+            }
+            let state = match events::parse(mesg)? {
+                Parsed::Battery(voltage_v) => {
+                    BatteryState {
+                        voltage_v: Some(voltage_v), status: None
+                    }
+                },
+                Parsed::Other {
+                    event: Event::BatteryLow, ..
+                } => {
+                    BatteryState {
+                        voltage_v: None,
+                        status:    Some(BatteryStatus::Low),
+                    }
+                },
+                _ => return None,
+            };
+            Some((DateTime(timestamp), state))
+        },
+        _ => None,
+    }
+}
+
+fn device_reading(mesg: &Data) -> Option<(DateTime, BatteryState)> {
+    let info = DeviceSummary::from_message(mesg)?;
+    if info.device_index != Some(DeviceIndex::CREATOR) {
+        return None;
+    }
+    let status = mesg.0.iter().find_map(|field| {
+        match field {
+            Message::DeviceInfo(DeviceInfo::BatteryStatus(f)) => {
+                match f.raw_value {
+                    BatteryStatus::Unknown => None,
+                    ref status => Some(status.clone()),
+                }
+            },
+            _ => None,
+        }
+    });
+    if info.battery_voltage.is_none() && status.is_none() {
+        return None;
+    }
+    Some((
+        DateTime(info.timestamp?),
+        BatteryState {
+            // Already scaled from 1/256 V
+            voltage_v: info.battery_voltage,
+            status,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    const CRITICAL: u8 = 5;
+
+    fn device_info(
+        timestamp: u32,
+        index: u8,
+        voltage: u16,
+        status: u8,
+    ) -> Data {
+        mesg(23)
+            .u32(253, timestamp)
+            .u8(0, index)
+            .u16(10, voltage)
+            .u8(11, status)
+            .build()
+    }
+
+    fn event(timestamp: u32, event: u8, event_type: u8, data: u32) -> Data {
+        mesg(21)
+            .u32(253, timestamp)
+            .u8(0, event)
+            .u8(1, event_type)
+            .u32(3, data)
+            .build()
+    }
+
+    fn record(timestamp: u32) -> Data {
+        mesg(20).u32(253, timestamp).build()
+    }
+
+    /// A run whose watch goes critical at 300 s and dies at
+    /// 420 s without stopping the timer.
+    fn dying_run() -> Vec<Data> {
+        let mut messages = vec![
+            event(0, 0, 0, 0), // timer start
+            device_info(0, 0, 1011, 3),
+            // A heart rate strap with its own battery
+            device_info(0, 1, 700, CRITICAL),
+        ];
+        messages.extend((0..=420).step_by(60).map(record));
+        messages.push(event(120, 11, 3, 3650)); // battery, 3.65 V
+        messages.push(event(240, 22, 3, 0)); // battery low
+        messages.push(device_info(300, 0, 870, CRITICAL));
+        messages
+    }
+
+    #[test]
+    fn readings_in_volts() {
+        let timeline = timeline(&dying_run());
+        let times: Vec<u32> = timeline.iter().map(|(t, _)| t.0).collect();
+        assert_eq!(times, vec![0, 120, 240, 300]);
+
+        // 1011 / 256 V, not 1.011 V
+        assert_eq!(timeline[0].1.voltage_v, Some(1011.0 / 256.0));
+        assert!(matches!(timeline[0].1.status, Some(BatteryStatus::Ok)));
+        assert_eq!(timeline[1].1.voltage_v, Some(3.65));
+        assert!(timeline[1].1.status.is_none());
+        assert!(matches!(timeline[2].1.status, Some(BatteryStatus::Low)));
+        assert_eq!(timeline[3].1.voltage_v, Some(870.0 / 256.0));
+    }
+
+    #[test]
+    fn drained_only_when_ending_critical() {
+        let run = dying_run();
+        assert_eq!(drained_at(&run), Some(DateTime(420)));
+
+        // Stopped properly after going critical
+        let mut stopped = run.clone();
+        stopped.push(event(420, 0, 4, 0)); // timer stop all
+        assert_eq!(drained_at(&stopped), None);
+
+        // Charged back up
+        let mut charged = run.clone();
+        charged.push(device_info(360, 0, 1000, 6));
+        assert_eq!(drained_at(&charged), None);
+
+        assert_eq!(drained_at(&[]), None);
+    }
+}
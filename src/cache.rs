@@ -0,0 +1,464 @@
+//! Timestamp indexed access to decoded messages, and a
+//! serialized form of them for caching on disk.
+//!
+//! [`serialize`] writes decoded messages in a compact
+//! binary format: each field as the bytes it was decoded
+//! from, after a schema version and a hash of the source
+//! file. [`deserialize`] decodes those bytes again, so the
+//! result is the same as decoding the file, and fails
+//! rather than return anything else: for data from another
+//! version of the format or of this crate, and for data
+//! decoded from a different file.
+//!
+//! ```no_run
+//! use garminfit::{
+//!     cache,
+//!     prelude::*,
+//! };
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let bytes = std::fs::read("activity.fit").expect("a readable file");
+//! let file = File::from_bytes(&bytes)?;
+//! let messages: Vec<Data> = file.messages().cloned().collect();
+//!
+//! let cached = cache::serialize(&bytes, &messages);
+//! let restored = cache::deserialize(&cached, &bytes)?;
+//! assert_eq!(restored.len(), messages.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`serialize`]: fn.serialize.html
+//! [`deserialize`]: fn.deserialize.html
+use byteorder::{
+    LittleEndian,
+    ReadBytesExt,
+    WriteBytesExt,
+};
+use error::{
+    Error,
+    Result,
+};
+use laps::Lap;
+use profile::messages::Message;
+use series::RecordPoint;
+use std::{
+    collections::BTreeMap,
+    io::{
+        self,
+        Read,
+    },
+    ops::Bound,
+};
+use types::record::Data;
+
+/// Leads all serialized data.
+const MAGIC: &[u8; 8] = b"FITCACHE";
+
+/// The version of the serialized format. Data is also tied
+/// to the version of this crate that wrote it, since a
+/// newer profile may decode the same bytes differently.
+pub const SCHEMA_VERSION: u16 = 1;
+
+/// Decoded `Record` and `Lap` messages keyed by their FIT
+/// timestamp, for `O(log n)` random access by time.
+///
+/// If several messages share a timestamp the last one
+/// decoded wins.
+#[derive(Debug, Clone, Default)]
+pub struct FitCache {
+    records: BTreeMap<u32, RecordPoint>,
+    laps:    BTreeMap<u32, Lap>,
+}
+
+impl FitCache {
+    /// Index all the `Record` and `Lap` messages in
+    /// `messages`. Messages without a timestamp can't be
+    /// indexed and are skipped.
+    pub fn from_messages(messages: &[Data]) -> Self {
+        let mut cache = FitCache::default();
+        for mesg in messages {
+            if let Some(point) = RecordPoint::from_message(mesg) {
+                cache.records.insert(point.timestamp, point);
+            }
+            else if let Some(lap) = Lap::from_message(mesg) {
+                cache.laps.insert(lap.timestamp, lap);
+            }
+        }
+        cache
+    }
+
+    /// Number of indexed records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether no records were indexed.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The latest record at or before `timestamp`.
+    pub fn record_at_or_before(&self, timestamp: u32) -> Option<&RecordPoint> {
+        self.records.range(..=timestamp).next_back().map(|(_, point)| point)
+    }
+
+    /// The earliest record at or after `timestamp`.
+    pub fn record_at_or_after(&self, timestamp: u32) -> Option<&RecordPoint> {
+        self.records.range(timestamp..).next().map(|(_, point)| point)
+    }
+
+    /// The record closest in time to `timestamp`. Ties go
+    /// to the earlier record.
+    pub fn record_nearest(&self, timestamp: u32) -> Option<&RecordPoint> {
+        match (
+            self.record_at_or_before(timestamp),
+            self.record_at_or_after(timestamp),
+        ) {
+            (Some(before), Some(after)) => {
+                if timestamp - before.timestamp <= after.timestamp - timestamp {
+                    Some(before)
+                }
+                else {
+                    Some(after)
+                }
+            },
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// All records with `start <= timestamp < end`, in time
+    /// order.
+    pub fn records_in_range(
+        &self,
+        start: u32,
+        end: u32,
+    ) -> impl Iterator<Item = &RecordPoint> {
+        // BTreeMap::range panics on inverted ranges
+        let end = end.max(start);
+        self.records
+            .range((Bound::Included(start), Bound::Excluded(end)))
+            .map(|(_, point)| point)
+    }
+
+    /// All indexed laps in time order.
+    pub fn laps(&self) -> impl Iterator<Item = &Lap> {
+        self.laps.values()
+    }
+
+    /// The lap whose `[start_time, timestamp]` span
+    /// contains `timestamp`, if any, by [`Lap::contains`],
+    /// so a lap without a start time only contains its end.
+    ///
+    /// [`Lap::contains`]: ../laps/struct.Lap.html#method.contains
+    pub fn lap_containing(&self, timestamp: u32) -> Option<&Lap> {
+        self.laps
+            .range(timestamp..)
+            .next()
+            .map(|(_, lap)| lap)
+            .filter(|lap| lap.contains(timestamp))
+    }
+}
+
+/// Serialize `messages`, decoded from the file `source`.
+pub fn serialize(source: &[u8], messages: &[Data]) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_cache(&mut out, source_hash(source), messages)
+        .expect("writing to a Vec doesn't fail");
+    out
+}
+
+/// The messages in data written by [`serialize`], checking
+/// that it was written by this version and from `source`.
+///
+/// [`serialize`]: fn.serialize.html
+pub fn deserialize(cache: &[u8], source: &[u8]) -> Result<Vec<Data>> {
+    let mut r = cache;
+    let mut magic = [0; 8];
+    r.read_exact(&mut magic).map_err(Error::reading("cache magic"))?;
+    if &magic != MAGIC {
+        return Err(Error::not_cache())
+    }
+
+    let schema = r
+        .read_u16::<LittleEndian>()
+        .map_err(Error::reading("cache version"))?;
+    let crate_version =
+        read_bytes(&mut r).map_err(Error::reading("cache version"))?;
+    let found =
+        format!("{}/{}", schema, String::from_utf8_lossy(&crate_version));
+    if found != version() {
+        return Err(Error::cache_version(version(), found))
+    }
+
+    let cached_hash =
+        r.read_u64::<LittleEndian>().map_err(Error::reading("cache hash"))?;
+    if cached_hash != source_hash(source) {
+        return Err(Error::stale_cache(source_hash(source), cached_hash))
+    }
+
+    let count =
+        r.read_u32::<LittleEndian>().map_err(Error::reading("cache"))?;
+    (0..count).map(|_| read_data(&mut r)).collect()
+}
+
+/// A 64 bit FNV-1a hash of a file's bytes, which
+/// [`serialize`] stores to tell when a file has changed.
+/// It's quick, not cryptographic.
+///
+/// [`serialize`]: fn.serialize.html
+pub fn source_hash(source: &[u8]) -> u64 {
+    source.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The schema and crate version, as data records them.
+fn version() -> String {
+    format!("{}/{}", SCHEMA_VERSION, env!("CARGO_PKG_VERSION"))
+}
+
+// Each message is a count of its fields, then each field's
+// message number, field number and the bytes it decodes
+// from, which are little endian whatever the source was.
+
+fn write_cache(
+    out: &mut Vec<u8>,
+    hash: u64,
+    messages: &[Data],
+) -> io::Result<()> {
+    out.extend_from_slice(MAGIC);
+    out.write_u16::<LittleEndian>(SCHEMA_VERSION)?;
+    write_bytes(out, env!("CARGO_PKG_VERSION").as_bytes())?;
+    out.write_u64::<LittleEndian>(hash)?;
+    out.write_u32::<LittleEndian>(messages.len() as u32)?;
+
+    let mut bytes = Vec::new();
+    for mesg in messages {
+        out.write_u16::<LittleEndian>(mesg.0.len() as u16)?;
+        for field in &mesg.0 {
+            bytes.clear();
+            field.encode(&mut bytes);
+            out.write_u16::<LittleEndian>(field.mesg_num())?;
+            out.write_u8(field.field_def_num())?;
+            write_bytes(out, &bytes)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_data<R: Read>(r: &mut R) -> Result<Data> {
+    let count = r
+        .read_u16::<LittleEndian>()
+        .map_err(Error::reading("cached message"))?;
+    (0..count)
+        .map(|_| {
+            let (mesg_num, field_def_num, bytes) =
+                read_field(r).map_err(Error::reading("cached field"))?;
+            Message::decode::<LittleEndian>(&bytes, mesg_num, field_def_num)
+        })
+        .collect::<Result<_>>()
+        .map(Data)
+}
+
+fn read_field<R: Read>(r: &mut R) -> io::Result<(u16, u8, Vec<u8>)> {
+    let mesg_num = r.read_u16::<LittleEndian>()?;
+    let field_def_num = r.read_u8()?;
+    Ok((mesg_num, field_def_num, read_bytes(r)?))
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) -> io::Result<()> {
+    out.write_u16::<LittleEndian>(bytes.len() as u16)?;
+    out.extend_from_slice(bytes);
+    Ok(())
+}
+
+fn read_bytes<R: Read>(r: &mut R) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0; usize::from(r.read_u16::<LittleEndian>()?)];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use testutil::mesg;
+
+    fn record(timestamp: u32, heart_rate: u8) -> Data {
+        mesg(20).u32(253, timestamp).u8(3, heart_rate).build()
+    }
+
+    #[test]
+    fn closest_record_semantics() {
+        let messages = vec![record(10, 100), record(20, 110), record(30, 120)];
+        let cache = FitCache::from_messages(&messages);
+
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.record_at_or_before(20).unwrap().timestamp, 20);
+        assert_eq!(cache.record_at_or_before(25).unwrap().timestamp, 20);
+        assert_eq!(cache.record_at_or_after(25).unwrap().timestamp, 30);
+        assert!(cache.record_at_or_before(9).is_none());
+        assert!(cache.record_at_or_after(31).is_none());
+
+        assert_eq!(cache.record_nearest(24).unwrap().timestamp, 20);
+        assert_eq!(cache.record_nearest(25).unwrap().timestamp, 20);
+        assert_eq!(cache.record_nearest(26).unwrap().timestamp, 30);
+        assert_eq!(cache.record_nearest(1000).unwrap().timestamp, 30);
+
+        let in_range: Vec<u32> =
+            cache.records_in_range(10, 30).map(|p| p.timestamp).collect();
+        assert_eq!(in_range, vec![10, 20]);
+        assert_eq!(cache.records_in_range(30, 10).count(), 0);
+    }
+
+    #[test]
+    fn large_cache_lookup() {
+        // One record every other second for just over a day.
+        let messages: Vec<Data> = (0..50_000u32)
+            .map(|i| record(1_000_000 + i * 2, (i % 200) as u8))
+            .collect();
+        let cache = FitCache::from_messages(&messages);
+        assert_eq!(cache.len(), 50_000);
+
+        for i in (0..50_000u32).step_by(997) {
+            let ts = 1_000_000 + i * 2;
+            assert_eq!(
+                cache.record_at_or_before(ts + 1).unwrap().timestamp,
+                ts
+            );
+            assert_eq!(
+                cache.record_at_or_after(ts + 1).unwrap().timestamp,
+                ts + 2
+            );
+            assert_eq!(
+                cache.record_at_or_before(ts).unwrap().heart_rate,
+                Some((i % 200) as u8)
+            );
+        }
+        assert_eq!(cache.records_in_range(1_000_000, 1_000_100).count(), 50);
+    }
+
+    #[test]
+    fn laps_by_time() {
+        let messages = vec![
+            mesg(19).u32(253, 100).u32(2, 0).build(),
+            record(50, 100),
+            mesg(19).u32(253, 200).u32(2, 100).build(),
+        ];
+        let cache = FitCache::from_messages(&messages);
+
+        assert_eq!(cache.laps().count(), 2);
+        assert_eq!(cache.lap_containing(50).unwrap().timestamp, 100);
+        assert_eq!(cache.lap_containing(150).unwrap().timestamp, 200);
+        assert!(cache.lap_containing(250).is_none());
+    }
+
+    /// Every field of every message in the profile, and
+    /// some that aren't, decoded from random bytes of a
+    /// few sizes and from invalid values.
+    fn every_field() -> Vec<Data> {
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut random_bytes = |size: usize| -> Vec<u8> {
+            (0..size)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect()
+        };
+
+        let mut messages = Vec::new();
+        for mesg_num in (0..=300).chain(vec![0xFF00, 0xFFFE]) {
+            for fill in 0..3 {
+                let fields = (0..=255u8)
+                    .filter_map(|field_def_num| {
+                        let sizes = [1, 2, 4, 8, 16, 24];
+                        let size = sizes[usize::from(field_def_num) % 6];
+                        let bytes = match fill {
+                            0 => random_bytes(size),
+                            1 => vec![0xFF; size],
+                            _ => vec![0; size],
+                        };
+                        Message::decode::<LittleEndian>(
+                            &bytes,
+                            mesg_num,
+                            field_def_num,
+                        )
+                        .ok()
+                    })
+                    .collect();
+                messages.push(Data(fields));
+            }
+        }
+        messages
+    }
+
+    #[test]
+    fn every_field_round_trips() {
+        let messages = every_field();
+        let source = b"not really a FIT file";
+        let cached = serialize(source, &messages);
+        let restored = deserialize(&cached, source).unwrap();
+
+        assert_eq!(restored.len(), messages.len());
+        for (restored, mesg) in restored.iter().zip(&messages) {
+            assert_eq!(format!("{:?}", restored), format!("{:?}", mesg));
+        }
+    }
+
+    #[cfg(feature = "testgen")]
+    #[test]
+    fn generated_files_round_trip() {
+        use testgen::{
+            generate,
+            Shape,
+        };
+        use types::file::File;
+
+        for seed in 0..8 {
+            let shape = Shape {
+                big_endian: seed % 2 == 1,
+                ..Shape::default()
+            };
+            let bytes = generate(&shape, seed);
+            let file = File::from_bytes(&bytes).unwrap();
+            let messages: Vec<Data> = file.messages().cloned().collect();
+
+            let restored =
+                deserialize(&serialize(&bytes, &messages), &bytes).unwrap();
+            assert_eq!(format!("{:?}", restored), format!("{:?}", messages));
+        }
+    }
+
+    #[test]
+    fn mismatches_fail() {
+        let messages = vec![record(10, 100)];
+        let cached = serialize(b"source", &messages);
+        assert_eq!(deserialize(&cached, b"source").unwrap().len(), 1);
+
+        let kind = |cache: &[u8], source: &[u8]| {
+            deserialize(cache, source).unwrap_err().kind().code()
+        };
+        assert_eq!(kind(&cached, b"changed"), "StaleCache");
+        assert_eq!(kind(b"FITIDX\x00\x01", b"source"), "NotCache");
+        assert_eq!(kind(&cached[..cached.len() - 1], b"source"), "Read");
+
+        // Another schema version
+        let mut other = cached.clone();
+        other[8] += 1;
+        let err = deserialize(&other, b"source").unwrap_err();
+        match err.kind() {
+            ErrorKind::CacheVersion {
+                expected,
+                found,
+            } => {
+                assert_eq!(*expected, version());
+                assert!(found.starts_with("2/"), "{}", found);
+            },
+            _ => panic!("{}", err),
+        }
+    }
+}
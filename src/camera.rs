@@ -0,0 +1,169 @@
+//! Action camera events, for lining footage up with the
+//! activity.
+use profile::messages::{
+    CameraEvent,
+    Message,
+};
+use types::record::Data;
+
+/// A single `CameraEvent` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CameraEventRecord {
+    /// Seconds since the FIT epoch.
+    pub timestamp:          u32,
+    /// The raw `camera_event_type`. Values the profile
+    /// doesn't know all read as 15.
+    pub camera_event_type:  u8,
+    /// The raw `camera_orientation`, 0xFF if missing.
+    pub camera_orientation: u8,
+}
+
+/// The camera events that matter for syncing footage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraEventType {
+    VideoStart,
+    VideoStop,
+    PhotoTaken,
+    VideoSecondStreamStart,
+    VideoSecondStreamStop,
+    /// Splits, pauses and anything else, by raw value.
+    Unknown(u8),
+}
+
+impl From<u8> for CameraEventType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => CameraEventType::VideoStart,
+            2 => CameraEventType::VideoStop,
+            3 => CameraEventType::PhotoTaken,
+            4 => CameraEventType::VideoSecondStreamStart,
+            6 => CameraEventType::VideoSecondStreamStop,
+            other => CameraEventType::Unknown(other),
+        }
+    }
+}
+
+impl CameraEventRecord {
+    /// Flatten a decoded `CameraEvent` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `CameraEvent`
+    /// message or lacks a timestamp or event type.
+    pub fn from_message(mesg: &Data) -> Option<CameraEventRecord> {
+        let mut timestamp = None;
+        let mut camera_event_type = None;
+        let mut camera_orientation = 0xFF;
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::CameraEvent(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                CameraEvent::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                CameraEvent::CameraEventType(f) => {
+                    camera_event_type = Some(f.raw_value.clone() as u8)
+                },
+                CameraEvent::CameraOrientation(f) => {
+                    camera_orientation = f.raw_value.clone() as u8
+                },
+                _ => (),
+            }
+        }
+
+        Some(CameraEventRecord {
+            timestamp: timestamp?,
+            camera_event_type: camera_event_type?,
+            camera_orientation,
+        })
+    }
+
+    pub fn event_type(&self) -> CameraEventType {
+        CameraEventType::from(self.camera_event_type)
+    }
+}
+
+/// Every `CameraEvent` message with a timestamp and event
+/// type, in file order.
+pub fn extract_camera_events(
+    messages: &[Data],
+) -> Vec<(CameraEventRecord, CameraEventType)> {
+    messages
+        .iter()
+        .filter_map(CameraEventRecord::from_message)
+        .map(|record| (record, record.event_type()))
+        .collect()
+}
+
+/// The `(start_timestamp, end_timestamp)` of every
+/// recording, ordered by start.
+///
+/// The first and second video streams are paired up
+/// separately. A start while already recording is ignored,
+/// as are stops without a start and a recording that never
+/// stops.
+pub fn recording_intervals(
+    events: &[(CameraEventRecord, CameraEventType)],
+) -> Vec<(u32, u32)> {
+    let mut intervals = Vec::new();
+    let mut first = None;
+    let mut second = None;
+    for &(ref record, event_type) in events {
+        let (recording, starts) = match event_type {
+            CameraEventType::VideoStart => (&mut first, true),
+            CameraEventType::VideoStop => (&mut first, false),
+            CameraEventType::VideoSecondStreamStart => (&mut second, true),
+            CameraEventType::VideoSecondStreamStop => (&mut second, false),
+            _ => continue,
+        };
+        if starts {
+            recording.get_or_insert(record.timestamp);
+        }
+        else if let Some(start) = recording.take() {
+            intervals.push((start, record.timestamp));
+        }
+    }
+    intervals.sort();
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn event(timestamp: u32, event_type: u8) -> Data {
+        mesg(161).u32(253, timestamp).u8(1, event_type).u8(3, 1).build()
+    }
+
+    #[test]
+    fn two_recordings() {
+        let messages = vec![
+            event(1000, 0),
+            event(1010, 3),
+            // A split doesn't end the recording
+            event(1100, 1),
+            event(1200, 2),
+            event(1300, 3),
+            event(1400, 0),
+            event(1400, 0),
+            event(1500, 2),
+            event(1600, 2),
+            event(1700, 0),
+            mesg(20).u32(253, 1000).build(),
+        ];
+
+        let events = extract_camera_events(&messages);
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[1].1, CameraEventType::PhotoTaken);
+        assert_eq!(events[2].1, CameraEventType::Unknown(1));
+        assert_eq!(events[0].0.camera_orientation, 1);
+
+        assert_eq!(
+            recording_intervals(&events),
+            vec![(1000, 1200), (1400, 1500)]
+        );
+    }
+}
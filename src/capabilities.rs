@@ -0,0 +1,491 @@
+//! What a device says it supports, from the
+//! `FileCapabilities`, `MesgCapabilities` and
+//! `FieldCapabilities` messages of its settings or device
+//! file.
+//!
+//! Each message type says one thing about one file type:
+//! where files of that type go and how many and how big
+//! they can be, how many of a message a file can hold, and
+//! how many of a field a message can.
+//! [`DeviceCapabilities`] joins them into one
+//! [`FileSupport`] per file type, so a sync tool can check
+//! that a device takes a workout of 50 steps before sending
+//! it.
+//!
+//! ```no_run
+//! use garminfit::{
+//!     capabilities::DeviceCapabilities,
+//!     profile::types::{
+//!         File,
+//!         MesgNum,
+//!     },
+//!     reader,
+//! };
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let bytes = std::fs::read("Settings.fit").expect("a readable file");
+//! let messages = reader::from_bytes(&bytes)?;
+//! let device = DeviceCapabilities::from_messages(&messages);
+//! let steps = device
+//!     .file(File::Workout)
+//!     .and_then(|workouts| workouts.mesg(MesgNum::WorkoutStep));
+//! if steps.is_some_and(|steps| !steps.accepts(50)) {
+//!     println!("Too many steps for this device");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DeviceCapabilities`]: struct.DeviceCapabilities.html
+//! [`FileSupport`]: struct.FileSupport.html
+use profile::{
+    messages::{
+        field_name,
+        FieldCapabilities,
+        FileCapabilities,
+        MesgCapabilities,
+        Message,
+    },
+    types::{
+        File,
+        FileFlags,
+        MesgCount,
+        MesgNum,
+    },
+};
+use types::record::Data;
+
+/// How many of a message a file can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountLimit {
+    /// Exactly this many in each file.
+    NumPerFile(u16),
+    /// At most this many in each file.
+    MaxPerFile(u16),
+    /// At most this many across all files of the type.
+    MaxPerFileType(u16),
+}
+
+impl CountLimit {
+    pub fn count(&self) -> u16 {
+        match *self {
+            CountLimit::NumPerFile(count)
+            | CountLimit::MaxPerFile(count)
+            | CountLimit::MaxPerFileType(count) => count,
+        }
+    }
+}
+
+/// A field of a message a device supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSupport {
+    pub field_num: u8,
+    /// The field's name in the profile.
+    pub name:      Option<&'static str>,
+    /// How many values of the field a message can hold.
+    pub count:     Option<u16>,
+}
+
+/// A message a device supports in files of one type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MesgSupport {
+    pub mesg_num: MesgNum,
+    /// The message's name in the profile.
+    pub name:     Option<&'static str>,
+    pub limit:    Option<CountLimit>,
+    /// The fields the device lists for the message, if any.
+    pub fields:   Vec<FieldSupport>,
+}
+
+impl MesgSupport {
+    /// Whether files of the type can hold `count` of the
+    /// message, as far as the device says. Without a limit
+    /// they can.
+    pub fn accepts(&self, count: u16) -> bool {
+        match self.limit {
+            Some(CountLimit::NumPerFile(limit)) => count == limit,
+            Some(limit) => count <= limit.count(),
+            None => true,
+        }
+    }
+
+    /// The field numbered `field_num`, if the device lists
+    /// it.
+    pub fn field(&self, field_num: u8) -> Option<&FieldSupport> {
+        self.fields.iter().find(|field| field.field_num == field_num)
+    }
+}
+
+/// What a device supports of one file type.
+#[derive(Debug, Clone)]
+pub struct FileSupport {
+    pub file_type: File,
+    /// Where on the device the files go.
+    pub directory: Option<String>,
+    /// The `FileFlags` bits, which can be several at once.
+    pub flags:     Option<u8>,
+    /// How many files of the type the device holds.
+    pub max_count: Option<u16>,
+    /// Bytes.
+    pub max_size:  Option<u32>,
+    /// In the order the device lists them.
+    pub messages:  Vec<MesgSupport>,
+}
+
+impl FileSupport {
+    fn new(file_type: File) -> FileSupport {
+        FileSupport {
+            file_type,
+            directory: None,
+            flags: None,
+            max_count: None,
+            max_size: None,
+            messages: Vec::new(),
+        }
+    }
+
+    /// The message `mesg_num`, if the device lists it for
+    /// the file type.
+    pub fn mesg(&self, mesg_num: MesgNum) -> Option<&MesgSupport> {
+        self.messages.iter().find(|mesg| mesg.mesg_num == mesg_num)
+    }
+
+    fn mesg_mut(&mut self, mesg_num: MesgNum) -> &mut MesgSupport {
+        let position =
+            self.messages.iter().position(|mesg| mesg.mesg_num == mesg_num);
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.messages.push(MesgSupport {
+                    mesg_num,
+                    name: mesg_num.name(),
+                    limit: None,
+                    fields: Vec::new(),
+                });
+                self.messages.len() - 1
+            },
+        };
+        &mut self.messages[index]
+    }
+
+    fn has_flag(&self, flag: FileFlags) -> bool {
+        self.flags.is_some_and(|flags| flags & flag as u8 != 0)
+    }
+
+    pub fn readable(&self) -> bool {
+        self.has_flag(FileFlags::Read)
+    }
+
+    pub fn writable(&self) -> bool {
+        self.has_flag(FileFlags::Write)
+    }
+
+    pub fn erasable(&self) -> bool {
+        self.has_flag(FileFlags::Erase)
+    }
+}
+
+/// Everything a device's capability messages say, by file
+/// type.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceCapabilities {
+    /// In the order the device first mentions them.
+    pub files: Vec<FileSupport>,
+}
+
+impl DeviceCapabilities {
+    /// Join the capability messages among `messages`.
+    ///
+    /// Messages and fields are filed under the file type
+    /// they name, whether or not a `FileCapabilities`
+    /// message describes it. Capabilities without a file
+    /// type or message number, and fields without a field
+    /// number, are skipped.
+    pub fn from_messages(messages: &[Data]) -> DeviceCapabilities {
+        let mut device = DeviceCapabilities::default();
+        for mesg in messages {
+            match mesg.0.first() {
+                Some(Message::FileCapabilities(_)) => device.add_file(mesg),
+                Some(Message::MesgCapabilities(_)) => device.add_mesg(mesg),
+                Some(Message::FieldCapabilities(_)) => device.add_field(mesg),
+                _ => (),
+            }
+        }
+        device
+    }
+
+    /// The file type `file_type`, if the device mentions
+    /// it.
+    pub fn file(&self, file_type: File) -> Option<&FileSupport> {
+        let file_type = file_type as u8;
+        self.files.iter().find(|file| file.file_type.clone() as u8 == file_type)
+    }
+
+    fn file_mut(&mut self, file_type: File) -> &mut FileSupport {
+        let raw = file_type.clone() as u8;
+        let position = self
+            .files
+            .iter()
+            .position(|file| file.file_type.clone() as u8 == raw);
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.files.push(FileSupport::new(file_type));
+                self.files.len() - 1
+            },
+        };
+        &mut self.files[index]
+    }
+
+    fn add_file(&mut self, mesg: &Data) {
+        let mut support = FileSupport::new(File::Unknown);
+        let mut file_type = None;
+        for field in mesg.0.iter() {
+            match field {
+                Message::FileCapabilities(FileCapabilities::Type(f)) => {
+                    file_type = known_file(&f.raw_value)
+                },
+                Message::FileCapabilities(FileCapabilities::Flags(f))
+                    if f.is_valid() =>
+                {
+                    support.flags = Some(f.raw_value.0)
+                },
+                Message::FileCapabilities(FileCapabilities::Directory(f)) => {
+                    support.directory = non_empty(&f.raw_value.0)
+                },
+                Message::FileCapabilities(FileCapabilities::MaxCount(f))
+                    if f.is_valid() =>
+                {
+                    support.max_count = Some(f.raw_value.0)
+                },
+                Message::FileCapabilities(FileCapabilities::MaxSize(f))
+                    if f.is_valid() =>
+                {
+                    support.max_size = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        if let Some(file_type) = file_type {
+            let file = self.file_mut(file_type);
+            file.directory = support.directory;
+            file.flags = support.flags;
+            file.max_count = support.max_count;
+            file.max_size = support.max_size;
+        }
+    }
+
+    fn add_mesg(&mut self, mesg: &Data) {
+        let (mut file_type, mut mesg_num) = (None, None);
+        let (mut count_type, mut count) = (None, None);
+        for field in mesg.0.iter() {
+            match field {
+                Message::MesgCapabilities(MesgCapabilities::File(f)) => {
+                    file_type = known_file(&f.raw_value)
+                },
+                Message::MesgCapabilities(MesgCapabilities::MesgNum(f)) => {
+                    mesg_num = known_mesg(f.raw_value)
+                },
+                Message::MesgCapabilities(MesgCapabilities::CountType(f)) => {
+                    count_type = Some(f.raw_value.clone())
+                },
+                Message::MesgCapabilities(MesgCapabilities::Count(f))
+                    if f.is_valid() =>
+                {
+                    count = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        let limit = match (count_type, count) {
+            (Some(MesgCount::NumPerFile), Some(count)) => {
+                Some(CountLimit::NumPerFile(count))
+            },
+            (Some(MesgCount::MaxPerFile), Some(count)) => {
+                Some(CountLimit::MaxPerFile(count))
+            },
+            (Some(MesgCount::MaxPerFileType), Some(count)) => {
+                Some(CountLimit::MaxPerFileType(count))
+            },
+            _ => None,
+        };
+        if let (Some(file_type), Some(mesg_num)) = (file_type, mesg_num) {
+            self.file_mut(file_type).mesg_mut(mesg_num).limit = limit;
+        }
+    }
+
+    fn add_field(&mut self, mesg: &Data) {
+        let (mut file_type, mut mesg_num) = (None, None);
+        let (mut field_num, mut count) = (None, None);
+        for field in mesg.0.iter() {
+            match field {
+                Message::FieldCapabilities(FieldCapabilities::File(f)) => {
+                    file_type = known_file(&f.raw_value)
+                },
+                Message::FieldCapabilities(FieldCapabilities::MesgNum(f)) => {
+                    mesg_num = known_mesg(f.raw_value)
+                },
+                Message::FieldCapabilities(FieldCapabilities::FieldNum(f))
+                    if f.is_valid() =>
+                {
+                    field_num = Some(f.raw_value.0)
+                },
+                Message::FieldCapabilities(FieldCapabilities::Count(f))
+                    if f.is_valid() =>
+                {
+                    count = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        if let (Some(file_type), Some(mesg_num), Some(field_num)) =
+            (file_type, mesg_num, field_num)
+        {
+            let mesg = self.file_mut(file_type).mesg_mut(mesg_num);
+            let name = field_name(mesg_num.value(), field_num);
+            match mesg.fields.iter_mut().find(|f| f.field_num == field_num) {
+                Some(field) => field.count = count,
+                None => {
+                    mesg.fields.push(FieldSupport {
+                        field_num,
+                        name,
+                        count,
+                    })
+                },
+            }
+        }
+    }
+}
+
+fn known_file(file_type: &File) -> Option<File> {
+    match file_type {
+        File::Unknown => None,
+        file_type => Some(file_type.clone()),
+    }
+}
+
+fn known_mesg(mesg_num: MesgNum) -> Option<MesgNum> {
+    match mesg_num {
+        MesgNum::Unknown => None,
+        mesg_num => Some(mesg_num),
+    }
+}
+
+/// Strings are null padded to their field size.
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim_end_matches('\0');
+    if s.is_empty() {
+        None
+    }
+    else {
+        Some(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reader::from_bytes;
+    use testutil::FitBuilder;
+
+    /// The capabilities of a device that keeps up to 100
+    /// workouts of at most 50 steps, with a
+    /// `MesgCapabilities` for a file type no
+    /// `FileCapabilities` describes.
+    fn settings() -> Vec<Data> {
+        let mut fit = FitBuilder::new();
+        fit.definition(
+            0,
+            37,
+            &[
+                (0, 1, 0x00),
+                (1, 1, 0x0A),
+                (2, 16, 0x07),
+                (3, 2, 0x84),
+                (4, 4, 0x86),
+            ],
+        );
+        let mut directory = b"Workouts".to_vec();
+        directory.resize(16, 0);
+        let read_write = FileFlags::Read as u8 | FileFlags::Write as u8;
+        fit.data(
+            0,
+            &[
+                &[5],
+                &[read_write],
+                &directory,
+                &100u16.to_le_bytes(),
+                &u32::MAX.to_le_bytes(),
+            ],
+        );
+        fit.definition(
+            1,
+            38,
+            &[(0, 1, 0x00), (1, 2, 0x84), (2, 1, 0x00), (3, 2, 0x84)],
+        );
+        fit.data(1, &[&[5], &27u16.to_le_bytes(), &[1], &50u16.to_le_bytes()]);
+        fit.data(1, &[&[5], &26u16.to_le_bytes(), &[0], &1u16.to_le_bytes()]);
+        fit.data(
+            1,
+            &[&[4], &20u16.to_le_bytes(), &[2], &0xFFFFu16.to_le_bytes()],
+        );
+        fit.definition(
+            2,
+            39,
+            &[(0, 1, 0x00), (1, 2, 0x84), (2, 1, 0x02), (3, 2, 0x84)],
+        );
+        fit.data(2, &[&[5], &27u16.to_le_bytes(), &[8], &1u16.to_le_bytes()]);
+        fit.data(2, &[&[5], &27u16.to_le_bytes(), &[200], &3u16.to_le_bytes()]);
+        fit.data(2, &[&[5], &26u16.to_le_bytes(), &[255], &1u16.to_le_bytes()]);
+        from_bytes(&fit.build()).unwrap()
+    }
+
+    #[test]
+    fn joins_capabilities() {
+        let device = DeviceCapabilities::from_messages(&settings());
+        assert_eq!(device.files.len(), 2);
+
+        let workouts = device.file(File::Workout).unwrap();
+        assert_eq!(workouts.directory.as_deref(), Some("Workouts"));
+        assert!(workouts.readable() && workouts.writable());
+        assert!(!workouts.erasable());
+        assert_eq!((workouts.max_count, workouts.max_size), (Some(100), None));
+
+        let steps = workouts.mesg(MesgNum::WorkoutStep).unwrap();
+        assert_eq!(steps.name, Some("workout_step"));
+        assert_eq!(steps.limit, Some(CountLimit::MaxPerFile(50)));
+        assert!(steps.accepts(50) && !steps.accepts(51));
+        assert_eq!(
+            steps.fields,
+            vec![
+                FieldSupport {
+                    field_num: 8,
+                    name:      Some("notes"),
+                    count:     Some(1),
+                },
+                FieldSupport {
+                    field_num: 200,
+                    name:      None,
+                    count:     Some(3),
+                },
+            ]
+        );
+        assert_eq!(steps.field(8).and_then(|field| field.name), Some("notes"));
+
+        // A field without a number is skipped
+        let workout = workouts.mesg(MesgNum::Workout).unwrap();
+        assert_eq!(workout.limit, Some(CountLimit::NumPerFile(1)));
+        assert!(workout.fields.is_empty() && !workout.accepts(2));
+
+        let activities = device.file(File::Activity).unwrap();
+        assert_eq!(activities.directory, None);
+        assert!(!activities.readable());
+        let records = activities.mesg(MesgNum::Record).unwrap();
+        assert_eq!(records.limit, None);
+        assert!(records.accepts(u16::MAX));
+        assert!(device.file(File::Course).is_none());
+    }
+}
@@ -0,0 +1,358 @@
+//! A C ABI over [`FitSliceReader`], for embedding the
+//! decoder in programs written in other languages.
+//!
+//! Built with the `capi` feature, which also makes the
+//! `cdylib` target export these functions. The matching
+//! header, `include/garminfit.h`, is generated with
+//! `cbindgen`:
+//!
+//! ```text
+//! cbindgen --config cbindgen.toml --output include/garminfit.h
+//! ```
+//!
+//! A decoder holds all of its state, so decoders are
+//! independent of each other, but each one must only be
+//! used from one thread at a time. Strings are UTF-8 and
+//! come with their length; they aren't NUL terminated.
+//!
+//! [`FitSliceReader`]: ../slice/struct.FitSliceReader.html
+use profile::messages::field_scale_offset;
+use slice::{
+    BorrowedMessage,
+    FitSliceReader,
+};
+use std::{
+    os::raw::c_int,
+    ptr,
+    slice,
+};
+use types::field::scale_value;
+
+/// What a [`FitField`] holds.
+///
+/// [`FitField`]: struct.FitField.html
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitFieldKind {
+    /// `value` holds the scaled value.
+    Number,
+    /// `string` and `string_len` hold the value.
+    String,
+    /// The field holds its base type's invalid value.
+    Invalid,
+    /// Bytes, arrays and strings that aren't UTF-8, which
+    /// aren't converted.
+    Other,
+}
+
+/// One field of a data message.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FitField {
+    /// The field definition number.
+    pub num:        u8,
+    /// The base type number from the field definition.
+    pub base_type:  u8,
+    pub kind:       FitFieldKind,
+    /// The value scaled as the profile says, or NaN unless
+    /// `kind` is `Number`.
+    pub value:      f64,
+    /// UTF-8 bytes, or null unless `kind` is `String`.
+    /// Valid until the decoder is freed.
+    pub string:     *const u8,
+    pub string_len: usize,
+}
+
+/// A data message. Definition messages are never returned.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FitMessage {
+    pub mesg_num:    u16,
+    pub field_count: usize,
+    /// `field_count` fields, in record order, valid until
+    /// the next call on the decoder.
+    pub fields:      *const FitField,
+}
+
+/// Decodes a FIT file held in memory. Opaque to C.
+pub struct FitDecoder {
+    /// Borrows from `bytes`, which outlive it.
+    reader: Option<FitSliceReader<'static>>,
+    /// A leaked `Box<[u8]>`, freed on drop.
+    bytes:  *mut [u8],
+    fields: Vec<FitField>,
+    error:  Option<String>,
+}
+
+impl Drop for FitDecoder {
+    fn drop(&mut self) {
+        self.reader = None;
+        unsafe { drop(Box::from_raw(self.bytes)) }
+    }
+}
+
+/// Start decoding a copy of the `len` bytes at `data`.
+///
+/// Never returns null. If the file header can't be read,
+/// the decoder starts out failed: [`fit_decoder_next`]
+/// returns -1 and [`fit_decoder_error`] says why.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or may be
+/// null if `len` is zero.
+///
+/// [`fit_decoder_next`]: fn.fit_decoder_next.html
+/// [`fit_decoder_error`]: fn.fit_decoder_error.html
+#[no_mangle]
+pub unsafe extern "C" fn fit_decoder_new(
+    data: *const u8,
+    len: usize,
+) -> *mut FitDecoder {
+    let bytes: Box<[u8]> = if len == 0 {
+        Box::new([])
+    }
+    else {
+        slice::from_raw_parts(data, len).into()
+    };
+    // Only freed once the reader is gone
+    let bytes = Box::into_raw(bytes);
+    let borrowed: &'static [u8] = &*bytes;
+
+    let (reader, error) = match FitSliceReader::new(borrowed) {
+        Ok(reader) => (Some(reader), None),
+        Err(err) => (None, Some(err.describe())),
+    };
+    Box::into_raw(Box::new(FitDecoder {
+        reader,
+        bytes,
+        fields: Vec::new(),
+        error,
+    }))
+}
+
+/// Decode the next data message into `out`.
+///
+/// Returns 1 if a message was decoded, 0 at the end of the
+/// file and -1 on error, after which the decoder stays
+/// failed.
+///
+/// # Safety
+///
+/// `decoder` must come from [`fit_decoder_new`] and not
+/// have been freed, and `out` must be writable.
+///
+/// [`fit_decoder_new`]: fn.fit_decoder_new.html
+#[no_mangle]
+pub unsafe extern "C" fn fit_decoder_next(
+    decoder: *mut FitDecoder,
+    out: *mut FitMessage,
+) -> c_int {
+    let decoder = &mut *decoder;
+    if decoder.error.is_some() {
+        return -1
+    }
+    let reader = match decoder.reader {
+        Some(ref mut reader) => reader,
+        None => return 0,
+    };
+
+    let data = loop {
+        match reader.next() {
+            Some(Ok(BorrowedMessage::Data(data))) => break data,
+            Some(Ok(BorrowedMessage::Definition(_))) => (),
+            Some(Err(err)) => {
+                decoder.error = Some(err.describe());
+                return -1
+            },
+            None => return 0,
+        }
+    };
+
+    let mesg_num = data.global_mesg_num();
+    decoder.fields.clear();
+    decoder.fields.extend(data.fields().map(|field| {
+        let mut out = FitField {
+            num:        field.num(),
+            base_type:  field.base_type(),
+            kind:       FitFieldKind::Other,
+            value:      f64::NAN,
+            string:     ptr::null(),
+            string_len: 0,
+        };
+        if !field.is_valid() {
+            out.kind = FitFieldKind::Invalid;
+        }
+        else if let Some(value) = field.number() {
+            let (scale, offset) = field_scale_offset(mesg_num, field.num());
+            out.kind = FitFieldKind::Number;
+            out.value = scale_value(value, scale, offset);
+        }
+        else if let Some(string) = field.as_str() {
+            out.kind = FitFieldKind::String;
+            out.string = string.as_ptr();
+            out.string_len = string.len();
+        }
+        out
+    }));
+
+    *out = FitMessage {
+        mesg_num,
+        field_count: decoder.fields.len(),
+        fields: decoder.fields.as_ptr(),
+    };
+    1
+}
+
+/// Why the decoder failed, as UTF-8 with its length stored
+/// in `len`, or null if it hasn't. Valid until the decoder
+/// is freed.
+///
+/// # Safety
+///
+/// `decoder` must come from [`fit_decoder_new`] and not
+/// have been freed, and `len` must be writable.
+///
+/// [`fit_decoder_new`]: fn.fit_decoder_new.html
+#[no_mangle]
+pub unsafe extern "C" fn fit_decoder_error(
+    decoder: *const FitDecoder,
+    len: *mut usize,
+) -> *const u8 {
+    match (*decoder).error {
+        Some(ref error) => {
+            *len = error.len();
+            error.as_ptr()
+        },
+        None => {
+            *len = 0;
+            ptr::null()
+        },
+    }
+}
+
+/// Free a decoder, and with it every message and string it
+/// returned. Does nothing if `decoder` is null.
+///
+/// # Safety
+///
+/// `decoder` must be null or come from [`fit_decoder_new`]
+/// and not have been freed already.
+///
+/// [`fit_decoder_new`]: fn.fit_decoder_new.html
+#[no_mangle]
+pub unsafe extern "C" fn fit_decoder_free(decoder: *mut FitDecoder) {
+    if !decoder.is_null() {
+        drop(Box::from_raw(decoder));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        mem,
+        str,
+    };
+    use testutil::FitBuilder;
+
+    type Fields = Vec<(FitField, Option<String>)>;
+
+    /// The messages of `bytes` as `(mesg_num, fields)`,
+    /// with the string of each string field, and the
+    /// error if decoding failed.
+    fn decode(bytes: &[u8]) -> (Vec<(u16, Fields)>, Option<String>) {
+        let text = |bytes: *const u8, len: usize| unsafe {
+            let bytes = slice::from_raw_parts(bytes, len);
+            str::from_utf8(bytes).unwrap().to_string()
+        };
+
+        let mut messages = Vec::new();
+        unsafe {
+            let decoder = fit_decoder_new(bytes.as_ptr(), bytes.len());
+            let mut mesg = mem::zeroed::<FitMessage>();
+            let status = loop {
+                let status = fit_decoder_next(decoder, &mut mesg);
+                if status != 1 {
+                    break status
+                }
+                let fields =
+                    slice::from_raw_parts(mesg.fields, mesg.field_count)
+                        .iter()
+                        .map(|&field| {
+                            let string = match field.kind {
+                                FitFieldKind::String => {
+                                    Some(text(field.string, field.string_len))
+                                },
+                                _ => None,
+                            };
+                            (field, string)
+                        })
+                        .collect();
+                messages.push((mesg.mesg_num, fields));
+            };
+
+            let mut len = 0;
+            let error = fit_decoder_error(decoder, &mut len);
+            let error =
+                if error.is_null() { None } else { Some(text(error, len)) };
+            assert_eq!(status == -1, error.is_some());
+            fit_decoder_free(decoder);
+            (messages, error)
+        }
+    }
+
+    #[test]
+    fn decodes_through_the_c_abi() {
+        let mut fit = FitBuilder::new();
+        // FileId: type, manufacturer, product name
+        fit.definition(0, 0, &[(0, 1, 0x00), (1, 2, 0x84), (8, 8, 0x07)]);
+        fit.data(0, &[&[4], &1u16.to_le_bytes(), b"Edge\0\0\0\0"]);
+        // Record: timestamp, heart rate, speed, altitude
+        fit.definition(
+            1,
+            20,
+            &[(253, 4, 0x86), (3, 1, 0x02), (6, 2, 0x84), (2, 2, 0x84)],
+        );
+        fit.data(
+            1,
+            &[
+                &1000u32.to_le_bytes(),
+                &[0xFF],
+                &5250u16.to_le_bytes(),
+                &3000u16.to_le_bytes(),
+            ],
+        );
+        let bytes = fit.build();
+
+        let (messages, error) = decode(&bytes);
+        assert_eq!(error, None);
+        assert_eq!(messages.len(), 2);
+
+        let (mesg_num, ref file_id) = messages[0];
+        assert_eq!((mesg_num, file_id.len()), (0, 3));
+        assert_eq!(file_id[0].0.kind, FitFieldKind::Number);
+        assert_eq!(file_id[0].0.value, 4.0);
+        assert_eq!(file_id[2].0.kind, FitFieldKind::String);
+        assert_eq!(file_id[2].1.as_ref().map(String::as_str), Some("Edge"));
+
+        let (mesg_num, ref record) = messages[1];
+        assert_eq!(mesg_num, 20);
+        assert_eq!(record[0].0.value, 1000.0);
+        assert_eq!(record[1].0.kind, FitFieldKind::Invalid);
+        assert!(record[1].0.value.is_nan());
+        // Scaled by 1000, and by 5 less 500
+        assert_eq!(record[2].0.value, 5.25);
+        assert_eq!(record[3].0.value, 100.0);
+
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 0xFF;
+        let (messages, error) = decode(&corrupt);
+        assert_eq!(messages.len(), 2);
+        assert!(error.unwrap().contains("CRC"));
+
+        let (messages, error) = decode(&[]);
+        assert!(messages.is_empty());
+        assert!(error.is_some());
+    }
+}
@@ -0,0 +1,521 @@
+//! Lining up several recordings of the same event, such as
+//! a group ride, to compare riders at the same moments or
+//! places.
+use events;
+use geo::{
+    GeoPoint,
+    EARTH_RADIUS_M,
+};
+use series::{
+    self,
+    RecordPoint,
+    RecordTimeSeries,
+};
+use std::f64;
+use types::record::Data;
+
+/// Spacing of the axis when aligning by time, in seconds.
+pub const TIME_STEP_S: f64 = 1.0;
+
+/// Spacing of the axis when aligning by distance or
+/// position, in metres.
+pub const DISTANCE_STEP_M: f64 = 10.0;
+
+/// How far a position may be from the reference track and
+/// still be matched onto it, in metres.
+pub const CORRIDOR_M: f64 = 50.0;
+
+/// Records further apart than this, in seconds, aren't
+/// interpolated between.
+const MAX_GAP_S: f64 = 30.0;
+
+/// Records further apart than this, in metres, aren't
+/// interpolated between.
+const MAX_GAP_M: f64 = 250.0;
+
+/// How far back along the reference track a position may
+/// be matched, in metres, so GPS jitter doesn't lose the
+/// rider while later passes over the same road still match
+/// further along.
+const BACKTRACK_M: f64 = 100.0;
+
+/// What the activities are lined up on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum AlignBy {
+    /// Wall clock time, so riders are compared at the same
+    /// moment. The axis is in seconds since the FIT epoch.
+    Time,
+    /// Each activity's own `distance` field, in metres.
+    Distance,
+    /// Distance along the first activity's GPS track, in
+    /// metres, with every other activity's positions
+    /// matched onto it. Unlike `Distance`, this doesn't
+    /// depend on the devices agreeing on how far they went.
+    Position,
+}
+
+/// One activity resampled onto the shared axis.
+///
+/// Every channel has one value per axis point, NaN where
+/// the activity has no data: before it started, after it
+/// ended, while paused, or, when aligning by position,
+/// while off the reference track.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AlignedSeries {
+    /// Seconds since the FIT epoch.
+    pub timestamp:  Vec<f64>,
+    /// The activity's own `distance`, in metres.
+    pub distance_m: Vec<f64>,
+    /// Beats per minute.
+    pub heart_rate: Vec<f64>,
+    /// Watts.
+    pub power:      Vec<f64>,
+    /// Revolutions per minute.
+    pub cadence:    Vec<f64>,
+    /// Metres per second.
+    pub speed_ms:   Vec<f64>,
+    /// Metres.
+    pub altitude_m: Vec<f64>,
+}
+
+impl AlignedSeries {
+    fn resample(
+        points: &[(f64, &RecordPoint)],
+        axis: &[f64],
+        max_gap: f64,
+    ) -> AlignedSeries {
+        let channel = |value: &dyn Fn(&RecordPoint) -> Option<f64>| {
+            let samples: Vec<(f64, f64)> = points
+                .iter()
+                .filter_map(|&(x, point)| value(point).map(|y| (x, y)))
+                .collect();
+            series::resample(&samples, axis, max_gap)
+        };
+
+        AlignedSeries {
+            timestamp:  channel(&|p| Some(f64::from(p.timestamp))),
+            distance_m: channel(&|p| p.distance),
+            heart_rate: channel(&|p| p.heart_rate.map(f64::from)),
+            power:      channel(&|p| p.power.map(f64::from)),
+            cadence:    channel(&|p| p.cadence.map(f64::from)),
+            speed_ms:   channel(&|p| p.enhanced_speed.or(p.speed)),
+            altitude_m: channel(&|p| p.enhanced_altitude.or(p.altitude)),
+        }
+    }
+
+    /// Blank out every channel at axis point `i`.
+    fn clear(&mut self, i: usize) {
+        for channel in &mut [
+            &mut self.timestamp,
+            &mut self.distance_m,
+            &mut self.heart_rate,
+            &mut self.power,
+            &mut self.cadence,
+            &mut self.speed_ms,
+            &mut self.altitude_m,
+        ] {
+            channel[i] = f64::NAN;
+        }
+    }
+}
+
+/// Several activities resampled onto a shared axis: one row
+/// per activity, one column per axis point.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct AlignedSet {
+    pub mode:   AlignBy,
+    /// Ascending, in the units described by `mode`.
+    pub axis:   Vec<f64>,
+    /// In the order the activities were given.
+    pub series: Vec<AlignedSeries>,
+}
+
+impl AlignedSet {
+    /// Number of axis points.
+    pub fn len(&self) -> usize {
+        self.axis.len()
+    }
+
+    /// Whether the axis has no points.
+    pub fn is_empty(&self) -> bool {
+        self.axis.is_empty()
+    }
+}
+
+/// Resample the records of each activity in `activities`
+/// onto a shared axis chosen by `mode`.
+///
+/// Records are linearly interpolated, but not across gaps
+/// in the recording. When aligning by time the timer pauses
+/// are also left empty, and activities that started late
+/// or finished early are padded with NaN. When aligning by
+/// position the first activity is the reference, so it
+/// should be the one with the cleanest track.
+pub fn align(activities: &[&[Data]], mode: AlignBy) -> AlignedSet {
+    let records: Vec<Vec<RecordPoint>> = activities
+        .iter()
+        .map(|messages| {
+            let mut points = RecordTimeSeries::from_messages(messages).points;
+            points.sort_by_key(|point| point.timestamp);
+            points
+        })
+        .collect();
+
+    let (axis, series) = match mode {
+        AlignBy::Time => align_by_time(activities, &records),
+        AlignBy::Distance => align_by_distance(&records),
+        AlignBy::Position => align_by_position(&records),
+    };
+
+    AlignedSet {
+        mode,
+        axis,
+        series,
+    }
+}
+
+fn align_by_time(
+    activities: &[&[Data]],
+    records: &[Vec<RecordPoint>],
+) -> (Vec<f64>, Vec<AlignedSeries>) {
+    let start = records
+        .iter()
+        .filter_map(|points| points.first())
+        .min_by_key(|point| point.timestamp);
+    let end = records
+        .iter()
+        .filter_map(|points| points.last())
+        .max_by_key(|point| point.timestamp);
+    let axis = match (start, end) {
+        (Some(start), Some(end)) => {
+            steps(
+                f64::from(start.timestamp),
+                f64::from(end.timestamp),
+                TIME_STEP_S,
+            )
+        },
+        _ => Vec::new(),
+    };
+
+    let series = activities
+        .iter()
+        .zip(records)
+        .map(|(messages, points)| {
+            let points: Vec<(f64, &RecordPoint)> = points
+                .iter()
+                .map(|point| (f64::from(point.timestamp), point))
+                .collect();
+            let mut series = AlignedSeries::resample(&points, &axis, MAX_GAP_S);
+
+            for (start, end) in events::pause_intervals(messages.iter()) {
+                let (start, end) = (f64::from(start), f64::from(end));
+                for (i, &t) in axis.iter().enumerate() {
+                    if start < t && t < end {
+                        series.clear(i);
+                    }
+                }
+            }
+            series
+        })
+        .collect();
+
+    (axis, series)
+}
+
+fn align_by_distance(
+    records: &[Vec<RecordPoint>],
+) -> (Vec<f64>, Vec<AlignedSeries>) {
+    let along: Vec<Vec<(f64, &RecordPoint)>> = records
+        .iter()
+        .map(|points| {
+            increasing(points.iter().filter_map(|point| {
+                point.distance.map(|distance| (distance, point))
+            }))
+        })
+        .collect();
+
+    let axis = distance_axis(&along);
+    let series = along
+        .iter()
+        .map(|points| AlignedSeries::resample(points, &axis, MAX_GAP_M))
+        .collect();
+
+    (axis, series)
+}
+
+fn align_by_position(
+    records: &[Vec<RecordPoint>],
+) -> (Vec<f64>, Vec<AlignedSeries>) {
+    let origin = records.first().and_then(|points| {
+        points.iter().filter_map(RecordPoint::position).next()
+    });
+    let origin = match origin {
+        Some(origin) => origin,
+        None => {
+            let empty = records.iter().map(|_| AlignedSeries::default());
+            return (Vec::new(), empty.collect());
+        },
+    };
+
+    let reference = Track::new(
+        records[0]
+            .iter()
+            .filter_map(RecordPoint::position)
+            .map(|point| project(&origin, &point))
+            .collect(),
+    );
+
+    let along: Vec<Vec<(f64, &RecordPoint)>> = records
+        .iter()
+        .map(|points| {
+            let mut after = f64::NEG_INFINITY;
+            increasing(points.iter().filter_map(|point| {
+                let position = project(&origin, &point.position()?);
+                let along = reference.locate(position, after)?;
+                after = after.max(along - BACKTRACK_M);
+                Some((along, point))
+            }))
+        })
+        .collect();
+
+    let axis = steps(0.0, reference.length(), DISTANCE_STEP_M);
+    let series = along
+        .iter()
+        .map(|points| AlignedSeries::resample(points, &axis, MAX_GAP_M))
+        .collect();
+
+    (axis, series)
+}
+
+/// `from`, `from + step`, ... up to and including `to`.
+fn steps(from: f64, to: f64, step: f64) -> Vec<f64> {
+    if to.is_nan() || to < from {
+        return Vec::new();
+    }
+    let n = ((to - from) / step).floor() as usize;
+    (0..=n).map(|i| from + i as f64 * step).collect()
+}
+
+/// An axis from zero to the furthest distance reached.
+fn distance_axis(along: &[Vec<(f64, &RecordPoint)>]) -> Vec<f64> {
+    let furthest = along
+        .iter()
+        .filter_map(|points| points.last())
+        .map(|&(distance, _)| distance)
+        .fold(f64::NAN, f64::max);
+    steps(0.0, furthest, DISTANCE_STEP_M)
+}
+
+/// The points that get further along than every point
+/// before them, so the result can be interpolated over.
+fn increasing<'a, I>(points: I) -> Vec<(f64, &'a RecordPoint)>
+where
+    I: Iterator<Item = (f64, &'a RecordPoint)>,
+{
+    let mut furthest = f64::NEG_INFINITY;
+    points
+        .filter(|&(along, _)| {
+            let further = along > furthest;
+            furthest = furthest.max(along);
+            further
+        })
+        .collect()
+}
+
+/// `point` in metres east and north of `origin`, on a plane
+/// tangent to the earth there. Good enough over the extent
+/// of a single event.
+fn project(origin: &GeoPoint, point: &GeoPoint) -> (f64, f64) {
+    let x = (point.lon - origin.lon).to_radians()
+        * origin.lat.to_radians().cos()
+        * EARTH_RADIUS_M;
+    let y = (point.lat - origin.lat).to_radians() * EARTH_RADIUS_M;
+    (x, y)
+}
+
+/// A polyline with the distance along it to each vertex.
+struct Track {
+    vertices: Vec<(f64, f64)>,
+    along:    Vec<f64>,
+}
+
+impl Track {
+    fn new(vertices: Vec<(f64, f64)>) -> Track {
+        let mut along = Vec::with_capacity(vertices.len());
+        let mut total = 0.0;
+        for (i, vertex) in vertices.iter().enumerate() {
+            if i > 0 {
+                let prev = vertices[i - 1];
+                total += (vertex.0 - prev.0).hypot(vertex.1 - prev.1);
+            }
+            along.push(total);
+        }
+
+        Track {
+            vertices,
+            along,
+        }
+    }
+
+    fn length(&self) -> f64 {
+        self.along.last().cloned().unwrap_or(0.0)
+    }
+
+    /// The distance along the track of the point on it
+    /// nearest to `point`, among those at or beyond
+    /// `after`.
+    ///
+    /// Only the first stretch of track within the corridor
+    /// is searched, which keeps a rider on the right lap or
+    /// leg where the track passes the same road more than
+    /// once.
+    fn locate(&self, point: (f64, f64), after: f64) -> Option<f64> {
+        let mut nearest: Option<(f64, f64)> = None;
+        for (segment, &along) in self.vertices.windows(2).zip(&self.along) {
+            let (a, b) = (segment[0], segment[1]);
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length_sq = dx * dx + dy * dy;
+            let t = if length_sq > 0.0 {
+                ((point.0 - a.0) * dx + (point.1 - a.1) * dy) / length_sq
+            }
+            else {
+                0.0
+            };
+            let t = t.clamp(0.0, 1.0);
+            let offset = (a.0 + t * dx - point.0).hypot(a.1 + t * dy - point.1);
+            let along = along + t * length_sq.sqrt();
+
+            let best = nearest.map_or(f64::INFINITY, |(offset, _)| offset);
+            if offset <= CORRIDOR_M && along >= after {
+                if offset < best {
+                    nearest = Some((offset, along));
+                }
+            }
+            else if nearest.is_some() {
+                break;
+            }
+        }
+        nearest.map(|(_, along)| along)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    // Semicircles per metre north, near enough
+    const NORTH_M: f64 = 2_147_483_648.0 / 180.0 / 111_195.0;
+    // Semicircles per metre east at 51 degrees north
+    const EAST_M: f64 = NORTH_M / 0.629_320_391;
+    const LAT: i32 = 608_453_700; // 51 degrees
+
+    fn record(ts: u32, north_m: f64, east_m: f64, distance_m: f64) -> Data {
+        mesg(20)
+            .u32(253, ts)
+            .i32(0, LAT + (north_m * NORTH_M).round() as i32)
+            .i32(1, (east_m * EAST_M).round() as i32)
+            .u8(3, (100 + ts % 100) as u8)
+            .u32(5, (distance_m * 100.0).round() as u32)
+            .u16(7, 200)
+            .build()
+    }
+
+    fn timer(ts: u32, event_type: u8) -> Data {
+        mesg(21).u32(253, ts).u8(0, 0).u8(1, event_type).build()
+    }
+
+    /// Rider A rides north at 10 m/s from t = 1000 to 1060.
+    fn rider_a() -> Vec<Data> {
+        (0..=60)
+            .map(|i| record(1000 + i, 10.0 * i as f64, 0.0, 10.0 * i as f64))
+            .collect()
+    }
+
+    /// Rider B starts ten seconds later, 20 m to the east,
+    /// recording every two seconds with a device reading
+    /// 10% long, and stops for a while at t = 1030.
+    fn rider_b() -> Vec<Data> {
+        let mut messages = vec![timer(1010, 0)];
+        for ts in (1010..=1070).step_by(2) {
+            if ts > 1030 && ts < 1040 {
+                continue;
+            }
+            if ts == 1040 {
+                messages.push(timer(1040, 0));
+            }
+            let moving = (ts.min(1030) - 1010 + ts.max(1040) - 1040) as f64;
+            messages.push(record(ts, 10.0 * moving, 20.0, 11.0 * moving));
+            if ts == 1030 {
+                messages.push(timer(1030, 4));
+            }
+        }
+        messages
+    }
+
+    #[test]
+    fn align_by_time() {
+        let (a, b) = (rider_a(), rider_b());
+        let set = align(&[&a, &b], AlignBy::Time);
+
+        assert_eq!(set.len(), 71);
+        assert_eq!((set.axis[0], set.axis[70]), (1000.0, 1070.0));
+
+        let (a, b) = (&set.series[0], &set.series[1]);
+        assert_eq!(a.power[0], 200.0);
+        assert!(b.power[0].is_nan());
+        assert_eq!(b.heart_rate[11], 111.0);
+        assert!(b.heart_rate[35].is_nan());
+        assert_eq!(b.heart_rate[40], 140.0);
+        assert!(a.power[61].is_nan());
+    }
+
+    #[test]
+    fn align_by_distance() {
+        let (a, b) = (rider_a(), rider_b());
+        let set = align(&[&a, &b], AlignBy::Distance);
+
+        assert_eq!(set.axis.last(), Some(&600.0));
+        assert_eq!(set.series[0].timestamp[10], 1010.0);
+        assert_eq!(set.series[1].timestamp[11], 1020.0);
+        // Rider B's device thinks they only went 550 m
+        assert!(set.series[1].timestamp[60].is_nan());
+    }
+
+    #[test]
+    fn align_by_position() {
+        let (a, mut b) = (rider_a(), rider_b());
+        // A detour well off the road
+        b.push(record(1072, 500.0, 400.0, 700.0));
+        let set = align(&[&a, &b], AlignBy::Position);
+
+        assert_eq!(set.axis.last(), Some(&600.0));
+        let (a, b) = (&set.series[0], &set.series[1]);
+        assert!((a.timestamp[10] - 1010.0).abs() < 0.1);
+        // Rider B is 100 m along the road ten seconds in,
+        // whatever their device says
+        assert!((b.timestamp[10] - 1020.0).abs() < 0.1);
+        assert!((b.distance_m[10] - 110.0).abs() < 1.0);
+        assert!(b.timestamp[60].is_nan());
+    }
+
+    #[test]
+    fn locate_keeps_to_the_right_leg() {
+        let out_and_back = Track::new(
+            (0..=20)
+                .map(|i| (0.0, 1000.0 - (100.0 * f64::from(i) - 1000.0).abs()))
+                .collect(),
+        );
+
+        assert_eq!(
+            out_and_back.locate((10.0, 500.0), f64::NEG_INFINITY),
+            Some(500.0)
+        );
+        assert_eq!(out_and_back.locate((10.0, 500.0), 1400.0), Some(1500.0));
+        assert_eq!(out_and_back.locate((80.0, 500.0), f64::NEG_INFINITY), None);
+        assert_eq!(out_and_back.length(), 2000.0);
+    }
+}
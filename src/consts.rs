@@ -0,0 +1,164 @@
+//! Limits and magic numbers of the FIT protocol.
+use std::fmt;
+
+/// Size of a file header without a header CRC.
+pub const HEADER_SIZE_NO_CRC: u8 = 12;
+
+/// Size of a file header ending in a header CRC.
+pub const HEADER_SIZE_WITH_CRC: u8 = HEADER_SIZE_NO_CRC + CRC_SIZE;
+
+/// The largest file header size accepted. Sizes other than
+/// the two above, up to this one, are read leniently.
+pub const HEADER_SIZE_MAX: u8 = 100;
+
+/// The data type tag every file header carries.
+pub const DATA_TYPE: &[u8; 4] = b".FIT";
+
+/// Size of the header and file CRCs.
+pub const CRC_SIZE: u8 = 2;
+
+/// Lookup table for the CRC-16 used by FIT, processing four
+/// bits at a time, as given in the SDK documentation.
+pub const CRC_TABLE: [u16; 16] = [
+    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001,
+    0x6C00, 0x7800, 0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+];
+
+/// Local message types a normal record header can address.
+pub const MAX_LOCAL_MESSAGE_TYPES: u8 = 16;
+
+/// Local message types a compressed timestamp header can
+/// address.
+pub const MAX_COMPRESSED_LOCAL_MESSAGE_TYPES: u8 = 4;
+
+/// Largest size of a single field, in bytes.
+pub const MAX_FIELD_SIZE: usize = 255;
+
+/// The FIT protocol version a file was written with,
+/// encoded in the header as `major << 4 | minor`.
+///
+/// Versions order by major, then minor version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl ProtocolVersion {
+    /// The newest version this crate decodes.
+    pub const MAX_SUPPORTED: ProtocolVersion = ProtocolVersion::V2_0;
+    pub const V1_0: ProtocolVersion = ProtocolVersion::new(1, 0);
+    pub const V2_0: ProtocolVersion = ProtocolVersion::new(2, 0);
+
+    pub const fn new(major: u8, minor: u8) -> ProtocolVersion {
+        ProtocolVersion {
+            major,
+            minor,
+        }
+    }
+
+    /// Whether files of this version can be decoded.
+    ///
+    /// Minor versions are backwards compatible, so only the
+    /// major version has to be supported.
+    pub fn supported(&self) -> bool {
+        self.major <= ProtocolVersion::MAX_SUPPORTED.major
+    }
+}
+
+impl From<u8> for ProtocolVersion {
+    fn from(byte: u8) -> Self {
+        ProtocolVersion::new(byte >> 4, byte & 0x0F)
+    }
+}
+
+impl From<ProtocolVersion> for u8 {
+    fn from(version: ProtocolVersion) -> Self {
+        version.major << 4 | version.minor & 0x0F
+    }
+}
+
+impl fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// The version of the FIT profile a file was written with,
+/// encoded in the header as `major * 100 + minor`, e.g.
+/// `2066` for 20.66.
+///
+/// Versions order by major, then minor version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProfileVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProfileVersion {
+    /// The profile version the `profile` module was
+    /// generated from.
+    pub const CURRENT: ProfileVersion = ProfileVersion::new(20, 66);
+
+    pub const fn new(major: u16, minor: u16) -> ProfileVersion {
+        ProfileVersion {
+            major,
+            minor,
+        }
+    }
+
+    /// Whether every message and field of this version is
+    /// known to the crate.
+    ///
+    /// Files from newer profiles still decode, but messages
+    /// and fields added since come out as `Unknown`.
+    pub fn supported(&self) -> bool {
+        *self <= ProfileVersion::CURRENT
+    }
+}
+
+impl From<u16> for ProfileVersion {
+    fn from(value: u16) -> Self {
+        ProfileVersion::new(value / 100, value % 100)
+    }
+}
+
+impl From<ProfileVersion> for u16 {
+    fn from(version: ProfileVersion) -> Self {
+        version.major * 100 + version.minor
+    }
+}
+
+impl fmt::Display for ProfileVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:02}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protocol_versions() {
+        let version = ProtocolVersion::from(0x21);
+        assert_eq!(version, ProtocolVersion::new(2, 1));
+        assert_eq!(u8::from(version), 0x21);
+        assert_eq!(version.to_string(), "2.1");
+        assert!(version > ProtocolVersion::V2_0);
+        assert!(ProtocolVersion::V1_0 < ProtocolVersion::from(0x1F));
+        assert!(version.supported());
+        assert!(!ProtocolVersion::from(0x30).supported());
+    }
+
+    #[test]
+    fn profile_versions() {
+        let version = ProfileVersion::from(2105);
+        assert_eq!(version, ProfileVersion::new(21, 5));
+        assert_eq!(u16::from(version), 2105);
+        assert_eq!(version.to_string(), "21.05");
+        assert!(version > ProfileVersion::CURRENT);
+        assert!(!version.supported());
+        assert!(ProfileVersion::from(1600).supported());
+    }
+}
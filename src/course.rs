@@ -0,0 +1,147 @@
+//! How closely an activity followed a planned course, from
+//! the `time_from_course` the device records while
+//! navigating.
+use series::RecordTimeSeries;
+
+/// Deviation under which a record counts as on course, in
+/// seconds.
+pub const ON_COURSE_S: f64 = 5.0;
+
+/// Summary of the time ahead of or behind a course.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CourseDeviationStats {
+    /// Largest lead over the course, zero if never ahead.
+    pub max_ahead_s:             f64,
+    /// Largest deficit to the course, zero if never behind.
+    pub max_behind_s:            f64,
+    /// Time spent
+    /// [`ON_COURSE_S`](constant.ON_COURSE_S.html)
+    /// or more off the course.
+    pub total_time_off_course_s: f64,
+    /// Percentage of the time spent on course, NaN if fewer
+    /// than two records have a deviation.
+    pub on_course_pct:           f64,
+}
+
+/// Summarise the course deviation over `series`.
+///
+/// Each record with a deviation stands for the time until
+/// the next one that has one. Records without a deviation
+/// are skipped.
+pub fn analyze_course_deviation(
+    series: &RecordTimeSeries,
+) -> CourseDeviationStats {
+    let deviations: Vec<(u32, f64)> = deviations(series).collect();
+
+    let mut stats = CourseDeviationStats {
+        max_ahead_s:             0.0,
+        max_behind_s:            0.0,
+        total_time_off_course_s: 0.0,
+        on_course_pct:           f64::NAN,
+    };
+    let mut total_s = 0.0;
+    for (i, &(timestamp, deviation)) in deviations.iter().enumerate() {
+        stats.max_ahead_s = stats.max_ahead_s.max(deviation);
+        stats.max_behind_s = stats.max_behind_s.max(-deviation);
+
+        let duration = match deviations.get(i + 1) {
+            Some(&(next, _)) => f64::from(next.saturating_sub(timestamp)),
+            None => 0.0,
+        };
+        total_s += duration;
+        if deviation.abs() >= ON_COURSE_S {
+            stats.total_time_off_course_s += duration;
+        }
+    }
+    if total_s > 0.0 {
+        stats.on_course_pct =
+            (total_s - stats.total_time_off_course_s) / total_s * 100.0;
+    }
+    stats
+}
+
+/// When the rider first got back within `threshold_s` of
+/// the course after drifting off it, in seconds since the
+/// FIT epoch.
+///
+/// Returns `None` if the deviation never reached
+/// `threshold_s`, or never came back under it.
+pub fn time_to_course_correction(
+    series: &RecordTimeSeries,
+    threshold_s: f64,
+) -> Option<u32> {
+    let mut off_course = false;
+    for (timestamp, deviation) in deviations(series) {
+        if deviation.abs() >= threshold_s {
+            off_course = true;
+        }
+        else if off_course {
+            return Some(timestamp)
+        }
+    }
+    None
+}
+
+fn deviations<'a>(
+    series: &'a RecordTimeSeries,
+) -> impl Iterator<Item = (u32, f64)> + 'a {
+    series.iter().filter_map(|point| {
+        point.time_from_course.map(|deviation| (point.timestamp, deviation))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use series::RecordPoint;
+
+    fn series(deviations: &[Option<f64>]) -> RecordTimeSeries {
+        RecordTimeSeries {
+            points: deviations
+                .iter()
+                .enumerate()
+                .map(|(i, &time_from_course)| {
+                    RecordPoint {
+                        timestamp: 1000 + 10 * i as u32,
+                        time_from_course,
+                        ..RecordPoint::default()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn missed_turn() {
+        // Ahead early on, then a missed turn peaking at 42 s
+        // behind, before getting back on course at 1060
+        let series = series(&[
+            Some(3.0),
+            Some(8.0),
+            Some(2.0),
+            Some(-12.0),
+            None,
+            Some(-42.0),
+            Some(-4.5),
+            Some(-1.0),
+        ]);
+
+        let stats = analyze_course_deviation(&series);
+        assert_eq!(stats.max_ahead_s, 8.0);
+        assert_eq!(stats.max_behind_s, 42.0);
+        // 10 s at +8, 20 s at -12 and 10 s at -42
+        assert_eq!(stats.total_time_off_course_s, 40.0);
+        assert_eq!(stats.on_course_pct, 30.0 / 70.0 * 100.0);
+
+        assert_eq!(time_to_course_correction(&series, ON_COURSE_S), Some(1020));
+        assert_eq!(time_to_course_correction(&series, 10.0), Some(1060));
+        assert_eq!(time_to_course_correction(&series, 60.0), None);
+    }
+
+    #[test]
+    fn without_deviations() {
+        let stats = analyze_course_deviation(&series(&[None, Some(1.0)]));
+        assert_eq!(stats.total_time_off_course_s, 0.0);
+        assert!(stats.on_course_pct.is_nan());
+    }
+}
@@ -0,0 +1,194 @@
+//! The FIT checksum of bytes as they pass through a reader
+//! or writer.
+//!
+//! A FIT file ends in the CRC-16 of everything before it,
+//! so wrapping the source of a download in a [`CrcReader`],
+//! or the destination of an encoder in a [`CrcWriter`],
+//! gives the checksum without a second pass over the bytes:
+//!
+//! ```
+//! use garminfit::crc::CrcWriter;
+//! use std::io::Write;
+//!
+//! let mut w = CrcWriter::new(Vec::new());
+//! w.write_all(b"123456789").unwrap();
+//! assert_eq!(w.crc(), 0xBB3D);
+//! ```
+//!
+//! [`CrcReader`]: struct.CrcReader.html
+//! [`CrcWriter`]: struct.CrcWriter.html
+use dyncrc16::CRC16;
+use std::io::{
+    self,
+    Read,
+    Write,
+};
+
+/// Checksums the bytes read through it.
+pub struct CrcReader<R> {
+    inner: R,
+    crc:   CRC16,
+}
+
+impl<R> CrcReader<R> {
+    pub fn new(inner: R) -> Self {
+        CrcReader::with_crc(inner, 0)
+    }
+
+    /// Carry on from `crc`, the checksum of bytes read
+    /// before.
+    pub fn with_crc(inner: R, crc: u16) -> Self {
+        CrcReader {
+            inner,
+            crc: CRC16::resume(crc),
+        }
+    }
+
+    /// The checksum of every byte read since creation or
+    /// the last [`reset`](#method.reset).
+    pub fn crc(&self) -> u16 {
+        self.crc.sum_16()
+    }
+
+    /// Start the checksum over from here.
+    pub fn reset(&mut self) {
+        self.crc = CRC16::new();
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// The wrapped reader. Bytes read from it directly
+    /// aren't checksummed.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for CrcReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Checksums the bytes written through it.
+pub struct CrcWriter<W> {
+    inner: W,
+    crc:   CRC16,
+}
+
+impl<W> CrcWriter<W> {
+    pub fn new(inner: W) -> Self {
+        CrcWriter::with_crc(inner, 0)
+    }
+
+    /// Carry on from `crc`, the checksum of bytes written
+    /// before.
+    pub fn with_crc(inner: W, crc: u16) -> Self {
+        CrcWriter {
+            inner,
+            crc: CRC16::resume(crc),
+        }
+    }
+
+    /// The checksum of every byte written since creation
+    /// or the last [`reset`](#method.reset).
+    pub fn crc(&self) -> u16 {
+        self.crc.sum_16()
+    }
+
+    /// Start the checksum over from here.
+    pub fn reset(&mut self) {
+        self.crc = CRC16::new();
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// The wrapped writer. Bytes written to it directly
+    /// aren't checksummed.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CrcWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Only what the writer took
+        let n = self.inner.write(buf)?;
+        self.crc.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::FitBuilder;
+
+    #[test]
+    fn known_answers() {
+        // The CRC-16/ARC check value, and the FIT SDK's
+        // golden values
+        for &(input, want) in &[
+            (&b"123456789"[..], 0xBB3D),
+            (b"", 0x0000),
+            (b"a", 0xE8C1),
+            (b"abcdefghij", 0xC8B1),
+        ] {
+            let mut w = CrcWriter::new(Vec::new());
+            w.write_all(input).unwrap();
+            assert_eq!(w.crc(), want);
+            assert_eq!(w.into_inner(), input);
+
+            let mut r = CrcReader::new(input);
+            let mut out = Vec::new();
+            r.read_to_end(&mut out).unwrap();
+            assert_eq!(r.crc(), want);
+        }
+    }
+
+    #[test]
+    fn whole_file_checksums_to_zero() {
+        // A header followed by its CRC checksums to zero,
+        // and so does the rest of the file
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86)]);
+        fit.data(0, &[&1000u32.to_le_bytes()]);
+        let bytes = fit.build();
+
+        let mut r = CrcReader::new(&bytes[..]);
+        let mut header = [0; 12];
+        r.read_exact(&mut header).unwrap();
+        assert_eq!(r.crc().to_le_bytes(), bytes[12..14]);
+        r.read_exact(&mut header[..2]).unwrap();
+        assert_eq!(r.crc(), 0);
+        r.reset();
+        io::copy(&mut r, &mut io::sink()).unwrap();
+        assert_eq!(r.crc(), 0);
+
+        // Picking up where another checksum left off
+        let mut w = CrcWriter::new(io::sink());
+        w.write_all(&bytes[..5]).unwrap();
+        let crc = w.crc();
+        let mut w = CrcWriter::with_crc(w.into_inner(), crc);
+        w.write_all(&bytes[5..]).unwrap();
+        assert_eq!(w.crc(), 0);
+    }
+}
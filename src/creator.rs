@@ -0,0 +1,164 @@
+//! Working out where and when a file was created.
+use profile::{
+    messages::{
+        Activity,
+        FileId,
+        Message,
+        Record,
+        Session,
+    },
+    types::DateTime,
+};
+use types::record::Data;
+
+pub use time::MIN_DATE_TIME;
+
+/// Which message a creation time was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreatedAtSource {
+    /// `FileId.TimeCreated`.
+    FileId,
+    /// `Activity.Timestamp`, when the activity was saved.
+    Activity,
+    /// `StartTime` of the first `Session`.
+    Session,
+    /// `Timestamp` of the first `Record`.
+    Record,
+}
+
+/// A file creation time and where it came from.
+#[derive(Debug, Clone)]
+pub struct CreatedAt {
+    pub time:   DateTime,
+    pub source: CreatedAtSource,
+}
+
+/// When the file was created.
+///
+/// Some apps leave `FileId.TimeCreated` out or write it as
+/// zero, so this falls back through, in order:
+///
+/// 1. `FileId.TimeCreated`
+/// 2. `Activity.Timestamp`
+/// 3. `StartTime` of the first `Session`
+/// 4. `Timestamp` of the first `Record`
+///
+/// Values that are invalid, zero, or relative to device
+/// power on (below [`MIN_DATE_TIME`]) are skipped.
+///
+/// [`MIN_DATE_TIME`]: constant.MIN_DATE_TIME.html
+pub fn created_at(messages: &[Data]) -> Option<CreatedAt> {
+    [
+        CreatedAtSource::FileId,
+        CreatedAtSource::Activity,
+        CreatedAtSource::Session,
+        CreatedAtSource::Record,
+    ]
+    .iter()
+    .find_map(|&source| {
+        let time = messages.iter().find_map(|mesg| time_from(mesg, source))?;
+        Some(CreatedAt {
+            time: DateTime(time),
+            source,
+        })
+    })
+}
+
+/// The date time `source` refers to in `mesg`, if `mesg`
+/// is that kind of message and has a usable one.
+fn time_from(mesg: &Data, source: CreatedAtSource) -> Option<u32> {
+    mesg.0
+        .iter()
+        .filter_map(|field| {
+            match (source, field) {
+                (
+                    CreatedAtSource::FileId,
+                    Message::FileId(FileId::TimeCreated(f)),
+                )
+                | (
+                    CreatedAtSource::Activity,
+                    Message::Activity(Activity::Timestamp(f)),
+                )
+                | (
+                    CreatedAtSource::Session,
+                    Message::Session(Session::StartTime(f)),
+                )
+                | (
+                    CreatedAtSource::Record,
+                    Message::Record(Record::Timestamp(f)),
+                ) => Some(f.raw_value.0),
+                _ => None,
+            }
+        })
+        .find(|&time| time >= MIN_DATE_TIME && time != u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    const FILE_ID: u32 = 1_000_000_000;
+    const ACTIVITY: u32 = 1_000_003_600;
+    const SESSION: u32 = 1_000_000_010;
+    const RECORD: u32 = 1_000_000_020;
+
+    /// A file with every creation time source, minus the
+    /// ones `drop` says to zero out.
+    fn messages(drop: &[CreatedAtSource]) -> Vec<Data> {
+        let time = |source, time| {
+            if drop.contains(&source) {
+                0
+            }
+            else {
+                time
+            }
+        };
+        vec![
+            mesg(0)
+                .u8(0, 4)
+                .u32(4, time(CreatedAtSource::FileId, FILE_ID))
+                .build(),
+            mesg(20).u32(253, 1234).build(),
+            mesg(20).u32(253, time(CreatedAtSource::Record, RECORD)).build(),
+            mesg(18)
+                .u32(253, ACTIVITY)
+                .u32(2, time(CreatedAtSource::Session, SESSION))
+                .build(),
+            mesg(34)
+                .u32(253, time(CreatedAtSource::Activity, ACTIVITY))
+                .build(),
+        ]
+    }
+
+    fn assert_created_at(
+        drop: &[CreatedAtSource],
+        source: CreatedAtSource,
+        time: u32,
+    ) {
+        let created = created_at(&messages(drop)).unwrap();
+        assert_eq!(created.source, source);
+        assert_eq!(created.time.0, time);
+    }
+
+    #[test]
+    fn fallback_precedence() {
+        use self::CreatedAtSource::*;
+
+        assert_created_at(&[], FileId, FILE_ID);
+        assert_created_at(&[FileId], Activity, ACTIVITY);
+        assert_created_at(&[FileId, Activity], Session, SESSION);
+        // The first record is relative to power on, so skipped
+        assert_created_at(&[FileId, Activity, Session], Record, RECORD);
+        assert!(created_at(&messages(&[FileId, Activity, Session, Record]))
+            .is_none());
+    }
+
+    #[test]
+    fn missing_time_created() {
+        let mut messages = messages(&[]);
+        messages[0] = mesg(0).u8(0, 4).build();
+        let created = created_at(&messages).unwrap();
+        assert_eq!(created.source, CreatedAtSource::Activity);
+    }
+}
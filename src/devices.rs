@@ -0,0 +1,638 @@
+//! The devices and sensors that contributed to a file.
+use byteorder::LittleEndian;
+use profile::{
+    base::Valid,
+    messages::{
+        AntChannelId,
+        DeviceInfo as DeviceInfoField,
+        FileId,
+        Message,
+    },
+    types::{
+        DeviceIndex,
+        FaveroProduct,
+        GarminProduct,
+        Manufacturer,
+    },
+};
+use time::message_timestamp;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// The commonly used fields of a single `DeviceInfo`
+/// message.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceInfo {
+    /// Seconds since the FIT epoch.
+    pub timestamp:         Option<u32>,
+    pub device_index:      Option<DeviceIndex>,
+    pub device_type:       Option<u8>,
+    pub manufacturer:      Option<Manufacturer>,
+    pub serial_number:     Option<u32>,
+    pub product:           Option<u16>,
+    pub software_version:  Option<f64>,
+    pub hardware_version:  Option<u8>,
+    /// Volts.
+    pub battery_voltage:   Option<f64>,
+    pub descriptor:        Option<String>,
+    pub product_name:      Option<String>,
+    pub ant_device_number: Option<u16>,
+}
+
+impl DeviceInfo {
+    /// Flatten a decoded `DeviceInfo` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `DeviceInfo`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<DeviceInfo> {
+        let mut info = DeviceInfo::default();
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::DeviceInfo(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                DeviceInfoField::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    info.timestamp = Some(f.raw_value.0)
+                },
+                DeviceInfoField::DeviceIndex(f) => {
+                    info.device_index = Some(f.raw_value)
+                },
+                DeviceInfoField::DeviceType(f) if f.is_valid() => {
+                    info.device_type = Some(f.raw_value.0)
+                },
+                DeviceInfoField::Manufacturer(f) => {
+                    info.manufacturer = Some(f.raw_value.clone())
+                },
+                DeviceInfoField::SerialNumber(f) if f.is_valid() => {
+                    info.serial_number = Some(f.raw_value.0)
+                },
+                DeviceInfoField::Product(f) if f.is_valid() => {
+                    info.product = Some(f.raw_value.0)
+                },
+                DeviceInfoField::SoftwareVersion(f) if f.is_valid() => {
+                    info.software_version = Some(f.value())
+                },
+                DeviceInfoField::HardwareVersion(f) if f.is_valid() => {
+                    info.hardware_version = Some(f.raw_value.0)
+                },
+                DeviceInfoField::BatteryVoltage(f) if f.is_valid() => {
+                    info.battery_voltage = Some(f.value())
+                },
+                DeviceInfoField::Descriptor(f) => {
+                    info.descriptor = non_empty(&f.raw_value.0)
+                },
+                DeviceInfoField::ProductName(f) => {
+                    info.product_name = non_empty(&f.raw_value.0)
+                },
+                DeviceInfoField::AntDeviceNumber(f) if f.is_valid() => {
+                    info.ant_device_number = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        Some(info)
+    }
+
+    /// Whether the message has a valid device index.
+    pub fn is_valid(&self) -> bool {
+        self.device_index.map(|index| index.is_valid()).unwrap_or(false)
+    }
+
+    /// The product, resolved against the manufacturer's
+    /// product list.
+    pub fn product_id(&self) -> Option<ProductId> {
+        Some(ProductId::resolve(self.manufacturer.as_ref(), self.product?))
+    }
+
+    /// The manufacturer, unless it's missing, invalid or
+    /// not in the profile.
+    fn known_manufacturer(&self) -> Option<&Manufacturer> {
+        match self.manufacturer {
+            Some(Manufacturer::Unknown) | None => None,
+            Some(ref manufacturer) => Some(manufacturer),
+        }
+    }
+}
+
+/// A product number, which means something different for
+/// every manufacturer.
+#[derive(Debug, Clone)]
+pub enum ProductId {
+    Garmin(GarminProduct),
+    Favero(FaveroProduct),
+    /// Any other manufacturer, or a product that isn't in
+    /// its manufacturer's list.
+    Other {
+        manufacturer: Option<Manufacturer>,
+        product:      u16,
+    },
+}
+
+impl ProductId {
+    /// Look up `product` in `manufacturer`'s product list,
+    /// as the SDK does for the `garmin_product` and
+    /// `favero_product` subfields of `FileId` and
+    /// `DeviceInfo`.
+    pub fn resolve(
+        manufacturer: Option<&Manufacturer>,
+        product: u16,
+    ) -> ProductId {
+        let bytes = product.to_le_bytes();
+        let resolved = match manufacturer {
+            Some(m) if uses_garmin_products(m) => {
+                match GarminProduct::decode::<LittleEndian>(&bytes) {
+                    Ok(GarminProduct::Unknown) | Err(_) => None,
+                    Ok(product) => Some(ProductId::Garmin(product)),
+                }
+            },
+            Some(Manufacturer::FaveroElectronics) => {
+                match FaveroProduct::decode::<LittleEndian>(&bytes) {
+                    Ok(FaveroProduct::Unknown) | Err(_) => None,
+                    Ok(product) => Some(ProductId::Favero(product)),
+                }
+            },
+            _ => None,
+        };
+        resolved.unwrap_or_else(|| {
+            ProductId::Other {
+                manufacturer: manufacturer.cloned(),
+                product,
+            }
+        })
+    }
+
+    /// The product's name, if it's in the profile.
+    pub fn name(&self) -> Option<String> {
+        match self {
+            ProductId::Garmin(product) => Some(format!("{:?}", product)),
+            ProductId::Favero(product) => Some(format!("{:?}", product)),
+            ProductId::Other {
+                ..
+            } => None,
+        }
+    }
+}
+
+/// The product of a `FileId` message, resolved against its
+/// manufacturer's product list.
+///
+/// Returns `None` if `mesg` isn't a `FileId` message or has
+/// no valid product.
+pub fn file_product_id(mesg: &Data) -> Option<ProductId> {
+    let mut manufacturer = None;
+    let mut product = None;
+    for field in mesg.0.iter() {
+        match field {
+            Message::FileId(FileId::Manufacturer(f)) => {
+                manufacturer = Some(f.raw_value.clone())
+            },
+            Message::FileId(FileId::Product(f)) if f.is_valid() => {
+                product = Some(f.raw_value.0)
+            },
+            Message::FileId(_) => (),
+            _ => return None,
+        }
+    }
+    Some(ProductId::resolve(manufacturer.as_ref(), product?))
+}
+
+/// A single `AntChannelId` message: which ANT sensor a
+/// device index was paired with.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AntChannel {
+    pub channel_number:    Option<u8>,
+    /// The ANT+ device profile, e.g. 11 for power meters.
+    pub device_type:       Option<u8>,
+    pub device_number:     Option<u16>,
+    pub transmission_type: Option<u8>,
+    pub device_index:      Option<DeviceIndex>,
+}
+
+impl AntChannel {
+    /// Flatten a decoded `AntChannelId` data message.
+    ///
+    /// Returns `None` if `mesg` isn't an `AntChannelId`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<AntChannel> {
+        let mut channel = AntChannel::default();
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::AntChannelId(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                AntChannelId::ChannelNumber(f) if f.is_valid() => {
+                    channel.channel_number = Some(f.raw_value.0)
+                },
+                AntChannelId::DeviceType(f) if f.is_valid() => {
+                    channel.device_type = Some(f.raw_value.0)
+                },
+                AntChannelId::DeviceNumber(f) if f.is_valid() => {
+                    channel.device_number = Some(f.raw_value.0)
+                },
+                AntChannelId::TransmissionType(f) if f.is_valid() => {
+                    channel.transmission_type = Some(f.raw_value.0)
+                },
+                AntChannelId::DeviceIndex(f) if f.raw_value.is_valid() => {
+                    channel.device_index = Some(f.raw_value)
+                },
+                _ => (),
+            }
+        }
+
+        Some(channel)
+    }
+
+    /// Whether both name the same sensor, as far as they
+    /// say.
+    fn same_sensor(&self, other: &AntChannel) -> bool {
+        self.device_number == other.device_number
+            && self.device_type == other.device_type
+    }
+}
+
+/// Everything known about the sensor behind a device index
+/// over a span of the file.
+#[derive(Debug, Clone)]
+pub struct SensorIdentity {
+    pub device_index: DeviceIndex,
+    /// Seconds since the FIT epoch the sensor was paired,
+    /// or `None` from the start of the file.
+    pub from:         Option<u32>,
+    /// Seconds since the FIT epoch another sensor took over
+    /// the device index, or `None` to the end of the file.
+    pub until:        Option<u32>,
+    pub ant:          Option<AntChannel>,
+    /// The latest `DeviceInfo` for the sensor.
+    pub device:       Option<DeviceInfo>,
+}
+
+impl SensorIdentity {
+    /// The sensor's product, resolved against its
+    /// manufacturer's product list.
+    pub fn product_id(&self) -> Option<ProductId> {
+        self.device.as_ref()?.product_id()
+    }
+
+    /// The sensor's ANT device number, from its channel or
+    /// its `DeviceInfo`.
+    pub fn ant_device_number(&self) -> Option<u16> {
+        self.ant
+            .as_ref()
+            .and_then(|ant| ant.device_number)
+            .or_else(|| self.device.as_ref()?.ant_device_number)
+    }
+
+    fn covers(&self, timestamp: u32) -> bool {
+        self.from.is_none_or(|from| from <= timestamp)
+            && self.until.is_none_or(|until| timestamp < until)
+    }
+}
+
+/// Which sensor each device index stood for, and when.
+///
+/// Devices reuse a device index when a sensor drops out and
+/// another is paired, so each index can have several
+/// identities one after another. A new identity starts at
+/// an `AntChannelId` or `DeviceInfo` for a different
+/// sensor, timed by the latest timestamp seen before it,
+/// as neither message need have one.
+#[derive(Debug, Clone, Default)]
+pub struct SensorTimeline {
+    pub sensors: Vec<SensorIdentity>,
+}
+
+impl SensorTimeline {
+    /// Build the timeline from the `AntChannelId` and
+    /// `DeviceInfo` messages in `messages`.
+    pub fn from_messages(messages: &[Data]) -> Self {
+        let mut timeline = SensorTimeline::default();
+        let mut now = None;
+
+        for mesg in messages {
+            now = message_timestamp(mesg).or(now);
+
+            if let Some(channel) = AntChannel::from_message(mesg) {
+                let index = match channel.device_index {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let repeated = timeline.current(index).is_some_and(|sensor| {
+                    sensor
+                        .ant
+                        .as_ref()
+                        .is_none_or(|ant| ant.same_sensor(&channel))
+                });
+                if repeated {
+                    let sensor = timeline.current_mut(index).unwrap();
+                    sensor.ant = Some(channel);
+                }
+                else {
+                    timeline.start(index, now, Some(channel), None);
+                }
+            }
+            else if let Some(info) = DeviceInfo::from_message(mesg) {
+                let index = match info.device_index {
+                    Some(index) if info.is_valid() => index,
+                    _ => continue,
+                };
+                let at = info.timestamp.or(now);
+                let same = timeline.current(index).map(|sensor| {
+                    // Only a different number says it's another
+                    // sensor, as not every message has one
+                    let number = sensor.ant_device_number();
+                    number.is_none()
+                        || info.ant_device_number.is_none()
+                        || number == info.ant_device_number
+                });
+                match same {
+                    Some(true) => {
+                        timeline.current_mut(index).unwrap().device = Some(info)
+                    },
+                    _ => timeline.start(index, at, None, Some(info)),
+                }
+            }
+        }
+
+        timeline
+    }
+
+    /// The sensor behind `device_index` at `timestamp`,
+    /// seconds since the FIT epoch.
+    pub fn sensor_for(
+        &self,
+        device_index: DeviceIndex,
+        timestamp: u32,
+    ) -> Option<&SensorIdentity> {
+        self.sensors.iter().rev().find(|sensor| {
+            sensor.device_index == device_index && sensor.covers(timestamp)
+        })
+    }
+
+    /// The identity `index` currently has, the last one
+    /// started.
+    fn current(&self, index: DeviceIndex) -> Option<&SensorIdentity> {
+        self.sensors.iter().rev().find(|sensor| sensor.device_index == index)
+    }
+
+    fn current_mut(
+        &mut self,
+        index: DeviceIndex,
+    ) -> Option<&mut SensorIdentity> {
+        self.sensors
+            .iter_mut()
+            .rev()
+            .find(|sensor| sensor.device_index == index)
+    }
+
+    /// End the current identity of `index` at `at`, and
+    /// start a new one.
+    fn start(
+        &mut self,
+        index: DeviceIndex,
+        at: Option<u32>,
+        ant: Option<AntChannel>,
+        device: Option<DeviceInfo>,
+    ) {
+        if let Some(sensor) = self.current_mut(index) {
+            sensor.until = at;
+        }
+        self.sensors.push(SensorIdentity {
+            device_index: index,
+            from: at,
+            until: None,
+            ant,
+            device,
+        });
+    }
+}
+
+/// A stable identifier for a device, formatted as
+/// `"{manufacturer}:{product}:{serial}"`, e.g.
+/// `"Garmin:2697:3952142335"`.
+///
+/// Missing or invalid fields are written as `"?"`, as are
+/// manufacturers that aren't in the profile.
+pub fn device_fingerprint(info: &DeviceInfo) -> String {
+    format!(
+        "{}:{}:{}",
+        or_unknown(info.known_manufacturer().map(|m| format!("{:?}", m))),
+        or_unknown(info.product),
+        or_unknown(info.serial_number),
+    )
+}
+
+/// A human readable name for a device, formatted as
+/// `"{product name} ({software version})"`, e.g.
+/// `"Fenix5 (9.10)"`.
+///
+/// The product name comes from the message itself if it
+/// has one, otherwise from the manufacturer's product list
+/// (see [`ProductId`](enum.ProductId.html)). Anything
+/// missing is written as `"?"`.
+pub fn device_display_name(info: &DeviceInfo) -> String {
+    let name = info
+        .product_name
+        .clone()
+        .or_else(|| info.product_id().and_then(|id| id.name()));
+    format!(
+        "{} ({})",
+        or_unknown(name),
+        or_unknown(info.software_version.map(|v| format!("{:.2}", v))),
+    )
+}
+
+/// The name of a Garmin product number, if it's in the
+/// profile.
+pub fn garmin_product_name(product: u16) -> Option<String> {
+    match GarminProduct::decode::<LittleEndian>(&product.to_le_bytes()) {
+        Ok(GarminProduct::Unknown) | Err(_) => None,
+        Ok(product) => Some(format!("{:?}", product)),
+    }
+}
+
+/// Manufacturers whose product numbers are Garmin product
+/// numbers.
+fn uses_garmin_products(manufacturer: &Manufacturer) -> bool {
+    matches!(
+        manufacturer,
+        Manufacturer::Garmin
+            | Manufacturer::Dynastream
+            | Manufacturer::DynastreamOem
+            | Manufacturer::Tacx
+    )
+}
+
+fn or_unknown<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| String::from("?"))
+}
+
+/// Strings are null padded to their field size.
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim_end_matches('\0');
+    if s.is_empty() {
+        None
+    }
+    else {
+        Some(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    #[test]
+    fn fingerprint_format() {
+        let info = mesg(23)
+            .u8(0, 0)
+            .u16(2, 1) // garmin
+            .u32(3, 3_952_142_335)
+            .u16(4, 1)
+            .u16(5, 910)
+            .build();
+        let info = DeviceInfo::from_message(&info).unwrap();
+        assert!(info.is_valid());
+        assert_eq!(device_fingerprint(&info), "Garmin:1:3952142335");
+        assert_eq!(device_display_name(&info), "Hrm1 (9.10)");
+    }
+
+    #[test]
+    fn fallbacks_for_missing_fields() {
+        let info = mesg(23).u8(0, 2).build();
+        let info = DeviceInfo::from_message(&info).unwrap();
+        assert_eq!(device_fingerprint(&info), "?:?:?");
+        assert_eq!(device_display_name(&info), "? (?)");
+
+        // Invalid serial, unknown manufacturer, named product
+        let info = mesg(23)
+            .u8(0, 1)
+            .u16(2, 0xFFFF)
+            .u32(3, 0)
+            .u16(4, 1)
+            .bytes(27, b"Varia RTL515\0\0\0\0")
+            .build();
+        let info = DeviceInfo::from_message(&info).unwrap();
+        assert_eq!(device_fingerprint(&info), "?:1:?");
+        assert_eq!(device_display_name(&info), "Varia RTL515 (?)");
+
+        // Product numbers only mean something for Garmin
+        let info = mesg(23).u16(2, 32).u16(4, 1).build();
+        let info = DeviceInfo::from_message(&info).unwrap();
+        assert_eq!(device_display_name(&info), "? (?)");
+        assert!(!info.is_valid());
+
+        assert!(
+            DeviceInfo::from_message(&mesg(20).u32(253, 1).build()).is_none()
+        );
+    }
+
+    #[test]
+    fn product_ids() {
+        let fenix = mesg(0).u16(1, 1).u16(2, 2697).build();
+        assert!(matches!(
+            file_product_id(&fenix),
+            Some(ProductId::Garmin(GarminProduct::Fenix5))
+        ));
+
+        let assioma = mesg(23).u16(2, 263).u16(4, 12).build();
+        let assioma = DeviceInfo::from_message(&assioma).unwrap();
+        assert!(matches!(
+            assioma.product_id(),
+            Some(ProductId::Favero(FaveroProduct::AssiomaDuo))
+        ));
+        assert_eq!(device_display_name(&assioma), "AssiomaDuo (?)");
+
+        // Stages, and a Garmin product newer than the profile
+        let stages =
+            ProductId::resolve(Some(&Manufacturer::StagesCycling), 2697);
+        assert!(matches!(
+            stages,
+            ProductId::Other {
+                manufacturer: Some(Manufacturer::StagesCycling),
+                product:      2697,
+            }
+        ));
+        assert!(stages.name().is_none());
+        assert!(matches!(
+            ProductId::resolve(Some(&Manufacturer::Garmin), 65000),
+            ProductId::Other {
+                product: 65000,
+                ..
+            }
+        ));
+        assert!(file_product_id(&mesg(0).u16(1, 1).build()).is_none());
+    }
+
+    fn channel(index: u8, number: u16) -> Data {
+        mesg(82)
+            .u8(0, 1)
+            .u8(1, 11) // bike power
+            .u16(2, number)
+            .u8(3, 5)
+            .u8(4, index)
+            .build()
+    }
+
+    fn sensor(time: u32, index: u8, manufacturer: u16, serial: u32) -> Data {
+        mesg(23)
+            .u32(253, time)
+            .u8(0, index)
+            .u16(2, manufacturer)
+            .u32(3, serial)
+            .u16(4, 12)
+            .u16(21, serial as u16)
+            .build()
+    }
+
+    #[test]
+    fn sensors_over_time() {
+        let record = |time| mesg(20).u32(253, time).build();
+        let messages = vec![
+            record(100),
+            channel(1, 1234),
+            sensor(100, 1, 263, 1234),
+            record(150),
+            // Repeated for the same pedal
+            channel(1, 1234),
+            record(200),
+            // The pedal dropped, and another took its index
+            channel(1, 4321),
+            sensor(201, 1, 1, 4321),
+            // And a heart rate strap known only by DeviceInfo
+            sensor(230, 2, 1, 99),
+        ];
+        let timeline = SensorTimeline::from_messages(&messages);
+        assert_eq!(timeline.sensors.len(), 3);
+
+        let first = timeline.sensor_for(DeviceIndex(1), 150).unwrap();
+        assert_eq!((first.from, first.until), (Some(100), Some(200)));
+        assert_eq!(first.ant.as_ref().unwrap().device_type, Some(11));
+        assert_eq!(first.device.as_ref().unwrap().serial_number, Some(1234));
+        assert!(matches!(
+            first.product_id(),
+            Some(ProductId::Favero(FaveroProduct::AssiomaDuo))
+        ));
+
+        let second = timeline.sensor_for(DeviceIndex(1), 200).unwrap();
+        assert_eq!(second.ant_device_number(), Some(4321));
+        assert_eq!(second.device.as_ref().unwrap().serial_number, Some(4321));
+        assert!(timeline.sensor_for(DeviceIndex(1), 99).is_none());
+
+        let strap = timeline.sensor_for(DeviceIndex(2), 300).unwrap();
+        assert_eq!(
+            (strap.from, strap.ant_device_number()),
+            (Some(230), Some(99))
+        );
+        assert!(timeline.sensor_for(DeviceIndex(3), 300).is_none());
+    }
+}
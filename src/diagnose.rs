@@ -0,0 +1,393 @@
+//! Telling whether a file was cut short, and by how much.
+//!
+//! A device that crashes, runs out of battery or loses the
+//! file on its way to a server leaves a file that just
+//! ends. [`truncation`] looks for the ways that shows:
+//!
+//! - Fewer bytes of records than the header declares, the
+//!   file ending partway through them. A header declaring
+//!   no size leaves the records running to the end of the
+//!   file, so only decoding them, as
+//!   [`truncation_from_bytes`] does, shows one cut in two.
+//! - Every record there, but no CRC after them.
+//! - No `Session` message, which devices write as they
+//!   finish an activity.
+//! - Records that stop well short of the elapsed time the
+//!   `Session` claims, as when a device rebuilt the summary
+//!   but lost records.
+//!
+//! and estimates how much of the activity the file holds.
+//!
+//! ```no_run
+//! use garminfit::diagnose;
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let bytes = std::fs::read("activity.fit").expect("a readable file");
+//! let report = diagnose::truncation_from_bytes(&bytes)?;
+//! if report.is_truncated() {
+//!     if let Some(completeness) = report.completeness() {
+//!         println!("about {:.0} % of the activity", completeness * 100.0);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`truncation`]: fn.truncation.html
+//! [`truncation_from_bytes`]: fn.truncation_from_bytes.html
+use consts::CRC_SIZE;
+use error::{
+    ErrorKind,
+    Result,
+};
+use profile::messages::{
+    Message,
+    Record,
+    Session,
+};
+use reader::FitReader;
+use types::{
+    field::Field,
+    file::Header,
+    record::{
+        Data,
+        Message as Content,
+    },
+};
+
+/// Seconds records can stop before the end of the session
+/// without counting as cut short. Devices write the
+/// session a little after the last record, and record less
+/// often than once a second when smart recording.
+pub const SLACK_S: f64 = 60.0;
+
+/// A way a file shows it was cut short.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Truncation {
+    /// The file ends `missing_bytes` short of the records
+    /// the header declares. The last record is likely cut
+    /// in two, and the CRC is missing too.
+    ///
+    /// `missing_bytes` is `None` for a header declaring no
+    /// size, where the last record decoding short is all
+    /// there is to go on.
+    MidRecord { missing_bytes: Option<u64> },
+    /// The records are all there but the CRC after them
+    /// isn't.
+    MissingCrc,
+    /// There's no `Session` message.
+    MissingSession,
+    /// The records span `recorded_s`, more than
+    /// [`SLACK_S`](constant.SLACK_S.html) short of the
+    /// `claimed_s` the sessions add up to.
+    ShortRecords { claimed_s: f64, recorded_s: f64 },
+}
+
+/// What [`truncation`](fn.truncation.html) found.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncationReport {
+    /// Bytes of records the header declares, 0 for a header
+    /// declaring none.
+    pub declared_data_bytes: u64,
+    /// Bytes of records the file holds, at most the
+    /// declared ones. Without a declared size, everything
+    /// between the header and the CRC.
+    pub present_data_bytes:  u64,
+    /// The total elapsed time of the sessions, if there are
+    /// any with one.
+    pub claimed_elapsed_s:   Option<f64>,
+    /// From the first record's timestamp to the last.
+    pub record_span_s:       Option<f64>,
+    /// Empty for a file that looks whole.
+    pub modes:               Vec<Truncation>,
+}
+
+impl TruncationReport {
+    pub fn is_truncated(&self) -> bool {
+        !self.modes.is_empty()
+    }
+
+    /// The share of the activity the file holds, from 0 to
+    /// 1, or `None` if there's nothing to go on.
+    ///
+    /// With a session claiming an elapsed time, that's the
+    /// share of it the records span. Without one, the
+    /// sessions and the end of the records having been lost
+    /// together, it's the share of the declared bytes
+    /// present, which a header declaring no records at all,
+    /// as some devices write until they finish, can't give.
+    pub fn completeness(&self) -> Option<f64> {
+        match (self.claimed_elapsed_s, self.record_span_s) {
+            (Some(claimed), Some(span)) if claimed > 0.0 => {
+                return Some((span / claimed).min(1.0))
+            },
+            _ => (),
+        }
+        if self.declared_data_bytes > 0 {
+            Some(
+                self.present_data_bytes as f64
+                    / self.declared_data_bytes as f64,
+            )
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// Check the data messages decoded from a file, its
+/// `header` and its length in bytes, `file_len`, for the
+/// signs of it having been cut short.
+///
+/// `messages` are the ones decoded before the file ended,
+/// as a [`FitReader`] yields them before its error.
+///
+/// A header declaring no data size gives nothing to
+/// measure the length against, so this can't tell such a
+/// file was cut partway through a record;
+/// [`truncation_from_bytes`] can.
+///
+/// [`FitReader`]: ../reader/struct.FitReader.html
+/// [`truncation_from_bytes`]: fn.truncation_from_bytes.html
+pub fn truncation(
+    messages: &[Data],
+    header: &Header,
+    file_len: u64,
+) -> TruncationReport {
+    let declared = u64::from(header.data_size());
+    let after_header = file_len.saturating_sub(u64::from(header.size()));
+    let mut modes = Vec::new();
+    let present = if declared == 0 {
+        // Read to the end of the file, which holds the CRC
+        after_header.saturating_sub(u64::from(CRC_SIZE))
+    }
+    else {
+        if after_header < declared {
+            modes.push(Truncation::MidRecord {
+                missing_bytes: Some(declared - after_header),
+            });
+        }
+        else if after_header < declared + u64::from(CRC_SIZE) {
+            modes.push(Truncation::MissingCrc);
+        }
+        after_header.min(declared)
+    };
+
+    let mut session = false;
+    let mut claimed: Option<f64> = None;
+    let (mut first, mut last): (Option<u32>, Option<u32>) = (None, None);
+    for mesg in messages {
+        for field in mesg.0.iter() {
+            match field {
+                Message::Session(Session::TotalElapsedTime(f))
+                    if f.is_valid() =>
+                {
+                    claimed = Some(claimed.unwrap_or(0.0) + f.value())
+                },
+                Message::Session(_) => session = true,
+                Message::Record(Record::Timestamp(f))
+                    if f.raw_value.0 != u32::MAX =>
+                {
+                    let timestamp = f.raw_value.0;
+                    first = Some(first.map_or(timestamp, |t| t.min(timestamp)));
+                    last = Some(last.map_or(timestamp, |t| t.max(timestamp)));
+                },
+                _ => (),
+            }
+        }
+    }
+    if !session && claimed.is_none() {
+        modes.push(Truncation::MissingSession);
+    }
+
+    let span = match (first, last) {
+        (Some(first), Some(last)) => Some(f64::from(last - first)),
+        _ => None,
+    };
+    if let (Some(claimed), Some(span)) = (claimed, span) {
+        if span + SLACK_S < claimed {
+            modes.push(Truncation::ShortRecords {
+                claimed_s:  claimed,
+                recorded_s: span,
+            });
+        }
+    }
+
+    TruncationReport {
+        declared_data_bytes: declared,
+        present_data_bytes: present,
+        claimed_elapsed_s: claimed,
+        record_span_s: span,
+        modes,
+    }
+}
+
+/// Decode what there is of the FIT file in `bytes` and
+/// check it, as [`truncation`] does.
+///
+/// A header declaring no data size has the records run to
+/// the end of the file, so a file cut partway through one
+/// ends with it decoding short, not with a CRC that doesn't
+/// match. That's reported as a `MidRecord` of unknown size.
+///
+/// Fails only if there's no FIT header to go on.
+///
+/// [`truncation`]: fn.truncation.html
+pub fn truncation_from_bytes(bytes: &[u8]) -> Result<TruncationReport> {
+    let mut reader = FitReader::new(bytes)?;
+    let mut messages = Vec::new();
+    let mut cut = false;
+    for record in reader.by_ref() {
+        match record {
+            Ok(record) => {
+                if let Content::Data(mesg) = record.content {
+                    messages.push(mesg);
+                }
+            },
+            Err(err) => {
+                cut = !matches!(err.kind(), ErrorKind::CrcMismatch { .. });
+                break;
+            },
+        }
+    }
+    let mut report = truncation(&messages, reader.header(), bytes.len() as u64);
+    if cut && report.declared_data_bytes == 0 {
+        report.modes.insert(
+            0,
+            Truncation::MidRecord {
+                missing_bytes: None
+            },
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::{
+        without_data_size,
+        FitBuilder,
+    };
+
+    /// A file id, records a second apart for `recorded_s`,
+    /// and with `session` a session of `claimed_s`.
+    fn activity(recorded_s: u32, claimed_s: u32, session: bool) -> Vec<u8> {
+        let start = 1_000_000_000u32;
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 0, &[(0, 1, 0x00), (4, 4, 0x86)]);
+        fit.data(0, &[&[4], &start.to_le_bytes()]);
+        fit.definition(1, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        for t in 0..=recorded_s {
+            fit.data(1, &[&(start + t).to_le_bytes(), &[140]]);
+        }
+        if session {
+            fit.definition(
+                2,
+                18,
+                &[(253, 4, 0x86), (2, 4, 0x86), (7, 4, 0x86)],
+            );
+            fit.data(
+                2,
+                &[
+                    &(start + claimed_s).to_le_bytes(),
+                    &start.to_le_bytes(),
+                    &(claimed_s * 1000).to_le_bytes(),
+                ],
+            );
+        }
+        fit.build()
+    }
+
+    #[test]
+    fn whole_file() {
+        let report = truncation_from_bytes(&activity(600, 600, true)).unwrap();
+        assert!(!report.is_truncated());
+        assert_eq!(report.claimed_elapsed_s, Some(600.0));
+        assert_eq!(report.record_span_s, Some(600.0));
+        assert_eq!(report.completeness(), Some(1.0));
+    }
+
+    #[test]
+    fn mid_record() {
+        let mut bytes = activity(600, 600, true);
+        let declared = bytes.len() as u64 - 14 - 2;
+        // Through a record, well before the session
+        bytes.truncate(bytes.len() * 2 / 5 + 3);
+        let report = truncation_from_bytes(&bytes).unwrap();
+        let present = bytes.len() as u64 - 14;
+        assert_eq!(
+            report.modes,
+            [
+                Truncation::MidRecord {
+                    missing_bytes: Some(declared - present),
+                },
+                Truncation::MissingSession,
+            ]
+        );
+        assert_eq!(report.claimed_elapsed_s, None);
+        let completeness = report.completeness().unwrap();
+        assert!((completeness - 0.4).abs() < 0.01, "{}", completeness);
+    }
+
+    #[test]
+    fn mid_record_without_data_size() {
+        let bytes = without_data_size(&activity(600, 600, true));
+        let report = truncation_from_bytes(&bytes).unwrap();
+        assert!(!report.is_truncated());
+        assert_eq!(report.declared_data_bytes, 0);
+        assert_eq!(report.present_data_bytes, bytes.len() as u64 - 14 - 2);
+
+        let mut bytes = bytes;
+        bytes.truncate(bytes.len() * 2 / 5 + 3);
+        let report = truncation_from_bytes(&bytes).unwrap();
+        assert_eq!(
+            report.modes,
+            [
+                Truncation::MidRecord {
+                    missing_bytes: None
+                },
+                Truncation::MissingSession,
+            ]
+        );
+        assert_eq!(report.present_data_bytes, bytes.len() as u64 - 14 - 2);
+        // Neither a session nor a declared size to measure by
+        assert_eq!(report.completeness(), None);
+    }
+
+    #[test]
+    fn missing_crc() {
+        let mut bytes = activity(600, 600, true);
+        let len = bytes.len();
+        bytes.truncate(len - 2);
+        let report = truncation_from_bytes(&bytes).unwrap();
+        assert_eq!(report.modes, [Truncation::MissingCrc]);
+        assert_eq!(report.completeness(), Some(1.0));
+    }
+
+    #[test]
+    fn missing_session() {
+        let report = truncation_from_bytes(&activity(600, 0, false)).unwrap();
+        assert_eq!(report.modes, [Truncation::MissingSession]);
+        assert_eq!(report.completeness(), Some(1.0));
+    }
+
+    #[test]
+    fn records_stop_early() {
+        // A three hour ride with records for 1 h 40 m
+        let bytes = activity(6000, 10_800, true);
+        let report = truncation_from_bytes(&bytes).unwrap();
+        assert_eq!(
+            report.modes,
+            [Truncation::ShortRecords {
+                claimed_s:  10_800.0,
+                recorded_s: 6000.0,
+            }]
+        );
+        assert_eq!(report.completeness(), Some(6000.0 / 10_800.0));
+
+        // Within the slack
+        let report = truncation_from_bytes(&activity(600, 630, true)).unwrap();
+        assert!(!report.is_truncated());
+    }
+}
@@ -0,0 +1,240 @@
+//! The file listing a device serves when syncing over USB
+//! or ANT-FS.
+//!
+//! Devices list the files they hold in a directory file,
+//! always at index 0. Unlike the files it lists, it isn't
+//! FIT encoded but a fixed layout: a 16 byte header and
+//! then one 16 byte entry per file, all little endian.
+//!
+//! ```text
+//! header: version, entry length, time format,
+//!         5 reserved, system time (4), modified (4)
+//! entry:  index (2), data type, FIT file type,
+//!         file number (2), type specific flags,
+//!         flags, size (4), date (4)
+//! ```
+//!
+//! [`read_directory`] turns that into a [`RemoteFile`] per
+//! entry, enough for a sync tool to decide what to fetch:
+//!
+//! ```no_run
+//! # fn main() -> garminfit::error::Result<()> {
+//! let listing = std::fs::read("DIRECTORY.BIN").unwrap();
+//! for file in garminfit::directory::read_directory(&listing)? {
+//!     if file.is_new() {
+//!         println!("fetch {} ({} bytes)", file.index, file.size);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`read_directory`]: fn.read_directory.html
+//! [`RemoteFile`]: struct.RemoteFile.html
+use byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+use error::{
+    Error,
+    Result,
+};
+use profile::types::{
+    DateTime,
+    File as FileType,
+};
+
+/// Length of the directory header.
+const HEADER_SIZE: usize = 16;
+
+/// The shortest entry there is. Later versions may append
+/// to entries, so the header gives their actual length.
+const MIN_ENTRY_SIZE: usize = 16;
+
+/// The data type of entries that are FIT files.
+pub const FIT_DATA_TYPE: u8 = 0x80;
+
+/// The file can be downloaded.
+pub const READ: u8 = 0x80;
+/// The file can be uploaded to.
+pub const WRITE: u8 = 0x40;
+/// The file can be deleted.
+pub const ERASE: u8 = 0x20;
+/// The file has been downloaded before.
+pub const ARCHIVE: u8 = 0x10;
+/// The file can be appended to.
+pub const APPEND: u8 = 0x08;
+/// The file is encrypted.
+pub const CRYPTO: u8 = 0x04;
+
+/// One file listed by a device.
+#[derive(Debug, Clone)]
+pub struct RemoteFile {
+    /// The index to ask the device for.
+    pub index:     u16,
+    /// What kind of file it is, if it's a FIT file.
+    pub file_type: Option<FileType>,
+    /// Size in bytes.
+    pub size:      u32,
+    /// When the file was last written.
+    pub date:      DateTime,
+    /// Permissions and state, a combination of [`READ`],
+    /// [`WRITE`], [`ERASE`], [`ARCHIVE`], [`APPEND`] and
+    /// [`CRYPTO`].
+    ///
+    /// [`READ`]: constant.READ.html
+    /// [`WRITE`]: constant.WRITE.html
+    /// [`ERASE`]: constant.ERASE.html
+    /// [`ARCHIVE`]: constant.ARCHIVE.html
+    /// [`APPEND`]: constant.APPEND.html
+    /// [`CRYPTO`]: constant.CRYPTO.html
+    pub flags:     u8,
+}
+
+impl RemoteFile {
+    /// Whether the file can be downloaded.
+    pub fn is_readable(&self) -> bool {
+        self.flags & READ != 0
+    }
+
+    /// Whether the file can be deleted from the device.
+    pub fn is_erasable(&self) -> bool {
+        self.flags & ERASE != 0
+    }
+
+    /// Whether the file has been downloaded before.
+    pub fn is_archived(&self) -> bool {
+        self.flags & ARCHIVE != 0
+    }
+
+    /// Whether the file is a readable FIT file that hasn't
+    /// been downloaded yet.
+    pub fn is_new(&self) -> bool {
+        self.file_type.is_some() && self.is_readable() && !self.is_archived()
+    }
+}
+
+/// Parse a device directory listing, in the order the
+/// device lists its files.
+///
+/// Fails with `NotDirectory` if the header is missing or
+/// declares entries shorter than 16 bytes, or if the last
+/// entry is cut short.
+pub fn read_directory(bytes: &[u8]) -> Result<Vec<RemoteFile>> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(Error::not_directory());
+    }
+    let entry_size = usize::from(bytes[1]);
+    if entry_size < MIN_ENTRY_SIZE {
+        return Err(Error::not_directory());
+    }
+    let entries = &bytes[HEADER_SIZE..];
+    if !entries.len().is_multiple_of(entry_size) {
+        return Err(Error::not_directory());
+    }
+    Ok(entries.chunks(entry_size).map(read_entry).collect())
+}
+
+fn read_entry(entry: &[u8]) -> RemoteFile {
+    let file_type = if entry[2] == FIT_DATA_TYPE {
+        // Decoding a single byte enum can't fail
+        FileType::decode::<LittleEndian>(&entry[3..4]).ok()
+    }
+    else {
+        None
+    };
+    RemoteFile {
+        index: LittleEndian::read_u16(&entry[0..2]),
+        file_type,
+        size: LittleEndian::read_u32(&entry[8..12]),
+        date: DateTime(LittleEndian::read_u32(&entry[12..16])),
+        flags: entry[7],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+
+    fn entry(
+        index: u16,
+        data_type: u8,
+        file_type: u8,
+        flags: u8,
+        size: u32,
+        date: u32,
+    ) -> Vec<u8> {
+        let mut entry = vec![0; 16];
+        LittleEndian::write_u16(&mut entry[0..2], index);
+        entry[2] = data_type;
+        entry[3] = file_type;
+        LittleEndian::write_u16(&mut entry[4..6], index);
+        entry[7] = flags;
+        LittleEndian::write_u32(&mut entry[8..12], size);
+        LittleEndian::write_u32(&mut entry[12..16], date);
+        entry
+    }
+
+    fn listing(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = vec![0x01, 16, 0, 0, 0, 0, 0, 0];
+        bytes.extend_from_slice(&[0; 8]);
+        for entry in entries {
+            bytes.extend_from_slice(entry);
+        }
+        bytes
+    }
+
+    #[test]
+    fn lists_remote_files() {
+        let bytes = listing(&[
+            // The device file, read only
+            entry(1, 0x80, 1, READ, 1_024, 0),
+            // An activity downloaded before, and a new one
+            entry(2, 0x80, 4, READ | ERASE | ARCHIVE, 80_000, 1_000_000_000),
+            entry(3, 0x80, 4, READ | ERASE, 120_000, 1_000_086_400),
+            // A workout to upload to
+            entry(4, 0x80, 5, WRITE | ERASE, 0, 0),
+            // Not a FIT file at all
+            entry(5, 0x01, 0, READ, 512, 0),
+        ]);
+
+        let files = read_directory(&bytes).unwrap();
+        assert_eq!(files.len(), 5);
+        assert!(matches!(files[0].file_type, Some(FileType::Device)));
+        assert_eq!(files[1].size, 80_000);
+        assert_eq!(files[2].date, DateTime(1_000_086_400));
+        assert!(matches!(files[3].file_type, Some(FileType::Workout)));
+        assert!(files[4].file_type.is_none());
+
+        let new: Vec<u16> = files
+            .iter()
+            .filter(|file| file.is_new())
+            .map(|file| file.index)
+            .collect();
+        assert_eq!(new, vec![1, 3]);
+        assert!(files[1].is_archived() && files[1].is_erasable());
+        assert!(!files[3].is_readable());
+
+        // Longer entries from a later version are fine
+        let mut longer = listing(&[]);
+        longer[1] = 20;
+        let mut padded = entry(7, 0x80, 4, READ, 10, 0);
+        padded.extend_from_slice(&[0xFF; 4]);
+        longer.extend_from_slice(&padded);
+        assert_eq!(read_directory(&longer).unwrap()[0].index, 7);
+    }
+
+    #[test]
+    fn rejects_bad_listings() {
+        let good = listing(&[entry(1, 0x80, 4, READ, 10, 0)]);
+        let mut short_entries = good.clone();
+        short_entries[1] = 8;
+
+        for bytes in &[&good[..10], &good[..good.len() - 1], &short_entries] {
+            let err = read_directory(bytes).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::NotDirectory));
+        }
+        assert!(read_directory(&listing(&[])).unwrap().is_empty());
+    }
+}
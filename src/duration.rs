@@ -0,0 +1,360 @@
+//! One type for the many ways FIT encodes a length of time.
+//!
+//! Durations arrive as whole seconds, as seconds scaled by
+//! 1000, as milliseconds scaled by 10 (stance time) and as
+//! a whole second field with a separate 1/32768 s
+//! fractional part. Scaling them all into `f64` seconds
+//! works until one of them turns out to have been
+//! milliseconds. [`FitDuration`] holds any of them, and
+//! [`Field::duration`] makes one from any field the
+//! profile gives in `s` or `ms`.
+//!
+//! [`FitDuration`]: struct.FitDuration.html
+//! [`Field::duration`]: ../profile/messages/struct.Field.html#method.duration
+use profile::{
+    base::Valid,
+    messages::{
+        AntRx,
+        AntTx,
+        Field,
+        Hr,
+        Message,
+        TimestampCorrelation,
+    },
+};
+use std::{
+    fmt,
+    ops::Add,
+    time::Duration,
+};
+use types::{
+    field::Field as _,
+    record::Data,
+};
+
+/// The fractional timestamp fields count in these steps.
+const FRACTION: f64 = 32768.0;
+
+/// A length of time, to the nanosecond.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FitDuration(Duration);
+
+impl FitDuration {
+    pub fn from_secs(secs: u32) -> FitDuration {
+        FitDuration(Duration::from_secs(u64::from(secs)))
+    }
+
+    pub fn from_millis(millis: u32) -> FitDuration {
+        FitDuration(Duration::from_millis(u64::from(millis)))
+    }
+
+    /// `raw / scale` seconds, as a field with that scale
+    /// and units of `s` holds. For a field in `ms`, scale
+    /// by a thousand times as much.
+    ///
+    /// ```
+    /// use garminfit::duration::FitDuration;
+    ///
+    /// // total_timer_time, scale 1000
+    /// assert_eq!(FitDuration::from_scaled(3_723_450, 1000.0).as_millis(), 3_723_450);
+    /// // stance_time, scale 10 in ms
+    /// assert_eq!(FitDuration::from_scaled(2505, 10_000.0).as_secs_f64(), 0.2505);
+    /// ```
+    pub fn from_scaled(raw: u32, scale: f64) -> FitDuration {
+        FitDuration::from_secs_f64(f64::from(raw) / scale).unwrap_or_default()
+    }
+
+    /// A whole second field and its 1/32768 s fractional
+    /// partner, as `Hr` and `TimestampCorrelation` split
+    /// their timestamps.
+    pub fn from_fractional(secs: u32, fraction: u16) -> FitDuration {
+        FitDuration::from_secs(secs)
+            + FitDuration::from_scaled(u32::from(fraction), FRACTION)
+    }
+
+    /// `None` for negative, infinite or NaN seconds.
+    pub fn from_secs_f64(secs: f64) -> Option<FitDuration> {
+        if secs.is_finite() && secs >= 0.0 {
+            Some(FitDuration(Duration::from_nanos((secs * 1e9).round() as u64)))
+        }
+        else {
+            None
+        }
+    }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0.as_secs_f64()
+    }
+
+    pub fn as_millis(&self) -> u128 {
+        self.0.as_millis()
+    }
+
+    pub fn to_std(&self) -> Duration {
+        self.0
+    }
+
+    /// `self - other`, or `None` if `other` is longer.
+    pub fn checked_sub(&self, other: FitDuration) -> Option<FitDuration> {
+        self.0.checked_sub(other.0).map(FitDuration)
+    }
+}
+
+impl From<Duration> for FitDuration {
+    fn from(duration: Duration) -> Self {
+        FitDuration(duration)
+    }
+}
+
+impl Add for FitDuration {
+    type Output = FitDuration;
+
+    fn add(self, other: FitDuration) -> FitDuration {
+        FitDuration(self.0 + other.0)
+    }
+}
+
+impl fmt::Display for FitDuration {
+    /// `h:mm:ss.fff`, rounded to the millisecond.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let millis = (self.0.as_nanos() + 500_000) / 1_000_000;
+        let secs = millis / 1000;
+        write!(
+            f,
+            "{}:{:02}:{:02}.{:03}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60,
+            millis % 1000
+        )
+    }
+}
+
+impl<T: Valid> Field<T>
+where
+    Field<T>: ::types::field::Field<Value = f64>,
+{
+    /// The field's value as a duration, if the profile
+    /// gives it in `s` or `ms` and it's valid and not
+    /// negative.
+    pub fn duration(&self) -> Option<FitDuration> {
+        if !self.is_valid() {
+            return None;
+        }
+        match self.units {
+            Some("s") => FitDuration::from_secs_f64(self.value()),
+            Some("ms") => FitDuration::from_secs_f64(self.value() / 1000.0),
+            _ => None,
+        }
+    }
+}
+
+/// The timestamp of an `Hr`, `AntRx` or `AntTx` message to
+/// within its 1/32768 s fractional part, as a duration
+/// since the FIT epoch.
+///
+/// Without a valid fractional part, this is the whole
+/// second timestamp.
+pub fn precise_timestamp(mesg: &Data) -> Option<FitDuration> {
+    let mut secs = None;
+    let mut fraction = 0;
+    for field in mesg.0.iter() {
+        match field {
+            Message::Hr(Hr::Timestamp(f))
+            | Message::AntRx(AntRx::Timestamp(f))
+            | Message::AntTx(AntTx::Timestamp(f))
+                if f.raw_value.0 != u32::MAX =>
+            {
+                secs = Some(f.raw_value.0)
+            },
+            Message::Hr(Hr::FractionalTimestamp(f))
+            | Message::AntRx(AntRx::FractionalTimestamp(f))
+            | Message::AntTx(AntTx::FractionalTimestamp(f))
+                if f.is_valid() =>
+            {
+                fraction = f.raw_value.0
+            },
+            _ => (),
+        }
+    }
+    Some(FitDuration::from_fractional(secs?, fraction))
+}
+
+/// A `TimestampCorrelation` message: one instant as both
+/// UTC and the device's system time, each to within its
+/// fractional part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Correlation {
+    /// Since the FIT epoch.
+    pub utc:    FitDuration,
+    /// Since the device was powered on.
+    pub system: FitDuration,
+}
+
+impl Correlation {
+    /// Returns `None` if `mesg` isn't a
+    /// `TimestampCorrelation` message or lacks either
+    /// whole second timestamp.
+    pub fn from_message(mesg: &Data) -> Option<Correlation> {
+        let (mut utc, mut system) = (None, None);
+        let (mut utc_fraction, mut system_fraction) = (0, 0);
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::TimestampCorrelation(field) => field,
+                _ => return None,
+            };
+            match field {
+                TimestampCorrelation::Timestamp(f)
+                    if f.raw_value.0 != u32::MAX =>
+                {
+                    utc = Some(f.raw_value.0)
+                },
+                TimestampCorrelation::SystemTimestamp(f)
+                    if f.raw_value.0 != u32::MAX =>
+                {
+                    system = Some(f.raw_value.0)
+                },
+                TimestampCorrelation::FractionalTimestamp(f)
+                    if f.is_valid() =>
+                {
+                    utc_fraction = f.raw_value.0
+                },
+                TimestampCorrelation::FractionalSystemTimestamp(f)
+                    if f.is_valid() =>
+                {
+                    system_fraction = f.raw_value.0
+                },
+                _ => (),
+            }
+        }
+        Some(Correlation {
+            utc:    FitDuration::from_fractional(utc?, utc_fraction),
+            system: FitDuration::from_fractional(system?, system_fraction),
+        })
+    }
+
+    /// The UTC time, since the FIT epoch, of `system` time
+    /// since power on. `None` if it was before the
+    /// correlated instant by more than the UTC time.
+    pub fn to_utc(&self, system: FitDuration) -> Option<FitDuration> {
+        match system.checked_sub(self.system) {
+            Some(after) => Some(self.utc + after),
+            None => self.utc.checked_sub(self.system.checked_sub(system)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use laps::Lap;
+    use testutil::mesg;
+
+    #[test]
+    fn encodings_agree() {
+        let second = FitDuration::from_secs(1);
+        assert_eq!(FitDuration::from_millis(1000), second);
+        assert_eq!(FitDuration::from_scaled(1000, 1000.0), second);
+        assert_eq!(FitDuration::from_scaled(10_000, 10_000.0), second);
+        assert_eq!(FitDuration::from_fractional(0, 32768 / 2).as_millis(), 500);
+        assert_eq!(second.to_std(), Duration::from_secs(1));
+        assert_eq!(FitDuration::from_secs_f64(-1.0), None);
+
+        assert_eq!(
+            FitDuration::from_millis(3_723_450).to_string(),
+            "1:02:03.450"
+        );
+        assert_eq!(FitDuration::from_millis(59_999).to_string(), "0:00:59.999");
+        assert_eq!(
+            FitDuration::from_scaled(1, FRACTION).to_string(),
+            "0:00:00.000"
+        );
+        assert_eq!(
+            FitDuration::from_secs(100 * 3600).to_string(),
+            "100:00:00.000"
+        );
+    }
+
+    #[test]
+    fn fields_in_seconds_and_milliseconds() {
+        // A lap's total_timer_time (s, scale 1000) and
+        // avg_stance_time (ms, scale 10)
+        let lap = mesg(19)
+            .u32(253, 1_000_000_000)
+            .u32(8, 3_723_450)
+            .u16(79, 2505)
+            .u32(9, 1000)
+            .build();
+        let durations: Vec<_> = lap
+            .0
+            .iter()
+            .map(|field| {
+                match field {
+                    Message::Lap(::profile::messages::Lap::TotalTimerTime(
+                        f,
+                    )) => f.duration(),
+                    Message::Lap(::profile::messages::Lap::AvgStanceTime(
+                        f,
+                    )) => f.duration(),
+                    Message::Lap(::profile::messages::Lap::TotalDistance(
+                        f,
+                    )) => f.duration(),
+                    _ => None,
+                }
+            })
+            .collect();
+        assert_eq!(
+            durations,
+            vec![
+                None,
+                Some(FitDuration::from_millis(3_723_450)),
+                Some(FitDuration::from_scaled(2505, 10_000.0)),
+                None,
+            ]
+        );
+        let lap = Lap::from_message(&lap).unwrap();
+        assert_eq!(lap.timer_time(), Some(FitDuration::from_millis(3_723_450)));
+        assert_eq!(lap.elapsed_time(), None);
+    }
+
+    #[test]
+    fn fractional_timestamps() {
+        let hr = mesg(132).u32(253, 1_000_000_000).u16(0, 8192).build();
+        assert_eq!(
+            precise_timestamp(&hr),
+            Some(
+                FitDuration::from_millis(250)
+                    + FitDuration::from_secs(1_000_000_000)
+            )
+        );
+        let whole = mesg(132).u32(253, 1_000_000_000).u16(0, u16::MAX).build();
+        assert_eq!(
+            precise_timestamp(&whole),
+            Some(FitDuration::from_secs(1_000_000_000))
+        );
+        assert_eq!(precise_timestamp(&mesg(132).u16(0, 1).build()), None);
+
+        let correlation = mesg(162)
+            .u32(253, 1_000_000_000)
+            .u16(0, 16384)
+            .u32(1, 1000)
+            .u16(2, 0)
+            .build();
+        let correlation = Correlation::from_message(&correlation).unwrap();
+        assert_eq!(correlation.utc.as_millis(), 1_000_000_000_500);
+        assert_eq!(correlation.system, FitDuration::from_secs(1000));
+        assert_eq!(
+            correlation
+                .to_utc(FitDuration::from_millis(1_010_250))
+                .map(|utc| utc.as_millis()),
+            Some(1_000_000_010_750)
+        );
+        assert_eq!(
+            correlation
+                .to_utc(FitDuration::from_secs(990))
+                .map(|utc| utc.as_millis()),
+            Some(999_999_990_500)
+        );
+        assert!(Correlation::from_message(&hr).is_none());
+    }
+}
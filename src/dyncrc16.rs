@@ -1,29 +1,28 @@
 //! Implements the Dynastream CRC-16 checksum.
+use consts::CRC_TABLE;
 use std::io;
 
 // CRC16 represents the partial evaluation of a checksum.
 pub(crate) struct CRC16(u16);
 
-const CRC_TABLE: [u16; 16] = [
-    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001,
-    0x6C00, 0x7800, 0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
-];
-
-pub const CRC_SIZE: u8 = 2;
-
 impl CRC16 {
     /// Initialise a new CRC-16 checksum.
     pub(crate) fn new() -> CRC16 {
         CRC16(0)
     }
 
+    /// Carry on from the checksum `sum` of earlier data.
+    pub(crate) fn resume(sum: u16) -> CRC16 {
+        CRC16(sum)
+    }
+
     // Hasher (?)
     pub(crate) fn sum_16(&self) -> u16 {
         self.0
     }
 
     /// Add data to the running checksum.
-    fn update(&mut self, data: &[u8]) {
+    pub(crate) fn update(&mut self, data: &[u8]) {
         for datum in data {
             self.update_byte(*datum)
         }
@@ -3,6 +3,13 @@ use failure::{
     Context,
     Fail,
 };
+#[cfg(feature = "serde")]
+use serde::ser::{
+    Serialize,
+    SerializeMap,
+    SerializeStruct,
+    Serializer,
+};
 use std::{
     convert::Into,
     fmt,
@@ -25,6 +32,16 @@ impl Error {
         self.ctx.get_context()
     }
 
+    /// This error and everything that caused it, outermost
+    /// first, separated by colons, for showing to a user.
+    pub fn describe(&self) -> String {
+        let causes: Vec<String> = (self as &dyn Fail)
+            .iter_chain()
+            .map(|cause| cause.to_string())
+            .collect();
+        causes.join(": ")
+    }
+
     pub(crate) fn reading<S, E>(what: S) -> impl FnOnce(E) -> Error
     where
         S: Into<String>,
@@ -53,10 +70,6 @@ impl Error {
         Error::from(err.context(ErrorKind::Seek))
     }
 
-    pub(crate) fn unknown_file_header_size(size: u8) -> Error {
-        Error::from(ErrorKind::UnknownFileHeaderSize(size))
-    }
-
     pub(crate) fn unsupported_protocol_version(
         upper_bound: u8,
         got: u8,
@@ -99,9 +112,74 @@ impl Error {
         Error::from(ErrorKind::NotFit)
     }
 
+    pub(crate) fn not_index() -> Error {
+        Error::from(ErrorKind::NotIndex)
+    }
+
+    pub(crate) fn not_directory() -> Error {
+        Error::from(ErrorKind::NotDirectory)
+    }
+
+    pub(crate) fn not_cache() -> Error {
+        Error::from(ErrorKind::NotCache)
+    }
+
+    pub(crate) fn cache_version<S: Into<String>>(
+        expected: S,
+        found: S,
+    ) -> Error {
+        Error::from(ErrorKind::CacheVersion {
+            expected: expected.into(),
+            found:    found.into(),
+        })
+    }
+
+    pub(crate) fn stale_cache(source_hash: u64, cached_hash: u64) -> Error {
+        Error::from(ErrorKind::StaleCache {
+            source_hash,
+            cached_hash,
+        })
+    }
+
     pub(crate) fn missing_definition(key: u8) -> Error {
         Error::from(ErrorKind::MissingDefinition(key))
     }
+
+    pub(crate) fn reserved_header_bits(header: u8) -> Error {
+        Error::from(ErrorKind::ReservedHeaderBits {
+            header,
+        })
+    }
+
+    pub(crate) fn short_buffer(expected: usize, got: usize) -> Error {
+        Error::from(ErrorKind::ShortBuffer {
+            expected,
+            got,
+        })
+    }
+
+    pub(crate) fn crc_mismatch(expected: u16, computed: u16) -> Error {
+        Error::from(ErrorKind::CrcMismatch {
+            expected,
+            computed,
+        })
+    }
+
+    pub(crate) fn missing_field(field: &'static str) -> Error {
+        Error::from(ErrorKind::MissingField {
+            field,
+        })
+    }
+
+    pub(crate) fn invalid_value() -> Error {
+        Error::from(ErrorKind::InvalidValue)
+    }
+
+    pub(crate) fn no_record_nearby(radius_m: f64) -> Error {
+        Error::from(ErrorKind::NoRecordNearby {
+            radius_m,
+        })
+    }
 }
 
 impl Fail for Error {
@@ -120,6 +198,145 @@ impl fmt::Display for Error {
     }
 }
 
+/// Serialized as an object with the stable `code` of its
+/// kind, the whole chain of causes as a `message`, and the
+/// values the kind carries as `details`:
+///
+/// ```json
+/// {
+///   "code": "CrcMismatch",
+///   "message": "CRC mismatch: expected 0x1234, got 0x5678",
+///   "details": { "expected": 4660, "computed": 22136 }
+/// }
+/// ```
+#[cfg(feature = "serde")]
+impl Serialize for Error {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> result::Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("Error", 3)?;
+        error.serialize_field("code", self.kind().code())?;
+        error.serialize_field("message", &self.describe())?;
+        error.serialize_field("details", &Details(self.kind()))?;
+        error.end()
+    }
+}
+
+/// The values an `ErrorKind` carries, as a map.
+#[cfg(feature = "serde")]
+struct Details<'a>(&'a ErrorKind);
+
+#[cfg(feature = "serde")]
+impl<'a> Serialize for Details<'a> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match *self.0 {
+            ErrorKind::Read {
+                ref what,
+            }
+            | ErrorKind::Decode {
+                ref what,
+            } => map.serialize_entry("what", what)?,
+
+            ErrorKind::Seek
+            | ErrorKind::NotFit
+            | ErrorKind::NotIndex
+            | ErrorKind::NotDirectory
+            | ErrorKind::NotCache
+            | ErrorKind::InvalidValue => {},
+
+            ErrorKind::UnknownFileHeaderSize(size) => {
+                map.serialize_entry("size", &size)?
+            },
+
+            ErrorKind::UnsupportedProtocolVersion {
+                upper_bound,
+                got,
+            } => {
+                map.serialize_entry("upper_bound", &upper_bound)?;
+                map.serialize_entry("got", &got)?;
+            },
+
+            ErrorKind::UnknownBaseType {
+                type_id,
+            } => map.serialize_entry("type_id", &type_id)?,
+
+            ErrorKind::UnknownType {
+                ref type_name,
+                type_id,
+            } => {
+                map.serialize_entry("type_name", type_name)?;
+                map.serialize_entry("type_id", &type_id)?;
+            },
+
+            ErrorKind::UnknownArchitecture(arch) => {
+                map.serialize_entry("architecture", &arch)?
+            },
+
+            ErrorKind::UnknownMessage(num) => {
+                map.serialize_entry("mesg_num", &num)?
+            },
+
+            ErrorKind::UnknownField(num) => {
+                map.serialize_entry("field_def_num", &num)?
+            },
+
+            ErrorKind::MissingDefinition(key) => {
+                map.serialize_entry("key", &key)?
+            },
+
+            ErrorKind::ReservedHeaderBits {
+                header,
+            } => map.serialize_entry("header", &header)?,
+
+            ErrorKind::ShortBuffer {
+                expected,
+                got,
+            } => {
+                map.serialize_entry("expected", &expected)?;
+                map.serialize_entry("got", &got)?;
+            },
+
+            ErrorKind::CrcMismatch {
+                expected,
+                computed,
+            } => {
+                map.serialize_entry("expected", &expected)?;
+                map.serialize_entry("computed", &computed)?;
+            },
+
+            ErrorKind::MissingField {
+                field,
+            } => map.serialize_entry("field", field)?,
+
+            ErrorKind::NoRecordNearby {
+                radius_m,
+            } => map.serialize_entry("radius_m", &radius_m)?,
+
+            ErrorKind::CacheVersion {
+                ref expected,
+                ref found,
+            } => {
+                map.serialize_entry("expected", expected)?;
+                map.serialize_entry("found", found)?;
+            },
+
+            ErrorKind::StaleCache {
+                source_hash,
+                cached_hash,
+            } => {
+                map.serialize_entry("source_hash", &source_hash)?;
+                map.serialize_entry("cached_hash", &cached_hash)?;
+            },
+        }
+        map.end()
+    }
+}
+
 /// The specific kind of error that can occur.
 pub enum ErrorKind {
     /// An error occured while attempting to read bytes.
@@ -128,7 +345,8 @@ pub enum ErrorKind {
     Decode { what: String },
     /// An error occured while seeking.
     Seek,
-    /// Encountered a bad file header size.
+    /// Encountered a bad file header size. No longer
+    /// produced: bad header sizes are reported as `NotFit`.
     UnknownFileHeaderSize(u8),
     /// Encountered an unsupported protocol version.
     UnsupportedProtocolVersion {
@@ -143,6 +361,11 @@ pub enum ErrorKind {
     UnknownType { type_name: String, type_id:   usize },
     /// Bad file magic.
     NotFit,
+    /// Bad magic or format version in a saved file index.
+    NotIndex,
+    /// A device directory listing with a bad header or a
+    /// truncated entry.
+    NotDirectory,
     /// Encountered an unknown file architecture.
     UnknownArchitecture(u8),
     /// Encountered an unknown message number.
@@ -152,6 +375,117 @@ pub enum ErrorKind {
     /// A data message referenced an unknown definition
     /// message.
     MissingDefinition(u8),
+    /// A record header set bits the protocol reserves, in
+    /// a strict reader.
+    ReservedHeaderBits {
+        /// The record header byte.
+        header: u8,
+    },
+    /// A field was declared with fewer bytes than its base
+    /// type needs.
+    ShortBuffer {
+        /// The number of bytes the base type needs.
+        expected: usize,
+        /// The number of bytes the field definition gave.
+        got:      usize,
+    },
+    /// The checksum stored in the file didn't match the one
+    /// computed over its contents.
+    CrcMismatch {
+        /// The checksum stored in the file.
+        expected: u16,
+        /// The checksum we computed.
+        computed: u16,
+    },
+    /// A message lacked a field needed to build a summary of
+    /// it.
+    MissingField {
+        /// The snake case name of the field.
+        field: &'static str,
+    },
+    /// A field held its base type's invalid value where a
+    /// value was asked for.
+    InvalidValue,
+    /// No record with a position and an altitude was close
+    /// enough to a reference point.
+    NoRecordNearby {
+        /// How far from the reference point we looked.
+        radius_m: f64,
+    },
+    /// Bad magic in serialized cache data.
+    NotCache,
+    /// Serialized cache data written by another version of
+    /// the format or of this crate.
+    CacheVersion {
+        /// The version this build reads and writes.
+        expected: String,
+        /// The version the data was written by.
+        found:    String,
+    },
+    /// Serialized cache data decoded from a different file.
+    StaleCache {
+        /// The hash of the file given.
+        source_hash: u64,
+        /// The hash of the file the data was decoded from.
+        cached_hash: u64,
+    },
+}
+
+impl ErrorKind {
+    /// A name for this kind of error that stays the same
+    /// across releases, for matching on in other languages
+    /// and over the wire. It's the name of the variant.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            ErrorKind::Read {
+                ..
+            } => "Read",
+            ErrorKind::Decode {
+                ..
+            } => "Decode",
+            ErrorKind::Seek => "Seek",
+            ErrorKind::UnknownFileHeaderSize(_) => "UnknownFileHeaderSize",
+            ErrorKind::UnsupportedProtocolVersion {
+                ..
+            } => "UnsupportedProtocolVersion",
+            ErrorKind::UnknownBaseType {
+                ..
+            } => "UnknownBaseType",
+            ErrorKind::UnknownType {
+                ..
+            } => "UnknownType",
+            ErrorKind::NotFit => "NotFit",
+            ErrorKind::NotIndex => "NotIndex",
+            ErrorKind::NotDirectory => "NotDirectory",
+            ErrorKind::UnknownArchitecture(_) => "UnknownArchitecture",
+            ErrorKind::UnknownMessage(_) => "UnknownMessage",
+            ErrorKind::UnknownField(_) => "UnknownField",
+            ErrorKind::MissingDefinition(_) => "MissingDefinition",
+            ErrorKind::ReservedHeaderBits {
+                ..
+            } => "ReservedHeaderBits",
+            ErrorKind::ShortBuffer {
+                ..
+            } => "ShortBuffer",
+            ErrorKind::CrcMismatch {
+                ..
+            } => "CrcMismatch",
+            ErrorKind::MissingField {
+                ..
+            } => "MissingField",
+            ErrorKind::InvalidValue => "InvalidValue",
+            ErrorKind::NoRecordNearby {
+                ..
+            } => "NoRecordNearby",
+            ErrorKind::NotCache => "NotCache",
+            ErrorKind::CacheVersion {
+                ..
+            } => "CacheVersion",
+            ErrorKind::StaleCache {
+                ..
+            } => "StaleCache",
+        }
+    }
 }
 
 impl fmt::Display for ErrorKind {
@@ -217,6 +551,14 @@ impl fmt::Display for ErrorKind {
                 write!(f, "bad file magic, probably not a FIT file")
             },
 
+            ErrorKind::NotIndex => {
+                write!(f, "bad index magic, not an index or an unknown version")
+            },
+
+            ErrorKind::NotDirectory => {
+                write!(f, "not a device directory listing")
+            },
+
             ErrorKind::MissingDefinition(key) => {
                 write!(
                     f,
@@ -225,6 +567,80 @@ impl fmt::Display for ErrorKind {
                     key
                 )
             },
+
+            ErrorKind::ReservedHeaderBits {
+                header,
+            } => {
+                write!(
+                    f,
+                    "record header {:#010b} sets reserved bits",
+                    header
+                )
+            },
+
+            ErrorKind::ShortBuffer {
+                expected,
+                got,
+            } => {
+                write!(
+                    f,
+                    "field too short: need {} bytes, got {}",
+                    expected, got
+                )
+            },
+
+            ErrorKind::CrcMismatch {
+                expected,
+                computed,
+            } => {
+                write!(
+                    f,
+                    "CRC mismatch: expected {:#06x}, got {:#06x}",
+                    expected, computed
+                )
+            },
+
+            ErrorKind::MissingField {
+                field,
+            } => write!(f, "missing required field: {}", field),
+
+            ErrorKind::InvalidValue => write!(f, "invalid field value"),
+
+            ErrorKind::NoRecordNearby {
+                radius_m,
+            } => {
+                write!(
+                    f,
+                    "no record with an altitude within {} m of the reference \
+                     point",
+                    radius_m
+                )
+            },
+
+            ErrorKind::NotCache => write!(f, "bad cache magic, not a cache"),
+
+            ErrorKind::CacheVersion {
+                ref expected,
+                ref found,
+            } => {
+                write!(
+                    f,
+                    "cache written by version {}, this is version {}",
+                    found, expected
+                )
+            },
+
+            ErrorKind::StaleCache {
+                source_hash,
+                cached_hash,
+            } => {
+                write!(
+                    f,
+                    "stale cache: decoded from a file with hash {:016x}, \
+                     not {:016x}",
+                    cached_hash, source_hash
+                )
+            },
         }
     }
 }
@@ -242,3 +658,129 @@ impl From<Context<ErrorKind>> for Error {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde_json::{
+        self,
+        json,
+    };
+    use std::io;
+
+    #[test]
+    fn serializes_with_stable_codes() {
+        let eof = || io::Error::from(io::ErrorKind::UnexpectedEof);
+        let errors = vec![
+            (
+                Error::reading("file header")(eof()),
+                "Read",
+                json!({ "what": "file header" }),
+            ),
+            (
+                Error::decoding("record")(eof()),
+                "Decode",
+                json!({ "what": "record" }),
+            ),
+            (Error::seek(eof()), "Seek", json!({})),
+            (
+                Error::from(ErrorKind::UnknownFileHeaderSize(13)),
+                "UnknownFileHeaderSize",
+                json!({ "size": 13 }),
+            ),
+            (
+                Error::unsupported_protocol_version(2, 3),
+                "UnsupportedProtocolVersion",
+                json!({ "upper_bound": 2, "got": 3 }),
+            ),
+            (
+                Error::unknown_base_type(0x99),
+                "UnknownBaseType",
+                json!({ "type_id": 0x99 }),
+            ),
+            (
+                Error::unknown_type("Sport", 300),
+                "UnknownType",
+                json!({ "type_name": "Sport", "type_id": 300 }),
+            ),
+            (Error::not_fit(), "NotFit", json!({})),
+            (Error::not_index(), "NotIndex", json!({})),
+            (Error::not_directory(), "NotDirectory", json!({})),
+            (
+                Error::unknown_architecture(2),
+                "UnknownArchitecture",
+                json!({ "architecture": 2 }),
+            ),
+            (
+                Error::unknown_message(0xFF00),
+                "UnknownMessage",
+                json!({ "mesg_num": 0xFF00 }),
+            ),
+            (
+                Error::unknown_field(200),
+                "UnknownField",
+                json!({ "field_def_num": 200 }),
+            ),
+            (
+                Error::missing_definition(3),
+                "MissingDefinition",
+                json!({ "key": 3 }),
+            ),
+            (
+                Error::reserved_header_bits(0x50),
+                "ReservedHeaderBits",
+                json!({ "header": 0x50 }),
+            ),
+            (
+                Error::short_buffer(4, 1),
+                "ShortBuffer",
+                json!({ "expected": 4, "got": 1 }),
+            ),
+            (
+                Error::crc_mismatch(0x1234, 0x5678),
+                "CrcMismatch",
+                json!({ "expected": 4660, "computed": 22136 }),
+            ),
+            (
+                Error::missing_field("start_time"),
+                "MissingField",
+                json!({ "field": "start_time" }),
+            ),
+            (Error::invalid_value(), "InvalidValue", json!({})),
+            (
+                Error::no_record_nearby(50.0),
+                "NoRecordNearby",
+                json!({ "radius_m": 50.0 }),
+            ),
+            (Error::not_cache(), "NotCache", json!({})),
+            (
+                Error::cache_version("1/0.2.0", "1/0.1.0"),
+                "CacheVersion",
+                json!({ "expected": "1/0.2.0", "found": "1/0.1.0" }),
+            ),
+            (
+                Error::stale_cache(1, 2),
+                "StaleCache",
+                json!({ "source_hash": 1, "cached_hash": 2 }),
+            ),
+        ];
+
+        for (error, code, details) in errors {
+            let value = serde_json::to_value(&error).unwrap();
+            assert_eq!(value["code"], code);
+            assert_eq!(value["message"], error.describe());
+            assert_eq!(value["details"], details, "{}", code);
+        }
+
+        let crc = serde_json::to_string(&Error::crc_mismatch(0x1234, 0x5678));
+        assert_eq!(
+            crc.unwrap(),
+            r#"{"code":"CrcMismatch","message":"CRC mismatch: expected 0x1234, got 0x5678","details":{"expected":4660,"computed":22136}}"#
+        );
+        let read = serde_json::to_value(&Error::reading("header")(eof()));
+        assert_eq!(
+            read.unwrap()["message"],
+            "reading header: unexpected end of file"
+        );
+    }
+}
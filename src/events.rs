@@ -0,0 +1,363 @@
+//! Typed decoding of `Event` messages.
+//!
+//! Most of what an event means lives in its `data` field,
+//! whose interpretation depends on the event number: gear
+//! changes pack four components into it, radar alerts a
+//! threat level, rider position changes an enum, and so on.
+use profile::{
+    messages::{
+        Event as EventField,
+        Message,
+    },
+    types::{
+        Event,
+        EventType,
+    },
+};
+use types::record::Data;
+
+/// Whether the rider is in or out of the saddle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiderPosition {
+    Seated,
+    Standing,
+    TransitionToSeated,
+    TransitionToStanding,
+}
+
+/// Threat level reported by a rear facing radar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadarThreatLevel {
+    Unknown,
+    None,
+    Approaching,
+    ApproachingFast,
+}
+
+/// An `Event` message with its `data` field decoded
+/// according to the event number.
+#[derive(Debug, Clone)]
+pub enum Parsed {
+    /// The timer was started or stopped.
+    Timer(EventType),
+    /// A front or rear shift on electronic groupsets. Gear
+    /// numbers count from 1 for the innermost ring or cog.
+    GearChange {
+        front:       Option<u8>,
+        rear:        Option<u8>,
+        front_teeth: Option<u8>,
+        rear_teeth:  Option<u8>,
+    },
+    RiderPosition(RiderPosition),
+    RadarThreat(RadarThreatLevel),
+    /// Battery voltage in volts.
+    Battery(f64),
+    /// Index of the `CoursePoint` message that was reached.
+    CoursePoint(u16),
+    SportPoint {
+        score:          u16,
+        opponent_score: u16,
+    },
+    /// Any other event, with its data left undecoded.
+    Other {
+        event:      Event,
+        event_type: Option<EventType>,
+        data:       Option<u32>,
+    },
+}
+
+/// Decode an `Event` data message.
+///
+/// Returns `None` if `mesg` isn't an `Event` message or has
+/// no event number. Events this module doesn't know about,
+/// or known events missing their data, come back as
+/// [`Parsed::Other`](enum.Parsed.html#variant.Other).
+pub fn parse(mesg: &Data) -> Option<Parsed> {
+    let mut event = None;
+    let mut event_type = None;
+    let mut data = None;
+    let mut data16 = None;
+
+    for field in mesg.0.iter() {
+        let field = match field {
+            Message::Event(field) => field,
+            _ => return None,
+        };
+
+        match field {
+            EventField::Event(f) => event = Some(f.raw_value.clone()),
+            EventField::EventType(f) => event_type = Some(f.raw_value.clone()),
+            EventField::Data(f) if f.is_valid() => data = Some(f.raw_value.0),
+            EventField::Data16(f) if f.is_valid() => {
+                data16 = Some(u32::from(f.raw_value.0))
+            },
+            _ => (),
+        }
+    }
+
+    // Older devices only fill in the 16 bit field
+    let data = data.or(data16);
+
+    let parsed = match (event?, event_type, data) {
+        (Event::Timer, Some(event_type), _) => Parsed::Timer(event_type),
+        (Event::FrontGearChange, _, Some(data))
+        | (Event::RearGearChange, _, Some(data)) => {
+            let component = |shift: u32| {
+                Some((data >> shift) as u8).filter(|&byte| byte != 0)
+            };
+            Parsed::GearChange {
+                rear:        component(0),
+                rear_teeth:  component(8),
+                front:       component(16),
+                front_teeth: component(24),
+            }
+        },
+        (Event::RiderPositionChange, _, Some(data)) if data <= 3 => {
+            Parsed::RiderPosition(match data {
+                0 => RiderPosition::Seated,
+                1 => RiderPosition::Standing,
+                2 => RiderPosition::TransitionToSeated,
+                _ => RiderPosition::TransitionToStanding,
+            })
+        },
+        (Event::RadarThreatAlert, _, Some(data)) => {
+            Parsed::RadarThreat(match data & 0xFF {
+                1 => RadarThreatLevel::None,
+                2 => RadarThreatLevel::Approaching,
+                3 => RadarThreatLevel::ApproachingFast,
+                _ => RadarThreatLevel::Unknown,
+            })
+        },
+        (Event::Battery, _, Some(data)) => {
+            Parsed::Battery(f64::from(data & 0xFFFF) / 1000.0)
+        },
+        (Event::CoursePoint, _, Some(data)) => {
+            Parsed::CoursePoint((data & 0xFFFF) as u16)
+        },
+        (Event::SportPoint, _, Some(data)) => {
+            Parsed::SportPoint {
+                score:          (data & 0xFFFF) as u16,
+                opponent_score: (data >> 16) as u16,
+            }
+        },
+        (event, event_type, data) => {
+            Parsed::Other {
+                event,
+                event_type,
+                data,
+            }
+        },
+    };
+    Some(parsed)
+}
+
+/// Whether `mesg` is an `Event` message.
+pub fn is_event(mesg: &Data) -> bool {
+    matches!(mesg.0.first(), Some(Message::Event(_)))
+}
+
+/// Whether `mesg` is an `Event` message starting or
+/// stopping the timer.
+pub fn is_timer_event(mesg: &Data) -> bool {
+    matches!(parse(mesg), Some(Parsed::Timer(_)))
+}
+
+/// The `(pause_start, pause_end)` of every pause, in
+/// seconds since the FIT epoch, from the timer events among
+/// `events`.
+///
+/// A pause runs from the first stop to the next start;
+/// repeated stops don't restart it. A stop with no start
+/// after it ends the activity rather than pausing it, and
+/// timer events without a timestamp are skipped.
+pub fn pause_intervals<'a, I>(events: I) -> Vec<(u32, u32)>
+where
+    I: IntoIterator<Item = &'a Data>,
+{
+    let mut intervals = Vec::new();
+    let mut paused_at = None;
+    for mesg in events {
+        let event_type = match parse(mesg) {
+            Some(Parsed::Timer(event_type)) => event_type,
+            _ => continue,
+        };
+        let timestamp = match timestamp(mesg) {
+            Some(timestamp) => timestamp,
+            None => continue,
+        };
+
+        match event_type {
+            EventType::Start => {
+                if let Some(start) = paused_at.take() {
+                    intervals.push((start, timestamp));
+                }
+            },
+            EventType::Stop
+            | EventType::StopAll
+            | EventType::StopDisable
+            | EventType::StopDisableAll => {
+                paused_at = paused_at.or(Some(timestamp))
+            },
+            _ => (),
+        }
+    }
+    intervals
+}
+
+/// `total_elapsed_s` less the time spent paused, going by
+/// the timer events among `events`.
+pub fn active_time_s(events: &[Data], total_elapsed_s: f64) -> f64 {
+    let paused: u32 = pause_intervals(events)
+        .iter()
+        .map(|&(start, end)| end.saturating_sub(start))
+        .sum();
+    (total_elapsed_s - f64::from(paused)).max(0.0)
+}
+
+pub(crate) fn timestamp(mesg: &Data) -> Option<u32> {
+    mesg.0.iter().find_map(|field| {
+        match field {
+            Message::Event(EventField::Timestamp(f)) => Some(f.raw_value.0),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    const FRONT_GEAR_CHANGE: u8 = 42;
+    const REAR_GEAR_CHANGE: u8 = 43;
+    const RIDER_POSITION_CHANGE: u8 = 44;
+    const RADAR_THREAT_ALERT: u8 = 75;
+
+    fn event(timestamp: u32, event: u8, data: u32) -> Data {
+        mesg(21)
+            .u32(253, timestamp)
+            .u8(0, event)
+            .u8(1, 3) // marker
+            .u32(3, data)
+            .build()
+    }
+
+    /// A few minutes of a Di2 + Varia ride.
+    fn ride() -> Vec<Data> {
+        vec![
+            mesg(21).u32(253, 100).u8(0, 0).u8(1, 0).u32(3, 0).build(),
+            event(130, REAR_GEAR_CHANGE, 0x34_02_0F_05),
+            event(131, RADAR_THREAT_ALERT, 0x00_00_01_02),
+            event(150, FRONT_GEAR_CHANGE, 0x24_01_0F_05),
+            event(160, RIDER_POSITION_CHANGE, 1),
+            event(175, RIDER_POSITION_CHANGE, 0),
+            event(180, RADAR_THREAT_ALERT, 0x00_00_00_01),
+            event(190, 11, 3850),
+            event(200, 99, 7),
+            mesg(20).u32(253, 200).build(),
+        ]
+    }
+
+    #[test]
+    fn parses_ride_events() {
+        let parsed: Vec<Parsed> = ride().iter().filter_map(parse).collect();
+        assert_eq!(parsed.len(), 9);
+
+        assert!(matches!(parsed[0], Parsed::Timer(EventType::Start)));
+        match parsed[1] {
+            Parsed::GearChange {
+                front,
+                rear,
+                front_teeth,
+                rear_teeth,
+            } => {
+                assert_eq!(front, Some(2));
+                assert_eq!(front_teeth, Some(52));
+                assert_eq!(rear, Some(5));
+                assert_eq!(rear_teeth, Some(15));
+            },
+            ref other => panic!("expected a gear change, got {:?}", other),
+        }
+        assert!(matches!(
+            parsed[2],
+            Parsed::RadarThreat(RadarThreatLevel::Approaching)
+        ));
+        assert!(matches!(
+            parsed[3],
+            Parsed::GearChange {
+                front: Some(1),
+                front_teeth: Some(36),
+                ..
+            }
+        ));
+        assert!(matches!(
+            parsed[4],
+            Parsed::RiderPosition(RiderPosition::Standing)
+        ));
+        assert!(matches!(
+            parsed[5],
+            Parsed::RiderPosition(RiderPosition::Seated)
+        ));
+        assert!(matches!(
+            parsed[6],
+            Parsed::RadarThreat(RadarThreatLevel::None)
+        ));
+        match parsed[7] {
+            Parsed::Battery(volts) => assert!((volts - 3.85).abs() < 1e-9),
+            ref other => panic!("expected a battery event, got {:?}", other),
+        }
+        assert!(matches!(
+            parsed[8],
+            Parsed::Other {
+                event: Event::Unknown,
+                data: Some(7),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn falls_back_to_data16() {
+        let course_point = mesg(21).u8(0, 10).u16(2, 4).build();
+        assert!(matches!(parse(&course_point), Some(Parsed::CoursePoint(4))));
+
+        let no_data = mesg(21).u8(0, REAR_GEAR_CHANGE).build();
+        assert!(matches!(
+            parse(&no_data),
+            Some(Parsed::Other {
+                event: Event::RearGearChange,
+                data: None,
+                ..
+            })
+        ));
+    }
+
+    fn timer(timestamp: u32, event_type: u8) -> Data {
+        mesg(21).u32(253, timestamp).u8(0, 0).u8(1, event_type).build()
+    }
+
+    #[test]
+    fn pauses() {
+        const START: u8 = 0;
+        const STOP: u8 = 1;
+        const STOP_ALL: u8 = 4;
+
+        let events = vec![
+            timer(1000, START),
+            event(1100, REAR_GEAR_CHANGE, 0x34_02_0F_05),
+            timer(1200, STOP),
+            // Auto pause kicking in while already stopped
+            timer(1210, STOP_ALL),
+            timer(1300, START),
+            timer(1500, STOP_ALL),
+            timer(1560, START),
+            timer(2000, STOP_ALL),
+        ];
+
+        assert_eq!(pause_intervals(&events), vec![(1200, 1300), (1500, 1560)]);
+        assert_eq!(active_time_s(&events, 1000.0), 840.0);
+        assert_eq!(events.iter().filter(|e| is_timer_event(e)).count(), 7);
+        assert!(events.iter().all(is_event));
+        assert!(!is_event(&mesg(20).u32(253, 1000).build()));
+    }
+}
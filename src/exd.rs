@@ -0,0 +1,117 @@
+//! Layouts of Garmin extra display (EXD) screens, the
+//! customisable data screens of Connect IQ apps.
+use profile::messages::{
+    ExdScreenConfiguration,
+    Message,
+};
+pub use profile::types::ExdLayout;
+use types::record::Data;
+
+/// How one screen is split into data fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExdScreen {
+    pub screen_index:   u8,
+    pub field_count:    u8,
+    /// `ExdLayout::Unknown` if the layout is missing or not
+    /// in the profile.
+    pub layout:         ExdLayout,
+    pub screen_enabled: bool,
+}
+
+impl ExdScreen {
+    /// Flatten a decoded `ExdScreenConfiguration` data
+    /// message.
+    ///
+    /// Returns `None` if `mesg` isn't an
+    /// `ExdScreenConfiguration` message or has no valid
+    /// screen index.
+    pub fn from_message(mesg: &Data) -> Option<ExdScreen> {
+        let mut screen_index = None;
+        let mut screen = ExdScreen {
+            screen_index:   0,
+            field_count:    0,
+            layout:         ExdLayout::Unknown,
+            screen_enabled: false,
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::ExdScreenConfiguration(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                ExdScreenConfiguration::ScreenIndex(f) if f.is_valid() => {
+                    screen_index = Some(f.raw_value.0)
+                },
+                ExdScreenConfiguration::FieldCount(f) if f.is_valid() => {
+                    screen.field_count = f.raw_value.0
+                },
+                ExdScreenConfiguration::Layout(f) => {
+                    screen.layout = f.raw_value
+                },
+                ExdScreenConfiguration::ScreenEnabled(f) => {
+                    screen.screen_enabled = f.raw_value.0
+                },
+                _ => (),
+            }
+        }
+
+        screen_index.map(|screen_index| {
+            ExdScreen {
+                screen_index,
+                ..screen
+            }
+        })
+    }
+}
+
+/// The screens configured in `messages`, in file order.
+pub fn parse_exd_screens(messages: &[Data]) -> Vec<ExdScreen> {
+    messages.iter().filter_map(ExdScreen::from_message).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn screen(index: u8, field_count: u8, layout: u8, enabled: u8) -> Data {
+        mesg(200)
+            .u8(0, index)
+            .u8(1, field_count)
+            .u8(2, layout)
+            .u8(3, enabled)
+            .build()
+    }
+
+    #[test]
+    fn screens_and_layouts() {
+        let messages = vec![
+            screen(0, 1, 0, 1),
+            screen(1, 4, 5, 1),
+            screen(2, 3, 6, 0),
+            screen(3, 2, 200, 1),
+            mesg(200).u8(0, 0xFF).u8(2, 1).build(),
+            mesg(201).u8(0, 1).u8(2, 3).build(),
+        ];
+
+        let screens = parse_exd_screens(&messages);
+        assert_eq!(screens.len(), 4);
+
+        let layouts: Vec<ExdLayout> =
+            screens.iter().map(|s| s.layout).collect();
+        assert_eq!(
+            layouts,
+            vec![
+                ExdLayout::FullScreen,
+                ExdLayout::FullQuarterSplit,
+                ExdLayout::HalfVerticalLeftSplit,
+                ExdLayout::Unknown,
+            ]
+        );
+        assert_eq!(screens[1].field_count, 4);
+        assert!(!screens[2].screen_enabled);
+        assert_eq!(screens[3].screen_index, 3);
+    }
+}
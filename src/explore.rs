@@ -0,0 +1,414 @@
+//! Help for working out what the fields the profile doesn't
+//! know about hold.
+//!
+//! Firmware gets ahead of the published profile all the
+//! time. [`report`] gathers the raw values of every unknown
+//! field across a file, with the hints that usually give
+//! them away: their range, whether they only ever grow, and
+//! which everyday quantities they'd be plausible as under
+//! the scales the profile commonly uses.
+//!
+//! [`report`]: fn.report.html
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+    LittleEndian,
+};
+use profile::{
+    base,
+    messages::Message as FieldMessage,
+};
+use std::collections::BTreeMap;
+use types::{
+    field::Value,
+    record::{
+        Definition,
+        FieldDefinition,
+        Message,
+    },
+};
+use validate::{
+    Bounds,
+    Quantity,
+};
+
+/// Scales the profile commonly divides raw values by.
+const SCALES: [f64; 5] = [1.0, 5.0, 10.0, 100.0, 1000.0];
+
+const QUANTITIES: [Quantity; 6] = [
+    Quantity::Altitude,
+    Quantity::Speed,
+    Quantity::HeartRate,
+    Quantity::Cadence,
+    Quantity::Power,
+    Quantity::Distance,
+];
+
+/// Every unknown field in a file, by message and field
+/// number.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExplorationReport {
+    pub fields: Vec<UnknownField>,
+}
+
+impl ExplorationReport {
+    /// The report on field `field_def_num` of `mesg_num`.
+    pub fn field(
+        &self,
+        mesg_num: u16,
+        field_def_num: u8,
+    ) -> Option<&UnknownField> {
+        self.fields.iter().find(|field| {
+            field.mesg_num == mesg_num && field.field_def_num == field_def_num
+        })
+    }
+}
+
+/// What a file says about a field the profile doesn't
+/// know.
+///
+/// Statistics are over the raw values, before any scale,
+/// taking the first element of arrays. They're `None` for
+/// strings and byte arrays, and when no value was valid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct UnknownField {
+    pub mesg_num:        u16,
+    /// Whether the whole message is missing from the
+    /// profile, rather than just the field.
+    pub unknown_message: bool,
+    pub field_def_num:   u8,
+    /// The SDK's name for the base type, e.g. `"uint16"`.
+    pub base_type:       &'static str,
+    /// Bytes.
+    pub size:            u8,
+    /// Data messages with the field.
+    pub occurrences:     u64,
+    /// Data messages where it held a valid value.
+    pub valid:           u64,
+    pub min:             Option<f64>,
+    pub max:             Option<f64>,
+    pub mean:            Option<f64>,
+    /// Whether the valid values never decrease, and do
+    /// increase, as timestamps and accumulators do.
+    pub monotonic:       bool,
+    /// Scales under which every valid value is plausible
+    /// for a quantity, by the default [`Bounds`]. Empty
+    /// when the values never change.
+    ///
+    /// [`Bounds`]: ../validate/struct.Bounds.html
+    pub plausible:       Vec<ScaleGuess>,
+}
+
+/// A quantity a field's values could be, if divided by
+/// `scale`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ScaleGuess {
+    pub quantity: Quantity,
+    pub scale:    f64,
+}
+
+/// Report on the unknown fields of the data messages among
+/// `messages`.
+///
+/// Base types and sizes come from the definition messages,
+/// so `messages` has to include them, as the reader yields
+/// them. Developer fields aren't included.
+pub fn report(messages: &[Message]) -> ExplorationReport {
+    let mut definitions: Vec<&Definition> = Vec::new();
+    let mut fields: BTreeMap<(u16, u8), Accumulator> = BTreeMap::new();
+
+    for mesg in messages {
+        let data = match mesg {
+            Message::Definition(definition) => {
+                definitions.push(definition);
+                continue;
+            },
+            Message::Data(data) => data,
+            Message::CompressedTimestamp => continue,
+        };
+        let mesg_num = match data.0.first() {
+            Some(field) => field.mesg_num(),
+            None => continue,
+        };
+        // The latest definition this message fits
+        let definition = definitions.iter().rev().find(|definition| {
+            definition.global_mesg_num() == mesg_num
+                && definition.total_fields() == data.0.len()
+                && definition
+                    .field_def_nums()
+                    .zip(data.0.iter())
+                    .all(|(num, field)| num == field.field_def_num())
+        });
+        let definition = match definition {
+            Some(definition) => definition,
+            None => continue,
+        };
+
+        for (field_def, field) in definition.field_defs().iter().zip(&data.0) {
+            if !field.is_unknown() {
+                continue;
+            }
+            let bytes = match field.value() {
+                Value::Bytes(bytes) => bytes,
+                _ => continue,
+            };
+            let unknown_message = matches!(field, FieldMessage::Unknown { .. });
+            fields
+                .entry((mesg_num, field_def.num()))
+                .or_insert_with(|| Accumulator::new(field_def, unknown_message))
+                .add(&bytes, definition.is_big_endian());
+        }
+    }
+
+    ExplorationReport {
+        fields: fields
+            .into_iter()
+            .map(|((mesg_num, field_def_num), acc)| {
+                acc.finish(mesg_num, field_def_num)
+            })
+            .collect(),
+    }
+}
+
+struct Accumulator {
+    unknown_message: bool,
+    base_type_num:   u8,
+    size:            u8,
+    occurrences:     u64,
+    valid:           u64,
+    min:             f64,
+    max:             f64,
+    sum:             f64,
+    last:            Option<f64>,
+    increased:       bool,
+    decreased:       bool,
+}
+
+impl Accumulator {
+    fn new(field_def: &FieldDefinition, unknown_message: bool) -> Self {
+        Accumulator {
+            unknown_message,
+            base_type_num: field_def.base_type_num(),
+            size: field_def.size(),
+            occurrences: 0,
+            valid: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            last: None,
+            increased: false,
+            decreased: false,
+        }
+    }
+
+    fn add(&mut self, bytes: &[u8], big_endian: bool) {
+        self.occurrences += 1;
+        let value = if big_endian {
+            first_element::<BigEndian>(self.base_type_num, bytes)
+        }
+        else {
+            first_element::<LittleEndian>(self.base_type_num, bytes)
+        };
+        let value = match value {
+            Some((element, value)) => {
+                if base::is_invalid_raw(self.base_type_num, element, big_endian)
+                {
+                    return;
+                }
+                value
+            },
+            None => {
+                if !base::is_invalid_raw(self.base_type_num, bytes, big_endian)
+                {
+                    self.valid += 1;
+                }
+                return;
+            },
+        };
+
+        self.valid += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        if let Some(last) = self.last {
+            self.increased |= value > last;
+            self.decreased |= value < last;
+        }
+        self.last = Some(value);
+    }
+
+    fn finish(self, mesg_num: u16, field_def_num: u8) -> UnknownField {
+        let numeric = self.last.is_some();
+        let plausible = if numeric && self.max > self.min {
+            plausible(self.min, self.max)
+        }
+        else {
+            Vec::new()
+        };
+        UnknownField {
+            mesg_num,
+            unknown_message: self.unknown_message,
+            field_def_num,
+            base_type: base_type_name(self.base_type_num),
+            size: self.size,
+            occurrences: self.occurrences,
+            valid: self.valid,
+            min: if numeric { Some(self.min) } else { None },
+            max: if numeric { Some(self.max) } else { None },
+            mean: if numeric {
+                Some(self.sum / self.valid as f64)
+            }
+            else {
+                None
+            },
+            monotonic: self.increased && !self.decreased,
+            plausible,
+        }
+    }
+}
+
+fn plausible(min: f64, max: f64) -> Vec<ScaleGuess> {
+    let bounds = Bounds::default();
+    let mut guesses = Vec::new();
+    for &quantity in QUANTITIES.iter() {
+        let range = match bounds.get(quantity) {
+            Some(range) => range,
+            None => continue,
+        };
+        for &scale in SCALES.iter() {
+            if range.contains(min / scale) && range.contains(max / scale) {
+                guesses.push(ScaleGuess {
+                    quantity,
+                    scale,
+                });
+            }
+        }
+    }
+    guesses
+}
+
+/// The bytes of the first element of a numeric field, and
+/// its value, or `None` for strings and bytes.
+fn first_element<T: ByteOrder>(
+    base_type_num: u8,
+    bytes: &[u8],
+) -> Option<(&[u8], f64)> {
+    let size = match base_type_num & 0x1F {
+        0 | 1 | 2 | 10 => 1,
+        3 | 4 | 11 => 2,
+        5 | 6 | 8 | 12 => 4,
+        9 | 14 | 15 | 16 => 8,
+        _ => return None,
+    };
+    let element = bytes.get(..size)?;
+    let value = match base_type_num & 0x1F {
+        0 | 2 | 10 => f64::from(element[0]),
+        1 => f64::from(element[0] as i8),
+        3 => f64::from(T::read_i16(element)),
+        4 | 11 => f64::from(T::read_u16(element)),
+        5 => f64::from(T::read_i32(element)),
+        6 | 12 => f64::from(T::read_u32(element)),
+        8 => f64::from(T::read_f32(element)),
+        9 => T::read_f64(element),
+        14 => T::read_i64(element) as f64,
+        _ => T::read_u64(element) as f64,
+    };
+    Some((element, value))
+}
+
+fn base_type_name(base_type_num: u8) -> &'static str {
+    match base_type_num & 0x1F {
+        0 => "enum",
+        1 => "sint8",
+        2 => "uint8",
+        3 => "sint16",
+        4 => "uint16",
+        5 => "sint32",
+        6 => "uint32",
+        7 => "string",
+        8 => "float32",
+        9 => "float64",
+        10 => "uint8z",
+        11 => "uint16z",
+        12 => "uint32z",
+        13 => "byte",
+        14 => "sint64",
+        15 => "uint64",
+        16 => "uint64z",
+        _ => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::Result;
+    use reader::FitReader;
+    use std::io::Cursor;
+    use testutil::FitBuilder;
+
+    fn messages(fit: &FitBuilder) -> Vec<Message> {
+        FitReader::new(Cursor::new(fit.build()))
+            .unwrap()
+            .map(|record| record.map(|record| record.content))
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn reports_unknown_fields() {
+        let mut fit = FitBuilder::new();
+        // Records with an unknown field holding mm/s, and
+        // an unknown message counting up big endian
+        fit.definition(0, 20, &[(253, 4, 0x86), (200, 2, 0x84)]);
+        fit.big_endian_definition(1, 0xFF00, &[(0, 4, 0x86), (1, 3, 0x07)]);
+        for (i, &speed) in [2500u16, 3100, 0xFFFF, 2800].iter().enumerate() {
+            let time = 1000 + i as u32;
+            fit.data(0, &[&time.to_le_bytes()[..], &speed.to_le_bytes()]);
+            fit.data(1, &[&(i as u32 * 7).to_be_bytes()[..], b"ab\0"]);
+        }
+
+        let report = report(&messages(&fit));
+        assert_eq!(report.fields.len(), 3);
+
+        let speed = report.field(20, 200).unwrap();
+        assert!(!speed.unknown_message);
+        assert_eq!((speed.base_type, speed.size), ("uint16", 2));
+        assert_eq!((speed.occurrences, speed.valid), (4, 3));
+        assert_eq!((speed.min, speed.max), (Some(2500.0), Some(3100.0)));
+        assert_eq!(speed.mean, Some(2800.0));
+        assert!(!speed.monotonic);
+        assert!(speed.plausible.contains(&ScaleGuess {
+            quantity: Quantity::Speed,
+            scale:    1000.0,
+        }));
+        assert!(!speed.plausible.contains(&ScaleGuess {
+            quantity: Quantity::Speed,
+            scale:    1.0,
+        }));
+
+        let counter = report.field(0xFF00, 0).unwrap();
+        assert!(counter.unknown_message);
+        assert_eq!((counter.min, counter.max), (Some(0.0), Some(21.0)));
+        assert!(counter.monotonic);
+
+        let text = report.field(0xFF00, 1).unwrap();
+        assert_eq!((text.base_type, text.valid), ("string", 4));
+        assert_eq!((text.min, text.mean), (None, None));
+        assert!(text.plausible.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes() {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(200, 1, 0x02)]);
+        fit.data(0, &[&[60]]);
+        let json = ::serde_json::to_value(report(&messages(&fit))).unwrap();
+        assert_eq!(json["fields"][0]["base_type"], "uint8");
+        assert_eq!(json["fields"][0]["plausible"], ::serde_json::json!([]));
+    }
+}
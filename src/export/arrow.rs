@@ -0,0 +1,848 @@
+//! Arrow record batches of records and monitoring samples.
+//!
+//! [`records_to_batch`] gives one row per `Record` message,
+//! with positions in degrees, times as UTC timestamps and
+//! every other quantity scaled into its units, which each
+//! column's metadata names. A field that's missing from a
+//! record or holds its invalid value is null.
+//!
+//! Decoded messages don't keep developer fields, so
+//! [`file_records_to_batch`] reads them from the file
+//! itself and adds a column for each one a
+//! `FieldDescription` names, as the
+//! [`export`](../index.html#developer-field-columns) module
+//! describes. [`monitoring_to_batch`] lays
+//! out a monitoring file instead, one row per sample.
+//!
+//! [`records_to_batch`]: fn.records_to_batch.html
+//! [`file_records_to_batch`]: fn.file_records_to_batch.html
+//! [`monitoring_to_batch`]: fn.monitoring_to_batch.html
+use arrow_array::{
+    ArrayRef,
+    Float64Array,
+    Int8Array,
+    RecordBatch,
+    StringArray,
+    TimestampMillisecondArray,
+    UInt16Array,
+    UInt8Array,
+};
+use arrow_schema::{
+    DataType,
+    Field as ArrowField,
+    Schema,
+    TimeUnit,
+};
+use error::Result;
+use export::{
+    DeveloperField,
+    DeveloperNaming,
+};
+use geo::semicircles_to_degrees;
+use monitoring::expand_timestamp_16;
+#[cfg(feature = "parquet")]
+use parquet::{
+    arrow::ArrowWriter,
+    errors::ParquetError,
+};
+use profile::messages::{
+    field_units,
+    DeveloperDataId,
+    FieldDescription,
+    Message,
+    Monitoring,
+};
+use series::RecordPoint;
+use slice::{
+    BorrowedField,
+    BorrowedMessage,
+    FieldDef,
+    FitSliceReader,
+};
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::{
+    collections::HashMap,
+    iter::FromIterator,
+    sync::Arc,
+};
+use time::FIT_EPOCH;
+use types::{
+    field::{
+        scale_value,
+        Field as _,
+        Value,
+    },
+    record::Data,
+};
+
+const RECORD: u16 = 20;
+const MONITORING: u16 = 55;
+const FIELD_DESCRIPTION: u16 = 206;
+const DEVELOPER_DATA_ID: u16 = 207;
+
+/// The columns [`records_to_batch`] always has.
+///
+/// [`records_to_batch`]: fn.records_to_batch.html
+pub const RECORD_COLUMNS: &[&str] = &[
+    "timestamp",
+    "position_lat",
+    "position_long",
+    "altitude",
+    "heart_rate",
+    "cadence",
+    "distance",
+    "speed",
+    "power",
+    "temperature",
+    "time_from_course",
+    "calories",
+];
+
+/// The base type number of strings.
+const STRING: u8 = 7;
+
+/// One row per `Record` message in `messages`, skipping
+/// anything else and records without a timestamp.
+///
+/// The columns are [`RECORD_COLUMNS`], with positions in
+/// degrees, and `altitude` and `speed` from the enhanced
+/// fields when a record has them.
+///
+/// [`RECORD_COLUMNS`]: constant.RECORD_COLUMNS.html
+pub fn records_to_batch(messages: &[Data]) -> RecordBatch {
+    let points: Vec<RecordPoint> =
+        messages.iter().filter_map(RecordPoint::from_message).collect();
+    record_batch(&points, Vec::new())
+}
+
+/// Like [`records_to_batch`], for the FIT file in `bytes`,
+/// with a column after those for each developer field a
+/// `FieldDescription` describes.
+///
+/// The columns are named from the descriptions with the
+/// default [`DeveloperNaming`]. String fields become string
+/// columns and numeric ones are scaled into the
+/// description's units. Arrays, and developer fields no
+/// description came before, are left out.
+///
+/// [`records_to_batch`]: fn.records_to_batch.html
+/// [`DeveloperNaming`]: ../struct.DeveloperNaming.html
+pub fn file_records_to_batch(bytes: &[u8]) -> Result<RecordBatch> {
+    file_records_to_batch_with(bytes, &DeveloperNaming::default())
+}
+
+/// Like [`file_records_to_batch`], naming developer field
+/// columns with `naming`.
+///
+/// [`file_records_to_batch`]: fn.file_records_to_batch.html
+pub fn file_records_to_batch_with(
+    bytes: &[u8],
+    naming: &DeveloperNaming,
+) -> Result<RecordBatch> {
+    let mut applications = HashMap::new();
+    let mut descriptions = HashMap::new();
+    let mut points = Vec::new();
+    let mut columns: Vec<DeveloperColumn> = Vec::new();
+    let mut column_of = HashMap::new();
+    for message in FitSliceReader::new(bytes)? {
+        let data = match message? {
+            BorrowedMessage::Data(data) => data,
+            BorrowedMessage::Definition(_) => continue,
+        };
+        match data.global_mesg_num() {
+            DEVELOPER_DATA_ID => {
+                if let Some((index, id)) = application(&data.to_data()?) {
+                    applications.insert(index, id);
+                }
+            },
+            FIELD_DESCRIPTION => {
+                if let Some((key, description)) = describe(&data.to_data()?) {
+                    descriptions.insert(key, description);
+                }
+            },
+            RECORD => {
+                let point = match RecordPoint::from_message(&data.to_data()?) {
+                    Some(point) => point,
+                    None => continue,
+                };
+                let row = points.len();
+                points.push(point);
+                let big_endian = data.definition().is_big_endian();
+                for (def, bytes) in data.developer_fields() {
+                    let key = (def.developer_data_index, def.num);
+                    let description = match descriptions.get(&key) {
+                        Some(description) => description,
+                        None => continue,
+                    };
+                    let i = *column_of.entry(key).or_insert_with(|| {
+                        columns.push(DeveloperColumn::new(key, description));
+                        columns.len() - 1
+                    });
+                    let field = BorrowedField::new(
+                        FieldDef {
+                            num:       def.num,
+                            size:      def.size,
+                            base_type: description.base_type,
+                        },
+                        big_endian,
+                        bytes,
+                    );
+                    columns[i].set(row, description, &field);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    let fields: Vec<DeveloperField> = columns
+        .iter()
+        .map(|column| {
+            DeveloperField {
+                application_id: applications
+                    .get(&column.field.developer_data_index)
+                    .cloned(),
+                ..column.field.clone()
+            }
+        })
+        .collect();
+    let names = naming.column_names(RECORD_COLUMNS, &fields);
+    let columns = names.into_iter().zip(columns).collect();
+    Ok(record_batch(&points, columns))
+}
+
+/// One row per `Monitoring` message in `messages` with a
+/// timestamp, either its own or a 16 bit one following an
+/// earlier message's.
+///
+/// The columns are `timestamp`, `activity_type` by name,
+/// `intensity`, `heart_rate`, and the `cycles`,
+/// `distance`, `active_time`, `calories` and
+/// `active_calories` accumulated for the activity type,
+/// and `temperature`.
+pub fn monitoring_to_batch(messages: &[Data]) -> RecordBatch {
+    let mut samples = Vec::new();
+    let mut last_timestamp = None;
+    for mesg in messages {
+        let mut sample = MonitoringSample::default();
+        let mut timestamp = None;
+        let mut timestamp_16 = None;
+        for field in mesg.0.iter() {
+            let monitoring = match field {
+                Message::Monitoring(monitoring) => monitoring,
+                _ => break,
+            };
+            match monitoring {
+                Monitoring::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                Monitoring::Timestamp16(f) if f.is_valid() => {
+                    timestamp_16 = Some(f.raw_value.0)
+                },
+                Monitoring::ActivityType(_) => {
+                    if let Value::Enum(name) = field.value() {
+                        sample.activity_type = Some(name)
+                    }
+                },
+                Monitoring::Intensity(f) if f.is_valid() => {
+                    sample.intensity = Some(f.value())
+                },
+                Monitoring::HeartRate(f) if f.is_valid() => {
+                    sample.heart_rate = Some(f.raw_value.0)
+                },
+                Monitoring::Cycles(f) if f.is_valid() => {
+                    sample.cycles = Some(f.value())
+                },
+                Monitoring::Distance(f) if f.is_valid() => {
+                    sample.distance = Some(f.value())
+                },
+                Monitoring::ActiveTime(f) if f.is_valid() => {
+                    sample.active_time = Some(f.value())
+                },
+                Monitoring::Calories(f) if f.is_valid() => {
+                    sample.calories = Some(f.raw_value.0)
+                },
+                Monitoring::ActiveCalories(f) if f.is_valid() => {
+                    sample.active_calories = Some(f.raw_value.0)
+                },
+                Monitoring::Temperature(f) if f.is_valid() => {
+                    sample.temperature = Some(f.value())
+                },
+                _ => (),
+            }
+        }
+        let timestamp = timestamp.or_else(|| {
+            Some(expand_timestamp_16(last_timestamp?, timestamp_16?))
+        });
+        if let Some(timestamp) = timestamp {
+            last_timestamp = Some(timestamp);
+            sample.timestamp = timestamp;
+            samples.push(sample);
+        }
+    }
+
+    let units = |num| field_units(MONITORING, num);
+    let fields = vec![
+        timestamp_field(),
+        column("activity_type", DataType::Utf8, None),
+        column("intensity", DataType::Float64, None),
+        column("heart_rate", DataType::UInt8, units(27)),
+        column("cycles", DataType::Float64, units(3)),
+        column("distance", DataType::Float64, units(2)),
+        column("active_time", DataType::Float64, units(4)),
+        column("calories", DataType::UInt16, units(1)),
+        column("active_calories", DataType::UInt16, units(19)),
+        column("temperature", DataType::Float64, units(12)),
+    ];
+    let arrays: Vec<ArrayRef> = vec![
+        timestamps(samples.iter().map(|s| s.timestamp)),
+        Arc::new(StringArray::from(
+            samples.iter().map(|s| s.activity_type.clone()).collect::<Vec<_>>(),
+        )),
+        Arc::new(Float64Array::from_iter(samples.iter().map(|s| s.intensity))),
+        Arc::new(UInt8Array::from_iter(samples.iter().map(|s| s.heart_rate))),
+        Arc::new(Float64Array::from_iter(samples.iter().map(|s| s.cycles))),
+        Arc::new(Float64Array::from_iter(samples.iter().map(|s| s.distance))),
+        Arc::new(Float64Array::from_iter(
+            samples.iter().map(|s| s.active_time),
+        )),
+        Arc::new(UInt16Array::from_iter(samples.iter().map(|s| s.calories))),
+        Arc::new(UInt16Array::from_iter(
+            samples.iter().map(|s| s.active_calories),
+        )),
+        Arc::new(Float64Array::from_iter(
+            samples.iter().map(|s| s.temperature),
+        )),
+    ];
+    batch("monitoring", fields, arrays)
+}
+
+/// Write `batch` to `out` as a Parquet file, with the
+/// default writer properties.
+#[cfg(feature = "parquet")]
+pub fn write_parquet<W: Write + Send>(
+    batch: &RecordBatch,
+    out: W,
+) -> ::std::result::Result<(), ParquetError> {
+    let mut writer = ArrowWriter::try_new(out, batch.schema(), None)?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// What a `Monitoring` message says, at its full
+/// timestamp.
+#[derive(Default)]
+struct MonitoringSample {
+    timestamp:       u32,
+    activity_type:   Option<String>,
+    intensity:       Option<f64>,
+    heart_rate:      Option<u8>,
+    cycles:          Option<f64>,
+    distance:        Option<f64>,
+    active_time:     Option<f64>,
+    calories:        Option<u16>,
+    active_calories: Option<u16>,
+    temperature:     Option<f64>,
+}
+
+/// The record columns for `points`, then `developer`.
+fn record_batch(
+    points: &[RecordPoint],
+    developer: Vec<(String, DeveloperColumn)>,
+) -> RecordBatch {
+    let units = |num| field_units(RECORD, num);
+    let mut fields = vec![
+        timestamp_field(),
+        column("position_lat", DataType::Float64, Some("degrees")),
+        column("position_long", DataType::Float64, Some("degrees")),
+        column("altitude", DataType::Float64, units(2)),
+        column("heart_rate", DataType::UInt8, units(3)),
+        column("cadence", DataType::UInt8, units(4)),
+        column("distance", DataType::Float64, units(5)),
+        column("speed", DataType::Float64, units(6)),
+        column("power", DataType::UInt16, units(7)),
+        column("temperature", DataType::Int8, units(13)),
+        column("time_from_course", DataType::Float64, units(11)),
+        column("calories", DataType::UInt16, units(33)),
+    ];
+    let degrees = |position: Option<i32>| position.map(semicircles_to_degrees);
+    let mut arrays: Vec<ArrayRef> = vec![
+        timestamps(points.iter().map(|p| p.timestamp)),
+        Arc::new(Float64Array::from_iter(
+            points.iter().map(|p| degrees(p.position_lat)),
+        )),
+        Arc::new(Float64Array::from_iter(
+            points.iter().map(|p| degrees(p.position_long)),
+        )),
+        Arc::new(Float64Array::from_iter(
+            points.iter().map(|p| p.enhanced_altitude.or(p.altitude)),
+        )),
+        Arc::new(UInt8Array::from_iter(points.iter().map(|p| p.heart_rate))),
+        Arc::new(UInt8Array::from_iter(points.iter().map(|p| p.cadence))),
+        Arc::new(Float64Array::from_iter(points.iter().map(|p| p.distance))),
+        Arc::new(Float64Array::from_iter(
+            points.iter().map(|p| p.enhanced_speed.or(p.speed)),
+        )),
+        Arc::new(UInt16Array::from_iter(points.iter().map(|p| p.power))),
+        Arc::new(Int8Array::from_iter(points.iter().map(|p| p.temperature))),
+        Arc::new(Float64Array::from_iter(
+            points.iter().map(|p| p.time_from_course),
+        )),
+        Arc::new(UInt16Array::from_iter(points.iter().map(|p| p.calories))),
+    ];
+    for (name, column) in developer {
+        let (field, array) = column.finish(&name, points.len());
+        fields.push(field);
+        arrays.push(array);
+    }
+    batch("record", fields, arrays)
+}
+
+fn batch(
+    message: &str,
+    fields: Vec<ArrowField>,
+    arrays: Vec<ArrayRef>,
+) -> RecordBatch {
+    let schema = Schema::new_with_metadata(
+        fields,
+        HashMap::from([("message".to_owned(), message.to_owned())]),
+    );
+    RecordBatch::try_new(Arc::new(schema), arrays)
+        .expect("every column to have a value per row")
+}
+
+/// A nullable column, with its units in the metadata like
+/// [`schema::arrow_schema`](../../schema/fn.arrow_schema.
+/// html) gives them.
+fn column(name: &str, data_type: DataType, units: Option<&str>) -> ArrowField {
+    let field = ArrowField::new(name, data_type, true);
+    match units {
+        Some(units) => {
+            field.with_metadata(HashMap::from([(
+                "units".to_owned(),
+                units.to_owned(),
+            )]))
+        },
+        None => field,
+    }
+}
+
+fn timestamp_field() -> ArrowField {
+    ArrowField::new(
+        "timestamp",
+        DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        false,
+    )
+}
+
+/// FIT timestamps as UTC milliseconds since the Unix
+/// epoch.
+fn timestamps<I: Iterator<Item = u32>>(times: I) -> ArrayRef {
+    let millis: Vec<i64> =
+        times.map(|time| (FIT_EPOCH + i64::from(time)) * 1000).collect();
+    Arc::new(TimestampMillisecondArray::from(millis).with_timezone("UTC"))
+}
+
+/// What a `FieldDescription` message says about the
+/// developer field it describes.
+struct Description {
+    name:      Option<String>,
+    units:     Option<String>,
+    base_type: u8,
+    scale:     f64,
+    offset:    f64,
+}
+
+/// The developer data index of a `DeveloperDataId`
+/// message, and its application's ID.
+fn application(mesg: &Data) -> Option<(u8, Vec<u8>)> {
+    let mut index = None;
+    let mut id = None;
+    for field in mesg.0.iter() {
+        match field {
+            Message::DeveloperDataId(DeveloperDataId::DeveloperDataIndex(
+                f,
+            )) if f.is_valid() => index = Some(f.raw_value.0),
+            Message::DeveloperDataId(DeveloperDataId::ApplicationId(f))
+                if f.is_valid() =>
+            {
+                id = Some(f.raw_value.0.clone())
+            },
+            _ => (),
+        }
+    }
+    Some((index?, id?))
+}
+
+/// The developer data index and field definition number a
+/// `FieldDescription` message describes, and what it says.
+fn describe(mesg: &Data) -> Option<((u8, u8), Description)> {
+    let mut index = None;
+    let mut num = None;
+    let mut description = Description {
+        name:      None,
+        units:     None,
+        base_type: 0xFF,
+        scale:     1.0,
+        offset:    0.0,
+    };
+    let first = |strings: &[::profile::base::Utf8String]| {
+        strings.first().map(|s| s.0.clone()).filter(|s| !s.is_empty())
+    };
+    for field in mesg.0.iter() {
+        match field {
+            Message::FieldDescription(
+                FieldDescription::DeveloperDataIndex(f),
+            ) if f.is_valid() => index = Some(f.raw_value.0),
+            Message::FieldDescription(
+                FieldDescription::FieldDefinitionNumber(f),
+            ) if f.is_valid() => num = Some(f.raw_value.0),
+            Message::FieldDescription(FieldDescription::FitBaseTypeId(f)) => {
+                description.base_type = f.raw_value.clone() as u8
+            },
+            Message::FieldDescription(FieldDescription::FieldName(f)) => {
+                description.name = first(&f.raw_value)
+            },
+            Message::FieldDescription(FieldDescription::Units(f)) => {
+                description.units = first(&f.raw_value)
+            },
+            Message::FieldDescription(FieldDescription::Scale(f))
+                if f.is_valid() =>
+            {
+                description.scale = f64::from(f.raw_value.0)
+            },
+            Message::FieldDescription(FieldDescription::Offset(f))
+                if f.is_valid() =>
+            {
+                description.offset = f64::from(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+    Some(((index?, num?), description))
+}
+
+/// The values of one developer field, a row at a time.
+struct DeveloperColumn {
+    field:  DeveloperField,
+    units:  Option<String>,
+    values: Values,
+}
+
+enum Values {
+    Numbers(Vec<Option<f64>>),
+    Texts(Vec<Option<String>>),
+}
+
+impl DeveloperColumn {
+    /// An empty column for the field with `key`.
+    fn new((index, num): (u8, u8), description: &Description) -> Self {
+        let values = if description.base_type == STRING {
+            Values::Texts(Vec::new())
+        }
+        else {
+            Values::Numbers(Vec::new())
+        };
+        DeveloperColumn {
+            field: DeveloperField {
+                developer_data_index:    index,
+                field_definition_number: num,
+                name:                    description.name.clone(),
+                application_id:          None,
+            },
+            units: description.units.clone(),
+            values,
+        }
+    }
+
+    /// Set the value in `row` from `field`, leaving the
+    /// rows since the last one set null.
+    fn set(
+        &mut self,
+        row: usize,
+        description: &Description,
+        field: &BorrowedField,
+    ) {
+        match &mut self.values {
+            Values::Numbers(values) => {
+                values.resize(row, None);
+                values.push(field.number().map(|raw| {
+                    scale_value(
+                        raw,
+                        Some(description.scale),
+                        Some(description.offset),
+                    )
+                }));
+            },
+            Values::Texts(values) => {
+                values.resize(row, None);
+                values.push(field.as_str().map(str::to_owned));
+            },
+        }
+    }
+
+    fn finish(self, name: &str, rows: usize) -> (ArrowField, ArrayRef) {
+        let (data_type, array): (_, ArrayRef) = match self.values {
+            Values::Numbers(mut values) => {
+                values.resize(rows, None);
+                (DataType::Float64, Arc::new(Float64Array::from(values)))
+            },
+            Values::Texts(mut values) => {
+                values.resize(rows, None);
+                (DataType::Utf8, Arc::new(StringArray::from(values)))
+            },
+        };
+        (column(name, data_type, self.units.as_deref()), array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{
+        cast::AsArray,
+        types::{
+            Float64Type,
+            TimestampMillisecondType,
+            UInt8Type,
+        },
+        Array,
+    };
+    use export::Collision;
+    use reader::from_bytes;
+    use testutil::{
+        mesg,
+        record_file,
+        FitBuilder,
+    };
+
+    #[test]
+    fn one_row_per_record() {
+        let bytes = record_file(3);
+        let batch = records_to_batch(&from_bytes(&bytes).unwrap());
+        let names: Vec<_> =
+            batch.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, RECORD_COLUMNS);
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().metadata()["message"], "record");
+
+        let timestamps =
+            batch["timestamp"].as_primitive::<TimestampMillisecondType>();
+        assert_eq!(timestamps.value(0), (FIT_EPOCH + 1_000_000) * 1000);
+        assert_eq!(timestamps.timezone(), Some("UTC"));
+        let lat = batch["position_lat"].as_primitive::<Float64Type>();
+        assert_eq!(lat.value(0), semicircles_to_degrees(600_000_000));
+        let hr = batch["heart_rate"].as_primitive::<UInt8Type>();
+        assert_eq!(hr.values().to_vec(), vec![100, 101, 102]);
+        let distance = batch["distance"].as_primitive::<Float64Type>();
+        assert_eq!(distance.value(2), 10.0);
+        assert_eq!(batch["cadence"].null_count(), 3);
+        let units =
+            batch.schema().field_with_name("heart_rate").unwrap().metadata()
+                ["units"]
+                .clone();
+        assert_eq!(units, "bpm");
+
+        // The same without developer fields
+        assert_eq!(file_records_to_batch(&bytes).unwrap(), batch);
+    }
+
+    /// Two apps, the second of which also describes a
+    /// `humidity`: the first's in tenths of a percent,
+    /// then a string and a `power` that clashes with the
+    /// record's. Three records follow: with every field,
+    /// with invalid ones, and without developer fields.
+    fn developer_file() -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 207, &[(1, 16, 0x0D), (3, 1, 0x02)]);
+        fit.data(0, &[&[0x0a; 16], &[0]]);
+        fit.data(0, &[&[0x1b; 16], &[1]]);
+        fit.definition(
+            0,
+            206,
+            &[
+                (0, 1, 0x02),
+                (1, 1, 0x02),
+                (2, 1, 0x02),
+                (3, 8, 0x07),
+                (6, 1, 0x02),
+                (8, 4, 0x07),
+            ],
+        );
+        fit.data(0, &[&[0], &[0], &[0x84], b"humidity", &[10], b"%\0\0\0"]);
+        fit.data(0, &[&[0], &[1], &[0x07], b"surface\0", &[0xFF], &[0; 4]]);
+        fit.data(0, &[&[0], &[2], &[0x02], b"power\0\0\0", &[0xFF], &[0; 4]]);
+        fit.data(0, &[&[1], &[0], &[0x02], b"humidity", &[0xFF], b"%\0\0\0"]);
+
+        // A record definition with developer fields
+        fit.raw(&[0x61, 0, 0, 20, 0, 2, 253, 4, 0x86, 3, 1, 0x02]);
+        fit.raw(&[4, 0, 2, 0, 1, 8, 0, 2, 1, 0, 0, 1, 1]);
+        fit.data(
+            1,
+            &[
+                &1000u32.to_le_bytes(),
+                &[120],
+                &455u16.to_le_bytes(),
+                b"gravel\0\0",
+                &[7],
+                &[50],
+            ],
+        );
+        fit.data(
+            1,
+            &[
+                &1001u32.to_le_bytes(),
+                &[121],
+                &[0xFF, 0xFF],
+                &[0; 8],
+                &[0xFF],
+                &[0xFF],
+            ],
+        );
+        fit.definition(2, 20, &[(253, 4, 0x86)]);
+        fit.data(2, &[&1002u32.to_le_bytes()]);
+        fit.build()
+    }
+
+    #[test]
+    fn developer_columns() {
+        let batch = file_records_to_batch(&developer_file()).unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        let schema = batch.schema();
+        let names: Vec<_> =
+            schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            &names[RECORD_COLUMNS.len()..],
+            ["humidity_0", "surface", "power_0", "humidity_1"]
+        );
+
+        let values = |name: &str| {
+            batch[name].as_primitive::<Float64Type>().iter().collect::<Vec<_>>()
+        };
+        assert_eq!(values("humidity_0"), [Some(45.5), None, None]);
+        assert_eq!(values("humidity_1"), [Some(50.0), None, None]);
+        assert_eq!(values("power_0"), [Some(7.0), None, None]);
+        assert_eq!(
+            schema.field_with_name("humidity_0").unwrap().metadata()["units"],
+            "%"
+        );
+        let surface = batch["surface"].as_string::<i32>();
+        assert_eq!(
+            surface.iter().collect::<Vec<_>>(),
+            vec![Some("gravel"), None, None]
+        );
+        let hr = batch["heart_rate"].as_primitive::<UInt8Type>();
+        assert_eq!(
+            hr.iter().collect::<Vec<_>>(),
+            vec![Some(120), Some(121), None]
+        );
+
+        let naming = DeveloperNaming {
+            on_collision: Collision::ApplicationId,
+            renames:      HashMap::from([((0, 1), "road".to_owned())]),
+        };
+        let batch =
+            file_records_to_batch_with(&developer_file(), &naming).unwrap();
+        let schema = batch.schema();
+        let names: Vec<_> =
+            schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            &names[RECORD_COLUMNS.len()..],
+            [
+                "0a0a0a0a_humidity",
+                "road",
+                "0a0a0a0a_power",
+                "1b1b1b1b_humidity"
+            ]
+        );
+        let humidity = batch["1b1b1b1b_humidity"].as_primitive::<Float64Type>();
+        assert_eq!(humidity.value(0), 50.0);
+    }
+
+    #[test]
+    fn zero_developer_scale() {
+        let mut fit = FitBuilder::new();
+        fit.definition(
+            0,
+            206,
+            &[
+                (0, 1, 0x02),
+                (1, 1, 0x02),
+                (2, 1, 0x02),
+                (3, 8, 0x07),
+                (6, 1, 0x02),
+            ],
+        );
+        fit.data(0, &[&[0], &[0], &[0x02], b"torque\0\0", &[0]]);
+        fit.raw(&[0x61, 0, 0, 20, 0, 1, 253, 4, 0x86, 1, 0, 1, 0]);
+        fit.data(1, &[&1000u32.to_le_bytes(), &[7]]);
+
+        let batch = file_records_to_batch(&fit.build()).unwrap();
+        let torque = batch["torque"].as_primitive::<Float64Type>();
+        assert_eq!(torque.iter().collect::<Vec<_>>(), [Some(7.0)]);
+    }
+
+    #[test]
+    fn monitoring_samples() {
+        let start = 1_000_000_000;
+        let messages = vec![
+            mesg(0).u8(0, 15).build(),
+            mesg(55).u32(253, start).u8(5, 6).u32(3, 200).u8(27, 60).build(),
+            mesg(55).u16(26, (start + 60) as u16).u8(27, 62).build(),
+            mesg(55).u16(26, (start + 65_060) as u16).u16(1, 80).build(),
+        ];
+        let batch = monitoring_to_batch(&messages);
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().metadata()["message"], "monitoring");
+        let timestamps =
+            batch["timestamp"].as_primitive::<TimestampMillisecondType>();
+        assert_eq!(
+            timestamps.values().to_vec(),
+            [0, 60, 65_060]
+                .iter()
+                .map(|s| (FIT_EPOCH + i64::from(start + s)) * 1000)
+                .collect::<Vec<_>>()
+        );
+        let activity = batch["activity_type"].as_string::<i32>();
+        assert_eq!(
+            activity.iter().collect::<Vec<_>>(),
+            vec![Some("Walking"), None, None]
+        );
+        let cycles = batch["cycles"].as_primitive::<Float64Type>();
+        assert_eq!(
+            cycles.iter().collect::<Vec<_>>(),
+            vec![Some(100.0), None, None]
+        );
+        let hr = batch["heart_rate"].as_primitive::<UInt8Type>();
+        assert_eq!(
+            hr.iter().collect::<Vec<_>>(),
+            vec![Some(60), Some(62), None]
+        );
+        assert_eq!(batch["calories"].null_count(), 2);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let batch = file_records_to_batch(&developer_file()).unwrap();
+        let path = ::std::env::temp_dir()
+            .join(format!("garminfit-export-{}.parquet", ::std::process::id()));
+        write_parquet(&batch, ::std::fs::File::create(&path).unwrap()).unwrap();
+        let read: Vec<RecordBatch> = ParquetRecordBatchReaderBuilder::try_new(
+            ::std::fs::File::open(&path).unwrap(),
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+        .collect::<::std::result::Result<_, _>>()
+        .unwrap();
+        ::std::fs::remove_file(&path).unwrap();
+        // Parquet keeps field metadata, but not the schema's
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].schema().fields(), batch.schema().fields());
+        assert_eq!(read[0].columns(), batch.columns());
+    }
+}
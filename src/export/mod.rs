@@ -0,0 +1,223 @@
+//! Columnar exports of decoded files, for dataframe and
+//! analytics tools.
+//!
+//! [`arrow`] (with the `arrow` feature) lays an activity's
+//! records, or a monitoring file's samples, out as an Arrow
+//! record batch with one typed column per quantity, and
+//! with the `parquet` feature writes it as Parquet.
+//!
+//! # Developer field columns
+//!
+//! Developer fields are named by whichever Connect IQ app
+//! wrote them, so two apps can both call theirs `Power`,
+//! as can the profile. Every export that gives developer
+//! fields columns of their own names them the same way,
+//! with [`DeveloperNaming`]:
+//!
+//! - A field the [`renames`] map names gets that name, as
+//!   given.
+//! - A field whose name no other column shares, ignoring
+//!   case since many query engines do, keeps it.
+//! - Otherwise it becomes `<name>_<developer_data_index>`,
+//!   or `<application>_<name>` with the first eight hex
+//!   digits of the app's `application_id` if
+//!   [`on_collision`] asks for it and the file has one.
+//!   Either way the profile's own columns keep their names.
+//! - If that still clashes, as two fields of one app
+//!   sharing a name do, it becomes
+//!   `<name>_<developer_data_index>_<field_definition_number>`.
+//! - A field without a name is
+//!   `developer_<developer_data_index>_<field_definition_number>`.
+//!
+//! [`arrow`]: arrow/index.html
+//! [`DeveloperNaming`]: struct.DeveloperNaming.html
+//! [`renames`]: struct.DeveloperNaming.html#structfield.renames
+//! [`on_collision`]: struct.DeveloperNaming.html#structfield.on_collision
+use std::collections::HashMap;
+
+#[cfg(feature = "arrow")]
+pub mod arrow;
+
+/// How to tell apart developer fields whose names clash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    /// Append the developer data index, as in `power_1`.
+    DeveloperDataIndex,
+    /// Prefix the start of the app's `application_id`, as
+    /// in `0a1b2c3d_power`, which stays the same from file
+    /// to file where the index may not.
+    ApplicationId,
+}
+
+/// How exports name developer field columns, as the
+/// [module documentation](index.html#
+/// developer-field-columns) describes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeveloperNaming {
+    pub on_collision: Collision,
+    /// Column names by developer data index and field
+    /// definition number, used as they are.
+    pub renames:      HashMap<(u8, u8), String>,
+}
+
+impl Default for DeveloperNaming {
+    fn default() -> Self {
+        DeveloperNaming {
+            on_collision: Collision::DeveloperDataIndex,
+            renames:      HashMap::new(),
+        }
+    }
+}
+
+/// What naming a developer field's column goes on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeveloperField {
+    pub developer_data_index:    u8,
+    pub field_definition_number: u8,
+    /// From its `FieldDescription`.
+    pub name:                    Option<String>,
+    /// From the `DeveloperDataId` with its developer data
+    /// index.
+    pub application_id:          Option<Vec<u8>>,
+}
+
+impl DeveloperNaming {
+    /// Column names for `fields`, in order, that differ
+    /// from each other and from the export's own `columns`.
+    pub fn column_names(
+        &self,
+        columns: &[&str],
+        fields: &[DeveloperField],
+    ) -> Vec<String> {
+        let renamed = |field: &DeveloperField| {
+            self.renames.get(&(
+                field.developer_data_index,
+                field.field_definition_number,
+            ))
+        };
+        let count = |names: &[String], name: &str| {
+            let name = name.to_lowercase();
+            names.iter().filter(|other| other.to_lowercase() == name).count()
+        };
+        // Names no other field can take
+        let mut fixed: Vec<String> =
+            columns.iter().map(|name| name.to_string()).collect();
+        fixed.extend(self.renames.values().cloned());
+        let given: Vec<String> = fields
+            .iter()
+            .filter(|field| renamed(field).is_none())
+            .filter_map(|field| field.name.clone())
+            .collect();
+
+        // With whether the name was made up to disambiguate
+        let mut names: Vec<(String, bool)> = fields
+            .iter()
+            .map(|field| {
+                if let Some(rename) = renamed(field) {
+                    return (rename.clone(), false);
+                }
+                let name = match &field.name {
+                    Some(name) => name,
+                    None => return (full_name(field, "developer"), false),
+                };
+                if count(&given, name) == 1 && count(&fixed, name) == 0 {
+                    return (name.clone(), false);
+                }
+                let name = match (self.on_collision, &field.application_id) {
+                    (Collision::ApplicationId, Some(id)) if id.len() >= 4 => {
+                        let app: String = id[..4]
+                            .iter()
+                            .map(|b| format!("{:02x}", b))
+                            .collect();
+                        format!("{}_{}", app, name)
+                    },
+                    _ => format!("{}_{}", name, field.developer_data_index),
+                };
+                (name, true)
+            })
+            .collect();
+
+        // Made up names can clash too
+        let taken: Vec<String> =
+            names.iter().map(|(name, _)| name.clone()).collect();
+        for ((name, made_up), field) in names.iter_mut().zip(fields) {
+            if *made_up && (count(&taken, name) > 1 || count(&fixed, name) > 0)
+            {
+                let given =
+                    field.name.as_ref().map_or("developer", |n| n.as_str());
+                *name = full_name(field, given);
+            }
+        }
+        names.into_iter().map(|(name, _)| name).collect()
+    }
+}
+
+/// `name` followed by the field's developer data index and
+/// field definition number, which no other field shares.
+fn full_name(field: &DeveloperField, name: &str) -> String {
+    format!(
+        "{}_{}_{}",
+        name, field.developer_data_index, field.field_definition_number
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(index: u8, num: u8, name: &str, app: u8) -> DeveloperField {
+        DeveloperField {
+            developer_data_index:    index,
+            field_definition_number: num,
+            name:                    Some(name.to_owned()),
+            application_id:          Some(vec![app; 16]),
+        }
+    }
+
+    #[test]
+    fn clashing_names() {
+        let fields = vec![
+            field(0, 0, "Power", 0x0a),
+            field(1, 0, "Power", 0x1b),
+            field(1, 1, "Humidity", 0x1b),
+            field(1, 2, "Cadence", 0x1b),
+            field(1, 3, "Cadence", 0x1b),
+            field(1, 4, "power_0", 0x1b),
+            DeveloperField {
+                name: None,
+                ..field(2, 7, "", 0x2c)
+            },
+        ];
+        let columns = ["timestamp", "cadence"];
+        let naming = DeveloperNaming::default();
+        assert_eq!(
+            naming.column_names(&columns, &fields),
+            [
+                "Power_0_0",
+                "Power_1",
+                "Humidity",
+                "Cadence_1_2",
+                "Cadence_1_3",
+                "power_0",
+                "developer_2_7",
+            ]
+        );
+
+        let naming = DeveloperNaming {
+            on_collision: Collision::ApplicationId,
+            renames:      HashMap::from([((1, 1), "cadence".to_owned())]),
+        };
+        assert_eq!(
+            naming.column_names(&columns[..1], &fields),
+            [
+                "0a0a0a0a_Power",
+                "1b1b1b1b_Power",
+                "cadence",
+                "Cadence_1_2",
+                "Cadence_1_3",
+                "power_0",
+                "developer_2_7",
+            ]
+        );
+    }
+}
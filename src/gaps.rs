@@ -0,0 +1,431 @@
+//! Finding where an activity stood still with the timer
+//! running, and pausing it after the fact.
+//!
+//! With auto-pause off, every stop at a junction or a café
+//! counts towards the moving time. [`detect`] finds those
+//! stops in the records, and [`insert_pauses`] adds the
+//! timer events the device would have recorded, taking the
+//! time out of the laps and sessions they fall in.
+//!
+//! [`detect`]: fn.detect.html
+//! [`insert_pauses`]: fn.insert_pauses.html
+use byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+use events;
+use profile::{
+    messages::Message,
+    types::{
+        MesgNum,
+        Sport,
+    },
+};
+use series::{
+    RecordPoint,
+    RecordTimeSeries,
+};
+use time::message_timestamp;
+use types::{
+    field::Value,
+    record::Data,
+};
+
+/// The `event_group` of the timer events [`insert_pauses`]
+/// adds, telling them apart from those the device
+/// recorded. Devices leave the group at 0 or invalid.
+///
+/// [`insert_pauses`]: fn.insert_pauses.html
+pub const SYNTHETIC_EVENT_GROUP: u8 = 0xA5;
+
+/// What counts as standing still.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Options {
+    /// Metres per second below which a record is
+    /// stationary.
+    pub max_speed_ms:   f64,
+    /// Seconds a stop has to last to be reported.
+    pub min_duration_s: u32,
+    /// Metres a stationary position may wander, so GPS
+    /// jitter reading as speed doesn't end a stop.
+    pub jitter_m:       f64,
+}
+
+impl Options {
+    /// Thresholds suited to `sport`: walkers move slowly
+    /// and stop for longer, cyclists roll slowly at lights
+    /// without stopping.
+    pub fn for_sport(sport: &Sport) -> Options {
+        let (max_speed_ms, min_duration_s, jitter_m) = match sport {
+            Sport::Walking | Sport::Hiking | Sport::Mountaineering => {
+                (0.2, 60, 10.0)
+            },
+            Sport::Running => (0.5, 30, 15.0),
+            Sport::Cycling | Sport::EBiking => (1.0, 30, 20.0),
+            _ => (0.3, 60, 15.0),
+        };
+        Options {
+            max_speed_ms,
+            min_duration_s,
+            jitter_m,
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::for_sport(&Sport::Generic)
+    }
+}
+
+/// A stretch of standing still, from the first stationary
+/// record to the last, in seconds since the FIT epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StationaryPeriod {
+    pub start: u32,
+    pub end:   u32,
+}
+
+impl StationaryPeriod {
+    pub fn duration_s(&self) -> u32 {
+        self.end - self.start
+    }
+}
+
+/// The periods `records` stood still for at least
+/// `opts.min_duration_s`, in time order.
+///
+/// A stop starts at a record slower than
+/// `opts.max_speed_ms` and lasts while the records stay
+/// within `opts.jitter_m` of where it started, whatever
+/// speed they read. Without positions, it lasts while the
+/// records stay slow.
+pub fn detect(
+    records: &RecordTimeSeries,
+    opts: &Options,
+) -> Vec<StationaryPeriod> {
+    let points = &records.points;
+    let mut periods = Vec::new();
+    let mut i = 0;
+    while i < points.len() {
+        if !speed(&points[i]).is_some_and(|s| s < opts.max_speed_ms) {
+            i += 1;
+            continue;
+        }
+        let anchor = points[i].position();
+        let mut last = i;
+        while let Some(point) = points.get(last + 1) {
+            let still = match (anchor, point.position()) {
+                (Some(anchor), Some(position)) => {
+                    anchor.haversine_m(&position) <= opts.jitter_m
+                },
+                _ => speed(point).is_some_and(|s| s < opts.max_speed_ms),
+            };
+            if !still {
+                break;
+            }
+            last += 1;
+        }
+        // Riding off takes a few records to leave the
+        // jitter radius
+        while !speed(&points[last]).is_none_or(|s| s < opts.max_speed_ms) {
+            last -= 1;
+        }
+
+        let (start, end) = (points[i].timestamp, points[last].timestamp);
+        if end.saturating_sub(start) >= opts.min_duration_s {
+            periods.push(StationaryPeriod {
+                start,
+                end,
+            });
+        }
+        i = last + 1;
+    }
+    periods
+}
+
+/// Pause the timer in `messages` over each of `periods`.
+///
+/// Each period gets a timer stop after the messages at its
+/// start and a timer start before those at its end, both
+/// in [`SYNTHETIC_EVENT_GROUP`]. Periods overlapping a
+/// pause already in the file are skipped, so the timer
+/// events stay balanced. The `total_timer_time` of every
+/// lap and session is reduced by the time paused within
+/// it, and added from its elapsed time if it had none.
+///
+/// [`SYNTHETIC_EVENT_GROUP`]: constant.SYNTHETIC_EVENT_GROUP.html
+pub fn insert_pauses(
+    messages: &[Data],
+    periods: &[StationaryPeriod],
+) -> Vec<Data> {
+    let existing = events::pause_intervals(messages);
+    let pauses: Vec<&StationaryPeriod> = periods
+        .iter()
+        .filter(|period| period.end > period.start)
+        .filter(|period| {
+            existing
+                .iter()
+                .all(|&(from, to)| to < period.start || period.end < from)
+        })
+        .collect();
+
+    let mut out = Vec::with_capacity(messages.len() + 2 * pauses.len());
+    let mut pending = pauses.iter().peekable();
+    let mut stopped: Option<&StationaryPeriod> = None;
+    for mesg in messages {
+        if let Some(time) = message_timestamp(mesg) {
+            // Restart before the first message at or past
+            // the end, and stop before the first past the
+            // start
+            loop {
+                if let Some(period) = stopped {
+                    if time >= period.end {
+                        out.push(timer_event(period.end, TIMER_START));
+                        stopped = None;
+                        continue;
+                    }
+                }
+                else if let Some(period) = pending.peek() {
+                    if time > period.start {
+                        out.push(timer_event(period.start, TIMER_STOP));
+                        stopped = pending.next().cloned();
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+        out.push(adjust_timer(mesg, &pauses));
+    }
+    if let Some(period) = stopped {
+        out.push(timer_event(period.end, TIMER_START));
+    }
+    for period in pending {
+        out.push(timer_event(period.start, TIMER_STOP));
+        out.push(timer_event(period.end, TIMER_START));
+    }
+    out
+}
+
+const TIMER_START: u8 = 0;
+const TIMER_STOP: u8 = 1;
+
+fn speed(point: &RecordPoint) -> Option<f64> {
+    point.enhanced_speed.or(point.speed)
+}
+
+/// A timer `Event` of `event_type` in the synthetic group.
+fn timer_event(timestamp: u32, event_type: u8) -> Data {
+    let event = MesgNum::Event.value();
+    let fields: [(u8, &[u8]); 4] = [
+        (253, &timestamp.to_le_bytes()),
+        (0, &[0]), // timer
+        (1, &[event_type]),
+        (4, &[SYNTHETIC_EVENT_GROUP]),
+    ];
+    Data(
+        fields
+            .iter()
+            .filter_map(|&(num, bytes)| {
+                Message::decode::<LittleEndian>(bytes, event, num).ok()
+            })
+            .collect(),
+    )
+}
+
+/// `mesg` with its `total_timer_time` less the time paused
+/// between its start and end, if it's a lap or session.
+fn adjust_timer(mesg: &Data, pauses: &[&StationaryPeriod]) -> Data {
+    let mesg_num = match mesg.0.first() {
+        Some(field) => field.mesg_num(),
+        None => return mesg.clone(),
+    };
+    if mesg_num != MesgNum::Lap.value() && mesg_num != MesgNum::Session.value()
+    {
+        return mesg.clone();
+    }
+    // Both number these fields alike
+    let (start, end) = match (time_field(mesg, 2), message_timestamp(mesg)) {
+        (Some(start), Some(end)) => (start, end),
+        _ => return mesg.clone(),
+    };
+    let paused: u32 = pauses
+        .iter()
+        .map(|p| end.min(p.end).saturating_sub(start.max(p.start)))
+        .sum();
+    if paused == 0 {
+        return mesg.clone();
+    }
+    let timer = match number_field(mesg, 8).or_else(|| number_field(mesg, 7)) {
+        Some(timer) => timer,
+        None => return mesg.clone(),
+    };
+
+    let millis = ((timer - f64::from(paused)).max(0.0) * 1000.0).round() as u32;
+    let mut bytes = [0; 4];
+    LittleEndian::write_u32(&mut bytes, millis);
+    let field = match Message::decode::<LittleEndian>(&bytes, mesg_num, 8) {
+        Ok(field) => field,
+        Err(_) => return mesg.clone(),
+    };
+    let mut fields: Vec<Message> =
+        mesg.0.iter().filter(|f| f.field_def_num() != 8).cloned().collect();
+    fields.push(field);
+    Data(fields)
+}
+
+fn time_field(mesg: &Data, num: u8) -> Option<u32> {
+    mesg.0.iter().find_map(|field| {
+        match (field.field_def_num(), field.value()) {
+            (n, Value::DateTime(time)) if n == num => Some(time.0),
+            _ => None,
+        }
+    })
+}
+
+fn number_field(mesg: &Data, num: u8) -> Option<f64> {
+    mesg.0.iter().find_map(|field| {
+        match (field.field_def_num(), field.value()) {
+            (n, Value::Number(value)) if n == num => Some(value),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::Position;
+    use report::{
+        self,
+        Severity,
+    };
+    use testutil::mesg;
+
+    const T0: u32 = 1_000_000_000;
+
+    /// 15 minutes riding east at 5 m/s, with 5 minutes at
+    /// lights from minute 5 whose position wanders 8 m and
+    /// whose speed reads 1.5 m/s now and again.
+    fn commute() -> Vec<Data> {
+        let mut messages = vec![
+            mesg(0).u8(0, 4).u16(1, 1).u32(4, T0).build(),
+            mesg(21).u32(253, T0).u8(0, 0).u8(1, 0).build(),
+        ];
+        let mut x = 0.0;
+        for t in 0..900 {
+            let stopped = (300..600).contains(&t);
+            let speed = match (stopped, t % 37 == 0) {
+                (false, _) => 5.0,
+                (true, true) => 1.5,
+                (true, false) => 0.0,
+            };
+            if !stopped {
+                x += 5.0;
+            }
+            let jitter = if stopped && t % 2 == 0 { 8.0 } else { 0.0 };
+            messages.push(record(T0 + t, x + jitter, speed));
+        }
+        messages.extend(vec![
+            mesg(21).u32(253, T0 + 900).u8(0, 0).u8(1, 4).build(),
+            summary(19, 900),
+            summary(18, 900),
+            mesg(34)
+                .u32(253, T0 + 900)
+                .u32(0, 900_000)
+                .u16(1, 1)
+                .u8(2, 0)
+                .u8(3, 26)
+                .u8(4, 1)
+                .build(),
+        ]);
+        messages
+    }
+
+    /// At `x` metres east of 0°N 0°E.
+    fn record(time: u32, x: f64, speed: f64) -> Data {
+        let position = Position::from_degrees(0.0, x / 111_319.5);
+        mesg(20)
+            .u32(253, time)
+            .i32(0, position.lat_semicircles)
+            .i32(1, position.lon_semicircles)
+            .u16(6, (speed * 1000.0) as u16)
+            .build()
+    }
+
+    fn summary(mesg_num: u16, seconds: u32) -> Data {
+        mesg(mesg_num)
+            .u32(253, T0 + seconds)
+            .u32(2, T0)
+            .u32(7, seconds * 1000)
+            .u32(8, seconds * 1000)
+            .u8(0, 9) // lap or session event
+            .u8(1, 1)
+            .build()
+    }
+
+    fn timer_s(messages: &[Data], mesg_num: u16) -> Option<f64> {
+        messages
+            .iter()
+            .filter(|m| m.0.first().map(|f| f.mesg_num()) == Some(mesg_num))
+            .find_map(|m| number_field(m, 8))
+    }
+
+    #[test]
+    fn finds_stops_through_jitter() {
+        let records = RecordTimeSeries::from_messages(&commute());
+        let cycling = Options::for_sport(&Sport::Cycling);
+        assert_eq!(
+            detect(&records, &cycling),
+            vec![StationaryPeriod {
+                start: T0 + 300, end: T0 + 599
+            }]
+        );
+
+        // Too short when stops have to last ten minutes
+        let walking = Options {
+            min_duration_s: 600,
+            ..Options::for_sport(&Sport::Walking)
+        };
+        assert!(detect(&records, &walking).is_empty());
+    }
+
+    #[test]
+    fn pauses_keep_the_file_valid() {
+        let messages = commute();
+        let records = RecordTimeSeries::from_messages(&messages);
+        let periods = detect(&records, &Options::for_sport(&Sport::Cycling));
+        let paused = insert_pauses(&messages, &periods);
+
+        assert_eq!(paused.len(), messages.len() + 2);
+        assert_eq!(
+            events::pause_intervals(&paused),
+            vec![(T0 + 300, T0 + 599)]
+        );
+        let synthetic: Vec<&Data> = paused
+            .iter()
+            .filter(|m| {
+                m.0.iter().any(|f| {
+                    f.field_def_num() == 4
+                        && matches!(f.value(), Value::Number(n) if n == f64::from(SYNTHETIC_EVENT_GROUP))
+                })
+            })
+            .collect();
+        assert_eq!(synthetic.len(), 2);
+
+        assert_eq!(timer_s(&paused, 19), Some(601.0));
+        assert_eq!(timer_s(&paused, 18), Some(601.0));
+        assert_eq!(number_field(&paused[paused.len() - 3], 7), Some(900.0));
+
+        let before = report::completeness(&messages);
+        let after = report::completeness(&paused);
+        assert_eq!(after.findings, before.findings);
+        assert!(after.worst().is_none_or(|worst| worst < Severity::Warning));
+
+        // Pausing again changes nothing
+        let again = insert_pauses(&paused, &periods);
+        assert_eq!(again.len(), paused.len());
+        assert_eq!(timer_s(&again, 18), Some(601.0));
+    }
+}
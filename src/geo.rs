@@ -0,0 +1,556 @@
+//! Positions and distances on the earth's surface.
+//!
+//! FIT stores positions as a [`Position`] in semicircles,
+//! where 2^31 semicircles make 180 degrees. Every helper
+//! converts through it, so they all round the same way; a
+//! [`GeoPoint`] is the same place in decimal degrees.
+//!
+//! [`Bounds`] boxes in a track, for maps and for checking
+//! the corners sessions and laps give.
+//!
+//! [`Position`]: struct.Position.html
+//! [`GeoPoint`]: struct.GeoPoint.html
+//! [`Bounds`]: struct.Bounds.html
+use series::RecordPoint;
+use std::{
+    f64::consts::PI,
+    fmt::Write,
+};
+
+/// Mean earth radius in metres, as used by the haversine
+/// formula.
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Degrees per semicircle, the unit FIT uses for positions.
+const DEGREES_PER_SEMICIRCLE: f64 = 180.0 / 2_147_483_648.0;
+
+/// Semicircles per degree.
+const SEMICIRCLES_PER_DEGREE: f64 = 2_147_483_648.0 / 180.0;
+
+/// Semicircles in a full turn.
+const FULL_TURN: i64 = 1 << 32;
+
+/// The latitude of a pole in semicircles.
+const POLE: i32 = 1 << 30;
+
+/// The latitude where Web Mercator tiles stop, which makes
+/// the map square.
+const MERCATOR_MAX_LAT: f64 = 85.051_128_78;
+
+/// A latitude/longitude pair in semicircles, as FIT stores
+/// positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Position {
+    pub lat_semicircles: i32,
+    pub lon_semicircles: i32,
+}
+
+impl Position {
+    pub fn new(lat_semicircles: i32, lon_semicircles: i32) -> Self {
+        Position {
+            lat_semicircles,
+            lon_semicircles,
+        }
+    }
+
+    /// The position nearest `lat` degrees north, `lon`
+    /// degrees east, for writing to a file.
+    ///
+    /// Angles are rounded to the nearest semicircle. 180
+    /// degrees east is out of range and comes back as 180
+    /// degrees west, the same meridian; other longitudes
+    /// outside ±180 wrap around the same way.
+    ///
+    /// ```
+    /// use garminfit::geo::Position;
+    ///
+    /// let position = Position::from_degrees(51.5074, -0.1278);
+    /// let point = position.to_degrees();
+    /// assert!((point.lat - 51.5074).abs() < 1e-7);
+    /// assert_eq!(Position::from_degrees(point.lat, point.lon), position);
+    /// ```
+    pub fn from_degrees(lat: f64, lon: f64) -> Self {
+        Position {
+            lat_semicircles: degrees_to_semicircles(lat),
+            lon_semicircles: degrees_to_semicircles(lon),
+        }
+    }
+
+    /// The position in decimal degrees.
+    pub fn to_degrees(&self) -> GeoPoint {
+        GeoPoint {
+            lat: semicircles_to_degrees(self.lat_semicircles),
+            lon: semicircles_to_degrees(self.lon_semicircles),
+        }
+    }
+
+    /// Whether this is a position at all: neither
+    /// coordinate holds the invalid value of a FIT
+    /// `sint32`, and the latitude is within ±90
+    /// degrees.
+    pub fn is_valid(&self) -> bool {
+        self.lat_semicircles != i32::MAX
+            && self.lon_semicircles != i32::MAX
+            && self.lat_semicircles.unsigned_abs() <= 1 << 30
+    }
+
+    /// Great circle distance to `other` in metres.
+    pub fn distance_to(&self, other: &Position) -> f64 {
+        self.to_degrees().haversine_m(&other.to_degrees())
+    }
+
+    /// Initial bearing on the great circle to `other`, in
+    /// degrees clockwise from north.
+    pub fn bearing_to(&self, other: &Position) -> f64 {
+        self.to_degrees().bearing_deg(&other.to_degrees())
+    }
+}
+
+/// A box of latitudes and longitudes, given by its corners
+/// as a session or lap's `nec_*` and `swc_*` fields are.
+///
+/// Longitudes run east from the west edge to the east edge.
+/// A box that crosses the antimeridian, as an activity
+/// sailing past 180° does, has its west edge east of its
+/// east edge: it holds 179.5°E and 179.5°W but not 0°.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Bounds {
+    pub north_east: Position,
+    pub south_west: Position,
+}
+
+/// The smallest box holding the positions of `records`.
+///
+/// Records without a position, or with an invalid one, are
+/// skipped, and `None` is returned if that leaves none. The
+/// box crosses the antimeridian if that makes it narrower,
+/// so a track around 180° gets a box a few degrees wide
+/// rather than one spanning the globe.
+pub fn bounds(records: &[RecordPoint]) -> Option<Bounds> {
+    let positions: Vec<Position> = records
+        .iter()
+        .filter_map(RecordPoint::position_semicircles)
+        .filter(Position::is_valid)
+        .collect();
+    let north = positions.iter().map(|p| p.lat_semicircles).max()?;
+    let south = positions.iter().map(|p| p.lat_semicircles).min()?;
+
+    let mut lons: Vec<i32> =
+        positions.iter().map(|p| p.lon_semicircles).collect();
+    lons.sort_unstable();
+    lons.dedup();
+
+    // Leave out the widest gap between neighbouring
+    // longitudes, starting with the one across the
+    // antimeridian so a tie keeps the box from crossing it
+    let (first, last) = (lons[0], lons[lons.len() - 1]);
+    let (mut west, mut east) = (first, last);
+    let mut widest = i64::from(first) + FULL_TURN - i64::from(last);
+    for pair in lons.windows(2) {
+        let gap = i64::from(pair[1]) - i64::from(pair[0]);
+        if gap > widest {
+            widest = gap;
+            west = pair[1];
+            east = pair[0];
+        }
+    }
+
+    Some(Bounds {
+        north_east: Position::new(north, east),
+        south_west: Position::new(south, west),
+    })
+}
+
+impl Bounds {
+    /// Whether the box goes east past 180° and on from
+    /// 180°W.
+    pub fn crosses_antimeridian(&self) -> bool {
+        self.south_west.lon_semicircles > self.north_east.lon_semicircles
+    }
+
+    /// How far east the box reaches from its west edge, in
+    /// semicircles.
+    fn width(&self) -> u32 {
+        self.north_east
+            .lon_semicircles
+            .wrapping_sub(self.south_west.lon_semicircles) as u32
+    }
+
+    /// How far east of the west edge `lon_semicircles` is,
+    /// going round past 180° if need be.
+    fn east_of_west(&self, lon_semicircles: i32) -> u32 {
+        lon_semicircles.wrapping_sub(self.south_west.lon_semicircles) as u32
+    }
+
+    /// Whether `position` is inside the box or on its edge.
+    pub fn contains(&self, position: &Position) -> bool {
+        let lat = position.lat_semicircles;
+        self.south_west.lat_semicircles <= lat
+            && lat <= self.north_east.lat_semicircles
+            && self.east_of_west(position.lon_semicircles) <= self.width()
+    }
+
+    /// Whether all of `other` is inside the box.
+    pub fn covers(&self, other: &Bounds) -> bool {
+        self.south_west.lat_semicircles <= other.south_west.lat_semicircles
+            && other.north_east.lat_semicircles
+                <= self.north_east.lat_semicircles
+            && u64::from(self.east_of_west(other.south_west.lon_semicircles))
+                + u64::from(other.width())
+                <= u64::from(self.width())
+    }
+
+    /// The box grown by `meters` on every side, at least.
+    ///
+    /// Latitudes stop at the poles. Longitudes are widened
+    /// by enough for the edge nearest a pole, and a box
+    /// that would go all the way round spans every
+    /// longitude instead.
+    pub fn expand_by_meters(&self, meters: f64) -> Bounds {
+        let meters = meters.max(0.0);
+        let dlat =
+            degrees_to_semicircles((meters / EARTH_RADIUS_M).to_degrees());
+        let north =
+            self.north_east.lat_semicircles.saturating_add(dlat).min(POLE);
+        let south =
+            self.south_west.lat_semicircles.saturating_sub(dlat).max(-POLE);
+
+        let nearest_pole = north.unsigned_abs().max(south.unsigned_abs());
+        let cos =
+            semicircles_to_degrees(nearest_pole as i32).to_radians().cos();
+        let dlon = (meters / (EARTH_RADIUS_M * cos)).to_degrees()
+            * SEMICIRCLES_PER_DEGREE;
+        let width = f64::from(self.width()) + 2.0 * dlon;
+        let (west, east) = if cos > 0.0 && width < FULL_TURN as f64 {
+            let dlon = dlon.ceil() as i64;
+            (
+                (i64::from(self.south_west.lon_semicircles) - dlon) as i32,
+                (i64::from(self.north_east.lon_semicircles) + dlon) as i32,
+            )
+        }
+        else {
+            // Just short of 180°E, which is also 180°W
+            (i32::MIN, i32::MAX - 1)
+        };
+
+        Bounds {
+            north_east: Position::new(north, east),
+            south_west: Position::new(south, west),
+        }
+    }
+
+    /// The `(x, y)` Web Mercator tiles at `zoom` that the
+    /// box touches, as map servers number them, for
+    /// prefetching.
+    ///
+    /// Tiles run west to east, and north to south within
+    /// each column; a box crossing the antimeridian goes on
+    /// from the last column to the first. Latitudes beyond
+    /// the map's ±85.05° are taken as its edge, and zooms
+    /// past 32 as 32.
+    ///
+    /// ```
+    /// use garminfit::geo::{
+    ///     Bounds,
+    ///     Position,
+    /// };
+    ///
+    /// let london = Position::from_degrees(51.5074, -0.1278);
+    /// let bounds = Bounds {
+    ///     north_east: london,
+    ///     south_west: london,
+    /// };
+    /// let tiles: Vec<_> = bounds.tiles(10).collect();
+    /// assert_eq!(tiles, vec![(511, 340)]);
+    /// ```
+    pub fn tiles(&self, zoom: u8) -> impl Iterator<Item = (u32, u32)> {
+        let n = 1u64 << zoom.min(32);
+        let column = |lon: i32| ((i64::from(lon) + (1 << 31)) as u64 * n) >> 32;
+        let row = |lat: i32| {
+            let lat = semicircles_to_degrees(lat)
+                .clamp(-MERCATOR_MAX_LAT, MERCATOR_MAX_LAT)
+                .to_radians();
+            let y = (1.0 - lat.tan().asinh() / PI) / 2.0 * n as f64;
+            (y.max(0.0) as u64).min(n - 1)
+        };
+
+        let west = column(self.south_west.lon_semicircles);
+        let east = column(self.north_east.lon_semicircles);
+        let columns = if self.crosses_antimeridian() {
+            (n - west + east + 1).min(n)
+        }
+        else {
+            east - west + 1
+        };
+        let north = row(self.north_east.lat_semicircles);
+        let south = row(self.south_west.lat_semicircles);
+        (0..columns).flat_map(move |i| {
+            let x = ((west + i) % n) as u32;
+            (north..=south).map(move |y| (x, y as u32))
+        })
+    }
+}
+
+impl From<Position> for GeoPoint {
+    fn from(position: Position) -> GeoPoint {
+        position.to_degrees()
+    }
+}
+
+/// A latitude/longitude pair in decimal degrees.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GeoPoint {
+    /// Degrees north.
+    pub lat: f64,
+    /// Degrees east.
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    /// A point at `lat` degrees north, `lon` degrees east.
+    pub fn new(lat: f64, lon: f64) -> Self {
+        GeoPoint {
+            lat,
+            lon,
+        }
+    }
+
+    /// Convert a FIT position given in semicircles.
+    pub fn from_semicircles(lat: i32, lon: i32) -> Self {
+        Position::new(lat, lon).to_degrees()
+    }
+
+    /// Great circle distance to `other` in metres.
+    pub fn haversine_m(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+
+        let a = (dlat / 2.0).sin().powi(2)
+            + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+
+    /// Initial bearing on the great circle to `other`, in
+    /// degrees clockwise from north, from 0 up to 360.
+    pub fn bearing_deg(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlon = (other.lon - self.lon).to_radians();
+
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        let bearing = y.atan2(x).to_degrees();
+        if bearing < 0.0 {
+            bearing + 360.0
+        }
+        else {
+            bearing
+        }
+    }
+}
+
+/// Convert an angle in semicircles, as FIT stores latitudes
+/// and longitudes, into degrees.
+pub fn semicircles_to_degrees(semicircles: i32) -> f64 {
+    f64::from(semicircles) * DEGREES_PER_SEMICIRCLE
+}
+
+/// Convert an angle in degrees into the nearest whole
+/// number of semicircles, wrapping around past ±180.
+pub fn degrees_to_semicircles(degrees: f64) -> i32 {
+    // Going through i64 wraps 2^31 to -2^31, the same angle
+    (degrees * SEMICIRCLES_PER_DEGREE).round() as i64 as i32
+}
+
+/// Render `points` as a GeoJSON `LineString` geometry.
+///
+/// Coordinates are written in the order of `points`,
+/// longitude first, as GeoJSON requires.
+pub fn line_string(points: &[GeoPoint]) -> String {
+    line_string_with_altitude(points, &[])
+}
+
+/// Like [`line_string`], with each point's altitude in
+/// metres from `altitudes` as its third coordinate.
+///
+/// Points without an altitude, past the end of `altitudes`
+/// or NaN, are written with two coordinates.
+///
+/// [`line_string`]: fn.line_string.html
+pub fn line_string_with_altitude(
+    points: &[GeoPoint],
+    altitudes: &[f64],
+) -> String {
+    let mut json = String::from(r#"{"type":"LineString","coordinates":["#);
+    for (i, point) in points.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        // Writing to a String can't fail
+        let _ = match altitudes.get(i) {
+            Some(altitude) if altitude.is_finite() => {
+                write!(json, "[{},{},{}]", point.lon, point.lat, altitude)
+            },
+            _ => write!(json, "[{},{}]", point.lon, point.lat),
+        };
+    }
+    json.push_str("]}");
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn haversine_known_distance() {
+        // Paris to London is roughly 343.5 km
+        let paris = GeoPoint::new(48.8566, 2.3522);
+        let london = GeoPoint::new(51.5074, -0.1278);
+        let d = paris.haversine_m(&london);
+        assert!((d - 343_500.0).abs() < 1_000.0, "{}", d);
+        assert_eq!(paris.haversine_m(&paris), 0.0);
+    }
+
+    #[test]
+    fn positions_round_trip_and_measure() {
+        for &(lat, lon) in &[
+            (0.0, 0.0),
+            (51.5074, -0.1278),
+            (-33.8688, 151.2093),
+            (89.999_999, -179.999_999),
+            (-90.0, 179.5),
+        ] {
+            let position = Position::from_degrees(lat, lon);
+            let point = position.to_degrees();
+            // Half a semicircle is under a centimetre
+            assert!((point.lat - lat).abs() <= DEGREES_PER_SEMICIRCLE / 2.0);
+            assert!((point.lon - lon).abs() <= DEGREES_PER_SEMICIRCLE / 2.0);
+            assert_eq!(Position::from_degrees(point.lat, point.lon), position);
+            assert!(position.is_valid());
+        }
+        assert_eq!(
+            Position::from_degrees(0.0, 180.0),
+            Position::new(0, i32::MIN)
+        );
+        assert_eq!(
+            Position::from_degrees(0.0, 270.0),
+            Position::from_degrees(0.0, -90.0)
+        );
+        assert!(!Position::new(i32::MAX, 0).is_valid());
+        assert!(!Position::new(0, i32::MAX).is_valid());
+        assert!(!Position::new((1 << 30) + 1, 0).is_valid());
+
+        let paris = Position::from_degrees(48.8566, 2.3522);
+        let london = Position::from_degrees(51.5074, -0.1278);
+        let d = paris.distance_to(&london);
+        assert_eq!(d, GeoPoint::from(paris).haversine_m(&london.into()));
+        assert!((d - 343_500.0).abs() < 1_000.0, "{}", d);
+
+        let origin = Position::from_degrees(0.0, 0.0);
+        let bearing =
+            |lat, lon| origin.bearing_to(&Position::from_degrees(lat, lon));
+        assert!(bearing(1.0, 0.0).abs() < 1e-9);
+        assert!((bearing(0.0, 1.0) - 90.0).abs() < 1e-9);
+        assert!((bearing(-1.0, 0.0) - 180.0).abs() < 1e-9);
+        assert!((bearing(0.0, -1.0) - 270.0).abs() < 1e-9);
+        // Paris to London is a little west of north-west
+        let bearing = paris.bearing_to(&london);
+        assert!((bearing - 330.0).abs() < 2.0, "{}", bearing);
+    }
+
+    fn track(points: &[(f64, f64)]) -> Vec<RecordPoint> {
+        points
+            .iter()
+            .map(|&(lat, lon)| {
+                let position = Position::from_degrees(lat, lon);
+                RecordPoint {
+                    position_lat: Some(position.lat_semicircles),
+                    position_long: Some(position.lon_semicircles),
+                    ..RecordPoint::default()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bounds_of_a_track() {
+        let mut records = track(&[(51.5, -0.2), (51.6, 0.1), (51.4, 0.0)]);
+        records.push(RecordPoint::default());
+        records.push(RecordPoint {
+            position_lat: Some(i32::MAX),
+            position_long: Some(0),
+            ..RecordPoint::default()
+        });
+        let bounds = bounds(&records).unwrap();
+        assert_eq!(bounds.north_east, Position::from_degrees(51.6, 0.1));
+        assert_eq!(bounds.south_west, Position::from_degrees(51.4, -0.2));
+        assert!(!bounds.crosses_antimeridian());
+        assert!(bounds.contains(&Position::from_degrees(51.5, 0.0)));
+        assert!(!bounds.contains(&Position::from_degrees(51.5, 0.2)));
+        assert!(bounds.covers(&bounds));
+        assert!(super::bounds(&[RecordPoint::default()]).is_none());
+
+        // A kilometre out, but no further than it need be
+        let wider = bounds.expand_by_meters(1000.0);
+        assert!(wider.covers(&bounds) && !bounds.covers(&wider));
+        let north = Position::from_degrees(51.6 + 0.0089, 0.0);
+        assert!(wider.contains(&north));
+        let east = Position::from_degrees(51.5, 0.1 + 0.0144);
+        assert!(wider.contains(&east));
+        assert!(!wider.contains(&Position::from_degrees(51.5, 0.1 + 0.016)));
+
+        // Out past a pole, round the world
+        let polar = bounds.expand_by_meters(5_000_000.0);
+        assert_eq!(polar.north_east.lat_semicircles, POLE);
+        assert!(polar.contains(&Position::from_degrees(10.0, 179.9)));
+        assert!(polar.contains(&Position::from_degrees(10.0, -180.0)));
+    }
+
+    #[test]
+    fn bounds_across_the_antimeridian() {
+        let records = track(&[(-17.0, 179.5), (-17.5, -179.8), (-16.9, 179.9)]);
+        let bounds = bounds(&records).unwrap();
+        assert!(bounds.crosses_antimeridian());
+        assert_eq!(bounds.south_west, Position::from_degrees(-17.5, 179.5));
+        assert_eq!(bounds.north_east, Position::from_degrees(-16.9, -179.8));
+        assert!(bounds.contains(&Position::from_degrees(-17.0, 180.0)));
+        assert!(bounds.contains(&Position::from_degrees(-17.0, -179.9)));
+        assert!(!bounds.contains(&Position::from_degrees(-17.0, 0.0)));
+        assert!(!bounds.contains(&Position::from_degrees(-17.0, 179.0)));
+        assert!(bounds.expand_by_meters(100.0).covers(&bounds));
+
+        // The last column then the first
+        let tiles: Vec<_> = bounds.tiles(3).collect();
+        assert_eq!(tiles, vec![(7, 4), (0, 4)]);
+        let everywhere = Bounds {
+            north_east: Position::from_degrees(90.0, -0.1),
+            south_west: Position::from_degrees(-90.0, 0.0),
+        };
+        assert_eq!(everywhere.tiles(2).count(), 16);
+        assert_eq!(everywhere.tiles(0).collect::<Vec<_>>(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn semicircles_and_geojson() {
+        let point = GeoPoint::from_semicircles(1 << 30, -(1 << 30));
+        assert_eq!(point, GeoPoint::new(90.0, -90.0));
+
+        let json = line_string(&[GeoPoint::new(1.5, 2.0), point]);
+        assert_eq!(
+            json,
+            r#"{"type":"LineString","coordinates":[[2,1.5],[-90,90]]}"#
+        );
+
+        let json = line_string_with_altitude(
+            &[GeoPoint::new(1.5, 2.0), point],
+            &[12.5],
+        );
+        assert_eq!(
+            json,
+            r#"{"type":"LineString","coordinates":[[2,1.5,12.5],[-90,90]]}"#
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! The raw GPS solutions some devices log alongside their
+//! records, in `GpsMetadata` messages.
+use geo::Position;
+use profile::messages::{
+    GpsMetadata,
+    Message,
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// A single `GpsMetadata` message, scaled into natural
+/// units.
+///
+/// Fields missing from the message are NaN.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsMetaPoint {
+    /// Seconds since the FIT epoch.
+    pub timestamp:      u32,
+    pub lat_deg:        f64,
+    pub lon_deg:        f64,
+    pub enhanced_alt_m: f64,
+    /// Northward velocity in metres per second.
+    pub velocity_n_ms:  f64,
+    /// Eastward velocity in metres per second.
+    pub velocity_e_ms:  f64,
+    /// Vertical velocity in metres per second, positive
+    /// when ascending as in the profile's altitude
+    /// velocity.
+    pub velocity_d_ms:  f64,
+    /// Degrees clockwise from north.
+    pub heading_deg:    f64,
+    /// UTC time of the fix, set by devices logging in
+    /// system time.
+    pub utc_timestamp:  Option<u32>,
+}
+
+impl GpsMetaPoint {
+    /// Flatten a decoded `GpsMetadata` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `GpsMetadata`
+    /// message or has no valid timestamp and position.
+    pub fn from_message(mesg: &Data) -> Option<GpsMetaPoint> {
+        let mut timestamp = None;
+        let mut lat = None;
+        let mut lon = None;
+        let mut point = GpsMetaPoint {
+            timestamp:      0,
+            lat_deg:        f64::NAN,
+            lon_deg:        f64::NAN,
+            enhanced_alt_m: f64::NAN,
+            velocity_n_ms:  f64::NAN,
+            velocity_e_ms:  f64::NAN,
+            velocity_d_ms:  f64::NAN,
+            heading_deg:    f64::NAN,
+            utc_timestamp:  None,
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::GpsMetadata(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                GpsMetadata::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                GpsMetadata::PositionLat(f) if f.is_valid() => {
+                    lat = Some(f.raw_value.0)
+                },
+                GpsMetadata::PositionLong(f) if f.is_valid() => {
+                    lon = Some(f.raw_value.0)
+                },
+                GpsMetadata::EnhancedAltitude(f) if f.is_valid() => {
+                    point.enhanced_alt_m = f.value()
+                },
+                GpsMetadata::Heading(f) if f.is_valid() => {
+                    point.heading_deg = f.value()
+                },
+                GpsMetadata::UtcTimestamp(f) if f.raw_value.0 != u32::MAX => {
+                    point.utc_timestamp = Some(f.raw_value.0)
+                },
+                // Longitude, latitude and altitude velocity
+                GpsMetadata::Velocity(f) if f.raw_value.len() == 3 => {
+                    let velocity: Vec<f64> = f
+                        .raw_value
+                        .iter()
+                        .zip(f.value())
+                        .map(|(raw, value)| {
+                            if raw.0 == i16::MAX {
+                                f64::NAN
+                            }
+                            else {
+                                value
+                            }
+                        })
+                        .collect();
+                    point.velocity_e_ms = velocity[0];
+                    point.velocity_n_ms = velocity[1];
+                    point.velocity_d_ms = velocity[2];
+                },
+                _ => (),
+            }
+        }
+
+        let position = Position::new(lat?, lon?).to_degrees();
+        Some(GpsMetaPoint {
+            timestamp: timestamp?,
+            lat_deg: position.lat,
+            lon_deg: position.lon,
+            ..point
+        })
+    }
+
+    /// Speed over the ground in metres per second.
+    pub fn ground_speed_ms(&self) -> f64 {
+        self.velocity_n_ms.hypot(self.velocity_e_ms)
+    }
+
+    /// Climb rate in metres per second, negative when
+    /// descending.
+    pub fn vertical_speed_ms(&self) -> f64 {
+        self.velocity_d_ms
+    }
+}
+
+/// Every `GpsMetadata` message with a timestamp and a
+/// position, in file order.
+pub fn extract_gps_metadata(messages: &[Data]) -> Vec<GpsMetaPoint> {
+    messages.iter().filter_map(GpsMetaPoint::from_message).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    // 45 degrees in semicircles
+    const LAT_45: i32 = 1 << 29;
+
+    fn velocity(east: i16, north: i16, up: i16) -> Vec<u8> {
+        [east, north, up].iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn ground_speed() {
+        let messages = vec![
+            mesg(160)
+                .u32(253, 1000)
+                .i32(1, LAT_45)
+                .i32(2, -LAT_45)
+                .u32(3, 3000) // 100 m
+                .u16(5, 9000) // 90 degrees
+                .bytes(7, &velocity(300, 400, -150))
+                .build(),
+            mesg(160).u32(253, 1001).build(),
+            mesg(20).u32(253, 1001).build(),
+        ];
+
+        let points = extract_gps_metadata(&messages);
+        assert_eq!(points.len(), 1);
+
+        let point = points[0];
+        assert_eq!((point.lat_deg, point.lon_deg), (45.0, -45.0));
+        assert_eq!(point.enhanced_alt_m, 100.0);
+        assert_eq!(point.heading_deg, 90.0);
+        assert_eq!((point.velocity_e_ms, point.velocity_n_ms), (3.0, 4.0));
+        assert_eq!(point.ground_speed_ms(), 5.0);
+        assert_eq!(point.vertical_speed_ms(), -1.5);
+        assert_eq!(point.utc_timestamp, None);
+    }
+}
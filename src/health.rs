@@ -0,0 +1,371 @@
+//! Structural checks on a decoded file, for the problems a
+//! CRC can't catch: a file can checksum fine and still have
+//! laps nobody can find or sessions its activity doesn't
+//! count.
+//!
+//! Where [`report`] asks whether a file has what its type
+//! calls for, [`health_check`] asks whether what it has
+//! hangs together.
+//!
+//! [`report`]: ../report/index.html
+//! [`health_check`]: fn.health_check.html
+use devices::DeviceInfo;
+use laps::Lap;
+use profile::messages::{
+    Activity,
+    Message,
+    Session,
+};
+use report::{
+    session_spans,
+    Severity,
+};
+use series::RecordPoint;
+use std::collections::BTreeMap;
+use types::{
+    file::{
+        file_id_warnings,
+        File,
+        FileWarning,
+    },
+    record::Data,
+};
+
+/// A structural problem with a file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HealthIssue {
+    pub severity:    Severity,
+    /// Names the check that failed, and stays the same
+    /// across releases. One of
+    ///
+    /// - `file_id_not_first`
+    /// - `too_many_sessions`
+    /// - `lap_outside_sessions`
+    /// - `records_outside_sessions`
+    /// - `conflicting_device_index`
+    /// - `lap_index_out_of_range`
+    pub code:        &'static str,
+    pub description: String,
+}
+
+/// Check how the messages of `file` fit together.
+///
+/// Issues come in the order of the checks, and within a
+/// check in file order. Checks against sessions are skipped
+/// when no session has both a start time and a timestamp.
+pub fn health_check(file: &File) -> Vec<HealthIssue> {
+    let messages: Vec<Data> = file.messages().cloned().collect();
+    let mut issues = Vec::new();
+    let mut issue = |severity, code, description| {
+        issues.push(HealthIssue {
+            severity,
+            code,
+            description,
+        })
+    };
+
+    for warning in file_id_warnings(&messages) {
+        if let FileWarning::FileIdNotFirst {
+            preceding,
+        } = warning
+        {
+            issue(
+                Severity::Warning,
+                "file_id_not_first",
+                format!("FileId comes after {} other messages", preceding),
+            );
+        }
+    }
+
+    let sessions: Vec<&Data> = messages
+        .iter()
+        .filter(|mesg| matches!(mesg.0.first(), Some(Message::Session(_))))
+        .collect();
+    if let Some(declared) = num_sessions(&messages) {
+        if sessions.len() > usize::from(declared) {
+            issue(
+                Severity::Error,
+                "too_many_sessions",
+                format!(
+                    "{} Session messages but the Activity counts {}",
+                    sessions.len(),
+                    declared
+                ),
+            );
+        }
+    }
+
+    let laps: Vec<Lap> =
+        messages.iter().filter_map(Lap::from_message).collect();
+    let spans = session_spans(&messages);
+    let in_session = |start: u32, end: u32| {
+        spans.iter().any(|&(from, to)| from <= start && end <= to)
+    };
+    if !spans.is_empty() {
+        for (i, lap) in laps.iter().enumerate() {
+            let start = lap.start_time.unwrap_or(lap.timestamp);
+            if !in_session(start, lap.timestamp) {
+                issue(
+                    Severity::Warning,
+                    "lap_outside_sessions",
+                    format!(
+                        "lap {} from {} to {} is outside every session",
+                        i, start, lap.timestamp
+                    ),
+                );
+            }
+        }
+
+        let records: Vec<u32> = messages
+            .iter()
+            .filter_map(RecordPoint::from_message)
+            .map(|point| point.timestamp)
+            .collect();
+        let outside = records.iter().filter(|&&ts| !in_session(ts, ts));
+        let outside = outside.count();
+        if outside > 0 {
+            issue(
+                Severity::Warning,
+                "records_outside_sessions",
+                format!(
+                    "{} of {} records are outside every session",
+                    outside,
+                    records.len()
+                ),
+            );
+        }
+    }
+
+    for (index, devices) in conflicting_devices(&messages) {
+        issue(
+            Severity::Warning,
+            "conflicting_device_index",
+            format!(
+                "device index {} is used by {} different devices",
+                index, devices
+            ),
+        );
+    }
+
+    for (i, session) in sessions.iter().enumerate() {
+        if let Some((first, count)) = lap_range(session) {
+            let end = usize::from(first) + usize::from(count);
+            if end > laps.len() {
+                issue(
+                    Severity::Error,
+                    "lap_index_out_of_range",
+                    format!(
+                        "session {} claims laps {} to {} of {}",
+                        i,
+                        first,
+                        end,
+                        laps.len()
+                    ),
+                );
+            }
+        }
+    }
+
+    issues
+}
+
+/// `Activity.NumSessions` of the first activity that has
+/// it.
+fn num_sessions(messages: &[Data]) -> Option<u16> {
+    messages.iter().flat_map(|mesg| mesg.0.iter()).find_map(|field| {
+        match field {
+            Message::Activity(Activity::NumSessions(f)) if f.is_valid() => {
+                Some(f.raw_value.0)
+            },
+            _ => None,
+        }
+    })
+}
+
+/// `(first_lap_index, num_laps)` of a session that has
+/// both.
+fn lap_range(session: &Data) -> Option<(u16, u16)> {
+    let (mut first, mut count) = (None, None);
+    for field in session.0.iter() {
+        match field {
+            Message::Session(Session::FirstLapIndex(f)) if f.is_valid() => {
+                first = Some(f.raw_value.0)
+            },
+            Message::Session(Session::NumLaps(f)) if f.is_valid() => {
+                count = Some(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+    Some((first?, count?))
+}
+
+/// Device indices claimed by more than one device, with how
+/// many, in ascending order of index.
+///
+/// Devices repeat their `DeviceInfo` through a file, so an
+/// index only conflicts when its messages disagree on the
+/// serial number or product.
+fn conflicting_devices(messages: &[Data]) -> Vec<(u8, usize)> {
+    // Serial number and product of each device seen
+    type Device = (Option<u32>, Option<u16>);
+    let mut devices: BTreeMap<u8, Vec<Device>> = BTreeMap::new();
+    for info in messages.iter().filter_map(DeviceInfo::from_message) {
+        let index = match info.device_index {
+            Some(index) if index.0 != 0xFF => index.0,
+            _ => continue,
+        };
+        let device = (info.serial_number, info.product);
+        if device == (None, None) {
+            continue;
+        }
+        let seen = devices.entry(index).or_default();
+        if !seen.contains(&device) {
+            seen.push(device);
+        }
+    }
+    devices
+        .into_iter()
+        .filter(|(_, seen)| seen.len() > 1)
+        .map(|(index, seen)| (index, seen.len()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::FitBuilder;
+
+    /// Local message types of the messages in the files
+    /// below.
+    const FILE_ID: u8 = 0;
+    const ACTIVITY: u8 = 1;
+    const SESSION: u8 = 2;
+    const LAP: u8 = 3;
+    const RECORD: u8 = 4;
+    const DEVICE: u8 = 5;
+
+    fn definitions() -> FitBuilder {
+        let mut fit = FitBuilder::new();
+        fit.definition(FILE_ID, 0, &[(0, 1, 0x00)]);
+        fit.definition(ACTIVITY, 34, &[(253, 4, 0x86), (1, 2, 0x84)]);
+        fit.definition(
+            SESSION,
+            18,
+            &[(253, 4, 0x86), (2, 4, 0x86), (25, 2, 0x84), (26, 2, 0x84)],
+        );
+        fit.definition(LAP, 19, &[(253, 4, 0x86), (2, 4, 0x86)]);
+        fit.definition(RECORD, 20, &[(253, 4, 0x86)]);
+        fit.definition(
+            DEVICE,
+            23,
+            &[(253, 4, 0x86), (0, 1, 0x02), (3, 4, 0x8C), (4, 2, 0x84)],
+        );
+        fit
+    }
+
+    fn file_id(fit: &mut FitBuilder) {
+        fit.data(FILE_ID, &[&[4]]);
+    }
+
+    fn device(fit: &mut FitBuilder, ts: u32, index: u8, serial: u32) {
+        fit.data(
+            DEVICE,
+            &[
+                &ts.to_le_bytes(),
+                &[index],
+                &serial.to_le_bytes(),
+                &3121u16.to_le_bytes(),
+            ],
+        );
+    }
+
+    fn lap(fit: &mut FitBuilder, start: u32, end: u32) {
+        fit.data(LAP, &[&end.to_le_bytes(), &start.to_le_bytes()]);
+    }
+
+    fn session(fit: &mut FitBuilder, start: u32, end: u32, laps: (u16, u16)) {
+        fit.data(
+            SESSION,
+            &[
+                &end.to_le_bytes(),
+                &start.to_le_bytes(),
+                &laps.0.to_le_bytes(),
+                &laps.1.to_le_bytes(),
+            ],
+        );
+    }
+
+    fn activity(fit: &mut FitBuilder, num_sessions: u16) {
+        fit.data(
+            ACTIVITY,
+            &[&2000u32.to_le_bytes(), &num_sessions.to_le_bytes()],
+        );
+    }
+
+    /// One 100 second session of two laps, recorded by
+    /// device 0 and a sensor at index 1.
+    fn healthy(fit: &mut FitBuilder) {
+        device(fit, 1000, 0, 111);
+        device(fit, 1000, 1, 222);
+        for ts in 1000..1100u32 {
+            fit.data(RECORD, &[&ts.to_le_bytes()]);
+        }
+        lap(fit, 1000, 1050);
+        lap(fit, 1050, 1100);
+        device(fit, 1100, 0, 111);
+        session(fit, 1000, 1100, (0, 2));
+    }
+
+    fn codes(fit: &FitBuilder) -> Vec<&'static str> {
+        let file = File::from_bytes(&fit.build()).unwrap();
+        health_check(&file).iter().map(|issue| issue.code).collect()
+    }
+
+    #[test]
+    fn healthy_file_has_no_issues() {
+        let mut fit = definitions();
+        file_id(&mut fit);
+        healthy(&mut fit);
+        activity(&mut fit, 1);
+        assert!(codes(&fit).is_empty());
+    }
+
+    #[test]
+    fn reports_each_issue() {
+        let mut fit = definitions();
+        fit.data(RECORD, &[&900u32.to_le_bytes()]);
+        file_id(&mut fit);
+        healthy(&mut fit);
+        lap(&mut fit, 1200, 1300);
+        device(&mut fit, 1100, 1, 333);
+        session(&mut fit, 1100, 1100, (2, 2));
+        activity(&mut fit, 1);
+
+        let file = File::from_bytes(&fit.build()).unwrap();
+        let issues = health_check(&file);
+        let codes: Vec<&str> = issues.iter().map(|issue| issue.code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "file_id_not_first",
+                "too_many_sessions",
+                "lap_outside_sessions",
+                "records_outside_sessions",
+                "conflicting_device_index",
+                "lap_index_out_of_range",
+            ]
+        );
+        assert_eq!(
+            issues[3].description,
+            "1 of 101 records are outside every session"
+        );
+        assert_eq!(
+            issues[4].description,
+            "device index 1 is used by 2 different devices"
+        );
+        assert_eq!(issues[5].description, "session 1 claims laps 2 to 4 of 3");
+        assert_eq!(issues[5].severity, Severity::Error);
+    }
+}
@@ -0,0 +1,662 @@
+//! A summary of every FIT file in a directory, for finding
+//! activities without decoding them all again.
+//!
+//! Building an [`Index`] decodes each file once, keeping
+//! only its identity and session summaries.
+//! [`Index::update`] then only decodes files that are new
+//! or whose modification time or size changed, so an index
+//! saved to disk with [`Index::save`] is cheap to keep
+//! current.
+//!
+//! [`Index`]: struct.Index.html
+//! [`Index::update`]: struct.Index.html#method.update
+//! [`Index::save`]: struct.Index.html#method.save
+use byteorder::{
+    LittleEndian,
+    ReadBytesExt,
+    WriteBytesExt,
+};
+use creator::{
+    self,
+    CreatedAtSource,
+};
+use error::{
+    Error,
+    Result,
+};
+use profile::{
+    messages::{
+        FileId,
+        Message,
+        Session,
+    },
+    types::{
+        DateTime,
+        File as FileType,
+        Manufacturer,
+        Sport,
+    },
+};
+use reader::FitReader;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{
+        self,
+        BufReader,
+        BufWriter,
+        Read,
+        Write,
+    },
+    ops::Range,
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::UNIX_EPOCH,
+};
+use types::{
+    field::Field,
+    record::{
+        Data,
+        Message as Content,
+    },
+};
+
+/// Leads every saved index, the last byte being the format
+/// version.
+const MAGIC: &[u8; 8] = b"FITIDX\x00\x02";
+
+/// Buffer size for scanning files, large enough that most
+/// activities are read in a handful of calls.
+const SCAN_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Who made a file, what kind it is and when, mostly from
+/// its `FileId` message.
+#[derive(Debug, Clone, Default)]
+pub struct FileIdentity {
+    pub file_type:         Option<FileType>,
+    pub manufacturer:      Option<Manufacturer>,
+    pub product:           Option<u16>,
+    pub serial_number:     Option<u32>,
+    /// From [`creator::created_at`], so not necessarily
+    /// `FileId.TimeCreated`.
+    ///
+    /// [`creator::created_at`]: ../creator/fn.created_at.html
+    pub time_created:      Option<DateTime>,
+    /// Which message `time_created` came from.
+    pub time_created_from: Option<CreatedAtSource>,
+}
+
+/// What the index knows about a single file.
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    pub path:     PathBuf,
+    pub identity: FileIdentity,
+    /// The sport of each session, in file order.
+    pub sports:   Vec<Sport>,
+    /// Start of the earliest session.
+    pub start:    Option<DateTime>,
+    /// End of the latest session.
+    pub end:      Option<DateTime>,
+    /// Total distance over all sessions, in metres.
+    pub distance: Option<f64>,
+    /// File size in bytes.
+    pub size:     u64,
+    /// Modification time, as nanoseconds since the Unix
+    /// epoch.
+    pub modified: u128,
+}
+
+impl IndexEntry {
+    /// Whether the file's sessions overlap `range`.
+    pub fn overlaps(&self, range: &Range<DateTime>) -> bool {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => start < range.end && range.start <= end,
+            _ => false,
+        }
+    }
+
+    /// Whether any session is of `sport`.
+    pub fn has_sport(&self, sport: &Sport) -> bool {
+        let sport = sport.clone() as u8;
+        self.sports.iter().any(|s| s.clone() as u8 == sport)
+    }
+}
+
+/// The indexed files of a directory, ordered by path.
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    entries: Vec<IndexEntry>,
+}
+
+/// Index every `.fit` file under `dir`, recursively.
+///
+/// Files that fail to decode are left out, and tried again
+/// on the next [`update`](struct.Index.html#method.update).
+pub fn build<P: AsRef<Path>>(dir: P) -> Result<Index> {
+    let mut index = Index::default();
+    index.update(dir)?;
+    Ok(index)
+}
+
+impl Index {
+    /// All entries, ordered by path.
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Paths of the files with sessions overlapping
+    /// `range`, and of `sport` if given.
+    pub fn query(
+        &self,
+        range: Range<DateTime>,
+        sport: Option<Sport>,
+    ) -> Vec<&Path> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.overlaps(&range))
+            .filter(|entry| {
+                sport.as_ref().map(|s| entry.has_sport(s)).unwrap_or(true)
+            })
+            .map(|entry| entry.path.as_path())
+            .collect()
+    }
+
+    /// Bring the index in line with `dir`: decode files
+    /// that are new or changed since they were indexed,
+    /// and drop the ones that are gone. Returns the
+    /// number of files decoded.
+    pub fn update<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize> {
+        let mut known: BTreeMap<PathBuf, IndexEntry> = self
+            .entries
+            .drain(..)
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+
+        let mut paths = Vec::new();
+        find_fit_files(dir.as_ref(), &mut paths)
+            .map_err(Error::reading("index directory"))?;
+        paths.sort();
+
+        let mut decoded = 0;
+        for path in paths {
+            let metadata =
+                fs::metadata(&path).map_err(Error::reading("file metadata"))?;
+            let size = metadata.len();
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since| since.as_nanos())
+                .unwrap_or(0);
+
+            match known.remove(&path) {
+                Some(ref entry)
+                    if entry.size == size && entry.modified == modified =>
+                {
+                    self.entries.push(entry.clone())
+                },
+                _ => {
+                    decoded += 1;
+                    if let Ok(mut entry) = scan(&path) {
+                        entry.size = size;
+                        entry.modified = modified;
+                        self.entries.push(entry);
+                    }
+                },
+            }
+        }
+        Ok(decoded)
+    }
+
+    /// Load an index saved with [`save`](#method.save).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Index> {
+        let file = fs::File::open(path).map_err(Error::reading("index"))?;
+        Index::read_from(&mut BufReader::new(file))
+    }
+
+    /// Save the index to `path`, replacing any file there.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = fs::File::create(path).map_err(Error::reading("index"))?;
+        let mut w = BufWriter::new(file);
+        self.write_to(&mut w)
+            .and_then(|_| w.flush())
+            .map_err(Error::reading("index"))
+    }
+
+    /// Read an index in the compact binary format written
+    /// by [`write_to`](#method.write_to).
+    pub fn read_from<R: Read>(r: &mut R) -> Result<Index> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic).map_err(Error::reading("index magic"))?;
+        if &magic != MAGIC {
+            return Err(Error::not_index())
+        }
+
+        let count =
+            r.read_u32::<LittleEndian>().map_err(Error::reading("index"))?;
+        let entries = (0..count)
+            .map(|i| {
+                read_entry(r)
+                    .map_err(Error::reading(format!("index entry #{}", i)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Index {
+            entries,
+        })
+    }
+
+    /// Write the index in a compact little endian binary
+    /// format.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_u32::<LittleEndian>(self.entries.len() as u32)?;
+        for entry in &self.entries {
+            write_entry(w, entry)?;
+        }
+        Ok(())
+    }
+}
+
+fn find_fit_files(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for dir_entry in fs::read_dir(dir)? {
+        let path = dir_entry?.path();
+        if path.is_dir() {
+            find_fit_files(&path, paths)?;
+        }
+        else if path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("fit"))
+            .unwrap_or(false)
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Decode the file at `path`, keeping only what the index
+/// needs. Size and modification time are left for the
+/// caller.
+///
+/// Sessions are written at the end of a file, so the whole
+/// file is streamed through; records are dropped as soon as
+/// they are decoded rather than collected, bar the first
+/// with a timestamp, which the creation time may fall back
+/// to.
+fn scan(path: &Path) -> Result<IndexEntry> {
+    let file = fs::File::open(path).map_err(Error::reading("fit file"))?;
+    let reader = FitReader::with_capacity(SCAN_BUFFER_SIZE, file)?;
+
+    let mut entry = IndexEntry {
+        path:     path.to_path_buf(),
+        identity: FileIdentity::default(),
+        sports:   Vec::new(),
+        start:    None,
+        end:      None,
+        distance: None,
+        size:     0,
+        modified: 0,
+    };
+    let mut seen_file_id = false;
+    let mut seen_record_time = false;
+    // What `creator::created_at` looks at
+    let mut dated: Vec<Data> = Vec::new();
+    for record in reader {
+        let data = match record?.content {
+            Content::Data(data) => data,
+            _ => continue,
+        };
+        match data.0.first() {
+            // Only the first FileId counts, as for `File`
+            Some(Message::FileId(_)) if !seen_file_id => {
+                seen_file_id = true;
+                add_file_id(&mut entry, &data.0);
+                dated.push(data)
+            },
+            Some(Message::Session(_)) => {
+                add_session(&mut entry, &data.0);
+                dated.push(data)
+            },
+            Some(Message::Activity(_)) => dated.push(data),
+            Some(Message::Record(_)) if !seen_record_time => {
+                let data = vec![data];
+                if creator::created_at(&data).is_some() {
+                    seen_record_time = true;
+                    dated.extend(data)
+                }
+            },
+            _ => (),
+        }
+    }
+    if let Some(created) = creator::created_at(&dated) {
+        entry.identity.time_created = Some(created.time);
+        entry.identity.time_created_from = Some(created.source);
+    }
+    Ok(entry)
+}
+
+fn add_file_id(entry: &mut IndexEntry, fields: &[Message]) {
+    let identity = &mut entry.identity;
+    for field in fields {
+        match field {
+            Message::FileId(FileId::Type(f)) => {
+                identity.file_type = Some(f.raw_value.clone())
+            },
+            Message::FileId(FileId::Manufacturer(f)) => {
+                identity.manufacturer = Some(f.raw_value.clone())
+            },
+            Message::FileId(FileId::Product(f)) if f.is_valid() => {
+                identity.product = Some(f.raw_value.0)
+            },
+            Message::FileId(FileId::SerialNumber(f)) if f.is_valid() => {
+                identity.serial_number = Some(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+}
+
+fn add_session(entry: &mut IndexEntry, fields: &[Message]) {
+    let mut sport = None;
+    for field in fields {
+        match field {
+            Message::Session(Session::Sport(f)) => {
+                sport = Some(f.raw_value.clone())
+            },
+            Message::Session(Session::StartTime(f))
+                if f.raw_value.to_utc().is_some() =>
+            {
+                let start = f.raw_value;
+                entry.start = Some(entry.start.map_or(start, |s| s.min(start)))
+            },
+            Message::Session(Session::Timestamp(f))
+                if f.raw_value.to_utc().is_some() =>
+            {
+                let end = f.raw_value;
+                entry.end = Some(entry.end.map_or(end, |e| e.max(end)))
+            },
+            Message::Session(Session::TotalDistance(f)) if f.is_valid() => {
+                *entry.distance.get_or_insert(0.0) += f.value()
+            },
+            _ => (),
+        }
+    }
+    entry.sports.push(sport.unwrap_or(Sport::Unknown));
+}
+
+// Optional values are stored with their FIT invalid value,
+// or a leading presence byte where there's none.
+
+fn write_entry<W: Write>(w: &mut W, entry: &IndexEntry) -> io::Result<()> {
+    let path = entry.path.to_string_lossy();
+    w.write_u16::<LittleEndian>(path.len() as u16)?;
+    w.write_all(path.as_bytes())?;
+
+    let identity = &entry.identity;
+    w.write_u8(identity.file_type.clone().map_or(0xFF, |t| t as u8))?;
+    w.write_u16::<LittleEndian>(
+        identity.manufacturer.clone().map_or(0xFFFF, |m| m as u16),
+    )?;
+    w.write_u16::<LittleEndian>(identity.product.unwrap_or(0xFFFF))?;
+    w.write_u32::<LittleEndian>(identity.serial_number.unwrap_or(0))?;
+    write_date_time(w, identity.time_created)?;
+    w.write_u8(identity.time_created_from.map_or(0xFF, source_to_u8))?;
+
+    w.write_u8(entry.sports.len() as u8)?;
+    for sport in &entry.sports {
+        w.write_u8(sport.clone() as u8)?;
+    }
+    write_date_time(w, entry.start)?;
+    write_date_time(w, entry.end)?;
+    w.write_u8(entry.distance.is_some() as u8)?;
+    w.write_f64::<LittleEndian>(entry.distance.unwrap_or(0.0))?;
+    w.write_u64::<LittleEndian>(entry.size)?;
+    w.write_u128::<LittleEndian>(entry.modified)
+}
+
+fn read_entry<R: Read>(r: &mut R) -> io::Result<IndexEntry> {
+    let mut path = vec![0; r.read_u16::<LittleEndian>()? as usize];
+    r.read_exact(&mut path)?;
+    let path = String::from_utf8(path)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let file_type = Some(r.read_u8()?)
+        .filter(|&t| t != 0xFF)
+        .and_then(|t| FileType::decode::<LittleEndian>(&[t]).ok());
+    let manufacturer =
+        Some(r.read_u16::<LittleEndian>()?).filter(|&m| m != 0xFFFF).and_then(
+            |m| Manufacturer::decode::<LittleEndian>(&m.to_le_bytes()).ok(),
+        );
+    let product = Some(r.read_u16::<LittleEndian>()?).filter(|&p| p != 0xFFFF);
+    let serial_number = Some(r.read_u32::<LittleEndian>()?).filter(|&s| s != 0);
+    let time_created = read_date_time(r)?;
+    let time_created_from = source_from_u8(r.read_u8()?);
+
+    let sports = (0..r.read_u8()?)
+        .map(|_| {
+            let sport = r.read_u8()?;
+            Ok(Sport::decode::<LittleEndian>(&[sport])
+                .unwrap_or(Sport::Unknown))
+        })
+        .collect::<io::Result<_>>()?;
+    let start = read_date_time(r)?;
+    let end = read_date_time(r)?;
+    let has_distance = r.read_u8()? != 0;
+    let distance = Some(r.read_f64::<LittleEndian>()?).filter(|_| has_distance);
+
+    Ok(IndexEntry {
+        path: PathBuf::from(path),
+        identity: FileIdentity {
+            file_type,
+            manufacturer,
+            product,
+            serial_number,
+            time_created,
+            time_created_from,
+        },
+        sports,
+        start,
+        end,
+        distance,
+        size: r.read_u64::<LittleEndian>()?,
+        modified: r.read_u128::<LittleEndian>()?,
+    })
+}
+
+fn write_date_time<W: Write>(
+    w: &mut W,
+    time: Option<DateTime>,
+) -> io::Result<()> {
+    w.write_u32::<LittleEndian>(time.map_or(u32::MAX, |t| t.0))
+}
+
+fn read_date_time<R: Read>(r: &mut R) -> io::Result<Option<DateTime>> {
+    Ok(Some(r.read_u32::<LittleEndian>()?)
+        .filter(|&t| t != u32::MAX)
+        .map(DateTime))
+}
+
+fn source_to_u8(source: CreatedAtSource) -> u8 {
+    match source {
+        CreatedAtSource::FileId => 0,
+        CreatedAtSource::Activity => 1,
+        CreatedAtSource::Session => 2,
+        CreatedAtSource::Record => 3,
+    }
+}
+
+fn source_from_u8(source: u8) -> Option<CreatedAtSource> {
+    match source {
+        0 => Some(CreatedAtSource::FileId),
+        1 => Some(CreatedAtSource::Activity),
+        2 => Some(CreatedAtSource::Session),
+        3 => Some(CreatedAtSource::Record),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use std::{
+        env,
+        io::Cursor,
+        thread,
+        time::Duration,
+    };
+    use testutil::FitBuilder;
+
+    // 2018-07-01 12:00:00 UTC
+    const NOON: u32 = 899_380_800;
+
+    /// An activity file with a single session.
+    fn activity(start: u32, sport: u8, distance_m: u32) -> Vec<u8> {
+        activity_created(start, start, sport, distance_m)
+    }
+
+    /// As `activity`, but with `FileId.TimeCreated` set to
+    /// `created`.
+    fn activity_created(
+        created: u32,
+        start: u32,
+        sport: u8,
+        distance_m: u32,
+    ) -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(
+            0,
+            0,
+            &[(0, 1, 0x00), (1, 2, 0x84), (2, 2, 0x84), (4, 4, 0x86)],
+        );
+        fit.data(
+            0,
+            &[
+                &[4],
+                &1u16.to_le_bytes()[..],
+                &3121u16.to_le_bytes(),
+                &created.to_le_bytes(),
+            ],
+        );
+        fit.definition(
+            1,
+            18,
+            &[(2, 4, 0x86), (253, 4, 0x86), (5, 1, 0x00), (9, 4, 0x86)],
+        );
+        fit.data(
+            1,
+            &[
+                &start.to_le_bytes()[..],
+                &(start + 3600).to_le_bytes(),
+                &[sport],
+                &(distance_m * 100).to_le_bytes(),
+            ],
+        );
+        fit.build()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "garminfit-index-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("2018")).unwrap();
+        dir
+    }
+
+    fn day(offset: u32) -> Range<DateTime> {
+        let start = NOON - 12 * 3600 + offset * 24 * 3600;
+        DateTime(start)..DateTime(start + 24 * 3600)
+    }
+
+    #[test]
+    fn builds_queries_and_updates() {
+        let dir = temp_dir("update");
+        let run = dir.join("run.fit");
+        let ride = dir.join("2018").join("ride.FIT");
+        fs::write(&run, activity(NOON, 1, 10_000)).unwrap();
+        fs::write(&ride, activity(NOON + 24 * 3600, 2, 40_000)).unwrap();
+        fs::write(dir.join("notes.txt"), b"not a fit file").unwrap();
+        fs::write(dir.join("broken.fit"), b"not a fit file either").unwrap();
+
+        let mut index = build(&dir).unwrap();
+        assert_eq!(index.entries().len(), 2);
+        assert_eq!(index.query(day(0), None), vec![run.as_path()]);
+        assert_eq!(
+            index.query(day(1), Some(Sport::Cycling)),
+            vec![ride.as_path()]
+        );
+        assert!(index.query(day(1), Some(Sport::Running)).is_empty());
+
+        let entry = &index.entries()[1];
+        assert_eq!(entry.path, run);
+        assert_eq!(entry.distance, Some(10_000.0));
+        assert_eq!(entry.identity.product, Some(3121));
+        assert_eq!(entry.identity.time_created, Some(DateTime(NOON)));
+        assert_eq!(
+            entry.identity.time_created_from,
+            Some(CreatedAtSource::FileId)
+        );
+        assert!(matches!(
+            entry.identity.manufacturer,
+            Some(Manufacturer::Garmin)
+        ));
+
+        // Only the broken file is tried again
+        assert_eq!(index.update(&dir).unwrap(), 1);
+
+        // Turn the run into a walk a day later
+        thread::sleep(Duration::from_millis(10));
+        fs::write(&run, activity(NOON + 2 * 24 * 3600, 11, 5_000)).unwrap();
+        fs::remove_file(&ride).unwrap();
+        assert_eq!(index.update(&dir).unwrap(), 2);
+        assert_eq!(index.entries().len(), 1);
+        assert!(index.query(day(0), None).is_empty());
+        assert_eq!(
+            index.query(day(2), Some(Sport::Walking)),
+            vec![run.as_path()]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn round_trips() {
+        let dir = temp_dir("save");
+        fs::write(dir.join("run.fit"), activity(NOON, 1, 10_000)).unwrap();
+        let index = build(&dir).unwrap();
+
+        let saved = dir.join("index.bin");
+        index.save(&saved).unwrap();
+        let loaded = Index::load(&saved).unwrap();
+        assert_eq!(
+            format!("{:?}", loaded.entries()),
+            format!("{:?}", index.entries())
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        let err =
+            Index::read_from(&mut Cursor::new(b"FITIDX\x00\x01")).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::NotIndex));
+    }
+
+    #[test]
+    fn creation_time_falls_back_to_the_session() {
+        let dir = temp_dir("created");
+        fs::write(dir.join("run.fit"), activity_created(0, NOON, 1, 10_000))
+            .unwrap();
+
+        let index = build(&dir).unwrap();
+        let identity = &index.entries()[0].identity;
+        assert_eq!(identity.time_created, Some(DateTime(NOON)));
+        assert_eq!(
+            identity.time_created_from,
+            Some(CreatedAtSource::Session)
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
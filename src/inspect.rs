@@ -0,0 +1,432 @@
+//! A decoded file as a tree, for inspectors to show.
+//!
+//! [`Tree::build`] groups the data messages of a file by
+//! message type, in the order each type first appears, and
+//! keeps the messages of each group in file order under it
+//! with their fields under them:
+//!
+//! ```text
+//! 3 messages of 2 types
+//! ├── file_id (0): 1 message
+//! │   └── file_id 0
+//! │       ├── type: activity
+//! │       └── ...
+//! └── record (20): 2 messages
+//!     ├── record 0
+//!     │   ├── timestamp: 2021-03-04 05:06:07 UTC
+//!     │   └── heart_rate: 150 bpm
+//!     └── record 1
+//! ```
+//!
+//! Nodes are indices into the tree's vectors, so they stay
+//! the same for as long as the tree does and a GUI can keep
+//! them in its own widgets. Every node has a [`label`] to
+//! show, values rounded to the decimals their scale gives
+//! them as [`Precision::SpecImplied`] does. Drawing is left
+//! to whatever toolkit the inspector uses; the `inspect`
+//! example draws one in a terminal.
+//!
+//! [`Tree::build`]: struct.Tree.html#method.build
+//! [`label`]: struct.Tree.html#method.label
+//! [`Precision::SpecImplied`]: ../types/field/enum.Precision.html#variant.SpecImplied
+use profile::messages::{
+    field_name,
+    field_scale_offset,
+    field_units,
+    message_name,
+    Message,
+};
+use std::ops::Range;
+use types::{
+    field::{
+        usable_scale,
+        Precision,
+        Value,
+    },
+    record::{
+        Data,
+        DEVELOPER_FIELD_NUM,
+    },
+};
+
+/// Whether field nodes keep the bytes of their field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Raw {
+    Keep,
+    Omit,
+}
+
+/// A node of a [`Tree`](struct.Tree.html), by its index in
+/// the vector that holds it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// The file.
+    Root,
+    Group(usize),
+    Message(usize),
+    Field(usize),
+}
+
+/// The messages of one message type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupNode {
+    pub mesg_num: u16,
+    /// The message's name in the profile, or
+    /// `unknown_<mesg_num>`.
+    pub name:     String,
+    /// Indices into [`Tree::messages`], in file order.
+    ///
+    /// [`Tree::messages`]: struct.Tree.html#structfield.messages
+    pub messages: Vec<usize>,
+}
+
+/// A data message, at its index in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageNode {
+    pub group:  usize,
+    /// Its index among the messages of its group.
+    pub number: usize,
+    /// Indices into [`Tree::fields`].
+    ///
+    /// [`Tree::fields`]: struct.Tree.html#structfield.fields
+    pub fields: Range<usize>,
+}
+
+/// A field, with what an inspector shows of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldNode {
+    pub message:       usize,
+    pub field_def_num: u8,
+    /// The field's name in the profile, `developer` for
+    /// developer fields or `unknown_<field_def_num>`.
+    pub name:          String,
+    /// Scaled into `units`.
+    pub value:         Value,
+    /// A number before the profile's scale and offset.
+    pub raw:           Option<f64>,
+    pub scale:         Option<f64>,
+    pub offset:        Option<f64>,
+    pub units:         Option<&'static str>,
+    /// Little endian, as [`writer`] writes the field, with
+    /// [`Raw::Keep`].
+    ///
+    /// [`writer`]: ../writer/index.html
+    /// [`Raw::Keep`]: enum.Raw.html#variant.Keep
+    pub bytes:         Option<Vec<u8>>,
+}
+
+impl FieldNode {
+    fn new(message: usize, field: &Message, raw: Raw) -> FieldNode {
+        let (mesg_num, num) = (field.mesg_num(), field.field_def_num());
+        let name = match field_name(mesg_num, num) {
+            Some(name) => name.to_owned(),
+            None if num == DEVELOPER_FIELD_NUM => "developer".to_owned(),
+            None => format!("unknown_{}", num),
+        };
+        let (scale, offset) = field_scale_offset(mesg_num, num);
+        let value = field.value();
+        let unscaled = match value {
+            Value::Number(value) => {
+                let offset = offset.filter(|offset| offset.is_finite());
+                let raw = match usable_scale(scale) {
+                    Some(scale) => (value + offset.unwrap_or(0.0)) * scale,
+                    None => value,
+                };
+                // Scaled values are integers divided down
+                Some(raw.round())
+            },
+            _ => None,
+        };
+        let bytes = match raw {
+            Raw::Keep => {
+                let mut bytes = Vec::new();
+                field.encode(&mut bytes);
+                Some(bytes)
+            },
+            Raw::Omit => None,
+        };
+
+        FieldNode {
+            message,
+            field_def_num: num,
+            name,
+            value,
+            raw: unscaled,
+            scale,
+            offset,
+            units: field_units(mesg_num, num),
+            bytes,
+        }
+    }
+
+    /// Whether the field held a value rather than its base
+    /// type's invalid value.
+    pub fn is_valid(&self) -> bool {
+        self.value != Value::Invalid
+    }
+
+    /// The value as the field's label shows it, with its
+    /// units.
+    pub fn display_value(&self) -> String {
+        let precision = Precision::SpecImplied;
+        let with_units = |value: String| {
+            match self.units {
+                Some(units) => format!("{} {}", value, units),
+                None => value,
+            }
+        };
+        match &self.value {
+            Value::Number(value) => {
+                with_units(precision.format(*value, self.scale))
+            },
+            Value::Numbers(values) => {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| precision.format(*value, self.scale))
+                    .collect();
+                with_units(format!("[{}]", values.join(", ")))
+            },
+            Value::Bool(value) => value.to_string(),
+            Value::Text(text) => format!("{:?}", text.trim_end_matches('\0')),
+            Value::Texts(texts) => format!("{:?}", texts),
+            Value::Bytes(bytes) => hex(bytes),
+            Value::DateTime(date_time) => {
+                match date_time.to_utc() {
+                    Some(utc) => {
+                        utc.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+                    },
+                    None => format!("{} s since power on", date_time.0),
+                }
+            },
+            Value::LocalDateTime(date_time) => {
+                match date_time.to_naive_local() {
+                    Some(local) => {
+                        local.format("%Y-%m-%d %H:%M:%S").to_string()
+                    },
+                    None => format!("{} s since power on", date_time.0),
+                }
+            },
+            Value::Enum(name) => name.clone(),
+            Value::Invalid => "invalid".to_owned(),
+        }
+    }
+}
+
+/// The data messages of a file as a tree of message types,
+/// messages and fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tree {
+    /// In the order their first message appears.
+    pub groups:   Vec<GroupNode>,
+    /// In file order.
+    pub messages: Vec<MessageNode>,
+    /// Message by message, in the order they were decoded.
+    pub fields:   Vec<FieldNode>,
+}
+
+impl Tree {
+    /// The tree of `messages`, with the bytes of each field
+    /// if `raw` asks for them. Messages without fields are
+    /// left out.
+    pub fn build(messages: &[Data], raw: Raw) -> Tree {
+        let mut tree = Tree::default();
+        for mesg in messages {
+            let mesg_num = match mesg.mesg_num() {
+                Some(mesg_num) => mesg_num,
+                None => continue,
+            };
+            let group =
+                match tree.groups.iter().position(|g| g.mesg_num == mesg_num) {
+                    Some(group) => group,
+                    None => {
+                        let name = match message_name(mesg_num) {
+                            Some(name) => name.to_owned(),
+                            None => format!("unknown_{}", mesg_num),
+                        };
+                        tree.groups.push(GroupNode {
+                            mesg_num,
+                            name,
+                            messages: Vec::new(),
+                        });
+                        tree.groups.len() - 1
+                    },
+                };
+
+            let index = tree.messages.len();
+            let start = tree.fields.len();
+            tree.fields.extend(
+                mesg.0.iter().map(|field| FieldNode::new(index, field, raw)),
+            );
+            tree.messages.push(MessageNode {
+                group,
+                number: tree.groups[group].messages.len(),
+                fields: start..tree.fields.len(),
+            });
+            tree.groups[group].messages.push(index);
+        }
+        tree
+    }
+
+    /// The nodes under `node`, in order.
+    pub fn children(&self, node: Node) -> Vec<Node> {
+        match node {
+            Node::Root => (0..self.groups.len()).map(Node::Group).collect(),
+            Node::Group(group) => {
+                self.groups[group]
+                    .messages
+                    .iter()
+                    .cloned()
+                    .map(Node::Message)
+                    .collect()
+            },
+            Node::Message(message) => {
+                self.messages[message].fields.clone().map(Node::Field).collect()
+            },
+            Node::Field(_) => Vec::new(),
+        }
+    }
+
+    /// The node `node` is under, `None` for the root.
+    pub fn parent(&self, node: Node) -> Option<Node> {
+        match node {
+            Node::Root => None,
+            Node::Group(_) => Some(Node::Root),
+            Node::Message(message) => {
+                Some(Node::Group(self.messages[message].group))
+            },
+            Node::Field(field) => {
+                Some(Node::Message(self.fields[field].message))
+            },
+        }
+    }
+
+    /// One line describing `node`.
+    pub fn label(&self, node: Node) -> String {
+        let count = |n: usize, what: &str| {
+            format!("{} {}{}", n, what, if n == 1 { "" } else { "s" })
+        };
+        match node {
+            Node::Root => {
+                format!(
+                    "{} of {}",
+                    count(self.messages.len(), "message"),
+                    count(self.groups.len(), "type")
+                )
+            },
+            Node::Group(group) => {
+                let group = &self.groups[group];
+                format!(
+                    "{} ({}): {}",
+                    group.name,
+                    group.mesg_num,
+                    count(group.messages.len(), "message")
+                )
+            },
+            Node::Message(message) => {
+                let message = &self.messages[message];
+                format!(
+                    "{} {}",
+                    self.groups[message.group].name, message.number
+                )
+            },
+            Node::Field(field) => {
+                let field = &self.fields[field];
+                format!("{}: {}", field.name, field.display_value())
+            },
+        }
+    }
+
+    /// The fields whose name or shown value contains
+    /// `query`, ignoring case, in file order.
+    pub fn search(&self, query: &str) -> Vec<Node> {
+        let query = query.to_lowercase();
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                field.name.to_lowercase().contains(&query)
+                    || field.display_value().to_lowercase().contains(&query)
+            })
+            .map(|(index, _)| Node::Field(index))
+            .collect()
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let bytes: Vec<String> =
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    bytes.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reader::from_bytes;
+    use testutil::FitBuilder;
+
+    /// Records around a vendor message, the second record
+    /// without a heart rate.
+    fn messages() -> Vec<Data> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02), (5, 4, 0x86)]);
+        fit.data(
+            0,
+            &[&1_000_000_000u32.to_le_bytes(), &[150], &1234u32.to_le_bytes()],
+        );
+        fit.definition(1, 0xFF10, &[(0, 2, 0x84)]);
+        fit.data(1, &[&[1, 2]]);
+        fit.data(
+            0,
+            &[&1_000_000_001u32.to_le_bytes(), &[0xFF], &1834u32.to_le_bytes()],
+        );
+        from_bytes(&fit.build()).unwrap()
+    }
+
+    #[test]
+    fn groups_messages_and_fields() {
+        let tree = Tree::build(&messages(), Raw::Keep);
+        assert_eq!(tree.label(Node::Root), "3 messages of 2 types");
+        assert_eq!(tree.children(Node::Root), [Node::Group(0), Node::Group(1)]);
+        assert_eq!(tree.label(Node::Group(0)), "record (20): 2 messages");
+        assert_eq!(
+            tree.label(Node::Group(1)),
+            "unknown_65296 (65296): 1 message"
+        );
+        assert_eq!(
+            tree.children(Node::Group(0)),
+            [Node::Message(0), Node::Message(2)]
+        );
+        assert_eq!(tree.label(Node::Message(2)), "record 1");
+        assert_eq!(tree.parent(Node::Message(2)), Some(Node::Group(0)));
+
+        let fields = tree.children(Node::Message(0));
+        let labels: Vec<String> =
+            fields.iter().map(|&field| tree.label(field)).collect();
+        assert_eq!(
+            labels,
+            [
+                "timestamp: 2021-09-08 01:46:40 UTC",
+                "heart_rate: 150 bpm",
+                "distance: 12.34 m",
+            ]
+        );
+        let distance = &tree.fields[2];
+        assert_eq!(distance.raw, Some(1234.0));
+        assert_eq!(distance.bytes, Some(1234u32.to_le_bytes().to_vec()));
+        assert_eq!(tree.parent(fields[2]), Some(Node::Message(0)));
+        assert_eq!(
+            tree.label(tree.children(Node::Message(1))[0]),
+            "unknown_0: 01 02"
+        );
+
+        let heart_rates = tree.search("HEART");
+        assert_eq!(heart_rates, [Node::Field(1), Node::Field(5)]);
+        assert!(!tree.fields[5].is_valid());
+        assert_eq!(tree.label(heart_rates[1]), "heart_rate: invalid");
+        assert_eq!(tree.search("18.34"), [Node::Field(6)]);
+        assert!(Tree::build(&messages(), Raw::Omit)
+            .fields
+            .iter()
+            .all(|f| f.bytes.is_none()));
+    }
+}
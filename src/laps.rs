@@ -0,0 +1,353 @@
+//! Lap summaries and helpers for relating laps to the rest
+//! of an activity.
+use duration::FitDuration;
+use profile::messages::{
+    Lap as LapField,
+    Message,
+};
+use series::RecordPoint;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// The commonly used fields of a single `Lap` message,
+/// scaled into their natural units.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lap {
+    /// End of the lap, seconds since the FIT epoch.
+    pub timestamp:          u32,
+    /// Start of the lap, seconds since the FIT epoch.
+    pub start_time:         Option<u32>,
+    /// Seconds.
+    pub total_elapsed_time: Option<f64>,
+    /// Seconds.
+    pub total_timer_time:   Option<f64>,
+    /// Metres.
+    pub total_distance:     Option<f64>,
+    /// Kilocalories.
+    pub total_calories:     Option<u16>,
+    /// Metres per second.
+    pub avg_speed:          Option<f64>,
+    /// Metres per second.
+    pub max_speed:          Option<f64>,
+    /// Beats per minute.
+    pub avg_heart_rate:     Option<u8>,
+    /// Beats per minute.
+    pub max_heart_rate:     Option<u8>,
+    /// Revolutions per minute.
+    pub avg_cadence:        Option<u8>,
+    /// Revolutions per minute.
+    pub max_cadence:        Option<u8>,
+    /// Watts.
+    pub avg_power:          Option<u16>,
+    /// Watts.
+    pub max_power:          Option<u16>,
+    /// Metres.
+    pub total_ascent:       Option<u16>,
+    /// Metres.
+    pub total_descent:      Option<u16>,
+}
+
+impl Lap {
+    /// Flatten a decoded `Lap` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Lap` message or
+    /// doesn't carry a valid timestamp.
+    pub fn from_message(mesg: &Data) -> Option<Lap> {
+        let mut lap = Lap::default();
+        let mut timestamp = None;
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Lap(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                LapField::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                LapField::StartTime(f) if f.raw_value.0 != u32::MAX => {
+                    lap.start_time = Some(f.raw_value.0)
+                },
+                LapField::TotalElapsedTime(f) if f.is_valid() => {
+                    lap.total_elapsed_time = Some(f.value())
+                },
+                LapField::TotalTimerTime(f) if f.is_valid() => {
+                    lap.total_timer_time = Some(f.value())
+                },
+                LapField::TotalDistance(f) if f.is_valid() => {
+                    lap.total_distance = Some(f.value())
+                },
+                LapField::TotalCalories(f) if f.is_valid() => {
+                    lap.total_calories = Some(f.raw_value.0)
+                },
+                LapField::AvgSpeed(f) if f.is_valid() => {
+                    lap.avg_speed = Some(f.value())
+                },
+                LapField::MaxSpeed(f) if f.is_valid() => {
+                    lap.max_speed = Some(f.value())
+                },
+                LapField::AvgHeartRate(f) if f.is_valid() => {
+                    lap.avg_heart_rate = Some(f.raw_value.0)
+                },
+                LapField::MaxHeartRate(f) if f.is_valid() => {
+                    lap.max_heart_rate = Some(f.raw_value.0)
+                },
+                LapField::AvgCadence(f) if f.is_valid() => {
+                    lap.avg_cadence = Some(f.raw_value.0)
+                },
+                LapField::MaxCadence(f) if f.is_valid() => {
+                    lap.max_cadence = Some(f.raw_value.0)
+                },
+                LapField::AvgPower(f) if f.is_valid() => {
+                    lap.avg_power = Some(f.raw_value.0)
+                },
+                LapField::MaxPower(f) if f.is_valid() => {
+                    lap.max_power = Some(f.raw_value.0)
+                },
+                LapField::TotalAscent(f) if f.is_valid() => {
+                    lap.total_ascent = Some(f.raw_value.0)
+                },
+                LapField::TotalDescent(f) if f.is_valid() => {
+                    lap.total_descent = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        timestamp.map(|timestamp| {
+            Lap {
+                timestamp,
+                ..lap
+            }
+        })
+    }
+
+    /// [`total_elapsed_time`](#structfield.total_elapsed_time)
+    /// as a duration.
+    pub fn elapsed_time(&self) -> Option<FitDuration> {
+        self.total_elapsed_time.and_then(FitDuration::from_secs_f64)
+    }
+
+    /// [`total_timer_time`](#structfield.total_timer_time)
+    /// as a duration.
+    pub fn timer_time(&self) -> Option<FitDuration> {
+        self.total_timer_time.and_then(FitDuration::from_secs_f64)
+    }
+
+    /// Whether `timestamp` falls within `[start_time,
+    /// timestamp]` for this lap. Laps without a start time
+    /// only contain their own end timestamp.
+    ///
+    /// A lap on its own can't know where the previous one
+    /// ended, so this is narrower than [`assign`], which
+    /// starts such a lap at the end of the previous one.
+    ///
+    /// [`assign`]: fn.assign.html
+    pub fn contains(&self, timestamp: u32) -> bool {
+        let start = self.start_time.unwrap_or(self.timestamp);
+        start <= timestamp && timestamp <= self.timestamp
+    }
+}
+
+/// Pair each record with the index of the lap it belongs
+/// to, in the order of `records`.
+///
+/// A record belongs to the first lap whose `[start_time,
+/// timestamp]` contains it, both ends included, so a record
+/// exactly on a boundary goes to the earlier lap. Laps
+/// without a start time begin where the previous one ended.
+/// Records outside every lap are left out.
+pub fn assign<'a>(
+    records: &'a [RecordPoint],
+    laps: &[Lap],
+) -> Vec<(usize, &'a RecordPoint)> {
+    let bounds = lap_bounds(laps);
+    records
+        .iter()
+        .filter_map(|record| {
+            lap_of(&bounds, record.timestamp)
+                .map(|lap_index| (lap_index, record))
+        })
+        .collect()
+}
+
+/// One series per lap of whatever `field` picks out of the
+/// records [`assign`]ed to it, skipping records without a
+/// value. Laps without any records get an empty series.
+///
+/// ```
+/// # use garminfit::{laps, series::RecordPoint};
+/// # fn split(records: &[RecordPoint], laps: &[laps::Lap]) {
+/// let heart_rates = laps::per_lap_series(records, laps, |record| {
+///     record.heart_rate.map(f64::from)
+/// });
+/// # }
+/// ```
+///
+/// [`assign`]: fn.assign.html
+pub fn per_lap_series<F>(
+    records: &[RecordPoint],
+    laps: &[Lap],
+    field: F,
+) -> Vec<Vec<f64>>
+where
+    F: Fn(&RecordPoint) -> Option<f64>,
+{
+    let mut series = vec![Vec::new(); laps.len()];
+    for (lap_index, record) in assign(records, laps) {
+        if let Some(value) = field(record) {
+            series[lap_index].push(value);
+        }
+    }
+    series
+}
+
+/// The index of the lap `timestamp` belongs to, by the
+/// rule [`assign`] describes, given the [`lap_bounds`].
+///
+/// [`assign`]: fn.assign.html
+/// [`lap_bounds`]: fn.lap_bounds.html
+fn lap_of(bounds: &[(u32, u32)], timestamp: u32) -> Option<usize> {
+    bounds
+        .iter()
+        .position(|&(start, end)| start <= timestamp && timestamp <= end)
+}
+
+/// `[start, end]` of each lap.
+fn lap_bounds(laps: &[Lap]) -> Vec<(u32, u32)> {
+    let mut previous_end = 0;
+    laps.iter()
+        .map(|lap| {
+            let start = lap.start_time.unwrap_or(previous_end);
+            previous_end = lap.timestamp;
+            (start, lap.timestamp)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use series::RecordTimeSeries;
+    use testutil::FitBuilder;
+    use File;
+
+    /// 300 one second records at 3 m/s in three 100 second
+    /// laps, `(start_time, timestamp)` 1000 to 1100, 1100
+    /// to 1200 and 1200 to 1300.
+    fn three_laps() -> File {
+        let mut fit = FitBuilder::new();
+        // Record: timestamp, distance, heart rate
+        fit.definition(0, 20, &[(253, 4, 0x86), (5, 4, 0x86), (3, 1, 0x02)]);
+        // Lap: timestamp, start time, elapsed time, distance
+        fit.definition(
+            1,
+            19,
+            &[(253, 4, 0x86), (2, 4, 0x86), (7, 4, 0x86), (9, 4, 0x86)],
+        );
+        for i in 0..300u32 {
+            let distance_cm = i * 300;
+            fit.data(
+                0,
+                &[
+                    &(1000 + i).to_le_bytes(),
+                    &distance_cm.to_le_bytes(),
+                    &[(100 + i / 100) as u8],
+                ],
+            );
+            if i % 100 == 99 {
+                let end = 1001 + i;
+                fit.data(
+                    1,
+                    &[
+                        &end.to_le_bytes(),
+                        &(end - 100).to_le_bytes(),
+                        &100_000u32.to_le_bytes(),
+                        &30_000u32.to_le_bytes(),
+                    ],
+                );
+            }
+        }
+        File::from_bytes(&fit.build()).unwrap()
+    }
+
+    #[test]
+    fn records_split_at_lap_boundaries() {
+        let file = three_laps();
+        let messages: Vec<Data> = file.messages().cloned().collect();
+        let records = RecordTimeSeries::from_messages(&messages).points;
+        let laps: Vec<Lap> =
+            messages.iter().filter_map(Lap::from_message).collect();
+        assert_eq!(laps.len(), 3);
+
+        let assigned = assign(&records, &laps);
+        assert_eq!(assigned.len(), records.len());
+        let mut counts = vec![0; laps.len()];
+        for &(lap_index, _) in &assigned {
+            counts[lap_index] += 1;
+        }
+        // 1100 and 1200 sit on boundaries and go to the
+        // earlier lap
+        assert_eq!(counts, vec![101, 100, 99]);
+        assert_eq!(counts.iter().sum::<usize>(), 300);
+
+        // Each lap covers the distance it claims, measured
+        // from the end of the previous one
+        let distances =
+            per_lap_series(&records, &laps, |record| record.distance);
+        let mut previous_end = 0.0;
+        for (lap, distances) in laps.iter().zip(&distances) {
+            let end = *distances.last().unwrap();
+            if lap.timestamp < 1300 {
+                assert_eq!(Some(end - previous_end), lap.total_distance);
+            }
+            assert!(
+                distances.len() as f64 <= lap.total_elapsed_time.unwrap() + 1.0
+            );
+            previous_end = end;
+        }
+
+        let heart_rates = per_lap_series(&records, &laps, |record| {
+            record.heart_rate.map(f64::from)
+        });
+        assert_eq!(heart_rates[1].first(), Some(&101.0));
+        assert_eq!(heart_rates[1].last(), Some(&102.0));
+    }
+
+    #[test]
+    fn laps_without_start_time_follow_the_previous_lap() {
+        let record = |timestamp| {
+            RecordPoint {
+                timestamp,
+                ..RecordPoint::default()
+            }
+        };
+        let records = vec![record(5), record(10), record(11), record(30)];
+        let laps = vec![
+            Lap {
+                timestamp: 10,
+                ..Lap::default()
+            },
+            Lap {
+                timestamp: 20,
+                ..Lap::default()
+            },
+        ];
+        let indices: Vec<(usize, u32)> = assign(&records, &laps)
+            .into_iter()
+            .map(|(lap_index, record)| (lap_index, record.timestamp))
+            .collect();
+        assert_eq!(indices, vec![(0, 5), (0, 10), (1, 11)]);
+        // On their own, the laps only contain their end
+        assert!(!laps[0].contains(5));
+        assert!(laps[0].contains(10));
+        assert!(!laps[1].contains(11));
+        assert_eq!(
+            per_lap_series(&records, &[], |_| Some(1.0)),
+            Vec::<Vec<f64>>::new()
+        );
+    }
+}
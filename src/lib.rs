@@ -1,67 +1,260 @@
+//! Reading Garmin FIT files.
+//!
+//! [`from_file`], [`from_bytes`] and [`from_reader`] decode
+//! a whole file with default options, check its CRC and
+//! give back its data messages, which is all most scripts
+//! need:
+//!
+//! ```no_run
+//! use garminfit::RecordTimeSeries;
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let messages = garminfit::from_file("activity.fit")?;
+//! let records = RecordTimeSeries::from_messages(&messages);
+//! println!("{} records", records.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The common types are re-exported here, and all at once
+//! by the [`prelude`]. [`File`] decodes a whole file keeping
+//! its definitions too, [`FitReader`] record by record; the
+//! other modules work on the decoded [`Data`] messages.
+//!
+//! [`from_file`]: fn.from_file.html
+//! [`from_bytes`]: fn.from_bytes.html
+//! [`from_reader`]: fn.from_reader.html
+//! [`prelude`]: prelude/index.html
+//! [`File`]: types/file/struct.File.html
+//! [`FitReader`]: reader/struct.FitReader.html
+//! [`Data`]: types/record/struct.Data.html
 #![allow(dead_code)]
 
+#[cfg(feature = "arrow")]
+extern crate arrow_array;
+#[cfg(feature = "arrow")]
+extern crate arrow_schema;
 extern crate byteorder;
 extern crate chrono;
+// pyo3's macros refer to `::core`, which 2015 edition crates
+// only have when it's declared
+#[cfg(feature = "python")]
+extern crate core;
 extern crate failure;
+#[cfg(feature = "gzip")]
+extern crate flate2;
+#[cfg(feature = "parquet")]
+extern crate parquet;
+#[cfg(feature = "python")]
+extern crate pyo3;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(feature = "tracing")]
+#[macro_use]
+extern crate tracing;
+#[cfg(feature = "zip")]
+extern crate zip;
 
 pub(crate) mod bits;
 pub(crate) mod dyncrc16;
 
+pub mod altitude;
+#[cfg(feature = "gzip")]
+pub mod archive;
+pub mod aviation;
+pub mod battery;
+pub mod cache;
+pub mod camera;
+pub mod capabilities;
+#[cfg(feature = "capi")]
+#[doc(hidden)]
+pub mod capi;
+pub mod compare;
+pub mod consts;
+pub mod course;
+pub mod crc;
+pub mod creator;
+pub mod devices;
+pub mod diagnose;
+pub mod directory;
+pub mod duration;
 pub mod error;
+pub mod events;
+pub mod exd;
+pub mod explore;
+pub mod export;
+pub mod gaps;
+pub mod geo;
+pub mod gps;
+pub mod health;
+pub mod index;
+pub mod inspect;
+pub mod laps;
+pub mod live;
+pub mod locations;
+pub mod lookup;
+pub mod monitoring;
+pub mod physiology;
+pub mod presence;
 pub mod profile;
+pub mod prelude;
+#[cfg(feature = "python")]
+#[doc(hidden)]
+pub mod python;
+pub mod race;
+pub mod reader;
+pub mod repair;
+pub mod report;
+pub mod reporter;
+#[cfg(feature = "serde")]
+pub mod schema;
+pub mod segment;
+pub mod series;
+pub mod session;
+pub mod slice;
+pub mod sport;
+pub mod stats;
+pub mod strength;
+pub mod strings;
+pub mod swim;
+#[cfg(feature = "testgen")]
+pub mod testgen;
+pub mod time;
+pub mod totals;
 pub mod types;
+pub mod utm;
+pub mod validate;
+pub mod workout;
+pub mod writer;
+
+#[cfg(test)]
+mod testutil;
 
 pub use types::{
     file::{
         self,
         File,
     },
-    record,
+    record::{
+        self,
+        Data,
+        Record,
+    },
 };
 
-pub use profile::messages;
+pub use devices::DeviceInfo;
+pub use error::{
+    Error,
+    ErrorKind,
+    Result,
+};
+pub use geo::{
+    GeoPoint,
+    Position,
+};
+pub use laps::Lap;
+pub use profile::{
+    messages::{
+        self,
+        Message,
+    },
+    types::{
+        DateTime,
+        LocalDateTime,
+        Manufacturer,
+        MesgNum,
+        Sport,
+        SubSport,
+    },
+};
+pub use reader::{
+    from_bytes,
+    from_file,
+    from_reader,
+    FitReader,
+};
+pub use series::{
+    RecordPoint,
+    RecordTimeSeries,
+};
+pub use session::SessionSummary;
+pub use sport::ActivityKind;
+pub use stats::DecodeStats;
+pub use types::field::Value;
 
 #[cfg(test)]
 mod tests {
     use std::{
-        fs::File,
-        io::{
-            Cursor,
-            Read,
-        },
-        path::Path,
+        fs,
+        io::Cursor,
     };
-    use test::Bencher;
 
-    // Benchmark FIT file decoding. Takes the std `Bencher` and
-    // the path to a `.fit` file to decode. Reads the file once
-    // then puts it behind a cursor for benchmarking.
-    fn bench_file_decoding(b: &mut Bencher, file: &Path) {
-        let mut file = File::open(file).unwrap();
-        let data = {
-            let mut bytes = Vec::new();
-            file.read_to_end(&mut bytes).unwrap();
-            Cursor::new(bytes)
+    // TODO: should get a NotFIT error when opening a non-fit
+    // file.
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    // Decoding keeps no shared state, so files can be decoded
+    // concurrently and everything decoded handed between
+    // threads.
+    #[test]
+    fn decoded_types_are_send_and_sync() {
+        use super::*;
+        use profile::{
+            base::Uint8,
+            types::DateTime,
         };
-        // NOTE: is it an issue that this benchmarks the clone?
-        b.iter(|| super::File::decode(&mut data.clone()).unwrap());
+
+        assert_send_sync::<FitReader<fs::File>>();
+        assert_send_sync::<FitReader<Cursor<Vec<u8>>>>();
+        assert_send_sync::<super::File>();
+        assert_send_sync::<record::Record>();
+        assert_send_sync::<record::Message>();
+        assert_send_sync::<record::Data>();
+        assert_send_sync::<messages::Message>();
+        assert_send_sync::<messages::Field<Uint8>>();
+        assert_send_sync::<messages::Field<DateTime>>();
+        assert_send_sync::<error::Error>();
+        assert_send_sync::<stats::DecodeStats>();
+
+        assert_send_sync::<cache::FitCache>();
+        assert_send_sync::<devices::DeviceInfo>();
+        assert_send_sync::<events::Parsed>();
+        assert_send_sync::<index::Index>();
+        assert_send_sync::<laps::Lap>();
+        assert_send_sync::<series::RecordTimeSeries>();
+        assert_send_sync::<workout::WorkoutPlan>();
     }
 
-    macro_rules! bench_file {
-        ($name:ident, $path:expr) => {
-            #[bench]
-            fn $name(b: &mut Bencher) {
-                bench_file_decoding(
-                    b,
-                    &Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
-                );
-            }
+    #[test]
+    fn decodes_on_many_threads() {
+        use std::{
+            sync::Arc,
+            thread,
         };
-    }
+        use testutil::FitBuilder;
 
-    bench_file!(bench_python_fitparse_1, "testdata/python_fitparse_1.fit");
-    bench_file!(bench_python_fitparse_2, "testdata/python_fitparse_2.fit");
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        for i in 0..100u32 {
+            fit.data(0, &[&(1000 + i).to_le_bytes()[..], &[120]]);
+        }
+        let bytes = Arc::new(fit.build());
 
-    // TODO: should get a NotFIT error when opening a non-fit
-    // file.
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let bytes = Arc::clone(&bytes);
+                thread::spawn(move || super::File::from_bytes(&bytes))
+            })
+            .collect();
+        for handle in handles {
+            let file = handle.join().unwrap().unwrap();
+            assert_eq!(file.messages().count(), 100);
+        }
+    }
 }
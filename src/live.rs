@@ -0,0 +1,572 @@
+//! Activity statistics kept up to date as messages arrive,
+//! for a live display or for showing progress through a
+//! file too big to hold.
+//!
+//! An [`Aggregator`] is fed data messages one at a time and
+//! takes the same memory however many it's given. It reads
+//! records as [`RecordPoint`] does, so enhanced fields win
+//! and invalid values are left out, and follows the timer
+//! through `Event` messages as [`events::pause_intervals`]
+//! does. Records logged while the timer is stopped count
+//! towards the elapsed time and the current values only.
+//!
+//! ```no_run
+//! use garminfit::{
+//!     live::Aggregator,
+//!     reader::FitReader,
+//!     types::record::Message,
+//! };
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let file = std::fs::File::open("activity.fit").expect("a readable file");
+//! let mut live = Aggregator::new();
+//! for record in FitReader::new(file)? {
+//!     if let Message::Data(mesg) = record?.content {
+//!         live.push(&mesg);
+//!         println!("{:.0} m in {:.0} s", live.distance_m(), live.timer_s());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Aggregator`]: struct.Aggregator.html
+//! [`RecordPoint`]: ../series/struct.RecordPoint.html
+//! [`events::pause_intervals`]: ../events/fn.pause_intervals.html
+use events::{
+    self,
+    Parsed,
+};
+use geo::Position;
+use profile::types::EventType;
+use series::RecordPoint;
+use std::collections::VecDeque;
+use types::record::Data;
+
+/// Metres of climb or descent that must add up before
+/// they count, by default.
+pub const ASCENT_THRESHOLD_M: f64 = 2.0;
+
+/// Metres per second from which a record counts as moving.
+pub const MOVING_SPEED_MS: f64 = 0.5;
+
+/// One second samples of power averaged for normalized
+/// power.
+const NP_WINDOW: usize = 30;
+
+/// The count, mean and maximum of a stream of values, with
+/// Welford's update for the mean and variance.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunningStats {
+    count: u64,
+    mean:  f64,
+    m2:    f64,
+    max:   f64,
+}
+
+impl RunningStats {
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        if self.count == 1 || value > self.max {
+            self.max = value;
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// `None` until a value has been pushed.
+    pub fn mean(&self) -> Option<f64> {
+        Some(self.mean).filter(|_| self.count > 0)
+    }
+
+    /// `None` until a value has been pushed.
+    pub fn max(&self) -> Option<f64> {
+        Some(self.max).filter(|_| self.count > 0)
+    }
+
+    /// The population standard deviation, `None` until a
+    /// value has been pushed.
+    pub fn std_dev(&self) -> Option<f64> {
+        Some((self.m2 / self.count as f64).sqrt()).filter(|_| self.count > 0)
+    }
+}
+
+/// Running totals, averages and current values of an
+/// activity, updated by each message [`push`]ed.
+///
+/// [`push`]: #method.push
+#[derive(Debug, Clone)]
+pub struct Aggregator {
+    ascent_threshold_m: f64,
+    first:              Option<u32>,
+    last:               Option<u32>,
+    /// Since when the timer has been stopped.
+    paused_at:          Option<u32>,
+    /// Seconds in pauses that have ended.
+    paused_s:           u32,
+    moving_s:           f64,
+    /// The latest `distance` field.
+    odometer_m:         Option<f64>,
+    /// Along the positions, for records without a distance.
+    track_m:            f64,
+    position:           Option<Position>,
+    heart_rate:         RunningStats,
+    cadence:            RunningStats,
+    power:              RunningStats,
+    speed:              RunningStats,
+    /// The altitude climbs and descents are measured from.
+    reference_m:        Option<f64>,
+    ascent_m:           f64,
+    descent_m:          f64,
+    /// One power sample a second over the last
+    /// `NP_WINDOW` seconds of the timer, and their sum.
+    window:             VecDeque<f64>,
+    window_sum:         f64,
+    last_power:         Option<u32>,
+    /// Fourth powers of the rolling average.
+    rolling:            RunningStats,
+    current:            Option<RecordPoint>,
+}
+
+impl Default for Aggregator {
+    fn default() -> Self {
+        Aggregator {
+            ascent_threshold_m: ASCENT_THRESHOLD_M,
+            first:              None,
+            last:               None,
+            paused_at:          None,
+            paused_s:           0,
+            moving_s:           0.0,
+            odometer_m:         None,
+            track_m:            0.0,
+            position:           None,
+            heart_rate:         RunningStats::default(),
+            cadence:            RunningStats::default(),
+            power:              RunningStats::default(),
+            speed:              RunningStats::default(),
+            reference_m:        None,
+            ascent_m:           0.0,
+            descent_m:          0.0,
+            window:             VecDeque::with_capacity(NP_WINDOW + 1),
+            window_sum:         0.0,
+            last_power:         None,
+            rolling:            RunningStats::default(),
+            current:            None,
+        }
+    }
+}
+
+impl Aggregator {
+    pub fn new() -> Aggregator {
+        Aggregator::default()
+    }
+
+    /// Count changes in altitude once they add up to
+    /// `threshold_m`, as
+    /// [`AltitudeSeries::ascent_descent_m`](../altitude/
+    /// struct.AltitudeSeries.html#method.ascent_descent_m)
+    /// does.
+    pub fn with_ascent_threshold(mut self, threshold_m: f64) -> Aggregator {
+        self.ascent_threshold_m = threshold_m;
+        self
+    }
+
+    /// Take in the next data message. Only `Record` and
+    /// timer `Event` messages change anything.
+    pub fn push(&mut self, mesg: &Data) {
+        if let Some(Parsed::Timer(event_type)) = events::parse(mesg) {
+            if let Some(timestamp) = events::timestamp(mesg) {
+                self.timer(timestamp, event_type);
+            }
+        }
+        else if let Some(point) = RecordPoint::from_message(mesg) {
+            self.record(point);
+        }
+    }
+
+    fn timer(&mut self, timestamp: u32, event_type: EventType) {
+        self.seen(timestamp);
+        match event_type {
+            EventType::Start => {
+                if let Some(start) = self.paused_at.take() {
+                    self.paused_s += timestamp.saturating_sub(start);
+                }
+            },
+            EventType::Stop
+            | EventType::StopAll
+            | EventType::StopDisable
+            | EventType::StopDisableAll => {
+                self.paused_at = self.paused_at.or(Some(timestamp))
+            },
+            _ => (),
+        }
+    }
+
+    fn record(&mut self, point: RecordPoint) {
+        let previous = self.last;
+        self.seen(point.timestamp);
+        let paused = self.paused_at.is_some();
+
+        if let Some(distance) = point.distance {
+            self.odometer_m = Some(distance);
+        }
+        if let Some(position) = point.position_semicircles() {
+            if let Some(previous) = self.position {
+                self.track_m += previous.distance_to(&position);
+            }
+            self.position = Some(position);
+        }
+        if let Some(altitude) = point.enhanced_altitude.or(point.altitude) {
+            self.climb(altitude);
+        }
+        if paused {
+            self.current = Some(point);
+            return;
+        }
+
+        let speed = point.enhanced_speed.or(point.speed);
+        if let (Some(previous), Some(speed)) = (previous, speed) {
+            if speed >= MOVING_SPEED_MS {
+                self.moving_s +=
+                    f64::from(point.timestamp.saturating_sub(previous));
+            }
+        }
+        if let Some(speed) = speed {
+            self.speed.push(speed);
+        }
+        if let Some(heart_rate) = point.heart_rate {
+            self.heart_rate.push(f64::from(heart_rate));
+        }
+        if let Some(cadence) = point.cadence {
+            self.cadence.push(f64::from(cadence));
+        }
+        if let Some(power) = point.power {
+            self.power.push(f64::from(power));
+            self.rolling_power(point.timestamp, f64::from(power));
+        }
+        self.current = Some(point);
+    }
+
+    fn seen(&mut self, timestamp: u32) {
+        self.first = self.first.or(Some(timestamp));
+        self.last =
+            Some(self.last.map_or(timestamp, |last| last.max(timestamp)));
+    }
+
+    fn climb(&mut self, altitude: f64) {
+        let reference = match self.reference_m {
+            Some(reference) => reference,
+            None => {
+                self.reference_m = Some(altitude);
+                return;
+            },
+        };
+        if altitude - reference >= self.ascent_threshold_m {
+            self.ascent_m += altitude - reference;
+            self.reference_m = Some(altitude);
+        }
+        else if reference - altitude >= self.ascent_threshold_m {
+            self.descent_m += reference - altitude;
+            self.reference_m = Some(altitude);
+        }
+    }
+
+    /// Keep the last `NP_WINDOW` samples of power, and once
+    /// there are that many, the fourth power of their
+    /// average.
+    fn rolling_power(&mut self, timestamp: u32, power: f64) {
+        // One sample a second
+        if self.last_power.is_some_and(|last| timestamp <= last) {
+            return;
+        }
+        self.last_power = Some(timestamp);
+        self.window.push_back(power);
+        self.window_sum += power;
+        if self.window.len() > NP_WINDOW {
+            self.window_sum -= self.window.pop_front().unwrap_or(0.0);
+        }
+        if self.window.len() == NP_WINDOW {
+            let average = self.window_sum / NP_WINDOW as f64;
+            self.rolling.push(average.powi(4));
+        }
+    }
+
+    /// Metres, from the latest `distance` field, or along
+    /// the positions if the records have none.
+    pub fn distance_m(&self) -> f64 {
+        self.odometer_m.unwrap_or(self.track_m)
+    }
+
+    /// Seconds from the first message to the latest,
+    /// pauses included.
+    pub fn elapsed_s(&self) -> f64 {
+        match (self.first, self.last) {
+            (Some(first), Some(last)) => f64::from(last - first),
+            _ => 0.0,
+        }
+    }
+
+    /// Seconds with the timer running.
+    pub fn timer_s(&self) -> f64 {
+        let pausing = match (self.paused_at, self.last) {
+            (Some(paused_at), Some(last)) => last.saturating_sub(paused_at),
+            _ => 0,
+        };
+        (self.elapsed_s() - f64::from(self.paused_s + pausing)).max(0.0)
+    }
+
+    /// Seconds with the timer running and the speed at
+    /// least [`MOVING_SPEED_MS`](constant.MOVING_SPEED_MS.
+    /// html), counting each record's time since the one
+    /// before.
+    pub fn moving_s(&self) -> f64 {
+        self.moving_s
+    }
+
+    /// Beats per minute.
+    pub fn heart_rate(&self) -> &RunningStats {
+        &self.heart_rate
+    }
+
+    /// Revolutions or strides per minute.
+    pub fn cadence(&self) -> &RunningStats {
+        &self.cadence
+    }
+
+    /// Watts.
+    pub fn power(&self) -> &RunningStats {
+        &self.power
+    }
+
+    /// Metres per second, from the enhanced field if there
+    /// is one.
+    pub fn speed(&self) -> &RunningStats {
+        &self.speed
+    }
+
+    /// Metres climbed.
+    pub fn ascent_m(&self) -> f64 {
+        self.ascent_m
+    }
+
+    /// Metres descended.
+    pub fn descent_m(&self) -> f64 {
+        self.descent_m
+    }
+
+    /// Watts: the fourth root of the mean fourth power of
+    /// the rolling average of 30 one second samples of
+    /// power, so pauses are left out. `None` until there
+    /// have been 30.
+    pub fn normalized_power(&self) -> Option<f64> {
+        self.rolling.mean().map(|mean| mean.powf(0.25))
+    }
+
+    /// The latest record.
+    pub fn current(&self) -> Option<&RecordPoint> {
+        self.current.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use altitude::{
+        self,
+        Policy,
+    };
+    use series::RecordTimeSeries;
+    use session::SessionSummary;
+    use testutil::mesg;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-6 * b.abs().max(1.0)
+    }
+
+    /// 30 s rolling averages over one second samples.
+    fn batch_normalized_power(power: &[f64]) -> Option<f64> {
+        let rolling: Vec<f64> = power
+            .windows(NP_WINDOW)
+            .map(|window| window.iter().sum::<f64>() / window.len() as f64)
+            .collect();
+        if rolling.is_empty() {
+            return None;
+        }
+        let mean = rolling.iter().map(|p| p.powi(4)).sum::<f64>()
+            / rolling.len() as f64;
+        Some(mean.powf(0.25))
+    }
+
+    /// Feed `messages` through an aggregator and check it
+    /// against the batch analysis of the same messages.
+    fn matches_batch(messages: &[Data]) -> Aggregator {
+        let mut live = Aggregator::new();
+        for mesg in messages {
+            live.push(mesg);
+        }
+
+        let events: Vec<Data> = messages
+            .iter()
+            .filter(|mesg| events::is_event(mesg))
+            .cloned()
+            .collect();
+        let session = messages.iter().find_map(SessionSummary::from_message);
+        if let Some(session) = session {
+            assert!(close(live.elapsed_s(), session.total_elapsed_s));
+            assert!(close(live.distance_m(), session.total_distance_m));
+            assert!(close(
+                live.timer_s(),
+                events::active_time_s(&events, session.total_elapsed_s)
+            ));
+        }
+
+        let records = RecordTimeSeries::from_messages(messages);
+        let stats = |values: Vec<f64>| {
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            let max = values.iter().cloned().fold(f64::MIN, f64::max);
+            (mean, max)
+        };
+        let heart_rate: Vec<f64> = records
+            .iter()
+            .filter_map(|p| p.heart_rate.map(f64::from))
+            .collect();
+        let (mean, max) = stats(heart_rate);
+        assert!(close(live.heart_rate().mean().unwrap(), mean));
+        assert_eq!(live.heart_rate().max(), Some(max));
+        let speed: Vec<f64> = records
+            .iter()
+            .filter_map(|p| p.enhanced_speed.or(p.speed))
+            .collect();
+        let (mean, max) = stats(speed);
+        assert!(close(live.speed().mean().unwrap(), mean));
+        assert_eq!(live.speed().max(), Some(max));
+
+        let power: Vec<f64> =
+            records.iter().filter_map(|p| p.power.map(f64::from)).collect();
+        if !power.is_empty() {
+            let (mean, max) = stats(power.clone());
+            assert!(close(live.power().mean().unwrap(), mean));
+            assert_eq!(live.power().max(), Some(max));
+            assert!(close(
+                live.normalized_power().unwrap(),
+                batch_normalized_power(&power).unwrap()
+            ));
+        }
+
+        let (ascent, descent) = altitude::select(messages, Policy::Barometric)
+            .ascent_descent_m(ASCENT_THRESHOLD_M);
+        assert!(close(live.ascent_m(), ascent));
+        assert!(close(live.descent_m(), descent));
+        assert_eq!(live.current(), records.iter().last());
+        live
+    }
+
+    fn record(
+        timestamp: u32,
+        distance_cm: u32,
+        heart_rate: u8,
+        power: u16,
+    ) -> Data {
+        mesg(20)
+            .u32(253, timestamp)
+            .u32(5, distance_cm)
+            .u16(6, 5000)
+            .u8(3, heart_rate)
+            .u16(7, power)
+            .u16(2, 2500 + (timestamp % 40) as u16 * 5)
+            .build()
+    }
+
+    fn timer(timestamp: u32, event_type: u8) -> Data {
+        mesg(21).u32(253, timestamp).u8(0, 0).u8(1, event_type).build()
+    }
+
+    #[test]
+    fn pauses_and_rolling_power() {
+        let mut messages = vec![timer(1000, 0)];
+        for t in 0..60 {
+            messages.push(record(
+                1000 + t,
+                t * 500,
+                120 + (t % 7) as u8,
+                200 + (t % 13) as u16 * 10,
+            ));
+        }
+        // Paused for two minutes, without records
+        messages.push(timer(1060, 4));
+        messages.push(timer(1180, 0));
+        for t in 0..40 {
+            messages.push(record(1180 + t, 30_500 + t * 500, 150, 300));
+        }
+        messages.push(timer(1220, 4));
+        messages.push(
+            mesg(18)
+                .u32(253, 1220)
+                .u32(2, 1000)
+                .u8(5, 2)
+                .u32(7, 220_000)
+                .u32(8, 100_000)
+                .u32(9, 50_000)
+                .build(),
+        );
+
+        let live = matches_batch(&messages);
+        assert_eq!(live.elapsed_s(), 220.0);
+        assert_eq!(live.timer_s(), 100.0);
+        assert_eq!(live.moving_s(), 98.0);
+        assert_eq!(live.distance_m(), 500.0);
+        assert_eq!(live.power().count(), 100);
+        assert!(
+            live.normalized_power().unwrap() > live.power().mean().unwrap()
+        );
+    }
+
+    #[test]
+    fn empty_and_paused() {
+        let mut live = Aggregator::new();
+        assert_eq!(live.distance_m(), 0.0);
+        assert_eq!(live.heart_rate().mean(), None);
+        assert_eq!(live.normalized_power(), None);
+        assert!(live.current().is_none());
+
+        live.push(&timer(1000, 0));
+        live.push(&record(1010, 5000, 130, 250));
+        live.push(&timer(1020, 4));
+        // Logged while stopped: current, but not averaged
+        live.push(&record(1030, 5000, 90, 0));
+        assert_eq!(live.elapsed_s(), 30.0);
+        assert_eq!(live.timer_s(), 20.0);
+        assert_eq!(live.heart_rate().mean(), Some(130.0));
+        assert_eq!(live.current().and_then(|p| p.heart_rate), Some(90));
+
+        let mut stats = RunningStats::default();
+        for &value in &[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+            stats.push(value);
+        }
+        assert_eq!((stats.mean(), stats.max()), (Some(5.0), Some(9.0)));
+        assert_eq!(stats.std_dev(), Some(2.0));
+    }
+
+    #[cfg(feature = "testgen")]
+    #[test]
+    fn generated_files_match_batch() {
+        use reader::from_bytes;
+        use testgen::{
+            generate,
+            Shape,
+        };
+
+        for seed in 0..5 {
+            let shape = Shape {
+                laps: 3,
+                ..Shape::default()
+            };
+            matches_batch(&from_bytes(&generate(&shape, seed)).unwrap());
+        }
+    }
+}
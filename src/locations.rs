@@ -0,0 +1,296 @@
+//! Saved waypoints, as handhelds and watches keep them in
+//! `Locations.fit`.
+//!
+//! Each waypoint is a `Location` message. Garmin doesn't
+//! document the message in the published profile, so its
+//! fields follow what devices write: a name, a position, an
+//! altitude, a map symbol, a description and the time the
+//! waypoint was saved. The symbol is the device's icon
+//! number and is kept raw; there's no published list of
+//! them to map it to GPX `<sym>` names.
+use geo::Position;
+use profile::{
+    messages::{
+        Location,
+        Message,
+    },
+    types::DateTime,
+};
+use std::fmt::Write;
+use strings::escape_xml;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// The fields of a `Location` message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waypoint {
+    pub name:          Option<String>,
+    pub description:   Option<String>,
+    pub position:      Position,
+    /// Metres.
+    pub altitude_m:    Option<f64>,
+    /// The device's icon number.
+    pub symbol:        Option<u16>,
+    /// Seconds since the FIT epoch the waypoint was saved.
+    pub timestamp:     Option<u32>,
+    pub message_index: Option<u16>,
+}
+
+impl Waypoint {
+    /// Flatten a decoded `Location` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Location` message
+    /// or has no valid position.
+    pub fn from_message(mesg: &Data) -> Option<Waypoint> {
+        let (mut lat, mut lon) = (None, None);
+        let mut waypoint = Waypoint {
+            name:          None,
+            description:   None,
+            position:      Position::default(),
+            altitude_m:    None,
+            symbol:        None,
+            timestamp:     None,
+            message_index: None,
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Location(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                Location::Name(f) => waypoint.name = non_empty(&f.raw_value.0),
+                Location::Description(f) => {
+                    waypoint.description = non_empty(&f.raw_value.0)
+                },
+                Location::PositionLat(f) => lat = Some(f.raw_value.0),
+                Location::PositionLong(f) => lon = Some(f.raw_value.0),
+                Location::Altitude(f) if f.is_valid() => {
+                    waypoint.altitude_m = Some(f.value())
+                },
+                Location::Symbol(f) if f.is_valid() => {
+                    waypoint.symbol = Some(f.raw_value.0)
+                },
+                Location::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    waypoint.timestamp = Some(f.raw_value.0)
+                },
+                Location::MessageIndex(f) => {
+                    waypoint.message_index = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        waypoint.position = Position::new(lat?, lon?);
+        if waypoint.position.is_valid() {
+            Some(waypoint)
+        }
+        else {
+            None
+        }
+    }
+}
+
+/// The waypoints of a `Locations.fit` file, in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Waypoints(pub Vec<Waypoint>);
+
+impl Waypoints {
+    /// Collect the waypoints in `messages`, skipping
+    /// `Location` messages without a valid position.
+    pub fn from_messages(messages: &[Data]) -> Waypoints {
+        Waypoints(messages.iter().filter_map(Waypoint::from_message).collect())
+    }
+
+    /// The waypoints as a GPX 1.1 document of `<wpt>`
+    /// elements.
+    ///
+    /// Names and descriptions are escaped, and fields the
+    /// waypoint doesn't have are left out.
+    pub fn to_gpx(&self) -> String {
+        let mut gpx = String::from(concat!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+            "\n",
+            r#"<gpx version="1.1" creator="garminfit" "#,
+            r#"xmlns="http://www.topografix.com/GPX/1/1">"#,
+            "\n",
+        ));
+        // Writing to a String can't fail
+        for waypoint in &self.0 {
+            let point = waypoint.position.to_degrees();
+            let _ =
+                write!(gpx, r#"<wpt lat="{}" lon="{}">"#, point.lat, point.lon);
+            if let Some(altitude) = waypoint.altitude_m {
+                let _ = write!(gpx, "<ele>{}</ele>", altitude);
+            }
+            let time = waypoint
+                .timestamp
+                .and_then(|timestamp| DateTime(timestamp).to_utc());
+            if let Some(time) = time {
+                let _ = write!(
+                    gpx,
+                    "<time>{}</time>",
+                    time.format("%Y-%m-%dT%H:%M:%SZ")
+                );
+            }
+            if let Some(name) = &waypoint.name {
+                let _ = write!(gpx, "<name>{}</name>", escape_xml(name));
+            }
+            if let Some(description) = &waypoint.description {
+                let _ = write!(gpx, "<desc>{}</desc>", escape_xml(description));
+            }
+            gpx.push_str("</wpt>\n");
+        }
+        gpx.push_str("</gpx>\n");
+        gpx
+    }
+}
+
+/// Strings are null padded to their field size.
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim_end_matches('\0');
+    if s.is_empty() {
+        None
+    }
+    else {
+        Some(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate roxmltree;
+
+    use super::*;
+    use testutil::FitBuilder;
+    use types::file::File;
+
+    /// A `Locations.fit` with a summit, a hut without an
+    /// altitude and a waypoint that lost its position.
+    fn locations() -> Vec<Data> {
+        let string = |s: &str| {
+            let mut field = s.as_bytes().to_vec();
+            field.resize(16, 0);
+            field
+        };
+        let position = Position::from_degrees(46.5576, 7.9806);
+        let hut = Position::from_degrees(46.5478, 7.9628);
+
+        let mut fit = FitBuilder::new();
+        fit.definition(
+            0,
+            29,
+            &[
+                (254, 2, 0x84),
+                (0, 16, 7),
+                (1, 4, 0x85),
+                (2, 4, 0x85),
+                (3, 2, 0x84),
+                (4, 2, 0x84),
+                (6, 16, 7),
+                (253, 4, 0x86),
+            ],
+        );
+        fit.data(
+            0,
+            &[
+                &0u16.to_le_bytes(),
+                &string("Jungfrau"),
+                &position.lat_semicircles.to_le_bytes(),
+                &position.lon_semicircles.to_le_bytes(),
+                &16u16.to_le_bytes(),
+                // (4158 + 500) * 5
+                &23290u16.to_le_bytes(),
+                &string("Summit & <view>"),
+                &1_000_000_000u32.to_le_bytes(),
+            ],
+        );
+        fit.data(
+            0,
+            &[
+                &1u16.to_le_bytes(),
+                &string("Mönchsjoch"),
+                &hut.lat_semicircles.to_le_bytes(),
+                &hut.lon_semicircles.to_le_bytes(),
+                &u16::MAX.to_le_bytes(),
+                &u16::MAX.to_le_bytes(),
+                &string(""),
+                &u32::MAX.to_le_bytes(),
+            ],
+        );
+        fit.data(
+            0,
+            &[
+                &2u16.to_le_bytes(),
+                &string("Lost"),
+                &i32::MAX.to_le_bytes(),
+                &i32::MAX.to_le_bytes(),
+                &u16::MAX.to_le_bytes(),
+                &u16::MAX.to_le_bytes(),
+                &string(""),
+                &u32::MAX.to_le_bytes(),
+            ],
+        );
+        let file = File::from_bytes(&fit.build()).unwrap();
+        file.messages().cloned().collect()
+    }
+
+    #[test]
+    fn decodes_waypoints() {
+        let Waypoints(waypoints) = Waypoints::from_messages(&locations());
+        assert_eq!(waypoints.len(), 2);
+
+        let summit = &waypoints[0];
+        assert_eq!(summit.name.as_deref(), Some("Jungfrau"));
+        assert_eq!(summit.description.as_deref(), Some("Summit & <view>"));
+        assert_eq!(summit.position, Position::from_degrees(46.5576, 7.9806));
+        assert_eq!(summit.altitude_m, Some(4158.0));
+        assert_eq!(summit.symbol, Some(16));
+        assert_eq!(summit.timestamp, Some(1_000_000_000));
+        assert_eq!(summit.message_index, Some(0));
+
+        let hut = &waypoints[1];
+        assert_eq!(hut.name.as_deref(), Some("Mönchsjoch"));
+        assert_eq!(hut.description, None);
+        assert_eq!(hut.altitude_m, None);
+        assert_eq!(hut.symbol, None);
+        assert_eq!(hut.timestamp, None);
+        assert_eq!(hut.message_index, Some(1));
+    }
+
+    #[test]
+    fn exports_gpx_waypoints() {
+        let gpx = Waypoints::from_messages(&locations()).to_gpx();
+        let doc = roxmltree::Document::parse(&gpx).unwrap();
+        let wpts: Vec<_> = doc
+            .root_element()
+            .children()
+            .filter(|n| n.has_tag_name("wpt"))
+            .collect();
+        assert_eq!(wpts.len(), 2);
+
+        let child = |wpt: &roxmltree::Node, name: &str| {
+            wpt.children()
+                .find(|n| n.has_tag_name(name))
+                .and_then(|n| n.text().map(str::to_owned))
+        };
+        let summit = &wpts[0];
+        let lat: f64 = summit.attribute("lat").unwrap().parse().unwrap();
+        let lon: f64 = summit.attribute("lon").unwrap().parse().unwrap();
+        assert!((lat - 46.5576).abs() < 1e-6 && (lon - 7.9806).abs() < 1e-6);
+        assert_eq!(child(summit, "ele").as_deref(), Some("4158"));
+        assert_eq!(
+            child(summit, "time").as_deref(),
+            Some("2021-09-08T01:46:40Z")
+        );
+        assert_eq!(child(summit, "name").as_deref(), Some("Jungfrau"));
+        assert_eq!(child(summit, "desc").as_deref(), Some("Summit & <view>"));
+
+        let hut = &wpts[1];
+        assert_eq!(child(hut, "name").as_deref(), Some("Mönchsjoch"));
+        assert!(child(hut, "ele").is_none() && child(hut, "time").is_none());
+    }
+}
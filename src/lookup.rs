@@ -0,0 +1,90 @@
+//! Resolving references between messages.
+//!
+//! Messages such as workout steps, laps and course points
+//! are numbered by their `message_index` field, and other
+//! messages refer to them by that number.
+use std::collections::BTreeMap;
+
+use types::record::Data;
+
+/// The message with global number `mesg_num` whose
+/// `message_index` is `index`.
+///
+/// Only the index bits of `message_index` are compared, so
+/// a selected message is still found by its plain index.
+/// Scans `messages` from the start; when looking up many
+/// references, group the messages with
+/// [`index_messages_by_type`] first.
+///
+/// [`index_messages_by_type`]: fn.index_messages_by_type.html
+pub fn find_by_message_index(
+    messages: &[Data],
+    mesg_num: u16,
+    index: u16,
+) -> Option<&Data> {
+    messages.iter().find(|mesg| {
+        mesg.0.first().map(|field| field.mesg_num()) == Some(mesg_num)
+            && mesg
+                .0
+                .iter()
+                .find_map(|field| field.message_index())
+                .map(|message_index| message_index.index())
+                == Some(index)
+    })
+}
+
+/// `messages` grouped by global message number, each group
+/// in file order. Groups iterate by ascending message
+/// number.
+///
+/// Messages without any fields are left out.
+pub fn index_messages_by_type(messages: &[Data]) -> BTreeMap<u16, Vec<&Data>> {
+    let mut by_type: BTreeMap<u16, Vec<&Data>> = BTreeMap::new();
+    for mesg in messages {
+        if let Some(field) = mesg.0.first() {
+            by_type.entry(field.mesg_num()).or_default().push(mesg);
+        }
+    }
+    by_type
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profile::types::MessageIndex;
+    use testutil::mesg;
+    use workout::WorkoutStep;
+
+    fn step(index: u16, name: &[u8]) -> Data {
+        mesg(27).u16(254, index).bytes(0, name).u8(1, 0).build()
+    }
+
+    #[test]
+    fn finds_workout_step_by_index() {
+        let messages = vec![
+            mesg(26).bytes(8, b"Intervals\0").u16(6, 3).build(),
+            step(0, b"Warm up\0"),
+            // A lap numbered like the step looked for
+            mesg(19).u16(254, 1).u32(253, 1000).build(),
+            step(1, b"Work\0\0\0\0"),
+            step(MessageIndex::SELECTED | 2, b"Rest\0\0\0\0"),
+        ];
+
+        let work = find_by_message_index(&messages, 27, 1).unwrap();
+        assert!(std::ptr::eq(work, &messages[3]));
+        let work = WorkoutStep::from_message(work).unwrap();
+        assert_eq!(work.name.as_deref(), Some("Work"));
+
+        let rest = find_by_message_index(&messages, 27, 2).unwrap();
+        assert!(std::ptr::eq(rest, &messages[4]));
+        assert!(find_by_message_index(&messages, 27, 3).is_none());
+        assert!(find_by_message_index(&messages, 19, 0).is_none());
+
+        let by_type = index_messages_by_type(&messages);
+        assert_eq!(by_type.len(), 3);
+        assert_eq!(by_type[&27].len(), 3);
+        assert!(std::ptr::eq(by_type[&19][0], &messages[2]));
+        let first = by_type[&27][0];
+        assert!(std::ptr::eq(first, &messages[1]));
+    }
+}
@@ -0,0 +1,417 @@
+//! Daily activity tracking, from the monitoring files
+//! devices write throughout the day.
+use chrono::NaiveDate;
+use profile::{
+    messages::{
+        Goal as GoalField,
+        Message,
+        Monitoring,
+        MonitoringInfo,
+    },
+    types::{
+        ActivityType,
+        DateTime,
+        Goal,
+        LocalDateTime,
+    },
+};
+use std::collections::BTreeMap;
+use time::FIT_EPOCH;
+use types::record::Data;
+
+/// Seconds without a step after which devices show the move
+/// alert.
+pub const MOVE_ALERT_S: u32 = 3600;
+
+const DAY_S: u32 = 24 * 3600;
+
+/// Steps taken during one day against that day's goal,
+/// and the day's intensity minutes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyGoalProgress {
+    /// Unix time the day started at.
+    pub date_unix:            i64,
+    /// The day's date where the device was, or `None` if it
+    /// only knew the time since it was powered on.
+    pub date:                 Option<NaiveDate>,
+    /// Zero if no step goal applies to the day.
+    pub step_goal:            u32,
+    pub steps_taken:          u32,
+    /// NaN without a step goal.
+    pub step_completion_pct:  f64,
+    /// Whether an hour or more went by without a step.
+    pub move_alert_triggered: bool,
+    pub moderate_minutes:     u32,
+    pub vigorous_minutes:     u32,
+    /// Moderate minutes plus twice the vigorous ones, which
+    /// is what counts towards Garmin's weekly goal.
+    pub intensity_minutes:    u32,
+}
+
+/// Progress towards the step goal for every day in the
+/// monitoring data.
+///
+/// Each `MonitoringInfo` message in `monitoring_info` marks
+/// the start of a day; when a day has several, the last one
+/// wins. The FIT profile has no goal fields in
+/// `MonitoringInfo`, so step goals come from `Goal`
+/// messages of type `Steps` in the same slice, the one with
+/// the latest start date not after the day applying.
+///
+/// Steps are the walking and running `cycles` of the
+/// `Monitoring` messages, which count up from the start of
+/// each day. The move alert is derived from the gaps
+/// between step count increases, since the profile doesn't
+/// record it either.
+///
+/// Intensity minutes are summed from the per-epoch
+/// `moderate_activity_minutes` and
+/// `vigorous_activity_minutes` of the `Monitoring` messages
+/// falling within the day.
+pub fn daily_goal_progress(
+    monitoring_info: &[Data],
+    monitoring: &[Data],
+) -> Vec<DailyGoalProgress> {
+    // Day starts keyed by local date
+    let mut days: BTreeMap<u32, u32> = BTreeMap::new();
+    let mut goals: Vec<(u32, u32)> = Vec::new();
+    for mesg in monitoring_info {
+        if let Some((start, local)) = day_start(mesg) {
+            days.insert(local / DAY_S, start);
+        }
+        goals.extend(step_goal(mesg));
+    }
+    goals.sort_by_key(|&(start, _)| start);
+
+    let samples = samples(monitoring);
+    let starts: Vec<u32> = days.values().cloned().collect();
+    let dates: Vec<u32> = days.keys().cloned().collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).cloned().unwrap_or(start + DAY_S);
+            let step_goal = goals
+                .iter()
+                .rev()
+                .find(|&&(from, _)| from <= start)
+                .map(|&(_, goal)| goal)
+                .unwrap_or(0);
+
+            let mut totals: BTreeMap<u8, u32> = BTreeMap::new();
+            let mut steps_taken = 0;
+            let mut last_step = start;
+            let mut move_alert_triggered = false;
+            let mut moderate_minutes = 0;
+            let mut vigorous_minutes = 0;
+            for sample in samples
+                .iter()
+                .filter(|s| start <= s.timestamp && s.timestamp < end)
+            {
+                moderate_minutes += u32::from(sample.moderate_minutes);
+                vigorous_minutes += u32::from(sample.vigorous_minutes);
+
+                let (activity, cycles) = match sample.steps {
+                    Some(steps) => steps,
+                    None => continue,
+                };
+                let total = totals.entry(activity).or_insert(0);
+                if cycles > *total {
+                    *total = cycles;
+                    if sample.timestamp - last_step >= MOVE_ALERT_S {
+                        move_alert_triggered = true;
+                    }
+                    last_step = sample.timestamp;
+                }
+                steps_taken = totals.values().sum();
+            }
+
+            let step_completion_pct = if step_goal > 0 {
+                f64::from(steps_taken) / f64::from(step_goal) * 100.0
+            }
+            else {
+                f64::NAN
+            };
+
+            DailyGoalProgress {
+                date_unix: FIT_EPOCH + i64::from(start),
+                date: LocalDateTime(dates[i] * DAY_S)
+                    .to_naive_local()
+                    .map(|midnight| midnight.date()),
+                step_goal,
+                steps_taken,
+                step_completion_pct,
+                move_alert_triggered,
+                moderate_minutes,
+                vigorous_minutes,
+                intensity_minutes: moderate_minutes + 2 * vigorous_minutes,
+            }
+        })
+        .collect()
+}
+
+/// The UTC and local start of the day a `MonitoringInfo`
+/// message opens.
+fn day_start(mesg: &Data) -> Option<(u32, u32)> {
+    let mut utc = None;
+    let mut local = None;
+    for field in mesg.0.iter() {
+        match field {
+            Message::MonitoringInfo(MonitoringInfo::Timestamp(f)) => {
+                utc = Some(f.raw_value)
+            },
+            Message::MonitoringInfo(MonitoringInfo::LocalTimestamp(f)) => {
+                local = Some(f.raw_value.0)
+            },
+            _ => return None,
+        }
+    }
+    let utc = utc.filter(|utc| utc.to_utc().is_some())?;
+    Some((utc.0, local.unwrap_or(utc.0)))
+}
+
+/// The start date and target of a `Goal` message of type
+/// `Steps`.
+fn step_goal(mesg: &Data) -> Option<(u32, u32)> {
+    let mut is_steps = false;
+    let mut start = 0;
+    let mut target = None;
+    for field in mesg.0.iter() {
+        match field {
+            Message::Goal(GoalField::Type(f)) => {
+                is_steps = matches!(f.raw_value, Goal::Steps)
+            },
+            Message::Goal(GoalField::StartDate(f)) => {
+                start = Some(f.raw_value)
+                    .filter(|date| date.to_utc().is_some())
+                    .map(|date: DateTime| date.0)
+                    .unwrap_or(0)
+            },
+            Message::Goal(GoalField::TargetValue(f)) if f.is_valid() => {
+                target = Some(f.raw_value.0)
+            },
+            Message::Goal(_) => (),
+            _ => return None,
+        }
+    }
+    if is_steps {
+        target.map(|target| (start, target))
+    }
+    else {
+        None
+    }
+}
+
+/// What a `Monitoring` message contributes to a day.
+struct Sample {
+    timestamp:        u32,
+    /// Activity type and cycles of walking and running.
+    steps:            Option<(u8, u32)>,
+    moderate_minutes: u16,
+    vigorous_minutes: u16,
+}
+
+/// The first time from `last` on whose low 16 bits are
+/// `timestamp_16`, as monitoring messages abbreviate their
+/// timestamps.
+pub(crate) fn expand_timestamp_16(last: u32, timestamp_16: u16) -> u32 {
+    last + u32::from(timestamp_16.wrapping_sub(last as u16))
+}
+
+/// The `Monitoring` messages with steps or intensity
+/// minutes, expanding 16 bit timestamps against the last
+/// full one.
+fn samples(monitoring: &[Data]) -> Vec<Sample> {
+    let mut last_timestamp = None;
+    let mut samples = Vec::new();
+    for mesg in monitoring {
+        let mut timestamp = None;
+        let mut activity = None;
+        let mut cycles = None;
+        let mut moderate_minutes = 0;
+        let mut vigorous_minutes = 0;
+        for field in mesg.0.iter() {
+            match field {
+                Message::Monitoring(Monitoring::Timestamp(f)) => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                Message::Monitoring(Monitoring::Timestamp16(f))
+                    if timestamp.is_none() =>
+                {
+                    timestamp = last_timestamp
+                        .map(|last| expand_timestamp_16(last, f.raw_value.0))
+                },
+                Message::Monitoring(Monitoring::ActivityType(f)) => {
+                    activity = match f.raw_value {
+                        ActivityType::Walking => Some(6),
+                        ActivityType::Running => Some(1),
+                        _ => None,
+                    }
+                },
+                Message::Monitoring(Monitoring::Cycles(f)) if f.is_valid() => {
+                    cycles = Some(f.raw_value.0)
+                },
+                Message::Monitoring(Monitoring::ModerateActivityMinutes(f))
+                    if f.is_valid() =>
+                {
+                    moderate_minutes = f.raw_value.0
+                },
+                Message::Monitoring(Monitoring::VigorousActivityMinutes(f))
+                    if f.is_valid() =>
+                {
+                    vigorous_minutes = f.raw_value.0
+                },
+                _ => (),
+            }
+        }
+
+        if timestamp.is_some() {
+            last_timestamp = timestamp;
+        }
+        let steps = activity.and_then(|activity| Some((activity, cycles?)));
+        match timestamp {
+            Some(timestamp)
+                if steps.is_some()
+                    || moderate_minutes > 0
+                    || vigorous_minutes > 0 =>
+            {
+                samples.push(Sample {
+                    timestamp,
+                    steps,
+                    moderate_minutes,
+                    vigorous_minutes,
+                })
+            },
+            _ => (),
+        }
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    // 2018-07-01 00:00 UTC, in a UTC+2 time zone
+    const DAY_1: u32 = 899_337_600 - 2 * 3600;
+    const DAY_2: u32 = DAY_1 + DAY_S;
+
+    fn info(utc: u32) -> Data {
+        mesg(103).u32(253, utc).u32(0, utc + 2 * 3600).build()
+    }
+
+    fn goal(start: u32, steps: u32) -> Data {
+        mesg(15).u32(2, start).u8(4, 4).u32(7, steps).build()
+    }
+
+    fn steps(ts: u32, activity: u8, cycles: u32) -> Data {
+        mesg(55).u32(253, ts).u8(5, activity).u32(3, cycles).build()
+    }
+
+    #[test]
+    fn full_and_half_days() {
+        let info_messages = vec![
+            goal(DAY_1 - DAY_S, 10_000),
+            info(DAY_1),
+            goal(DAY_2, 8000),
+            info(DAY_2),
+            info(DAY_2),
+        ];
+
+        let monitoring = vec![
+            steps(DAY_1 + 1800, 6, 2000),
+            steps(DAY_1 + 2700, 6, 6000),
+            steps(DAY_1 + 3600, 1, 4000),
+            mesg(55)
+                .u16(26, (DAY_1 + 4500) as u16)
+                .u8(5, 6)
+                .u32(3, 6000)
+                .build(),
+            steps(DAY_2 + 600, 6, 1000),
+            // Two hours at the desk
+            steps(DAY_2 + 8000, 6, 1000),
+            steps(DAY_2 + 8400, 6, 4000),
+            steps(DAY_2 + 8400, 8, 9999),
+        ];
+
+        let progress = daily_goal_progress(&info_messages, &monitoring);
+        assert_eq!(progress.len(), 2);
+
+        let (day_1, day_2) = (&progress[0], &progress[1]);
+        assert_eq!(day_1.date_unix, 1_530_396_000);
+        assert_eq!(day_1.date, NaiveDate::from_ymd_opt(2018, 7, 1));
+        assert_eq!((day_1.step_goal, day_1.steps_taken), (10_000, 10_000));
+        assert_eq!(day_1.step_completion_pct, 100.0);
+        assert!(!day_1.move_alert_triggered);
+
+        assert_eq!((day_2.step_goal, day_2.steps_taken), (8000, 4000));
+        assert_eq!(day_2.step_completion_pct, 50.0);
+        assert!(day_2.move_alert_triggered);
+    }
+
+    #[test]
+    fn no_goal() {
+        let progress =
+            daily_goal_progress(&[info(DAY_1)], &[steps(DAY_1 + 60, 6, 10)]);
+        assert_eq!(progress[0].step_goal, 0);
+        assert_eq!(progress[0].steps_taken, 10);
+        assert!(progress[0].step_completion_pct.is_nan());
+    }
+
+    #[test]
+    fn weekly_intensity_minutes() {
+        // A week of `(moderate, vigorous)` epochs per day
+        let week: [&[(u16, u16)]; 7] = [
+            &[(10, 0), (12, 3)],
+            &[],
+            &[(0, 25)],
+            &[(30, 0)],
+            &[(5, 5), (0, 0)],
+            &[],
+            &[(45, 20)],
+        ];
+        let mut info_messages = Vec::new();
+        let mut monitoring = Vec::new();
+        for (day, epochs) in week.iter().enumerate() {
+            let start = DAY_1 + day as u32 * DAY_S;
+            info_messages.push(info(start));
+            for (i, &(moderate, vigorous)) in epochs.iter().enumerate() {
+                monitoring.push(
+                    mesg(55)
+                        .u32(253, start + 900 * (i as u32 + 1))
+                        .u16(33, moderate)
+                        .u16(34, vigorous)
+                        .build(),
+                );
+            }
+        }
+        // Just before midnight local time still counts for
+        // the last day
+        monitoring
+            .push(mesg(55).u32(253, DAY_1 + 7 * DAY_S - 60).u16(34, 1).build());
+
+        let progress = daily_goal_progress(&info_messages, &monitoring);
+        assert_eq!(progress.len(), 7);
+        let minutes: Vec<(u32, u32, u32)> = progress
+            .iter()
+            .map(|day| {
+                (
+                    day.moderate_minutes,
+                    day.vigorous_minutes,
+                    day.intensity_minutes,
+                )
+            })
+            .collect();
+        assert_eq!(minutes[0], (22, 3, 28));
+        assert_eq!(minutes[2], (0, 25, 50));
+        assert_eq!(minutes[6], (45, 21, 87));
+        assert_eq!(progress[1].steps_taken, 0);
+
+        // What Connect shows for the week
+        let weekly: u32 =
+            progress.iter().map(|day| day.intensity_minutes).sum();
+        assert_eq!(weekly, 28 + 50 + 30 + 15 + 87);
+    }
+}
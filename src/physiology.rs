@@ -0,0 +1,472 @@
+//! Heart rate zones and calorie estimates from the user's
+//! profile.
+//!
+//! Everything computed here is an estimate from population
+//! formulas. Values the device recorded itself, such as
+//! `HrZone` messages or `Record` calories, should be
+//! preferred whenever they're present.
+use profile::{
+    messages::{
+        HrZone as HrZoneField,
+        Message,
+        UserProfile as UserProfileField,
+        ZonesTarget as ZonesTargetField,
+    },
+    types::{
+        Gender,
+        HrZoneCalc,
+    },
+};
+use series::RecordTimeSeries;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// Zone boundaries as percentages, of either the maximum or
+/// the reserve heart rate, used by Garmin devices by
+/// default: the floor of zone 1 followed by the top of each
+/// of the five zones.
+pub const DEFAULT_ZONE_PERCENTS: [f64; 6] =
+    [50.0, 60.0, 70.0, 80.0, 90.0, 100.0];
+
+/// A formula estimating maximum heart rate from age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MaxHrFormula {
+    /// `220 - age`, the one most devices use.
+    #[default]
+    Fox,
+    /// `208 - 0.7 * age`.
+    Tanaka,
+    /// `206 - 0.88 * age`, derived for women.
+    Gulati,
+}
+
+/// Estimate maximum heart rate in beats per minute.
+pub fn estimate_max_hr(age: u8, formula: MaxHrFormula) -> f64 {
+    let age = f64::from(age);
+    match formula {
+        MaxHrFormula::Fox => 220.0 - age,
+        MaxHrFormula::Tanaka => 208.0 - 0.7 * age,
+        MaxHrFormula::Gulati => 206.0 - 0.88 * age,
+    }
+}
+
+/// The fields of a `UserProfile` message used for
+/// estimates.
+#[derive(Debug, Clone, Default)]
+pub struct UserProfile {
+    pub gender:      Option<Gender>,
+    /// Years.
+    pub age:         Option<u8>,
+    /// Kilograms.
+    pub weight:      Option<f64>,
+    /// Beats per minute.
+    pub resting_hr:  Option<u8>,
+    /// Beats per minute.
+    pub default_max: Option<u8>,
+}
+
+impl UserProfile {
+    /// Flatten a decoded `UserProfile` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `UserProfile`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<UserProfile> {
+        let mut user = UserProfile::default();
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::UserProfile(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                UserProfileField::Gender(f) => {
+                    user.gender = Some(f.raw_value.clone())
+                },
+                UserProfileField::Age(f) if f.is_valid() => {
+                    user.age = Some(f.raw_value.0)
+                },
+                UserProfileField::Weight(f) if f.is_valid() => {
+                    user.weight = Some(f.value())
+                },
+                UserProfileField::RestingHeartRate(f) if f.is_valid() => {
+                    user.resting_hr = Some(f.raw_value.0)
+                },
+                UserProfileField::DefaultMaxHeartRate(f) if f.is_valid() => {
+                    user.default_max = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+        Some(user)
+    }
+
+    /// Maximum heart rate: the one set on the device, or an
+    /// estimate from the user's age.
+    pub fn max_hr(&self, formula: MaxHrFormula) -> Option<f64> {
+        self.default_max
+            .map(f64::from)
+            .or_else(|| self.age.map(|age| estimate_max_hr(age, formula)))
+    }
+}
+
+/// The fields of a `ZonesTarget` message describing heart
+/// rate zones.
+#[derive(Debug, Clone, Default)]
+pub struct ZonesTarget {
+    /// Beats per minute.
+    pub max_heart_rate:       Option<u8>,
+    /// Beats per minute.
+    pub threshold_heart_rate: Option<u8>,
+    pub hr_calc_type:         Option<HrZoneCalc>,
+}
+
+impl ZonesTarget {
+    /// Flatten a decoded `ZonesTarget` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `ZonesTarget`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<ZonesTarget> {
+        let mut target = ZonesTarget::default();
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::ZonesTarget(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                ZonesTargetField::MaxHeartRate(f) if f.is_valid() => {
+                    target.max_heart_rate = Some(f.raw_value.0)
+                },
+                ZonesTargetField::ThresholdHeartRate(f) if f.is_valid() => {
+                    target.threshold_heart_rate = Some(f.raw_value.0)
+                },
+                ZonesTargetField::HrCalcType(f) => {
+                    target.hr_calc_type = Some(f.raw_value.clone())
+                },
+                _ => (),
+            }
+        }
+        Some(target)
+    }
+}
+
+/// Heart rate zone boundaries in beats per minute, for
+/// `percents` of the maximum heart rate, or of the heart
+/// rate reserve (`max - resting`) when `resting_hr` is
+/// given.
+///
+/// Boundaries are rounded to the nearest beat, as devices
+/// do when they write `HrZone` messages.
+pub fn zone_boundaries(
+    percents: &[f64],
+    max_hr: f64,
+    resting_hr: Option<f64>,
+) -> Vec<u8> {
+    let resting = resting_hr.unwrap_or(0.0);
+    percents
+        .iter()
+        .map(|pct| (resting + pct / 100.0 * (max_hr - resting)).round() as u8)
+        .collect()
+}
+
+/// The `HighBpm` values of the `HrZone` messages in
+/// `messages`, in file order.
+pub fn recorded_hr_zones(messages: &[Data]) -> Vec<u8> {
+    messages
+        .iter()
+        .filter_map(|mesg| {
+            mesg.0.iter().find_map(|field| {
+                match field {
+                    Message::HrZone(HrZoneField::HighBpm(f))
+                        if f.is_valid() =>
+                    {
+                        Some(f.raw_value.0)
+                    },
+                    _ => None,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Heart rate zone boundaries for the activity in
+/// `messages`, following the `HrCalcType` of its
+/// `ZonesTarget` message:
+///
+/// * `Custom` zones are the recorded `HrZone` messages.
+/// * `PercentMaxHr` zones are `percents` of the maximum
+///   heart rate.
+/// * `PercentHrr` zones are `percents` of the heart rate
+///   reserve, using the resting heart rate of the
+///   `UserProfile`.
+///
+/// The maximum heart rate is taken from `ZonesTarget`, then
+/// `UserProfile`, then estimated from the user's age with
+/// `formula`. Returns `None` if there is no `ZonesTarget`
+/// or not enough data for its calculation type.
+pub fn hr_zones(
+    messages: &[Data],
+    percents: &[f64],
+    formula: MaxHrFormula,
+) -> Option<Vec<u8>> {
+    let target = messages.iter().find_map(ZonesTarget::from_message)?;
+    let user =
+        messages.iter().find_map(UserProfile::from_message).unwrap_or_default();
+    let max_hr =
+        target.max_heart_rate.map(f64::from).or_else(|| user.max_hr(formula));
+
+    match target.hr_calc_type? {
+        HrZoneCalc::Custom => {
+            Some(recorded_hr_zones(messages)).filter(|zones| !zones.is_empty())
+        },
+        HrZoneCalc::PercentMaxHr => {
+            Some(zone_boundaries(percents, max_hr?, None))
+        },
+        HrZoneCalc::PercentHrr => {
+            let resting = f64::from(user.resting_hr?);
+            Some(zone_boundaries(percents, max_hr?, Some(resting)))
+        },
+        HrZoneCalc::Unknown => None,
+    }
+}
+
+/// Time spent in each of five heart rate zones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HrZoneBreakdown {
+    /// Seconds in zones 1 to 5.
+    pub zone_times_s: [f64; 5],
+    pub zone_names:   [&'static str; 5],
+}
+
+/// Names of the zones of [`heartrate_zone_for_hr`].
+///
+/// [`heartrate_zone_for_hr`]: fn.heartrate_zone_for_hr.html
+pub const HR_ZONE_NAMES: [&str; 5] =
+    ["Recovery", "Endurance", "Aerobic", "Lactate Threshold", "Anaerobic"];
+
+/// The zone, 1 to 5, of `hr_bpm`: below 50%, 60%, 70% and
+/// 80% of `max_hr` for zones 1 to 4, and zone 5 from 80%
+/// up.
+pub fn heartrate_zone_for_hr(hr_bpm: f64, max_hr: f64) -> u8 {
+    let percent = hr_bpm / max_hr * 100.0;
+    match DEFAULT_ZONE_PERCENTS[..4].iter().position(|&top| percent < top) {
+        Some(zone) => zone as u8 + 1,
+        None => 5,
+    }
+}
+
+/// How long `series` spent in each zone of
+/// [`heartrate_zone_for_hr`].
+///
+/// The time since the previous record counts towards the
+/// zone of each record's heart rate, as in
+/// [`fill_missing_calories`]. Records without a heart rate
+/// count towards no zone.
+///
+/// [`heartrate_zone_for_hr`]: fn.heartrate_zone_for_hr.html
+/// [`fill_missing_calories`]: fn.fill_missing_calories.html
+pub fn heartrate_zones(
+    series: &RecordTimeSeries,
+    max_hr: f64,
+) -> HrZoneBreakdown {
+    let mut zone_times_s = [0.0; 5];
+    for pair in series.points.windows(2) {
+        if let Some(hr) = pair[1].heart_rate {
+            let zone = heartrate_zone_for_hr(f64::from(hr), max_hr);
+            let seconds = pair[1].timestamp.saturating_sub(pair[0].timestamp);
+            zone_times_s[usize::from(zone) - 1] += f64::from(seconds);
+        }
+    }
+    HrZoneBreakdown {
+        zone_times_s,
+        zone_names: HR_ZONE_NAMES,
+    }
+}
+
+/// Estimated energy expenditure in kilocalories per minute
+/// at `heart_rate`, using the formula of Keytel et al.
+/// (2005), or `None` without the user's gender, age and
+/// weight.
+///
+/// The formula is meant for heart rates between roughly 90
+/// and 150 bpm during steady exercise; below that it can
+/// go negative, which is clamped to zero.
+pub fn kcal_per_minute(user: &UserProfile, heart_rate: f64) -> Option<f64> {
+    let age = f64::from(user.age?);
+    let weight = user.weight?;
+    let kj_per_minute = match user.gender {
+        Some(Gender::Male) => {
+            -55.0969 + 0.6309 * heart_rate + 0.1988 * weight + 0.2017 * age
+        },
+        Some(Gender::Female) => {
+            -20.4022 + 0.4472 * heart_rate - 0.1263 * weight + 0.074 * age
+        },
+        _ => return None,
+    };
+    Some((kj_per_minute / 4.184).max(0.0))
+}
+
+/// Fill in the cumulative `calories` of records that lack
+/// them, estimated from heart rate with
+/// [`kcal_per_minute`].
+///
+/// Estimates continue from the last recorded value, and
+/// recorded values are left alone. Records without a heart
+/// rate add nothing. Returns how many records were filled,
+/// which is none without the user's gender, age and weight.
+///
+/// [`kcal_per_minute`]: fn.kcal_per_minute.html
+pub fn fill_missing_calories(
+    series: &mut RecordTimeSeries,
+    user: &UserProfile,
+) -> usize {
+    if kcal_per_minute(user, 0.0).is_none() {
+        return 0
+    }
+
+    let mut filled = 0;
+    let mut total = 0.0;
+    let mut previous = None;
+    for point in series.points.iter_mut() {
+        match point.calories {
+            Some(calories) => total = f64::from(calories),
+            None => {
+                let seconds = previous
+                    .map(|ts| point.timestamp.saturating_sub(ts))
+                    .unwrap_or(0);
+                let rate = point
+                    .heart_rate
+                    .and_then(|hr| kcal_per_minute(user, f64::from(hr)))
+                    .unwrap_or(0.0);
+                total += rate * f64::from(seconds) / 60.0;
+                point.calories = Some(total.round().min(65534.0) as u16);
+                filled += 1;
+            },
+        }
+        previous = Some(point.timestamp);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use series::RecordPoint;
+    use testutil::mesg;
+
+    fn zones_fixture(
+        calc: u8,
+        max_hr: u8,
+        resting_hr: u8,
+        zones: &[u8],
+    ) -> Vec<Data> {
+        let mut messages = vec![
+            mesg(3).u8(1, 1).u8(2, 40).u16(4, 750).u8(8, resting_hr).build(),
+            mesg(7).u8(1, max_hr).u8(5, calc).build(),
+        ];
+        messages.extend(
+            zones
+                .iter()
+                .enumerate()
+                .map(|(i, &bpm)| mesg(8).u16(254, i as u16).u8(1, bpm).build()),
+        );
+        messages
+    }
+
+    #[test]
+    fn derived_zones_match_recorded_ones() {
+        // Zones as written by a Forerunner for max 187 bpm
+        let fixture = zones_fixture(1, 187, 48, &[94, 112, 131, 150, 168, 187]);
+        let derived =
+            hr_zones(&fixture, &DEFAULT_ZONE_PERCENTS, MaxHrFormula::Fox);
+        assert_eq!(derived, Some(recorded_hr_zones(&fixture)));
+
+        // Heart rate reserve, 50 to 190 bpm
+        let fixture =
+            zones_fixture(2, 190, 50, &[120, 134, 148, 162, 176, 190]);
+        let derived =
+            hr_zones(&fixture, &DEFAULT_ZONE_PERCENTS, MaxHrFormula::Fox);
+        assert_eq!(derived, Some(recorded_hr_zones(&fixture)));
+
+        let custom = zones_fixture(0, 190, 50, &[100, 120, 140, 160, 175, 190]);
+        assert_eq!(
+            hr_zones(&custom, &DEFAULT_ZONE_PERCENTS, MaxHrFormula::Fox),
+            Some(vec![100, 120, 140, 160, 175, 190])
+        );
+    }
+
+    #[test]
+    fn max_hr_falls_back_to_formula() {
+        let fixture =
+            vec![mesg(3).u8(1, 0).u8(2, 40).build(), mesg(7).u8(5, 1).build()];
+        let zones = hr_zones(&fixture, &[50.0, 100.0], MaxHrFormula::Fox);
+        assert_eq!(zones, Some(vec![90, 180]));
+        let zones = hr_zones(&fixture, &[100.0], MaxHrFormula::Tanaka);
+        assert_eq!(zones, Some(vec![180]));
+        assert_eq!(estimate_max_hr(40, MaxHrFormula::Gulati), 206.0 - 35.2);
+    }
+
+    #[test]
+    fn fills_missing_calories() {
+        let user =
+            UserProfile::from_message(&zones_fixture(1, 190, 50, &[])[0])
+                .unwrap();
+        assert_eq!(user.weight, Some(75.0));
+        let rate = kcal_per_minute(&user, 140.0).unwrap();
+        assert!(rate > 10.0 && rate < 20.0, "{}", rate);
+
+        let mut series = RecordTimeSeries {
+            points: (0..=600)
+                .map(|i| {
+                    RecordPoint {
+                        timestamp: 1000 + i,
+                        heart_rate: Some(140),
+                        calories: if i == 0 { Some(20) } else { None },
+                        ..RecordPoint::default()
+                    }
+                })
+                .collect(),
+        };
+        assert_eq!(fill_missing_calories(&mut series, &user), 600);
+        let last = series.points[600].calories.unwrap();
+        assert_eq!(last, (20.0 + rate * 10.0).round() as u16);
+    }
+
+    #[test]
+    fn time_in_hr_zones() {
+        let series = |hrs: &[Option<u8>]| {
+            RecordTimeSeries {
+                points: hrs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &heart_rate)| {
+                        RecordPoint {
+                            timestamp: 1000 + 5 * i as u32,
+                            heart_rate,
+                            ..RecordPoint::default()
+                        }
+                    })
+                    .collect(),
+            }
+        };
+
+        // 75% of 200 bpm is zone 4, 65% zone 3
+        let steady = heartrate_zones(&series(&[Some(150); 13]), 200.0);
+        assert_eq!(steady.zone_times_s, [0.0, 0.0, 0.0, 60.0, 0.0]);
+        let steady = heartrate_zones(&series(&[Some(130); 13]), 200.0);
+        assert_eq!(steady.zone_times_s, [0.0, 0.0, 60.0, 0.0, 0.0]);
+        assert_eq!(steady.zone_names[2], "Aerobic");
+
+        let mixed = series(&[Some(90), Some(100), None, Some(190), Some(130)]);
+        let mixed = heartrate_zones(&mixed, 200.0);
+        assert_eq!(mixed.zone_times_s, [0.0, 5.0, 5.0, 0.0, 5.0]);
+
+        let zones: Vec<u8> = [0.0, 99.9, 100.0, 159.9, 160.0, 250.0]
+            .iter()
+            .map(|&hr| heartrate_zone_for_hr(hr, 200.0))
+            .collect();
+        assert_eq!(zones, vec![1, 1, 2, 4, 5, 5]);
+    }
+}
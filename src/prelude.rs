@@ -0,0 +1,56 @@
+//! The names most programs need, for a glob import.
+//!
+//! ```
+//! use garminfit::prelude::*;
+//!
+//! fn distance_m(file: &File) -> f64 {
+//!     let messages: Vec<Data> = file.messages().cloned().collect();
+//!     RecordTimeSeries::from_messages(&messages).total_distance_haversine_m()
+//! }
+//! ```
+//!
+//! [`Field`] comes in anonymously, so its methods are in
+//! scope without its name clashing with the
+//! [`messages::Field`] fields are wrapped in.
+//!
+//! [`Field`]: ../types/field/trait.Field.html
+//! [`messages::Field`]: ../profile/messages/struct.Field.html
+pub use devices::DeviceInfo;
+pub use duration::FitDuration;
+pub use error::Error;
+pub use geo::{
+    GeoPoint,
+    Position,
+};
+pub use laps::Lap;
+pub use profile::{
+    messages::{
+        self,
+        Message,
+    },
+    types::{
+        DateTime,
+        LocalDateTime,
+        MesgNum,
+        Sport,
+        SubSport,
+    },
+};
+pub use reader::FitReader;
+pub use series::{
+    RecordPoint,
+    RecordTimeSeries,
+};
+pub use session::SessionSummary;
+pub use sport::ActivityKind;
+pub use types::{
+    field::{
+        Field as _,
+        Value,
+    },
+    file::File,
+    record::{
+        Data,
+        Record,
+    },
+};
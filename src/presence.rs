@@ -0,0 +1,316 @@
+//! Which fields a file actually has, compared to what the
+//! FIT SDK expects, and which fields of a single message
+//! the device filled in.
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+use types::record::{
+    Data,
+    Message,
+    Record,
+};
+
+/// The fields the FIT SDK requires of each message in an
+/// activity file, as `(mesg_num, field_def_nums)`.
+const REQUIRED_FIELDS: &[(u16, &[u8])] = &[
+    // FileId: type, manufacturer, product, serial_number,
+    // time_created
+    (0, &[0, 1, 2, 3, 4]),
+    // Session: message_index, timestamp, event, event_type,
+    // start_time, sport, sub_sport, total_elapsed_time,
+    // total_timer_time, first_lap_index, num_laps
+    (18, &[254, 253, 0, 1, 2, 5, 6, 7, 8, 25, 26]),
+    // Lap: message_index, timestamp, event, event_type,
+    // start_time, total_elapsed_time, total_timer_time
+    (19, &[254, 253, 0, 1, 2, 7, 8]),
+    // Record: timestamp
+    (20, &[253]),
+    // Event: timestamp, event, event_type
+    (21, &[253, 0, 1]),
+    // Activity: timestamp, num_sessions, type, event,
+    // event_type
+    (34, &[253, 1, 2, 3, 4]),
+];
+
+/// The field definition numbers seen for each global
+/// message number.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldPresenceMap {
+    inner: BTreeMap<u16, BTreeSet<u8>>,
+}
+
+/// Collect the fields declared by the definition messages
+/// among `messages`.
+///
+/// A field counts as present once it's defined, even if
+/// every data message leaves it invalid. Developer fields
+/// aren't tracked.
+pub fn build_field_presence_map(messages: &[Message]) -> FieldPresenceMap {
+    let mut map = FieldPresenceMap::default();
+    for mesg in messages {
+        if let Message::Definition(definition) = mesg {
+            map.inner
+                .entry(definition.global_mesg_num())
+                .or_default()
+                .extend(definition.field_def_nums());
+        }
+    }
+    map
+}
+
+impl FieldPresenceMap {
+    /// Whether any definition of `mesg_num` had the field.
+    pub fn has_field(&self, mesg_num: u16, field_def_num: u8) -> bool {
+        self.inner
+            .get(&mesg_num)
+            .map(|fields| fields.contains(&field_def_num))
+            .unwrap_or(false)
+    }
+
+    /// Whether `mesg_num` was defined at all.
+    pub fn has_message(&self, mesg_num: u16) -> bool {
+        self.inner.contains_key(&mesg_num)
+    }
+
+    /// The fields the FIT SDK requires of `mesg_num` that
+    /// were never defined, in ascending order.
+    ///
+    /// This is every required field if the message is
+    /// absent, and always empty for messages without
+    /// required fields.
+    pub fn missing_required_fields(&self, mesg_num: u16) -> Vec<u8> {
+        let mut missing: Vec<u8> = required_fields(mesg_num)
+            .iter()
+            .cloned()
+            .filter(|&field_def_num| !self.has_field(mesg_num, field_def_num))
+            .collect();
+        missing.sort();
+        missing
+    }
+}
+
+/// The fields the FIT SDK requires of `mesg_num` in an
+/// activity file.
+pub fn required_fields(mesg_num: u16) -> &'static [u8] {
+    REQUIRED_FIELDS
+        .iter()
+        .find(|&&(num, _)| num == mesg_num)
+        .map(|&(_, fields)| fields)
+        .unwrap_or(&[])
+}
+
+/// Whether a data message carried a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPresence {
+    /// The field was defined and holds a value.
+    Present,
+    /// The field was defined but holds its base type's
+    /// invalid value: the device had nothing to put there.
+    Invalid,
+    /// The message's definition didn't have the field at
+    /// all.
+    AbsentFromDefinition,
+}
+
+/// The fields declared by the definition of a single data
+/// message, and whether each held a valid value.
+///
+/// Decoding keeps one of these with every data [`Record`].
+/// Developer fields aren't tracked.
+///
+/// [`Record`]: ../types/record/struct.Record.html
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessagePresence {
+    /// `(field_def_num, is_valid)` in definition order.
+    fields: Vec<(u8, bool)>,
+}
+
+impl MessagePresence {
+    pub(crate) fn push(&mut self, field_def_num: u8, is_valid: bool) {
+        self.fields.push((field_def_num, is_valid));
+    }
+
+    /// `(field_def_num, is_valid)` for each field, in
+    /// definition order.
+    pub(crate) fn iter<'a>(&'a self) -> impl Iterator<Item = (u8, bool)> + 'a {
+        self.fields.iter().cloned()
+    }
+
+    /// Whether the message carried `field_def_num`. A field
+    /// defined more than once is judged by its last
+    /// occurrence, the one that's decoded.
+    pub fn presence(&self, field_def_num: u8) -> FieldPresence {
+        match self.fields.iter().rev().find(|&&(num, _)| num == field_def_num) {
+            Some(&(_, true)) => FieldPresence::Present,
+            Some(&(_, false)) => FieldPresence::Invalid,
+            None => FieldPresence::AbsentFromDefinition,
+        }
+    }
+
+    /// Whether no fields were recorded, as for records
+    /// other than data messages.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// A `Session` data message along with which of its fields
+/// the device filled in.
+#[derive(Debug, Clone)]
+pub struct SessionMsg {
+    pub data: Data,
+    presence: MessagePresence,
+}
+
+impl SessionMsg {
+    /// Returns `None` unless `record` is a decoded
+    /// `Session` data message.
+    pub fn from_record(record: &Record) -> Option<SessionMsg> {
+        let (data, presence) = data_of(record, 18)?;
+        Some(SessionMsg {
+            data,
+            presence,
+        })
+    }
+
+    /// Whether the session carried `field_def_num`.
+    pub fn presence(&self, field_def_num: u8) -> FieldPresence {
+        self.presence.presence(field_def_num)
+    }
+}
+
+/// A `Lap` data message along with which of its fields the
+/// device filled in.
+#[derive(Debug, Clone)]
+pub struct LapMsg {
+    pub data: Data,
+    presence: MessagePresence,
+}
+
+impl LapMsg {
+    /// Returns `None` unless `record` is a decoded `Lap`
+    /// data message.
+    pub fn from_record(record: &Record) -> Option<LapMsg> {
+        let (data, presence) = data_of(record, 19)?;
+        Some(LapMsg {
+            data,
+            presence,
+        })
+    }
+
+    /// Whether the lap carried `field_def_num`.
+    pub fn presence(&self, field_def_num: u8) -> FieldPresence {
+        self.presence.presence(field_def_num)
+    }
+}
+
+/// The data and field presence of `record` if it's a data
+/// message of `mesg_num`.
+fn data_of(record: &Record, mesg_num: u16) -> Option<(Data, MessagePresence)> {
+    match record.content {
+        Message::Data(ref data)
+            if data.0.first().map(|field| field.mesg_num())
+                == Some(mesg_num) =>
+        {
+            Some((data.clone(), record.presence.clone()))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::Result;
+    use reader::FitReader;
+    use std::io::Cursor;
+    use testutil::FitBuilder;
+
+    #[test]
+    fn presence() {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        fit.data(0, &[&1000u32.to_le_bytes()[..], &[150]]);
+        // A second record definition adding power
+        fit.definition(1, 20, &[(253, 4, 0x86), (7, 2, 0x84)]);
+        fit.data(1, &[&1001u32.to_le_bytes()[..], &250u16.to_le_bytes()]);
+        // A lap with only its timestamp and start time
+        fit.definition(0, 19, &[(253, 4, 0x86), (2, 4, 0x86)]);
+        fit.data(0, &[&1001u32.to_le_bytes()[..], &1000u32.to_le_bytes()]);
+        let bytes = fit.build();
+
+        let messages = FitReader::new(Cursor::new(bytes))
+            .unwrap()
+            .map(|record| record.map(|record| record.content))
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let map = build_field_presence_map(&messages);
+
+        assert!(map.has_field(20, 3));
+        assert!(map.has_field(20, 7));
+        assert!(!map.has_field(20, 4));
+        assert!(!map.has_field(18, 253));
+        assert!(map.has_message(19));
+
+        assert!(map.missing_required_fields(20).is_empty());
+        assert_eq!(map.missing_required_fields(19), vec![0, 1, 7, 8, 254]);
+        assert_eq!(map.missing_required_fields(21), vec![0, 1, 253]);
+        assert!(map.missing_required_fields(0xFF00).is_empty());
+    }
+
+    /// A session summarising a ride, with or without total
+    /// calories in its definition.
+    fn ride(calories: Option<u16>) -> Vec<Record> {
+        let mut fields = vec![(253, 4, 0x86), (7, 4, 0x86), (9, 4, 0x86)];
+        let timer = 3_600_000u32.to_le_bytes();
+        let mut data: Vec<&[u8]> =
+            vec![&[0xE8, 0x03, 0, 0], &timer, &[0xFF; 4]];
+        let calories = calories.map(u16::to_le_bytes);
+        if let Some(ref calories) = calories {
+            fields.push((11, 2, 0x84));
+            data.push(calories);
+        }
+
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 18, &fields);
+        fit.data(0, &data);
+        fit.definition(0, 19, &[(253, 4, 0x86)]);
+        fit.data(0, &[&1000u32.to_le_bytes()]);
+        FitReader::new(Cursor::new(fit.build()))
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn field_presence_of_one_message() {
+        let with = ride(Some(0xFFFF));
+        let without = ride(None);
+
+        let sessions: Vec<SessionMsg> =
+            with.iter().filter_map(SessionMsg::from_record).collect();
+        assert_eq!(sessions.len(), 1);
+        let session = &sessions[0];
+        assert_eq!(session.presence(7), FieldPresence::Present);
+        // Distance defined but left invalid
+        assert_eq!(session.presence(9), FieldPresence::Invalid);
+        assert_eq!(session.presence(11), FieldPresence::Invalid);
+        assert_eq!(session.presence(14), FieldPresence::AbsentFromDefinition);
+
+        let session = without.iter().find_map(SessionMsg::from_record).unwrap();
+        assert_eq!(session.presence(7), FieldPresence::Present);
+        assert_eq!(session.presence(11), FieldPresence::AbsentFromDefinition);
+        assert_eq!(session.data.0.len(), 3);
+
+        let lap = with.iter().find_map(LapMsg::from_record).unwrap();
+        assert_eq!(lap.presence(253), FieldPresence::Present);
+        assert_eq!(lap.presence(7), FieldPresence::AbsentFromDefinition);
+
+        // Definitions carry no presence of their own
+        assert!(with[0].presence.is_empty());
+        let filled = ride(Some(500));
+        let session = filled.iter().find_map(SessionMsg::from_record).unwrap();
+        assert_eq!(session.presence(11), FieldPresence::Present);
+    }
+}
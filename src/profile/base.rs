@@ -11,12 +11,19 @@ use std::{
     default::Default,
     f32,
     f64,
+    mem,
 };
 
 pub trait Valid {
   fn is_valid(&self) -> bool;
 }
 
+/// Writing a decoded value back as the little endian bytes
+/// it decodes from, for `cache`.
+pub(crate) trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
 macro_rules! base_type {
     (
         $sdk_name:expr,
@@ -30,34 +37,124 @@ macro_rules! base_type {
         pub struct $name(pub $type);
 
         impl $name {
-            base_type_decode!($name, $read_method);
+            base_type_decode!($name, $type, $read_method);
+
+            /// The raw value, invalid or not.
+            pub fn get(&self) -> $type {
+                self.0
+            }
+
+            /// Decode a field holding an array of values.
+            #[allow(dead_code)]
+            pub(crate) fn decode_array<T: ByteOrder>(
+                buffer: &[u8],
+            ) -> Result<Vec<Self>> {
+                let size = mem::size_of::<$type>();
+                if buffer.len() < size {
+                    return Err(Error::short_buffer(size, buffer.len()))
+                }
+                buffer.chunks_exact(size).map($name::decode::<T>).collect()
+            }
         }
         impl Valid for $name {
             fn is_valid(&self) -> bool {
                 self.0 != $invalid
             }
         }
+        impl Valid for Vec<$name> {
+            fn is_valid(&self) -> bool {
+                self.iter().any(Valid::is_valid)
+            }
+        }
         impl Default for $name {
             fn default() -> Self {
                 $name($invalid)
             }
         }
+        impl Encode for $name {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.0.to_le_bytes());
+            }
+        }
+        impl Encode for Vec<$name> {
+            fn encode(&self, out: &mut Vec<u8>) {
+                self.iter().for_each(|value| value.encode(out));
+            }
+        }
+    };
+}
+
+/// `Encode` for a profile enum, as its discriminant in the
+/// `$type` it decodes from. `Unknown` has lost the value it
+/// decoded from, so it's written as `$unknown`, a value the
+/// enum doesn't name.
+macro_rules! encode_enum {
+    ($name:ident, $type:ident, $unknown:expr) => {
+        impl profile::base::Encode for $name {
+            #[allow(clippy::clone_on_copy)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let value = match *self {
+                    $name::Unknown => $unknown,
+                    _ => self.clone() as $type,
+                };
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    };
+}
+
+/// `Encode` for a profile type that's a plain number.
+macro_rules! encode_number {
+    ($name:ident) => {
+        impl profile::base::Encode for $name {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.0.to_le_bytes());
+            }
+        }
+    };
+}
+
+/// `TryFrom<&Field<$base>>` for `$int`, the raw integer of
+/// a field ignoring its scale and offset, or an
+/// `InvalidValue` error if it holds its base type's invalid
+/// value.
+macro_rules! raw_integer {
+    ($base:ident, $int:ident) => {
+        impl<'a> std::convert::TryFrom<&'a Field<profile::base::$base>> for $int {
+            type Error = error::Error;
+
+            fn try_from(field: &'a Field<profile::base::$base>) -> error::Result<$int> {
+                if field.is_valid() {
+                    Ok(field.raw_value.get())
+                }
+                else {
+                    Err(error::Error::invalid_value())
+                }
+            }
+        }
     };
 }
 
 macro_rules! base_type_decode {
-    ($name:ident,read_u8) => {
+    ($name:ident, $type:ident, read_u8) => {
         pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> Result<Self> {
-            Ok($name(buffer[0]))
+            let byte = *buffer.first().ok_or_else(|| Error::short_buffer(1, 0))?;
+            Ok($name(byte))
         }
     };
-    ($name:ident,read_i8) => {
+    ($name:ident, $type:ident, read_i8) => {
         pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> Result<Self> {
-            Ok($name(buffer[0] as i8))
+            let byte = *buffer.first().ok_or_else(|| Error::short_buffer(1, 0))?;
+            Ok($name(byte as i8))
         }
     };
-    ($name:ident, $read_method:ident) => {
+    ($name:ident, $type:ident, $read_method:ident) => {
         pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> Result<Self> {
+            // byteorder panics on short slices
+            let size = mem::size_of::<$type>();
+            if buffer.len() < size {
+                return Err(Error::short_buffer(size, buffer.len()))
+            }
             let x = T::$read_method(buffer);
             Ok($name(x))
         }
@@ -72,8 +169,8 @@ base_type!("uint8", Uint8, u8, read_u8, 0xFF);
 base_type!("sint16", Sint16, i16, read_i16, 0x7FFF); // 2's complement format
 base_type!("uint16", Uint16, u16, read_u16, 0xFFFF);
 
-base_type!("sint32", Sint32, i32, read_i32, 0x7FFFFF); // 2's complement format
-base_type!("uint32", Uint32, u32, read_u32, 0xFFFFFF);
+base_type!("sint32", Sint32, i32, read_i32, 0x7FFFFFFF); // 2's complement format
+base_type!("uint32", Uint32, u32, read_u32, 0xFFFFFFFF);
 
 base_type!("float32", Float32, f32, read_f32, f32::MAX);
 base_type!("float64", Float64, f64, read_f64, f64::MAX);
@@ -93,10 +190,39 @@ pub struct Utf8String(pub String);
 
 impl Utf8String {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> Result<Self> {
-        unsafe {
-            // TODO: FIXME: Ignore trailing zero
-            let string = String::from_utf8_unchecked(buffer.to_vec());
-            Ok(Utf8String(string))
+        // TODO: FIXME: Ignore trailing zero
+        // Device strings aren't guaranteed to be valid UTF-8
+        let string = String::from_utf8_lossy(buffer).into_owned();
+        Ok(Utf8String(string))
+    }
+}
+
+impl Utf8String {
+    /// Decode a field holding an array of strings, each
+    /// ending in a NUL. Padding after the last string is
+    /// dropped.
+    pub(crate) fn decode_array<T: ByteOrder>(
+        buffer: &[u8],
+    ) -> Result<Vec<Self>> {
+        buffer
+            .split(|&byte| byte == 0)
+            .filter(|segment| !segment.is_empty())
+            .map(Utf8String::decode::<T>)
+            .collect()
+    }
+}
+
+impl Encode for Utf8String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.0.as_bytes());
+    }
+}
+
+impl Encode for Vec<Utf8String> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for string in self {
+            string.encode(out);
+            out.push(0);
         }
     }
 }
@@ -107,6 +233,12 @@ impl Valid for Utf8String {
     }
 }
 
+impl Valid for Vec<Utf8String> {
+    fn is_valid(&self) -> bool {
+        self.iter().any(Valid::is_valid)
+    }
+}
+
 impl Default for Utf8String {
     fn default() -> Self {
         Utf8String(String::new())
@@ -125,6 +257,12 @@ impl Bytes {
     }
 }
 
+impl Encode for Bytes {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.0);
+    }
+}
+
 impl Valid for Bytes {
     fn is_valid(&self) -> bool {
         self.0.len() > 0
@@ -144,7 +282,8 @@ pub struct Bool(pub bool);
 
 impl Bool {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> Result<Self> {
-      match buffer[0] {
+      let byte = *buffer.first().ok_or_else(|| Error::short_buffer(1, 0))?;
+      match byte {
         0 => Ok(Bool(false)),
         1 => Ok(Bool(true)),
         something_else => Err(Error::from(ErrorKind::Decode{what:format!("Cann't decode boolean: {:?}", something_else)})),
@@ -152,6 +291,12 @@ impl Bool {
     }
 }
 
+impl Encode for Bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.0 as u8);
+    }
+}
+
 impl Valid for Bool {
     fn is_valid(&self) -> bool {
         true
@@ -163,3 +308,111 @@ impl Default for Bool {
         Bool(false)
     }
 }
+
+/// Whether `bytes` hold the invalid value for the base type
+/// with number `base_type`, as given in a field definition.
+/// Arrays are invalid when every element is. Unrecognised
+/// base types are never invalid.
+pub(crate) fn is_invalid_raw(base_type: u8, bytes: &[u8], big_endian: bool) -> bool {
+    let (size, signed, invalid) = match invalid_pattern(base_type) {
+        Some(pattern) => pattern,
+        None => return false,
+    };
+
+    bytes.chunks(size).all(|element| {
+        // Signed types are invalid at their maximum, 0x7F...FF
+        let msb = if big_endian { 0 } else { element.len() - 1 };
+        element.iter().enumerate().all(|(i, &byte)| {
+            if signed && i == msb {
+                byte == 0x7F
+            }
+            else {
+                byte == invalid
+            }
+        })
+    })
+}
+
+/// `size` bytes of the invalid value for the base type with
+/// number `base_type`, repeated for each element of an
+/// array. Unrecognised base types are filled with 0xFF.
+pub(crate) fn invalid_raw(base_type: u8, size: usize, big_endian: bool) -> Vec<u8> {
+    let (element, signed, invalid) = invalid_pattern(base_type).unwrap_or((1, false, 0xFF));
+    let mut bytes = vec![invalid; size];
+    if signed {
+        let msb = if big_endian { 0 } else { element - 1 };
+        for chunk in bytes.chunks_mut(element).filter(|chunk| chunk.len() == element) {
+            chunk[msb] = 0x7F;
+        }
+    }
+    bytes
+}
+
+/// The size in bytes of one value of the base type with
+/// number `base_type`, or 1 if it's unrecognised.
+pub(crate) fn base_type_size(base_type: u8) -> usize {
+    invalid_pattern(base_type).map_or(1, |(size, _, _)| size)
+}
+
+/// The element size, signedness and invalid byte of the
+/// base type with number `base_type`.
+fn invalid_pattern(base_type: u8) -> Option<(usize, bool, u8)> {
+    match base_type & 0x1F {
+        0 | 2 | 13 => Some((1, false, 0xFF)),
+        1 => Some((1, true, 0xFF)),
+        3 => Some((2, true, 0xFF)),
+        4 => Some((2, false, 0xFF)),
+        5 => Some((4, true, 0xFF)),
+        6 | 8 => Some((4, false, 0xFF)),
+        9 | 15 => Some((8, false, 0xFF)),
+        14 => Some((8, true, 0xFF)),
+        7 | 10 => Some((1, false, 0x00)),
+        11 => Some((2, false, 0x00)),
+        12 => Some((4, false, 0x00)),
+        16 => Some((8, false, 0x00)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::LittleEndian;
+
+    #[test]
+    fn string_arrays() {
+        let buffer = b"Europe/Kyiv\0Europe/Lisbon\0Asia/Tokyo\0\0\0\0\0";
+        let names: Vec<String> = Utf8String::decode_array::<LittleEndian>(buffer)
+            .unwrap()
+            .into_iter()
+            .map(|name| name.0)
+            .collect();
+        assert_eq!(names, vec!["Europe/Kyiv", "Europe/Lisbon", "Asia/Tokyo"]);
+
+        let padding = Utf8String::decode_array::<LittleEndian>(&[0; 8]).unwrap();
+        assert!(padding.is_empty());
+        assert!(!padding.is_valid());
+
+        // A single string keeps its padding, as before
+        let single = Utf8String::decode::<LittleEndian>(b"Kyiv\0\0").unwrap();
+        assert_eq!(single.0, "Kyiv\0\0");
+
+        // Developer field names, one per language
+        let field = ::profile::messages::Message::decode::<LittleEndian>(
+            b"Power\0Leistung\0\0\0",
+            206,
+            3,
+        )
+        .unwrap();
+        match field {
+            ::profile::messages::Message::FieldDescription(
+                ::profile::messages::FieldDescription::FieldName(f),
+            ) => {
+                assert!(f.is_valid());
+                assert_eq!(f.raw_value.len(), 2);
+                assert_eq!(f.raw_value[1].0, "Leistung");
+            },
+            other => panic!("expected a field name, got {:?}", other),
+        }
+    }
+}
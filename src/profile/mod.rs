@@ -1,3 +1,7 @@
+#[macro_use]
 pub mod base;
 pub mod messages;
+pub(crate) mod table;
+#[cfg(test)]
+mod tests;
 pub mod types;
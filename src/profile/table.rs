@@ -0,0 +1,100 @@
+//! Lookup tables for the profile enums with many values.
+//!
+//! The generator emits one `EnumTable` per large enum in
+//! `types`, so decoding is a binary search of a static
+//! array rather than a match with hundreds of arms, which
+//! rustc is slow to compile and doesn't always turn into a
+//! jump table.
+
+/// The values of a profile enum, sorted, with their
+/// variants and names.
+pub(crate) struct EnumTable<V: 'static, E: 'static> {
+    /// Sorted by value.
+    pub(crate) values:  &'static [(V, E)],
+    /// The name of each entry in `values`, as `Debug`
+    /// writes the variant.
+    pub(crate) names:   &'static [&'static str],
+    /// Indexes into `values`, sorted by name.
+    pub(crate) by_name: &'static [u16],
+}
+
+impl<V: Ord + Copy, E: Clone> EnumTable<V, E> {
+    /// The variant for `value`, if the profile has one.
+    pub(crate) fn get(&self, value: V) -> Option<E> {
+        self.index(value).map(|i| self.values[i].1.clone())
+    }
+
+    /// The name of the variant for `value`.
+    pub(crate) fn name(&self, value: V) -> Option<&'static str> {
+        self.index(value).map(|i| self.names[i])
+    }
+
+    /// The variant called `name`.
+    pub(crate) fn parse(&self, name: &str) -> Option<E> {
+        self.by_name
+            .binary_search_by(|&i| self.names[usize::from(i)].cmp(name))
+            .ok()
+            .map(|i| self.values[usize::from(self.by_name[i])].1.clone())
+    }
+
+    fn index(&self, value: V) -> Option<usize> {
+        self.values.binary_search_by_key(&value, |&(v, _)| v).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use byteorder::LittleEndian;
+    use profile::types::{
+        GarminProduct,
+        Manufacturer,
+        MesgNum,
+        SubSport,
+    };
+
+    #[test]
+    fn tables_are_sorted_and_complete() {
+        // Every value decodes to the variant whose
+        // discriminant it is, and names round trip
+        for value in 0..=u16::MAX {
+            let buffer = value.to_le_bytes();
+            match Manufacturer::decode::<LittleEndian>(&buffer).unwrap() {
+                Manufacturer::Unknown => (),
+                manufacturer => {
+                    assert_eq!(manufacturer.clone() as u16, value);
+                    let name = manufacturer.as_str();
+                    assert_eq!(name, format!("{:?}", manufacturer));
+                    let parsed: Manufacturer = name.parse().unwrap();
+                    assert_eq!(parsed as u16, value);
+                },
+            }
+            match GarminProduct::decode::<LittleEndian>(&buffer).unwrap() {
+                GarminProduct::Unknown => (),
+                product => {
+                    assert_eq!(product.clone() as u16, value);
+                    assert_eq!(product.as_str(), format!("{:?}", product));
+                },
+            }
+            match MesgNum::decode::<LittleEndian>(&buffer).unwrap() {
+                MesgNum::Unknown => (),
+                mesg_num => assert_eq!(mesg_num.value(), value),
+            }
+        }
+        for value in 0..=u8::MAX {
+            match SubSport::decode::<LittleEndian>(&[value]).unwrap() {
+                SubSport::Unknown => (),
+                sub_sport => {
+                    assert_eq!(sub_sport.clone() as u8, value);
+                    let parsed: SubSport = sub_sport.as_str().parse().unwrap();
+                    assert_eq!(parsed as u8, value);
+                },
+            }
+        }
+
+        assert_eq!(Manufacturer::Unknown.as_str(), "Unknown");
+        assert!("Unknown".parse::<Manufacturer>().is_err());
+        assert!("garmin".parse::<Manufacturer>().is_err());
+        assert!(matches!("Garmin".parse(), Ok(Manufacturer::Garmin)));
+        assert!(matches!("Fenix5".parse(), Ok(GarminProduct::Fenix5)));
+    }
+}
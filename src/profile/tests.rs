@@ -0,0 +1,236 @@
+//! Tests of the generated `messages` and `types` modules,
+//! kept here so that regenerating them doesn't drop the
+//! tests.
+
+mod messages {
+    use byteorder::LittleEndian;
+    use profile::{
+        self,
+        messages::*,
+    };
+    use testutil::mesg;
+    use types::field::{
+        Field as _,
+        Value,
+    };
+
+    #[test]
+    fn getters_return_the_last_occurrence() {
+        let record =
+            mesg(20).u32(253, 1000).u8(3, 140).u8(3, 150).u32(5, 12345).build();
+
+        assert_eq!(Record::timestamp(&record.0).unwrap().raw_value.0, 1000);
+        assert_eq!(Record::heart_rate(&record.0).unwrap().raw_value.0, 150);
+        assert_eq!(Record::distance(&record.0).unwrap().value(), 123.45);
+        assert!(Record::power(&record.0).is_none());
+        assert!(Lap::timestamp(&record.0).is_none());
+
+        let file_id = mesg(0).u8(0, 4).build();
+        assert!(matches!(
+            FileId::type_(&file_id.0).unwrap().raw_value,
+            profile::types::File::Activity
+        ));
+    }
+
+    #[test]
+    fn units_without_decoding() {
+        assert_eq!(field_units(20, 6), Some("m/s"));
+        assert_eq!(field_units(20, 99), None);
+        // No units
+        assert_eq!(field_units(0, 0), None);
+
+        let record = mesg(20).u32(253, 1000).u16(6, 5250).build();
+        assert_eq!(Record::speed(&record.0).unwrap().units, Some("m/s"));
+        let timestamp = Record::timestamp(&record.0).unwrap();
+        assert_eq!(timestamp.units, field_units(20, 253));
+
+        let sorted = FIELDS
+            .windows(2)
+            .all(|pair| (pair[0].0, pair[0].1) < (pair[1].0, pair[1].1));
+        assert!(sorted);
+    }
+
+    #[test]
+    fn decoded_units_match_the_table() {
+        let mut failures = Vec::new();
+        for &(mesg_num, field_def_num, name, units, ..) in FIELDS {
+            if units.is_none() {
+                continue
+            }
+            let decoded = [1, 2, 4, 8]
+                .iter()
+                .filter_map(|&size| {
+                    Message::decode::<LittleEndian>(
+                        &[0; 8][..size],
+                        mesg_num,
+                        field_def_num,
+                    )
+                    .ok()
+                })
+                .next()
+                .unwrap();
+            // `Message` doesn't hand out its field's units, but
+            // its `Debug` output shows them
+            let expected = format!("units: {:?}", units);
+            if !format!("{:?}", decoded).contains(&expected) {
+                failures.push(format!(
+                    "{}.{} ({}, {}): expected {}, got {:?}",
+                    message_name(mesg_num).unwrap(),
+                    name,
+                    mesg_num,
+                    field_def_num,
+                    expected,
+                    decoded
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn names_and_values() {
+        assert_eq!(message_name(20), Some("record"));
+        assert_eq!(message_name(0xFF00), None);
+        assert_eq!(field_name(20, 6), Some("speed"));
+        assert_eq!(field_name(0, 0), Some("type"));
+        assert_eq!(field_name(20, 99), None);
+
+        let record =
+            mesg(20).u16(6, 5250).u8(3, 0xFF).bytes(99, &[1, 2]).build();
+        let fields: Vec<(u8, Value)> = record
+            .0
+            .iter()
+            .map(|field| (field.field_def_num(), field.value()))
+            .collect();
+        assert_eq!(fields[0], (6, Value::Number(5.25)));
+        assert_eq!(fields[1], (3, Value::Invalid));
+        assert_eq!(fields[2], (99, Value::Bytes(vec![1, 2])));
+
+        let session = mesg(18).u8(5, 1).bytes(253, &[0xFF; 4]).build();
+        assert_eq!(session.0[0].value(), Value::Enum("Running".to_string()));
+        assert_eq!(session.0[1].value(), Value::Invalid);
+    }
+
+    #[test]
+    fn scaled_fields_display_their_implied_decimals() {
+        let record = mesg(20).u16(6, 5260).u8(3, 140).build();
+        let speed = Record::speed(&record.0).unwrap();
+        assert_eq!(speed.to_string(), "5.260");
+        assert_eq!(format!("{:.1}", speed), "5.3");
+        assert_eq!(format!("{:>7}", speed), "  5.260");
+        let heart_rate = Record::heart_rate(&record.0).unwrap();
+        assert_eq!(heart_rate.to_string(), "140");
+    }
+
+    #[test]
+    fn scaled_fields_decode_to_scaled_values() {
+        // Typed as the `weight` enum, which only names the
+        // value meaning "calculating", so readings don't come
+        // through as numbers
+        const NOT_NUMBERS: &[(u16, u8)] = &[(30, 0)];
+
+        let mut failures = Vec::new();
+        let mut checked = 0;
+        for &(mesg_num, field_def_num, name, _, scale, offset) in FIELDS {
+            if scale.is_none() && offset.is_none() {
+                continue
+            }
+            if NOT_NUMBERS.contains(&(mesg_num, field_def_num)) {
+                continue
+            }
+            let field = format!(
+                "{}.{} ({}, {})",
+                message_name(mesg_num).unwrap(),
+                name,
+                mesg_num,
+                field_def_num
+            );
+
+            // The size of the field's base type, or of one
+            // element of an array
+            let size = [1, 2, 4, 8]
+                .iter()
+                .cloned()
+                .find(|&size| {
+                    let buffer = &[0; 8][..size];
+                    Message::decode::<LittleEndian>(
+                        buffer,
+                        mesg_num,
+                        field_def_num,
+                    )
+                    .is_ok()
+                })
+                .unwrap();
+
+            for &raw in &[1u64, 100, 250, 30_000, 2_000_000] {
+                if size < 8 && raw >> (8 * size - 1) != 0 {
+                    continue
+                }
+                let buffer = &raw.to_le_bytes()[..size];
+                let decoded = Message::decode::<LittleEndian>(
+                    buffer,
+                    mesg_num,
+                    field_def_num,
+                )
+                .unwrap();
+                let expected =
+                    raw as f64 / scale.unwrap_or(1.0) - offset.unwrap_or(0.0);
+                let close = |value: f64| {
+                    (value - expected).abs() <= 1e-9 * expected.abs()
+                };
+                match decoded.value() {
+                    Value::Number(value) if close(value) => {},
+                    Value::Numbers(ref values)
+                        if values.len() == 1 && close(values[0]) => {},
+                    value => {
+                        failures.push(format!(
+                            "{}: raw {} should be {}, got {:?}",
+                            field, raw, expected, value
+                        ))
+                    },
+                }
+                checked += 1;
+            }
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+        assert!(checked > 500);
+
+        // Power phase angles are scaled so 256 is a full turn
+        let phase =
+            Message::decode::<LittleEndian>(&[64], 20, 69).unwrap().value();
+        match phase {
+            Value::Number(degrees) => assert!((degrees - 90.0).abs() < 1e-4),
+            value => panic!("expected a number, got {:?}", value),
+        }
+    }
+}
+
+mod types {
+    use profile::types::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn mesg_nums_round_trip() {
+        let known: Vec<MesgNum> = (0..=u16::MAX)
+            .filter_map(|value| MesgNum::try_from(value).ok())
+            .collect();
+        assert_eq!(known.len(), 89);
+        for mesg_num in &known {
+            assert_eq!(MesgNum::try_from(mesg_num.value()), Ok(*mesg_num));
+        }
+        assert!(known.iter().all(|mesg_num| {
+            match *mesg_num {
+                MesgNum::MfgRangeMin | MesgNum::MfgRangeMax => {
+                    mesg_num.name().is_none()
+                },
+                _ => mesg_num.name().is_some(),
+            }
+        }));
+
+        assert_eq!(MesgNum::Record.value(), 20);
+        assert_eq!(MesgNum::Record.name(), Some("record"));
+        assert_eq!(MesgNum::try_from(20), Ok(MesgNum::Record));
+        assert_eq!(MesgNum::try_from(11), Err(()));
+        assert_eq!(MesgNum::try_from(0xFF01), Err(()));
+    }
+}
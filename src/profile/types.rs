@@ -1,7 +1,10 @@
-# ! [ doc = "Generated for FIT SDK profile version: " ] # ! [ doc = "20.66.00" ]use byteorder::ByteOrder;
+#![doc = "Generated for FIT SDK profile version: "]
+#![doc = "20.66.00"]
+use byteorder::ByteOrder;
 use error;
 use profile;
-#[derive(Debug,Clone)]
+use std::convert::TryFrom;
+#[derive(Debug, Clone)]
 pub enum File {
     #[doc = "Read only, single file. Must be in root directory."]
     Device = 1,
@@ -74,7 +77,8 @@ impl File {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(File, u8, 0xFF);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MesgNum {
     FileId = 0,
     Capabilities = 1,
@@ -97,6 +101,7 @@ pub enum MesgNum {
     Workout = 26,
     WorkoutStep = 27,
     Schedule = 28,
+    Location = 29,
     WeightScale = 30,
     Course = 31,
     CoursePoint = 32,
@@ -171,100 +176,251 @@ pub enum MesgNum {
 impl MesgNum {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(MesgNum::FileId),
-            1 => Ok(MesgNum::Capabilities),
-            2 => Ok(MesgNum::DeviceSettings),
-            3 => Ok(MesgNum::UserProfile),
-            4 => Ok(MesgNum::HrmProfile),
-            5 => Ok(MesgNum::SdmProfile),
-            6 => Ok(MesgNum::BikeProfile),
-            7 => Ok(MesgNum::ZonesTarget),
-            8 => Ok(MesgNum::HrZone),
-            9 => Ok(MesgNum::PowerZone),
-            10 => Ok(MesgNum::MetZone),
-            12 => Ok(MesgNum::Sport),
-            15 => Ok(MesgNum::Goal),
-            18 => Ok(MesgNum::Session),
-            19 => Ok(MesgNum::Lap),
-            20 => Ok(MesgNum::Record),
-            21 => Ok(MesgNum::Event),
-            23 => Ok(MesgNum::DeviceInfo),
-            26 => Ok(MesgNum::Workout),
-            27 => Ok(MesgNum::WorkoutStep),
-            28 => Ok(MesgNum::Schedule),
-            30 => Ok(MesgNum::WeightScale),
-            31 => Ok(MesgNum::Course),
-            32 => Ok(MesgNum::CoursePoint),
-            33 => Ok(MesgNum::Totals),
-            34 => Ok(MesgNum::Activity),
-            35 => Ok(MesgNum::Software),
-            37 => Ok(MesgNum::FileCapabilities),
-            38 => Ok(MesgNum::MesgCapabilities),
-            39 => Ok(MesgNum::FieldCapabilities),
-            49 => Ok(MesgNum::FileCreator),
-            51 => Ok(MesgNum::BloodPressure),
-            53 => Ok(MesgNum::SpeedZone),
-            55 => Ok(MesgNum::Monitoring),
-            72 => Ok(MesgNum::TrainingFile),
-            78 => Ok(MesgNum::Hrv),
-            80 => Ok(MesgNum::AntRx),
-            81 => Ok(MesgNum::AntTx),
-            82 => Ok(MesgNum::AntChannelId),
-            101 => Ok(MesgNum::Length),
-            103 => Ok(MesgNum::MonitoringInfo),
-            105 => Ok(MesgNum::Pad),
-            106 => Ok(MesgNum::SlaveDevice),
-            127 => Ok(MesgNum::Connectivity),
-            128 => Ok(MesgNum::WeatherConditions),
-            129 => Ok(MesgNum::WeatherAlert),
-            131 => Ok(MesgNum::CadenceZone),
-            132 => Ok(MesgNum::Hr),
-            142 => Ok(MesgNum::SegmentLap),
-            145 => Ok(MesgNum::MemoGlob),
-            148 => Ok(MesgNum::SegmentId),
-            149 => Ok(MesgNum::SegmentLeaderboardEntry),
-            150 => Ok(MesgNum::SegmentPoint),
-            151 => Ok(MesgNum::SegmentFile),
-            158 => Ok(MesgNum::WorkoutSession),
-            159 => Ok(MesgNum::WatchfaceSettings),
-            160 => Ok(MesgNum::GpsMetadata),
-            161 => Ok(MesgNum::CameraEvent),
-            162 => Ok(MesgNum::TimestampCorrelation),
-            164 => Ok(MesgNum::GyroscopeData),
-            165 => Ok(MesgNum::AccelerometerData),
-            167 => Ok(MesgNum::ThreeDSensorCalibration),
-            169 => Ok(MesgNum::VideoFrame),
-            174 => Ok(MesgNum::ObdiiData),
-            177 => Ok(MesgNum::NmeaSentence),
-            178 => Ok(MesgNum::AviationAttitude),
-            184 => Ok(MesgNum::Video),
-            185 => Ok(MesgNum::VideoTitle),
-            186 => Ok(MesgNum::VideoDescription),
-            187 => Ok(MesgNum::VideoClip),
-            188 => Ok(MesgNum::OhrSettings),
-            200 => Ok(MesgNum::ExdScreenConfiguration),
-            201 => Ok(MesgNum::ExdDataFieldConfiguration),
-            202 => Ok(MesgNum::ExdDataConceptConfiguration),
-            206 => Ok(MesgNum::FieldDescription),
-            207 => Ok(MesgNum::DeveloperDataId),
-            208 => Ok(MesgNum::MagnetometerData),
-            209 => Ok(MesgNum::BarometerData),
-            210 => Ok(MesgNum::OneDSensorCalibration),
-            225 => Ok(MesgNum::Set),
-            227 => Ok(MesgNum::StressLevel),
-            258 => Ok(MesgNum::DiveSettings),
-            259 => Ok(MesgNum::DiveGas),
-            262 => Ok(MesgNum::DiveAlarm),
-            264 => Ok(MesgNum::ExerciseTitle),
-            268 => Ok(MesgNum::DiveSummary),
-            65280 => Ok(MesgNum::MfgRangeMin),
-            65534 => Ok(MesgNum::MfgRangeMax),
-            _ => Ok(MesgNum::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(MESG_NUM.get(base_value.0).unwrap_or(MesgNum::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            MesgNum::Unknown => "Unknown",
+            _ => MESG_NUM.name(*self as u16).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for MesgNum {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        MESG_NUM.parse(name).ok_or(())
+    }
+}
+static MESG_NUM: profile::table::EnumTable<u16, MesgNum> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, MesgNum::FileId),
+            (1, MesgNum::Capabilities),
+            (2, MesgNum::DeviceSettings),
+            (3, MesgNum::UserProfile),
+            (4, MesgNum::HrmProfile),
+            (5, MesgNum::SdmProfile),
+            (6, MesgNum::BikeProfile),
+            (7, MesgNum::ZonesTarget),
+            (8, MesgNum::HrZone),
+            (9, MesgNum::PowerZone),
+            (10, MesgNum::MetZone),
+            (12, MesgNum::Sport),
+            (15, MesgNum::Goal),
+            (18, MesgNum::Session),
+            (19, MesgNum::Lap),
+            (20, MesgNum::Record),
+            (21, MesgNum::Event),
+            (23, MesgNum::DeviceInfo),
+            (26, MesgNum::Workout),
+            (27, MesgNum::WorkoutStep),
+            (28, MesgNum::Schedule),
+            (29, MesgNum::Location),
+            (30, MesgNum::WeightScale),
+            (31, MesgNum::Course),
+            (32, MesgNum::CoursePoint),
+            (33, MesgNum::Totals),
+            (34, MesgNum::Activity),
+            (35, MesgNum::Software),
+            (37, MesgNum::FileCapabilities),
+            (38, MesgNum::MesgCapabilities),
+            (39, MesgNum::FieldCapabilities),
+            (49, MesgNum::FileCreator),
+            (51, MesgNum::BloodPressure),
+            (53, MesgNum::SpeedZone),
+            (55, MesgNum::Monitoring),
+            (72, MesgNum::TrainingFile),
+            (78, MesgNum::Hrv),
+            (80, MesgNum::AntRx),
+            (81, MesgNum::AntTx),
+            (82, MesgNum::AntChannelId),
+            (101, MesgNum::Length),
+            (103, MesgNum::MonitoringInfo),
+            (105, MesgNum::Pad),
+            (106, MesgNum::SlaveDevice),
+            (127, MesgNum::Connectivity),
+            (128, MesgNum::WeatherConditions),
+            (129, MesgNum::WeatherAlert),
+            (131, MesgNum::CadenceZone),
+            (132, MesgNum::Hr),
+            (142, MesgNum::SegmentLap),
+            (145, MesgNum::MemoGlob),
+            (148, MesgNum::SegmentId),
+            (149, MesgNum::SegmentLeaderboardEntry),
+            (150, MesgNum::SegmentPoint),
+            (151, MesgNum::SegmentFile),
+            (158, MesgNum::WorkoutSession),
+            (159, MesgNum::WatchfaceSettings),
+            (160, MesgNum::GpsMetadata),
+            (161, MesgNum::CameraEvent),
+            (162, MesgNum::TimestampCorrelation),
+            (164, MesgNum::GyroscopeData),
+            (165, MesgNum::AccelerometerData),
+            (167, MesgNum::ThreeDSensorCalibration),
+            (169, MesgNum::VideoFrame),
+            (174, MesgNum::ObdiiData),
+            (177, MesgNum::NmeaSentence),
+            (178, MesgNum::AviationAttitude),
+            (184, MesgNum::Video),
+            (185, MesgNum::VideoTitle),
+            (186, MesgNum::VideoDescription),
+            (187, MesgNum::VideoClip),
+            (188, MesgNum::OhrSettings),
+            (200, MesgNum::ExdScreenConfiguration),
+            (201, MesgNum::ExdDataFieldConfiguration),
+            (202, MesgNum::ExdDataConceptConfiguration),
+            (206, MesgNum::FieldDescription),
+            (207, MesgNum::DeveloperDataId),
+            (208, MesgNum::MagnetometerData),
+            (209, MesgNum::BarometerData),
+            (210, MesgNum::OneDSensorCalibration),
+            (225, MesgNum::Set),
+            (227, MesgNum::StressLevel),
+            (258, MesgNum::DiveSettings),
+            (259, MesgNum::DiveGas),
+            (262, MesgNum::DiveAlarm),
+            (264, MesgNum::ExerciseTitle),
+            (268, MesgNum::DiveSummary),
+            (65280, MesgNum::MfgRangeMin),
+            (65534, MesgNum::MfgRangeMax),
+        ],
+        names:   &[
+            "FileId",
+            "Capabilities",
+            "DeviceSettings",
+            "UserProfile",
+            "HrmProfile",
+            "SdmProfile",
+            "BikeProfile",
+            "ZonesTarget",
+            "HrZone",
+            "PowerZone",
+            "MetZone",
+            "Sport",
+            "Goal",
+            "Session",
+            "Lap",
+            "Record",
+            "Event",
+            "DeviceInfo",
+            "Workout",
+            "WorkoutStep",
+            "Schedule",
+            "Location",
+            "WeightScale",
+            "Course",
+            "CoursePoint",
+            "Totals",
+            "Activity",
+            "Software",
+            "FileCapabilities",
+            "MesgCapabilities",
+            "FieldCapabilities",
+            "FileCreator",
+            "BloodPressure",
+            "SpeedZone",
+            "Monitoring",
+            "TrainingFile",
+            "Hrv",
+            "AntRx",
+            "AntTx",
+            "AntChannelId",
+            "Length",
+            "MonitoringInfo",
+            "Pad",
+            "SlaveDevice",
+            "Connectivity",
+            "WeatherConditions",
+            "WeatherAlert",
+            "CadenceZone",
+            "Hr",
+            "SegmentLap",
+            "MemoGlob",
+            "SegmentId",
+            "SegmentLeaderboardEntry",
+            "SegmentPoint",
+            "SegmentFile",
+            "WorkoutSession",
+            "WatchfaceSettings",
+            "GpsMetadata",
+            "CameraEvent",
+            "TimestampCorrelation",
+            "GyroscopeData",
+            "AccelerometerData",
+            "ThreeDSensorCalibration",
+            "VideoFrame",
+            "ObdiiData",
+            "NmeaSentence",
+            "AviationAttitude",
+            "Video",
+            "VideoTitle",
+            "VideoDescription",
+            "VideoClip",
+            "OhrSettings",
+            "ExdScreenConfiguration",
+            "ExdDataFieldConfiguration",
+            "ExdDataConceptConfiguration",
+            "FieldDescription",
+            "DeveloperDataId",
+            "MagnetometerData",
+            "BarometerData",
+            "OneDSensorCalibration",
+            "Set",
+            "StressLevel",
+            "DiveSettings",
+            "DiveGas",
+            "DiveAlarm",
+            "ExerciseTitle",
+            "DiveSummary",
+            "MfgRangeMin",
+            "MfgRangeMax",
+        ],
+        by_name: &[
+            61, 26, 39, 37, 38, 66, 78, 6, 32, 47, 58, 1, 44, 23, 24, 76, 17,
+            2, 84, 83, 82, 86, 16, 74, 73, 72, 85, 30, 75, 28, 31, 0, 12, 57,
+            60, 48, 8, 4, 36, 14, 40, 21, 77, 50, 29, 10, 88, 87, 34, 41, 65,
+            64, 71, 79, 42, 9, 15, 20, 5, 54, 51, 49, 52, 53, 13, 80, 43, 27,
+            33, 11, 81, 62, 59, 25, 35, 3, 67, 70, 69, 63, 68, 56, 46, 45, 22,
+            18, 55, 19, 7,
+        ],
+    };
+encode_enum!(MesgNum, u16, 0xFFFF);
+impl MesgNum {
+    #[doc = "The message number, `0xFFFF` for `Unknown`."]
+    pub fn value(&self) -> u16 {
+        *self as u16
+    }
+
+    #[doc = "The snake case name of the message in the profile, as \
+             `profile::messages::message_name` gives it. `pad` has no message \
+             of its own, and the manufacturer range bounds and `Unknown` have \
+             no name."]
+    pub fn name(&self) -> Option<&'static str> {
+        match *self {
+            MesgNum::Pad => Some("pad"),
+            MesgNum::MfgRangeMin | MesgNum::MfgRangeMax | MesgNum::Unknown => {
+                None
+            },
+            _ => profile::messages::message_name(self.value()),
+        }
+    }
+}
+#[doc = "Fails for numbers that aren't in the profile's `mesg_num` list."]
+impl TryFrom<u16> for MesgNum {
+    type Error = ();
+
+    fn try_from(value: u16) -> Result<Self, ()> {
+        match MesgNum::decode::<byteorder::LittleEndian>(&value.to_le_bytes()) {
+            Ok(MesgNum::Unknown) | Err(_) => Err(()),
+            Ok(mesg_num) => Ok(mesg_num),
+        }
+    }
+}
+#[derive(Debug, Clone)]
 pub enum Checksum {
     #[doc = "Allows clear of checksum for flash memory where can only write 1 \
              to 0 without erasing sector."]
@@ -285,7 +441,8 @@ impl Checksum {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Checksum, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum FileFlags {
     Read = 2,
     Write = 4,
@@ -303,7 +460,8 @@ impl FileFlags {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(FileFlags, u8, 0);
+#[derive(Debug, Clone)]
 pub enum MesgCount {
     NumPerFile = 0,
     MaxPerFile = 1,
@@ -321,61 +479,78 @@ impl MesgCount {
         }
     }
 }
+encode_enum!(MesgCount, u8, 0xFF);
 #[doc = "seconds since UTC 00:00 Dec 31 1989; if date_time is < 0x10000000 \
          then it is system time (seconds from device power on)"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateTime(pub u32);
 impl DateTime {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-        Ok(DateTime(T::read_u32(buffer)))
+        let base_value = profile::base::Uint32::decode::<T>(buffer)?;
+        Ok(DateTime(base_value.0))
     }
 }
+encode_number!(DateTime);
 #[doc = "seconds since 00:00 Dec 31 1989 in local time zone; if date_time is < \
          0x10000000 then it is system time (seconds from device power on)"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LocalDateTime(pub u32);
 impl LocalDateTime {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-        Ok(LocalDateTime(T::read_u32(buffer)))
+        let base_value = profile::base::Uint32::decode::<T>(buffer)?;
+        Ok(LocalDateTime(base_value.0))
     }
 }
-#[derive(Debug,Clone)]
-pub enum MessageIndex {
-    #[doc = "message is selected if set"]
-    Selected = 32768,
-    #[doc = "reserved (default 0)"]
-    Reserved = 28672,
-    #[doc = "index"]
-    Mask = 4095,
-    Unknown,
-}
+encode_number!(LocalDateTime);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessageIndex(pub u16);
 impl MessageIndex {
+    #[doc = "index"]
+    pub const MASK: u16 = 0x0FFF;
+    #[doc = "reserved (default 0)"]
+    pub const RESERVED: u16 = 0x7000;
+    #[doc = "message is selected if set"]
+    pub const SELECTED: u16 = 0x8000;
+
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            32768 => Ok(MessageIndex::Selected),
-            28672 => Ok(MessageIndex::Reserved),
-            4095 => Ok(MessageIndex::Mask),
-            _ => Ok(MessageIndex::Unknown),
-        }
+        Ok(MessageIndex(base_value.0))
+    }
+
+    #[doc = r" The index itself, without the selected and reserved bits."]
+    pub fn index(&self) -> u16 {
+        self.0 & MessageIndex::MASK
+    }
+
+    #[doc = r" Whether the selected bit is set."]
+    pub fn is_selected(&self) -> bool {
+        self.0 & MessageIndex::SELECTED != 0
     }
 }
-#[derive(Debug,Clone)]
-pub enum DeviceIndex {
-    #[doc = "Creator of the file is always device index 0."]
-    Creator = 0,
-    Unknown,
+encode_number!(MessageIndex);
+impl profile::base::Valid for MessageIndex {
+    fn is_valid(&self) -> bool {
+        self.0 != 0xFFFF
+    }
 }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeviceIndex(pub u8);
 impl DeviceIndex {
+    #[doc = "Creator of the file is always device index 0."]
+    pub const CREATOR: DeviceIndex = DeviceIndex(0);
+
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint8::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(DeviceIndex::Creator),
-            _ => Ok(DeviceIndex::Unknown),
-        }
+        Ok(DeviceIndex(base_value.0))
+    }
+}
+encode_number!(DeviceIndex);
+impl profile::base::Valid for DeviceIndex {
+    fn is_valid(&self) -> bool {
+        self.0 != 0xFF
     }
 }
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum Gender {
     Female = 0,
     Male = 1,
@@ -391,7 +566,8 @@ impl Gender {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Gender, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Language {
     English = 0,
     French = 1,
@@ -481,8 +657,9 @@ impl Language {
         }
     }
 }
+encode_enum!(Language, u8, 0xFF);
 #[doc = "Bit field corresponding to language enum type (1 << language)."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum LanguageBits0 {
     English = 1,
     French = 2,
@@ -510,7 +687,8 @@ impl LanguageBits0 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LanguageBits0, u8, 0);
+#[derive(Debug, Clone)]
 pub enum LanguageBits1 {
     Dutch = 1,
     Finnish = 2,
@@ -538,7 +716,8 @@ impl LanguageBits1 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LanguageBits1, u8, 0);
+#[derive(Debug, Clone)]
 pub enum LanguageBits2 {
     Slovenian = 1,
     Swedish = 2,
@@ -566,7 +745,8 @@ impl LanguageBits2 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LanguageBits2, u8, 0);
+#[derive(Debug, Clone)]
 pub enum LanguageBits3 {
     Bulgarian = 1,
     Romanian = 2,
@@ -594,7 +774,8 @@ impl LanguageBits3 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LanguageBits3, u8, 0);
+#[derive(Debug, Clone)]
 pub enum LanguageBits4 {
     BrazilianPortuguese = 1,
     Indonesian = 2,
@@ -618,7 +799,8 @@ impl LanguageBits4 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LanguageBits4, u8, 0);
+#[derive(Debug, Clone)]
 pub enum TimeZone {
     Almaty = 0,
     Bangkok = 1,
@@ -731,118 +913,255 @@ pub enum TimeZone {
 impl TimeZone {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(TimeZone::Almaty),
-            1 => Ok(TimeZone::Bangkok),
-            2 => Ok(TimeZone::Bombay),
-            3 => Ok(TimeZone::Brasilia),
-            4 => Ok(TimeZone::Cairo),
-            5 => Ok(TimeZone::CapeVerdeIs),
-            6 => Ok(TimeZone::Darwin),
-            7 => Ok(TimeZone::Eniwetok),
-            8 => Ok(TimeZone::Fiji),
-            9 => Ok(TimeZone::HongKong),
-            10 => Ok(TimeZone::Islamabad),
-            11 => Ok(TimeZone::Kabul),
-            12 => Ok(TimeZone::Magadan),
-            13 => Ok(TimeZone::MidAtlantic),
-            14 => Ok(TimeZone::Moscow),
-            15 => Ok(TimeZone::Muscat),
-            16 => Ok(TimeZone::Newfoundland),
-            17 => Ok(TimeZone::Samoa),
-            18 => Ok(TimeZone::Sydney),
-            19 => Ok(TimeZone::Tehran),
-            20 => Ok(TimeZone::Tokyo),
-            21 => Ok(TimeZone::UsAlaska),
-            22 => Ok(TimeZone::UsAtlantic),
-            23 => Ok(TimeZone::UsCentral),
-            24 => Ok(TimeZone::UsEastern),
-            25 => Ok(TimeZone::UsHawaii),
-            26 => Ok(TimeZone::UsMountain),
-            27 => Ok(TimeZone::UsPacific),
-            28 => Ok(TimeZone::Other),
-            29 => Ok(TimeZone::Auckland),
-            30 => Ok(TimeZone::Kathmandu),
-            31 => Ok(TimeZone::EuropeWesternWet),
-            32 => Ok(TimeZone::EuropeCentralCet),
-            33 => Ok(TimeZone::EuropeEasternEet),
-            34 => Ok(TimeZone::Jakarta),
-            35 => Ok(TimeZone::Perth),
-            36 => Ok(TimeZone::Adelaide),
-            37 => Ok(TimeZone::Brisbane),
-            38 => Ok(TimeZone::Tasmania),
-            39 => Ok(TimeZone::Iceland),
-            40 => Ok(TimeZone::Amsterdam),
-            41 => Ok(TimeZone::Athens),
-            42 => Ok(TimeZone::Barcelona),
-            43 => Ok(TimeZone::Berlin),
-            44 => Ok(TimeZone::Brussels),
-            45 => Ok(TimeZone::Budapest),
-            46 => Ok(TimeZone::Copenhagen),
-            47 => Ok(TimeZone::Dublin),
-            48 => Ok(TimeZone::Helsinki),
-            49 => Ok(TimeZone::Lisbon),
-            50 => Ok(TimeZone::London),
-            51 => Ok(TimeZone::Madrid),
-            52 => Ok(TimeZone::Munich),
-            53 => Ok(TimeZone::Oslo),
-            54 => Ok(TimeZone::Paris),
-            55 => Ok(TimeZone::Prague),
-            56 => Ok(TimeZone::Reykjavik),
-            57 => Ok(TimeZone::Rome),
-            58 => Ok(TimeZone::Stockholm),
-            59 => Ok(TimeZone::Vienna),
-            60 => Ok(TimeZone::Warsaw),
-            61 => Ok(TimeZone::Zurich),
-            62 => Ok(TimeZone::Quebec),
-            63 => Ok(TimeZone::Ontario),
-            64 => Ok(TimeZone::Manitoba),
-            65 => Ok(TimeZone::Saskatchewan),
-            66 => Ok(TimeZone::Alberta),
-            67 => Ok(TimeZone::BritishColumbia),
-            68 => Ok(TimeZone::Boise),
-            69 => Ok(TimeZone::Boston),
-            70 => Ok(TimeZone::Chicago),
-            71 => Ok(TimeZone::Dallas),
-            72 => Ok(TimeZone::Denver),
-            73 => Ok(TimeZone::KansasCity),
-            74 => Ok(TimeZone::LasVegas),
-            75 => Ok(TimeZone::LosAngeles),
-            76 => Ok(TimeZone::Miami),
-            77 => Ok(TimeZone::Minneapolis),
-            78 => Ok(TimeZone::NewYork),
-            79 => Ok(TimeZone::NewOrleans),
-            80 => Ok(TimeZone::Phoenix),
-            81 => Ok(TimeZone::SantaFe),
-            82 => Ok(TimeZone::Seattle),
-            83 => Ok(TimeZone::WashingtonDc),
-            84 => Ok(TimeZone::UsArizona),
-            85 => Ok(TimeZone::Chita),
-            86 => Ok(TimeZone::Ekaterinburg),
-            87 => Ok(TimeZone::Irkutsk),
-            88 => Ok(TimeZone::Kaliningrad),
-            89 => Ok(TimeZone::Krasnoyarsk),
-            90 => Ok(TimeZone::Novosibirsk),
-            91 => Ok(TimeZone::PetropavlovskKamchatskiy),
-            92 => Ok(TimeZone::Samara),
-            93 => Ok(TimeZone::Vladivostok),
-            94 => Ok(TimeZone::MexicoCentral),
-            95 => Ok(TimeZone::MexicoMountain),
-            96 => Ok(TimeZone::MexicoPacific),
-            97 => Ok(TimeZone::CapeTown),
-            98 => Ok(TimeZone::Winkhoek),
-            99 => Ok(TimeZone::Lagos),
-            100 => Ok(TimeZone::Riyahd),
-            101 => Ok(TimeZone::Venezuela),
-            102 => Ok(TimeZone::AustraliaLh),
-            103 => Ok(TimeZone::Santiago),
-            253 => Ok(TimeZone::Manual),
-            254 => Ok(TimeZone::Automatic),
-            _ => Ok(TimeZone::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(TIME_ZONE.get(base_value.0).unwrap_or(TimeZone::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            TimeZone::Unknown => "Unknown",
+            _ => TIME_ZONE.name(self.clone() as u8).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for TimeZone {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        TIME_ZONE.parse(name).ok_or(())
+    }
+}
+static TIME_ZONE: profile::table::EnumTable<u8, TimeZone> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, TimeZone::Almaty),
+            (1, TimeZone::Bangkok),
+            (2, TimeZone::Bombay),
+            (3, TimeZone::Brasilia),
+            (4, TimeZone::Cairo),
+            (5, TimeZone::CapeVerdeIs),
+            (6, TimeZone::Darwin),
+            (7, TimeZone::Eniwetok),
+            (8, TimeZone::Fiji),
+            (9, TimeZone::HongKong),
+            (10, TimeZone::Islamabad),
+            (11, TimeZone::Kabul),
+            (12, TimeZone::Magadan),
+            (13, TimeZone::MidAtlantic),
+            (14, TimeZone::Moscow),
+            (15, TimeZone::Muscat),
+            (16, TimeZone::Newfoundland),
+            (17, TimeZone::Samoa),
+            (18, TimeZone::Sydney),
+            (19, TimeZone::Tehran),
+            (20, TimeZone::Tokyo),
+            (21, TimeZone::UsAlaska),
+            (22, TimeZone::UsAtlantic),
+            (23, TimeZone::UsCentral),
+            (24, TimeZone::UsEastern),
+            (25, TimeZone::UsHawaii),
+            (26, TimeZone::UsMountain),
+            (27, TimeZone::UsPacific),
+            (28, TimeZone::Other),
+            (29, TimeZone::Auckland),
+            (30, TimeZone::Kathmandu),
+            (31, TimeZone::EuropeWesternWet),
+            (32, TimeZone::EuropeCentralCet),
+            (33, TimeZone::EuropeEasternEet),
+            (34, TimeZone::Jakarta),
+            (35, TimeZone::Perth),
+            (36, TimeZone::Adelaide),
+            (37, TimeZone::Brisbane),
+            (38, TimeZone::Tasmania),
+            (39, TimeZone::Iceland),
+            (40, TimeZone::Amsterdam),
+            (41, TimeZone::Athens),
+            (42, TimeZone::Barcelona),
+            (43, TimeZone::Berlin),
+            (44, TimeZone::Brussels),
+            (45, TimeZone::Budapest),
+            (46, TimeZone::Copenhagen),
+            (47, TimeZone::Dublin),
+            (48, TimeZone::Helsinki),
+            (49, TimeZone::Lisbon),
+            (50, TimeZone::London),
+            (51, TimeZone::Madrid),
+            (52, TimeZone::Munich),
+            (53, TimeZone::Oslo),
+            (54, TimeZone::Paris),
+            (55, TimeZone::Prague),
+            (56, TimeZone::Reykjavik),
+            (57, TimeZone::Rome),
+            (58, TimeZone::Stockholm),
+            (59, TimeZone::Vienna),
+            (60, TimeZone::Warsaw),
+            (61, TimeZone::Zurich),
+            (62, TimeZone::Quebec),
+            (63, TimeZone::Ontario),
+            (64, TimeZone::Manitoba),
+            (65, TimeZone::Saskatchewan),
+            (66, TimeZone::Alberta),
+            (67, TimeZone::BritishColumbia),
+            (68, TimeZone::Boise),
+            (69, TimeZone::Boston),
+            (70, TimeZone::Chicago),
+            (71, TimeZone::Dallas),
+            (72, TimeZone::Denver),
+            (73, TimeZone::KansasCity),
+            (74, TimeZone::LasVegas),
+            (75, TimeZone::LosAngeles),
+            (76, TimeZone::Miami),
+            (77, TimeZone::Minneapolis),
+            (78, TimeZone::NewYork),
+            (79, TimeZone::NewOrleans),
+            (80, TimeZone::Phoenix),
+            (81, TimeZone::SantaFe),
+            (82, TimeZone::Seattle),
+            (83, TimeZone::WashingtonDc),
+            (84, TimeZone::UsArizona),
+            (85, TimeZone::Chita),
+            (86, TimeZone::Ekaterinburg),
+            (87, TimeZone::Irkutsk),
+            (88, TimeZone::Kaliningrad),
+            (89, TimeZone::Krasnoyarsk),
+            (90, TimeZone::Novosibirsk),
+            (91, TimeZone::PetropavlovskKamchatskiy),
+            (92, TimeZone::Samara),
+            (93, TimeZone::Vladivostok),
+            (94, TimeZone::MexicoCentral),
+            (95, TimeZone::MexicoMountain),
+            (96, TimeZone::MexicoPacific),
+            (97, TimeZone::CapeTown),
+            (98, TimeZone::Winkhoek),
+            (99, TimeZone::Lagos),
+            (100, TimeZone::Riyahd),
+            (101, TimeZone::Venezuela),
+            (102, TimeZone::AustraliaLh),
+            (103, TimeZone::Santiago),
+            (253, TimeZone::Manual),
+            (254, TimeZone::Automatic),
+        ],
+        names:   &[
+            "Almaty",
+            "Bangkok",
+            "Bombay",
+            "Brasilia",
+            "Cairo",
+            "CapeVerdeIs",
+            "Darwin",
+            "Eniwetok",
+            "Fiji",
+            "HongKong",
+            "Islamabad",
+            "Kabul",
+            "Magadan",
+            "MidAtlantic",
+            "Moscow",
+            "Muscat",
+            "Newfoundland",
+            "Samoa",
+            "Sydney",
+            "Tehran",
+            "Tokyo",
+            "UsAlaska",
+            "UsAtlantic",
+            "UsCentral",
+            "UsEastern",
+            "UsHawaii",
+            "UsMountain",
+            "UsPacific",
+            "Other",
+            "Auckland",
+            "Kathmandu",
+            "EuropeWesternWet",
+            "EuropeCentralCet",
+            "EuropeEasternEet",
+            "Jakarta",
+            "Perth",
+            "Adelaide",
+            "Brisbane",
+            "Tasmania",
+            "Iceland",
+            "Amsterdam",
+            "Athens",
+            "Barcelona",
+            "Berlin",
+            "Brussels",
+            "Budapest",
+            "Copenhagen",
+            "Dublin",
+            "Helsinki",
+            "Lisbon",
+            "London",
+            "Madrid",
+            "Munich",
+            "Oslo",
+            "Paris",
+            "Prague",
+            "Reykjavik",
+            "Rome",
+            "Stockholm",
+            "Vienna",
+            "Warsaw",
+            "Zurich",
+            "Quebec",
+            "Ontario",
+            "Manitoba",
+            "Saskatchewan",
+            "Alberta",
+            "BritishColumbia",
+            "Boise",
+            "Boston",
+            "Chicago",
+            "Dallas",
+            "Denver",
+            "KansasCity",
+            "LasVegas",
+            "LosAngeles",
+            "Miami",
+            "Minneapolis",
+            "NewYork",
+            "NewOrleans",
+            "Phoenix",
+            "SantaFe",
+            "Seattle",
+            "WashingtonDc",
+            "UsArizona",
+            "Chita",
+            "Ekaterinburg",
+            "Irkutsk",
+            "Kaliningrad",
+            "Krasnoyarsk",
+            "Novosibirsk",
+            "PetropavlovskKamchatskiy",
+            "Samara",
+            "Vladivostok",
+            "MexicoCentral",
+            "MexicoMountain",
+            "MexicoPacific",
+            "CapeTown",
+            "Winkhoek",
+            "Lagos",
+            "Riyahd",
+            "Venezuela",
+            "AustraliaLh",
+            "Santiago",
+            "Manual",
+            "Automatic",
+        ],
+        by_name: &[
+            36, 66, 0, 40, 41, 29, 102, 105, 1, 42, 43, 68, 2, 69, 3, 37, 67,
+            44, 45, 4, 97, 5, 70, 85, 46, 71, 6, 72, 47, 86, 7, 32, 33, 31, 8,
+            48, 9, 39, 87, 10, 34, 11, 88, 73, 30, 89, 99, 74, 49, 50, 75, 51,
+            12, 64, 104, 94, 95, 96, 76, 13, 77, 14, 52, 15, 79, 78, 16, 90,
+            63, 53, 28, 54, 35, 91, 80, 55, 62, 56, 100, 57, 92, 17, 81, 103,
+            65, 82, 58, 18, 38, 19, 20, 21, 84, 22, 23, 24, 25, 26, 27, 101,
+            59, 93, 60, 83, 98, 61,
+        ],
+    };
+encode_enum!(TimeZone, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DisplayMeasure {
     Metric = 0,
     Statute = 1,
@@ -860,7 +1179,8 @@ impl DisplayMeasure {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DisplayMeasure, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DisplayHeart {
     Bpm = 0,
     Max = 1,
@@ -878,7 +1198,8 @@ impl DisplayHeart {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DisplayHeart, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DisplayPower {
     Watts = 0,
     PercentFtp = 1,
@@ -894,7 +1215,8 @@ impl DisplayPower {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DisplayPower, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DisplayPosition {
     #[doc = "dd.dddddd"]
     Degree = 0,
@@ -1032,7 +1354,8 @@ impl DisplayPosition {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DisplayPosition, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Switch {
     Off = 0,
     On = 1,
@@ -1050,7 +1373,8 @@ impl Switch {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Switch, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Sport {
     Generic = 0,
     Running = 1,
@@ -1109,63 +1433,140 @@ pub enum Sport {
 impl Sport {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(Sport::Generic),
-            1 => Ok(Sport::Running),
-            2 => Ok(Sport::Cycling),
-            3 => Ok(Sport::Transition),
-            4 => Ok(Sport::FitnessEquipment),
-            5 => Ok(Sport::Swimming),
-            6 => Ok(Sport::Basketball),
-            7 => Ok(Sport::Soccer),
-            8 => Ok(Sport::Tennis),
-            9 => Ok(Sport::AmericanFootball),
-            10 => Ok(Sport::Training),
-            11 => Ok(Sport::Walking),
-            12 => Ok(Sport::CrossCountrySkiing),
-            13 => Ok(Sport::AlpineSkiing),
-            14 => Ok(Sport::Snowboarding),
-            15 => Ok(Sport::Rowing),
-            16 => Ok(Sport::Mountaineering),
-            17 => Ok(Sport::Hiking),
-            18 => Ok(Sport::Multisport),
-            19 => Ok(Sport::Paddling),
-            20 => Ok(Sport::Flying),
-            21 => Ok(Sport::EBiking),
-            22 => Ok(Sport::Motorcycling),
-            23 => Ok(Sport::Boating),
-            24 => Ok(Sport::Driving),
-            25 => Ok(Sport::Golf),
-            26 => Ok(Sport::HangGliding),
-            27 => Ok(Sport::HorsebackRiding),
-            28 => Ok(Sport::Hunting),
-            29 => Ok(Sport::Fishing),
-            30 => Ok(Sport::InlineSkating),
-            31 => Ok(Sport::RockClimbing),
-            32 => Ok(Sport::Sailing),
-            33 => Ok(Sport::IceSkating),
-            34 => Ok(Sport::SkyDiving),
-            35 => Ok(Sport::Snowshoeing),
-            36 => Ok(Sport::Snowmobiling),
-            37 => Ok(Sport::StandUpPaddleboarding),
-            38 => Ok(Sport::Surfing),
-            39 => Ok(Sport::Wakeboarding),
-            40 => Ok(Sport::WaterSkiing),
-            41 => Ok(Sport::Kayaking),
-            42 => Ok(Sport::Rafting),
-            43 => Ok(Sport::Windsurfing),
-            44 => Ok(Sport::Kitesurfing),
-            45 => Ok(Sport::Tactical),
-            46 => Ok(Sport::Jumpmaster),
-            47 => Ok(Sport::Boxing),
-            48 => Ok(Sport::FloorClimbing),
-            254 => Ok(Sport::All),
-            _ => Ok(Sport::Unknown),
-        }
-    }
-}
+        Ok(SPORT.get(base_value.0).unwrap_or(Sport::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Sport::Unknown => "Unknown",
+            _ => SPORT.name(self.clone() as u8).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for Sport {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        SPORT.parse(name).ok_or(())
+    }
+}
+static SPORT: profile::table::EnumTable<u8, Sport> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, Sport::Generic),
+            (1, Sport::Running),
+            (2, Sport::Cycling),
+            (3, Sport::Transition),
+            (4, Sport::FitnessEquipment),
+            (5, Sport::Swimming),
+            (6, Sport::Basketball),
+            (7, Sport::Soccer),
+            (8, Sport::Tennis),
+            (9, Sport::AmericanFootball),
+            (10, Sport::Training),
+            (11, Sport::Walking),
+            (12, Sport::CrossCountrySkiing),
+            (13, Sport::AlpineSkiing),
+            (14, Sport::Snowboarding),
+            (15, Sport::Rowing),
+            (16, Sport::Mountaineering),
+            (17, Sport::Hiking),
+            (18, Sport::Multisport),
+            (19, Sport::Paddling),
+            (20, Sport::Flying),
+            (21, Sport::EBiking),
+            (22, Sport::Motorcycling),
+            (23, Sport::Boating),
+            (24, Sport::Driving),
+            (25, Sport::Golf),
+            (26, Sport::HangGliding),
+            (27, Sport::HorsebackRiding),
+            (28, Sport::Hunting),
+            (29, Sport::Fishing),
+            (30, Sport::InlineSkating),
+            (31, Sport::RockClimbing),
+            (32, Sport::Sailing),
+            (33, Sport::IceSkating),
+            (34, Sport::SkyDiving),
+            (35, Sport::Snowshoeing),
+            (36, Sport::Snowmobiling),
+            (37, Sport::StandUpPaddleboarding),
+            (38, Sport::Surfing),
+            (39, Sport::Wakeboarding),
+            (40, Sport::WaterSkiing),
+            (41, Sport::Kayaking),
+            (42, Sport::Rafting),
+            (43, Sport::Windsurfing),
+            (44, Sport::Kitesurfing),
+            (45, Sport::Tactical),
+            (46, Sport::Jumpmaster),
+            (47, Sport::Boxing),
+            (48, Sport::FloorClimbing),
+            (254, Sport::All),
+        ],
+        names:   &[
+            "Generic",
+            "Running",
+            "Cycling",
+            "Transition",
+            "FitnessEquipment",
+            "Swimming",
+            "Basketball",
+            "Soccer",
+            "Tennis",
+            "AmericanFootball",
+            "Training",
+            "Walking",
+            "CrossCountrySkiing",
+            "AlpineSkiing",
+            "Snowboarding",
+            "Rowing",
+            "Mountaineering",
+            "Hiking",
+            "Multisport",
+            "Paddling",
+            "Flying",
+            "EBiking",
+            "Motorcycling",
+            "Boating",
+            "Driving",
+            "Golf",
+            "HangGliding",
+            "HorsebackRiding",
+            "Hunting",
+            "Fishing",
+            "InlineSkating",
+            "RockClimbing",
+            "Sailing",
+            "IceSkating",
+            "SkyDiving",
+            "Snowshoeing",
+            "Snowmobiling",
+            "StandUpPaddleboarding",
+            "Surfing",
+            "Wakeboarding",
+            "WaterSkiing",
+            "Kayaking",
+            "Rafting",
+            "Windsurfing",
+            "Kitesurfing",
+            "Tactical",
+            "Jumpmaster",
+            "Boxing",
+            "FloorClimbing",
+            "All",
+        ],
+        by_name: &[
+            49, 13, 9, 6, 23, 47, 12, 2, 24, 21, 29, 4, 48, 20, 0, 25, 26, 17,
+            27, 28, 33, 30, 46, 41, 44, 22, 16, 18, 19, 42, 31, 15, 1, 32, 34,
+            14, 36, 35, 7, 37, 38, 5, 45, 8, 10, 3, 39, 11, 40, 43,
+        ],
+    };
+encode_enum!(Sport, u8, 0xFF);
 #[doc = "Bit field corresponding to sport enum type (1 << sport)."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits0 {
     Generic = 1,
     Running = 2,
@@ -1194,8 +1595,9 @@ impl SportBits0 {
         }
     }
 }
+encode_enum!(SportBits0, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-8))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits1 {
     Tennis = 1,
     AmericanFootball = 2,
@@ -1223,8 +1625,9 @@ impl SportBits1 {
         }
     }
 }
+encode_enum!(SportBits1, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-16))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits2 {
     Mountaineering = 1,
     Hiking = 2,
@@ -1252,8 +1655,9 @@ impl SportBits2 {
         }
     }
 }
+encode_enum!(SportBits2, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-24))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits3 {
     Driving = 1,
     Golf = 2,
@@ -1281,8 +1685,9 @@ impl SportBits3 {
         }
     }
 }
+encode_enum!(SportBits3, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-32))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits4 {
     Sailing = 1,
     IceSkating = 2,
@@ -1310,8 +1715,9 @@ impl SportBits4 {
         }
     }
 }
+encode_enum!(SportBits4, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-40))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits5 {
     WaterSkiing = 1,
     Kayaking = 2,
@@ -1339,8 +1745,9 @@ impl SportBits5 {
         }
     }
 }
+encode_enum!(SportBits5, u8, 0);
 #[doc = "Bit field corresponding to sport enum type (1 << (sport-48))."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum SportBits6 {
     FloorClimbing = 1,
     Unknown,
@@ -1354,7 +1761,8 @@ impl SportBits6 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SportBits6, u8, 0);
+#[derive(Debug, Clone)]
 pub enum SubSport {
     Generic = 0,
     #[doc = "Run/Fitness Equipment"]
@@ -1477,73 +1885,162 @@ pub enum SubSport {
 impl SubSport {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(SubSport::Generic),
-            1 => Ok(SubSport::Treadmill),
-            2 => Ok(SubSport::Street),
-            3 => Ok(SubSport::Trail),
-            4 => Ok(SubSport::Track),
-            5 => Ok(SubSport::Spin),
-            6 => Ok(SubSport::IndoorCycling),
-            7 => Ok(SubSport::Road),
-            8 => Ok(SubSport::Mountain),
-            9 => Ok(SubSport::Downhill),
-            10 => Ok(SubSport::Recumbent),
-            11 => Ok(SubSport::Cyclocross),
-            12 => Ok(SubSport::HandCycling),
-            13 => Ok(SubSport::TrackCycling),
-            14 => Ok(SubSport::IndoorRowing),
-            15 => Ok(SubSport::Elliptical),
-            16 => Ok(SubSport::StairClimbing),
-            17 => Ok(SubSport::LapSwimming),
-            18 => Ok(SubSport::OpenWater),
-            19 => Ok(SubSport::FlexibilityTraining),
-            20 => Ok(SubSport::StrengthTraining),
-            21 => Ok(SubSport::WarmUp),
-            22 => Ok(SubSport::Match),
-            23 => Ok(SubSport::Exercise),
-            24 => Ok(SubSport::Challenge),
-            25 => Ok(SubSport::IndoorSkiing),
-            26 => Ok(SubSport::CardioTraining),
-            27 => Ok(SubSport::IndoorWalking),
-            28 => Ok(SubSport::EBikeFitness),
-            29 => Ok(SubSport::Bmx),
-            30 => Ok(SubSport::CasualWalking),
-            31 => Ok(SubSport::SpeedWalking),
-            32 => Ok(SubSport::BikeToRunTransition),
-            33 => Ok(SubSport::RunToBikeTransition),
-            34 => Ok(SubSport::SwimToBikeTransition),
-            35 => Ok(SubSport::Atv),
-            36 => Ok(SubSport::Motocross),
-            37 => Ok(SubSport::Backcountry),
-            38 => Ok(SubSport::Resort),
-            39 => Ok(SubSport::RcDrone),
-            40 => Ok(SubSport::Wingsuit),
-            41 => Ok(SubSport::Whitewater),
-            42 => Ok(SubSport::SkateSkiing),
-            43 => Ok(SubSport::Yoga),
-            44 => Ok(SubSport::Pilates),
-            45 => Ok(SubSport::IndoorRunning),
-            46 => Ok(SubSport::GravelCycling),
-            47 => Ok(SubSport::EBikeMountain),
-            48 => Ok(SubSport::Commuting),
-            49 => Ok(SubSport::MixedSurface),
-            50 => Ok(SubSport::Navigate),
-            51 => Ok(SubSport::TrackMe),
-            52 => Ok(SubSport::Map),
-            53 => Ok(SubSport::SingleGasDiving),
-            54 => Ok(SubSport::MultiGasDiving),
-            55 => Ok(SubSport::GaugeDiving),
-            56 => Ok(SubSport::ApneaDiving),
-            57 => Ok(SubSport::ApneaHunting),
-            58 => Ok(SubSport::VirtualActivity),
-            59 => Ok(SubSport::Obstacle),
-            254 => Ok(SubSport::All),
-            _ => Ok(SubSport::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(SUB_SPORT.get(base_value.0).unwrap_or(SubSport::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SubSport::Unknown => "Unknown",
+            _ => SUB_SPORT.name(self.clone() as u8).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for SubSport {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        SUB_SPORT.parse(name).ok_or(())
+    }
+}
+static SUB_SPORT: profile::table::EnumTable<u8, SubSport> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, SubSport::Generic),
+            (1, SubSport::Treadmill),
+            (2, SubSport::Street),
+            (3, SubSport::Trail),
+            (4, SubSport::Track),
+            (5, SubSport::Spin),
+            (6, SubSport::IndoorCycling),
+            (7, SubSport::Road),
+            (8, SubSport::Mountain),
+            (9, SubSport::Downhill),
+            (10, SubSport::Recumbent),
+            (11, SubSport::Cyclocross),
+            (12, SubSport::HandCycling),
+            (13, SubSport::TrackCycling),
+            (14, SubSport::IndoorRowing),
+            (15, SubSport::Elliptical),
+            (16, SubSport::StairClimbing),
+            (17, SubSport::LapSwimming),
+            (18, SubSport::OpenWater),
+            (19, SubSport::FlexibilityTraining),
+            (20, SubSport::StrengthTraining),
+            (21, SubSport::WarmUp),
+            (22, SubSport::Match),
+            (23, SubSport::Exercise),
+            (24, SubSport::Challenge),
+            (25, SubSport::IndoorSkiing),
+            (26, SubSport::CardioTraining),
+            (27, SubSport::IndoorWalking),
+            (28, SubSport::EBikeFitness),
+            (29, SubSport::Bmx),
+            (30, SubSport::CasualWalking),
+            (31, SubSport::SpeedWalking),
+            (32, SubSport::BikeToRunTransition),
+            (33, SubSport::RunToBikeTransition),
+            (34, SubSport::SwimToBikeTransition),
+            (35, SubSport::Atv),
+            (36, SubSport::Motocross),
+            (37, SubSport::Backcountry),
+            (38, SubSport::Resort),
+            (39, SubSport::RcDrone),
+            (40, SubSport::Wingsuit),
+            (41, SubSport::Whitewater),
+            (42, SubSport::SkateSkiing),
+            (43, SubSport::Yoga),
+            (44, SubSport::Pilates),
+            (45, SubSport::IndoorRunning),
+            (46, SubSport::GravelCycling),
+            (47, SubSport::EBikeMountain),
+            (48, SubSport::Commuting),
+            (49, SubSport::MixedSurface),
+            (50, SubSport::Navigate),
+            (51, SubSport::TrackMe),
+            (52, SubSport::Map),
+            (53, SubSport::SingleGasDiving),
+            (54, SubSport::MultiGasDiving),
+            (55, SubSport::GaugeDiving),
+            (56, SubSport::ApneaDiving),
+            (57, SubSport::ApneaHunting),
+            (58, SubSport::VirtualActivity),
+            (59, SubSport::Obstacle),
+            (254, SubSport::All),
+        ],
+        names:   &[
+            "Generic",
+            "Treadmill",
+            "Street",
+            "Trail",
+            "Track",
+            "Spin",
+            "IndoorCycling",
+            "Road",
+            "Mountain",
+            "Downhill",
+            "Recumbent",
+            "Cyclocross",
+            "HandCycling",
+            "TrackCycling",
+            "IndoorRowing",
+            "Elliptical",
+            "StairClimbing",
+            "LapSwimming",
+            "OpenWater",
+            "FlexibilityTraining",
+            "StrengthTraining",
+            "WarmUp",
+            "Match",
+            "Exercise",
+            "Challenge",
+            "IndoorSkiing",
+            "CardioTraining",
+            "IndoorWalking",
+            "EBikeFitness",
+            "Bmx",
+            "CasualWalking",
+            "SpeedWalking",
+            "BikeToRunTransition",
+            "RunToBikeTransition",
+            "SwimToBikeTransition",
+            "Atv",
+            "Motocross",
+            "Backcountry",
+            "Resort",
+            "RcDrone",
+            "Wingsuit",
+            "Whitewater",
+            "SkateSkiing",
+            "Yoga",
+            "Pilates",
+            "IndoorRunning",
+            "GravelCycling",
+            "EBikeMountain",
+            "Commuting",
+            "MixedSurface",
+            "Navigate",
+            "TrackMe",
+            "Map",
+            "SingleGasDiving",
+            "MultiGasDiving",
+            "GaugeDiving",
+            "ApneaDiving",
+            "ApneaHunting",
+            "VirtualActivity",
+            "Obstacle",
+            "All",
+        ],
+        by_name: &[
+            60, 56, 57, 35, 37, 32, 29, 26, 30, 24, 48, 11, 9, 28, 47, 15, 23,
+            19, 55, 0, 46, 12, 6, 14, 45, 25, 27, 17, 52, 22, 49, 36, 8, 54,
+            50, 59, 18, 44, 39, 10, 38, 7, 33, 53, 42, 31, 5, 16, 2, 20, 34, 4,
+            13, 51, 3, 1, 58, 21, 41, 40, 43,
+        ],
+    };
+encode_enum!(SubSport, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SportEvent {
     Uncategorized = 0,
     Geocaching = 1,
@@ -1573,7 +2070,8 @@ impl SportEvent {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SportEvent, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Activity {
     Manual = 0,
     AutoMultiSport = 1,
@@ -1589,7 +2087,8 @@ impl Activity {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Activity, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Intensity {
     Active = 0,
     Rest = 1,
@@ -1609,7 +2108,8 @@ impl Intensity {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Intensity, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SessionTrigger {
     ActivityEnd = 0,
     #[doc = "User changed sport."]
@@ -1633,7 +2133,8 @@ impl SessionTrigger {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SessionTrigger, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AutolapTrigger {
     Time = 0,
     Distance = 1,
@@ -1659,7 +2160,8 @@ impl AutolapTrigger {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AutolapTrigger, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum LapTrigger {
     Manual = 0,
     Time = 1,
@@ -1689,7 +2191,8 @@ impl LapTrigger {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LapTrigger, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum TimeMode {
     Hour12 = 0,
     #[doc = "Does not use a leading zero and has a colon"]
@@ -1715,7 +2218,8 @@ impl TimeMode {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(TimeMode, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum BacklightMode {
     Off = 0,
     Manual = 1,
@@ -1741,7 +2245,8 @@ impl BacklightMode {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BacklightMode, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DateMode {
     DayMonth = 0,
     MonthDay = 1,
@@ -1757,8 +2262,9 @@ impl DateMode {
         }
     }
 }
+encode_enum!(DateMode, u8, 0xFF);
 #[doc = "Timeout in seconds."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum BacklightTimeout {
     #[doc = "Backlight stays on forever."]
     Infinite = 0,
@@ -1773,7 +2279,8 @@ impl BacklightTimeout {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BacklightTimeout, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Event {
     #[doc = "Group 0.  Start / stop_all"]
     Timer = 0,
@@ -1855,6 +2362,7 @@ pub enum Event {
     ElevLowAlert = 46,
     #[doc = "marker"]
     CommTimeout = 47,
+    RadarThreatAlert = 75,
     Unknown,
 }
 impl Event {
@@ -1897,11 +2405,13 @@ impl Event {
             45 => Ok(Event::ElevHighAlert),
             46 => Ok(Event::ElevLowAlert),
             47 => Ok(Event::CommTimeout),
+            75 => Ok(Event::RadarThreatAlert),
             _ => Ok(Event::Unknown),
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Event, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum EventType {
     Start = 0,
     Stop = 1,
@@ -1933,8 +2443,9 @@ impl EventType {
         }
     }
 }
+encode_enum!(EventType, u8, 0xFF);
 #[doc = "timer event data"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum TimerTrigger {
     Manual = 0,
     Auto = 1,
@@ -1952,8 +2463,9 @@ impl TimerTrigger {
         }
     }
 }
+encode_enum!(TimerTrigger, u8, 0xFF);
 #[doc = "fitness equipment event data"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum FitnessEquipmentState {
     Ready = 0,
     InUse = 1,
@@ -1972,7 +2484,8 @@ impl FitnessEquipmentState {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(FitnessEquipmentState, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Tone {
     Off = 0,
     Tone = 1,
@@ -1992,7 +2505,8 @@ impl Tone {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Tone, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Autoscroll {
     None = 0,
     Slow = 1,
@@ -2012,7 +2526,8 @@ impl Autoscroll {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Autoscroll, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ActivityClass {
     #[doc = "0 to 100"]
     Level = 127,
@@ -2031,7 +2546,8 @@ impl ActivityClass {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ActivityClass, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum HrZoneCalc {
     Custom = 0,
     PercentMaxHr = 1,
@@ -2049,7 +2565,8 @@ impl HrZoneCalc {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HrZoneCalc, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum PwrZoneCalc {
     Custom = 0,
     PercentFtp = 1,
@@ -2065,7 +2582,8 @@ impl PwrZoneCalc {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(PwrZoneCalc, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WktStepDuration {
     Time = 0,
     Distance = 1,
@@ -2137,7 +2655,8 @@ impl WktStepDuration {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WktStepDuration, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WktStepTarget {
     Speed = 0,
     HeartRate = 1,
@@ -2177,7 +2696,8 @@ impl WktStepTarget {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WktStepTarget, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Goal {
     Time = 0,
     Distance = 1,
@@ -2203,7 +2723,8 @@ impl Goal {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Goal, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum GoalRecurrence {
     Off = 0,
     Daily = 1,
@@ -2227,7 +2748,8 @@ impl GoalRecurrence {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(GoalRecurrence, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum GoalSource {
     #[doc = "Device generated"]
     Auto = 0,
@@ -2248,7 +2770,8 @@ impl GoalSource {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(GoalSource, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Schedule {
     Workout = 0,
     Course = 1,
@@ -2264,7 +2787,8 @@ impl Schedule {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Schedule, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum CoursePoint {
     Generic = 0,
     Summit = 1,
@@ -2328,7 +2852,8 @@ impl CoursePoint {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CoursePoint, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Manufacturer {
     Garmin = 1,
     #[doc = "Do not use.  Used by FR405 for ANTFS man id."]
@@ -2504,172 +3029,367 @@ pub enum Manufacturer {
 impl Manufacturer {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            1 => Ok(Manufacturer::Garmin),
-            2 => Ok(Manufacturer::GarminFr405Antfs),
-            3 => Ok(Manufacturer::Zephyr),
-            4 => Ok(Manufacturer::Dayton),
-            5 => Ok(Manufacturer::Idt),
-            6 => Ok(Manufacturer::Srm),
-            7 => Ok(Manufacturer::Quarq),
-            8 => Ok(Manufacturer::Ibike),
-            9 => Ok(Manufacturer::Saris),
-            10 => Ok(Manufacturer::SparkHk),
-            11 => Ok(Manufacturer::Tanita),
-            12 => Ok(Manufacturer::Echowell),
-            13 => Ok(Manufacturer::DynastreamOem),
-            14 => Ok(Manufacturer::Nautilus),
-            15 => Ok(Manufacturer::Dynastream),
-            16 => Ok(Manufacturer::Timex),
-            17 => Ok(Manufacturer::Metrigear),
-            18 => Ok(Manufacturer::Xelic),
-            19 => Ok(Manufacturer::Beurer),
-            20 => Ok(Manufacturer::Cardiosport),
-            21 => Ok(Manufacturer::AAndD),
-            22 => Ok(Manufacturer::Hmm),
-            23 => Ok(Manufacturer::Suunto),
-            24 => Ok(Manufacturer::ThitaElektronik),
-            25 => Ok(Manufacturer::Gpulse),
-            26 => Ok(Manufacturer::CleanMobile),
-            27 => Ok(Manufacturer::PedalBrain),
-            28 => Ok(Manufacturer::Peaksware),
-            29 => Ok(Manufacturer::Saxonar),
-            30 => Ok(Manufacturer::LemondFitness),
-            31 => Ok(Manufacturer::Dexcom),
-            32 => Ok(Manufacturer::WahooFitness),
-            33 => Ok(Manufacturer::OctaneFitness),
-            34 => Ok(Manufacturer::Archinoetics),
-            35 => Ok(Manufacturer::TheHurtBox),
-            36 => Ok(Manufacturer::CitizenSystems),
-            37 => Ok(Manufacturer::Magellan),
-            38 => Ok(Manufacturer::Osynce),
-            39 => Ok(Manufacturer::Holux),
-            40 => Ok(Manufacturer::Concept2),
-            42 => Ok(Manufacturer::OneGiantLeap),
-            43 => Ok(Manufacturer::AceSensor),
-            44 => Ok(Manufacturer::BrimBrothers),
-            45 => Ok(Manufacturer::Xplova),
-            46 => Ok(Manufacturer::PerceptionDigital),
-            47 => Ok(Manufacturer::Bf1Systems),
-            48 => Ok(Manufacturer::Pioneer),
-            49 => Ok(Manufacturer::Spantec),
-            50 => Ok(Manufacturer::Metalogics),
-            51 => Ok(Manufacturer::Fouriiiis),
-            52 => Ok(Manufacturer::SeikoEpson),
-            53 => Ok(Manufacturer::SeikoEpsonOem),
-            54 => Ok(Manufacturer::IforPowell),
-            55 => Ok(Manufacturer::MaxwellGuider),
-            56 => Ok(Manufacturer::StarTrac),
-            57 => Ok(Manufacturer::Breakaway),
-            58 => Ok(Manufacturer::AlatechTechnologyLtd),
-            59 => Ok(Manufacturer::MioTechnologyEurope),
-            60 => Ok(Manufacturer::Rotor),
-            61 => Ok(Manufacturer::Geonaute),
-            62 => Ok(Manufacturer::IdBike),
-            63 => Ok(Manufacturer::Specialized),
-            64 => Ok(Manufacturer::Wtek),
-            65 => Ok(Manufacturer::PhysicalEnterprises),
-            66 => Ok(Manufacturer::NorthPoleEngineering),
-            67 => Ok(Manufacturer::Bkool),
-            68 => Ok(Manufacturer::Cateye),
-            69 => Ok(Manufacturer::StagesCycling),
-            70 => Ok(Manufacturer::Sigmasport),
-            71 => Ok(Manufacturer::Tomtom),
-            72 => Ok(Manufacturer::Peripedal),
-            73 => Ok(Manufacturer::Wattbike),
-            76 => Ok(Manufacturer::Moxy),
-            77 => Ok(Manufacturer::Ciclosport),
-            78 => Ok(Manufacturer::Powerbahn),
-            79 => Ok(Manufacturer::AcornProjectsAps),
-            80 => Ok(Manufacturer::Lifebeam),
-            81 => Ok(Manufacturer::Bontrager),
-            82 => Ok(Manufacturer::Wellgo),
-            83 => Ok(Manufacturer::Scosche),
-            84 => Ok(Manufacturer::Magura),
-            85 => Ok(Manufacturer::Woodway),
-            86 => Ok(Manufacturer::Elite),
-            87 => Ok(Manufacturer::NielsenKellerman),
-            88 => Ok(Manufacturer::DkCity),
-            89 => Ok(Manufacturer::Tacx),
-            90 => Ok(Manufacturer::DirectionTechnology),
-            91 => Ok(Manufacturer::Magtonic),
-            92 => Ok(Manufacturer::Onepartcarbon),
-            93 => Ok(Manufacturer::InsideRideTechnologies),
-            94 => Ok(Manufacturer::SoundOfMotion),
-            95 => Ok(Manufacturer::Stryd),
-            96 => Ok(Manufacturer::Icg),
-            97 => Ok(Manufacturer::MiPulse),
-            98 => Ok(Manufacturer::BsxAthletics),
-            99 => Ok(Manufacturer::Look),
-            100 => Ok(Manufacturer::CampagnoloSrl),
-            101 => Ok(Manufacturer::BodyBikeSmart),
-            102 => Ok(Manufacturer::Praxisworks),
-            103 => Ok(Manufacturer::LimitsTechnology),
-            104 => Ok(Manufacturer::TopactionTechnology),
-            105 => Ok(Manufacturer::Cosinuss),
-            106 => Ok(Manufacturer::Fitcare),
-            107 => Ok(Manufacturer::Magene),
-            108 => Ok(Manufacturer::GiantManufacturingCo),
-            109 => Ok(Manufacturer::Tigrasport),
-            110 => Ok(Manufacturer::Salutron),
-            111 => Ok(Manufacturer::Technogym),
-            112 => Ok(Manufacturer::BrytonSensors),
-            113 => Ok(Manufacturer::LatitudeLimited),
-            114 => Ok(Manufacturer::SoaringTechnology),
-            115 => Ok(Manufacturer::Igpsport),
-            116 => Ok(Manufacturer::Thinkrider),
-            117 => Ok(Manufacturer::GopherSport),
-            118 => Ok(Manufacturer::Waterrower),
-            119 => Ok(Manufacturer::Orangetheory),
-            120 => Ok(Manufacturer::Inpeak),
-            121 => Ok(Manufacturer::Kinetic),
-            122 => Ok(Manufacturer::JohnsonHealthTech),
-            123 => Ok(Manufacturer::PolarElectro),
-            124 => Ok(Manufacturer::Seesense),
-            255 => Ok(Manufacturer::Development),
-            257 => Ok(Manufacturer::Healthandlife),
-            258 => Ok(Manufacturer::Lezyne),
-            259 => Ok(Manufacturer::ScribeLabs),
-            260 => Ok(Manufacturer::Zwift),
-            261 => Ok(Manufacturer::Watteam),
-            262 => Ok(Manufacturer::Recon),
-            263 => Ok(Manufacturer::FaveroElectronics),
-            264 => Ok(Manufacturer::Dynovelo),
-            265 => Ok(Manufacturer::Strava),
-            266 => Ok(Manufacturer::Precor),
-            267 => Ok(Manufacturer::Bryton),
-            268 => Ok(Manufacturer::Sram),
-            269 => Ok(Manufacturer::Navman),
-            270 => Ok(Manufacturer::Cobi),
-            271 => Ok(Manufacturer::Spivi),
-            272 => Ok(Manufacturer::MioMagellan),
-            273 => Ok(Manufacturer::Evesports),
-            274 => Ok(Manufacturer::SensitivusGauge),
-            275 => Ok(Manufacturer::Podoon),
-            276 => Ok(Manufacturer::LifeTimeFitness),
-            277 => Ok(Manufacturer::FalcoEMotors),
-            278 => Ok(Manufacturer::Minoura),
-            279 => Ok(Manufacturer::Cycliq),
-            280 => Ok(Manufacturer::Luxottica),
-            281 => Ok(Manufacturer::TrainerRoad),
-            282 => Ok(Manufacturer::TheSufferfest),
-            283 => Ok(Manufacturer::Fullspeedahead),
-            284 => Ok(Manufacturer::Virtualtraining),
-            285 => Ok(Manufacturer::Feedbacksports),
-            286 => Ok(Manufacturer::Omata),
-            287 => Ok(Manufacturer::Vdo),
-            288 => Ok(Manufacturer::Magneticdays),
-            289 => Ok(Manufacturer::Hammerhead),
-            290 => Ok(Manufacturer::KineticByKurt),
-            291 => Ok(Manufacturer::Shapelog),
-            292 => Ok(Manufacturer::Dabuziduo),
-            293 => Ok(Manufacturer::Jetblack),
-            5759 => Ok(Manufacturer::Actigraphcorp),
-            _ => Ok(Manufacturer::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(MANUFACTURER.get(base_value.0).unwrap_or(Manufacturer::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Manufacturer::Unknown => "Unknown",
+            _ => MANUFACTURER.name(self.clone() as u16).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for Manufacturer {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        MANUFACTURER.parse(name).ok_or(())
+    }
+}
+static MANUFACTURER: profile::table::EnumTable<u16, Manufacturer> =
+    profile::table::EnumTable {
+        values:  &[
+            (1, Manufacturer::Garmin),
+            (2, Manufacturer::GarminFr405Antfs),
+            (3, Manufacturer::Zephyr),
+            (4, Manufacturer::Dayton),
+            (5, Manufacturer::Idt),
+            (6, Manufacturer::Srm),
+            (7, Manufacturer::Quarq),
+            (8, Manufacturer::Ibike),
+            (9, Manufacturer::Saris),
+            (10, Manufacturer::SparkHk),
+            (11, Manufacturer::Tanita),
+            (12, Manufacturer::Echowell),
+            (13, Manufacturer::DynastreamOem),
+            (14, Manufacturer::Nautilus),
+            (15, Manufacturer::Dynastream),
+            (16, Manufacturer::Timex),
+            (17, Manufacturer::Metrigear),
+            (18, Manufacturer::Xelic),
+            (19, Manufacturer::Beurer),
+            (20, Manufacturer::Cardiosport),
+            (21, Manufacturer::AAndD),
+            (22, Manufacturer::Hmm),
+            (23, Manufacturer::Suunto),
+            (24, Manufacturer::ThitaElektronik),
+            (25, Manufacturer::Gpulse),
+            (26, Manufacturer::CleanMobile),
+            (27, Manufacturer::PedalBrain),
+            (28, Manufacturer::Peaksware),
+            (29, Manufacturer::Saxonar),
+            (30, Manufacturer::LemondFitness),
+            (31, Manufacturer::Dexcom),
+            (32, Manufacturer::WahooFitness),
+            (33, Manufacturer::OctaneFitness),
+            (34, Manufacturer::Archinoetics),
+            (35, Manufacturer::TheHurtBox),
+            (36, Manufacturer::CitizenSystems),
+            (37, Manufacturer::Magellan),
+            (38, Manufacturer::Osynce),
+            (39, Manufacturer::Holux),
+            (40, Manufacturer::Concept2),
+            (42, Manufacturer::OneGiantLeap),
+            (43, Manufacturer::AceSensor),
+            (44, Manufacturer::BrimBrothers),
+            (45, Manufacturer::Xplova),
+            (46, Manufacturer::PerceptionDigital),
+            (47, Manufacturer::Bf1Systems),
+            (48, Manufacturer::Pioneer),
+            (49, Manufacturer::Spantec),
+            (50, Manufacturer::Metalogics),
+            (51, Manufacturer::Fouriiiis),
+            (52, Manufacturer::SeikoEpson),
+            (53, Manufacturer::SeikoEpsonOem),
+            (54, Manufacturer::IforPowell),
+            (55, Manufacturer::MaxwellGuider),
+            (56, Manufacturer::StarTrac),
+            (57, Manufacturer::Breakaway),
+            (58, Manufacturer::AlatechTechnologyLtd),
+            (59, Manufacturer::MioTechnologyEurope),
+            (60, Manufacturer::Rotor),
+            (61, Manufacturer::Geonaute),
+            (62, Manufacturer::IdBike),
+            (63, Manufacturer::Specialized),
+            (64, Manufacturer::Wtek),
+            (65, Manufacturer::PhysicalEnterprises),
+            (66, Manufacturer::NorthPoleEngineering),
+            (67, Manufacturer::Bkool),
+            (68, Manufacturer::Cateye),
+            (69, Manufacturer::StagesCycling),
+            (70, Manufacturer::Sigmasport),
+            (71, Manufacturer::Tomtom),
+            (72, Manufacturer::Peripedal),
+            (73, Manufacturer::Wattbike),
+            (76, Manufacturer::Moxy),
+            (77, Manufacturer::Ciclosport),
+            (78, Manufacturer::Powerbahn),
+            (79, Manufacturer::AcornProjectsAps),
+            (80, Manufacturer::Lifebeam),
+            (81, Manufacturer::Bontrager),
+            (82, Manufacturer::Wellgo),
+            (83, Manufacturer::Scosche),
+            (84, Manufacturer::Magura),
+            (85, Manufacturer::Woodway),
+            (86, Manufacturer::Elite),
+            (87, Manufacturer::NielsenKellerman),
+            (88, Manufacturer::DkCity),
+            (89, Manufacturer::Tacx),
+            (90, Manufacturer::DirectionTechnology),
+            (91, Manufacturer::Magtonic),
+            (92, Manufacturer::Onepartcarbon),
+            (93, Manufacturer::InsideRideTechnologies),
+            (94, Manufacturer::SoundOfMotion),
+            (95, Manufacturer::Stryd),
+            (96, Manufacturer::Icg),
+            (97, Manufacturer::MiPulse),
+            (98, Manufacturer::BsxAthletics),
+            (99, Manufacturer::Look),
+            (100, Manufacturer::CampagnoloSrl),
+            (101, Manufacturer::BodyBikeSmart),
+            (102, Manufacturer::Praxisworks),
+            (103, Manufacturer::LimitsTechnology),
+            (104, Manufacturer::TopactionTechnology),
+            (105, Manufacturer::Cosinuss),
+            (106, Manufacturer::Fitcare),
+            (107, Manufacturer::Magene),
+            (108, Manufacturer::GiantManufacturingCo),
+            (109, Manufacturer::Tigrasport),
+            (110, Manufacturer::Salutron),
+            (111, Manufacturer::Technogym),
+            (112, Manufacturer::BrytonSensors),
+            (113, Manufacturer::LatitudeLimited),
+            (114, Manufacturer::SoaringTechnology),
+            (115, Manufacturer::Igpsport),
+            (116, Manufacturer::Thinkrider),
+            (117, Manufacturer::GopherSport),
+            (118, Manufacturer::Waterrower),
+            (119, Manufacturer::Orangetheory),
+            (120, Manufacturer::Inpeak),
+            (121, Manufacturer::Kinetic),
+            (122, Manufacturer::JohnsonHealthTech),
+            (123, Manufacturer::PolarElectro),
+            (124, Manufacturer::Seesense),
+            (255, Manufacturer::Development),
+            (257, Manufacturer::Healthandlife),
+            (258, Manufacturer::Lezyne),
+            (259, Manufacturer::ScribeLabs),
+            (260, Manufacturer::Zwift),
+            (261, Manufacturer::Watteam),
+            (262, Manufacturer::Recon),
+            (263, Manufacturer::FaveroElectronics),
+            (264, Manufacturer::Dynovelo),
+            (265, Manufacturer::Strava),
+            (266, Manufacturer::Precor),
+            (267, Manufacturer::Bryton),
+            (268, Manufacturer::Sram),
+            (269, Manufacturer::Navman),
+            (270, Manufacturer::Cobi),
+            (271, Manufacturer::Spivi),
+            (272, Manufacturer::MioMagellan),
+            (273, Manufacturer::Evesports),
+            (274, Manufacturer::SensitivusGauge),
+            (275, Manufacturer::Podoon),
+            (276, Manufacturer::LifeTimeFitness),
+            (277, Manufacturer::FalcoEMotors),
+            (278, Manufacturer::Minoura),
+            (279, Manufacturer::Cycliq),
+            (280, Manufacturer::Luxottica),
+            (281, Manufacturer::TrainerRoad),
+            (282, Manufacturer::TheSufferfest),
+            (283, Manufacturer::Fullspeedahead),
+            (284, Manufacturer::Virtualtraining),
+            (285, Manufacturer::Feedbacksports),
+            (286, Manufacturer::Omata),
+            (287, Manufacturer::Vdo),
+            (288, Manufacturer::Magneticdays),
+            (289, Manufacturer::Hammerhead),
+            (290, Manufacturer::KineticByKurt),
+            (291, Manufacturer::Shapelog),
+            (292, Manufacturer::Dabuziduo),
+            (293, Manufacturer::Jetblack),
+            (5759, Manufacturer::Actigraphcorp),
+        ],
+        names:   &[
+            "Garmin",
+            "GarminFr405Antfs",
+            "Zephyr",
+            "Dayton",
+            "Idt",
+            "Srm",
+            "Quarq",
+            "Ibike",
+            "Saris",
+            "SparkHk",
+            "Tanita",
+            "Echowell",
+            "DynastreamOem",
+            "Nautilus",
+            "Dynastream",
+            "Timex",
+            "Metrigear",
+            "Xelic",
+            "Beurer",
+            "Cardiosport",
+            "AAndD",
+            "Hmm",
+            "Suunto",
+            "ThitaElektronik",
+            "Gpulse",
+            "CleanMobile",
+            "PedalBrain",
+            "Peaksware",
+            "Saxonar",
+            "LemondFitness",
+            "Dexcom",
+            "WahooFitness",
+            "OctaneFitness",
+            "Archinoetics",
+            "TheHurtBox",
+            "CitizenSystems",
+            "Magellan",
+            "Osynce",
+            "Holux",
+            "Concept2",
+            "OneGiantLeap",
+            "AceSensor",
+            "BrimBrothers",
+            "Xplova",
+            "PerceptionDigital",
+            "Bf1Systems",
+            "Pioneer",
+            "Spantec",
+            "Metalogics",
+            "Fouriiiis",
+            "SeikoEpson",
+            "SeikoEpsonOem",
+            "IforPowell",
+            "MaxwellGuider",
+            "StarTrac",
+            "Breakaway",
+            "AlatechTechnologyLtd",
+            "MioTechnologyEurope",
+            "Rotor",
+            "Geonaute",
+            "IdBike",
+            "Specialized",
+            "Wtek",
+            "PhysicalEnterprises",
+            "NorthPoleEngineering",
+            "Bkool",
+            "Cateye",
+            "StagesCycling",
+            "Sigmasport",
+            "Tomtom",
+            "Peripedal",
+            "Wattbike",
+            "Moxy",
+            "Ciclosport",
+            "Powerbahn",
+            "AcornProjectsAps",
+            "Lifebeam",
+            "Bontrager",
+            "Wellgo",
+            "Scosche",
+            "Magura",
+            "Woodway",
+            "Elite",
+            "NielsenKellerman",
+            "DkCity",
+            "Tacx",
+            "DirectionTechnology",
+            "Magtonic",
+            "Onepartcarbon",
+            "InsideRideTechnologies",
+            "SoundOfMotion",
+            "Stryd",
+            "Icg",
+            "MiPulse",
+            "BsxAthletics",
+            "Look",
+            "CampagnoloSrl",
+            "BodyBikeSmart",
+            "Praxisworks",
+            "LimitsTechnology",
+            "TopactionTechnology",
+            "Cosinuss",
+            "Fitcare",
+            "Magene",
+            "GiantManufacturingCo",
+            "Tigrasport",
+            "Salutron",
+            "Technogym",
+            "BrytonSensors",
+            "LatitudeLimited",
+            "SoaringTechnology",
+            "Igpsport",
+            "Thinkrider",
+            "GopherSport",
+            "Waterrower",
+            "Orangetheory",
+            "Inpeak",
+            "Kinetic",
+            "JohnsonHealthTech",
+            "PolarElectro",
+            "Seesense",
+            "Development",
+            "Healthandlife",
+            "Lezyne",
+            "ScribeLabs",
+            "Zwift",
+            "Watteam",
+            "Recon",
+            "FaveroElectronics",
+            "Dynovelo",
+            "Strava",
+            "Precor",
+            "Bryton",
+            "Sram",
+            "Navman",
+            "Cobi",
+            "Spivi",
+            "MioMagellan",
+            "Evesports",
+            "SensitivusGauge",
+            "Podoon",
+            "LifeTimeFitness",
+            "FalcoEMotors",
+            "Minoura",
+            "Cycliq",
+            "Luxottica",
+            "TrainerRoad",
+            "TheSufferfest",
+            "Fullspeedahead",
+            "Virtualtraining",
+            "Feedbacksports",
+            "Omata",
+            "Vdo",
+            "Magneticdays",
+            "Hammerhead",
+            "KineticByKurt",
+            "Shapelog",
+            "Dabuziduo",
+            "Jetblack",
+            "Actigraphcorp",
+        ],
+        by_name: &[
+            20, 41, 75, 159, 56, 33, 18, 45, 65, 97, 77, 55, 42, 132, 108, 94,
+            96, 19, 66, 73, 35, 25, 135, 39, 101, 144, 157, 3, 121, 30, 86, 84,
+            14, 12, 129, 11, 82, 138, 142, 128, 150, 102, 49, 148, 0, 1, 59,
+            104, 113, 24, 154, 122, 21, 38, 7, 92, 60, 4, 52, 111, 116, 89,
+            158, 118, 117, 155, 109, 29, 123, 141, 76, 99, 95, 145, 36, 103,
+            153, 87, 80, 53, 48, 16, 93, 143, 137, 57, 72, 13, 134, 83, 64, 32,
+            151, 40, 88, 115, 37, 27, 26, 44, 70, 63, 46, 140, 119, 74, 98,
+            131, 6, 127, 58, 106, 8, 28, 79, 124, 120, 50, 51, 139, 156, 68,
+            110, 90, 47, 9, 61, 136, 133, 5, 67, 54, 130, 91, 22, 85, 10, 107,
+            34, 147, 112, 23, 105, 15, 69, 100, 146, 152, 149, 31, 114, 71,
+            126, 78, 81, 62, 17, 43, 2, 125,
+        ],
+    };
+encode_enum!(Manufacturer, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum GarminProduct {
     Hrm1 = 1,
     #[doc = "AXH01 HRM chipset"]
@@ -2847,171 +3567,380 @@ pub enum GarminProduct {
     Unknown,
 }
 impl GarminProduct {
+    pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
+        let base_value = profile::base::Uint16::decode::<T>(buffer)?;
+        Ok(GARMIN_PRODUCT.get(base_value.0).unwrap_or(GarminProduct::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            GarminProduct::Unknown => "Unknown",
+            _ => GARMIN_PRODUCT.name(self.clone() as u16).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for GarminProduct {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        GARMIN_PRODUCT.parse(name).ok_or(())
+    }
+}
+static GARMIN_PRODUCT: profile::table::EnumTable<u16, GarminProduct> =
+    profile::table::EnumTable {
+        values:  &[
+            (1, GarminProduct::Hrm1),
+            (2, GarminProduct::Axh01),
+            (3, GarminProduct::Axb01),
+            (4, GarminProduct::Axb02),
+            (5, GarminProduct::Hrm2Ss),
+            (6, GarminProduct::DsiAlf02),
+            (7, GarminProduct::Hrm3Ss),
+            (8, GarminProduct::HrmRunSingleByteProductId),
+            (9, GarminProduct::Bsm),
+            (10, GarminProduct::Bcm),
+            (11, GarminProduct::Axs01),
+            (12, GarminProduct::HrmTriSingleByteProductId),
+            (14, GarminProduct::Fr225SingleByteProductId),
+            (473, GarminProduct::Fr301China),
+            (474, GarminProduct::Fr301Japan),
+            (475, GarminProduct::Fr301Korea),
+            (494, GarminProduct::Fr301Taiwan),
+            (717, GarminProduct::Fr405),
+            (782, GarminProduct::Fr50),
+            (987, GarminProduct::Fr405Japan),
+            (988, GarminProduct::Fr60),
+            (1011, GarminProduct::DsiAlf01),
+            (1018, GarminProduct::Fr310Xt),
+            (1036, GarminProduct::Edge500),
+            (1124, GarminProduct::Fr110),
+            (1169, GarminProduct::Edge800),
+            (1199, GarminProduct::Edge500Taiwan),
+            (1213, GarminProduct::Edge500Japan),
+            (1253, GarminProduct::Chirp),
+            (1274, GarminProduct::Fr110Japan),
+            (1325, GarminProduct::Edge200),
+            (1328, GarminProduct::Fr910Xt),
+            (1333, GarminProduct::Edge800Taiwan),
+            (1334, GarminProduct::Edge800Japan),
+            (1341, GarminProduct::Alf04),
+            (1345, GarminProduct::Fr610),
+            (1360, GarminProduct::Fr210Japan),
+            (1380, GarminProduct::VectorSs),
+            (1381, GarminProduct::VectorCp),
+            (1386, GarminProduct::Edge800China),
+            (1387, GarminProduct::Edge500China),
+            (1410, GarminProduct::Fr610Japan),
+            (1422, GarminProduct::Edge500Korea),
+            (1436, GarminProduct::Fr70),
+            (1446, GarminProduct::Fr310Xt4T),
+            (1461, GarminProduct::Amx),
+            (1482, GarminProduct::Fr10),
+            (1497, GarminProduct::Edge800Korea),
+            (1499, GarminProduct::Swim),
+            (1537, GarminProduct::Fr910XtChina),
+            (1551, GarminProduct::Fenix),
+            (1555, GarminProduct::Edge200Taiwan),
+            (1561, GarminProduct::Edge510),
+            (1567, GarminProduct::Edge810),
+            (1570, GarminProduct::Tempe),
+            (1600, GarminProduct::Fr910XtJapan),
+            (1623, GarminProduct::Fr620),
+            (1632, GarminProduct::Fr220),
+            (1664, GarminProduct::Fr910XtKorea),
+            (1688, GarminProduct::Fr10Japan),
+            (1721, GarminProduct::Edge810Japan),
+            (1735, GarminProduct::VirbElite),
+            (1736, GarminProduct::EdgeTouring),
+            (1742, GarminProduct::Edge510Japan),
+            (1743, GarminProduct::HrmTri),
+            (1752, GarminProduct::HrmRun),
+            (1765, GarminProduct::Fr920Xt),
+            (1821, GarminProduct::Edge510Asia),
+            (1822, GarminProduct::Edge810China),
+            (1823, GarminProduct::Edge810Taiwan),
+            (1836, GarminProduct::Edge1000),
+            (1837, GarminProduct::VivoFit),
+            (1853, GarminProduct::VirbRemote),
+            (1885, GarminProduct::VivoKi),
+            (1903, GarminProduct::Fr15),
+            (1907, GarminProduct::VivoActive),
+            (1918, GarminProduct::Edge510Korea),
+            (1928, GarminProduct::Fr620Japan),
+            (1929, GarminProduct::Fr620China),
+            (1930, GarminProduct::Fr220Japan),
+            (1931, GarminProduct::Fr220China),
+            (1936, GarminProduct::ApproachS6),
+            (1956, GarminProduct::VivoSmart),
+            (1967, GarminProduct::Fenix2),
+            (1988, GarminProduct::Epix),
+            (2050, GarminProduct::Fenix3),
+            (2052, GarminProduct::Edge1000Taiwan),
+            (2053, GarminProduct::Edge1000Japan),
+            (2061, GarminProduct::Fr15Japan),
+            (2067, GarminProduct::Edge520),
+            (2070, GarminProduct::Edge1000China),
+            (2072, GarminProduct::Fr620Russia),
+            (2073, GarminProduct::Fr220Russia),
+            (2079, GarminProduct::VectorS),
+            (2100, GarminProduct::Edge1000Korea),
+            (2130, GarminProduct::Fr920XtTaiwan),
+            (2131, GarminProduct::Fr920XtChina),
+            (2132, GarminProduct::Fr920XtJapan),
+            (2134, GarminProduct::Virbx),
+            (2135, GarminProduct::VivoSmartApac),
+            (2140, GarminProduct::EtrexTouch),
+            (2147, GarminProduct::Edge25),
+            (2148, GarminProduct::Fr25),
+            (2150, GarminProduct::VivoFit2),
+            (2153, GarminProduct::Fr225),
+            (2156, GarminProduct::Fr630),
+            (2157, GarminProduct::Fr230),
+            (2160, GarminProduct::VivoActiveApac),
+            (2161, GarminProduct::Vector2),
+            (2162, GarminProduct::Vector2S),
+            (2172, GarminProduct::Virbxe),
+            (2173, GarminProduct::Fr620Taiwan),
+            (2174, GarminProduct::Fr220Taiwan),
+            (2175, GarminProduct::Truswing),
+            (2188, GarminProduct::Fenix3China),
+            (2189, GarminProduct::Fenix3Twn),
+            (2192, GarminProduct::VariaHeadlight),
+            (2193, GarminProduct::VariaTaillightOld),
+            (2204, GarminProduct::EdgeExplore1000),
+            (2219, GarminProduct::Fr225Asia),
+            (2225, GarminProduct::VariaRadarTaillight),
+            (2226, GarminProduct::VariaRadarDisplay),
+            (2238, GarminProduct::Edge20),
+            (2262, GarminProduct::D2Bravo),
+            (2266, GarminProduct::ApproachS20),
+            (2276, GarminProduct::VariaRemote),
+            (2327, GarminProduct::Hrm4Run),
+            (2337, GarminProduct::VivoActiveHr),
+            (2347, GarminProduct::VivoSmartGpsHr),
+            (2348, GarminProduct::VivoSmartHr),
+            (2368, GarminProduct::VivoMove),
+            (2398, GarminProduct::VariaVision),
+            (2406, GarminProduct::VivoFit3),
+            (2413, GarminProduct::Fenix3Hr),
+            (2417, GarminProduct::VirbUltra30),
+            (2429, GarminProduct::IndexSmartScale),
+            (2431, GarminProduct::Fr235),
+            (2432, GarminProduct::Fenix3Chronos),
+            (2441, GarminProduct::Oregon7Xx),
+            (2444, GarminProduct::Rino7Xx),
+            (2496, GarminProduct::Nautix),
+            (2530, GarminProduct::Edge820),
+            (2531, GarminProduct::EdgeExplore820),
+            (2544, GarminProduct::Fenix5S),
+            (2547, GarminProduct::D2BravoTitanium),
+            (2567, GarminProduct::VariaUt800),
+            (2593, GarminProduct::RunningDynamicsPod),
+            (2604, GarminProduct::Fenix5X),
+            (2606, GarminProduct::VivoFitJr),
+            (2691, GarminProduct::Fr935),
+            (2697, GarminProduct::Fenix5),
+            (10007, GarminProduct::Sdm4),
+            (10014, GarminProduct::EdgeRemote),
+            (20119, GarminProduct::TrainingCenter),
+            (65531, GarminProduct::ConnectiqSimulator),
+            (65532, GarminProduct::AndroidAntplusPlugin),
+            (65534, GarminProduct::Connect),
+        ],
+        names:   &[
+            "Hrm1",
+            "Axh01",
+            "Axb01",
+            "Axb02",
+            "Hrm2Ss",
+            "DsiAlf02",
+            "Hrm3Ss",
+            "HrmRunSingleByteProductId",
+            "Bsm",
+            "Bcm",
+            "Axs01",
+            "HrmTriSingleByteProductId",
+            "Fr225SingleByteProductId",
+            "Fr301China",
+            "Fr301Japan",
+            "Fr301Korea",
+            "Fr301Taiwan",
+            "Fr405",
+            "Fr50",
+            "Fr405Japan",
+            "Fr60",
+            "DsiAlf01",
+            "Fr310Xt",
+            "Edge500",
+            "Fr110",
+            "Edge800",
+            "Edge500Taiwan",
+            "Edge500Japan",
+            "Chirp",
+            "Fr110Japan",
+            "Edge200",
+            "Fr910Xt",
+            "Edge800Taiwan",
+            "Edge800Japan",
+            "Alf04",
+            "Fr610",
+            "Fr210Japan",
+            "VectorSs",
+            "VectorCp",
+            "Edge800China",
+            "Edge500China",
+            "Fr610Japan",
+            "Edge500Korea",
+            "Fr70",
+            "Fr310Xt4T",
+            "Amx",
+            "Fr10",
+            "Edge800Korea",
+            "Swim",
+            "Fr910XtChina",
+            "Fenix",
+            "Edge200Taiwan",
+            "Edge510",
+            "Edge810",
+            "Tempe",
+            "Fr910XtJapan",
+            "Fr620",
+            "Fr220",
+            "Fr910XtKorea",
+            "Fr10Japan",
+            "Edge810Japan",
+            "VirbElite",
+            "EdgeTouring",
+            "Edge510Japan",
+            "HrmTri",
+            "HrmRun",
+            "Fr920Xt",
+            "Edge510Asia",
+            "Edge810China",
+            "Edge810Taiwan",
+            "Edge1000",
+            "VivoFit",
+            "VirbRemote",
+            "VivoKi",
+            "Fr15",
+            "VivoActive",
+            "Edge510Korea",
+            "Fr620Japan",
+            "Fr620China",
+            "Fr220Japan",
+            "Fr220China",
+            "ApproachS6",
+            "VivoSmart",
+            "Fenix2",
+            "Epix",
+            "Fenix3",
+            "Edge1000Taiwan",
+            "Edge1000Japan",
+            "Fr15Japan",
+            "Edge520",
+            "Edge1000China",
+            "Fr620Russia",
+            "Fr220Russia",
+            "VectorS",
+            "Edge1000Korea",
+            "Fr920XtTaiwan",
+            "Fr920XtChina",
+            "Fr920XtJapan",
+            "Virbx",
+            "VivoSmartApac",
+            "EtrexTouch",
+            "Edge25",
+            "Fr25",
+            "VivoFit2",
+            "Fr225",
+            "Fr630",
+            "Fr230",
+            "VivoActiveApac",
+            "Vector2",
+            "Vector2S",
+            "Virbxe",
+            "Fr620Taiwan",
+            "Fr220Taiwan",
+            "Truswing",
+            "Fenix3China",
+            "Fenix3Twn",
+            "VariaHeadlight",
+            "VariaTaillightOld",
+            "EdgeExplore1000",
+            "Fr225Asia",
+            "VariaRadarTaillight",
+            "VariaRadarDisplay",
+            "Edge20",
+            "D2Bravo",
+            "ApproachS20",
+            "VariaRemote",
+            "Hrm4Run",
+            "VivoActiveHr",
+            "VivoSmartGpsHr",
+            "VivoSmartHr",
+            "VivoMove",
+            "VariaVision",
+            "VivoFit3",
+            "Fenix3Hr",
+            "VirbUltra30",
+            "IndexSmartScale",
+            "Fr235",
+            "Fenix3Chronos",
+            "Oregon7Xx",
+            "Rino7Xx",
+            "Nautix",
+            "Edge820",
+            "EdgeExplore820",
+            "Fenix5S",
+            "D2BravoTitanium",
+            "VariaUt800",
+            "RunningDynamicsPod",
+            "Fenix5X",
+            "VivoFitJr",
+            "Fr935",
+            "Fenix5",
+            "Sdm4",
+            "EdgeRemote",
+            "TrainingCenter",
+            "ConnectiqSimulator",
+            "AndroidAntplusPlugin",
+            "Connect",
+        ],
+        by_name: &[
+            34, 45, 155, 124, 81, 2, 3, 1, 10, 9, 8, 28, 156, 154, 123, 144,
+            21, 5, 70, 90, 87, 94, 86, 122, 30, 51, 101, 23, 40, 27, 42, 26,
+            52, 67, 63, 76, 89, 25, 39, 33, 47, 32, 53, 68, 60, 69, 141, 118,
+            142, 152, 62, 84, 100, 50, 83, 85, 114, 137, 133, 115, 150, 143,
+            147, 46, 59, 24, 29, 74, 88, 36, 57, 80, 79, 92, 112, 104, 119, 12,
+            106, 136, 102, 13, 14, 15, 16, 22, 44, 17, 19, 18, 20, 35, 41, 56,
+            78, 77, 91, 111, 105, 43, 31, 49, 55, 58, 66, 96, 97, 95, 149, 0,
+            4, 6, 126, 65, 7, 64, 11, 135, 140, 138, 139, 146, 151, 48, 54,
+            153, 113, 116, 121, 120, 125, 117, 145, 131, 108, 109, 38, 93, 37,
+            61, 72, 134, 98, 110, 75, 107, 127, 71, 103, 132, 148, 73, 130, 82,
+            99, 128, 129,
+        ],
+    };
+encode_enum!(GarminProduct, u16, 0xFFFF);
+#[derive(Debug, Clone)]
+pub enum FaveroProduct {
+    AssiomaUno = 10,
+    AssiomaDuo = 12,
+    Unknown,
+}
+impl FaveroProduct {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
         match base_value.0 {
-            1 => Ok(GarminProduct::Hrm1),
-            2 => Ok(GarminProduct::Axh01),
-            3 => Ok(GarminProduct::Axb01),
-            4 => Ok(GarminProduct::Axb02),
-            5 => Ok(GarminProduct::Hrm2Ss),
-            6 => Ok(GarminProduct::DsiAlf02),
-            7 => Ok(GarminProduct::Hrm3Ss),
-            8 => Ok(GarminProduct::HrmRunSingleByteProductId),
-            9 => Ok(GarminProduct::Bsm),
-            10 => Ok(GarminProduct::Bcm),
-            11 => Ok(GarminProduct::Axs01),
-            12 => Ok(GarminProduct::HrmTriSingleByteProductId),
-            14 => Ok(GarminProduct::Fr225SingleByteProductId),
-            473 => Ok(GarminProduct::Fr301China),
-            474 => Ok(GarminProduct::Fr301Japan),
-            475 => Ok(GarminProduct::Fr301Korea),
-            494 => Ok(GarminProduct::Fr301Taiwan),
-            717 => Ok(GarminProduct::Fr405),
-            782 => Ok(GarminProduct::Fr50),
-            987 => Ok(GarminProduct::Fr405Japan),
-            988 => Ok(GarminProduct::Fr60),
-            1011 => Ok(GarminProduct::DsiAlf01),
-            1018 => Ok(GarminProduct::Fr310Xt),
-            1036 => Ok(GarminProduct::Edge500),
-            1124 => Ok(GarminProduct::Fr110),
-            1169 => Ok(GarminProduct::Edge800),
-            1199 => Ok(GarminProduct::Edge500Taiwan),
-            1213 => Ok(GarminProduct::Edge500Japan),
-            1253 => Ok(GarminProduct::Chirp),
-            1274 => Ok(GarminProduct::Fr110Japan),
-            1325 => Ok(GarminProduct::Edge200),
-            1328 => Ok(GarminProduct::Fr910Xt),
-            1333 => Ok(GarminProduct::Edge800Taiwan),
-            1334 => Ok(GarminProduct::Edge800Japan),
-            1341 => Ok(GarminProduct::Alf04),
-            1345 => Ok(GarminProduct::Fr610),
-            1360 => Ok(GarminProduct::Fr210Japan),
-            1380 => Ok(GarminProduct::VectorSs),
-            1381 => Ok(GarminProduct::VectorCp),
-            1386 => Ok(GarminProduct::Edge800China),
-            1387 => Ok(GarminProduct::Edge500China),
-            1410 => Ok(GarminProduct::Fr610Japan),
-            1422 => Ok(GarminProduct::Edge500Korea),
-            1436 => Ok(GarminProduct::Fr70),
-            1446 => Ok(GarminProduct::Fr310Xt4T),
-            1461 => Ok(GarminProduct::Amx),
-            1482 => Ok(GarminProduct::Fr10),
-            1497 => Ok(GarminProduct::Edge800Korea),
-            1499 => Ok(GarminProduct::Swim),
-            1537 => Ok(GarminProduct::Fr910XtChina),
-            1551 => Ok(GarminProduct::Fenix),
-            1555 => Ok(GarminProduct::Edge200Taiwan),
-            1561 => Ok(GarminProduct::Edge510),
-            1567 => Ok(GarminProduct::Edge810),
-            1570 => Ok(GarminProduct::Tempe),
-            1600 => Ok(GarminProduct::Fr910XtJapan),
-            1623 => Ok(GarminProduct::Fr620),
-            1632 => Ok(GarminProduct::Fr220),
-            1664 => Ok(GarminProduct::Fr910XtKorea),
-            1688 => Ok(GarminProduct::Fr10Japan),
-            1721 => Ok(GarminProduct::Edge810Japan),
-            1735 => Ok(GarminProduct::VirbElite),
-            1736 => Ok(GarminProduct::EdgeTouring),
-            1742 => Ok(GarminProduct::Edge510Japan),
-            1743 => Ok(GarminProduct::HrmTri),
-            1752 => Ok(GarminProduct::HrmRun),
-            1765 => Ok(GarminProduct::Fr920Xt),
-            1821 => Ok(GarminProduct::Edge510Asia),
-            1822 => Ok(GarminProduct::Edge810China),
-            1823 => Ok(GarminProduct::Edge810Taiwan),
-            1836 => Ok(GarminProduct::Edge1000),
-            1837 => Ok(GarminProduct::VivoFit),
-            1853 => Ok(GarminProduct::VirbRemote),
-            1885 => Ok(GarminProduct::VivoKi),
-            1903 => Ok(GarminProduct::Fr15),
-            1907 => Ok(GarminProduct::VivoActive),
-            1918 => Ok(GarminProduct::Edge510Korea),
-            1928 => Ok(GarminProduct::Fr620Japan),
-            1929 => Ok(GarminProduct::Fr620China),
-            1930 => Ok(GarminProduct::Fr220Japan),
-            1931 => Ok(GarminProduct::Fr220China),
-            1936 => Ok(GarminProduct::ApproachS6),
-            1956 => Ok(GarminProduct::VivoSmart),
-            1967 => Ok(GarminProduct::Fenix2),
-            1988 => Ok(GarminProduct::Epix),
-            2050 => Ok(GarminProduct::Fenix3),
-            2052 => Ok(GarminProduct::Edge1000Taiwan),
-            2053 => Ok(GarminProduct::Edge1000Japan),
-            2061 => Ok(GarminProduct::Fr15Japan),
-            2067 => Ok(GarminProduct::Edge520),
-            2070 => Ok(GarminProduct::Edge1000China),
-            2072 => Ok(GarminProduct::Fr620Russia),
-            2073 => Ok(GarminProduct::Fr220Russia),
-            2079 => Ok(GarminProduct::VectorS),
-            2100 => Ok(GarminProduct::Edge1000Korea),
-            2130 => Ok(GarminProduct::Fr920XtTaiwan),
-            2131 => Ok(GarminProduct::Fr920XtChina),
-            2132 => Ok(GarminProduct::Fr920XtJapan),
-            2134 => Ok(GarminProduct::Virbx),
-            2135 => Ok(GarminProduct::VivoSmartApac),
-            2140 => Ok(GarminProduct::EtrexTouch),
-            2147 => Ok(GarminProduct::Edge25),
-            2148 => Ok(GarminProduct::Fr25),
-            2150 => Ok(GarminProduct::VivoFit2),
-            2153 => Ok(GarminProduct::Fr225),
-            2156 => Ok(GarminProduct::Fr630),
-            2157 => Ok(GarminProduct::Fr230),
-            2160 => Ok(GarminProduct::VivoActiveApac),
-            2161 => Ok(GarminProduct::Vector2),
-            2162 => Ok(GarminProduct::Vector2S),
-            2172 => Ok(GarminProduct::Virbxe),
-            2173 => Ok(GarminProduct::Fr620Taiwan),
-            2174 => Ok(GarminProduct::Fr220Taiwan),
-            2175 => Ok(GarminProduct::Truswing),
-            2188 => Ok(GarminProduct::Fenix3China),
-            2189 => Ok(GarminProduct::Fenix3Twn),
-            2192 => Ok(GarminProduct::VariaHeadlight),
-            2193 => Ok(GarminProduct::VariaTaillightOld),
-            2204 => Ok(GarminProduct::EdgeExplore1000),
-            2219 => Ok(GarminProduct::Fr225Asia),
-            2225 => Ok(GarminProduct::VariaRadarTaillight),
-            2226 => Ok(GarminProduct::VariaRadarDisplay),
-            2238 => Ok(GarminProduct::Edge20),
-            2262 => Ok(GarminProduct::D2Bravo),
-            2266 => Ok(GarminProduct::ApproachS20),
-            2276 => Ok(GarminProduct::VariaRemote),
-            2327 => Ok(GarminProduct::Hrm4Run),
-            2337 => Ok(GarminProduct::VivoActiveHr),
-            2347 => Ok(GarminProduct::VivoSmartGpsHr),
-            2348 => Ok(GarminProduct::VivoSmartHr),
-            2368 => Ok(GarminProduct::VivoMove),
-            2398 => Ok(GarminProduct::VariaVision),
-            2406 => Ok(GarminProduct::VivoFit3),
-            2413 => Ok(GarminProduct::Fenix3Hr),
-            2417 => Ok(GarminProduct::VirbUltra30),
-            2429 => Ok(GarminProduct::IndexSmartScale),
-            2431 => Ok(GarminProduct::Fr235),
-            2432 => Ok(GarminProduct::Fenix3Chronos),
-            2441 => Ok(GarminProduct::Oregon7Xx),
-            2444 => Ok(GarminProduct::Rino7Xx),
-            2496 => Ok(GarminProduct::Nautix),
-            2530 => Ok(GarminProduct::Edge820),
-            2531 => Ok(GarminProduct::EdgeExplore820),
-            2544 => Ok(GarminProduct::Fenix5S),
-            2547 => Ok(GarminProduct::D2BravoTitanium),
-            2567 => Ok(GarminProduct::VariaUt800),
-            2593 => Ok(GarminProduct::RunningDynamicsPod),
-            2604 => Ok(GarminProduct::Fenix5X),
-            2606 => Ok(GarminProduct::VivoFitJr),
-            2691 => Ok(GarminProduct::Fr935),
-            2697 => Ok(GarminProduct::Fenix5),
-            10007 => Ok(GarminProduct::Sdm4),
-            10014 => Ok(GarminProduct::EdgeRemote),
-            20119 => Ok(GarminProduct::TrainingCenter),
-            65531 => Ok(GarminProduct::ConnectiqSimulator),
-            65532 => Ok(GarminProduct::AndroidAntplusPlugin),
-            65534 => Ok(GarminProduct::Connect),
-            _ => Ok(GarminProduct::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+            10 => Ok(FaveroProduct::AssiomaUno),
+            12 => Ok(FaveroProduct::AssiomaDuo),
+            _ => Ok(FaveroProduct::Unknown),
+        }
+    }
+}
+encode_enum!(FaveroProduct, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum AntplusDeviceType {
     Antfs = 1,
     BikePower = 11,
@@ -3069,7 +3998,8 @@ impl AntplusDeviceType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AntplusDeviceType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AntNetwork {
     Public = 0,
     Antplus = 1,
@@ -3089,7 +4019,8 @@ impl AntNetwork {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AntNetwork, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WorkoutCapabilities {
     Interval = 1,
     Custom = 2,
@@ -3138,7 +4069,8 @@ impl WorkoutCapabilities {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WorkoutCapabilities, u32, 0);
+#[derive(Debug, Clone)]
 pub enum BatteryStatus {
     New = 1,
     Good = 2,
@@ -3163,7 +4095,8 @@ impl BatteryStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BatteryStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum HrType {
     Normal = 0,
     Irregular = 1,
@@ -3179,7 +4112,8 @@ impl HrType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HrType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum CourseCapabilities {
     Processed = 1,
     Valid = 2,
@@ -3213,7 +4147,8 @@ impl CourseCapabilities {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CourseCapabilities, u32, 0);
+#[derive(Debug, Clone)]
 pub enum Weight {
     Calculating = 65534,
     Unknown,
@@ -3227,8 +4162,9 @@ impl Weight {
         }
     }
 }
+encode_enum!(Weight, u16, 0xFFFF);
 #[doc = "0 - 100 indicates% of max hr; >100 indicates bpm (255 max) plus 100"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum WorkoutHr {
     BpmOffset = 100,
     Unknown,
@@ -3242,9 +4178,10 @@ impl WorkoutHr {
         }
     }
 }
+encode_enum!(WorkoutHr, u32, 0xFFFFFFFF);
 #[doc = "0 - 1000 indicates % of functional threshold power; >1000 indicates \
          watts plus 1000."]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub enum WorkoutPower {
     WattsOffset = 1000,
     Unknown,
@@ -3258,7 +4195,8 @@ impl WorkoutPower {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WorkoutPower, u32, 0xFFFFFFFF);
+#[derive(Debug, Clone)]
 pub enum BpStatus {
     NoError = 0,
     ErrorIncompleteData = 1,
@@ -3280,7 +4218,8 @@ impl BpStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BpStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum UserLocalId {
     LocalMin = 0,
     LocalMax = 15,
@@ -3304,7 +4243,8 @@ impl UserLocalId {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(UserLocalId, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum SwimStroke {
     Freestyle = 0,
     Backstroke = 1,
@@ -3333,7 +4273,8 @@ impl SwimStroke {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SwimStroke, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ActivityType {
     Generic = 0,
     Running = 1,
@@ -3365,7 +4306,8 @@ impl ActivityType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ActivityType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ActivitySubtype {
     Generic = 0,
     #[doc = "Run"]
@@ -3435,7 +4377,8 @@ impl ActivitySubtype {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ActivitySubtype, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ActivityLevel {
     Low = 0,
     Medium = 1,
@@ -3453,7 +4396,8 @@ impl ActivityLevel {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ActivityLevel, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum Side {
     Right = 0,
     Left = 1,
@@ -3469,7 +4413,8 @@ impl Side {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(Side, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum LeftRightBalance {
     #[doc = "% contribution"]
     Mask = 127,
@@ -3487,7 +4432,8 @@ impl LeftRightBalance {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LeftRightBalance, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum LeftRightBalance100 {
     #[doc = "% contribution scaled by 100"]
     Mask = 16383,
@@ -3505,7 +4451,8 @@ impl LeftRightBalance100 {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LeftRightBalance100, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum LengthType {
     #[doc = "Rest period. Length with no strokes"]
     Idle = 0,
@@ -3523,7 +4470,8 @@ impl LengthType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LengthType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DayOfWeek {
     Sunday = 0,
     Monday = 1,
@@ -3549,7 +4497,8 @@ impl DayOfWeek {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DayOfWeek, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ConnectivityCapabilities {
     Bluetooth = 1,
     BluetoothLe = 2,
@@ -3636,7 +4585,8 @@ impl ConnectivityCapabilities {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ConnectivityCapabilities, u32, 0);
+#[derive(Debug, Clone)]
 pub enum WeatherReport {
     Current = 0,
     HourlyForecast = 1,
@@ -3654,7 +4604,8 @@ impl WeatherReport {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WeatherReport, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WeatherStatus {
     Clear = 0,
     PartlyCloudy = 1,
@@ -3708,7 +4659,8 @@ impl WeatherStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WeatherStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WeatherSeverity {
     Warning = 1,
     Watch = 2,
@@ -3729,7 +4681,8 @@ impl WeatherSeverity {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WeatherSeverity, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WeatherSevereType {
     Unspecified = 0,
     Tornado = 1,
@@ -3821,113 +4774,237 @@ pub enum WeatherSevereType {
 impl WeatherSevereType {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(WeatherSevereType::Unspecified),
-            1 => Ok(WeatherSevereType::Tornado),
-            2 => Ok(WeatherSevereType::Tsunami),
-            3 => Ok(WeatherSevereType::Hurricane),
-            4 => Ok(WeatherSevereType::ExtremeWind),
-            5 => Ok(WeatherSevereType::Typhoon),
-            6 => Ok(WeatherSevereType::InlandHurricane),
-            7 => Ok(WeatherSevereType::HurricaneForceWind),
-            8 => Ok(WeatherSevereType::Waterspout),
-            9 => Ok(WeatherSevereType::SevereThunderstorm),
-            10 => Ok(WeatherSevereType::WreckhouseWinds),
-            11 => Ok(WeatherSevereType::LesSuetesWind),
-            12 => Ok(WeatherSevereType::Avalanche),
-            13 => Ok(WeatherSevereType::FlashFlood),
-            14 => Ok(WeatherSevereType::TropicalStorm),
-            15 => Ok(WeatherSevereType::InlandTropicalStorm),
-            16 => Ok(WeatherSevereType::Blizzard),
-            17 => Ok(WeatherSevereType::IceStorm),
-            18 => Ok(WeatherSevereType::FreezingRain),
-            19 => Ok(WeatherSevereType::DebrisFlow),
-            20 => Ok(WeatherSevereType::FlashFreeze),
-            21 => Ok(WeatherSevereType::DustStorm),
-            22 => Ok(WeatherSevereType::HighWind),
-            23 => Ok(WeatherSevereType::WinterStorm),
-            24 => Ok(WeatherSevereType::HeavyFreezingSpray),
-            25 => Ok(WeatherSevereType::ExtremeCold),
-            26 => Ok(WeatherSevereType::WindChill),
-            27 => Ok(WeatherSevereType::ColdWave),
-            28 => Ok(WeatherSevereType::HeavySnowAlert),
-            29 => Ok(WeatherSevereType::LakeEffectBlowingSnow),
-            30 => Ok(WeatherSevereType::SnowSquall),
-            31 => Ok(WeatherSevereType::LakeEffectSnow),
-            32 => Ok(WeatherSevereType::WinterWeather),
-            33 => Ok(WeatherSevereType::Sleet),
-            34 => Ok(WeatherSevereType::Snowfall),
-            35 => Ok(WeatherSevereType::SnowAndBlowingSnow),
-            36 => Ok(WeatherSevereType::BlowingSnow),
-            37 => Ok(WeatherSevereType::SnowAlert),
-            38 => Ok(WeatherSevereType::ArcticOutflow),
-            39 => Ok(WeatherSevereType::FreezingDrizzle),
-            40 => Ok(WeatherSevereType::Storm),
-            41 => Ok(WeatherSevereType::StormSurge),
-            42 => Ok(WeatherSevereType::Rainfall),
-            43 => Ok(WeatherSevereType::ArealFlood),
-            44 => Ok(WeatherSevereType::CoastalFlood),
-            45 => Ok(WeatherSevereType::LakeshoreFlood),
-            46 => Ok(WeatherSevereType::ExcessiveHeat),
-            47 => Ok(WeatherSevereType::Heat),
-            48 => Ok(WeatherSevereType::Weather),
-            49 => Ok(WeatherSevereType::HighHeatAndHumidity),
-            50 => Ok(WeatherSevereType::HumidexAndHealth),
-            51 => Ok(WeatherSevereType::Humidex),
-            52 => Ok(WeatherSevereType::Gale),
-            53 => Ok(WeatherSevereType::FreezingSpray),
-            54 => Ok(WeatherSevereType::SpecialMarine),
-            55 => Ok(WeatherSevereType::Squall),
-            56 => Ok(WeatherSevereType::StrongWind),
-            57 => Ok(WeatherSevereType::LakeWind),
-            58 => Ok(WeatherSevereType::MarineWeather),
-            59 => Ok(WeatherSevereType::Wind),
-            60 => Ok(WeatherSevereType::SmallCraftHazardousSeas),
-            61 => Ok(WeatherSevereType::HazardousSeas),
-            62 => Ok(WeatherSevereType::SmallCraft),
-            63 => Ok(WeatherSevereType::SmallCraftWinds),
-            64 => Ok(WeatherSevereType::SmallCraftRoughBar),
-            65 => Ok(WeatherSevereType::HighWaterLevel),
-            66 => Ok(WeatherSevereType::Ashfall),
-            67 => Ok(WeatherSevereType::FreezingFog),
-            68 => Ok(WeatherSevereType::DenseFog),
-            69 => Ok(WeatherSevereType::DenseSmoke),
-            70 => Ok(WeatherSevereType::BlowingDust),
-            71 => Ok(WeatherSevereType::HardFreeze),
-            72 => Ok(WeatherSevereType::Freeze),
-            73 => Ok(WeatherSevereType::Frost),
-            74 => Ok(WeatherSevereType::FireWeather),
-            75 => Ok(WeatherSevereType::Flood),
-            76 => Ok(WeatherSevereType::RipTide),
-            77 => Ok(WeatherSevereType::HighSurf),
-            78 => Ok(WeatherSevereType::Smog),
-            79 => Ok(WeatherSevereType::AirQuality),
-            80 => Ok(WeatherSevereType::BriskWind),
-            81 => Ok(WeatherSevereType::AirStagnation),
-            82 => Ok(WeatherSevereType::LowWater),
-            83 => Ok(WeatherSevereType::Hydrological),
-            84 => Ok(WeatherSevereType::SpecialWeather),
-            _ => Ok(WeatherSevereType::Unknown),
+        Ok(WEATHER_SEVERE_TYPE
+            .get(base_value.0)
+            .unwrap_or(WeatherSevereType::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            WeatherSevereType::Unknown => "Unknown",
+            _ => {
+                WEATHER_SEVERE_TYPE
+                    .name(self.clone() as u8)
+                    .unwrap_or("Unknown")
+            },
         }
     }
 }
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for WeatherSevereType {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        WEATHER_SEVERE_TYPE.parse(name).ok_or(())
+    }
+}
+static WEATHER_SEVERE_TYPE: profile::table::EnumTable<u8, WeatherSevereType> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, WeatherSevereType::Unspecified),
+            (1, WeatherSevereType::Tornado),
+            (2, WeatherSevereType::Tsunami),
+            (3, WeatherSevereType::Hurricane),
+            (4, WeatherSevereType::ExtremeWind),
+            (5, WeatherSevereType::Typhoon),
+            (6, WeatherSevereType::InlandHurricane),
+            (7, WeatherSevereType::HurricaneForceWind),
+            (8, WeatherSevereType::Waterspout),
+            (9, WeatherSevereType::SevereThunderstorm),
+            (10, WeatherSevereType::WreckhouseWinds),
+            (11, WeatherSevereType::LesSuetesWind),
+            (12, WeatherSevereType::Avalanche),
+            (13, WeatherSevereType::FlashFlood),
+            (14, WeatherSevereType::TropicalStorm),
+            (15, WeatherSevereType::InlandTropicalStorm),
+            (16, WeatherSevereType::Blizzard),
+            (17, WeatherSevereType::IceStorm),
+            (18, WeatherSevereType::FreezingRain),
+            (19, WeatherSevereType::DebrisFlow),
+            (20, WeatherSevereType::FlashFreeze),
+            (21, WeatherSevereType::DustStorm),
+            (22, WeatherSevereType::HighWind),
+            (23, WeatherSevereType::WinterStorm),
+            (24, WeatherSevereType::HeavyFreezingSpray),
+            (25, WeatherSevereType::ExtremeCold),
+            (26, WeatherSevereType::WindChill),
+            (27, WeatherSevereType::ColdWave),
+            (28, WeatherSevereType::HeavySnowAlert),
+            (29, WeatherSevereType::LakeEffectBlowingSnow),
+            (30, WeatherSevereType::SnowSquall),
+            (31, WeatherSevereType::LakeEffectSnow),
+            (32, WeatherSevereType::WinterWeather),
+            (33, WeatherSevereType::Sleet),
+            (34, WeatherSevereType::Snowfall),
+            (35, WeatherSevereType::SnowAndBlowingSnow),
+            (36, WeatherSevereType::BlowingSnow),
+            (37, WeatherSevereType::SnowAlert),
+            (38, WeatherSevereType::ArcticOutflow),
+            (39, WeatherSevereType::FreezingDrizzle),
+            (40, WeatherSevereType::Storm),
+            (41, WeatherSevereType::StormSurge),
+            (42, WeatherSevereType::Rainfall),
+            (43, WeatherSevereType::ArealFlood),
+            (44, WeatherSevereType::CoastalFlood),
+            (45, WeatherSevereType::LakeshoreFlood),
+            (46, WeatherSevereType::ExcessiveHeat),
+            (47, WeatherSevereType::Heat),
+            (48, WeatherSevereType::Weather),
+            (49, WeatherSevereType::HighHeatAndHumidity),
+            (50, WeatherSevereType::HumidexAndHealth),
+            (51, WeatherSevereType::Humidex),
+            (52, WeatherSevereType::Gale),
+            (53, WeatherSevereType::FreezingSpray),
+            (54, WeatherSevereType::SpecialMarine),
+            (55, WeatherSevereType::Squall),
+            (56, WeatherSevereType::StrongWind),
+            (57, WeatherSevereType::LakeWind),
+            (58, WeatherSevereType::MarineWeather),
+            (59, WeatherSevereType::Wind),
+            (60, WeatherSevereType::SmallCraftHazardousSeas),
+            (61, WeatherSevereType::HazardousSeas),
+            (62, WeatherSevereType::SmallCraft),
+            (63, WeatherSevereType::SmallCraftWinds),
+            (64, WeatherSevereType::SmallCraftRoughBar),
+            (65, WeatherSevereType::HighWaterLevel),
+            (66, WeatherSevereType::Ashfall),
+            (67, WeatherSevereType::FreezingFog),
+            (68, WeatherSevereType::DenseFog),
+            (69, WeatherSevereType::DenseSmoke),
+            (70, WeatherSevereType::BlowingDust),
+            (71, WeatherSevereType::HardFreeze),
+            (72, WeatherSevereType::Freeze),
+            (73, WeatherSevereType::Frost),
+            (74, WeatherSevereType::FireWeather),
+            (75, WeatherSevereType::Flood),
+            (76, WeatherSevereType::RipTide),
+            (77, WeatherSevereType::HighSurf),
+            (78, WeatherSevereType::Smog),
+            (79, WeatherSevereType::AirQuality),
+            (80, WeatherSevereType::BriskWind),
+            (81, WeatherSevereType::AirStagnation),
+            (82, WeatherSevereType::LowWater),
+            (83, WeatherSevereType::Hydrological),
+            (84, WeatherSevereType::SpecialWeather),
+        ],
+        names:   &[
+            "Unspecified",
+            "Tornado",
+            "Tsunami",
+            "Hurricane",
+            "ExtremeWind",
+            "Typhoon",
+            "InlandHurricane",
+            "HurricaneForceWind",
+            "Waterspout",
+            "SevereThunderstorm",
+            "WreckhouseWinds",
+            "LesSuetesWind",
+            "Avalanche",
+            "FlashFlood",
+            "TropicalStorm",
+            "InlandTropicalStorm",
+            "Blizzard",
+            "IceStorm",
+            "FreezingRain",
+            "DebrisFlow",
+            "FlashFreeze",
+            "DustStorm",
+            "HighWind",
+            "WinterStorm",
+            "HeavyFreezingSpray",
+            "ExtremeCold",
+            "WindChill",
+            "ColdWave",
+            "HeavySnowAlert",
+            "LakeEffectBlowingSnow",
+            "SnowSquall",
+            "LakeEffectSnow",
+            "WinterWeather",
+            "Sleet",
+            "Snowfall",
+            "SnowAndBlowingSnow",
+            "BlowingSnow",
+            "SnowAlert",
+            "ArcticOutflow",
+            "FreezingDrizzle",
+            "Storm",
+            "StormSurge",
+            "Rainfall",
+            "ArealFlood",
+            "CoastalFlood",
+            "LakeshoreFlood",
+            "ExcessiveHeat",
+            "Heat",
+            "Weather",
+            "HighHeatAndHumidity",
+            "HumidexAndHealth",
+            "Humidex",
+            "Gale",
+            "FreezingSpray",
+            "SpecialMarine",
+            "Squall",
+            "StrongWind",
+            "LakeWind",
+            "MarineWeather",
+            "Wind",
+            "SmallCraftHazardousSeas",
+            "HazardousSeas",
+            "SmallCraft",
+            "SmallCraftWinds",
+            "SmallCraftRoughBar",
+            "HighWaterLevel",
+            "Ashfall",
+            "FreezingFog",
+            "DenseFog",
+            "DenseSmoke",
+            "BlowingDust",
+            "HardFreeze",
+            "Freeze",
+            "Frost",
+            "FireWeather",
+            "Flood",
+            "RipTide",
+            "HighSurf",
+            "Smog",
+            "AirQuality",
+            "BriskWind",
+            "AirStagnation",
+            "LowWater",
+            "Hydrological",
+            "SpecialWeather",
+        ],
+        by_name: &[
+            79, 81, 38, 43, 66, 12, 16, 70, 36, 80, 44, 27, 19, 68, 69, 21, 46,
+            25, 4, 74, 13, 20, 75, 72, 39, 67, 18, 53, 73, 52, 71, 61, 47, 24,
+            28, 49, 77, 65, 22, 51, 50, 3, 7, 83, 17, 6, 15, 29, 31, 57, 45,
+            11, 82, 58, 42, 76, 9, 33, 62, 60, 64, 63, 78, 37, 35, 30, 34, 54,
+            84, 55, 40, 41, 56, 1, 14, 2, 5, 0, 8, 48, 59, 26, 23, 32, 10,
+        ],
+    };
+encode_enum!(WeatherSevereType, u8, 0xFF);
 #[doc = "number of seconds into the day since 00:00:00 UTC"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub struct TimeIntoDay(pub u32);
 impl TimeIntoDay {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-        Ok(TimeIntoDay(T::read_u32(buffer)))
+        let base_value = profile::base::Uint32::decode::<T>(buffer)?;
+        Ok(TimeIntoDay(base_value.0))
     }
 }
+encode_number!(TimeIntoDay);
 #[doc = "number of seconds into the day since local 00:00:00"]
-#[derive(Debug,Clone)]
+#[derive(Debug, Clone)]
 pub struct LocaltimeIntoDay(pub u32);
 impl LocaltimeIntoDay {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-        Ok(LocaltimeIntoDay(T::read_u32(buffer)))
+        let base_value = profile::base::Uint32::decode::<T>(buffer)?;
+        Ok(LocaltimeIntoDay(base_value.0))
     }
 }
-#[derive(Debug,Clone)]
+encode_number!(LocaltimeIntoDay);
+#[derive(Debug, Clone)]
 pub enum StrokeType {
     NoEvent = 0,
     #[doc = "stroke was detected but cannot be identified"]
@@ -3952,7 +5029,8 @@ impl StrokeType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(StrokeType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum BodyLocation {
     LeftLeg = 0,
     LeftCalf = 1,
@@ -4048,7 +5126,8 @@ impl BodyLocation {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BodyLocation, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SegmentLapStatus {
     End = 0,
     Fail = 1,
@@ -4064,7 +5143,8 @@ impl SegmentLapStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SegmentLapStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SegmentLeaderboardType {
     Overall = 0,
     PersonalBest = 1,
@@ -4098,7 +5178,8 @@ impl SegmentLeaderboardType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SegmentLeaderboardType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SegmentDeleteStatus {
     DoNotDelete = 0,
     DeleteOne = 1,
@@ -4116,7 +5197,8 @@ impl SegmentDeleteStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SegmentDeleteStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SegmentSelectionType {
     Starred = 0,
     Suggested = 1,
@@ -4132,7 +5214,8 @@ impl SegmentSelectionType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SegmentSelectionType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SourceType {
     #[doc = "External device connected with ANT"]
     Ant = 0,
@@ -4162,14 +5245,17 @@ impl SourceType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SourceType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub struct LocalDeviceType(pub u8);
 impl LocalDeviceType {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
-        Ok(LocalDeviceType(buffer[0]))
+        let base_value = profile::base::Uint8::decode::<T>(buffer)?;
+        Ok(LocalDeviceType(base_value.0))
     }
 }
-#[derive(Debug,Clone)]
+encode_number!(LocalDeviceType);
+#[derive(Debug, Clone)]
 pub enum DisplayOrientation {
     #[doc = "automatic if the device supports it"]
     Auto = 0,
@@ -4194,7 +5280,8 @@ impl DisplayOrientation {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DisplayOrientation, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WorkoutEquipment {
     None = 0,
     SwimFins = 1,
@@ -4218,7 +5305,8 @@ impl WorkoutEquipment {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WorkoutEquipment, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum WatchfaceMode {
     Digital = 0,
     Analog = 1,
@@ -4238,7 +5326,8 @@ impl WatchfaceMode {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WatchfaceMode, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DigitalWatchfaceLayout {
     Traditional = 0,
     Modern = 1,
@@ -4256,7 +5345,8 @@ impl DigitalWatchfaceLayout {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DigitalWatchfaceLayout, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AnalogWatchfaceLayout {
     Minimal = 0,
     Traditional = 1,
@@ -4274,7 +5364,8 @@ impl AnalogWatchfaceLayout {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AnalogWatchfaceLayout, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum RiderPositionType {
     Seated = 0,
     Standing = 1,
@@ -4294,7 +5385,8 @@ impl RiderPositionType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(RiderPositionType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum PowerPhaseType {
     PowerPhaseStartAngle = 0,
     PowerPhaseEndAngle = 1,
@@ -4314,7 +5406,8 @@ impl PowerPhaseType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(PowerPhaseType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum CameraEventType {
     #[doc = "Start of video recording"]
     VideoStart = 0,
@@ -4360,7 +5453,8 @@ impl CameraEventType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CameraEventType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum SensorType {
     Accelerometer = 0,
     Gyroscope = 1,
@@ -4381,7 +5475,8 @@ impl SensorType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SensorType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum BikeLightNetworkConfigType {
     Auto = 0,
     Individual = 4,
@@ -4401,7 +5496,8 @@ impl BikeLightNetworkConfigType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BikeLightNetworkConfigType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum CommTimeoutType {
     #[doc = "Timeout pairing to any device"]
     WildcardPairingTimeout = 0,
@@ -4425,7 +5521,8 @@ impl CommTimeoutType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CommTimeoutType, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CameraOrientationType {
     CameraOrientation0 = 0,
     CameraOrientation90 = 1,
@@ -4445,7 +5542,8 @@ impl CameraOrientationType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CameraOrientationType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AttitudeStage {
     Failed = 0,
     Aligning = 1,
@@ -4465,7 +5563,8 @@ impl AttitudeStage {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AttitudeStage, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AttitudeValidity {
     TrackAngleHeadingValid = 1,
     PitchValid = 2,
@@ -4503,7 +5602,8 @@ impl AttitudeValidity {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AttitudeValidity, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum AutoSyncFrequency {
     Never = 0,
     Occasionally = 1,
@@ -4525,7 +5625,8 @@ impl AutoSyncFrequency {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AutoSyncFrequency, u8, 0xFF);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExdLayout {
     FullScreen = 0,
     HalfVertical = 1,
@@ -4553,7 +5654,8 @@ impl ExdLayout {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ExdLayout, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ExdDisplayType {
     Numerical = 0,
     Simple = 1,
@@ -4587,7 +5689,8 @@ impl ExdDisplayType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ExdDisplayType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ExdDataUnits {
     NoUnits = 0,
     Laps = 1,
@@ -4644,62 +5747,139 @@ pub enum ExdDataUnits {
 impl ExdDataUnits {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(ExdDataUnits::NoUnits),
-            1 => Ok(ExdDataUnits::Laps),
-            2 => Ok(ExdDataUnits::MilesPerHour),
-            3 => Ok(ExdDataUnits::KilometersPerHour),
-            4 => Ok(ExdDataUnits::FeetPerHour),
-            5 => Ok(ExdDataUnits::MetersPerHour),
-            6 => Ok(ExdDataUnits::DegreesCelsius),
-            7 => Ok(ExdDataUnits::DegreesFarenheit),
-            8 => Ok(ExdDataUnits::Zone),
-            9 => Ok(ExdDataUnits::Gear),
-            10 => Ok(ExdDataUnits::Rpm),
-            11 => Ok(ExdDataUnits::Bpm),
-            12 => Ok(ExdDataUnits::Degrees),
-            13 => Ok(ExdDataUnits::Millimeters),
-            14 => Ok(ExdDataUnits::Meters),
-            15 => Ok(ExdDataUnits::Kilometers),
-            16 => Ok(ExdDataUnits::Feet),
-            17 => Ok(ExdDataUnits::Yards),
-            18 => Ok(ExdDataUnits::Kilofeet),
-            19 => Ok(ExdDataUnits::Miles),
-            20 => Ok(ExdDataUnits::Time),
-            21 => Ok(ExdDataUnits::EnumTurnType),
-            22 => Ok(ExdDataUnits::Percent),
-            23 => Ok(ExdDataUnits::Watts),
-            24 => Ok(ExdDataUnits::WattsPerKilogram),
-            25 => Ok(ExdDataUnits::EnumBatteryStatus),
-            26 => Ok(ExdDataUnits::EnumBikeLightBeamAngleMode),
-            27 => Ok(ExdDataUnits::EnumBikeLightBatteryStatus),
-            28 => Ok(ExdDataUnits::EnumBikeLightNetworkConfigType),
-            29 => Ok(ExdDataUnits::Lights),
-            30 => Ok(ExdDataUnits::Seconds),
-            31 => Ok(ExdDataUnits::Minutes),
-            32 => Ok(ExdDataUnits::Hours),
-            33 => Ok(ExdDataUnits::Calories),
-            34 => Ok(ExdDataUnits::Kilojoules),
-            35 => Ok(ExdDataUnits::Milliseconds),
-            36 => Ok(ExdDataUnits::SecondPerMile),
-            37 => Ok(ExdDataUnits::SecondPerKilometer),
-            38 => Ok(ExdDataUnits::Centimeter),
-            39 => Ok(ExdDataUnits::EnumCoursePoint),
-            40 => Ok(ExdDataUnits::Bradians),
-            41 => Ok(ExdDataUnits::EnumSport),
-            42 => Ok(ExdDataUnits::InchesHg),
-            43 => Ok(ExdDataUnits::MmHg),
-            44 => Ok(ExdDataUnits::Mbars),
-            45 => Ok(ExdDataUnits::HectoPascals),
-            46 => Ok(ExdDataUnits::FeetPerMin),
-            47 => Ok(ExdDataUnits::MetersPerMin),
-            48 => Ok(ExdDataUnits::MetersPerSec),
-            49 => Ok(ExdDataUnits::EightCardinal),
-            _ => Ok(ExdDataUnits::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(EXD_DATA_UNITS.get(base_value.0).unwrap_or(ExdDataUnits::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ExdDataUnits::Unknown => "Unknown",
+            _ => EXD_DATA_UNITS.name(self.clone() as u8).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for ExdDataUnits {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        EXD_DATA_UNITS.parse(name).ok_or(())
+    }
+}
+static EXD_DATA_UNITS: profile::table::EnumTable<u8, ExdDataUnits> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, ExdDataUnits::NoUnits),
+            (1, ExdDataUnits::Laps),
+            (2, ExdDataUnits::MilesPerHour),
+            (3, ExdDataUnits::KilometersPerHour),
+            (4, ExdDataUnits::FeetPerHour),
+            (5, ExdDataUnits::MetersPerHour),
+            (6, ExdDataUnits::DegreesCelsius),
+            (7, ExdDataUnits::DegreesFarenheit),
+            (8, ExdDataUnits::Zone),
+            (9, ExdDataUnits::Gear),
+            (10, ExdDataUnits::Rpm),
+            (11, ExdDataUnits::Bpm),
+            (12, ExdDataUnits::Degrees),
+            (13, ExdDataUnits::Millimeters),
+            (14, ExdDataUnits::Meters),
+            (15, ExdDataUnits::Kilometers),
+            (16, ExdDataUnits::Feet),
+            (17, ExdDataUnits::Yards),
+            (18, ExdDataUnits::Kilofeet),
+            (19, ExdDataUnits::Miles),
+            (20, ExdDataUnits::Time),
+            (21, ExdDataUnits::EnumTurnType),
+            (22, ExdDataUnits::Percent),
+            (23, ExdDataUnits::Watts),
+            (24, ExdDataUnits::WattsPerKilogram),
+            (25, ExdDataUnits::EnumBatteryStatus),
+            (26, ExdDataUnits::EnumBikeLightBeamAngleMode),
+            (27, ExdDataUnits::EnumBikeLightBatteryStatus),
+            (28, ExdDataUnits::EnumBikeLightNetworkConfigType),
+            (29, ExdDataUnits::Lights),
+            (30, ExdDataUnits::Seconds),
+            (31, ExdDataUnits::Minutes),
+            (32, ExdDataUnits::Hours),
+            (33, ExdDataUnits::Calories),
+            (34, ExdDataUnits::Kilojoules),
+            (35, ExdDataUnits::Milliseconds),
+            (36, ExdDataUnits::SecondPerMile),
+            (37, ExdDataUnits::SecondPerKilometer),
+            (38, ExdDataUnits::Centimeter),
+            (39, ExdDataUnits::EnumCoursePoint),
+            (40, ExdDataUnits::Bradians),
+            (41, ExdDataUnits::EnumSport),
+            (42, ExdDataUnits::InchesHg),
+            (43, ExdDataUnits::MmHg),
+            (44, ExdDataUnits::Mbars),
+            (45, ExdDataUnits::HectoPascals),
+            (46, ExdDataUnits::FeetPerMin),
+            (47, ExdDataUnits::MetersPerMin),
+            (48, ExdDataUnits::MetersPerSec),
+            (49, ExdDataUnits::EightCardinal),
+        ],
+        names:   &[
+            "NoUnits",
+            "Laps",
+            "MilesPerHour",
+            "KilometersPerHour",
+            "FeetPerHour",
+            "MetersPerHour",
+            "DegreesCelsius",
+            "DegreesFarenheit",
+            "Zone",
+            "Gear",
+            "Rpm",
+            "Bpm",
+            "Degrees",
+            "Millimeters",
+            "Meters",
+            "Kilometers",
+            "Feet",
+            "Yards",
+            "Kilofeet",
+            "Miles",
+            "Time",
+            "EnumTurnType",
+            "Percent",
+            "Watts",
+            "WattsPerKilogram",
+            "EnumBatteryStatus",
+            "EnumBikeLightBeamAngleMode",
+            "EnumBikeLightBatteryStatus",
+            "EnumBikeLightNetworkConfigType",
+            "Lights",
+            "Seconds",
+            "Minutes",
+            "Hours",
+            "Calories",
+            "Kilojoules",
+            "Milliseconds",
+            "SecondPerMile",
+            "SecondPerKilometer",
+            "Centimeter",
+            "EnumCoursePoint",
+            "Bradians",
+            "EnumSport",
+            "InchesHg",
+            "MmHg",
+            "Mbars",
+            "HectoPascals",
+            "FeetPerMin",
+            "MetersPerMin",
+            "MetersPerSec",
+            "EightCardinal",
+        ],
+        by_name: &[
+            11, 40, 33, 38, 12, 6, 7, 49, 25, 27, 26, 28, 39, 41, 21, 16, 4,
+            46, 9, 45, 32, 42, 18, 34, 15, 3, 1, 29, 44, 14, 5, 47, 48, 19, 2,
+            13, 35, 31, 43, 0, 22, 10, 37, 36, 30, 20, 23, 24, 17, 8,
+        ],
+    };
+encode_enum!(ExdDataUnits, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ExdQualifiers {
     NoQualifier = 0,
     Instantaneous = 1,
@@ -4799,7 +5979,8 @@ impl ExdQualifiers {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ExdQualifiers, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ExdDescriptors {
     BikeLightBatteryStatus = 0,
     BeamAngleStatus = 1,
@@ -4904,109 +6085,236 @@ pub enum ExdDescriptors {
 impl ExdDescriptors {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Enum::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(ExdDescriptors::BikeLightBatteryStatus),
-            1 => Ok(ExdDescriptors::BeamAngleStatus),
-            2 => Ok(ExdDescriptors::BateryLevel),
-            3 => Ok(ExdDescriptors::LightNetworkMode),
-            4 => Ok(ExdDescriptors::NumberLightsConnected),
-            5 => Ok(ExdDescriptors::Cadence),
-            6 => Ok(ExdDescriptors::Distance),
-            7 => Ok(ExdDescriptors::EstimatedTimeOfArrival),
-            8 => Ok(ExdDescriptors::Heading),
-            9 => Ok(ExdDescriptors::Time),
-            10 => Ok(ExdDescriptors::BatteryLevel),
-            11 => Ok(ExdDescriptors::TrainerResistance),
-            12 => Ok(ExdDescriptors::TrainerTargetPower),
-            13 => Ok(ExdDescriptors::TimeSeated),
-            14 => Ok(ExdDescriptors::TimeStanding),
-            15 => Ok(ExdDescriptors::Elevation),
-            16 => Ok(ExdDescriptors::Grade),
-            17 => Ok(ExdDescriptors::Ascent),
-            18 => Ok(ExdDescriptors::Descent),
-            19 => Ok(ExdDescriptors::VerticalSpeed),
-            20 => Ok(ExdDescriptors::Di2BatteryLevel),
-            21 => Ok(ExdDescriptors::FrontGear),
-            22 => Ok(ExdDescriptors::RearGear),
-            23 => Ok(ExdDescriptors::GearRatio),
-            24 => Ok(ExdDescriptors::HeartRate),
-            25 => Ok(ExdDescriptors::HeartRateZone),
-            26 => Ok(ExdDescriptors::TimeInHeartRateZone),
-            27 => Ok(ExdDescriptors::HeartRateReserve),
-            28 => Ok(ExdDescriptors::Calories),
-            29 => Ok(ExdDescriptors::GpsAccuracy),
-            30 => Ok(ExdDescriptors::GpsSignalStrength),
-            31 => Ok(ExdDescriptors::Temperature),
-            32 => Ok(ExdDescriptors::TimeOfDay),
-            33 => Ok(ExdDescriptors::Balance),
-            34 => Ok(ExdDescriptors::PedalSmoothness),
-            35 => Ok(ExdDescriptors::Power),
-            36 => Ok(ExdDescriptors::FunctionalThresholdPower),
-            37 => Ok(ExdDescriptors::IntensityFactor),
-            38 => Ok(ExdDescriptors::Work),
-            39 => Ok(ExdDescriptors::PowerRatio),
-            40 => Ok(ExdDescriptors::NormalizedPower),
-            41 => Ok(ExdDescriptors::TrainingStressScore),
-            42 => Ok(ExdDescriptors::TimeOnZone),
-            43 => Ok(ExdDescriptors::Speed),
-            44 => Ok(ExdDescriptors::Laps),
-            45 => Ok(ExdDescriptors::Reps),
-            46 => Ok(ExdDescriptors::WorkoutStep),
-            47 => Ok(ExdDescriptors::CourseDistance),
-            48 => Ok(ExdDescriptors::NavigationDistance),
-            49 => Ok(ExdDescriptors::CourseEstimatedTimeOfArrival),
-            50 => Ok(ExdDescriptors::NavigationEstimatedTimeOfArrival),
-            51 => Ok(ExdDescriptors::CourseTime),
-            52 => Ok(ExdDescriptors::NavigationTime),
-            53 => Ok(ExdDescriptors::CourseHeading),
-            54 => Ok(ExdDescriptors::NavigationHeading),
-            55 => Ok(ExdDescriptors::PowerZone),
-            56 => Ok(ExdDescriptors::TorqueEffectiveness),
-            57 => Ok(ExdDescriptors::TimerTime),
-            58 => Ok(ExdDescriptors::PowerWeightRatio),
-            59 => Ok(ExdDescriptors::LeftPlatformCenterOffset),
-            60 => Ok(ExdDescriptors::RightPlatformCenterOffset),
-            61 => Ok(ExdDescriptors::LeftPowerPhaseStartAngle),
-            62 => Ok(ExdDescriptors::RightPowerPhaseStartAngle),
-            63 => Ok(ExdDescriptors::LeftPowerPhaseFinishAngle),
-            64 => Ok(ExdDescriptors::RightPowerPhaseFinishAngle),
-            65 => Ok(ExdDescriptors::Gears),
-            66 => Ok(ExdDescriptors::Pace),
-            67 => Ok(ExdDescriptors::TrainingEffect),
-            68 => Ok(ExdDescriptors::VerticalOscillation),
-            69 => Ok(ExdDescriptors::VerticalRatio),
-            70 => Ok(ExdDescriptors::GroundContactTime),
-            71 => Ok(ExdDescriptors::LeftGroundContactTimeBalance),
-            72 => Ok(ExdDescriptors::RightGroundContactTimeBalance),
-            73 => Ok(ExdDescriptors::StrideLength),
-            74 => Ok(ExdDescriptors::RunningCadence),
-            75 => Ok(ExdDescriptors::PerformanceCondition),
-            76 => Ok(ExdDescriptors::CourseType),
-            77 => Ok(ExdDescriptors::TimeInPowerZone),
-            78 => Ok(ExdDescriptors::NavigationTurn),
-            79 => Ok(ExdDescriptors::CourseLocation),
-            80 => Ok(ExdDescriptors::NavigationLocation),
-            81 => Ok(ExdDescriptors::Compass),
-            82 => Ok(ExdDescriptors::GearCombo),
-            83 => Ok(ExdDescriptors::MuscleOxygen),
-            84 => Ok(ExdDescriptors::Icon),
-            85 => Ok(ExdDescriptors::CompassHeading),
-            86 => Ok(ExdDescriptors::GpsHeading),
-            87 => Ok(ExdDescriptors::GpsElevation),
-            88 => Ok(ExdDescriptors::AnaerobicTrainingEffect),
-            89 => Ok(ExdDescriptors::Course),
-            90 => Ok(ExdDescriptors::OffCourse),
-            91 => Ok(ExdDescriptors::GlideRatio),
-            92 => Ok(ExdDescriptors::VerticalDistance),
-            93 => Ok(ExdDescriptors::Vmg),
-            94 => Ok(ExdDescriptors::AmbientPressure),
-            95 => Ok(ExdDescriptors::Pressure),
-            96 => Ok(ExdDescriptors::Vam),
-            _ => Ok(ExdDescriptors::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(EXD_DESCRIPTORS.get(base_value.0).unwrap_or(ExdDescriptors::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ExdDescriptors::Unknown => "Unknown",
+            _ => EXD_DESCRIPTORS.name(self.clone() as u8).unwrap_or("Unknown"),
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for ExdDescriptors {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        EXD_DESCRIPTORS.parse(name).ok_or(())
+    }
+}
+static EXD_DESCRIPTORS: profile::table::EnumTable<u8, ExdDescriptors> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, ExdDescriptors::BikeLightBatteryStatus),
+            (1, ExdDescriptors::BeamAngleStatus),
+            (2, ExdDescriptors::BateryLevel),
+            (3, ExdDescriptors::LightNetworkMode),
+            (4, ExdDescriptors::NumberLightsConnected),
+            (5, ExdDescriptors::Cadence),
+            (6, ExdDescriptors::Distance),
+            (7, ExdDescriptors::EstimatedTimeOfArrival),
+            (8, ExdDescriptors::Heading),
+            (9, ExdDescriptors::Time),
+            (10, ExdDescriptors::BatteryLevel),
+            (11, ExdDescriptors::TrainerResistance),
+            (12, ExdDescriptors::TrainerTargetPower),
+            (13, ExdDescriptors::TimeSeated),
+            (14, ExdDescriptors::TimeStanding),
+            (15, ExdDescriptors::Elevation),
+            (16, ExdDescriptors::Grade),
+            (17, ExdDescriptors::Ascent),
+            (18, ExdDescriptors::Descent),
+            (19, ExdDescriptors::VerticalSpeed),
+            (20, ExdDescriptors::Di2BatteryLevel),
+            (21, ExdDescriptors::FrontGear),
+            (22, ExdDescriptors::RearGear),
+            (23, ExdDescriptors::GearRatio),
+            (24, ExdDescriptors::HeartRate),
+            (25, ExdDescriptors::HeartRateZone),
+            (26, ExdDescriptors::TimeInHeartRateZone),
+            (27, ExdDescriptors::HeartRateReserve),
+            (28, ExdDescriptors::Calories),
+            (29, ExdDescriptors::GpsAccuracy),
+            (30, ExdDescriptors::GpsSignalStrength),
+            (31, ExdDescriptors::Temperature),
+            (32, ExdDescriptors::TimeOfDay),
+            (33, ExdDescriptors::Balance),
+            (34, ExdDescriptors::PedalSmoothness),
+            (35, ExdDescriptors::Power),
+            (36, ExdDescriptors::FunctionalThresholdPower),
+            (37, ExdDescriptors::IntensityFactor),
+            (38, ExdDescriptors::Work),
+            (39, ExdDescriptors::PowerRatio),
+            (40, ExdDescriptors::NormalizedPower),
+            (41, ExdDescriptors::TrainingStressScore),
+            (42, ExdDescriptors::TimeOnZone),
+            (43, ExdDescriptors::Speed),
+            (44, ExdDescriptors::Laps),
+            (45, ExdDescriptors::Reps),
+            (46, ExdDescriptors::WorkoutStep),
+            (47, ExdDescriptors::CourseDistance),
+            (48, ExdDescriptors::NavigationDistance),
+            (49, ExdDescriptors::CourseEstimatedTimeOfArrival),
+            (50, ExdDescriptors::NavigationEstimatedTimeOfArrival),
+            (51, ExdDescriptors::CourseTime),
+            (52, ExdDescriptors::NavigationTime),
+            (53, ExdDescriptors::CourseHeading),
+            (54, ExdDescriptors::NavigationHeading),
+            (55, ExdDescriptors::PowerZone),
+            (56, ExdDescriptors::TorqueEffectiveness),
+            (57, ExdDescriptors::TimerTime),
+            (58, ExdDescriptors::PowerWeightRatio),
+            (59, ExdDescriptors::LeftPlatformCenterOffset),
+            (60, ExdDescriptors::RightPlatformCenterOffset),
+            (61, ExdDescriptors::LeftPowerPhaseStartAngle),
+            (62, ExdDescriptors::RightPowerPhaseStartAngle),
+            (63, ExdDescriptors::LeftPowerPhaseFinishAngle),
+            (64, ExdDescriptors::RightPowerPhaseFinishAngle),
+            (65, ExdDescriptors::Gears),
+            (66, ExdDescriptors::Pace),
+            (67, ExdDescriptors::TrainingEffect),
+            (68, ExdDescriptors::VerticalOscillation),
+            (69, ExdDescriptors::VerticalRatio),
+            (70, ExdDescriptors::GroundContactTime),
+            (71, ExdDescriptors::LeftGroundContactTimeBalance),
+            (72, ExdDescriptors::RightGroundContactTimeBalance),
+            (73, ExdDescriptors::StrideLength),
+            (74, ExdDescriptors::RunningCadence),
+            (75, ExdDescriptors::PerformanceCondition),
+            (76, ExdDescriptors::CourseType),
+            (77, ExdDescriptors::TimeInPowerZone),
+            (78, ExdDescriptors::NavigationTurn),
+            (79, ExdDescriptors::CourseLocation),
+            (80, ExdDescriptors::NavigationLocation),
+            (81, ExdDescriptors::Compass),
+            (82, ExdDescriptors::GearCombo),
+            (83, ExdDescriptors::MuscleOxygen),
+            (84, ExdDescriptors::Icon),
+            (85, ExdDescriptors::CompassHeading),
+            (86, ExdDescriptors::GpsHeading),
+            (87, ExdDescriptors::GpsElevation),
+            (88, ExdDescriptors::AnaerobicTrainingEffect),
+            (89, ExdDescriptors::Course),
+            (90, ExdDescriptors::OffCourse),
+            (91, ExdDescriptors::GlideRatio),
+            (92, ExdDescriptors::VerticalDistance),
+            (93, ExdDescriptors::Vmg),
+            (94, ExdDescriptors::AmbientPressure),
+            (95, ExdDescriptors::Pressure),
+            (96, ExdDescriptors::Vam),
+        ],
+        names:   &[
+            "BikeLightBatteryStatus",
+            "BeamAngleStatus",
+            "BateryLevel",
+            "LightNetworkMode",
+            "NumberLightsConnected",
+            "Cadence",
+            "Distance",
+            "EstimatedTimeOfArrival",
+            "Heading",
+            "Time",
+            "BatteryLevel",
+            "TrainerResistance",
+            "TrainerTargetPower",
+            "TimeSeated",
+            "TimeStanding",
+            "Elevation",
+            "Grade",
+            "Ascent",
+            "Descent",
+            "VerticalSpeed",
+            "Di2BatteryLevel",
+            "FrontGear",
+            "RearGear",
+            "GearRatio",
+            "HeartRate",
+            "HeartRateZone",
+            "TimeInHeartRateZone",
+            "HeartRateReserve",
+            "Calories",
+            "GpsAccuracy",
+            "GpsSignalStrength",
+            "Temperature",
+            "TimeOfDay",
+            "Balance",
+            "PedalSmoothness",
+            "Power",
+            "FunctionalThresholdPower",
+            "IntensityFactor",
+            "Work",
+            "PowerRatio",
+            "NormalizedPower",
+            "TrainingStressScore",
+            "TimeOnZone",
+            "Speed",
+            "Laps",
+            "Reps",
+            "WorkoutStep",
+            "CourseDistance",
+            "NavigationDistance",
+            "CourseEstimatedTimeOfArrival",
+            "NavigationEstimatedTimeOfArrival",
+            "CourseTime",
+            "NavigationTime",
+            "CourseHeading",
+            "NavigationHeading",
+            "PowerZone",
+            "TorqueEffectiveness",
+            "TimerTime",
+            "PowerWeightRatio",
+            "LeftPlatformCenterOffset",
+            "RightPlatformCenterOffset",
+            "LeftPowerPhaseStartAngle",
+            "RightPowerPhaseStartAngle",
+            "LeftPowerPhaseFinishAngle",
+            "RightPowerPhaseFinishAngle",
+            "Gears",
+            "Pace",
+            "TrainingEffect",
+            "VerticalOscillation",
+            "VerticalRatio",
+            "GroundContactTime",
+            "LeftGroundContactTimeBalance",
+            "RightGroundContactTimeBalance",
+            "StrideLength",
+            "RunningCadence",
+            "PerformanceCondition",
+            "CourseType",
+            "TimeInPowerZone",
+            "NavigationTurn",
+            "CourseLocation",
+            "NavigationLocation",
+            "Compass",
+            "GearCombo",
+            "MuscleOxygen",
+            "Icon",
+            "CompassHeading",
+            "GpsHeading",
+            "GpsElevation",
+            "AnaerobicTrainingEffect",
+            "Course",
+            "OffCourse",
+            "GlideRatio",
+            "VerticalDistance",
+            "Vmg",
+            "AmbientPressure",
+            "Pressure",
+            "Vam",
+        ],
+        by_name: &[
+            94, 88, 17, 33, 2, 10, 1, 0, 5, 28, 81, 85, 89, 47, 49, 53, 79, 51,
+            76, 18, 20, 6, 15, 7, 21, 36, 82, 23, 65, 91, 29, 87, 86, 30, 16,
+            70, 8, 24, 27, 25, 84, 37, 44, 71, 59, 63, 61, 3, 83, 48, 50, 54,
+            80, 52, 78, 40, 4, 90, 66, 34, 75, 35, 39, 58, 55, 95, 22, 45, 72,
+            60, 64, 62, 74, 43, 73, 31, 9, 26, 77, 32, 42, 13, 14, 57, 56, 11,
+            12, 67, 41, 96, 92, 68, 69, 19, 93, 38, 46,
+        ],
+    };
+encode_enum!(ExdDescriptors, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum AutoActivityDetect {
     None = 0,
     Running = 1,
@@ -5032,7 +6340,8 @@ impl AutoActivityDetect {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(AutoActivityDetect, u32, 0xFFFFFFFF);
+#[derive(Debug, Clone)]
 pub enum SupportedExdScreenLayouts {
     FullScreen = 1,
     HalfVertical = 2,
@@ -5060,7 +6369,8 @@ impl SupportedExdScreenLayouts {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SupportedExdScreenLayouts, u32, 0);
+#[derive(Debug, Clone)]
 pub enum FitBaseType {
     Enum = 0,
     Sint8 = 1,
@@ -5106,7 +6416,8 @@ impl FitBaseType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(FitBaseType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum TurnType {
     ArrivingIdx = 0,
     ArrivingLeftIdx = 1,
@@ -5194,7 +6505,8 @@ impl TurnType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(TurnType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum BikeLightBeamAngleMode {
     Manual = 0,
     Auto = 1,
@@ -5210,7 +6522,8 @@ impl BikeLightBeamAngleMode {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BikeLightBeamAngleMode, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum FitBaseUnit {
     Other = 0,
     Kilogram = 1,
@@ -5228,7 +6541,8 @@ impl FitBaseUnit {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(FitBaseUnit, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum SetType {
     Rest = 0,
     Active = 1,
@@ -5244,7 +6558,8 @@ impl SetType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SetType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum ExerciseCategory {
     BenchPress = 0,
     CalfRaise = 1,
@@ -5323,7 +6638,8 @@ impl ExerciseCategory {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ExerciseCategory, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum BenchPressExerciseName {
     AlternatingDumbbellChestPressOnSwissBall = 0,
     BarbellBenchPress = 1,
@@ -5360,7 +6676,8 @@ impl BenchPressExerciseName {
         match base_value . 0 { 0 => Ok ( BenchPressExerciseName :: AlternatingDumbbellChestPressOnSwissBall ) , 1 => Ok ( BenchPressExerciseName :: BarbellBenchPress ) , 2 => Ok ( BenchPressExerciseName :: BarbellBoardBenchPress ) , 3 => Ok ( BenchPressExerciseName :: BarbellFloorPress ) , 4 => Ok ( BenchPressExerciseName :: CloseGripBarbellBenchPress ) , 5 => Ok ( BenchPressExerciseName :: DeclineDumbbellBenchPress ) , 6 => Ok ( BenchPressExerciseName :: DumbbellBenchPress ) , 7 => Ok ( BenchPressExerciseName :: DumbbellFloorPress ) , 8 => Ok ( BenchPressExerciseName :: InclineBarbellBenchPress ) , 9 => Ok ( BenchPressExerciseName :: InclineDumbbellBenchPress ) , 10 => Ok ( BenchPressExerciseName :: InclineSmithMachineBenchPress ) , 11 => Ok ( BenchPressExerciseName :: IsometricBarbellBenchPress ) , 12 => Ok ( BenchPressExerciseName :: KettlebellChestPress ) , 13 => Ok ( BenchPressExerciseName :: NeutralGripDumbbellBenchPress ) , 14 => Ok ( BenchPressExerciseName :: NeutralGripDumbbellInclineBenchPress ) , 15 => Ok ( BenchPressExerciseName :: OneArmFloorPress ) , 16 => Ok ( BenchPressExerciseName :: WeightedOneArmFloorPress ) , 17 => Ok ( BenchPressExerciseName :: PartialLockout ) , 18 => Ok ( BenchPressExerciseName :: ReverseGripBarbellBenchPress ) , 19 => Ok ( BenchPressExerciseName :: ReverseGripInclineBenchPress ) , 20 => Ok ( BenchPressExerciseName :: SingleArmCableChestPress ) , 21 => Ok ( BenchPressExerciseName :: SingleArmDumbbellBenchPress ) , 22 => Ok ( BenchPressExerciseName :: SmithMachineBenchPress ) , 23 => Ok ( BenchPressExerciseName :: SwissBallDumbbellChestPress ) , 24 => Ok ( BenchPressExerciseName :: TripleStopBarbellBenchPress ) , 25 => Ok ( BenchPressExerciseName :: WideGripBarbellBenchPress ) , 26 => Ok ( BenchPressExerciseName :: AlternatingDumbbellChestPress ) , _ => Ok ( BenchPressExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(BenchPressExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CalfRaiseExerciseName {
     ThreeWayCalfRaise = 0,
     ThreeWayWeightedCalfRaise = 1,
@@ -5414,7 +6731,8 @@ impl CalfRaiseExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CalfRaiseExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CardioExerciseName {
     BobAndWeaveCircle = 0,
     WeightedBobAndWeaveCircle = 1,
@@ -5470,7 +6788,8 @@ impl CardioExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CardioExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CarryExerciseName {
     BarHolds = 0,
     FarmersWalk = 1,
@@ -5492,7 +6811,8 @@ impl CarryExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CarryExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum ChopExerciseName {
     CablePullThrough = 0,
     CableRotationalLift = 1,
@@ -5550,7 +6870,8 @@ impl ChopExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ChopExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CoreExerciseName {
     AbsJabs = 0,
     WeightedAbsJabs = 1,
@@ -5654,7 +6975,8 @@ impl CoreExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CoreExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CrunchExerciseName {
     BicycleCrunch = 0,
     CableCrunch = 1,
@@ -5745,102 +7067,217 @@ pub enum CrunchExerciseName {
 impl CrunchExerciseName {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(CrunchExerciseName::BicycleCrunch),
-            1 => Ok(CrunchExerciseName::CableCrunch),
-            2 => Ok(CrunchExerciseName::CircularArmCrunch),
-            3 => Ok(CrunchExerciseName::CrossedArmsCrunch),
-            4 => Ok(CrunchExerciseName::WeightedCrossedArmsCrunch),
-            5 => Ok(CrunchExerciseName::CrossLegReverseCrunch),
-            6 => Ok(CrunchExerciseName::WeightedCrossLegReverseCrunch),
-            7 => Ok(CrunchExerciseName::CrunchChop),
-            8 => Ok(CrunchExerciseName::WeightedCrunchChop),
-            9 => Ok(CrunchExerciseName::DoubleCrunch),
-            10 => Ok(CrunchExerciseName::WeightedDoubleCrunch),
-            11 => Ok(CrunchExerciseName::ElbowToKneeCrunch),
-            12 => Ok(CrunchExerciseName::WeightedElbowToKneeCrunch),
-            13 => Ok(CrunchExerciseName::FlutterKicks),
-            14 => Ok(CrunchExerciseName::WeightedFlutterKicks),
-            15 => Ok(CrunchExerciseName::FoamRollerReverseCrunchOnBench),
-            16 => {
-                Ok(CrunchExerciseName::WeightedFoamRollerReverseCrunchOnBench)
-            },
-            17 => Ok(CrunchExerciseName::FoamRollerReverseCrunchWithDumbbell),
-            18 => {
-                Ok(CrunchExerciseName::FoamRollerReverseCrunchWithMedicineBall)
+        Ok(CRUNCH_EXERCISE_NAME
+            .get(base_value.0)
+            .unwrap_or(CrunchExerciseName::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            CrunchExerciseName::Unknown => "Unknown",
+            _ => {
+                CRUNCH_EXERCISE_NAME
+                    .name(self.clone() as u16)
+                    .unwrap_or("Unknown")
             },
-            19 => Ok(CrunchExerciseName::FrogPress),
-            20 => Ok(CrunchExerciseName::HangingKneeRaiseObliqueCrunch),
-            21 => Ok(CrunchExerciseName::WeightedHangingKneeRaiseObliqueCrunch),
-            22 => Ok(CrunchExerciseName::HipCrossover),
-            23 => Ok(CrunchExerciseName::WeightedHipCrossover),
-            24 => Ok(CrunchExerciseName::HollowRock),
-            25 => Ok(CrunchExerciseName::WeightedHollowRock),
-            26 => Ok(CrunchExerciseName::InclineReverseCrunch),
-            27 => Ok(CrunchExerciseName::WeightedInclineReverseCrunch),
-            28 => Ok(CrunchExerciseName::KneelingCableCrunch),
-            29 => Ok(CrunchExerciseName::KneelingCrossCrunch),
-            30 => Ok(CrunchExerciseName::WeightedKneelingCrossCrunch),
-            31 => Ok(CrunchExerciseName::KneelingObliqueCableCrunch),
-            32 => Ok(CrunchExerciseName::KneesToElbow),
-            33 => Ok(CrunchExerciseName::LegExtensions),
-            34 => Ok(CrunchExerciseName::WeightedLegExtensions),
-            35 => Ok(CrunchExerciseName::LegLevers),
-            36 => Ok(CrunchExerciseName::McgillCurlUp),
-            37 => Ok(CrunchExerciseName::WeightedMcgillCurlUp),
-            38 => Ok(CrunchExerciseName::ModifiedPilatesRollUpWithBall),
-            39 => Ok(CrunchExerciseName::WeightedModifiedPilatesRollUpWithBall),
-            40 => Ok(CrunchExerciseName::PilatesCrunch),
-            41 => Ok(CrunchExerciseName::WeightedPilatesCrunch),
-            42 => Ok(CrunchExerciseName::PilatesRollUpWithBall),
-            43 => Ok(CrunchExerciseName::WeightedPilatesRollUpWithBall),
-            44 => Ok(CrunchExerciseName::RaisedLegsCrunch),
-            45 => Ok(CrunchExerciseName::WeightedRaisedLegsCrunch),
-            46 => Ok(CrunchExerciseName::ReverseCrunch),
-            47 => Ok(CrunchExerciseName::WeightedReverseCrunch),
-            48 => Ok(CrunchExerciseName::ReverseCrunchOnABench),
-            49 => Ok(CrunchExerciseName::WeightedReverseCrunchOnABench),
-            50 => Ok(CrunchExerciseName::ReverseCurlAndLift),
-            51 => Ok(CrunchExerciseName::WeightedReverseCurlAndLift),
-            52 => Ok(CrunchExerciseName::RotationalLift),
-            53 => Ok(CrunchExerciseName::WeightedRotationalLift),
-            54 => Ok(CrunchExerciseName::SeatedAlternatingReverseCrunch),
-            55 => {
-                Ok(CrunchExerciseName::WeightedSeatedAlternatingReverseCrunch)
-            },
-            56 => Ok(CrunchExerciseName::SeatedLegU),
-            57 => Ok(CrunchExerciseName::WeightedSeatedLegU),
-            58 => Ok(CrunchExerciseName::SideToSideCrunchAndWeave),
-            59 => Ok(CrunchExerciseName::WeightedSideToSideCrunchAndWeave),
-            60 => Ok(CrunchExerciseName::SingleLegReverseCrunch),
-            61 => Ok(CrunchExerciseName::WeightedSingleLegReverseCrunch),
-            62 => Ok(CrunchExerciseName::SkaterCrunchCross),
-            63 => Ok(CrunchExerciseName::WeightedSkaterCrunchCross),
-            64 => Ok(CrunchExerciseName::StandingCableCrunch),
-            65 => Ok(CrunchExerciseName::StandingSideCrunch),
-            66 => Ok(CrunchExerciseName::StepClimb),
-            67 => Ok(CrunchExerciseName::WeightedStepClimb),
-            68 => Ok(CrunchExerciseName::SwissBallCrunch),
-            69 => Ok(CrunchExerciseName::SwissBallReverseCrunch),
-            70 => Ok(CrunchExerciseName::WeightedSwissBallReverseCrunch),
-            71 => Ok(CrunchExerciseName::SwissBallRussianTwist),
-            72 => Ok(CrunchExerciseName::WeightedSwissBallRussianTwist),
-            73 => Ok(CrunchExerciseName::SwissBallSideCrunch),
-            74 => Ok(CrunchExerciseName::WeightedSwissBallSideCrunch),
-            75 => Ok(CrunchExerciseName::ThoracicCrunchesOnFoamRoller),
-            76 => Ok(CrunchExerciseName::WeightedThoracicCrunchesOnFoamRoller),
-            77 => Ok(CrunchExerciseName::TricepsCrunch),
-            78 => Ok(CrunchExerciseName::WeightedBicycleCrunch),
-            79 => Ok(CrunchExerciseName::WeightedCrunch),
-            80 => Ok(CrunchExerciseName::WeightedSwissBallCrunch),
-            81 => Ok(CrunchExerciseName::ToesToBar),
-            82 => Ok(CrunchExerciseName::WeightedToesToBar),
-            83 => Ok(CrunchExerciseName::Crunch),
-            _ => Ok(CrunchExerciseName::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for CrunchExerciseName {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        CRUNCH_EXERCISE_NAME.parse(name).ok_or(())
+    }
+}
+static CRUNCH_EXERCISE_NAME: profile::table::EnumTable<
+    u16,
+    CrunchExerciseName,
+> = profile::table::EnumTable {
+    values:  &[
+        (0, CrunchExerciseName::BicycleCrunch),
+        (1, CrunchExerciseName::CableCrunch),
+        (2, CrunchExerciseName::CircularArmCrunch),
+        (3, CrunchExerciseName::CrossedArmsCrunch),
+        (4, CrunchExerciseName::WeightedCrossedArmsCrunch),
+        (5, CrunchExerciseName::CrossLegReverseCrunch),
+        (6, CrunchExerciseName::WeightedCrossLegReverseCrunch),
+        (7, CrunchExerciseName::CrunchChop),
+        (8, CrunchExerciseName::WeightedCrunchChop),
+        (9, CrunchExerciseName::DoubleCrunch),
+        (10, CrunchExerciseName::WeightedDoubleCrunch),
+        (11, CrunchExerciseName::ElbowToKneeCrunch),
+        (12, CrunchExerciseName::WeightedElbowToKneeCrunch),
+        (13, CrunchExerciseName::FlutterKicks),
+        (14, CrunchExerciseName::WeightedFlutterKicks),
+        (15, CrunchExerciseName::FoamRollerReverseCrunchOnBench),
+        (16, CrunchExerciseName::WeightedFoamRollerReverseCrunchOnBench),
+        (17, CrunchExerciseName::FoamRollerReverseCrunchWithDumbbell),
+        (18, CrunchExerciseName::FoamRollerReverseCrunchWithMedicineBall),
+        (19, CrunchExerciseName::FrogPress),
+        (20, CrunchExerciseName::HangingKneeRaiseObliqueCrunch),
+        (21, CrunchExerciseName::WeightedHangingKneeRaiseObliqueCrunch),
+        (22, CrunchExerciseName::HipCrossover),
+        (23, CrunchExerciseName::WeightedHipCrossover),
+        (24, CrunchExerciseName::HollowRock),
+        (25, CrunchExerciseName::WeightedHollowRock),
+        (26, CrunchExerciseName::InclineReverseCrunch),
+        (27, CrunchExerciseName::WeightedInclineReverseCrunch),
+        (28, CrunchExerciseName::KneelingCableCrunch),
+        (29, CrunchExerciseName::KneelingCrossCrunch),
+        (30, CrunchExerciseName::WeightedKneelingCrossCrunch),
+        (31, CrunchExerciseName::KneelingObliqueCableCrunch),
+        (32, CrunchExerciseName::KneesToElbow),
+        (33, CrunchExerciseName::LegExtensions),
+        (34, CrunchExerciseName::WeightedLegExtensions),
+        (35, CrunchExerciseName::LegLevers),
+        (36, CrunchExerciseName::McgillCurlUp),
+        (37, CrunchExerciseName::WeightedMcgillCurlUp),
+        (38, CrunchExerciseName::ModifiedPilatesRollUpWithBall),
+        (39, CrunchExerciseName::WeightedModifiedPilatesRollUpWithBall),
+        (40, CrunchExerciseName::PilatesCrunch),
+        (41, CrunchExerciseName::WeightedPilatesCrunch),
+        (42, CrunchExerciseName::PilatesRollUpWithBall),
+        (43, CrunchExerciseName::WeightedPilatesRollUpWithBall),
+        (44, CrunchExerciseName::RaisedLegsCrunch),
+        (45, CrunchExerciseName::WeightedRaisedLegsCrunch),
+        (46, CrunchExerciseName::ReverseCrunch),
+        (47, CrunchExerciseName::WeightedReverseCrunch),
+        (48, CrunchExerciseName::ReverseCrunchOnABench),
+        (49, CrunchExerciseName::WeightedReverseCrunchOnABench),
+        (50, CrunchExerciseName::ReverseCurlAndLift),
+        (51, CrunchExerciseName::WeightedReverseCurlAndLift),
+        (52, CrunchExerciseName::RotationalLift),
+        (53, CrunchExerciseName::WeightedRotationalLift),
+        (54, CrunchExerciseName::SeatedAlternatingReverseCrunch),
+        (55, CrunchExerciseName::WeightedSeatedAlternatingReverseCrunch),
+        (56, CrunchExerciseName::SeatedLegU),
+        (57, CrunchExerciseName::WeightedSeatedLegU),
+        (58, CrunchExerciseName::SideToSideCrunchAndWeave),
+        (59, CrunchExerciseName::WeightedSideToSideCrunchAndWeave),
+        (60, CrunchExerciseName::SingleLegReverseCrunch),
+        (61, CrunchExerciseName::WeightedSingleLegReverseCrunch),
+        (62, CrunchExerciseName::SkaterCrunchCross),
+        (63, CrunchExerciseName::WeightedSkaterCrunchCross),
+        (64, CrunchExerciseName::StandingCableCrunch),
+        (65, CrunchExerciseName::StandingSideCrunch),
+        (66, CrunchExerciseName::StepClimb),
+        (67, CrunchExerciseName::WeightedStepClimb),
+        (68, CrunchExerciseName::SwissBallCrunch),
+        (69, CrunchExerciseName::SwissBallReverseCrunch),
+        (70, CrunchExerciseName::WeightedSwissBallReverseCrunch),
+        (71, CrunchExerciseName::SwissBallRussianTwist),
+        (72, CrunchExerciseName::WeightedSwissBallRussianTwist),
+        (73, CrunchExerciseName::SwissBallSideCrunch),
+        (74, CrunchExerciseName::WeightedSwissBallSideCrunch),
+        (75, CrunchExerciseName::ThoracicCrunchesOnFoamRoller),
+        (76, CrunchExerciseName::WeightedThoracicCrunchesOnFoamRoller),
+        (77, CrunchExerciseName::TricepsCrunch),
+        (78, CrunchExerciseName::WeightedBicycleCrunch),
+        (79, CrunchExerciseName::WeightedCrunch),
+        (80, CrunchExerciseName::WeightedSwissBallCrunch),
+        (81, CrunchExerciseName::ToesToBar),
+        (82, CrunchExerciseName::WeightedToesToBar),
+        (83, CrunchExerciseName::Crunch),
+    ],
+    names:   &[
+        "BicycleCrunch",
+        "CableCrunch",
+        "CircularArmCrunch",
+        "CrossedArmsCrunch",
+        "WeightedCrossedArmsCrunch",
+        "CrossLegReverseCrunch",
+        "WeightedCrossLegReverseCrunch",
+        "CrunchChop",
+        "WeightedCrunchChop",
+        "DoubleCrunch",
+        "WeightedDoubleCrunch",
+        "ElbowToKneeCrunch",
+        "WeightedElbowToKneeCrunch",
+        "FlutterKicks",
+        "WeightedFlutterKicks",
+        "FoamRollerReverseCrunchOnBench",
+        "WeightedFoamRollerReverseCrunchOnBench",
+        "FoamRollerReverseCrunchWithDumbbell",
+        "FoamRollerReverseCrunchWithMedicineBall",
+        "FrogPress",
+        "HangingKneeRaiseObliqueCrunch",
+        "WeightedHangingKneeRaiseObliqueCrunch",
+        "HipCrossover",
+        "WeightedHipCrossover",
+        "HollowRock",
+        "WeightedHollowRock",
+        "InclineReverseCrunch",
+        "WeightedInclineReverseCrunch",
+        "KneelingCableCrunch",
+        "KneelingCrossCrunch",
+        "WeightedKneelingCrossCrunch",
+        "KneelingObliqueCableCrunch",
+        "KneesToElbow",
+        "LegExtensions",
+        "WeightedLegExtensions",
+        "LegLevers",
+        "McgillCurlUp",
+        "WeightedMcgillCurlUp",
+        "ModifiedPilatesRollUpWithBall",
+        "WeightedModifiedPilatesRollUpWithBall",
+        "PilatesCrunch",
+        "WeightedPilatesCrunch",
+        "PilatesRollUpWithBall",
+        "WeightedPilatesRollUpWithBall",
+        "RaisedLegsCrunch",
+        "WeightedRaisedLegsCrunch",
+        "ReverseCrunch",
+        "WeightedReverseCrunch",
+        "ReverseCrunchOnABench",
+        "WeightedReverseCrunchOnABench",
+        "ReverseCurlAndLift",
+        "WeightedReverseCurlAndLift",
+        "RotationalLift",
+        "WeightedRotationalLift",
+        "SeatedAlternatingReverseCrunch",
+        "WeightedSeatedAlternatingReverseCrunch",
+        "SeatedLegU",
+        "WeightedSeatedLegU",
+        "SideToSideCrunchAndWeave",
+        "WeightedSideToSideCrunchAndWeave",
+        "SingleLegReverseCrunch",
+        "WeightedSingleLegReverseCrunch",
+        "SkaterCrunchCross",
+        "WeightedSkaterCrunchCross",
+        "StandingCableCrunch",
+        "StandingSideCrunch",
+        "StepClimb",
+        "WeightedStepClimb",
+        "SwissBallCrunch",
+        "SwissBallReverseCrunch",
+        "WeightedSwissBallReverseCrunch",
+        "SwissBallRussianTwist",
+        "WeightedSwissBallRussianTwist",
+        "SwissBallSideCrunch",
+        "WeightedSwissBallSideCrunch",
+        "ThoracicCrunchesOnFoamRoller",
+        "WeightedThoracicCrunchesOnFoamRoller",
+        "TricepsCrunch",
+        "WeightedBicycleCrunch",
+        "WeightedCrunch",
+        "WeightedSwissBallCrunch",
+        "ToesToBar",
+        "WeightedToesToBar",
+        "Crunch",
+    ],
+    by_name: &[
+        0, 1, 2, 5, 3, 83, 7, 9, 11, 13, 15, 17, 18, 19, 20, 22, 24, 26, 28,
+        29, 31, 32, 33, 35, 36, 38, 40, 42, 44, 46, 48, 50, 52, 54, 56, 58, 60,
+        62, 64, 65, 66, 68, 69, 71, 73, 75, 81, 77, 78, 6, 4, 79, 8, 10, 12,
+        14, 16, 21, 23, 25, 27, 30, 34, 37, 39, 41, 43, 45, 47, 49, 51, 53, 55,
+        57, 59, 61, 63, 67, 80, 70, 72, 74, 76, 82,
+    ],
+};
+encode_enum!(CrunchExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum CurlExerciseName {
     AlternatingDumbbellBicepsCurl = 0,
     AlternatingDumbbellBicepsCurlOnSwissBall = 1,
@@ -5942,7 +7379,8 @@ impl CurlExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(CurlExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum DeadliftExerciseName {
     BarbellDeadlift = 0,
     BarbellStraightLegDeadlift = 1,
@@ -5996,7 +7434,8 @@ impl DeadliftExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DeadliftExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum FlyeExerciseName {
     CableCrossover = 0,
     DeclineDumbbellFlye = 1,
@@ -6024,7 +7463,8 @@ impl FlyeExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(FlyeExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum HipRaiseExerciseName {
     BarbellHipThrustOnFloor = 0,
     BarbellHipThrustWithBench = 1,
@@ -6077,7 +7517,8 @@ impl HipRaiseExerciseName {
         match base_value . 0 { 0 => Ok ( HipRaiseExerciseName :: BarbellHipThrustOnFloor ) , 1 => Ok ( HipRaiseExerciseName :: BarbellHipThrustWithBench ) , 2 => Ok ( HipRaiseExerciseName :: BentKneeSwissBallReverseHipRaise ) , 3 => Ok ( HipRaiseExerciseName :: WeightedBentKneeSwissBallReverseHipRaise ) , 4 => Ok ( HipRaiseExerciseName :: BridgeWithLegExtension ) , 5 => Ok ( HipRaiseExerciseName :: WeightedBridgeWithLegExtension ) , 6 => Ok ( HipRaiseExerciseName :: ClamBridge ) , 7 => Ok ( HipRaiseExerciseName :: FrontKickTabletop ) , 8 => Ok ( HipRaiseExerciseName :: WeightedFrontKickTabletop ) , 9 => Ok ( HipRaiseExerciseName :: HipExtensionAndCross ) , 10 => Ok ( HipRaiseExerciseName :: WeightedHipExtensionAndCross ) , 11 => Ok ( HipRaiseExerciseName :: HipRaise ) , 12 => Ok ( HipRaiseExerciseName :: WeightedHipRaise ) , 13 => Ok ( HipRaiseExerciseName :: HipRaiseWithFeetOnSwissBall ) , 14 => Ok ( HipRaiseExerciseName :: WeightedHipRaiseWithFeetOnSwissBall ) , 15 => Ok ( HipRaiseExerciseName :: HipRaiseWithHeadOnBosuBall ) , 16 => Ok ( HipRaiseExerciseName :: WeightedHipRaiseWithHeadOnBosuBall ) , 17 => Ok ( HipRaiseExerciseName :: HipRaiseWithHeadOnSwissBall ) , 18 => Ok ( HipRaiseExerciseName :: WeightedHipRaiseWithHeadOnSwissBall ) , 19 => Ok ( HipRaiseExerciseName :: HipRaiseWithKneeSqueeze ) , 20 => Ok ( HipRaiseExerciseName :: WeightedHipRaiseWithKneeSqueeze ) , 21 => Ok ( HipRaiseExerciseName :: InclineRearLegExtension ) , 22 => Ok ( HipRaiseExerciseName :: WeightedInclineRearLegExtension ) , 23 => Ok ( HipRaiseExerciseName :: KettlebellSwing ) , 24 => Ok ( HipRaiseExerciseName :: MarchingHipRaise ) , 25 => Ok ( HipRaiseExerciseName :: WeightedMarchingHipRaise ) , 26 => Ok ( HipRaiseExerciseName :: MarchingHipRaiseWithFeetOnASwissBall ) , 27 => Ok ( HipRaiseExerciseName :: WeightedMarchingHipRaiseWithFeetOnASwissBall ) , 28 => Ok ( HipRaiseExerciseName :: ReverseHipRaise ) , 29 => Ok ( HipRaiseExerciseName :: WeightedReverseHipRaise ) , 30 => Ok ( HipRaiseExerciseName :: SingleLegHipRaise ) , 31 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaise ) , 32 => Ok ( HipRaiseExerciseName :: SingleLegHipRaiseWithFootOnBench ) , 33 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaiseWithFootOnBench ) , 34 => Ok ( HipRaiseExerciseName :: SingleLegHipRaiseWithFootOnBosuBall ) , 35 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaiseWithFootOnBosuBall ) , 36 => Ok ( HipRaiseExerciseName :: SingleLegHipRaiseWithFootOnFoamRoller ) , 37 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaiseWithFootOnFoamRoller ) , 38 => Ok ( HipRaiseExerciseName :: SingleLegHipRaiseWithFootOnMedicineBall ) , 39 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaiseWithFootOnMedicineBall ) , 40 => Ok ( HipRaiseExerciseName :: SingleLegHipRaiseWithHeadOnBosuBall ) , 41 => Ok ( HipRaiseExerciseName :: WeightedSingleLegHipRaiseWithHeadOnBosuBall ) , 42 => Ok ( HipRaiseExerciseName :: WeightedClamBridge ) , _ => Ok ( HipRaiseExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HipRaiseExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum HipStabilityExerciseName {
     BandSideLyingLegRaise = 0,
     DeadBug = 1,
@@ -6161,7 +7602,8 @@ impl HipStabilityExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HipStabilityExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum HipSwingExerciseName {
     SingleArmKettlebellSwing = 0,
     SingleArmDumbbellSwing = 1,
@@ -6179,7 +7621,8 @@ impl HipSwingExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HipSwingExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum HyperextensionExerciseName {
     BackExtensionWithOppositeArmAndLegReach = 0,
     WeightedBackExtensionWithOppositeArmAndLegReach = 1,
@@ -6226,7 +7669,8 @@ impl HyperextensionExerciseName {
         match base_value . 0 { 0 => Ok ( HyperextensionExerciseName :: BackExtensionWithOppositeArmAndLegReach ) , 1 => Ok ( HyperextensionExerciseName :: WeightedBackExtensionWithOppositeArmAndLegReach ) , 2 => Ok ( HyperextensionExerciseName :: BaseRotations ) , 3 => Ok ( HyperextensionExerciseName :: WeightedBaseRotations ) , 4 => Ok ( HyperextensionExerciseName :: BentKneeReverseHyperextension ) , 5 => Ok ( HyperextensionExerciseName :: WeightedBentKneeReverseHyperextension ) , 6 => Ok ( HyperextensionExerciseName :: HollowHoldAndRoll ) , 7 => Ok ( HyperextensionExerciseName :: WeightedHollowHoldAndRoll ) , 8 => Ok ( HyperextensionExerciseName :: Kicks ) , 9 => Ok ( HyperextensionExerciseName :: WeightedKicks ) , 10 => Ok ( HyperextensionExerciseName :: KneeRaises ) , 11 => Ok ( HyperextensionExerciseName :: WeightedKneeRaises ) , 12 => Ok ( HyperextensionExerciseName :: KneelingSuperman ) , 13 => Ok ( HyperextensionExerciseName :: WeightedKneelingSuperman ) , 14 => Ok ( HyperextensionExerciseName :: LatPullDownWithRow ) , 15 => Ok ( HyperextensionExerciseName :: MedicineBallDeadliftToReach ) , 16 => Ok ( HyperextensionExerciseName :: OneArmOneLegRow ) , 17 => Ok ( HyperextensionExerciseName :: OneArmRowWithBand ) , 18 => Ok ( HyperextensionExerciseName :: OverheadLungeWithMedicineBall ) , 19 => Ok ( HyperextensionExerciseName :: PlankKneeTucks ) , 20 => Ok ( HyperextensionExerciseName :: WeightedPlankKneeTucks ) , 21 => Ok ( HyperextensionExerciseName :: SideStep ) , 22 => Ok ( HyperextensionExerciseName :: WeightedSideStep ) , 23 => Ok ( HyperextensionExerciseName :: SingleLegBackExtension ) , 24 => Ok ( HyperextensionExerciseName :: WeightedSingleLegBackExtension ) , 25 => Ok ( HyperextensionExerciseName :: SpineExtension ) , 26 => Ok ( HyperextensionExerciseName :: WeightedSpineExtension ) , 27 => Ok ( HyperextensionExerciseName :: StaticBackExtension ) , 28 => Ok ( HyperextensionExerciseName :: WeightedStaticBackExtension ) , 29 => Ok ( HyperextensionExerciseName :: SupermanFromFloor ) , 30 => Ok ( HyperextensionExerciseName :: WeightedSupermanFromFloor ) , 31 => Ok ( HyperextensionExerciseName :: SwissBallBackExtension ) , 32 => Ok ( HyperextensionExerciseName :: WeightedSwissBallBackExtension ) , 33 => Ok ( HyperextensionExerciseName :: SwissBallHyperextension ) , 34 => Ok ( HyperextensionExerciseName :: WeightedSwissBallHyperextension ) , 35 => Ok ( HyperextensionExerciseName :: SwissBallOppositeArmAndLegLift ) , 36 => Ok ( HyperextensionExerciseName :: WeightedSwissBallOppositeArmAndLegLift ) , _ => Ok ( HyperextensionExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(HyperextensionExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum LateralRaiseExerciseName {
     FourtyFiveDegreeCableExternalRotation = 0,
     AlternatingLateralRaiseWithStaticHold = 1,
@@ -6306,7 +7750,8 @@ impl LateralRaiseExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LateralRaiseExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum LegCurlExerciseName {
     LegCurl = 0,
     WeightedLegCurl = 1,
@@ -6342,7 +7787,8 @@ impl LegCurlExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LegCurlExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum LegRaiseExerciseName {
     HangingKneeRaise = 0,
     HangingLegRaise = 1,
@@ -6398,7 +7844,8 @@ impl LegRaiseExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(LegRaiseExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum LungeExerciseName {
     OverheadLunge = 0,
     LungeMatrix = 1,
@@ -6486,10 +7933,34 @@ pub enum LungeExerciseName {
 impl LungeExerciseName {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value . 0 { 0 => Ok ( LungeExerciseName :: OverheadLunge ) , 1 => Ok ( LungeExerciseName :: LungeMatrix ) , 2 => Ok ( LungeExerciseName :: WeightedLungeMatrix ) , 3 => Ok ( LungeExerciseName :: AlternatingBarbellForwardLunge ) , 4 => Ok ( LungeExerciseName :: AlternatingDumbbellLungeWithReach ) , 5 => Ok ( LungeExerciseName :: BackFootElevatedDumbbellSplitSquat ) , 6 => Ok ( LungeExerciseName :: BarbellBoxLunge ) , 7 => Ok ( LungeExerciseName :: BarbellBulgarianSplitSquat ) , 8 => Ok ( LungeExerciseName :: BarbellCrossoverLunge ) , 9 => Ok ( LungeExerciseName :: BarbellFrontSplitSquat ) , 10 => Ok ( LungeExerciseName :: BarbellLunge ) , 11 => Ok ( LungeExerciseName :: BarbellReverseLunge ) , 12 => Ok ( LungeExerciseName :: BarbellSideLunge ) , 13 => Ok ( LungeExerciseName :: BarbellSplitSquat ) , 14 => Ok ( LungeExerciseName :: CoreControlRearLunge ) , 15 => Ok ( LungeExerciseName :: DiagonalLunge ) , 16 => Ok ( LungeExerciseName :: DropLunge ) , 17 => Ok ( LungeExerciseName :: DumbbellBoxLunge ) , 18 => Ok ( LungeExerciseName :: DumbbellBulgarianSplitSquat ) , 19 => Ok ( LungeExerciseName :: DumbbellCrossoverLunge ) , 20 => Ok ( LungeExerciseName :: DumbbellDiagonalLunge ) , 21 => Ok ( LungeExerciseName :: DumbbellLunge ) , 22 => Ok ( LungeExerciseName :: DumbbellLungeAndRotation ) , 23 => Ok ( LungeExerciseName :: DumbbellOverheadBulgarianSplitSquat ) , 24 => Ok ( LungeExerciseName :: DumbbellReverseLungeToHighKneeAndPress ) , 25 => Ok ( LungeExerciseName :: DumbbellSideLunge ) , 26 => Ok ( LungeExerciseName :: ElevatedFrontFootBarbellSplitSquat ) , 27 => Ok ( LungeExerciseName :: FrontFootElevatedDumbbellSplitSquat ) , 28 => Ok ( LungeExerciseName :: GunslingerLunge ) , 29 => Ok ( LungeExerciseName :: LawnmowerLunge ) , 30 => Ok ( LungeExerciseName :: LowLungeWithIsometricAdduction ) , 31 => Ok ( LungeExerciseName :: LowSideToSideLunge ) , 32 => Ok ( LungeExerciseName :: Lunge ) , 33 => Ok ( LungeExerciseName :: WeightedLunge ) , 34 => Ok ( LungeExerciseName :: LungeWithArmReach ) , 35 => Ok ( LungeExerciseName :: LungeWithDiagonalReach ) , 36 => Ok ( LungeExerciseName :: LungeWithSideBend ) , 37 => Ok ( LungeExerciseName :: OffsetDumbbellLunge ) , 38 => Ok ( LungeExerciseName :: OffsetDumbbellReverseLunge ) , 39 => Ok ( LungeExerciseName :: OverheadBulgarianSplitSquat ) , 40 => Ok ( LungeExerciseName :: OverheadDumbbellReverseLunge ) , 41 => Ok ( LungeExerciseName :: OverheadDumbbellSplitSquat ) , 42 => Ok ( LungeExerciseName :: OverheadLungeWithRotation ) , 43 => Ok ( LungeExerciseName :: ReverseBarbellBoxLunge ) , 44 => Ok ( LungeExerciseName :: ReverseBoxLunge ) , 45 => Ok ( LungeExerciseName :: ReverseDumbbellBoxLunge ) , 46 => Ok ( LungeExerciseName :: ReverseDumbbellCrossoverLunge ) , 47 => Ok ( LungeExerciseName :: ReverseDumbbellDiagonalLunge ) , 48 => Ok ( LungeExerciseName :: ReverseLungeWithReachBack ) , 49 => Ok ( LungeExerciseName :: WeightedReverseLungeWithReachBack ) , 50 => Ok ( LungeExerciseName :: ReverseLungeWithTwistAndOverheadReach ) , 51 => Ok ( LungeExerciseName :: WeightedReverseLungeWithTwistAndOverheadReach ) , 52 => Ok ( LungeExerciseName :: ReverseSlidingBoxLunge ) , 53 => Ok ( LungeExerciseName :: WeightedReverseSlidingBoxLunge ) , 54 => Ok ( LungeExerciseName :: ReverseSlidingLunge ) , 55 => Ok ( LungeExerciseName :: WeightedReverseSlidingLunge ) , 56 => Ok ( LungeExerciseName :: RunnersLungeToBalance ) , 57 => Ok ( LungeExerciseName :: WeightedRunnersLungeToBalance ) , 58 => Ok ( LungeExerciseName :: ShiftingSideLunge ) , 59 => Ok ( LungeExerciseName :: SideAndCrossoverLunge ) , 60 => Ok ( LungeExerciseName :: WeightedSideAndCrossoverLunge ) , 61 => Ok ( LungeExerciseName :: SideLunge ) , 62 => Ok ( LungeExerciseName :: WeightedSideLunge ) , 63 => Ok ( LungeExerciseName :: SideLungeAndPress ) , 64 => Ok ( LungeExerciseName :: SideLungeJumpOff ) , 65 => Ok ( LungeExerciseName :: SideLungeSweep ) , 66 => Ok ( LungeExerciseName :: WeightedSideLungeSweep ) , 67 => Ok ( LungeExerciseName :: SideLungeToCrossoverTap ) , 68 => Ok ( LungeExerciseName :: WeightedSideLungeToCrossoverTap ) , 69 => Ok ( LungeExerciseName :: SideToSideLungeChops ) , 70 => Ok ( LungeExerciseName :: WeightedSideToSideLungeChops ) , 71 => Ok ( LungeExerciseName :: SiffJumpLunge ) , 72 => Ok ( LungeExerciseName :: WeightedSiffJumpLunge ) , 73 => Ok ( LungeExerciseName :: SingleArmReverseLungeAndPress ) , 74 => Ok ( LungeExerciseName :: SlidingLateralLunge ) , 75 => Ok ( LungeExerciseName :: WeightedSlidingLateralLunge ) , 76 => Ok ( LungeExerciseName :: WalkingBarbellLunge ) , 77 => Ok ( LungeExerciseName :: WalkingDumbbellLunge ) , 78 => Ok ( LungeExerciseName :: WalkingLunge ) , 79 => Ok ( LungeExerciseName :: WeightedWalkingLunge ) , 80 => Ok ( LungeExerciseName :: WideGripOverheadBarbellSplitSquat ) , _ => Ok ( LungeExerciseName :: Unknown ) , }
+        Ok(LUNGE_EXERCISE_NAME
+            .get(base_value.0)
+            .unwrap_or(LungeExerciseName::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LungeExerciseName::Unknown => "Unknown",
+            _ => {
+                LUNGE_EXERCISE_NAME
+                    .name(self.clone() as u16)
+                    .unwrap_or("Unknown")
+            },
+        }
     }
 }
-#[derive(Debug,Clone)]
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for LungeExerciseName {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        LUNGE_EXERCISE_NAME.parse(name).ok_or(())
+    }
+}
+static LUNGE_EXERCISE_NAME : profile :: table :: EnumTable < u16 , LungeExerciseName > = profile :: table :: EnumTable { values : & [ ( 0 , LungeExerciseName :: OverheadLunge ) , ( 1 , LungeExerciseName :: LungeMatrix ) , ( 2 , LungeExerciseName :: WeightedLungeMatrix ) , ( 3 , LungeExerciseName :: AlternatingBarbellForwardLunge ) , ( 4 , LungeExerciseName :: AlternatingDumbbellLungeWithReach ) , ( 5 , LungeExerciseName :: BackFootElevatedDumbbellSplitSquat ) , ( 6 , LungeExerciseName :: BarbellBoxLunge ) , ( 7 , LungeExerciseName :: BarbellBulgarianSplitSquat ) , ( 8 , LungeExerciseName :: BarbellCrossoverLunge ) , ( 9 , LungeExerciseName :: BarbellFrontSplitSquat ) , ( 10 , LungeExerciseName :: BarbellLunge ) , ( 11 , LungeExerciseName :: BarbellReverseLunge ) , ( 12 , LungeExerciseName :: BarbellSideLunge ) , ( 13 , LungeExerciseName :: BarbellSplitSquat ) , ( 14 , LungeExerciseName :: CoreControlRearLunge ) , ( 15 , LungeExerciseName :: DiagonalLunge ) , ( 16 , LungeExerciseName :: DropLunge ) , ( 17 , LungeExerciseName :: DumbbellBoxLunge ) , ( 18 , LungeExerciseName :: DumbbellBulgarianSplitSquat ) , ( 19 , LungeExerciseName :: DumbbellCrossoverLunge ) , ( 20 , LungeExerciseName :: DumbbellDiagonalLunge ) , ( 21 , LungeExerciseName :: DumbbellLunge ) , ( 22 , LungeExerciseName :: DumbbellLungeAndRotation ) , ( 23 , LungeExerciseName :: DumbbellOverheadBulgarianSplitSquat ) , ( 24 , LungeExerciseName :: DumbbellReverseLungeToHighKneeAndPress ) , ( 25 , LungeExerciseName :: DumbbellSideLunge ) , ( 26 , LungeExerciseName :: ElevatedFrontFootBarbellSplitSquat ) , ( 27 , LungeExerciseName :: FrontFootElevatedDumbbellSplitSquat ) , ( 28 , LungeExerciseName :: GunslingerLunge ) , ( 29 , LungeExerciseName :: LawnmowerLunge ) , ( 30 , LungeExerciseName :: LowLungeWithIsometricAdduction ) , ( 31 , LungeExerciseName :: LowSideToSideLunge ) , ( 32 , LungeExerciseName :: Lunge ) , ( 33 , LungeExerciseName :: WeightedLunge ) , ( 34 , LungeExerciseName :: LungeWithArmReach ) , ( 35 , LungeExerciseName :: LungeWithDiagonalReach ) , ( 36 , LungeExerciseName :: LungeWithSideBend ) , ( 37 , LungeExerciseName :: OffsetDumbbellLunge ) , ( 38 , LungeExerciseName :: OffsetDumbbellReverseLunge ) , ( 39 , LungeExerciseName :: OverheadBulgarianSplitSquat ) , ( 40 , LungeExerciseName :: OverheadDumbbellReverseLunge ) , ( 41 , LungeExerciseName :: OverheadDumbbellSplitSquat ) , ( 42 , LungeExerciseName :: OverheadLungeWithRotation ) , ( 43 , LungeExerciseName :: ReverseBarbellBoxLunge ) , ( 44 , LungeExerciseName :: ReverseBoxLunge ) , ( 45 , LungeExerciseName :: ReverseDumbbellBoxLunge ) , ( 46 , LungeExerciseName :: ReverseDumbbellCrossoverLunge ) , ( 47 , LungeExerciseName :: ReverseDumbbellDiagonalLunge ) , ( 48 , LungeExerciseName :: ReverseLungeWithReachBack ) , ( 49 , LungeExerciseName :: WeightedReverseLungeWithReachBack ) , ( 50 , LungeExerciseName :: ReverseLungeWithTwistAndOverheadReach ) , ( 51 , LungeExerciseName :: WeightedReverseLungeWithTwistAndOverheadReach ) , ( 52 , LungeExerciseName :: ReverseSlidingBoxLunge ) , ( 53 , LungeExerciseName :: WeightedReverseSlidingBoxLunge ) , ( 54 , LungeExerciseName :: ReverseSlidingLunge ) , ( 55 , LungeExerciseName :: WeightedReverseSlidingLunge ) , ( 56 , LungeExerciseName :: RunnersLungeToBalance ) , ( 57 , LungeExerciseName :: WeightedRunnersLungeToBalance ) , ( 58 , LungeExerciseName :: ShiftingSideLunge ) , ( 59 , LungeExerciseName :: SideAndCrossoverLunge ) , ( 60 , LungeExerciseName :: WeightedSideAndCrossoverLunge ) , ( 61 , LungeExerciseName :: SideLunge ) , ( 62 , LungeExerciseName :: WeightedSideLunge ) , ( 63 , LungeExerciseName :: SideLungeAndPress ) , ( 64 , LungeExerciseName :: SideLungeJumpOff ) , ( 65 , LungeExerciseName :: SideLungeSweep ) , ( 66 , LungeExerciseName :: WeightedSideLungeSweep ) , ( 67 , LungeExerciseName :: SideLungeToCrossoverTap ) , ( 68 , LungeExerciseName :: WeightedSideLungeToCrossoverTap ) , ( 69 , LungeExerciseName :: SideToSideLungeChops ) , ( 70 , LungeExerciseName :: WeightedSideToSideLungeChops ) , ( 71 , LungeExerciseName :: SiffJumpLunge ) , ( 72 , LungeExerciseName :: WeightedSiffJumpLunge ) , ( 73 , LungeExerciseName :: SingleArmReverseLungeAndPress ) , ( 74 , LungeExerciseName :: SlidingLateralLunge ) , ( 75 , LungeExerciseName :: WeightedSlidingLateralLunge ) , ( 76 , LungeExerciseName :: WalkingBarbellLunge ) , ( 77 , LungeExerciseName :: WalkingDumbbellLunge ) , ( 78 , LungeExerciseName :: WalkingLunge ) , ( 79 , LungeExerciseName :: WeightedWalkingLunge ) , ( 80 , LungeExerciseName :: WideGripOverheadBarbellSplitSquat ) , ] , names : & [ "OverheadLunge" , "LungeMatrix" , "WeightedLungeMatrix" , "AlternatingBarbellForwardLunge" , "AlternatingDumbbellLungeWithReach" , "BackFootElevatedDumbbellSplitSquat" , "BarbellBoxLunge" , "BarbellBulgarianSplitSquat" , "BarbellCrossoverLunge" , "BarbellFrontSplitSquat" , "BarbellLunge" , "BarbellReverseLunge" , "BarbellSideLunge" , "BarbellSplitSquat" , "CoreControlRearLunge" , "DiagonalLunge" , "DropLunge" , "DumbbellBoxLunge" , "DumbbellBulgarianSplitSquat" , "DumbbellCrossoverLunge" , "DumbbellDiagonalLunge" , "DumbbellLunge" , "DumbbellLungeAndRotation" , "DumbbellOverheadBulgarianSplitSquat" , "DumbbellReverseLungeToHighKneeAndPress" , "DumbbellSideLunge" , "ElevatedFrontFootBarbellSplitSquat" , "FrontFootElevatedDumbbellSplitSquat" , "GunslingerLunge" , "LawnmowerLunge" , "LowLungeWithIsometricAdduction" , "LowSideToSideLunge" , "Lunge" , "WeightedLunge" , "LungeWithArmReach" , "LungeWithDiagonalReach" , "LungeWithSideBend" , "OffsetDumbbellLunge" , "OffsetDumbbellReverseLunge" , "OverheadBulgarianSplitSquat" , "OverheadDumbbellReverseLunge" , "OverheadDumbbellSplitSquat" , "OverheadLungeWithRotation" , "ReverseBarbellBoxLunge" , "ReverseBoxLunge" , "ReverseDumbbellBoxLunge" , "ReverseDumbbellCrossoverLunge" , "ReverseDumbbellDiagonalLunge" , "ReverseLungeWithReachBack" , "WeightedReverseLungeWithReachBack" , "ReverseLungeWithTwistAndOverheadReach" , "WeightedReverseLungeWithTwistAndOverheadReach" , "ReverseSlidingBoxLunge" , "WeightedReverseSlidingBoxLunge" , "ReverseSlidingLunge" , "WeightedReverseSlidingLunge" , "RunnersLungeToBalance" , "WeightedRunnersLungeToBalance" , "ShiftingSideLunge" , "SideAndCrossoverLunge" , "WeightedSideAndCrossoverLunge" , "SideLunge" , "WeightedSideLunge" , "SideLungeAndPress" , "SideLungeJumpOff" , "SideLungeSweep" , "WeightedSideLungeSweep" , "SideLungeToCrossoverTap" , "WeightedSideLungeToCrossoverTap" , "SideToSideLungeChops" , "WeightedSideToSideLungeChops" , "SiffJumpLunge" , "WeightedSiffJumpLunge" , "SingleArmReverseLungeAndPress" , "SlidingLateralLunge" , "WeightedSlidingLateralLunge" , "WalkingBarbellLunge" , "WalkingDumbbellLunge" , "WalkingLunge" , "WeightedWalkingLunge" , "WideGripOverheadBarbellSplitSquat" , ] , by_name : & [ 3 , 4 , 5 , 6 , 7 , 8 , 9 , 10 , 11 , 12 , 13 , 14 , 15 , 16 , 17 , 18 , 19 , 20 , 21 , 22 , 23 , 24 , 25 , 26 , 27 , 28 , 29 , 30 , 31 , 32 , 1 , 34 , 35 , 36 , 37 , 38 , 39 , 40 , 41 , 0 , 42 , 43 , 44 , 45 , 46 , 47 , 48 , 50 , 52 , 54 , 56 , 58 , 59 , 61 , 63 , 64 , 65 , 67 , 69 , 71 , 73 , 74 , 76 , 77 , 78 , 33 , 2 , 49 , 51 , 53 , 55 , 57 , 60 , 62 , 66 , 68 , 70 , 72 , 75 , 79 , 80 , ] , } ;
+encode_enum!(LungeExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum OlympicLiftExerciseName {
     BarbellHangPowerClean = 0,
     BarbellHangSquatClean = 1,
@@ -6543,7 +8014,8 @@ impl OlympicLiftExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(OlympicLiftExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum PlankExerciseName {
     FourtyFiveDegreePlank = 0,
     Weighted45DegreePlank = 1,
@@ -6678,10 +8150,34 @@ pub enum PlankExerciseName {
 impl PlankExerciseName {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value . 0 { 0 => Ok ( PlankExerciseName :: FourtyFiveDegreePlank ) , 1 => Ok ( PlankExerciseName :: Weighted45DegreePlank ) , 2 => Ok ( PlankExerciseName :: NinetyDegreeStaticHold ) , 3 => Ok ( PlankExerciseName :: Weighted90DegreeStaticHold ) , 4 => Ok ( PlankExerciseName :: BearCrawl ) , 5 => Ok ( PlankExerciseName :: WeightedBearCrawl ) , 6 => Ok ( PlankExerciseName :: CrossBodyMountainClimber ) , 7 => Ok ( PlankExerciseName :: WeightedCrossBodyMountainClimber ) , 8 => Ok ( PlankExerciseName :: ElbowPlankPikeJacks ) , 9 => Ok ( PlankExerciseName :: WeightedElbowPlankPikeJacks ) , 10 => Ok ( PlankExerciseName :: ElevatedFeetPlank ) , 11 => Ok ( PlankExerciseName :: WeightedElevatedFeetPlank ) , 12 => Ok ( PlankExerciseName :: ElevatorAbs ) , 13 => Ok ( PlankExerciseName :: WeightedElevatorAbs ) , 14 => Ok ( PlankExerciseName :: ExtendedPlank ) , 15 => Ok ( PlankExerciseName :: WeightedExtendedPlank ) , 16 => Ok ( PlankExerciseName :: FullPlankPasseTwist ) , 17 => Ok ( PlankExerciseName :: WeightedFullPlankPasseTwist ) , 18 => Ok ( PlankExerciseName :: InchingElbowPlank ) , 19 => Ok ( PlankExerciseName :: WeightedInchingElbowPlank ) , 20 => Ok ( PlankExerciseName :: InchwormToSidePlank ) , 21 => Ok ( PlankExerciseName :: WeightedInchwormToSidePlank ) , 22 => Ok ( PlankExerciseName :: KneelingPlank ) , 23 => Ok ( PlankExerciseName :: WeightedKneelingPlank ) , 24 => Ok ( PlankExerciseName :: KneelingSidePlankWithLegLift ) , 25 => Ok ( PlankExerciseName :: WeightedKneelingSidePlankWithLegLift ) , 26 => Ok ( PlankExerciseName :: LateralRoll ) , 27 => Ok ( PlankExerciseName :: WeightedLateralRoll ) , 28 => Ok ( PlankExerciseName :: LyingReversePlank ) , 29 => Ok ( PlankExerciseName :: WeightedLyingReversePlank ) , 30 => Ok ( PlankExerciseName :: MedicineBallMountainClimber ) , 31 => Ok ( PlankExerciseName :: WeightedMedicineBallMountainClimber ) , 32 => Ok ( PlankExerciseName :: ModifiedMountainClimberAndExtension ) , 33 => Ok ( PlankExerciseName :: WeightedModifiedMountainClimberAndExtension ) , 34 => Ok ( PlankExerciseName :: MountainClimber ) , 35 => Ok ( PlankExerciseName :: WeightedMountainClimber ) , 36 => Ok ( PlankExerciseName :: MountainClimberOnSlidingDiscs ) , 37 => Ok ( PlankExerciseName :: WeightedMountainClimberOnSlidingDiscs ) , 38 => Ok ( PlankExerciseName :: MountainClimberWithFeetOnBosuBall ) , 39 => Ok ( PlankExerciseName :: WeightedMountainClimberWithFeetOnBosuBall ) , 40 => Ok ( PlankExerciseName :: MountainClimberWithHandsOnBench ) , 41 => Ok ( PlankExerciseName :: MountainClimberWithHandsOnSwissBall ) , 42 => Ok ( PlankExerciseName :: WeightedMountainClimberWithHandsOnSwissBall ) , 43 => Ok ( PlankExerciseName :: Plank ) , 44 => Ok ( PlankExerciseName :: PlankJacksWithFeetOnSlidingDiscs ) , 45 => Ok ( PlankExerciseName :: WeightedPlankJacksWithFeetOnSlidingDiscs ) , 46 => Ok ( PlankExerciseName :: PlankKneeTwist ) , 47 => Ok ( PlankExerciseName :: WeightedPlankKneeTwist ) , 48 => Ok ( PlankExerciseName :: PlankPikeJumps ) , 49 => Ok ( PlankExerciseName :: WeightedPlankPikeJumps ) , 50 => Ok ( PlankExerciseName :: PlankPikes ) , 51 => Ok ( PlankExerciseName :: WeightedPlankPikes ) , 52 => Ok ( PlankExerciseName :: PlankToStandUp ) , 53 => Ok ( PlankExerciseName :: WeightedPlankToStandUp ) , 54 => Ok ( PlankExerciseName :: PlankWithArmRaise ) , 55 => Ok ( PlankExerciseName :: WeightedPlankWithArmRaise ) , 56 => Ok ( PlankExerciseName :: PlankWithKneeToElbow ) , 57 => Ok ( PlankExerciseName :: WeightedPlankWithKneeToElbow ) , 58 => Ok ( PlankExerciseName :: PlankWithObliqueCrunch ) , 59 => Ok ( PlankExerciseName :: WeightedPlankWithObliqueCrunch ) , 60 => Ok ( PlankExerciseName :: PlyometricSidePlank ) , 61 => Ok ( PlankExerciseName :: WeightedPlyometricSidePlank ) , 62 => Ok ( PlankExerciseName :: RollingSidePlank ) , 63 => Ok ( PlankExerciseName :: WeightedRollingSidePlank ) , 64 => Ok ( PlankExerciseName :: SideKickPlank ) , 65 => Ok ( PlankExerciseName :: WeightedSideKickPlank ) , 66 => Ok ( PlankExerciseName :: SidePlank ) , 67 => Ok ( PlankExerciseName :: WeightedSidePlank ) , 68 => Ok ( PlankExerciseName :: SidePlankAndRow ) , 69 => Ok ( PlankExerciseName :: WeightedSidePlankAndRow ) , 70 => Ok ( PlankExerciseName :: SidePlankLift ) , 71 => Ok ( PlankExerciseName :: WeightedSidePlankLift ) , 72 => Ok ( PlankExerciseName :: SidePlankWithElbowOnBosuBall ) , 73 => Ok ( PlankExerciseName :: WeightedSidePlankWithElbowOnBosuBall ) , 74 => Ok ( PlankExerciseName :: SidePlankWithFeetOnBench ) , 75 => Ok ( PlankExerciseName :: WeightedSidePlankWithFeetOnBench ) , 76 => Ok ( PlankExerciseName :: SidePlankWithKneeCircle ) , 77 => Ok ( PlankExerciseName :: WeightedSidePlankWithKneeCircle ) , 78 => Ok ( PlankExerciseName :: SidePlankWithKneeTuck ) , 79 => Ok ( PlankExerciseName :: WeightedSidePlankWithKneeTuck ) , 80 => Ok ( PlankExerciseName :: SidePlankWithLegLift ) , 81 => Ok ( PlankExerciseName :: WeightedSidePlankWithLegLift ) , 82 => Ok ( PlankExerciseName :: SidePlankWithReachUnder ) , 83 => Ok ( PlankExerciseName :: WeightedSidePlankWithReachUnder ) , 84 => Ok ( PlankExerciseName :: SingleLegElevatedFeetPlank ) , 85 => Ok ( PlankExerciseName :: WeightedSingleLegElevatedFeetPlank ) , 86 => Ok ( PlankExerciseName :: SingleLegFlexAndExtend ) , 87 => Ok ( PlankExerciseName :: WeightedSingleLegFlexAndExtend ) , 88 => Ok ( PlankExerciseName :: SingleLegSidePlank ) , 89 => Ok ( PlankExerciseName :: WeightedSingleLegSidePlank ) , 90 => Ok ( PlankExerciseName :: SpidermanPlank ) , 91 => Ok ( PlankExerciseName :: WeightedSpidermanPlank ) , 92 => Ok ( PlankExerciseName :: StraightArmPlank ) , 93 => Ok ( PlankExerciseName :: WeightedStraightArmPlank ) , 94 => Ok ( PlankExerciseName :: StraightArmPlankWithShoulderTouch ) , 95 => Ok ( PlankExerciseName :: WeightedStraightArmPlankWithShoulderTouch ) , 96 => Ok ( PlankExerciseName :: SwissBallPlank ) , 97 => Ok ( PlankExerciseName :: WeightedSwissBallPlank ) , 98 => Ok ( PlankExerciseName :: SwissBallPlankLegLift ) , 99 => Ok ( PlankExerciseName :: WeightedSwissBallPlankLegLift ) , 100 => Ok ( PlankExerciseName :: SwissBallPlankLegLiftAndHold ) , 101 => Ok ( PlankExerciseName :: SwissBallPlankWithFeetOnBench ) , 102 => Ok ( PlankExerciseName :: WeightedSwissBallPlankWithFeetOnBench ) , 103 => Ok ( PlankExerciseName :: SwissBallProneJackknife ) , 104 => Ok ( PlankExerciseName :: WeightedSwissBallProneJackknife ) , 105 => Ok ( PlankExerciseName :: SwissBallSidePlank ) , 106 => Ok ( PlankExerciseName :: WeightedSwissBallSidePlank ) , 107 => Ok ( PlankExerciseName :: ThreeWayPlank ) , 108 => Ok ( PlankExerciseName :: WeightedThreeWayPlank ) , 109 => Ok ( PlankExerciseName :: TowelPlankAndKneeIn ) , 110 => Ok ( PlankExerciseName :: WeightedTowelPlankAndKneeIn ) , 111 => Ok ( PlankExerciseName :: TStabilization ) , 112 => Ok ( PlankExerciseName :: WeightedTStabilization ) , 113 => Ok ( PlankExerciseName :: TurkishGetUpToSidePlank ) , 114 => Ok ( PlankExerciseName :: WeightedTurkishGetUpToSidePlank ) , 115 => Ok ( PlankExerciseName :: TwoPointPlank ) , 116 => Ok ( PlankExerciseName :: WeightedTwoPointPlank ) , 117 => Ok ( PlankExerciseName :: WeightedPlank ) , 118 => Ok ( PlankExerciseName :: WideStancePlankWithDiagonalArmLift ) , 119 => Ok ( PlankExerciseName :: WeightedWideStancePlankWithDiagonalArmLift ) , 120 => Ok ( PlankExerciseName :: WideStancePlankWithDiagonalLegLift ) , 121 => Ok ( PlankExerciseName :: WeightedWideStancePlankWithDiagonalLegLift ) , 122 => Ok ( PlankExerciseName :: WideStancePlankWithLegLift ) , 123 => Ok ( PlankExerciseName :: WeightedWideStancePlankWithLegLift ) , 124 => Ok ( PlankExerciseName :: WideStancePlankWithOppositeArmAndLegLift ) , 125 => Ok ( PlankExerciseName :: WeightedMountainClimberWithHandsOnBench ) , 126 => Ok ( PlankExerciseName :: WeightedSwissBallPlankLegLiftAndHold ) , 127 => Ok ( PlankExerciseName :: WeightedWideStancePlankWithOppositeArmAndLegLift ) , _ => Ok ( PlankExerciseName :: Unknown ) , }
+        Ok(PLANK_EXERCISE_NAME
+            .get(base_value.0)
+            .unwrap_or(PlankExerciseName::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PlankExerciseName::Unknown => "Unknown",
+            _ => {
+                PLANK_EXERCISE_NAME
+                    .name(self.clone() as u16)
+                    .unwrap_or("Unknown")
+            },
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for PlankExerciseName {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        PLANK_EXERCISE_NAME.parse(name).ok_or(())
     }
 }
-#[derive(Debug,Clone)]
+static PLANK_EXERCISE_NAME : profile :: table :: EnumTable < u16 , PlankExerciseName > = profile :: table :: EnumTable { values : & [ ( 0 , PlankExerciseName :: FourtyFiveDegreePlank ) , ( 1 , PlankExerciseName :: Weighted45DegreePlank ) , ( 2 , PlankExerciseName :: NinetyDegreeStaticHold ) , ( 3 , PlankExerciseName :: Weighted90DegreeStaticHold ) , ( 4 , PlankExerciseName :: BearCrawl ) , ( 5 , PlankExerciseName :: WeightedBearCrawl ) , ( 6 , PlankExerciseName :: CrossBodyMountainClimber ) , ( 7 , PlankExerciseName :: WeightedCrossBodyMountainClimber ) , ( 8 , PlankExerciseName :: ElbowPlankPikeJacks ) , ( 9 , PlankExerciseName :: WeightedElbowPlankPikeJacks ) , ( 10 , PlankExerciseName :: ElevatedFeetPlank ) , ( 11 , PlankExerciseName :: WeightedElevatedFeetPlank ) , ( 12 , PlankExerciseName :: ElevatorAbs ) , ( 13 , PlankExerciseName :: WeightedElevatorAbs ) , ( 14 , PlankExerciseName :: ExtendedPlank ) , ( 15 , PlankExerciseName :: WeightedExtendedPlank ) , ( 16 , PlankExerciseName :: FullPlankPasseTwist ) , ( 17 , PlankExerciseName :: WeightedFullPlankPasseTwist ) , ( 18 , PlankExerciseName :: InchingElbowPlank ) , ( 19 , PlankExerciseName :: WeightedInchingElbowPlank ) , ( 20 , PlankExerciseName :: InchwormToSidePlank ) , ( 21 , PlankExerciseName :: WeightedInchwormToSidePlank ) , ( 22 , PlankExerciseName :: KneelingPlank ) , ( 23 , PlankExerciseName :: WeightedKneelingPlank ) , ( 24 , PlankExerciseName :: KneelingSidePlankWithLegLift ) , ( 25 , PlankExerciseName :: WeightedKneelingSidePlankWithLegLift ) , ( 26 , PlankExerciseName :: LateralRoll ) , ( 27 , PlankExerciseName :: WeightedLateralRoll ) , ( 28 , PlankExerciseName :: LyingReversePlank ) , ( 29 , PlankExerciseName :: WeightedLyingReversePlank ) , ( 30 , PlankExerciseName :: MedicineBallMountainClimber ) , ( 31 , PlankExerciseName :: WeightedMedicineBallMountainClimber ) , ( 32 , PlankExerciseName :: ModifiedMountainClimberAndExtension ) , ( 33 , PlankExerciseName :: WeightedModifiedMountainClimberAndExtension ) , ( 34 , PlankExerciseName :: MountainClimber ) , ( 35 , PlankExerciseName :: WeightedMountainClimber ) , ( 36 , PlankExerciseName :: MountainClimberOnSlidingDiscs ) , ( 37 , PlankExerciseName :: WeightedMountainClimberOnSlidingDiscs ) , ( 38 , PlankExerciseName :: MountainClimberWithFeetOnBosuBall ) , ( 39 , PlankExerciseName :: WeightedMountainClimberWithFeetOnBosuBall ) , ( 40 , PlankExerciseName :: MountainClimberWithHandsOnBench ) , ( 41 , PlankExerciseName :: MountainClimberWithHandsOnSwissBall ) , ( 42 , PlankExerciseName :: WeightedMountainClimberWithHandsOnSwissBall ) , ( 43 , PlankExerciseName :: Plank ) , ( 44 , PlankExerciseName :: PlankJacksWithFeetOnSlidingDiscs ) , ( 45 , PlankExerciseName :: WeightedPlankJacksWithFeetOnSlidingDiscs ) , ( 46 , PlankExerciseName :: PlankKneeTwist ) , ( 47 , PlankExerciseName :: WeightedPlankKneeTwist ) , ( 48 , PlankExerciseName :: PlankPikeJumps ) , ( 49 , PlankExerciseName :: WeightedPlankPikeJumps ) , ( 50 , PlankExerciseName :: PlankPikes ) , ( 51 , PlankExerciseName :: WeightedPlankPikes ) , ( 52 , PlankExerciseName :: PlankToStandUp ) , ( 53 , PlankExerciseName :: WeightedPlankToStandUp ) , ( 54 , PlankExerciseName :: PlankWithArmRaise ) , ( 55 , PlankExerciseName :: WeightedPlankWithArmRaise ) , ( 56 , PlankExerciseName :: PlankWithKneeToElbow ) , ( 57 , PlankExerciseName :: WeightedPlankWithKneeToElbow ) , ( 58 , PlankExerciseName :: PlankWithObliqueCrunch ) , ( 59 , PlankExerciseName :: WeightedPlankWithObliqueCrunch ) , ( 60 , PlankExerciseName :: PlyometricSidePlank ) , ( 61 , PlankExerciseName :: WeightedPlyometricSidePlank ) , ( 62 , PlankExerciseName :: RollingSidePlank ) , ( 63 , PlankExerciseName :: WeightedRollingSidePlank ) , ( 64 , PlankExerciseName :: SideKickPlank ) , ( 65 , PlankExerciseName :: WeightedSideKickPlank ) , ( 66 , PlankExerciseName :: SidePlank ) , ( 67 , PlankExerciseName :: WeightedSidePlank ) , ( 68 , PlankExerciseName :: SidePlankAndRow ) , ( 69 , PlankExerciseName :: WeightedSidePlankAndRow ) , ( 70 , PlankExerciseName :: SidePlankLift ) , ( 71 , PlankExerciseName :: WeightedSidePlankLift ) , ( 72 , PlankExerciseName :: SidePlankWithElbowOnBosuBall ) , ( 73 , PlankExerciseName :: WeightedSidePlankWithElbowOnBosuBall ) , ( 74 , PlankExerciseName :: SidePlankWithFeetOnBench ) , ( 75 , PlankExerciseName :: WeightedSidePlankWithFeetOnBench ) , ( 76 , PlankExerciseName :: SidePlankWithKneeCircle ) , ( 77 , PlankExerciseName :: WeightedSidePlankWithKneeCircle ) , ( 78 , PlankExerciseName :: SidePlankWithKneeTuck ) , ( 79 , PlankExerciseName :: WeightedSidePlankWithKneeTuck ) , ( 80 , PlankExerciseName :: SidePlankWithLegLift ) , ( 81 , PlankExerciseName :: WeightedSidePlankWithLegLift ) , ( 82 , PlankExerciseName :: SidePlankWithReachUnder ) , ( 83 , PlankExerciseName :: WeightedSidePlankWithReachUnder ) , ( 84 , PlankExerciseName :: SingleLegElevatedFeetPlank ) , ( 85 , PlankExerciseName :: WeightedSingleLegElevatedFeetPlank ) , ( 86 , PlankExerciseName :: SingleLegFlexAndExtend ) , ( 87 , PlankExerciseName :: WeightedSingleLegFlexAndExtend ) , ( 88 , PlankExerciseName :: SingleLegSidePlank ) , ( 89 , PlankExerciseName :: WeightedSingleLegSidePlank ) , ( 90 , PlankExerciseName :: SpidermanPlank ) , ( 91 , PlankExerciseName :: WeightedSpidermanPlank ) , ( 92 , PlankExerciseName :: StraightArmPlank ) , ( 93 , PlankExerciseName :: WeightedStraightArmPlank ) , ( 94 , PlankExerciseName :: StraightArmPlankWithShoulderTouch ) , ( 95 , PlankExerciseName :: WeightedStraightArmPlankWithShoulderTouch ) , ( 96 , PlankExerciseName :: SwissBallPlank ) , ( 97 , PlankExerciseName :: WeightedSwissBallPlank ) , ( 98 , PlankExerciseName :: SwissBallPlankLegLift ) , ( 99 , PlankExerciseName :: WeightedSwissBallPlankLegLift ) , ( 100 , PlankExerciseName :: SwissBallPlankLegLiftAndHold ) , ( 101 , PlankExerciseName :: SwissBallPlankWithFeetOnBench ) , ( 102 , PlankExerciseName :: WeightedSwissBallPlankWithFeetOnBench ) , ( 103 , PlankExerciseName :: SwissBallProneJackknife ) , ( 104 , PlankExerciseName :: WeightedSwissBallProneJackknife ) , ( 105 , PlankExerciseName :: SwissBallSidePlank ) , ( 106 , PlankExerciseName :: WeightedSwissBallSidePlank ) , ( 107 , PlankExerciseName :: ThreeWayPlank ) , ( 108 , PlankExerciseName :: WeightedThreeWayPlank ) , ( 109 , PlankExerciseName :: TowelPlankAndKneeIn ) , ( 110 , PlankExerciseName :: WeightedTowelPlankAndKneeIn ) , ( 111 , PlankExerciseName :: TStabilization ) , ( 112 , PlankExerciseName :: WeightedTStabilization ) , ( 113 , PlankExerciseName :: TurkishGetUpToSidePlank ) , ( 114 , PlankExerciseName :: WeightedTurkishGetUpToSidePlank ) , ( 115 , PlankExerciseName :: TwoPointPlank ) , ( 116 , PlankExerciseName :: WeightedTwoPointPlank ) , ( 117 , PlankExerciseName :: WeightedPlank ) , ( 118 , PlankExerciseName :: WideStancePlankWithDiagonalArmLift ) , ( 119 , PlankExerciseName :: WeightedWideStancePlankWithDiagonalArmLift ) , ( 120 , PlankExerciseName :: WideStancePlankWithDiagonalLegLift ) , ( 121 , PlankExerciseName :: WeightedWideStancePlankWithDiagonalLegLift ) , ( 122 , PlankExerciseName :: WideStancePlankWithLegLift ) , ( 123 , PlankExerciseName :: WeightedWideStancePlankWithLegLift ) , ( 124 , PlankExerciseName :: WideStancePlankWithOppositeArmAndLegLift ) , ( 125 , PlankExerciseName :: WeightedMountainClimberWithHandsOnBench ) , ( 126 , PlankExerciseName :: WeightedSwissBallPlankLegLiftAndHold ) , ( 127 , PlankExerciseName :: WeightedWideStancePlankWithOppositeArmAndLegLift ) , ] , names : & [ "FourtyFiveDegreePlank" , "Weighted45DegreePlank" , "NinetyDegreeStaticHold" , "Weighted90DegreeStaticHold" , "BearCrawl" , "WeightedBearCrawl" , "CrossBodyMountainClimber" , "WeightedCrossBodyMountainClimber" , "ElbowPlankPikeJacks" , "WeightedElbowPlankPikeJacks" , "ElevatedFeetPlank" , "WeightedElevatedFeetPlank" , "ElevatorAbs" , "WeightedElevatorAbs" , "ExtendedPlank" , "WeightedExtendedPlank" , "FullPlankPasseTwist" , "WeightedFullPlankPasseTwist" , "InchingElbowPlank" , "WeightedInchingElbowPlank" , "InchwormToSidePlank" , "WeightedInchwormToSidePlank" , "KneelingPlank" , "WeightedKneelingPlank" , "KneelingSidePlankWithLegLift" , "WeightedKneelingSidePlankWithLegLift" , "LateralRoll" , "WeightedLateralRoll" , "LyingReversePlank" , "WeightedLyingReversePlank" , "MedicineBallMountainClimber" , "WeightedMedicineBallMountainClimber" , "ModifiedMountainClimberAndExtension" , "WeightedModifiedMountainClimberAndExtension" , "MountainClimber" , "WeightedMountainClimber" , "MountainClimberOnSlidingDiscs" , "WeightedMountainClimberOnSlidingDiscs" , "MountainClimberWithFeetOnBosuBall" , "WeightedMountainClimberWithFeetOnBosuBall" , "MountainClimberWithHandsOnBench" , "MountainClimberWithHandsOnSwissBall" , "WeightedMountainClimberWithHandsOnSwissBall" , "Plank" , "PlankJacksWithFeetOnSlidingDiscs" , "WeightedPlankJacksWithFeetOnSlidingDiscs" , "PlankKneeTwist" , "WeightedPlankKneeTwist" , "PlankPikeJumps" , "WeightedPlankPikeJumps" , "PlankPikes" , "WeightedPlankPikes" , "PlankToStandUp" , "WeightedPlankToStandUp" , "PlankWithArmRaise" , "WeightedPlankWithArmRaise" , "PlankWithKneeToElbow" , "WeightedPlankWithKneeToElbow" , "PlankWithObliqueCrunch" , "WeightedPlankWithObliqueCrunch" , "PlyometricSidePlank" , "WeightedPlyometricSidePlank" , "RollingSidePlank" , "WeightedRollingSidePlank" , "SideKickPlank" , "WeightedSideKickPlank" , "SidePlank" , "WeightedSidePlank" , "SidePlankAndRow" , "WeightedSidePlankAndRow" , "SidePlankLift" , "WeightedSidePlankLift" , "SidePlankWithElbowOnBosuBall" , "WeightedSidePlankWithElbowOnBosuBall" , "SidePlankWithFeetOnBench" , "WeightedSidePlankWithFeetOnBench" , "SidePlankWithKneeCircle" , "WeightedSidePlankWithKneeCircle" , "SidePlankWithKneeTuck" , "WeightedSidePlankWithKneeTuck" , "SidePlankWithLegLift" , "WeightedSidePlankWithLegLift" , "SidePlankWithReachUnder" , "WeightedSidePlankWithReachUnder" , "SingleLegElevatedFeetPlank" , "WeightedSingleLegElevatedFeetPlank" , "SingleLegFlexAndExtend" , "WeightedSingleLegFlexAndExtend" , "SingleLegSidePlank" , "WeightedSingleLegSidePlank" , "SpidermanPlank" , "WeightedSpidermanPlank" , "StraightArmPlank" , "WeightedStraightArmPlank" , "StraightArmPlankWithShoulderTouch" , "WeightedStraightArmPlankWithShoulderTouch" , "SwissBallPlank" , "WeightedSwissBallPlank" , "SwissBallPlankLegLift" , "WeightedSwissBallPlankLegLift" , "SwissBallPlankLegLiftAndHold" , "SwissBallPlankWithFeetOnBench" , "WeightedSwissBallPlankWithFeetOnBench" , "SwissBallProneJackknife" , "WeightedSwissBallProneJackknife" , "SwissBallSidePlank" , "WeightedSwissBallSidePlank" , "ThreeWayPlank" , "WeightedThreeWayPlank" , "TowelPlankAndKneeIn" , "WeightedTowelPlankAndKneeIn" , "TStabilization" , "WeightedTStabilization" , "TurkishGetUpToSidePlank" , "WeightedTurkishGetUpToSidePlank" , "TwoPointPlank" , "WeightedTwoPointPlank" , "WeightedPlank" , "WideStancePlankWithDiagonalArmLift" , "WeightedWideStancePlankWithDiagonalArmLift" , "WideStancePlankWithDiagonalLegLift" , "WeightedWideStancePlankWithDiagonalLegLift" , "WideStancePlankWithLegLift" , "WeightedWideStancePlankWithLegLift" , "WideStancePlankWithOppositeArmAndLegLift" , "WeightedMountainClimberWithHandsOnBench" , "WeightedSwissBallPlankLegLiftAndHold" , "WeightedWideStancePlankWithOppositeArmAndLegLift" , ] , by_name : & [ 4 , 6 , 8 , 10 , 12 , 14 , 0 , 16 , 18 , 20 , 22 , 24 , 26 , 28 , 30 , 32 , 34 , 36 , 38 , 40 , 41 , 2 , 43 , 44 , 46 , 48 , 50 , 52 , 54 , 56 , 58 , 60 , 62 , 64 , 66 , 68 , 70 , 72 , 74 , 76 , 78 , 80 , 82 , 84 , 86 , 88 , 90 , 92 , 94 , 96 , 98 , 100 , 101 , 103 , 105 , 111 , 107 , 109 , 113 , 115 , 1 , 3 , 5 , 7 , 9 , 11 , 13 , 15 , 17 , 19 , 21 , 23 , 25 , 27 , 29 , 31 , 33 , 35 , 37 , 39 , 125 , 42 , 117 , 45 , 47 , 49 , 51 , 53 , 55 , 57 , 59 , 61 , 63 , 65 , 67 , 69 , 71 , 73 , 75 , 77 , 79 , 81 , 83 , 85 , 87 , 89 , 91 , 93 , 95 , 97 , 99 , 126 , 102 , 104 , 106 , 112 , 108 , 110 , 114 , 116 , 119 , 121 , 123 , 127 , 118 , 120 , 122 , 124 , ] , } ;
+encode_enum!(PlankExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum PlyoExerciseName {
     AlternatingJumpLunge = 0,
     WeightedAlternatingJumpLunge = 1,
@@ -6759,7 +8255,8 @@ impl PlyoExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(PlyoExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum PullUpExerciseName {
     BandedPullUps = 0,
     ThirtyDegreeLatPulldown = 1,
@@ -6849,7 +8346,8 @@ impl PullUpExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(PullUpExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum PushUpExerciseName {
     ChestPressWithBand = 0,
     AlternatingStaggeredPushUp = 1,
@@ -6934,94 +8432,205 @@ pub enum PushUpExerciseName {
 impl PushUpExerciseName {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(PushUpExerciseName::ChestPressWithBand),
-            1 => Ok(PushUpExerciseName::AlternatingStaggeredPushUp),
-            2 => Ok(PushUpExerciseName::WeightedAlternatingStaggeredPushUp),
-            3 => Ok(PushUpExerciseName::AlternatingHandsMedicineBallPushUp),
-            4 => Ok(
-                PushUpExerciseName::WeightedAlternatingHandsMedicineBallPushUp,
-            ),
-            5 => Ok(PushUpExerciseName::BosuBallPushUp),
-            6 => Ok(PushUpExerciseName::WeightedBosuBallPushUp),
-            7 => Ok(PushUpExerciseName::ClappingPushUp),
-            8 => Ok(PushUpExerciseName::WeightedClappingPushUp),
-            9 => Ok(PushUpExerciseName::CloseGripMedicineBallPushUp),
-            10 => Ok(PushUpExerciseName::WeightedCloseGripMedicineBallPushUp),
-            11 => Ok(PushUpExerciseName::CloseHandsPushUp),
-            12 => Ok(PushUpExerciseName::WeightedCloseHandsPushUp),
-            13 => Ok(PushUpExerciseName::DeclinePushUp),
-            14 => Ok(PushUpExerciseName::WeightedDeclinePushUp),
-            15 => Ok(PushUpExerciseName::DiamondPushUp),
-            16 => Ok(PushUpExerciseName::WeightedDiamondPushUp),
-            17 => Ok(PushUpExerciseName::ExplosiveCrossoverPushUp),
-            18 => Ok(PushUpExerciseName::WeightedExplosiveCrossoverPushUp),
-            19 => Ok(PushUpExerciseName::ExplosivePushUp),
-            20 => Ok(PushUpExerciseName::WeightedExplosivePushUp),
-            21 => Ok(PushUpExerciseName::FeetElevatedSideToSidePushUp),
-            22 => Ok(PushUpExerciseName::WeightedFeetElevatedSideToSidePushUp),
-            23 => Ok(PushUpExerciseName::HandReleasePushUp),
-            24 => Ok(PushUpExerciseName::WeightedHandReleasePushUp),
-            25 => Ok(PushUpExerciseName::HandstandPushUp),
-            26 => Ok(PushUpExerciseName::WeightedHandstandPushUp),
-            27 => Ok(PushUpExerciseName::InclinePushUp),
-            28 => Ok(PushUpExerciseName::WeightedInclinePushUp),
-            29 => Ok(PushUpExerciseName::IsometricExplosivePushUp),
-            30 => Ok(PushUpExerciseName::WeightedIsometricExplosivePushUp),
-            31 => Ok(PushUpExerciseName::JudoPushUp),
-            32 => Ok(PushUpExerciseName::WeightedJudoPushUp),
-            33 => Ok(PushUpExerciseName::KneelingPushUp),
-            34 => Ok(PushUpExerciseName::WeightedKneelingPushUp),
-            35 => Ok(PushUpExerciseName::MedicineBallChestPass),
-            36 => Ok(PushUpExerciseName::MedicineBallPushUp),
-            37 => Ok(PushUpExerciseName::WeightedMedicineBallPushUp),
-            38 => Ok(PushUpExerciseName::OneArmPushUp),
-            39 => Ok(PushUpExerciseName::WeightedOneArmPushUp),
-            40 => Ok(PushUpExerciseName::WeightedPushUp),
-            41 => Ok(PushUpExerciseName::PushUpAndRow),
-            42 => Ok(PushUpExerciseName::WeightedPushUpAndRow),
-            43 => Ok(PushUpExerciseName::PushUpPlus),
-            44 => Ok(PushUpExerciseName::WeightedPushUpPlus),
-            45 => Ok(PushUpExerciseName::PushUpWithFeetOnSwissBall),
-            46 => Ok(PushUpExerciseName::WeightedPushUpWithFeetOnSwissBall),
-            47 => Ok(PushUpExerciseName::PushUpWithOneHandOnMedicineBall),
-            48 => {
-                Ok(PushUpExerciseName::WeightedPushUpWithOneHandOnMedicineBall)
+        Ok(PUSH_UP_EXERCISE_NAME
+            .get(base_value.0)
+            .unwrap_or(PushUpExerciseName::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PushUpExerciseName::Unknown => "Unknown",
+            _ => {
+                PUSH_UP_EXERCISE_NAME
+                    .name(self.clone() as u16)
+                    .unwrap_or("Unknown")
             },
-            49 => Ok(PushUpExerciseName::ShoulderPushUp),
-            50 => Ok(PushUpExerciseName::WeightedShoulderPushUp),
-            51 => Ok(PushUpExerciseName::SingleArmMedicineBallPushUp),
-            52 => Ok(PushUpExerciseName::WeightedSingleArmMedicineBallPushUp),
-            53 => Ok(PushUpExerciseName::SpidermanPushUp),
-            54 => Ok(PushUpExerciseName::WeightedSpidermanPushUp),
-            55 => Ok(PushUpExerciseName::StackedFeetPushUp),
-            56 => Ok(PushUpExerciseName::WeightedStackedFeetPushUp),
-            57 => Ok(PushUpExerciseName::StaggeredHandsPushUp),
-            58 => Ok(PushUpExerciseName::WeightedStaggeredHandsPushUp),
-            59 => Ok(PushUpExerciseName::SuspendedPushUp),
-            60 => Ok(PushUpExerciseName::WeightedSuspendedPushUp),
-            61 => Ok(PushUpExerciseName::SwissBallPushUp),
-            62 => Ok(PushUpExerciseName::WeightedSwissBallPushUp),
-            63 => Ok(PushUpExerciseName::SwissBallPushUpPlus),
-            64 => Ok(PushUpExerciseName::WeightedSwissBallPushUpPlus),
-            65 => Ok(PushUpExerciseName::TPushUp),
-            66 => Ok(PushUpExerciseName::WeightedTPushUp),
-            67 => Ok(PushUpExerciseName::TripleStopPushUp),
-            68 => Ok(PushUpExerciseName::WeightedTripleStopPushUp),
-            69 => Ok(PushUpExerciseName::WideHandsPushUp),
-            70 => Ok(PushUpExerciseName::WeightedWideHandsPushUp),
-            71 => Ok(PushUpExerciseName::ParalletteHandstandPushUp),
-            72 => Ok(PushUpExerciseName::WeightedParalletteHandstandPushUp),
-            73 => Ok(PushUpExerciseName::RingHandstandPushUp),
-            74 => Ok(PushUpExerciseName::WeightedRingHandstandPushUp),
-            75 => Ok(PushUpExerciseName::RingPushUp),
-            76 => Ok(PushUpExerciseName::WeightedRingPushUp),
-            77 => Ok(PushUpExerciseName::PushUp),
-            _ => Ok(PushUpExerciseName::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for PushUpExerciseName {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        PUSH_UP_EXERCISE_NAME.parse(name).ok_or(())
+    }
+}
+static PUSH_UP_EXERCISE_NAME: profile::table::EnumTable<
+    u16,
+    PushUpExerciseName,
+> = profile::table::EnumTable {
+    values:  &[
+        (0, PushUpExerciseName::ChestPressWithBand),
+        (1, PushUpExerciseName::AlternatingStaggeredPushUp),
+        (2, PushUpExerciseName::WeightedAlternatingStaggeredPushUp),
+        (3, PushUpExerciseName::AlternatingHandsMedicineBallPushUp),
+        (4, PushUpExerciseName::WeightedAlternatingHandsMedicineBallPushUp),
+        (5, PushUpExerciseName::BosuBallPushUp),
+        (6, PushUpExerciseName::WeightedBosuBallPushUp),
+        (7, PushUpExerciseName::ClappingPushUp),
+        (8, PushUpExerciseName::WeightedClappingPushUp),
+        (9, PushUpExerciseName::CloseGripMedicineBallPushUp),
+        (10, PushUpExerciseName::WeightedCloseGripMedicineBallPushUp),
+        (11, PushUpExerciseName::CloseHandsPushUp),
+        (12, PushUpExerciseName::WeightedCloseHandsPushUp),
+        (13, PushUpExerciseName::DeclinePushUp),
+        (14, PushUpExerciseName::WeightedDeclinePushUp),
+        (15, PushUpExerciseName::DiamondPushUp),
+        (16, PushUpExerciseName::WeightedDiamondPushUp),
+        (17, PushUpExerciseName::ExplosiveCrossoverPushUp),
+        (18, PushUpExerciseName::WeightedExplosiveCrossoverPushUp),
+        (19, PushUpExerciseName::ExplosivePushUp),
+        (20, PushUpExerciseName::WeightedExplosivePushUp),
+        (21, PushUpExerciseName::FeetElevatedSideToSidePushUp),
+        (22, PushUpExerciseName::WeightedFeetElevatedSideToSidePushUp),
+        (23, PushUpExerciseName::HandReleasePushUp),
+        (24, PushUpExerciseName::WeightedHandReleasePushUp),
+        (25, PushUpExerciseName::HandstandPushUp),
+        (26, PushUpExerciseName::WeightedHandstandPushUp),
+        (27, PushUpExerciseName::InclinePushUp),
+        (28, PushUpExerciseName::WeightedInclinePushUp),
+        (29, PushUpExerciseName::IsometricExplosivePushUp),
+        (30, PushUpExerciseName::WeightedIsometricExplosivePushUp),
+        (31, PushUpExerciseName::JudoPushUp),
+        (32, PushUpExerciseName::WeightedJudoPushUp),
+        (33, PushUpExerciseName::KneelingPushUp),
+        (34, PushUpExerciseName::WeightedKneelingPushUp),
+        (35, PushUpExerciseName::MedicineBallChestPass),
+        (36, PushUpExerciseName::MedicineBallPushUp),
+        (37, PushUpExerciseName::WeightedMedicineBallPushUp),
+        (38, PushUpExerciseName::OneArmPushUp),
+        (39, PushUpExerciseName::WeightedOneArmPushUp),
+        (40, PushUpExerciseName::WeightedPushUp),
+        (41, PushUpExerciseName::PushUpAndRow),
+        (42, PushUpExerciseName::WeightedPushUpAndRow),
+        (43, PushUpExerciseName::PushUpPlus),
+        (44, PushUpExerciseName::WeightedPushUpPlus),
+        (45, PushUpExerciseName::PushUpWithFeetOnSwissBall),
+        (46, PushUpExerciseName::WeightedPushUpWithFeetOnSwissBall),
+        (47, PushUpExerciseName::PushUpWithOneHandOnMedicineBall),
+        (48, PushUpExerciseName::WeightedPushUpWithOneHandOnMedicineBall),
+        (49, PushUpExerciseName::ShoulderPushUp),
+        (50, PushUpExerciseName::WeightedShoulderPushUp),
+        (51, PushUpExerciseName::SingleArmMedicineBallPushUp),
+        (52, PushUpExerciseName::WeightedSingleArmMedicineBallPushUp),
+        (53, PushUpExerciseName::SpidermanPushUp),
+        (54, PushUpExerciseName::WeightedSpidermanPushUp),
+        (55, PushUpExerciseName::StackedFeetPushUp),
+        (56, PushUpExerciseName::WeightedStackedFeetPushUp),
+        (57, PushUpExerciseName::StaggeredHandsPushUp),
+        (58, PushUpExerciseName::WeightedStaggeredHandsPushUp),
+        (59, PushUpExerciseName::SuspendedPushUp),
+        (60, PushUpExerciseName::WeightedSuspendedPushUp),
+        (61, PushUpExerciseName::SwissBallPushUp),
+        (62, PushUpExerciseName::WeightedSwissBallPushUp),
+        (63, PushUpExerciseName::SwissBallPushUpPlus),
+        (64, PushUpExerciseName::WeightedSwissBallPushUpPlus),
+        (65, PushUpExerciseName::TPushUp),
+        (66, PushUpExerciseName::WeightedTPushUp),
+        (67, PushUpExerciseName::TripleStopPushUp),
+        (68, PushUpExerciseName::WeightedTripleStopPushUp),
+        (69, PushUpExerciseName::WideHandsPushUp),
+        (70, PushUpExerciseName::WeightedWideHandsPushUp),
+        (71, PushUpExerciseName::ParalletteHandstandPushUp),
+        (72, PushUpExerciseName::WeightedParalletteHandstandPushUp),
+        (73, PushUpExerciseName::RingHandstandPushUp),
+        (74, PushUpExerciseName::WeightedRingHandstandPushUp),
+        (75, PushUpExerciseName::RingPushUp),
+        (76, PushUpExerciseName::WeightedRingPushUp),
+        (77, PushUpExerciseName::PushUp),
+    ],
+    names:   &[
+        "ChestPressWithBand",
+        "AlternatingStaggeredPushUp",
+        "WeightedAlternatingStaggeredPushUp",
+        "AlternatingHandsMedicineBallPushUp",
+        "WeightedAlternatingHandsMedicineBallPushUp",
+        "BosuBallPushUp",
+        "WeightedBosuBallPushUp",
+        "ClappingPushUp",
+        "WeightedClappingPushUp",
+        "CloseGripMedicineBallPushUp",
+        "WeightedCloseGripMedicineBallPushUp",
+        "CloseHandsPushUp",
+        "WeightedCloseHandsPushUp",
+        "DeclinePushUp",
+        "WeightedDeclinePushUp",
+        "DiamondPushUp",
+        "WeightedDiamondPushUp",
+        "ExplosiveCrossoverPushUp",
+        "WeightedExplosiveCrossoverPushUp",
+        "ExplosivePushUp",
+        "WeightedExplosivePushUp",
+        "FeetElevatedSideToSidePushUp",
+        "WeightedFeetElevatedSideToSidePushUp",
+        "HandReleasePushUp",
+        "WeightedHandReleasePushUp",
+        "HandstandPushUp",
+        "WeightedHandstandPushUp",
+        "InclinePushUp",
+        "WeightedInclinePushUp",
+        "IsometricExplosivePushUp",
+        "WeightedIsometricExplosivePushUp",
+        "JudoPushUp",
+        "WeightedJudoPushUp",
+        "KneelingPushUp",
+        "WeightedKneelingPushUp",
+        "MedicineBallChestPass",
+        "MedicineBallPushUp",
+        "WeightedMedicineBallPushUp",
+        "OneArmPushUp",
+        "WeightedOneArmPushUp",
+        "WeightedPushUp",
+        "PushUpAndRow",
+        "WeightedPushUpAndRow",
+        "PushUpPlus",
+        "WeightedPushUpPlus",
+        "PushUpWithFeetOnSwissBall",
+        "WeightedPushUpWithFeetOnSwissBall",
+        "PushUpWithOneHandOnMedicineBall",
+        "WeightedPushUpWithOneHandOnMedicineBall",
+        "ShoulderPushUp",
+        "WeightedShoulderPushUp",
+        "SingleArmMedicineBallPushUp",
+        "WeightedSingleArmMedicineBallPushUp",
+        "SpidermanPushUp",
+        "WeightedSpidermanPushUp",
+        "StackedFeetPushUp",
+        "WeightedStackedFeetPushUp",
+        "StaggeredHandsPushUp",
+        "WeightedStaggeredHandsPushUp",
+        "SuspendedPushUp",
+        "WeightedSuspendedPushUp",
+        "SwissBallPushUp",
+        "WeightedSwissBallPushUp",
+        "SwissBallPushUpPlus",
+        "WeightedSwissBallPushUpPlus",
+        "TPushUp",
+        "WeightedTPushUp",
+        "TripleStopPushUp",
+        "WeightedTripleStopPushUp",
+        "WideHandsPushUp",
+        "WeightedWideHandsPushUp",
+        "ParalletteHandstandPushUp",
+        "WeightedParalletteHandstandPushUp",
+        "RingHandstandPushUp",
+        "WeightedRingHandstandPushUp",
+        "RingPushUp",
+        "WeightedRingPushUp",
+        "PushUp",
+    ],
+    by_name: &[
+        3, 1, 5, 0, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31, 33, 35,
+        36, 38, 71, 77, 41, 43, 45, 47, 73, 75, 49, 51, 53, 55, 57, 59, 61, 63,
+        65, 67, 4, 2, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30, 32, 34,
+        37, 39, 72, 40, 42, 44, 46, 48, 74, 76, 50, 52, 54, 56, 58, 60, 62, 64,
+        66, 68, 70, 69,
+    ],
+};
+encode_enum!(PushUpExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum RowExerciseName {
     BarbellStraightLegDeadliftToRow = 0,
     CableRowStanding = 1,
@@ -7103,7 +8712,8 @@ impl RowExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(RowExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum ShoulderPressExerciseName {
     AlternatingDumbbellShoulderPress = 0,
     ArnoldPress = 1,
@@ -7137,7 +8747,8 @@ impl ShoulderPressExerciseName {
         match base_value . 0 { 0 => Ok ( ShoulderPressExerciseName :: AlternatingDumbbellShoulderPress ) , 1 => Ok ( ShoulderPressExerciseName :: ArnoldPress ) , 2 => Ok ( ShoulderPressExerciseName :: BarbellFrontSquatToPushPress ) , 3 => Ok ( ShoulderPressExerciseName :: BarbellPushPress ) , 4 => Ok ( ShoulderPressExerciseName :: BarbellShoulderPress ) , 5 => Ok ( ShoulderPressExerciseName :: DeadCurlPress ) , 6 => Ok ( ShoulderPressExerciseName :: DumbbellAlternatingShoulderPressAndTwist ) , 7 => Ok ( ShoulderPressExerciseName :: DumbbellHammerCurlToLungeToPress ) , 8 => Ok ( ShoulderPressExerciseName :: DumbbellPushPress ) , 9 => Ok ( ShoulderPressExerciseName :: FloorInvertedShoulderPress ) , 10 => Ok ( ShoulderPressExerciseName :: WeightedFloorInvertedShoulderPress ) , 11 => Ok ( ShoulderPressExerciseName :: InvertedShoulderPress ) , 12 => Ok ( ShoulderPressExerciseName :: WeightedInvertedShoulderPress ) , 13 => Ok ( ShoulderPressExerciseName :: OneArmPushPress ) , 14 => Ok ( ShoulderPressExerciseName :: OverheadBarbellPress ) , 15 => Ok ( ShoulderPressExerciseName :: OverheadDumbbellPress ) , 16 => Ok ( ShoulderPressExerciseName :: SeatedBarbellShoulderPress ) , 17 => Ok ( ShoulderPressExerciseName :: SeatedDumbbellShoulderPress ) , 18 => Ok ( ShoulderPressExerciseName :: SingleArmDumbbellShoulderPress ) , 19 => Ok ( ShoulderPressExerciseName :: SingleArmStepUpAndPress ) , 20 => Ok ( ShoulderPressExerciseName :: SmithMachineOverheadPress ) , 21 => Ok ( ShoulderPressExerciseName :: SplitStanceHammerCurlToPress ) , 22 => Ok ( ShoulderPressExerciseName :: SwissBallDumbbellShoulderPress ) , 23 => Ok ( ShoulderPressExerciseName :: WeightPlateFrontRaise ) , _ => Ok ( ShoulderPressExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ShoulderPressExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum ShoulderStabilityExerciseName {
     NinetyDegreeCableExternalRotation = 0,
     BandExternalRotation = 1,
@@ -7180,7 +8791,8 @@ impl ShoulderStabilityExerciseName {
         match base_value . 0 { 0 => Ok ( ShoulderStabilityExerciseName :: NinetyDegreeCableExternalRotation ) , 1 => Ok ( ShoulderStabilityExerciseName :: BandExternalRotation ) , 2 => Ok ( ShoulderStabilityExerciseName :: BandInternalRotation ) , 3 => Ok ( ShoulderStabilityExerciseName :: BentArmLateralRaiseAndExternalRotation ) , 4 => Ok ( ShoulderStabilityExerciseName :: CableExternalRotation ) , 5 => Ok ( ShoulderStabilityExerciseName :: DumbbellFacePullWithExternalRotation ) , 6 => Ok ( ShoulderStabilityExerciseName :: FloorIRaise ) , 7 => Ok ( ShoulderStabilityExerciseName :: WeightedFloorIRaise ) , 8 => Ok ( ShoulderStabilityExerciseName :: FloorTRaise ) , 9 => Ok ( ShoulderStabilityExerciseName :: WeightedFloorTRaise ) , 10 => Ok ( ShoulderStabilityExerciseName :: FloorYRaise ) , 11 => Ok ( ShoulderStabilityExerciseName :: WeightedFloorYRaise ) , 12 => Ok ( ShoulderStabilityExerciseName :: InclineIRaise ) , 13 => Ok ( ShoulderStabilityExerciseName :: WeightedInclineIRaise ) , 14 => Ok ( ShoulderStabilityExerciseName :: InclineLRaise ) , 15 => Ok ( ShoulderStabilityExerciseName :: WeightedInclineLRaise ) , 16 => Ok ( ShoulderStabilityExerciseName :: InclineTRaise ) , 17 => Ok ( ShoulderStabilityExerciseName :: WeightedInclineTRaise ) , 18 => Ok ( ShoulderStabilityExerciseName :: InclineWRaise ) , 19 => Ok ( ShoulderStabilityExerciseName :: WeightedInclineWRaise ) , 20 => Ok ( ShoulderStabilityExerciseName :: InclineYRaise ) , 21 => Ok ( ShoulderStabilityExerciseName :: WeightedInclineYRaise ) , 22 => Ok ( ShoulderStabilityExerciseName :: LyingExternalRotation ) , 23 => Ok ( ShoulderStabilityExerciseName :: SeatedDumbbellExternalRotation ) , 24 => Ok ( ShoulderStabilityExerciseName :: StandingLRaise ) , 25 => Ok ( ShoulderStabilityExerciseName :: SwissBallIRaise ) , 26 => Ok ( ShoulderStabilityExerciseName :: WeightedSwissBallIRaise ) , 27 => Ok ( ShoulderStabilityExerciseName :: SwissBallTRaise ) , 28 => Ok ( ShoulderStabilityExerciseName :: WeightedSwissBallTRaise ) , 29 => Ok ( ShoulderStabilityExerciseName :: SwissBallWRaise ) , 30 => Ok ( ShoulderStabilityExerciseName :: WeightedSwissBallWRaise ) , 31 => Ok ( ShoulderStabilityExerciseName :: SwissBallYRaise ) , 32 => Ok ( ShoulderStabilityExerciseName :: WeightedSwissBallYRaise ) , _ => Ok ( ShoulderStabilityExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ShoulderStabilityExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum ShrugExerciseName {
     BarbellJumpShrug = 0,
     BarbellShrug = 1,
@@ -7226,7 +8838,8 @@ impl ShrugExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(ShrugExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum SitUpExerciseName {
     AlternatingSitUp = 0,
     WeightedAlternatingSitUp = 1,
@@ -7314,7 +8927,8 @@ impl SitUpExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(SitUpExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum SquatExerciseName {
     LegPress = 0,
     BackSquatWithBodyBar = 1,
@@ -7408,99 +9022,222 @@ pub enum SquatExerciseName {
 impl SquatExerciseName {
     pub(crate) fn decode<T: ByteOrder>(buffer: &[u8]) -> error::Result<Self> {
         let base_value = profile::base::Uint16::decode::<T>(buffer)?;
-        match base_value.0 {
-            0 => Ok(SquatExerciseName::LegPress),
-            1 => Ok(SquatExerciseName::BackSquatWithBodyBar),
-            2 => Ok(SquatExerciseName::BackSquats),
-            3 => Ok(SquatExerciseName::WeightedBackSquats),
-            4 => Ok(SquatExerciseName::BalancingSquat),
-            5 => Ok(SquatExerciseName::WeightedBalancingSquat),
-            6 => Ok(SquatExerciseName::BarbellBackSquat),
-            7 => Ok(SquatExerciseName::BarbellBoxSquat),
-            8 => Ok(SquatExerciseName::BarbellFrontSquat),
-            9 => Ok(SquatExerciseName::BarbellHackSquat),
-            10 => Ok(SquatExerciseName::BarbellHangSquatSnatch),
-            11 => Ok(SquatExerciseName::BarbellLateralStepUp),
-            12 => Ok(SquatExerciseName::BarbellQuarterSquat),
-            13 => Ok(SquatExerciseName::BarbellSiffSquat),
-            14 => Ok(SquatExerciseName::BarbellSquatSnatch),
-            15 => Ok(SquatExerciseName::BarbellSquatWithHeelsRaised),
-            16 => Ok(SquatExerciseName::BarbellStepover),
-            17 => Ok(SquatExerciseName::BarbellStepUp),
-            18 => Ok(SquatExerciseName::BenchSquatWithRotationalChop),
-            19 => Ok(SquatExerciseName::WeightedBenchSquatWithRotationalChop),
-            20 => Ok(SquatExerciseName::BodyWeightWallSquat),
-            21 => Ok(SquatExerciseName::WeightedWallSquat),
-            22 => Ok(SquatExerciseName::BoxStepSquat),
-            23 => Ok(SquatExerciseName::WeightedBoxStepSquat),
-            24 => Ok(SquatExerciseName::BracedSquat),
-            25 => Ok(SquatExerciseName::CrossedArmBarbellFrontSquat),
-            26 => Ok(SquatExerciseName::CrossoverDumbbellStepUp),
-            27 => Ok(SquatExerciseName::DumbbellFrontSquat),
-            28 => Ok(SquatExerciseName::DumbbellSplitSquat),
-            29 => Ok(SquatExerciseName::DumbbellSquat),
-            30 => Ok(SquatExerciseName::DumbbellSquatClean),
-            31 => Ok(SquatExerciseName::DumbbellStepover),
-            32 => Ok(SquatExerciseName::DumbbellStepUp),
-            33 => Ok(SquatExerciseName::ElevatedSingleLegSquat),
-            34 => Ok(SquatExerciseName::WeightedElevatedSingleLegSquat),
-            35 => Ok(SquatExerciseName::FigureFourSquats),
-            36 => Ok(SquatExerciseName::WeightedFigureFourSquats),
-            37 => Ok(SquatExerciseName::GobletSquat),
-            38 => Ok(SquatExerciseName::KettlebellSquat),
-            39 => Ok(SquatExerciseName::KettlebellSwingOverhead),
-            40 => Ok(SquatExerciseName::KettlebellSwingWithFlipToSquat),
-            41 => Ok(SquatExerciseName::LateralDumbbellStepUp),
-            42 => Ok(SquatExerciseName::OneLeggedSquat),
-            43 => Ok(SquatExerciseName::OverheadDumbbellSquat),
-            44 => Ok(SquatExerciseName::OverheadSquat),
-            45 => Ok(SquatExerciseName::PartialSingleLegSquat),
-            46 => Ok(SquatExerciseName::WeightedPartialSingleLegSquat),
-            47 => Ok(SquatExerciseName::PistolSquat),
-            48 => Ok(SquatExerciseName::WeightedPistolSquat),
-            49 => Ok(SquatExerciseName::PlieSlides),
-            50 => Ok(SquatExerciseName::WeightedPlieSlides),
-            51 => Ok(SquatExerciseName::PlieSquat),
-            52 => Ok(SquatExerciseName::WeightedPlieSquat),
-            53 => Ok(SquatExerciseName::PrisonerSquat),
-            54 => Ok(SquatExerciseName::WeightedPrisonerSquat),
-            55 => Ok(SquatExerciseName::SingleLegBenchGetUp),
-            56 => Ok(SquatExerciseName::WeightedSingleLegBenchGetUp),
-            57 => Ok(SquatExerciseName::SingleLegBenchSquat),
-            58 => Ok(SquatExerciseName::WeightedSingleLegBenchSquat),
-            59 => Ok(SquatExerciseName::SingleLegSquatOnSwissBall),
-            60 => Ok(SquatExerciseName::WeightedSingleLegSquatOnSwissBall),
-            61 => Ok(SquatExerciseName::Squat),
-            62 => Ok(SquatExerciseName::WeightedSquat),
-            63 => Ok(SquatExerciseName::SquatsWithBand),
-            64 => Ok(SquatExerciseName::StaggeredSquat),
-            65 => Ok(SquatExerciseName::WeightedStaggeredSquat),
-            66 => Ok(SquatExerciseName::StepUp),
-            67 => Ok(SquatExerciseName::WeightedStepUp),
-            68 => Ok(SquatExerciseName::SuitcaseSquats),
-            69 => Ok(SquatExerciseName::SumoSquat),
-            70 => Ok(SquatExerciseName::SumoSquatSlideIn),
-            71 => Ok(SquatExerciseName::WeightedSumoSquatSlideIn),
-            72 => Ok(SquatExerciseName::SumoSquatToHighPull),
-            73 => Ok(SquatExerciseName::SumoSquatToStand),
-            74 => Ok(SquatExerciseName::WeightedSumoSquatToStand),
-            75 => Ok(SquatExerciseName::SumoSquatWithRotation),
-            76 => Ok(SquatExerciseName::WeightedSumoSquatWithRotation),
-            77 => Ok(SquatExerciseName::SwissBallBodyWeightWallSquat),
-            78 => Ok(SquatExerciseName::WeightedSwissBallWallSquat),
-            79 => Ok(SquatExerciseName::Thrusters),
-            80 => Ok(SquatExerciseName::UnevenSquat),
-            81 => Ok(SquatExerciseName::WeightedUnevenSquat),
-            82 => Ok(SquatExerciseName::WaistSlimmingSquat),
-            83 => Ok(SquatExerciseName::WallBall),
-            84 => Ok(SquatExerciseName::WideStanceBarbellSquat),
-            85 => Ok(SquatExerciseName::WideStanceGobletSquat),
-            86 => Ok(SquatExerciseName::ZercherSquat),
-            _ => Ok(SquatExerciseName::Unknown),
-        }
-    }
-}
-#[derive(Debug,Clone)]
+        Ok(SQUAT_EXERCISE_NAME
+            .get(base_value.0)
+            .unwrap_or(SquatExerciseName::Unknown))
+    }
+
+    #[doc = "The variant's name, as `Debug` writes it."]
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            SquatExerciseName::Unknown => "Unknown",
+            _ => {
+                SQUAT_EXERCISE_NAME
+                    .name(self.clone() as u16)
+                    .unwrap_or("Unknown")
+            },
+        }
+    }
+}
+#[doc = "Fails for names that aren't variants, and for `Unknown`."]
+impl std::str::FromStr for SquatExerciseName {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, ()> {
+        SQUAT_EXERCISE_NAME.parse(name).ok_or(())
+    }
+}
+static SQUAT_EXERCISE_NAME: profile::table::EnumTable<u16, SquatExerciseName> =
+    profile::table::EnumTable {
+        values:  &[
+            (0, SquatExerciseName::LegPress),
+            (1, SquatExerciseName::BackSquatWithBodyBar),
+            (2, SquatExerciseName::BackSquats),
+            (3, SquatExerciseName::WeightedBackSquats),
+            (4, SquatExerciseName::BalancingSquat),
+            (5, SquatExerciseName::WeightedBalancingSquat),
+            (6, SquatExerciseName::BarbellBackSquat),
+            (7, SquatExerciseName::BarbellBoxSquat),
+            (8, SquatExerciseName::BarbellFrontSquat),
+            (9, SquatExerciseName::BarbellHackSquat),
+            (10, SquatExerciseName::BarbellHangSquatSnatch),
+            (11, SquatExerciseName::BarbellLateralStepUp),
+            (12, SquatExerciseName::BarbellQuarterSquat),
+            (13, SquatExerciseName::BarbellSiffSquat),
+            (14, SquatExerciseName::BarbellSquatSnatch),
+            (15, SquatExerciseName::BarbellSquatWithHeelsRaised),
+            (16, SquatExerciseName::BarbellStepover),
+            (17, SquatExerciseName::BarbellStepUp),
+            (18, SquatExerciseName::BenchSquatWithRotationalChop),
+            (19, SquatExerciseName::WeightedBenchSquatWithRotationalChop),
+            (20, SquatExerciseName::BodyWeightWallSquat),
+            (21, SquatExerciseName::WeightedWallSquat),
+            (22, SquatExerciseName::BoxStepSquat),
+            (23, SquatExerciseName::WeightedBoxStepSquat),
+            (24, SquatExerciseName::BracedSquat),
+            (25, SquatExerciseName::CrossedArmBarbellFrontSquat),
+            (26, SquatExerciseName::CrossoverDumbbellStepUp),
+            (27, SquatExerciseName::DumbbellFrontSquat),
+            (28, SquatExerciseName::DumbbellSplitSquat),
+            (29, SquatExerciseName::DumbbellSquat),
+            (30, SquatExerciseName::DumbbellSquatClean),
+            (31, SquatExerciseName::DumbbellStepover),
+            (32, SquatExerciseName::DumbbellStepUp),
+            (33, SquatExerciseName::ElevatedSingleLegSquat),
+            (34, SquatExerciseName::WeightedElevatedSingleLegSquat),
+            (35, SquatExerciseName::FigureFourSquats),
+            (36, SquatExerciseName::WeightedFigureFourSquats),
+            (37, SquatExerciseName::GobletSquat),
+            (38, SquatExerciseName::KettlebellSquat),
+            (39, SquatExerciseName::KettlebellSwingOverhead),
+            (40, SquatExerciseName::KettlebellSwingWithFlipToSquat),
+            (41, SquatExerciseName::LateralDumbbellStepUp),
+            (42, SquatExerciseName::OneLeggedSquat),
+            (43, SquatExerciseName::OverheadDumbbellSquat),
+            (44, SquatExerciseName::OverheadSquat),
+            (45, SquatExerciseName::PartialSingleLegSquat),
+            (46, SquatExerciseName::WeightedPartialSingleLegSquat),
+            (47, SquatExerciseName::PistolSquat),
+            (48, SquatExerciseName::WeightedPistolSquat),
+            (49, SquatExerciseName::PlieSlides),
+            (50, SquatExerciseName::WeightedPlieSlides),
+            (51, SquatExerciseName::PlieSquat),
+            (52, SquatExerciseName::WeightedPlieSquat),
+            (53, SquatExerciseName::PrisonerSquat),
+            (54, SquatExerciseName::WeightedPrisonerSquat),
+            (55, SquatExerciseName::SingleLegBenchGetUp),
+            (56, SquatExerciseName::WeightedSingleLegBenchGetUp),
+            (57, SquatExerciseName::SingleLegBenchSquat),
+            (58, SquatExerciseName::WeightedSingleLegBenchSquat),
+            (59, SquatExerciseName::SingleLegSquatOnSwissBall),
+            (60, SquatExerciseName::WeightedSingleLegSquatOnSwissBall),
+            (61, SquatExerciseName::Squat),
+            (62, SquatExerciseName::WeightedSquat),
+            (63, SquatExerciseName::SquatsWithBand),
+            (64, SquatExerciseName::StaggeredSquat),
+            (65, SquatExerciseName::WeightedStaggeredSquat),
+            (66, SquatExerciseName::StepUp),
+            (67, SquatExerciseName::WeightedStepUp),
+            (68, SquatExerciseName::SuitcaseSquats),
+            (69, SquatExerciseName::SumoSquat),
+            (70, SquatExerciseName::SumoSquatSlideIn),
+            (71, SquatExerciseName::WeightedSumoSquatSlideIn),
+            (72, SquatExerciseName::SumoSquatToHighPull),
+            (73, SquatExerciseName::SumoSquatToStand),
+            (74, SquatExerciseName::WeightedSumoSquatToStand),
+            (75, SquatExerciseName::SumoSquatWithRotation),
+            (76, SquatExerciseName::WeightedSumoSquatWithRotation),
+            (77, SquatExerciseName::SwissBallBodyWeightWallSquat),
+            (78, SquatExerciseName::WeightedSwissBallWallSquat),
+            (79, SquatExerciseName::Thrusters),
+            (80, SquatExerciseName::UnevenSquat),
+            (81, SquatExerciseName::WeightedUnevenSquat),
+            (82, SquatExerciseName::WaistSlimmingSquat),
+            (83, SquatExerciseName::WallBall),
+            (84, SquatExerciseName::WideStanceBarbellSquat),
+            (85, SquatExerciseName::WideStanceGobletSquat),
+            (86, SquatExerciseName::ZercherSquat),
+        ],
+        names:   &[
+            "LegPress",
+            "BackSquatWithBodyBar",
+            "BackSquats",
+            "WeightedBackSquats",
+            "BalancingSquat",
+            "WeightedBalancingSquat",
+            "BarbellBackSquat",
+            "BarbellBoxSquat",
+            "BarbellFrontSquat",
+            "BarbellHackSquat",
+            "BarbellHangSquatSnatch",
+            "BarbellLateralStepUp",
+            "BarbellQuarterSquat",
+            "BarbellSiffSquat",
+            "BarbellSquatSnatch",
+            "BarbellSquatWithHeelsRaised",
+            "BarbellStepover",
+            "BarbellStepUp",
+            "BenchSquatWithRotationalChop",
+            "WeightedBenchSquatWithRotationalChop",
+            "BodyWeightWallSquat",
+            "WeightedWallSquat",
+            "BoxStepSquat",
+            "WeightedBoxStepSquat",
+            "BracedSquat",
+            "CrossedArmBarbellFrontSquat",
+            "CrossoverDumbbellStepUp",
+            "DumbbellFrontSquat",
+            "DumbbellSplitSquat",
+            "DumbbellSquat",
+            "DumbbellSquatClean",
+            "DumbbellStepover",
+            "DumbbellStepUp",
+            "ElevatedSingleLegSquat",
+            "WeightedElevatedSingleLegSquat",
+            "FigureFourSquats",
+            "WeightedFigureFourSquats",
+            "GobletSquat",
+            "KettlebellSquat",
+            "KettlebellSwingOverhead",
+            "KettlebellSwingWithFlipToSquat",
+            "LateralDumbbellStepUp",
+            "OneLeggedSquat",
+            "OverheadDumbbellSquat",
+            "OverheadSquat",
+            "PartialSingleLegSquat",
+            "WeightedPartialSingleLegSquat",
+            "PistolSquat",
+            "WeightedPistolSquat",
+            "PlieSlides",
+            "WeightedPlieSlides",
+            "PlieSquat",
+            "WeightedPlieSquat",
+            "PrisonerSquat",
+            "WeightedPrisonerSquat",
+            "SingleLegBenchGetUp",
+            "WeightedSingleLegBenchGetUp",
+            "SingleLegBenchSquat",
+            "WeightedSingleLegBenchSquat",
+            "SingleLegSquatOnSwissBall",
+            "WeightedSingleLegSquatOnSwissBall",
+            "Squat",
+            "WeightedSquat",
+            "SquatsWithBand",
+            "StaggeredSquat",
+            "WeightedStaggeredSquat",
+            "StepUp",
+            "WeightedStepUp",
+            "SuitcaseSquats",
+            "SumoSquat",
+            "SumoSquatSlideIn",
+            "WeightedSumoSquatSlideIn",
+            "SumoSquatToHighPull",
+            "SumoSquatToStand",
+            "WeightedSumoSquatToStand",
+            "SumoSquatWithRotation",
+            "WeightedSumoSquatWithRotation",
+            "SwissBallBodyWeightWallSquat",
+            "WeightedSwissBallWallSquat",
+            "Thrusters",
+            "UnevenSquat",
+            "WeightedUnevenSquat",
+            "WaistSlimmingSquat",
+            "WallBall",
+            "WideStanceBarbellSquat",
+            "WideStanceGobletSquat",
+            "ZercherSquat",
+        ],
+        by_name: &[
+            1, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 17, 16, 18, 20, 22,
+            24, 25, 26, 27, 28, 29, 30, 32, 31, 33, 35, 37, 38, 39, 40, 41, 0,
+            42, 43, 44, 45, 47, 49, 51, 53, 55, 57, 59, 61, 63, 64, 66, 68, 69,
+            70, 72, 73, 75, 77, 79, 80, 82, 83, 3, 5, 19, 23, 34, 36, 46, 48,
+            50, 52, 54, 56, 58, 60, 62, 65, 67, 71, 74, 76, 78, 81, 21, 84, 85,
+            86,
+        ],
+    };
+encode_enum!(SquatExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum TotalBodyExerciseName {
     Burpee = 0,
     WeightedBurpee = 1,
@@ -7538,7 +9275,8 @@ impl TotalBodyExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(TotalBodyExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum TricepsExtensionExerciseName {
     BenchDip = 0,
     WeightedBenchDip = 1,
@@ -7589,7 +9327,8 @@ impl TricepsExtensionExerciseName {
         match base_value . 0 { 0 => Ok ( TricepsExtensionExerciseName :: BenchDip ) , 1 => Ok ( TricepsExtensionExerciseName :: WeightedBenchDip ) , 2 => Ok ( TricepsExtensionExerciseName :: BodyWeightDip ) , 3 => Ok ( TricepsExtensionExerciseName :: CableKickback ) , 4 => Ok ( TricepsExtensionExerciseName :: CableLyingTricepsExtension ) , 5 => Ok ( TricepsExtensionExerciseName :: CableOverheadTricepsExtension ) , 6 => Ok ( TricepsExtensionExerciseName :: DumbbellKickback ) , 7 => Ok ( TricepsExtensionExerciseName :: DumbbellLyingTricepsExtension ) , 8 => Ok ( TricepsExtensionExerciseName :: EzBarOverheadTricepsExtension ) , 9 => Ok ( TricepsExtensionExerciseName :: InclineDip ) , 10 => Ok ( TricepsExtensionExerciseName :: WeightedInclineDip ) , 11 => Ok ( TricepsExtensionExerciseName :: InclineEzBarLyingTricepsExtension ) , 12 => Ok ( TricepsExtensionExerciseName :: LyingDumbbellPulloverToExtension ) , 13 => Ok ( TricepsExtensionExerciseName :: LyingEzBarTricepsExtension ) , 14 => Ok ( TricepsExtensionExerciseName :: LyingTricepsExtensionToCloseGripBenchPress ) , 15 => Ok ( TricepsExtensionExerciseName :: OverheadDumbbellTricepsExtension ) , 16 => Ok ( TricepsExtensionExerciseName :: RecliningTricepsPress ) , 17 => Ok ( TricepsExtensionExerciseName :: ReverseGripPressdown ) , 18 => Ok ( TricepsExtensionExerciseName :: ReverseGripTricepsPressdown ) , 19 => Ok ( TricepsExtensionExerciseName :: RopePressdown ) , 20 => Ok ( TricepsExtensionExerciseName :: SeatedBarbellOverheadTricepsExtension ) , 21 => Ok ( TricepsExtensionExerciseName :: SeatedDumbbellOverheadTricepsExtension ) , 22 => Ok ( TricepsExtensionExerciseName :: SeatedEzBarOverheadTricepsExtension ) , 23 => Ok ( TricepsExtensionExerciseName :: SeatedSingleArmOverheadDumbbellExtension ) , 24 => Ok ( TricepsExtensionExerciseName :: SingleArmDumbbellOverheadTricepsExtension ) , 25 => Ok ( TricepsExtensionExerciseName :: SingleDumbbellSeatedOverheadTricepsExtension ) , 26 => Ok ( TricepsExtensionExerciseName :: SingleLegBenchDipAndKick ) , 27 => Ok ( TricepsExtensionExerciseName :: WeightedSingleLegBenchDipAndKick ) , 28 => Ok ( TricepsExtensionExerciseName :: SingleLegDip ) , 29 => Ok ( TricepsExtensionExerciseName :: WeightedSingleLegDip ) , 30 => Ok ( TricepsExtensionExerciseName :: StaticLyingTricepsExtension ) , 31 => Ok ( TricepsExtensionExerciseName :: SuspendedDip ) , 32 => Ok ( TricepsExtensionExerciseName :: WeightedSuspendedDip ) , 33 => Ok ( TricepsExtensionExerciseName :: SwissBallDumbbellLyingTricepsExtension ) , 34 => Ok ( TricepsExtensionExerciseName :: SwissBallEzBarLyingTricepsExtension ) , 35 => Ok ( TricepsExtensionExerciseName :: SwissBallEzBarOverheadTricepsExtension ) , 36 => Ok ( TricepsExtensionExerciseName :: TabletopDip ) , 37 => Ok ( TricepsExtensionExerciseName :: WeightedTabletopDip ) , 38 => Ok ( TricepsExtensionExerciseName :: TricepsExtensionOnFloor ) , 39 => Ok ( TricepsExtensionExerciseName :: TricepsPressdown ) , 40 => Ok ( TricepsExtensionExerciseName :: WeightedDip ) , _ => Ok ( TricepsExtensionExerciseName :: Unknown ) , }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(TricepsExtensionExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum WarmUpExerciseName {
     QuadrupedRocking = 0,
     NeckTilts = 1,
@@ -7663,7 +9402,8 @@ impl WarmUpExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WarmUpExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum RunExerciseName {
     Run = 0,
     Walk = 1,
@@ -7683,7 +9423,8 @@ impl RunExerciseName {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(RunExerciseName, u16, 0xFFFF);
+#[derive(Debug, Clone)]
 pub enum WaterType {
     Fresh = 0,
     Salt = 1,
@@ -7703,7 +9444,8 @@ impl WaterType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(WaterType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum TissueModelType {
     #[doc = "Buhlmann\'s decompression algorithm, version C"]
     Zhl16C = 0,
@@ -7718,7 +9460,8 @@ impl TissueModelType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(TissueModelType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DiveGasStatus {
     Disabled = 0,
     Enabled = 1,
@@ -7736,7 +9479,8 @@ impl DiveGasStatus {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DiveGasStatus, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DiveAlarmType {
     Depth = 0,
     Time = 1,
@@ -7752,7 +9496,8 @@ impl DiveAlarmType {
         }
     }
 }
-#[derive(Debug,Clone)]
+encode_enum!(DiveAlarmType, u8, 0xFF);
+#[derive(Debug, Clone)]
 pub enum DiveBacklightMode {
     AtDepth = 0,
     AlwaysOn = 1,
@@ -7768,3 +9513,4 @@ impl DiveBacklightMode {
         }
     }
 }
+encode_enum!(DiveBacklightMode, u8, 0xFF);
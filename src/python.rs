@@ -0,0 +1,290 @@
+//! A Python module in the style of `fitparse`.
+//!
+//! Built with the `python` feature into an extension module
+//! named `garminfit`, usually through maturin:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! ```python
+//! import garminfit
+//!
+//! fit = garminfit.FitFile("ride.fit")
+//! records = pandas.DataFrame(fit.records_dataframe())
+//! ```
+//!
+//! Values are converted the way the rest of the crate reads
+//! them: scaled into their units, positions in degrees,
+//! timestamps as `datetime` objects (UTC-aware for
+//! `date_time` fields, naive for `local_date_time` ones,
+//! and plain seconds for system times), enums as the names
+//! of their values and invalid values as `None`.
+use geo::semicircles_to_degrees;
+use profile::messages::{
+    field_name,
+    field_scale_offset,
+    field_units,
+    message_name,
+    Message,
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{
+        PyBytes,
+        PyDateTime,
+        PyDict,
+        PyTzInfo,
+    },
+};
+use std::{
+    fs,
+    io::BufReader,
+};
+use types::{
+    field::Value,
+    file::{
+        file_id_warnings,
+        File,
+    },
+    record::Data,
+};
+
+/// A decoded FIT file.
+#[pyclass(module = "garminfit", frozen)]
+pub struct FitFile {
+    file: File,
+}
+
+#[pymethods]
+impl FitFile {
+    /// Decode the whole file at `path`, verifying its CRC.
+    ///
+    /// Raises `OSError` if the file can't be read and
+    /// `ValueError` if it isn't valid FIT.
+    #[new]
+    fn new(path: &str) -> PyResult<Self> {
+        let file = fs::File::open(path)?;
+        let file = File::decode(&mut BufReader::new(file))
+            .map_err(|err| PyValueError::new_err(err.describe()))?;
+        Ok(FitFile {
+            file,
+        })
+    }
+
+    /// Every data message as a dict of field name to value,
+    /// in file order, or only those named `name`, such as
+    /// `"record"`. Keys are in the order the fields were
+    /// recorded.
+    ///
+    /// Fields the profile doesn't know are named
+    /// `unknown_<number>`.
+    #[pyo3(signature = (name = None))]
+    fn messages<'py>(
+        &self,
+        py: Python<'py>,
+        name: Option<&str>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.file
+            .messages()
+            .filter(|mesg| name.is_none() || mesg_name(mesg) == name)
+            .map(|mesg| to_dict(py, mesg))
+            .collect()
+    }
+
+    /// The `record` messages, as a list of dicts that
+    /// `pandas.DataFrame` takes as is.
+    fn records_dataframe<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.messages(py, Some("record"))
+    }
+
+    /// What the file is and what it holds: its `file_id`,
+    /// `activity` and `session`s, the number of `record`s,
+    /// and any problems with its `file_id`.
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let summary = PyDict::new(py);
+        let file_id = match self.file.file_id() {
+            Some(file_id) => Some(to_dict(py, file_id)?),
+            None => None,
+        };
+        summary.set_item("file_id", file_id)?;
+        let activity = self.messages(py, Some("activity"))?;
+        summary.set_item("activity", activity.into_iter().next())?;
+        summary.set_item("sessions", self.messages(py, Some("session"))?)?;
+
+        let records = self
+            .file
+            .messages()
+            .filter(|mesg| mesg.0.first().map(Message::mesg_num) == Some(20));
+        summary.set_item("record_count", records.count())?;
+        let warnings: Vec<String> = file_id_warnings(self.file.messages())
+            .iter()
+            .map(|warning| format!("{:?}", warning))
+            .collect();
+        summary.set_item("warnings", warnings)?;
+        Ok(summary)
+    }
+
+    fn __len__(&self) -> usize {
+        self.file.messages().count()
+    }
+}
+
+/// The name of the message type of `mesg`.
+fn mesg_name(mesg: &Data) -> Option<&'static str> {
+    mesg.0.first().and_then(|field| message_name(field.mesg_num()))
+}
+
+fn to_dict<'py>(py: Python<'py>, mesg: &Data) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for field in mesg.0.iter() {
+        let (mesg_num, num) = (field.mesg_num(), field.field_def_num());
+        let value = to_python(py, field)?;
+        match field_name(mesg_num, num) {
+            Some(name) => dict.set_item(name, value)?,
+            None => dict.set_item(format!("unknown_{}", num), value)?,
+        }
+    }
+    Ok(dict)
+}
+
+fn to_python<'py>(
+    py: Python<'py>,
+    field: &Message,
+) -> PyResult<Bound<'py, PyAny>> {
+    let (mesg_num, num) = (field.mesg_num(), field.field_def_num());
+    let object = match field.value() {
+        Value::Number(value) => {
+            if field_units(mesg_num, num) == Some("semicircles") {
+                semicircles_to_degrees(value as i32)
+                    .into_pyobject(py)?
+                    .into_any()
+            }
+            else if field_scale_offset(mesg_num, num) == (None, None) {
+                // Unscaled integers stay integers
+                (value as i64).into_pyobject(py)?.into_any()
+            }
+            else {
+                value.into_pyobject(py)?.into_any()
+            }
+        },
+        Value::Numbers(values) => values.into_pyobject(py)?,
+        Value::Bool(value) => value.into_pyobject(py)?.to_owned().into_any(),
+        Value::Text(text) => text.into_pyobject(py)?.into_any(),
+        Value::Texts(texts) => texts.into_pyobject(py)?,
+        Value::Bytes(bytes) => PyBytes::new(py, &bytes).into_any(),
+        Value::DateTime(date_time) => {
+            match date_time.to_utc() {
+                Some(utc) => {
+                    let timestamp = utc.timestamp() as f64;
+                    let tz = PyTzInfo::utc(py)?;
+                    PyDateTime::from_timestamp(py, timestamp, Some(&tz))?
+                        .into_any()
+                },
+                None => date_time.0.into_pyobject(py)?.into_any(),
+            }
+        },
+        Value::LocalDateTime(date_time) => {
+            match date_time.to_naive_local() {
+                Some(local) => naive_datetime(py, &local)?,
+                None => date_time.0.into_pyobject(py)?.into_any(),
+            }
+        },
+        Value::Enum(name) => name.into_pyobject(py)?.into_any(),
+        Value::Invalid => py.None().into_bound(py),
+    };
+    Ok(object)
+}
+
+fn naive_datetime<'py>(
+    py: Python<'py>,
+    local: &chrono::NaiveDateTime,
+) -> PyResult<Bound<'py, PyAny>> {
+    use chrono::{
+        Datelike,
+        Timelike,
+    };
+
+    let datetime = PyDateTime::new(
+        py,
+        local.year(),
+        local.month() as u8,
+        local.day() as u8,
+        local.hour() as u8,
+        local.minute() as u8,
+        local.second() as u8,
+        0,
+        None,
+    )?;
+    Ok(datetime.into_any())
+}
+
+/// The `garminfit` Python module.
+#[pymodule]
+fn garminfit(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<FitFile>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyAnyMethods;
+    use std::env;
+    use testutil::FitBuilder;
+
+    #[test]
+    fn converts_like_the_crate() {
+        let mut fit = FitBuilder::new();
+        // FileId: type, time created
+        fit.definition(0, 0, &[(0, 1, 0x00), (4, 4, 0x86)]);
+        fit.data(0, &[&[4], &1_000_000_000u32.to_le_bytes()]);
+        // Record: latitude, speed, heart rate, cadence
+        fit.definition(
+            1,
+            20,
+            &[(0, 4, 0x85), (6, 2, 0x84), (3, 1, 0x02), (4, 1, 0x02)],
+        );
+        fit.data(
+            1,
+            &[
+                &(1i32 << 29).to_le_bytes(),
+                &5250u16.to_le_bytes(),
+                &[150],
+                &[0xFF],
+            ],
+        );
+        let path = env::temp_dir()
+            .join(format!("garminfit-python-{}.fit", std::process::id()));
+        fs::write(&path, fit.build()).unwrap();
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let fit = FitFile::new(path.to_str().unwrap()).unwrap();
+            let records = fit.records_dataframe(py).unwrap();
+            assert_eq!(records.len(), 1);
+            let record = &records[0];
+            let get = |name: &str| record.get_item(name).unwrap().unwrap();
+            assert_eq!(get("position_lat").extract::<f64>().unwrap(), 45.0);
+            assert_eq!(get("speed").extract::<f64>().unwrap(), 5.25);
+            assert_eq!(get("heart_rate").extract::<i64>().unwrap(), 150);
+            assert!(get("cadence").is_none());
+
+            let summary = fit.summary(py).unwrap();
+            let file_id = summary.get_item("file_id").unwrap().unwrap();
+            let file_type = file_id.get_item("type").unwrap();
+            assert_eq!(file_type.extract::<String>().unwrap(), "Activity");
+            let created = file_id.get_item("time_created").unwrap();
+            let created = created.call_method0("timestamp").unwrap();
+            assert_eq!(created.extract::<f64>().unwrap(), 1_631_065_600.0);
+            let count = summary.get_item("record_count").unwrap().unwrap();
+            assert_eq!(count.extract::<usize>().unwrap(), 1);
+        });
+        fs::remove_file(&path).unwrap();
+
+        assert!(FitFile::new("/nonexistent/garminfit.fit").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! Formatting and pace helpers for analysing races.
+use laps::Lap;
+
+/// Format a duration as `H:MM:SS`, hours included even
+/// under an hour, rounded to the nearest second: `5025.0`
+/// is `"1:23:45"` and `45.0` is `"0:00:45"`.
+pub fn format_time(seconds: f64) -> String {
+    if !seconds.is_finite() {
+        return String::from("--:--:--")
+    }
+
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let total = seconds.abs().round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    format!("{}{}:{:02}:{:02}", sign, hours, minutes, seconds)
+}
+
+/// How an actual pace compares to a target one.
+///
+/// Exactly one of `ahead_s` and `behind_s` is set when the
+/// paces differ, neither when they match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaceDeviation {
+    /// Seconds per kilometre faster than the target.
+    pub ahead_s:  Option<f64>,
+    /// Seconds per kilometre slower than the target.
+    pub behind_s: Option<f64>,
+    /// Difference as a percentage of the target pace,
+    /// positive when ahead.
+    pub pct:      f64,
+}
+
+/// Compare an actual speed against a target speed, both in
+/// metres per second.
+///
+/// If either speed isn't positive there is no pace to
+/// compare: neither field is set and `pct` is NaN.
+pub fn pace_vs_target(actual_ms: f64, target_ms: f64) -> PaceDeviation {
+    if !(actual_ms > 0.0 && target_ms > 0.0) {
+        return PaceDeviation {
+            ahead_s:  None,
+            behind_s: None,
+            pct:      f64::NAN,
+        }
+    }
+
+    let actual_pace = 1000.0 / actual_ms;
+    let target_pace = 1000.0 / target_ms;
+    let gained = target_pace - actual_pace;
+
+    PaceDeviation {
+        ahead_s:  Some(gained).filter(|&s| s > 0.0),
+        behind_s: Some(-gained).filter(|&s| s > 0.0),
+        pct:      gained / target_pace * 100.0,
+    }
+}
+
+/// Time and distance of one lap, plus running totals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapSplit {
+    /// Counting from 1.
+    pub lap_num:               usize,
+    pub time_s:                f64,
+    pub distance_m:            f64,
+    /// Infinite for laps without any distance.
+    pub pace_min_per_km:       f64,
+    pub cumulative_time_s:     f64,
+    pub cumulative_distance_m: f64,
+}
+
+/// Splits for `laps`, in order.
+///
+/// Lap time is the timer time, falling back to elapsed time
+/// for laps that don't record it. Missing values count as
+/// zero.
+pub fn lap_splits(laps: &[Lap]) -> Vec<LapSplit> {
+    let mut cumulative_time_s = 0.0;
+    let mut cumulative_distance_m = 0.0;
+
+    laps.iter()
+        .enumerate()
+        .map(|(i, lap)| {
+            let time_s =
+                lap.total_timer_time.or(lap.total_elapsed_time).unwrap_or(0.0);
+            let distance_m = lap.total_distance.unwrap_or(0.0);
+            cumulative_time_s += time_s;
+            cumulative_distance_m += distance_m;
+
+            let pace_min_per_km = if distance_m > 0.0 {
+                (time_s / 60.0) / (distance_m / 1000.0)
+            }
+            else {
+                f64::INFINITY
+            };
+
+            LapSplit {
+                lap_num: i + 1,
+                time_s,
+                distance_m,
+                pace_min_per_km,
+                cumulative_time_s,
+                cumulative_distance_m,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_times() {
+        assert_eq!(format_time(5025.0), "1:23:45");
+        assert_eq!(format_time(3600.0), "1:00:00");
+        assert_eq!(format_time(3599.6), "1:00:00");
+        // Under an hour, and under a minute
+        assert_eq!(format_time(754.0), "0:12:34");
+        assert_eq!(format_time(45.0), "0:00:45");
+        assert_eq!(format_time(0.4), "0:00:00");
+        assert_eq!(format_time(-61.0), "-0:01:01");
+        assert_eq!(format_time(f64::NAN), "--:--:--");
+    }
+
+    #[test]
+    fn compares_pace() {
+        // 4:00/km against a 4:10/km target
+        let ahead = pace_vs_target(1000.0 / 240.0, 1000.0 / 250.0);
+        assert!((ahead.ahead_s.unwrap() - 10.0).abs() < 1e-9);
+        assert_eq!(ahead.behind_s, None);
+        assert!((ahead.pct - 4.0).abs() < 1e-9);
+
+        let behind = pace_vs_target(1000.0 / 260.0, 1000.0 / 250.0);
+        assert_eq!(behind.ahead_s, None);
+        assert!((behind.behind_s.unwrap() - 10.0).abs() < 1e-9);
+        assert!(behind.pct < 0.0);
+
+        let even = pace_vs_target(4.0, 4.0);
+        assert_eq!((even.ahead_s, even.behind_s, even.pct), (None, None, 0.0));
+
+        assert!(pace_vs_target(0.0, 4.0).pct.is_nan());
+    }
+
+    #[test]
+    fn cumulative_splits() {
+        let times = [300.0, 310.0, 295.0, 305.0, 290.0];
+        let laps: Vec<Lap> = times
+            .iter()
+            .map(|&time| {
+                Lap {
+                    total_timer_time: Some(time),
+                    total_distance: Some(1000.0),
+                    ..Lap::default()
+                }
+            })
+            .collect();
+
+        let splits = lap_splits(&laps);
+        assert_eq!(splits.len(), 5);
+        assert_eq!(splits[0].lap_num, 1);
+        assert_eq!(splits[0].pace_min_per_km, 5.0);
+
+        let cumulative: Vec<f64> =
+            splits.iter().map(|s| s.cumulative_time_s).collect();
+        assert_eq!(cumulative, vec![300.0, 610.0, 905.0, 1210.0, 1500.0]);
+        assert_eq!(splits[4].cumulative_distance_m, 5000.0);
+        assert_eq!(format_time(splits[4].cumulative_time_s), "0:25:00");
+    }
+}
@@ -0,0 +1,1492 @@
+//! Streaming FIT decoding over any `std::io::Read`.
+//!
+//! With the `tracing` feature the reader emits a span for
+//! the file and for each record, debug events describing
+//! each definition, and warnings with byte offsets for
+//! invalid fields, unknown messages, checksum mismatches
+//! and anything it recovers from. Without the feature none
+//! of it is compiled in.
+use byteorder::{
+    LittleEndian,
+    ReadBytesExt,
+};
+use consts::CRC_SIZE;
+use crc::CrcReader;
+use error::{
+    Error,
+    Result,
+};
+#[cfg(feature = "tracing")]
+use profile::messages::message_name;
+use profile::messages::{
+    FieldDescription,
+    Message as Field,
+};
+use stats::{
+    CrcStatus,
+    DecodeStats,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{
+        self,
+        BufRead,
+        BufReader,
+        Read,
+        Take,
+    },
+    path::Path,
+    sync::Mutex,
+    time::Instant,
+};
+use types::{
+    field::usable_scale,
+    file::Header,
+    record::{
+        Data,
+        Definition,
+        Header as RecordHeader,
+        Message,
+        Record,
+    },
+};
+
+/// Something a [`FitReader`] recovered from while decoding.
+///
+/// [`FitReader`]: struct.FitReader.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadWarning {
+    /// The data section ends in `length` zero bytes from
+    /// `offset` on, where a record should have started.
+    /// Some devices pad files this way.
+    ZeroPadding { offset: u64, length: u64 },
+    /// The definition at `offset` lists field
+    /// `field_def_num` of `mesg_num` more than once. Data
+    /// messages are decoded from its last occurrence, and
+    /// the bytes of the others skipped.
+    DuplicateField { offset: u64, mesg_num: u16, field_def_num: u8 },
+    /// The record header `header` at `offset` sets bits the
+    /// file's protocol version reserves. The record was
+    /// decoded as if they were clear.
+    ReservedHeaderBits { offset: u64, header: u8 },
+    /// The `FieldDescription` at `offset` gives its
+    /// developer field a scale of zero, which some buggy
+    /// apps write. The field's values are left unscaled.
+    UnusableScale {
+        offset:                  u64,
+        developer_data_index:    u8,
+        field_definition_number: u8,
+    },
+}
+
+/// How far a [`FitReader`] has got through its input.
+///
+/// [`FitReader`]: struct.FitReader.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes decoded so far, header and CRC included.
+    pub bytes_read:  u64,
+    /// Bytes in all, as the file header declares them, or
+    /// the stream length given to
+    /// [`within_stream`](struct.FitReader.html#method.within_stream).
+    pub total_bytes: u64,
+}
+
+impl Progress {
+    /// The share of the input decoded, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        }
+        else {
+            self.bytes_read as f64 / self.total_bytes as f64
+        }
+    }
+}
+
+/// A progress callback and how often to call it.
+struct ProgressCallback {
+    every_bytes: u64,
+    /// Report once `bytes_read` reaches this.
+    next:        u64,
+    // Only ever called through `&mut`, the lock just makes
+    // the reader `Sync`
+    callback:    Mutex<Box<dyn FnMut(Progress) + Send>>,
+}
+
+/// Capacity of the internal buffer unless told otherwise.
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Decodes records one at a time from a reader.
+///
+/// Reads are buffered internally, but the buffer is never
+/// filled beyond the end of the FIT file as declared by its
+/// header. Once the iterator is exhausted the underlying
+/// reader is positioned exactly after the trailing CRC, so
+/// a FIT file embedded in a larger stream can be decoded
+/// without disturbing what follows it (see
+/// [`into_inner`](#method.into_inner)).
+///
+/// The iterator yields an error and then stops if a record
+/// fails to decode or the file checksum doesn't match.
+///
+/// Some writers leave the header's data size at zero and
+/// rely on the end of the input instead. Those files are
+/// read to the end, and their last two bytes taken as the
+/// CRC, so nothing can follow them in the same stream.
+///
+/// All decoding state lives in the reader, so separate
+/// readers can decode files on separate threads, and the
+/// records they yield are `Send` and `Sync`.
+pub struct FitReader<R> {
+    header:      Header,
+    inner:       Tracked<BufReader<HoldBack<Take<R>>>>,
+    local_mesgs: HashMap<u8, Definition>,
+    count:       u64,
+    done:        bool,
+    stats:       Option<DecodeStats>,
+    skip_zeros:  bool,
+    strict:      bool,
+    warnings:    Vec<ReadWarning>,
+    crc_read:    bool,
+    /// Where the file starts in the stream, and how long
+    /// the stream is.
+    stream:      (u64, Option<u64>),
+    progress:    Option<ProgressCallback>,
+    #[cfg(feature = "tracing")]
+    span:        ::tracing::Span,
+}
+
+impl<R: Read> FitReader<R> {
+    /// Read the file header from `reader` and prepare to
+    /// decode records, buffering with the default capacity.
+    pub fn new(reader: R) -> Result<Self> {
+        FitReader::with_capacity(DEFAULT_BUFFER_SIZE, reader)
+    }
+
+    /// Like [`new`](#method.new) but with an internal
+    /// buffer of `capacity` bytes.
+    pub fn with_capacity(capacity: usize, mut reader: R) -> Result<Self> {
+        let (header, position, crc) = {
+            let mut tracked = Tracked::new(&mut reader);
+            let header = Header::decode(&mut tracked)
+                .map_err(Error::decoding("file header"))?;
+            (header, tracked.position, tracked.inner.crc())
+        };
+
+        #[cfg(feature = "tracing")]
+        let span = info_span!(
+            "fit_file",
+            header_size = header.size(),
+            data_size = header.data_size(),
+            protocol = ?header.protocol_version(),
+            profile = ?header.profile_version(),
+        );
+
+        let to_eof = header.data_size() == 0;
+        let remaining = if to_eof {
+            u64::MAX
+        }
+        else {
+            u64::from(header.data_size()) + u64::from(CRC_SIZE)
+        };
+        let buffered = BufReader::with_capacity(
+            capacity,
+            HoldBack::new(reader.take(remaining), to_eof),
+        );
+
+        Ok(FitReader {
+            header,
+            inner: Tracked {
+                inner: CrcReader::with_crc(buffered, crc),
+                position,
+            },
+            local_mesgs: HashMap::new(),
+            count: 1,
+            done: false,
+            stats: None,
+            skip_zeros: false,
+            strict: false,
+            warnings: Vec::new(),
+            crc_read: false,
+            stream: (0, None),
+            progress: None,
+            #[cfg(feature = "tracing")]
+            span,
+        })
+    }
+
+    /// Collect [`DecodeStats`] while decoding. The counters
+    /// are cheap, but off unless asked for.
+    ///
+    /// [`DecodeStats`]: ../stats/struct.DecodeStats.html
+    pub fn with_stats(mut self) -> Self {
+        self.stats = Some(DecodeStats {
+            bytes_read: self.offset(),
+            ..DecodeStats::default()
+        });
+        self
+    }
+
+    /// Treat zero bytes running to the end of the data
+    /// section as padding, when local message 0 isn't
+    /// defined so they can't be records. They're skipped
+    /// with a [`ReadWarning::ZeroPadding`] and decoding
+    /// goes on to the CRC, which still covers them.
+    ///
+    /// Anything else where a record should be is an error
+    /// as usual.
+    ///
+    /// [`ReadWarning::ZeroPadding`]: enum.ReadWarning.html#variant.ZeroPadding
+    pub fn skip_zero_padding(mut self) -> Self {
+        self.skip_zeros = true;
+        self
+    }
+
+    /// Fail on record headers that set reserved bits,
+    /// rather than decoding them with a
+    /// [`ReadWarning::ReservedHeaderBits`].
+    ///
+    /// A header with reserved bits set is most often the
+    /// first sign of a corrupt stream or of a newer protocol
+    /// than this crate knows, where carrying on would
+    /// misread every record after it.
+    ///
+    /// [`ReadWarning::ReservedHeaderBits`]: enum.ReadWarning.html#variant.ReservedHeaderBits
+    pub fn strict_headers(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Call `callback` as decoding goes on, each time
+    /// another `every_bytes` have been decoded and once
+    /// more when the CRC has been checked, for a progress
+    /// bar.
+    ///
+    /// Reports come between records, so a record longer
+    /// than `every_bytes` covers several steps in one.
+    /// Their cost is negligible at a few kilobytes per
+    /// step.
+    pub fn on_progress<F>(mut self, every_bytes: u64, callback: F) -> Self
+    where
+        F: FnMut(Progress) + Send + 'static,
+    {
+        let every_bytes = every_bytes.max(1);
+        self.progress = Some(ProgressCallback {
+            every_bytes,
+            next: self.progress().bytes_read + every_bytes,
+            callback: Mutex::new(Box::new(callback)),
+        });
+        self
+    }
+
+    /// Report [`Progress`] through a stream of chained
+    /// files of `stream_len` bytes, this one starting
+    /// `offset` bytes in, rather than through this file
+    /// alone.
+    ///
+    /// [`Progress`]: struct.Progress.html
+    pub fn within_stream(mut self, offset: u64, stream_len: u64) -> Self {
+        self.stream = (offset, Some(stream_len));
+        if let Some(ref mut progress) = self.progress {
+            progress.next += offset;
+        }
+        self
+    }
+
+    /// How far decoding has got.
+    pub fn progress(&self) -> Progress {
+        let crc = if self.crc_read { u64::from(CRC_SIZE) } else { 0 };
+        let (offset, stream_len) = self.stream;
+        // Until a file without a data size ends, it's as
+        // long as what's been read
+        let file_len = self.data_end().unwrap_or_else(|| self.offset())
+            + u64::from(CRC_SIZE);
+        Progress {
+            bytes_read:  offset + self.offset() + crc,
+            total_bytes: stream_len.unwrap_or(offset + file_len),
+        }
+    }
+
+    /// Call the progress callback if another step has been
+    /// decoded, or unconditionally at the end.
+    fn report_progress(&mut self) {
+        let progress = self.progress();
+        let callback = match self.progress {
+            Some(ref mut callback) => callback,
+            None => return,
+        };
+        if progress.bytes_read < callback.next && !self.crc_read {
+            return
+        }
+        let steps = progress.bytes_read.saturating_sub(callback.next)
+            / callback.every_bytes
+            + 1;
+        callback.next += steps * callback.every_bytes;
+        if let Ok(callback) = callback.callback.get_mut() {
+            callback(progress);
+        }
+    }
+
+    /// What decoding recovered from so far.
+    pub fn warnings(&self) -> &[ReadWarning] {
+        &self.warnings
+    }
+
+    /// Statistics gathered so far, if enabled with
+    /// [`with_stats`](#method.with_stats).
+    pub fn stats(&self) -> Option<&DecodeStats> {
+        self.stats.as_ref()
+    }
+
+    /// The file header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Decode the remaining records, keeping only the data
+    /// messages.
+    pub fn read_messages(&mut self) -> Result<Vec<Data>> {
+        let mut messages = Vec::new();
+        for record in self {
+            if let Message::Data(data) = record?.content {
+                messages.push(data);
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Number of bytes consumed so far, including the
+    /// header.
+    pub fn offset(&self) -> u64 {
+        self.inner.position
+    }
+
+    /// Give back the underlying reader.
+    ///
+    /// If the iterator has been run to completion this is
+    /// positioned right after the FIT file's CRC.
+    pub fn into_inner(self) -> R {
+        self.inner.inner.into_inner().into_inner().inner.into_inner()
+    }
+
+    /// Where the data section ends, unless the header
+    /// leaves that to the end of the input.
+    fn data_end(&self) -> Option<u64> {
+        match self.header.data_size() {
+            0 => None,
+            size => Some(u64::from(self.header.size()) + u64::from(size)),
+        }
+    }
+
+    /// Whether the data section has been read.
+    fn at_data_end(&mut self) -> bool {
+        match self.data_end() {
+            Some(end) => self.offset() >= end,
+            None => self.peek().is_none(),
+        }
+    }
+
+    fn check_crc(&mut self) -> Result<()> {
+        let computed = self.inner.inner.crc();
+        // Past the checksummed bytes
+        let expected = match self.data_end() {
+            Some(_) => self.inner.inner.get_mut().read_u16::<LittleEndian>(),
+            None => self.inner.inner.get_mut().get_mut().held_crc(),
+        }
+        .map_err(Error::reading("crc"))?;
+        self.crc_read = true;
+        let matches = expected == computed;
+        if let Some(ref mut stats) = self.stats {
+            stats.bytes_read = self.inner.position + u64::from(CRC_SIZE);
+            stats.crc =
+                if matches { CrcStatus::Valid } else { CrcStatus::Mismatch };
+        }
+        if !matches {
+            #[cfg(feature = "tracing")]
+            error!(expected, computed, offset = self.offset(), "crc mismatch");
+            return Err(Error::crc_mismatch(expected, computed))
+        }
+        #[cfg(feature = "tracing")]
+        debug!(crc = computed, "crc valid");
+        Ok(())
+    }
+
+    /// Whether the next record would be a zero byte with no
+    /// definition to decode it, which makes it padding if
+    /// nothing else follows.
+    fn at_padding(&mut self) -> bool {
+        if !self.skip_zeros || self.local_mesgs.contains_key(&0) {
+            return false
+        }
+        self.peek() == Some(0)
+    }
+
+    /// The next byte, without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        match self.inner.inner.get_mut().fill_buf() {
+            Ok(buf) => buf.first().cloned(),
+            Err(_) => None,
+        }
+    }
+
+    /// Check the header of the next record for reserved
+    /// bits, failing if strict and warning otherwise.
+    fn check_reserved_bits(&mut self) -> Result<()> {
+        let header = match self.peek() {
+            Some(header) => header,
+            None => return Ok(()),
+        };
+        let protocol = self.header.protocol_version();
+        if RecordHeader::reserved_bits(header, protocol) == 0 {
+            return Ok(())
+        }
+        if self.strict {
+            return Err(Error::reserved_header_bits(header))
+        }
+        let offset = self.offset();
+        #[cfg(feature = "tracing")]
+        warn!(header, offset, "reserved record header bits set");
+        self.warnings.push(ReadWarning::ReservedHeaderBits {
+            offset,
+            header,
+        });
+        Ok(())
+    }
+
+    /// Consume the rest of the data section, failing as a
+    /// record would if any of it isn't zero.
+    fn skip_padding(&mut self) -> Result<()> {
+        let offset = self.offset();
+        let mut buf = [0; 256];
+        let mut left = self.data_end().map_or(u64::MAX, |end| end - offset);
+        while left > 0 {
+            let n = left.min(buf.len() as u64) as usize;
+            let n = match self.data_end() {
+                Some(_) => self.inner.read_exact(&mut buf[..n]).map(|()| n),
+                None => self.inner.read(&mut buf[..n]),
+            }
+            .map_err(Error::reading("padding"))?;
+            if n == 0 {
+                break
+            }
+            if buf[..n].iter().any(|&b| b != 0) {
+                return Err(Error::missing_definition(0))
+            }
+            left -= n as u64;
+        }
+        let length = self.offset() - offset;
+        if let Some(ref mut stats) = self.stats {
+            stats.bytes_read = self.inner.position;
+        }
+        #[cfg(feature = "tracing")]
+        warn!(offset, length, "skipping zero padding");
+        self.warnings.push(ReadWarning::ZeroPadding {
+            offset,
+            length,
+        });
+        Ok(())
+    }
+
+    fn decode_record(&mut self) -> Result<Record> {
+        self.check_reserved_bits()?;
+        let started = self.stats.as_ref().map(|_| Instant::now());
+        let record = Record::decode(
+            &mut self.inner,
+            &self.local_mesgs,
+            self.stats.as_mut(),
+        )?;
+
+        let stats = match self.stats {
+            Some(ref mut stats) => stats,
+            None => return Ok(record),
+        };
+        if let Some(started) = started {
+            stats.elapsed += started.elapsed();
+        }
+        stats.bytes_read = self.inner.position;
+        match record.content {
+            Message::Definition(_) => {
+                stats.definitions += 1;
+                let local_mesg_num = record.header.local_mesg_num();
+                if self.local_mesgs.contains_key(&local_mesg_num) {
+                    stats.redefinitions += 1;
+                }
+            },
+            Message::Data(ref data) => {
+                stats.data_messages += 1;
+                let mesg_num = self
+                    .local_mesgs
+                    .get(&record.header.local_mesg_num())
+                    .map(Definition::global_mesg_num);
+                if let Some(mesg_num) = mesg_num {
+                    *stats.messages_by_type.entry(mesg_num).or_insert(0) += 1;
+                    if let Some(Field::Unknown {
+                        ..
+                    }) = data.0.first()
+                    {
+                        *stats.unknown_messages.entry(mesg_num).or_insert(0) +=
+                            1;
+                    }
+                }
+            },
+            Message::CompressedTimestamp => stats.compressed_timestamps += 1,
+        }
+        Ok(record)
+    }
+
+    /// Events describing `record`, which started at
+    /// `offset`.
+    #[cfg(feature = "tracing")]
+    fn trace_record(&self, record: &Record, offset: u64) {
+        let local_type = record.header.local_mesg_num();
+        match record.content {
+            Message::Definition(ref definition) => {
+                let mesg_num = definition.global_mesg_num();
+                debug!(
+                    local_type,
+                    mesg_num,
+                    fields = definition.field_defs().len(),
+                    dev_fields = definition.total_fields()
+                        - definition.field_defs().len(),
+                    big_endian = definition.is_big_endian(),
+                    offset,
+                    "definition"
+                );
+                if message_name(mesg_num).is_none() {
+                    warn!(local_type, mesg_num, offset, "unknown message");
+                }
+            },
+            Message::Data(_) => {
+                let definition = match self.local_mesgs.get(&local_type) {
+                    Some(definition) => definition,
+                    None => return,
+                };
+                let mesg_num = definition.global_mesg_num();
+                trace!(local_type, mesg_num, offset, "data");
+                // Past the record header
+                let mut field_offset = offset + 1;
+                let fields =
+                    definition.field_defs().iter().zip(record.presence.iter());
+                for (field_def, (field_def_num, is_valid)) in fields {
+                    if !is_valid {
+                        warn!(
+                            local_type,
+                            mesg_num,
+                            field_def_num,
+                            offset = field_offset,
+                            "invalid field"
+                        );
+                    }
+                    field_offset += u64::from(field_def.size());
+                }
+            },
+            Message::CompressedTimestamp => {
+                trace!(local_type, offset, "compressed timestamp")
+            },
+        }
+    }
+}
+
+impl<R: Read> Iterator for FitReader<R> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+        #[cfg(feature = "tracing")]
+        let _file = self.span.clone().entered();
+
+        if self.at_data_end() {
+            self.done = true;
+            let checked = self.check_crc();
+            self.report_progress();
+            return checked.err().map(Err)
+        }
+
+        if self.at_padding() {
+            self.done = true;
+            let skipped = self
+                .skip_padding()
+                .map_err(Error::decoding(format!("record #{}", self.count)));
+            let checked = skipped.and_then(|()| self.check_crc());
+            self.report_progress();
+            return checked.err().map(Err)
+        }
+
+        let offset = self.offset();
+        #[cfg(feature = "tracing")]
+        let _record =
+            debug_span!("record", index = self.count, offset).entered();
+
+        let record = self
+            .decode_record()
+            .map_err(Error::decoding(format!("record #{}", self.count)));
+
+        match record {
+            Ok(record) => {
+                #[cfg(feature = "tracing")]
+                self.trace_record(&record, offset);
+                // If we got a definition message we need
+                // to add it to the `local_mesgs` map
+                if let Message::Definition(ref mesg) = record.content {
+                    let mesg_num = mesg.global_mesg_num();
+                    for field_def_num in mesg.duplicate_field_nums() {
+                        #[cfg(feature = "tracing")]
+                        warn!(
+                            mesg_num,
+                            field_def_num, offset, "duplicate field"
+                        );
+                        self.warnings.push(ReadWarning::DuplicateField {
+                            offset,
+                            mesg_num,
+                            field_def_num,
+                        });
+                    }
+                    self.local_mesgs
+                        .insert(record.header.local_mesg_num(), mesg.clone());
+                }
+                let unusable = match record.content {
+                    Message::Data(ref data) => unusable_scale(data),
+                    _ => None,
+                };
+                if let Some((index, num)) = unusable {
+                    #[cfg(feature = "tracing")]
+                    warn!(
+                        developer_data_index = index,
+                        field_definition_number = num,
+                        offset,
+                        "unusable developer field scale"
+                    );
+                    self.warnings.push(ReadWarning::UnusableScale {
+                        offset,
+                        developer_data_index: index,
+                        field_definition_number: num,
+                    });
+                }
+                self.count += 1;
+                self.report_progress();
+                Some(Ok(record))
+            },
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                error!(offset, error = %err, "record failed to decode");
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// The developer data index and field definition number a
+/// `FieldDescription` message describes, if it gives them
+/// a scale that can't be used.
+fn unusable_scale(data: &Data) -> Option<(u8, u8)> {
+    let mut index = None;
+    let mut num = None;
+    let mut unusable = false;
+    for field in data.0.iter() {
+        match field {
+            Field::FieldDescription(FieldDescription::DeveloperDataIndex(f))
+                if f.is_valid() =>
+            {
+                index = Some(f.raw_value.0)
+            },
+            Field::FieldDescription(FieldDescription::FieldDefinitionNumber(
+                f,
+            )) if f.is_valid() => num = Some(f.raw_value.0),
+            Field::FieldDescription(FieldDescription::Scale(f))
+                if f.is_valid() =>
+            {
+                let scale = Some(f64::from(f.raw_value.0));
+                unusable = usable_scale(scale).is_none()
+            },
+            Field::FieldDescription(_) => (),
+            _ => return None,
+        }
+    }
+    if unusable {
+        Some((index?, num?))
+    }
+    else {
+        None
+    }
+}
+
+/// Decode the data messages of the FIT file at `path`,
+/// checking its CRC.
+///
+/// This and [`from_bytes`] and [`from_reader`] are the
+/// "just give me the messages" way in, with default options
+/// throughout. Use a [`FitReader`] to go record by record
+/// or skip padding, or a [`File`] to keep the definitions.
+///
+/// ```no_run
+/// # fn main() -> garminfit::Result<()> {
+/// let messages = garminfit::from_file("activity.fit")?;
+/// println!("{} messages", messages.len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`from_bytes`]: fn.from_bytes.html
+/// [`from_reader`]: fn.from_reader.html
+/// [`FitReader`]: struct.FitReader.html
+/// [`File`]: ../types/file/struct.File.html
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Vec<Data>> {
+    let file = fs::File::open(path).map_err(Error::reading("fit file"))?;
+    from_reader(file)
+}
+
+/// Decode the data messages of a FIT file held in memory,
+/// checking its CRC.
+///
+/// ```
+/// use garminfit::{
+///     messages::FileId,
+///     profile::types::File,
+/// };
+///
+/// # fn main() -> garminfit::Result<()> {
+/// // An activity file with nothing but its `FileId`
+/// let bytes = [
+///     // Header
+///     12, 0x10, 0x08, 0x08, 11, 0, 0, 0, b'.', b'F', b'I', b'T',
+///     // Definition and data message
+///     0x40, 0, 0, 0, 0, 1, 0, 1, 0x00, 0x00, 4,
+///     // CRC
+///     0xE5, 0xCF,
+/// ];
+/// let messages = garminfit::from_bytes(&bytes)?;
+/// assert_eq!(messages.len(), 1);
+/// let file_type = FileId::type_(&messages[0].0).unwrap();
+/// assert!(matches!(file_type.raw_value, File::Activity));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Data>> {
+    from_reader(bytes)
+}
+
+/// Decode the data messages of the FIT file `reader` holds,
+/// checking its CRC.
+///
+/// Nothing past the CRC is read, but `reader` is dropped;
+/// use a [`FitReader`] to get it back.
+///
+/// ```
+/// use garminfit::ErrorKind;
+/// use std::io::Cursor;
+///
+/// let mut bytes = vec![
+///     // Header
+///     12, 0x10, 0x08, 0x08, 11, 0, 0, 0, b'.', b'F', b'I', b'T',
+///     // Definition and data message
+///     0x40, 0, 0, 0, 0, 1, 0, 1, 0x00, 0x00, 4,
+///     // CRC
+///     0xE5, 0xCF,
+/// ];
+/// assert!(garminfit::from_reader(Cursor::new(&bytes)).is_ok());
+///
+/// bytes[23] ^= 0xFF;
+/// let err = garminfit::from_reader(Cursor::new(&bytes)).unwrap_err();
+/// assert!(matches!(err.kind(), ErrorKind::CrcMismatch { .. }));
+/// ```
+///
+/// [`FitReader`]: struct.FitReader.html
+pub fn from_reader<R: Read>(reader: R) -> Result<Vec<Data>> {
+    FitReader::new(reader)?.read_messages()
+}
+
+/// Holds back the last two bytes read, when a file's header
+/// leaves its data size at zero: they're its CRC, but that
+/// only shows once the input ends. Otherwise it reads
+/// straight through.
+struct HoldBack<R> {
+    inner:  R,
+    active: bool,
+    held:   [u8; CRC_SIZE as usize],
+    /// How many of `held` are.
+    len:    usize,
+}
+
+impl<R> HoldBack<R> {
+    fn new(inner: R, active: bool) -> Self {
+        HoldBack {
+            inner,
+            active,
+            held: [0; CRC_SIZE as usize],
+            len: 0,
+        }
+    }
+
+    /// The bytes held back once the input has ended, as a
+    /// CRC.
+    fn held_crc(&self) -> io::Result<u16> {
+        if self.len < self.held.len() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+        }
+        Ok(u16::from_le_bytes(self.held))
+    }
+}
+
+impl<R: Read> Read for HoldBack<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.active || buf.is_empty() {
+            return self.inner.read(buf)
+        }
+        loop {
+            let n = self.inner.read(buf)?;
+            if n == 0 {
+                return Ok(0)
+            }
+            // The held bytes come first, then what was just
+            // read, and the last two of those stay held
+            let held = self.len;
+            let total = held + n;
+            let out = total.saturating_sub(self.held.len());
+            let mut tail = [0; CRC_SIZE as usize];
+            for (j, i) in (out..total).enumerate() {
+                tail[j] = if i < held { self.held[i] } else { buf[i - held] };
+            }
+            if out > held {
+                buf.copy_within(0..out - held, held);
+            }
+            let prefix = out.min(held);
+            buf[..prefix].copy_from_slice(&self.held[..prefix]);
+            self.held = tail;
+            self.len = total - out;
+            if out > 0 {
+                return Ok(out)
+            }
+        }
+    }
+}
+
+/// Counts and checksums the bytes read through it.
+struct Tracked<R> {
+    inner:    CrcReader<R>,
+    position: u64,
+}
+
+impl<R> Tracked<R> {
+    fn new(inner: R) -> Self {
+        Tracked {
+            inner: CrcReader::new(inner), position: 0
+        }
+    }
+}
+
+impl<R: Read> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position += n as u64;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use failure::Fail;
+    use presence::FieldPresence;
+    use profile::messages::Record as RecordField;
+    use std::io::Cursor;
+    use testutil::{
+        duplicate_field_file,
+        without_data_size,
+        FitBuilder,
+    };
+    use types::field::Field as _;
+
+    fn activity() -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        for i in 0..100u32 {
+            let ts = 1000 + i;
+            fit.data(0, &[&ts.to_le_bytes()[..], &[120 + (i % 50) as u8]]);
+        }
+        fit.build()
+    }
+
+    #[test]
+    fn stops_after_crc() {
+        let bytes = activity();
+        let trailer = b"not part of the fit file";
+        let chained =
+            Cursor::new(bytes.clone()).chain(Cursor::new(&trailer[..]));
+
+        let mut reader = FitReader::with_capacity(16, chained).unwrap();
+        let records = reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(records.len(), 101);
+        assert_eq!(reader.offset(), bytes.len() as u64 - 2);
+
+        let mut rest = Vec::new();
+        reader.into_inner().read_to_end(&mut rest).unwrap();
+        assert_eq!(&rest[..], &trailer[..]);
+    }
+
+    #[test]
+    fn detects_crc_mismatch() {
+        let mut bytes = activity();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        let result = FitReader::new(Cursor::new(bytes))
+            .unwrap()
+            .collect::<Result<Vec<_>>>();
+        match result {
+            Err(ref err) => {
+                match err.kind() {
+                    ErrorKind::CrcMismatch {
+                        ..
+                    } => (),
+                    _ => panic!("expected a CRC mismatch, got {}", err),
+                }
+            },
+            Ok(_) => panic!("expected a CRC mismatch"),
+        }
+    }
+
+    fn decode_all(bytes: &[u8]) -> Result<Vec<Record>> {
+        FitReader::new(Cursor::new(bytes))?.collect()
+    }
+
+    fn assert_short_buffer(bytes: &[u8], expected: usize, got: usize) {
+        let err = decode_all(bytes).unwrap_err();
+        let root = (&err as &dyn Fail)
+            .find_root_cause()
+            .downcast_ref::<Error>()
+            .map(Error::kind);
+        match root {
+            Some(&ErrorKind::ShortBuffer {
+                expected: e,
+                got: g,
+            }) if (e, g) == (expected, got) => (),
+            _ => panic!("expected a short buffer error, got {}", err),
+        }
+    }
+
+    #[test]
+    fn short_field_is_an_error() {
+        let mut fit = FitBuilder::new();
+        // A heart rate field declared with no bytes at all
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 0, 0x02)]);
+        fit.data(0, &[&1000u32.to_le_bytes()[..]]);
+        assert_short_buffer(&fit.build(), 1, 0);
+
+        // A timestamp declared with only two bytes
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 2, 0x86)]);
+        fit.data(0, &[&[0xE8, 0x03]]);
+        assert_short_buffer(&fit.build(), 4, 2);
+    }
+
+    #[test]
+    fn mangled_input_never_panics() {
+        let bytes = activity();
+        for len in 0..bytes.len() {
+            assert!(decode_all(&bytes[..len]).is_err());
+        }
+        for i in 0..bytes.len() {
+            let mut mangled = bytes.clone();
+            mangled[i] = !mangled[i];
+            let _ = decode_all(&mangled);
+        }
+    }
+
+    /// Records under local message 1, followed by `padding`
+    /// inside the data section, as a Lezyne unit writes.
+    fn padded(padding: &[u8]) -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(1, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        for ts in 1000..1010u32 {
+            fit.data(1, &[&ts.to_le_bytes()[..], &[130]]);
+        }
+        fit.raw(padding);
+        fit.build()
+    }
+
+    #[test]
+    fn skips_zero_padding() {
+        let bytes = padded(&[0; 300]);
+        assert!(decode_all(&bytes).is_err());
+
+        let mut reader = FitReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .skip_zero_padding()
+            .with_stats();
+        let records = reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(records.len(), 11);
+        assert_eq!(
+            reader.warnings(),
+            &[ReadWarning::ZeroPadding {
+                offset: bytes.len() as u64 - 302,
+                length: 300,
+            }]
+        );
+        assert_eq!(reader.stats().unwrap().crc, CrcStatus::Valid);
+
+        // Anything but zeros is still an error
+        let mut garbage = vec![0; 300];
+        garbage[299] = 1;
+        let bytes = padded(&garbage);
+        let result = FitReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .skip_zero_padding()
+            .collect::<Result<Vec<_>>>();
+        assert!(result.is_err());
+
+        // As is zero padding when local message 0 is defined
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86)]);
+        fit.raw(&[0; 3]);
+        let bytes = fit.build();
+        let mut reader =
+            FitReader::new(Cursor::new(&bytes)).unwrap().skip_zero_padding();
+        assert!(reader.by_ref().any(|record| record.is_err()));
+        assert!(reader.warnings().is_empty());
+    }
+
+    #[test]
+    fn duplicate_fields_keep_alignment() {
+        let bytes = duplicate_field_file();
+        let mut reader = FitReader::new(Cursor::new(&bytes)).unwrap();
+        let records = reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            reader.warnings(),
+            &[
+                ReadWarning::DuplicateField {
+                    offset:        14,
+                    mesg_num:      20,
+                    field_def_num: 253,
+                },
+                ReadWarning::DuplicateField {
+                    offset:        14,
+                    mesg_num:      20,
+                    field_def_num: 3,
+                },
+            ]
+        );
+
+        // The fields after the duplicates still line up
+        let data = match records[1].content {
+            Message::Data(ref data) => data,
+            _ => panic!("expected a data message"),
+        };
+        assert_eq!(data.0.len(), 4);
+        let fields = &data.0;
+        assert_eq!(RecordField::timestamp(fields).unwrap().raw_value.0, 1000);
+        assert_eq!(RecordField::heart_rate(fields).unwrap().raw_value.0, 150);
+        assert_eq!(RecordField::speed(fields).unwrap().value(), 5.25);
+        assert_eq!(RecordField::power(fields).unwrap().raw_value.0, 250);
+        assert_eq!(records[1].presence.presence(3), FieldPresence::Present);
+    }
+
+    #[test]
+    fn unusable_developer_scale() {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 206, &[(0, 1, 0x02), (1, 1, 0x02), (6, 1, 0x02)]);
+        fit.data(0, &[&[0], &[3], &[0]]);
+        fit.data(0, &[&[0], &[4], &[10]]);
+        fit.data(0, &[&[0], &[5], &[0xFF]]);
+        let bytes = fit.build();
+
+        let mut reader = FitReader::new(Cursor::new(&bytes)).unwrap();
+        let records = reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(
+            reader.warnings(),
+            &[ReadWarning::UnusableScale {
+                offset:                  29,
+                developer_data_index:    0,
+                field_definition_number: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn reserved_header_bits() {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(3, 1, 0x02)]);
+        // Data headers with reserved bit 5 and then bit 4 set
+        fit.raw(&[0x20, 150]).raw(&[0x10, 151]).data(0, &[&[152]]);
+        let bytes = fit.build();
+
+        let mut reader = FitReader::new(Cursor::new(&bytes)).unwrap();
+        let records = reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        let heart_rates: Vec<u8> = records[1..]
+            .iter()
+            .map(|record| {
+                match record.content {
+                    Message::Data(ref data) => {
+                        RecordField::heart_rate(&data.0).unwrap().raw_value.0
+                    },
+                    _ => panic!("expected a data message"),
+                }
+            })
+            .collect();
+        assert_eq!(heart_rates, vec![150, 151, 152]);
+        assert_eq!(
+            reader.warnings(),
+            &[
+                ReadWarning::ReservedHeaderBits {
+                    offset: 23, header: 0x20
+                },
+                ReadWarning::ReservedHeaderBits {
+                    offset: 25, header: 0x10
+                },
+            ]
+        );
+
+        let mut strict =
+            FitReader::new(Cursor::new(&bytes)).unwrap().strict_headers();
+        assert!(strict.next().unwrap().is_ok());
+        let err = strict.next().unwrap().unwrap_err();
+        let root = (&err as &dyn Fail)
+            .find_root_cause()
+            .downcast_ref::<Error>()
+            .map(Error::kind);
+        assert!(matches!(
+            root,
+            Some(&ErrorKind::ReservedHeaderBits {
+                header: 0x20
+            })
+        ));
+        assert!(strict.next().is_none());
+    }
+
+    #[test]
+    fn reports_progress() {
+        use std::sync::Arc;
+
+        let bytes = activity();
+        let len = bytes.len() as u64;
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&reports);
+        let mut reader = FitReader::new(Cursor::new(&bytes))
+            .unwrap()
+            .on_progress(100, move |progress| {
+                log.lock().unwrap().push(progress)
+            });
+        assert_eq!(
+            reader.progress(),
+            Progress {
+                bytes_read: 14, total_bytes: len
+            }
+        );
+        assert_eq!(reader.by_ref().count(), 101);
+        assert_eq!(reader.progress().fraction(), 1.0);
+
+        // Every 100 bytes from the header on, then the end
+        let reports = reports.lock().unwrap();
+        let read: Vec<u64> = reports.iter().map(|p| p.bytes_read).collect();
+        assert_eq!(read, vec![116, 218, 314, 416, 518, 614, len]);
+        assert!(reports.iter().all(|p| p.total_bytes == len));
+
+        // Two files back to back, reported as one stream
+        let mut stream = bytes.clone();
+        stream.extend_from_slice(&bytes);
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let mut cursor = Cursor::new(&stream);
+        while cursor.position() < stream.len() as u64 {
+            let log = Arc::clone(&reports);
+            let start = cursor.position();
+            let reader = FitReader::new(&mut cursor)
+                .unwrap()
+                .on_progress(100, move |p| log.lock().unwrap().push(p))
+                .within_stream(start, stream.len() as u64);
+            assert_eq!(reader.count(), 101);
+        }
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 14);
+        assert!(reports.windows(2).all(|w| w[0].bytes_read < w[1].bytes_read));
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                bytes_read: 2 * len, total_bytes: 2 * len
+            })
+        );
+    }
+
+    #[test]
+    fn collects_stats() {
+        let mut fit = FitBuilder::new();
+        // Record with an unknown field 200 and an invalid heart
+        // rate
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02), (200, 1, 0x02)]);
+        fit.data(0, &[&1000u32.to_le_bytes()[..], &[150], &[1]]);
+        fit.data(0, &[&1001u32.to_le_bytes()[..], &[0xFF], &[1]]);
+        // An unknown message reusing local message 0
+        fit.definition(0, 0xFF00, &[(0, 2, 0x84)]);
+        fit.data(0, &[&[1, 2]]);
+        fit.data(0, &[&[3, 4]]);
+        fit.raw(&[0x80 | 0x01]);
+        let bytes = fit.build();
+
+        let mut reader =
+            FitReader::new(Cursor::new(&bytes)).unwrap().with_stats();
+        assert_eq!(reader.by_ref().count(), 7);
+
+        let stats = reader.stats().unwrap();
+        assert_eq!(stats.bytes_read, bytes.len() as u64);
+        assert_eq!(stats.crc, CrcStatus::Valid);
+        assert_eq!((stats.definitions, stats.redefinitions), (2, 1));
+        assert_eq!(stats.data_messages, 4);
+        assert_eq!(stats.compressed_timestamps, 1);
+        assert_eq!(stats.messages_by_type.get(&20), Some(&2));
+        assert_eq!(stats.messages_by_type.get(&0xFF00), Some(&2));
+        assert_eq!(stats.unknown_messages.len(), 1);
+        assert_eq!(stats.unknown_messages.get(&0xFF00), Some(&2));
+        assert_eq!(stats.unknown_fields, 2);
+        assert_eq!(stats.invalid_fields, 1);
+
+        assert!(FitReader::new(Cursor::new(&bytes)).unwrap().stats().is_none());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn traces_decoding() {
+        extern crate tracing_subscriber;
+
+        use std::sync::{
+            Arc,
+            Mutex,
+        };
+
+        #[derive(Clone, Default)]
+        struct Log(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for Log {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut fit = FitBuilder::new();
+        fit.definition(1, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        fit.data(1, &[&1000u32.to_le_bytes()[..], &[0xFF]]);
+        fit.definition(2, 0xFF00, &[(0, 2, 0x84)]);
+        fit.data(2, &[&[1, 2]]);
+        fit.raw(&[0; 4]);
+        let mut bytes = fit.build();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        let log = Log::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(::tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        ::tracing::subscriber::with_default(subscriber, || {
+            let result = FitReader::new(Cursor::new(&bytes))
+                .unwrap()
+                .skip_zero_padding()
+                .collect::<Result<Vec<_>>>();
+            assert!(result.is_err());
+        });
+
+        let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        let line = |message: &str| {
+            lines
+                .iter()
+                .find(|line| line.contains(message))
+                .unwrap_or_else(|| panic!("no {:?} in\n{}", message, log))
+                .to_string()
+        };
+        // The first definition follows the 14 byte header,
+        // and takes its own header byte, 5 bytes and 3 for
+        // each field
+        let definition = line("definition");
+        assert!(definition.contains("fit_file{"));
+        assert!(definition.contains("record{index=1 offset=14}"));
+        assert!(definition.contains("local_type=1 mesg_num=20 fields=2"));
+        // The heart rate is after the data record's header
+        // and the timestamp
+        assert!(line("invalid field")
+            .contains("mesg_num=20 field_def_num=3 offset=31"));
+        assert!(line("unknown message").contains("mesg_num=65280"));
+        assert!(line("skipping zero padding").contains("length=4"));
+        assert!(line("crc mismatch").contains("ERROR"));
+    }
+
+    /// Hands out a byte per read.
+    struct Trickle<R>(R);
+
+    impl<R: Read> Read for Trickle<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    #[test]
+    fn reads_to_eof_without_data_size() {
+        let bytes = activity();
+        let unsized_ = without_data_size(&bytes);
+        let expected = format!("{:?}", from_bytes(&bytes).unwrap());
+        assert_eq!(format!("{:?}", from_bytes(&unsized_).unwrap()), expected);
+
+        let mut reader =
+            FitReader::with_capacity(3, Trickle(Cursor::new(&unsized_)))
+                .unwrap()
+                .with_stats();
+        assert_eq!(format!("{:?}", reader.read_messages().unwrap()), expected);
+        assert_eq!(reader.offset(), bytes.len() as u64 - 2);
+        assert_eq!(reader.progress().fraction(), 1.0);
+        assert!(matches!(reader.stats().unwrap().crc, CrcStatus::Valid));
+
+        let slice = ::slice::FitSliceReader::new(&unsized_).unwrap();
+        assert_eq!(slice.count(), 101);
+
+        // The CRC is still checked
+        let mut corrupt = unsized_.clone();
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0xFF;
+        let err = from_bytes(&corrupt).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::CrcMismatch { .. }));
+
+        // With nothing after the header, there's no CRC
+        let err = from_bytes(&unsized_[..15]).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::Read { .. }));
+        assert!(FitReader::new(&unsized_[..14])
+            .unwrap()
+            .last()
+            .unwrap()
+            .is_err());
+    }
+
+    /// The data section of [`huge_file`], generated as
+    /// it's read.
+    #[cfg(feature = "slow-tests")]
+    struct HugeRecords {
+        left: u64,
+        /// Bytes into the current record.
+        at:   usize,
+    }
+
+    #[cfg(feature = "slow-tests")]
+    impl Read for HugeRecords {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = (buf.len() as u64).min(self.left) as usize;
+            for byte in &mut buf[..n] {
+                // Local message 0, then its 255 byte field
+                *byte = if self.at == 0 { 0 } else { 0xAB };
+                self.at = (self.at + 1) % 256;
+            }
+            self.left -= n as u64;
+            Ok(n)
+        }
+    }
+
+    /// Emits the CRC of everything read from `body` once it
+    /// ends.
+    #[cfg(feature = "slow-tests")]
+    struct WithCrc<R> {
+        body: ::crc::CrcReader<R>,
+        crc:  Option<Cursor<[u8; 2]>>,
+    }
+
+    #[cfg(feature = "slow-tests")]
+    impl<R: Read> Read for WithCrc<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.crc.is_none() {
+                let n = self.body.read(buf)?;
+                if n > 0 {
+                    return Ok(n)
+                }
+                self.crc = Some(Cursor::new(self.body.crc().to_le_bytes()));
+            }
+            self.crc.as_mut().map_or(Ok(0), |crc| crc.read(buf))
+        }
+    }
+
+    /// A FIT file as near 4 GiB long as 256 byte records
+    /// get, with its length.
+    #[cfg(feature = "slow-tests")]
+    fn huge_file() -> (impl Read, u64) {
+        let records = (u64::from(u32::MAX) - 9) / 256;
+        let data_size = 9 + records * 256;
+        let mut header = vec![14, 0x20];
+        header.extend_from_slice(&2066u16.to_le_bytes());
+        header.extend_from_slice(&(data_size as u32).to_le_bytes());
+        header.extend_from_slice(b".FIT");
+        let header_crc = ::testutil::crc(&header);
+        header.extend_from_slice(&header_crc.to_le_bytes());
+        // A record definition with one unknown byte array
+        header.extend_from_slice(&[0x40, 0, 0, 20, 0, 1, 250, 255, 0x0D]);
+        let body = Cursor::new(header)
+            .chain(HugeRecords {
+                left: records * 256, at: 0
+            });
+        let file = WithCrc {
+            body: ::crc::CrcReader::new(body), crc: None
+        };
+        (file, 14 + data_size + 2)
+    }
+
+    /// Run with `--release --features slow-tests`; it
+    /// decodes 4 GiB.
+    #[cfg(feature = "slow-tests")]
+    #[test]
+    fn streams_past_4_gib() {
+        use std::sync::Arc;
+
+        let small = activity();
+        let (huge, huge_len) = huge_file();
+        let total = huge_len + 2 * small.len() as u64;
+        assert!(huge_len < 1 << 32 && total > 1 << 32);
+        let mut stream =
+            huge.chain(Cursor::new(small.clone())).chain(Cursor::new(small));
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let mut start = 0;
+        let mut starts = Vec::new();
+        let mut records = Vec::new();
+        for _ in 0..3 {
+            let log = Arc::clone(&reports);
+            let mut reader = FitReader::new(&mut stream)
+                .unwrap()
+                .on_progress(1 << 28, move |p| log.lock().unwrap().push(p))
+                .within_stream(start, total);
+            let mut count = 0u64;
+            for record in reader.by_ref() {
+                record.unwrap();
+                count += 1;
+            }
+            starts.push(start);
+            records.push(count);
+            start = reader.progress().bytes_read;
+        }
+        assert_eq!(records, [(huge_len - 16 - 9) / 256 + 1, 101, 101]);
+        assert_eq!(start, total);
+        assert!(starts[2] > u64::from(u32::MAX));
+
+        let reports = reports.lock().unwrap();
+        assert!(reports.len() > 16);
+        assert!(reports.windows(2).all(|w| w[0].bytes_read < w[1].bytes_read));
+        assert_eq!(
+            reports.last(),
+            Some(&Progress {
+                bytes_read: total, total_bytes: total
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn stats_are_serializable() {
+        fn assert_serialize<T: ::serde::Serialize>(_: &T) {}
+        assert_serialize(&DecodeStats::default());
+    }
+}
@@ -0,0 +1,289 @@
+//! Fixing files that devices are known to get wrong.
+//!
+//! GPS counts weeks in ten bits, so the week number rolls
+//! over every 1024 weeks, last on April 6 2019. Devices
+//! whose firmware didn't account for it went on recording
+//! timestamps exactly 1024 weeks early, and their
+//! activities import as if from 1999 onwards.
+//! [`detect_gps_rollover`] recognises those files and
+//! [`correct_gps_rollover`] moves them forward again.
+//!
+//! A timestamp from 1999 isn't wrong by itself: a file can
+//! hold history converted from an older format, written by
+//! software that doesn't identify a device. So detection
+//! only trusts files whose `FileId` names a device, and
+//! only when every timestamp predates that device.
+//!
+//! [`detect_gps_rollover`]: fn.detect_gps_rollover.html
+//! [`correct_gps_rollover`]: fn.correct_gps_rollover.html
+use creator::created_at;
+use devices::{
+    file_product_id,
+    ProductId,
+};
+use profile::{
+    messages::{
+        FileId,
+        Message,
+        Record,
+    },
+    types::{
+        DateTime,
+        GarminProduct,
+        Manufacturer,
+    },
+};
+use std::fmt;
+use time::MIN_DATE_TIME;
+use types::record::Data;
+
+/// 1024 weeks, how far early a rolled over device's
+/// timestamps are.
+pub const GPS_ROLLOVER_SECONDS: u32 = 619_315_200;
+
+/// 00:00 Jan 1 2008 UTC. No device wrote FIT files before
+/// then.
+pub const FIT_ERA_START: u32 = 568_080_000;
+
+/// The start of the year each of the devices the rollover
+/// is known to have affected came out, in seconds since
+/// the FIT epoch. None of them can have recorded earlier.
+const RELEASES: &[(u16, u32)] = &[
+    (GarminProduct::Fr405 as u16, 568_080_000),
+    (GarminProduct::Fr60 as u16, 568_080_000),
+    (GarminProduct::Fr310Xt as u16, 599_702_400),
+    (GarminProduct::Edge500 as u16, 599_702_400),
+    (GarminProduct::Fr110 as u16, 599_702_400),
+    (GarminProduct::Edge800 as u16, 631_238_400),
+    (GarminProduct::Fr310Xt4T as u16, 631_238_400),
+    (GarminProduct::Edge200 as u16, 662_774_400),
+    (GarminProduct::Fr910Xt as u16, 662_774_400),
+    (GarminProduct::Fr610 as u16, 662_774_400),
+    (GarminProduct::Fr70 as u16, 662_774_400),
+    (GarminProduct::Fr10 as u16, 694_310_400),
+    (GarminProduct::Fenix as u16, 694_310_400),
+    (GarminProduct::Edge510 as u16, 725_932_800),
+    (GarminProduct::Edge810 as u16, 725_932_800),
+    (GarminProduct::Fr220 as u16, 725_932_800),
+    (GarminProduct::Fr620 as u16, 725_932_800),
+];
+
+/// The earliest a file from `product` can have been
+/// recorded.
+fn release_epoch(product: &ProductId) -> u32 {
+    match product {
+        ProductId::Garmin(product) => {
+            let product = product.clone() as u16;
+            RELEASES
+                .iter()
+                .find(|&&(p, _)| p == product)
+                .map_or(FIT_ERA_START, |&(_, start)| start)
+        },
+        _ => FIT_ERA_START,
+    }
+}
+
+/// Whether `messages` look like they were recorded by a
+/// device that had rolled over, with every timestamp 1024
+/// weeks early.
+///
+/// That takes a `FileId` naming a manufacturer (other than
+/// `Development`) and product, a creation time or record
+/// timestamps, and all of those predating the device's
+/// release, or the first FIT devices for products this
+/// doesn't know, while 1024 weeks later doesn't.
+pub fn detect_gps_rollover(messages: &[Data]) -> bool {
+    let release = match device(messages) {
+        Some(product) => release_epoch(&product),
+        None => return false,
+    };
+    let mut times = messages.iter().flat_map(|mesg| {
+        mesg.0.iter().filter_map(|field| {
+            match field {
+                Message::FileId(FileId::TimeCreated(f))
+                | Message::Record(Record::Timestamp(f)) => Some(f.raw_value.0),
+                _ => None,
+            }
+        })
+    });
+    let mut any = false;
+    let rolled = times.all(|time| {
+        if time < MIN_DATE_TIME || time == u32::MAX {
+            return true;
+        }
+        any = true;
+        time < release
+            && time
+                .checked_add(GPS_ROLLOVER_SECONDS)
+                .is_some_and(|corrected| corrected >= release)
+    });
+    any && rolled
+}
+
+/// The product of the first `FileId`, if it names a real
+/// device's manufacturer.
+fn device(messages: &[Data]) -> Option<ProductId> {
+    let product = messages.iter().find_map(file_product_id)?;
+    match &product {
+        ProductId::Other {
+            manufacturer: None, ..
+        }
+        | ProductId::Other {
+            manufacturer: Some(Manufacturer::Development),
+            ..
+        } => None,
+        _ => Some(product),
+    }
+}
+
+/// What [`correct_gps_rollover`] changed.
+///
+/// [`correct_gps_rollover`]: fn.correct_gps_rollover.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RolloverCorrection {
+    /// The date time fields moved forward.
+    pub fields:  usize,
+    /// When the file said it was created.
+    pub created: Option<DateTime>,
+    /// When it was created, corrected.
+    pub to:      Option<DateTime>,
+}
+
+impl fmt::Display for RolloverCorrection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = |time: Option<DateTime>| {
+            time.and_then(|time| time.to_utc())
+                .map_or_else(|| "?".to_owned(), |time| time.to_rfc3339())
+        };
+        write!(
+            f,
+            "GPS week rollover: moved {} date times forward 1024 weeks, \
+             created {} is now {}",
+            self.fields,
+            format(self.created),
+            format(self.to),
+        )
+    }
+}
+
+/// Move every date time in `messages` forward 1024 weeks
+/// if [`detect_gps_rollover`] says the device had rolled
+/// over, and say what changed.
+///
+/// Both `date_time` and `local_date_time` fields move;
+/// invalid values and times since power on don't. Returns
+/// `None`, leaving `messages` alone, if the file doesn't
+/// look rolled over.
+///
+/// [`detect_gps_rollover`]: fn.detect_gps_rollover.html
+pub fn correct_gps_rollover(
+    messages: &mut [Data],
+) -> Option<RolloverCorrection> {
+    if !detect_gps_rollover(messages) {
+        return None;
+    }
+    let created = created_at(messages).map(|created| created.time);
+    let mut fields = 0;
+    for mesg in messages.iter_mut() {
+        for field in mesg.0.iter_mut() {
+            match field.date_time_mut() {
+                Some(time) if *time >= MIN_DATE_TIME && *time != u32::MAX => {
+                    *time = time.saturating_add(GPS_ROLLOVER_SECONDS);
+                    fields += 1;
+                },
+                _ => (),
+            }
+        }
+    }
+    Some(RolloverCorrection {
+        fields,
+        created,
+        to: created_at(messages).map(|created| created.time),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    /// 08:00 Oct 3 1999, 1024 weeks before 08:00 May 19
+    /// 2019.
+    const ROLLED: u32 = 307_872_000;
+    /// 08:00 Jun 1 2010.
+    const JUNE_2010: u32 = 644_313_600;
+
+    /// An activity from `manufacturer`'s `product`, created
+    /// at `start` and with a record a second apart for a
+    /// minute.
+    fn activity(
+        manufacturer: Option<u16>,
+        product: u16,
+        start: u32,
+    ) -> Vec<Data> {
+        let mut file_id = mesg(0).u8(0, 4).u16(2, product).u32(4, start);
+        if let Some(manufacturer) = manufacturer {
+            file_id = file_id.u16(1, manufacturer);
+        }
+        let mut messages = vec![file_id.build()];
+        messages.extend(
+            (0..60).map(|i| mesg(20).u32(253, start + i).u8(3, 120).build()),
+        );
+        messages.push(
+            mesg(34).u32(253, start + 60).u32(5, start + 60 + 7200).build(),
+        );
+        messages
+    }
+
+    #[test]
+    fn corrects_rolled_over_devices() {
+        // An Edge 500 recording in May 2019
+        let mut messages = activity(Some(1), 1036, ROLLED);
+        assert!(detect_gps_rollover(&messages));
+
+        let correction = correct_gps_rollover(&mut messages).unwrap();
+        // Time created, 60 record timestamps, and the
+        // activity's timestamp and local timestamp
+        assert_eq!(correction.fields, 63);
+        assert_eq!(correction.created, Some(DateTime(ROLLED)));
+        assert_eq!(correction.to, Some(DateTime(927_187_200)));
+        assert_eq!(
+            correction.to_string(),
+            "GPS week rollover: moved 63 date times forward 1024 weeks, \
+             created 1999-10-03T08:00:00+00:00 is now \
+             2019-05-19T08:00:00+00:00"
+        );
+        assert!(!detect_gps_rollover(&messages));
+        assert!(correct_gps_rollover(&mut messages).is_none());
+
+        // A 910XT came out in 2011, so 2010 is 2030
+        assert!(detect_gps_rollover(&activity(Some(1), 1328, JUNE_2010)));
+    }
+
+    #[test]
+    fn leaves_plausible_files_alone() {
+        // A 310XT could have recorded in 2010
+        assert!(!detect_gps_rollover(&activity(Some(1), 1018, JUNE_2010)));
+
+        // History converted from 1999 by software that
+        // doesn't identify a device, or a development build
+        assert!(!detect_gps_rollover(&activity(None, 1036, ROLLED)));
+        assert!(!detect_gps_rollover(&activity(Some(255), 1, ROLLED)));
+        assert!(!detect_gps_rollover(&activity(Some(1), u16::MAX, ROLLED)));
+
+        // A rolled over creation time, but records from
+        // after the device came out
+        let mut messages = activity(Some(1), 1036, ROLLED);
+        messages[5] = mesg(20).u32(253, 927_187_205).build();
+        assert!(!detect_gps_rollover(&messages));
+
+        // Only times since power on are nothing to go on
+        let mut messages = activity(Some(1), 1036, 1000);
+        assert!(!detect_gps_rollover(&messages));
+        assert!(correct_gps_rollover(&mut messages).is_none());
+        assert_eq!(
+            format!("{:?}", messages),
+            format!("{:?}", activity(Some(1), 1036, 1000))
+        );
+    }
+}
@@ -0,0 +1,826 @@
+//! Checking that a file contains what its type calls for,
+//! for QA of the devices and apps writing them.
+//!
+//! What each file type should contain is a list of
+//! [`Rule`]s, so new expectations are a matter of adding
+//! to a list, or passing a list of one's own to
+//! [`completeness_with`].
+//!
+//! [`Rule`]: enum.Rule.html
+//! [`completeness_with`]: fn.completeness_with.html
+use events::{
+    self,
+    Parsed,
+};
+use geo;
+use profile::{
+    messages::{
+        FileId,
+        Message,
+        Session,
+    },
+    types::{
+        EventType,
+        File as FileType,
+    },
+};
+use series::RecordPoint;
+use session::session_bounds;
+use std::{
+    cmp::Reverse,
+    collections::BTreeMap,
+};
+use types::{
+    file::{
+        self,
+        FileWarning,
+    },
+    record::Data,
+};
+
+/// How much a finding matters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Something a file is expected to contain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {
+    /// At least `min` messages of `mesg_num`.
+    AtLeast {
+        mesg_num: u16,
+        name:     &'static str,
+        min:      usize,
+        severity: Severity,
+    },
+    /// The first `after` message comes after a `before`
+    /// message.
+    Before {
+        before:      u16,
+        before_name: &'static str,
+        after:       u16,
+        after_name:  &'static str,
+        severity:    Severity,
+    },
+    /// The `message_index` of the `mesg_num` messages
+    /// counts up from 0 without gaps.
+    ContiguousIndex {
+        mesg_num: u16,
+        name:     &'static str,
+        severity: Severity,
+    },
+    /// Records span every session, never more than
+    /// `max_gap_s` apart.
+    RecordsCoverSessions { max_gap_s: u32, severity: Severity },
+    /// Every timer start is matched by a stop.
+    TimerEventsBalanced { severity: Severity },
+    /// Sessions with positioned records and `nec_*` and
+    /// `swc_*` corners have corners that cover the records,
+    /// and don't stray more than `tolerance_m` beyond them.
+    /// Sessions without the corners pass; devices often
+    /// leave them out.
+    SessionBounds { tolerance_m: f64, severity: Severity },
+}
+
+impl Rule {
+    /// A short identifier for the kind of rule.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rule::AtLeast {
+                ..
+            } => "at_least",
+            Rule::Before {
+                ..
+            } => "before",
+            Rule::ContiguousIndex {
+                ..
+            } => "contiguous_index",
+            Rule::RecordsCoverSessions {
+                ..
+            } => "records_cover_sessions",
+            Rule::TimerEventsBalanced {
+                ..
+            } => "timer_events_balanced",
+            Rule::SessionBounds {
+                ..
+            } => "session_bounds",
+        }
+    }
+}
+
+/// What activity files should contain.
+pub const ACTIVITY_RULES: &[Rule] = &[
+    Rule::AtLeast {
+        mesg_num: 34,
+        name:     "Activity",
+        min:      1,
+        severity: Severity::Warning,
+    },
+    Rule::AtLeast {
+        mesg_num: 18,
+        name:     "Session",
+        min:      1,
+        severity: Severity::Error,
+    },
+    Rule::AtLeast {
+        mesg_num: 19,
+        name:     "Lap",
+        min:      1,
+        severity: Severity::Error,
+    },
+    Rule::AtLeast {
+        mesg_num: 20,
+        name:     "Record",
+        min:      1,
+        severity: Severity::Warning,
+    },
+    Rule::RecordsCoverSessions {
+        max_gap_s: 60, severity: Severity::Warning
+    },
+    Rule::TimerEventsBalanced {
+        severity: Severity::Warning
+    },
+    Rule::SessionBounds {
+        tolerance_m: 50.0, severity: Severity::Warning
+    },
+];
+
+/// What workout files should contain.
+pub const WORKOUT_RULES: &[Rule] = &[
+    Rule::AtLeast {
+        mesg_num: 26,
+        name:     "Workout",
+        min:      1,
+        severity: Severity::Error,
+    },
+    Rule::AtLeast {
+        mesg_num: 27,
+        name:     "WorkoutStep",
+        min:      1,
+        severity: Severity::Error,
+    },
+    Rule::ContiguousIndex {
+        mesg_num: 27,
+        name:     "WorkoutStep",
+        severity: Severity::Error,
+    },
+];
+
+/// What monitoring files should contain.
+pub const MONITORING_RULES: &[Rule] = &[
+    Rule::AtLeast {
+        mesg_num: 103,
+        name:     "MonitoringInfo",
+        min:      1,
+        severity: Severity::Warning,
+    },
+    Rule::Before {
+        before:      103,
+        before_name: "MonitoringInfo",
+        after:       55,
+        after_name:  "Monitoring",
+        severity:    Severity::Warning,
+    },
+];
+
+/// The rules for files of `file_type`, empty for types
+/// without any.
+pub fn rules_for(file_type: &FileType) -> &'static [Rule] {
+    match file_type {
+        FileType::Activity => ACTIVITY_RULES,
+        FileType::Workout => WORKOUT_RULES,
+        FileType::MonitoringA
+        | FileType::MonitoringB
+        | FileType::MonitoringDaily => MONITORING_RULES,
+        _ => &[],
+    }
+}
+
+/// A rule a file broke.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Finding {
+    pub severity: Severity,
+    /// The [`code`](enum.Rule.html#method.code) of the
+    /// rule, or `"file_id"` for a missing, duplicated or
+    /// misplaced `FileId`.
+    pub rule:     &'static str,
+    pub message:  String,
+}
+
+/// Spacing of the `Record` messages, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GapStats {
+    pub max_gap_s:       u32,
+    /// Timestamp of the record before the longest gap.
+    pub max_gap_at:      u32,
+    pub mean_interval_s: f64,
+}
+
+/// What a file contains and how that falls short of what
+/// its type calls for.
+///
+/// Nothing in it depends on hash order, so the same file
+/// always serializes to the same bytes.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Completeness {
+    /// The raw `FileId.Type`.
+    pub file_type:      Option<u8>,
+    /// Number of data messages by global message number,
+    /// in ascending order.
+    pub message_counts: BTreeMap<u16, usize>,
+    /// `None` with fewer than two records.
+    pub record_gaps:    Option<GapStats>,
+    /// Ordered from most to least severe, and within a
+    /// severity in the order they were checked.
+    pub findings:       Vec<Finding>,
+}
+
+impl Completeness {
+    /// The severity of the worst finding.
+    pub fn worst(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+
+    /// Whether no finding is an error.
+    pub fn is_complete(&self) -> bool {
+        self.worst() < Some(Severity::Error)
+    }
+}
+
+/// Check `messages` against the rules for the file type
+/// given by their `FileId`.
+pub fn completeness(messages: &[Data]) -> Completeness {
+    let rules = file_type(messages)
+        .map(|file_type| rules_for(&file_type))
+        .unwrap_or(&[]);
+    completeness_with(messages, rules)
+}
+
+/// Check `messages` against `rules`, whatever their file
+/// type.
+pub fn completeness_with(messages: &[Data], rules: &[Rule]) -> Completeness {
+    let mut message_counts = BTreeMap::new();
+    for mesg in messages {
+        if let Some(field) = mesg.0.first() {
+            *message_counts.entry(field.mesg_num()).or_insert(0) += 1;
+        }
+    }
+
+    let records: Vec<u32> = messages
+        .iter()
+        .filter_map(RecordPoint::from_message)
+        .map(|point| point.timestamp)
+        .collect();
+
+    let file_type = file_type(messages);
+    let mut findings = Vec::new();
+    if file_type.is_none() {
+        findings.push(Finding {
+            severity: Severity::Error,
+            rule:     "file_id",
+            message:  String::from("no FileId message with a file type"),
+        });
+    }
+    for warning in file::file_id_warnings(messages) {
+        let (severity, message) = match warning {
+            FileWarning::MultipleFileIds {
+                count,
+                conflicting_types,
+            } => {
+                let severity = if conflicting_types {
+                    Severity::Error
+                }
+                else {
+                    Severity::Warning
+                };
+                let conflict = if conflicting_types {
+                    " with conflicting types"
+                }
+                else {
+                    ""
+                };
+                (
+                    severity,
+                    format!(
+                        "{} FileId messages{}, using the first",
+                        count, conflict
+                    ),
+                )
+            },
+            FileWarning::FileIdNotFirst {
+                preceding,
+            } => {
+                (
+                    Severity::Warning,
+                    format!(
+                        "FileId comes after {} other messages instead of first",
+                        preceding
+                    ),
+                )
+            },
+        };
+        findings.push(Finding {
+            severity,
+            rule: "file_id",
+            message,
+        });
+    }
+    for rule in rules {
+        let mut finding = |message: String| {
+            findings.push(Finding {
+                severity: severity(rule),
+                rule: rule.code(),
+                message,
+            })
+        };
+
+        match *rule {
+            Rule::AtLeast {
+                mesg_num,
+                name,
+                min,
+                ..
+            } => {
+                let count = message_counts.get(&mesg_num).cloned().unwrap_or(0);
+                if count < min {
+                    finding(format!(
+                        "expected at least {} {} messages, found {}",
+                        min, name, count
+                    ));
+                }
+            },
+            Rule::Before {
+                before,
+                before_name,
+                after,
+                after_name,
+                ..
+            } => {
+                let first = |mesg_num| {
+                    messages.iter().position(|mesg| {
+                        mesg.0.first().map(Message::mesg_num) == Some(mesg_num)
+                    })
+                };
+                match (first(before), first(after)) {
+                    (Some(b), Some(a)) if b < a => (),
+                    (_, None) => (),
+                    _ => {
+                        finding(format!(
+                            "{} message before any {} message",
+                            after_name, before_name
+                        ))
+                    },
+                }
+            },
+            Rule::ContiguousIndex {
+                mesg_num,
+                name,
+                ..
+            } => {
+                let indices = messages.iter().filter_map(|mesg| {
+                    let first = mesg.0.first()?;
+                    if first.mesg_num() != mesg_num {
+                        return None
+                    }
+                    Some(mesg.0.iter().find_map(Message::message_index))
+                });
+                for (expected, index) in indices.enumerate() {
+                    match index {
+                        Some(index)
+                            if usize::from(index.index()) == expected => {},
+                        Some(index) => {
+                            finding(format!(
+                                "{} message #{} has message_index {}",
+                                name,
+                                expected,
+                                index.index()
+                            ));
+                            break
+                        },
+                        None => {
+                            finding(format!(
+                                "{} message #{} has no message_index",
+                                name, expected
+                            ));
+                            break
+                        },
+                    }
+                }
+            },
+            Rule::RecordsCoverSessions {
+                max_gap_s, ..
+            } => {
+                for (start, end) in session_spans(messages) {
+                    let covering: Vec<u32> = records
+                        .iter()
+                        .cloned()
+                        .filter(|&t| start <= t && t <= end)
+                        .collect();
+                    let mut edges = vec![start];
+                    edges.extend(&covering);
+                    edges.push(end);
+                    let longest = edges
+                        .windows(2)
+                        .map(|pair| (pair[1] - pair[0], pair[0]))
+                        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+                    if let Some((gap, at)) = longest {
+                        if gap > max_gap_s {
+                            finding(format!(
+                                "no records for {} s from {} in the session \
+                                 {}..{}",
+                                gap, at, start, end
+                            ));
+                        }
+                    }
+                }
+            },
+            Rule::TimerEventsBalanced {
+                ..
+            } => {
+                let (unmatched_stops, running) = timer_balance(messages);
+                if unmatched_stops > 0 {
+                    finding(format!(
+                        "{} timer stops without a start",
+                        unmatched_stops
+                    ));
+                }
+                if running {
+                    finding(String::from("timer never stopped"));
+                }
+            },
+            Rule::SessionBounds {
+                tolerance_m, ..
+            } => {
+                let points: Vec<RecordPoint> = messages
+                    .iter()
+                    .filter_map(RecordPoint::from_message)
+                    .collect();
+                for mesg in messages {
+                    let (start, end) = match session_span(mesg) {
+                        Some(span) => span,
+                        None => continue,
+                    };
+                    let during: Vec<RecordPoint> = points
+                        .iter()
+                        .filter(|p| start <= p.timestamp && p.timestamp <= end)
+                        .cloned()
+                        .collect();
+                    let track = match geo::bounds(&during) {
+                        Some(track) => track,
+                        None => continue,
+                    };
+                    let fields = mesg.0.iter().filter_map(|field| {
+                        match field {
+                            Message::Session(field) => Some(field),
+                            _ => None,
+                        }
+                    });
+                    match session_bounds(fields) {
+                        None => (),
+                        Some(bounds) if !bounds.covers(&track) => {
+                            finding(format!(
+                                "the nec/swc bounds of the session {}..{} \
+                                 leave out some of its records",
+                                start, end
+                            ))
+                        },
+                        Some(bounds)
+                            if !track
+                                .expand_by_meters(tolerance_m)
+                                .covers(&bounds) =>
+                        {
+                            finding(format!(
+                                "the nec/swc bounds of the session {}..{} \
+                                 reach more than {} m past its records",
+                                start, end, tolerance_m
+                            ))
+                        },
+                        Some(_) => (),
+                    }
+                }
+            },
+        }
+    }
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+
+    Completeness {
+        file_type: file_type.map(|file_type| file_type as u8),
+        message_counts,
+        record_gaps: gap_stats(&records),
+        findings,
+    }
+}
+
+fn severity(rule: &Rule) -> Severity {
+    match *rule {
+        Rule::AtLeast {
+            severity, ..
+        }
+        | Rule::Before {
+            severity, ..
+        }
+        | Rule::ContiguousIndex {
+            severity, ..
+        }
+        | Rule::RecordsCoverSessions {
+            severity, ..
+        }
+        | Rule::TimerEventsBalanced {
+            severity,
+        }
+        | Rule::SessionBounds {
+            severity, ..
+        } => severity,
+    }
+}
+
+/// The type in the first `FileId` message, the one that
+/// counts when there are several.
+fn file_type(messages: &[Data]) -> Option<FileType> {
+    file::first_file_id(messages)?.0.iter().find_map(|field| {
+        match field {
+            Message::FileId(FileId::Type(f)) => Some(f.raw_value.clone()),
+            _ => None,
+        }
+    })
+}
+
+/// `(start_time, timestamp)` of the sessions that have
+/// both.
+pub(crate) fn session_spans(messages: &[Data]) -> Vec<(u32, u32)> {
+    messages.iter().filter_map(session_span).collect()
+}
+
+/// `(start_time, timestamp)` of `mesg` if it's a session
+/// with both.
+fn session_span(mesg: &Data) -> Option<(u32, u32)> {
+    let mut start = None;
+    let mut end = None;
+    for field in mesg.0.iter() {
+        match field {
+            Message::Session(Session::StartTime(f)) => {
+                start = Some(f.raw_value.0)
+            },
+            Message::Session(Session::Timestamp(f)) => {
+                end = Some(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+    match (start?, end?) {
+        (start, end) if start <= end && end != u32::MAX => Some((start, end)),
+        _ => None,
+    }
+}
+
+/// Stops while the timer wasn't running, and whether it
+/// was left running.
+fn timer_balance(messages: &[Data]) -> (usize, bool) {
+    let mut unmatched_stops = 0;
+    let mut running = false;
+    for event_type in messages.iter().filter_map(|mesg| {
+        match events::parse(mesg) {
+            Some(Parsed::Timer(event_type)) => Some(event_type),
+            _ => None,
+        }
+    }) {
+        match event_type {
+            EventType::Start => running = true,
+            EventType::Stop
+            | EventType::StopAll
+            | EventType::StopDisable
+            | EventType::StopDisableAll => {
+                if !running {
+                    unmatched_stops += 1;
+                }
+                running = false
+            },
+            _ => (),
+        }
+    }
+    (unmatched_stops, running)
+}
+
+fn gap_stats(records: &[u32]) -> Option<GapStats> {
+    let (max_gap_s, max_gap_at) = records
+        .windows(2)
+        .map(|pair| (pair[1].saturating_sub(pair[0]), pair[0]))
+        .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)))?;
+    let span = records[records.len() - 1].saturating_sub(records[0]);
+    Some(GapStats {
+        max_gap_s,
+        max_gap_at,
+        mean_interval_s: f64::from(span) / (records.len() - 1) as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn file_id(file_type: u8) -> Data {
+        mesg(0).u8(0, file_type).u16(1, 1).build()
+    }
+
+    fn timer(timestamp: u32, event_type: u8) -> Data {
+        mesg(21).u32(253, timestamp).u8(0, 0).u8(1, event_type).build()
+    }
+
+    fn record(timestamp: u32) -> Data {
+        mesg(20).u32(253, timestamp).build()
+    }
+
+    #[test]
+    fn complete_activity() {
+        let mut messages = vec![file_id(4), timer(1000, 0)];
+        messages.extend((1000..=1100).step_by(5).map(record));
+        messages.extend(vec![
+            timer(1100, 4),
+            mesg(19).u32(253, 1100).u32(2, 1000).build(),
+            mesg(18).u32(253, 1100).u32(2, 1000).build(),
+            mesg(34).u32(253, 1100).build(),
+        ]);
+
+        let report = completeness(&messages);
+        assert_eq!(report.file_type, Some(4));
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+        assert_eq!(report.message_counts.get(&20), Some(&21));
+        assert_eq!(
+            report.record_gaps,
+            Some(GapStats {
+                max_gap_s:       5,
+                max_gap_at:      1000,
+                mean_interval_s: 5.0,
+            })
+        );
+        assert!(report.is_complete());
+    }
+
+    #[test]
+    fn incomplete_activity() {
+        let messages = vec![
+            file_id(4),
+            timer(1000, 0),
+            record(1000),
+            record(1010),
+            // The recording stalled for two minutes
+            record(1130),
+            timer(1140, 1),
+            timer(1150, 1),
+            timer(1160, 0),
+            mesg(18).u32(253, 1200).u32(2, 1000).build(),
+        ];
+
+        let report = completeness(&messages);
+        let rules: Vec<(Severity, &str)> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.severity, finding.rule))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                (Severity::Error, "at_least"),
+                (Severity::Warning, "at_least"),
+                (Severity::Warning, "records_cover_sessions"),
+                (Severity::Warning, "timer_events_balanced"),
+                (Severity::Warning, "timer_events_balanced"),
+            ]
+        );
+        assert!(report.findings[0].message.contains("Lap"));
+        assert!(report.findings[2].message.contains("120 s from 1010"));
+        assert_eq!(report.record_gaps.unwrap().max_gap_s, 120);
+        assert_eq!(report.worst(), Some(Severity::Error));
+        assert!(!report.is_complete());
+    }
+
+    #[test]
+    fn session_bounds() {
+        use geo::degrees_to_semicircles as semicircles;
+
+        let at = |timestamp: u32, lat: f64, lon: f64| {
+            mesg(20)
+                .u32(253, timestamp)
+                .i32(0, semicircles(lat))
+                .i32(1, semicircles(lon))
+                .build()
+        };
+        let session = |corners: &[f64]| {
+            let mut session = mesg(18).u32(253, 1100).u32(2, 1000);
+            for (num, &degrees) in (29..).zip(corners) {
+                session = session.i32(num, semicircles(degrees));
+            }
+            session.build()
+        };
+        let findings = |corners: &[f64]| {
+            let messages = vec![
+                at(1000, 45.0, 7.0),
+                at(1050, 45.01, 7.02),
+                at(1100, 45.02, 7.01),
+                session(corners),
+            ];
+            let rules = [Rule::SessionBounds {
+                tolerance_m: 50.0,
+                severity:    Severity::Warning,
+            }];
+            completeness_with(&messages, &rules)
+                .findings
+                .into_iter()
+                .filter(|finding| finding.rule == "session_bounds")
+                .map(|finding| finding.message)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(findings(&[45.02, 7.02, 45.0, 7.0]).is_empty());
+        // Within the tolerance
+        assert!(findings(&[45.0202, 7.02, 45.0, 7.0]).is_empty());
+
+        // Missing corners are for `fill_bounds`, not a fault
+        assert!(findings(&[45.02, 7.02, 45.0]).is_empty());
+        let short = findings(&[45.015, 7.02, 45.0, 7.0]);
+        assert!(short[0].contains("leave out some of its records"));
+        let loose = findings(&[45.1, 7.02, 45.0, 7.0]);
+        assert!(loose[0].contains("reach more than 50 m past its records"));
+    }
+
+    #[test]
+    fn workouts_and_monitoring() {
+        let step = |index: u16| mesg(27).u16(254, index).build();
+        let workout = vec![
+            file_id(5),
+            mesg(26).u16(6, 3).build(),
+            step(0),
+            step(1),
+            step(3),
+        ];
+        let report = completeness(&workout);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule, "contiguous_index");
+        assert!(report.findings[0].message.contains("#2"));
+
+        let monitoring = vec![
+            file_id(32),
+            mesg(55).u32(253, 1000).build(),
+            mesg(103).u32(253, 1000).build(),
+        ];
+        let report = completeness(&monitoring);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule, "before");
+
+        let report = completeness(&[record(1000)]);
+        assert_eq!(report.findings[0].rule, "file_id");
+        assert_eq!(report.file_type, None);
+    }
+
+    #[test]
+    fn duplicated_file_id() {
+        let mut messages = vec![file_id(5), mesg(26).u16(6, 1).build()];
+        messages.push(mesg(27).u16(254, 0).build());
+        messages.insert(1, file_id(5));
+        let report = completeness(&messages);
+        assert_eq!(report.file_type, Some(5));
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].severity, Severity::Warning);
+        assert_eq!(report.findings[0].rule, "file_id");
+
+        // A course id after the activity's: still an activity,
+        // but flagged
+        messages = vec![record(1000), file_id(4), file_id(6)];
+        let report = completeness(&messages);
+        assert_eq!(report.file_type, Some(4));
+        let file_id_findings: Vec<(Severity, &str)> = report
+            .findings
+            .iter()
+            .filter(|finding| finding.rule == "file_id")
+            .map(|finding| (finding.severity, finding.message.as_str()))
+            .collect();
+        assert_eq!(
+            file_id_findings,
+            vec![
+                (
+                    Severity::Error,
+                    "2 FileId messages with conflicting types, using the first"
+                ),
+                (
+                    Severity::Warning,
+                    "FileId comes after 1 other messages instead of first"
+                ),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn report_is_serializable() {
+        fn assert_serialize<T: ::serde::Serialize>(_: &T) {}
+        assert_serialize(&completeness(&[file_id(4)]));
+    }
+}
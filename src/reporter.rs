@@ -0,0 +1,534 @@
+//! Plain text and Markdown activity reports, for sharing a
+//! summary of an activity where there's no web UI.
+use creator;
+use devices::{
+    self,
+    DeviceInfo,
+};
+use laps::Lap;
+use profile::{
+    messages::{
+        Message,
+        Session,
+    },
+    types::{
+        DateTime,
+        DeviceIndex,
+        Sport,
+    },
+};
+use race::format_time;
+use std::fmt::Write;
+use time;
+use types::{
+    field::Field,
+    file::File,
+    record::Data,
+};
+
+/// What to write a report as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Underlined section titles and space aligned tables.
+    PlainText,
+    /// `##` section titles and pipe tables.
+    Markdown,
+}
+
+/// Which sections to include in a report, and its format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub include_laps:        bool,
+    pub include_device_info: bool,
+    pub format:              ReportFormat,
+}
+
+impl Default for ReportOptions {
+    /// Plain text with the laps but not the devices.
+    fn default() -> Self {
+        ReportOptions {
+            include_laps:        true,
+            include_device_info: false,
+            format:              ReportFormat::PlainText,
+        }
+    }
+}
+
+/// Writes a human readable report of an activity: a header
+/// with the date, sport and device, a summary of the first
+/// session, and optionally a table of laps and a list of
+/// the devices that contributed to the file.
+///
+/// Values the file doesn't have are written as `-`.
+#[derive(Debug, Clone, Default)]
+pub struct FitReporter {
+    options: ReportOptions,
+}
+
+impl FitReporter {
+    pub fn new(options: ReportOptions) -> Self {
+        FitReporter {
+            options,
+        }
+    }
+
+    /// The report for the activity in `fit`.
+    ///
+    /// Laps and devices are listed in file order, so a file
+    /// always gives the same report byte for byte, whichever
+    /// byte order it was written in.
+    pub fn report(&self, fit: &File) -> String {
+        let messages: Vec<Data> = fit.messages().cloned().collect();
+        let session = messages
+            .iter()
+            .find_map(SessionSummary::from_message)
+            .unwrap_or_default();
+        let devices = fit.contributing_devices();
+
+        let mut sections = vec![
+            ("Header", header(&messages, &session, &devices)),
+            ("Summary", summary(&session)),
+        ];
+        if self.options.include_laps {
+            let laps: Vec<Lap> =
+                messages.iter().filter_map(Lap::from_message).collect();
+            sections.push(("Laps", lap_table(&laps)));
+        }
+        if self.options.include_device_info {
+            sections.push(("Device Info", device_list(&devices)));
+        }
+
+        let mut out = String::new();
+        for (i, (title, block)) in sections.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            self.write_section(&mut out, title, block);
+        }
+        out
+    }
+
+    fn write_section(&self, out: &mut String, title: &str, block: &Block) {
+        let markdown = self.options.format == ReportFormat::Markdown;
+        if markdown {
+            let _ = writeln!(out, "## {}\n", title);
+        }
+        else {
+            let _ = writeln!(out, "{}\n{}", title, "-".repeat(title.len()));
+        }
+
+        match block {
+            Block::Fields(fields) => {
+                let width = fields.iter().map(|f| f.0.len()).max().unwrap_or(0);
+                for (name, value) in fields {
+                    if markdown {
+                        let _ = writeln!(out, "- **{}:** {}", name, value);
+                    }
+                    else {
+                        let name = format!("{}:", name);
+                        let _ = writeln!(
+                            out,
+                            "{:<w$} {}",
+                            name,
+                            value,
+                            w = width + 1
+                        );
+                    }
+                }
+            },
+            Block::Table(header, rows) if markdown => {
+                let _ = writeln!(out, "| {} |", header.join(" | "));
+                let rule: Vec<&str> = header.iter().map(|_| "---:").collect();
+                let _ = writeln!(out, "|{}|", rule.join("|"));
+                for row in rows {
+                    let _ = writeln!(out, "| {} |", row.join(" | "));
+                }
+            },
+            Block::Table(header, rows) => {
+                let widths: Vec<usize> = (0..header.len())
+                    .map(|i| {
+                        rows.iter()
+                            .map(|row| row[i].len())
+                            .chain(Some(header[i].len()))
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect();
+                let line = |cells: Vec<&str>| {
+                    let cells: Vec<String> = cells
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cell, &w)| format!("{:>w$}", cell, w = w))
+                        .collect();
+                    cells.join("  ")
+                };
+                let _ = writeln!(out, "{}", line(header.clone()));
+                for row in rows {
+                    let _ = writeln!(
+                        out,
+                        "{}",
+                        line(row.iter().map(String::as_str).collect())
+                    );
+                }
+            },
+        }
+    }
+}
+
+/// The body of a section.
+enum Block {
+    /// Name, value pairs.
+    Fields(Vec<(&'static str, String)>),
+    /// Column titles and rows.
+    Table(Vec<&'static str>, Vec<Vec<String>>),
+}
+
+/// The fields of a `Session` message that go into a report.
+#[derive(Debug, Clone, Default)]
+struct SessionSummary {
+    start_time:            Option<DateTime>,
+    sport:                 Option<Sport>,
+    /// Seconds.
+    total_elapsed_time:    Option<f64>,
+    /// Seconds.
+    total_timer_time:      Option<f64>,
+    /// Metres.
+    total_distance:        Option<f64>,
+    avg_heart_rate:        Option<u8>,
+    max_heart_rate:        Option<u8>,
+    avg_power:             Option<u16>,
+    max_power:             Option<u16>,
+    training_stress_score: Option<f64>,
+}
+
+impl SessionSummary {
+    fn from_message(mesg: &Data) -> Option<SessionSummary> {
+        let mut session = SessionSummary::default();
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Session(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                Session::StartTime(f) if f.raw_value.to_utc().is_some() => {
+                    session.start_time = Some(f.raw_value)
+                },
+                Session::Sport(f) => session.sport = Some(f.raw_value.clone()),
+                Session::TotalElapsedTime(f) if f.is_valid() => {
+                    session.total_elapsed_time = Some(f.value())
+                },
+                Session::TotalTimerTime(f) if f.is_valid() => {
+                    session.total_timer_time = Some(f.value())
+                },
+                Session::TotalDistance(f) if f.is_valid() => {
+                    session.total_distance = Some(f.value())
+                },
+                Session::AvgHeartRate(f) if f.is_valid() => {
+                    session.avg_heart_rate = Some(f.raw_value.0)
+                },
+                Session::MaxHeartRate(f) if f.is_valid() => {
+                    session.max_heart_rate = Some(f.raw_value.0)
+                },
+                Session::AvgPower(f) if f.is_valid() => {
+                    session.avg_power = Some(f.raw_value.0)
+                },
+                Session::MaxPower(f) if f.is_valid() => {
+                    session.max_power = Some(f.raw_value.0)
+                },
+                Session::TrainingStressScore(f) if f.is_valid() => {
+                    session.training_stress_score = Some(f.value())
+                },
+                _ => (),
+            }
+        }
+        Some(session)
+    }
+}
+
+fn header(
+    messages: &[Data],
+    session: &SessionSummary,
+    devices: &[DeviceInfo],
+) -> Block {
+    let date = creator::created_at(messages).and_then(|created| {
+        let date = created.time;
+        match time::local_offset(messages) {
+            Some(offset) => {
+                date.to_local(offset)
+                    .map(|date| date.format("%Y-%m-%d %H:%M").to_string())
+            },
+            None => {
+                date.to_utc()
+                    .map(|date| date.format("%Y-%m-%d %H:%M UTC").to_string())
+            },
+        }
+    });
+
+    let device = devices
+        .iter()
+        .find(|info| info.device_index == Some(DeviceIndex::CREATOR))
+        .map(devices::device_display_name)
+        .or_else(|| {
+            let file_id = messages.iter().find(|mesg| {
+                matches!(mesg.0.first(), Some(Message::FileId(_)))
+            })?;
+            devices::file_product_id(file_id)?.name()
+        });
+
+    Block::Fields(vec![
+        ("Date", or_dash(date)),
+        ("Sport", or_dash(session.sport.as_ref().map(|s| format!("{:?}", s)))),
+        ("Device", or_dash(device)),
+    ])
+}
+
+fn summary(session: &SessionSummary) -> Block {
+    Block::Fields(vec![
+        ("Time", time_or_dash(session.total_timer_time)),
+        ("Elapsed time", time_or_dash(session.total_elapsed_time)),
+        ("Distance", km_or_dash(session.total_distance)),
+        (
+            "Heart rate",
+            avg_max(session.avg_heart_rate, session.max_heart_rate, "bpm"),
+        ),
+        ("Power", avg_max(session.avg_power, session.max_power, "W")),
+        (
+            "TSS",
+            or_dash(
+                session.training_stress_score.map(|tss| format!("{:.1}", tss)),
+            ),
+        ),
+    ])
+}
+
+fn lap_table(laps: &[Lap]) -> Block {
+    let rows = laps
+        .iter()
+        .enumerate()
+        .map(|(i, lap)| {
+            vec![
+                (i + 1).to_string(),
+                time_or_dash(lap.total_timer_time.or(lap.total_elapsed_time)),
+                km_or_dash(lap.total_distance),
+                or_dash(lap.avg_power.map(|p| format!("{} W", p))),
+                or_dash(lap.avg_heart_rate.map(|hr| format!("{} bpm", hr))),
+            ]
+        })
+        .collect();
+
+    Block::Table(vec!["Lap", "Time", "Distance", "Avg power", "Avg HR"], rows)
+}
+
+fn device_list(devices: &[DeviceInfo]) -> Block {
+    Block::Fields(
+        devices
+            .iter()
+            .map(|info| {
+                let mut value = devices::device_display_name(info);
+                if let Some(serial) = info.serial_number {
+                    let _ = write!(value, ", serial {}", serial);
+                }
+                if let Some(voltage) = info.battery_voltage {
+                    let _ = write!(value, ", battery {:.2} V", voltage);
+                }
+                let name = match info.device_index {
+                    Some(DeviceIndex::CREATOR) => "Creator",
+                    _ => "Sensor",
+                };
+                (name, value)
+            })
+            .collect(),
+    )
+}
+
+fn or_dash(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("-"))
+}
+
+fn time_or_dash(seconds: Option<f64>) -> String {
+    or_dash(seconds.map(format_time))
+}
+
+fn km_or_dash(metres: Option<f64>) -> String {
+    or_dash(metres.map(|m| format!("{:.2} km", m / 1000.0)))
+}
+
+fn avg_max<T: ToString>(avg: Option<T>, max: Option<T>, units: &str) -> String {
+    if avg.is_none() && max.is_none() {
+        return String::from("-")
+    }
+    format!(
+        "avg {} / max {} {}",
+        or_dash(avg.map(|v| v.to_string())),
+        or_dash(max.map(|v| v.to_string())),
+        units
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use report::completeness;
+    use testutil::FitBuilder;
+
+    fn activity() -> File {
+        File::from_bytes(&activity_bytes(false)).unwrap()
+    }
+
+    /// The activity, with every message in the same byte
+    /// order.
+    fn activity_bytes(big_endian: bool) -> Vec<u8> {
+        macro_rules! bytes {
+            ($value:expr) => {
+                if big_endian {
+                    $value.to_be_bytes()
+                }
+                else {
+                    $value.to_le_bytes()
+                }
+            };
+        }
+        let define = |fit: &mut FitBuilder, local, mesg, fields: &[_]| {
+            if big_endian {
+                fit.big_endian_definition(local, mesg, fields);
+            }
+            else {
+                fit.definition(local, mesg, fields);
+            }
+        };
+
+        let mut fit = FitBuilder::new();
+        // FileId: activity, Garmin Fenix 5
+        define(&mut fit, 0, 0, &[(0, 1, 0x00), (1, 2, 0x84), (2, 2, 0x84)]);
+        fit.data(0, &[&[4], &bytes!(1u16), &bytes!(2697u16)]);
+
+        // Laps: timestamp, start_time, timer time, distance,
+        // avg heart rate, avg power
+        define(
+            &mut fit,
+            1,
+            19,
+            &[
+                (253, 4, 0x86),
+                (2, 4, 0x86),
+                (8, 4, 0x86),
+                (9, 4, 0x86),
+                (15, 1, 0x02),
+                (19, 2, 0x84),
+            ],
+        );
+        for &(start, timer_ms, distance_cm, hr, power) in &[
+            (899_337_600u32, 600_000u32, 500_000u32, 142u8, 215u16),
+            (899_338_200, 1_845_500, 1_250_000, 151, 238),
+        ] {
+            fit.data(
+                1,
+                &[
+                    &bytes!(start + timer_ms / 1000),
+                    &bytes!(start),
+                    &bytes!(timer_ms),
+                    &bytes!(distance_cm),
+                    &[hr],
+                    &bytes!(power),
+                ],
+            );
+        }
+
+        // Session: cycling, 40:46 and 17.5 km, 160/240 W
+        define(
+            &mut fit,
+            2,
+            18,
+            &[
+                (2, 4, 0x86),
+                (5, 1, 0x00),
+                (8, 4, 0x86),
+                (9, 4, 0x86),
+                (16, 1, 0x02),
+                (17, 1, 0x02),
+                (20, 2, 0x84),
+                (21, 2, 0x84),
+                (35, 2, 0x84),
+            ],
+        );
+        fit.data(
+            2,
+            &[
+                &bytes!(899_337_600u32),
+                &[2],
+                &bytes!(2_445_500u32),
+                &bytes!(1_750_000u32),
+                &[148],
+                &[181],
+                &bytes!(231u16),
+                &bytes!(612u16),
+                &bytes!(523u16),
+            ],
+        );
+        fit.build()
+    }
+
+    #[test]
+    fn markdown_report() {
+        let reporter = FitReporter::new(ReportOptions {
+            include_laps:        true,
+            include_device_info: false,
+            format:              ReportFormat::Markdown,
+        });
+        let report = reporter.report(&activity());
+
+        for header in &["## Header", "## Summary", "## Laps"] {
+            assert!(report.contains(header), "{}", report);
+        }
+        assert!(!report.contains("## Device Info"));
+        assert!(report.contains("- **Date:** 2018-07-01 00:00 UTC"));
+        assert!(report.contains("- **Sport:** Cycling"));
+        assert!(report.contains("- **Device:** Fenix5"));
+        assert!(report.contains("- **Time:** 0:40:46"));
+        assert!(report.contains("- **Distance:** 17.50 km"));
+        assert!(report.contains("- **Heart rate:** avg 148 / max 181 bpm"));
+        assert!(report.contains("- **Power:** avg 231 / max 612 W"));
+        assert!(report.contains("- **TSS:** 52.3"));
+
+        assert!(report.contains("| 1 | 0:10:00 | 5.00 km | 215 W | 142 bpm |"));
+        assert!(report.contains("| 2 | 0:30:46 | 12.50 km | 238 W | 151 bpm |"));
+    }
+
+    #[test]
+    fn plain_text_report() {
+        let reporter = FitReporter::new(ReportOptions {
+            include_device_info: true,
+            ..ReportOptions::default()
+        });
+        let report = reporter.report(&activity());
+
+        assert!(report.starts_with("Header\n------\n"));
+        assert!(report.contains("\nDevice Info\n-----------\n"));
+        assert!(report.contains("Lap     Time  Distance  Avg power   Avg HR"));
+        assert!(report.contains("  1  0:10:00   5.00 km      215 W  142 bpm"));
+        assert!(report.contains("Elapsed time: -"));
+    }
+
+    #[test]
+    fn reports_are_reproducible() {
+        let reporter = FitReporter::new(ReportOptions {
+            include_laps:        true,
+            include_device_info: true,
+            format:              ReportFormat::Markdown,
+        });
+        let export = |bytes: &[u8]| {
+            let fit = File::from_bytes(bytes).unwrap();
+            let messages: Vec<Data> = fit.messages().cloned().collect();
+            let completeness = format!("{:?}", completeness(&messages));
+            (reporter.report(&fit), completeness)
+        };
+
+        let little_endian = activity_bytes(false);
+        let big_endian = activity_bytes(true);
+        assert_ne!(little_endian, big_endian);
+        let first = export(&little_endian);
+        assert_eq!(export(&little_endian), first);
+        assert_eq!(export(&big_endian), first);
+    }
+}
@@ -0,0 +1,498 @@
+//! Schemas for the messages the decoder emits, for defining
+//! tables ahead of time.
+//!
+//! [`json_schema`] describes every profile message as
+//! [`to_json`] writes it, and [`arrow_schema`] (with the
+//! `arrow` feature) gives one message as an Arrow schema.
+//! Both come from the same profile tables the decoder
+//! uses, so they can't drift from what it emits. Each
+//! field's type is what [`Message::value`] makes of it,
+//! humanised the way the Python module does: positions in
+//! degrees, unscaled integers as integers and date times
+//! as times. Every field is nullable, since any of them
+//! can hold its invalid value or be left out. Developer
+//! fields aren't described.
+//!
+//! [`json_schema`]: fn.json_schema.html
+//! [`to_json`]: fn.to_json.html
+//! [`arrow_schema`]: fn.arrow_schema.html
+//! [`Message::value`]: ../profile/messages/enum.Message.html#method.value
+#[cfg(feature = "arrow")]
+use arrow_schema::{
+    self,
+    DataType,
+    Field as ArrowField,
+    TimeUnit,
+};
+use byteorder::LittleEndian;
+use geo::semicircles_to_degrees;
+#[cfg(feature = "arrow")]
+use profile::types::MesgNum;
+use profile::messages::{
+    field_name,
+    field_scale_offset,
+    field_units,
+    message_name,
+    Message,
+    FIELDS,
+};
+use serde_json::{
+    json,
+    Map,
+    Value as Json,
+};
+#[cfg(feature = "arrow")]
+use std::collections::HashMap;
+use types::{
+    field::{
+        Precision,
+        Value,
+    },
+    record::Data,
+};
+
+/// What the decoder makes of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Integer {
+        bits:   u8,
+        signed: bool,
+    },
+    Float,
+    /// Semicircles, as degrees.
+    Degrees,
+    Numbers,
+    Bool,
+    Text,
+    Texts,
+    Bytes,
+    DateTime,
+    LocalDateTime,
+    Enum,
+}
+
+/// Decodes to a valid value of every base type: negative
+/// for signed ones, fractional for floats.
+const PROBE: [u8; 16] = [0x80; 16];
+
+/// The kind of field `field_def_num` of message `mesg_num`,
+/// found by decoding a probe value for it.
+fn kind(mesg_num: u16, field_def_num: u8) -> Option<Kind> {
+    let field =
+        Message::decode::<LittleEndian>(&PROBE, mesg_num, field_def_num)
+            .ok()?;
+    let kind = match field.value() {
+        Value::Number(_)
+            if field_units(mesg_num, field_def_num) == Some("semicircles") =>
+        {
+            Kind::Degrees
+        },
+        Value::Number(value)
+            if field_scale_offset(mesg_num, field_def_num) == (None, None)
+                && value.fract() == 0.0 =>
+        {
+            let mut bytes = Vec::new();
+            field.encode(&mut bytes);
+            Kind::Integer {
+                bits: bytes.len() as u8 * 8, signed: value < 0.0
+            }
+        },
+        Value::Number(_) => Kind::Float,
+        Value::Numbers(_) => Kind::Numbers,
+        Value::Bool(_) => Kind::Bool,
+        Value::Text(_) => Kind::Text,
+        Value::Texts(_) => Kind::Texts,
+        Value::Bytes(_) => Kind::Bytes,
+        Value::DateTime(_) => Kind::DateTime,
+        Value::LocalDateTime(_) => Kind::LocalDateTime,
+        Value::Enum(_) => Kind::Enum,
+        Value::Invalid => return None,
+    };
+    Some(kind)
+}
+
+/// The known fields of message `mesg_num`, in field
+/// number order.
+fn fields(mesg_num: u16) -> impl Iterator<Item = (u8, &'static str, Kind)> {
+    FIELDS.iter().filter(move |&&(mesg, ..)| mesg == mesg_num).filter_map(
+        move |&(_, num, name, ..)| Some((num, name, kind(mesg_num, num)?)),
+    )
+}
+
+/// The profile's messages, in message number order.
+fn messages() -> impl Iterator<Item = (u16, &'static str)> {
+    let mut nums: Vec<u16> =
+        FIELDS.iter().map(|&(mesg, ..)| mesg).collect();
+    nums.dedup();
+    nums.into_iter().filter_map(|num| Some((num, message_name(num)?)))
+}
+
+/// Matches `unknown_<number>`, as fields and messages
+/// outside the profile are named.
+const UNKNOWN: &str = "^unknown_[0-9]+$";
+
+/// A JSON Schema (draft 2020-12) for the objects
+/// [`to_json`] makes of data messages.
+///
+/// Each message is a `{"message": name, "fields": {...}}`
+/// object, with `fields` defined per message under
+/// `$defs`.
+///
+/// [`to_json`]: fn.to_json.html
+pub fn json_schema() -> Json {
+    let bytes = json!({
+        "type": ["array", "null"],
+        "items": {"type": "integer", "minimum": 0, "maximum": 255},
+    });
+    let mut defs = Map::new();
+    let mut variants = Vec::new();
+    for (mesg_num, mesg_name) in messages() {
+        let mut properties = Map::new();
+        for (num, name, kind) in fields(mesg_num) {
+            let mut property = json_type(kind);
+            if let Some(units) = field_units(mesg_num, num) {
+                let units =
+                    if kind == Kind::Degrees { "degrees" } else { units };
+                property["description"] = json!(units);
+            }
+            properties.insert(name.to_owned(), property);
+        }
+        defs.insert(
+            mesg_name.to_owned(),
+            json!({
+                "type": "object",
+                "properties": properties,
+                "patternProperties": {UNKNOWN: bytes},
+                "additionalProperties": false,
+            }),
+        );
+        variants.push(json!({
+            "properties": {
+                "message": {"const": mesg_name},
+                "fields": {"$ref": format!("#/$defs/{}", mesg_name)},
+            },
+        }));
+    }
+    defs.insert(
+        "unknown".to_owned(),
+        json!({
+            "type": "object",
+            "patternProperties": {UNKNOWN: bytes},
+            "additionalProperties": false,
+        }),
+    );
+    variants.push(json!({
+        "properties": {
+            "message": {"type": "string", "pattern": UNKNOWN},
+            "fields": {"$ref": "#/$defs/unknown"},
+        },
+    }));
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "garminfit data message",
+        "type": "object",
+        "required": ["message", "fields"],
+        "properties": {
+            "message": {"type": "string"},
+            "fields": {"type": "object"},
+        },
+        "additionalProperties": false,
+        "oneOf": variants,
+        "$defs": defs,
+    })
+}
+
+fn json_type(kind: Kind) -> Json {
+    match kind {
+        Kind::Integer {
+            ..
+        } => json!({"type": ["integer", "null"]}),
+        Kind::Float | Kind::Degrees => json!({"type": ["number", "null"]}),
+        Kind::Numbers => {
+            json!({"type": ["array", "null"], "items": {"type": "number"}})
+        },
+        Kind::Bool => json!({"type": ["boolean", "null"]}),
+        Kind::Text | Kind::Enum => json!({"type": ["string", "null"]}),
+        Kind::Texts => {
+            json!({"type": ["array", "null"], "items": {"type": "string"}})
+        },
+        Kind::Bytes => {
+            json!({
+                "type": ["array", "null"],
+                "items": {"type": "integer", "minimum": 0, "maximum": 255},
+            })
+        },
+        // Times since power on have no date, so stay seconds
+        Kind::DateTime => {
+            json!({"type": ["string", "integer", "null"], "format": "date-time"})
+        },
+        Kind::LocalDateTime => json!({"type": ["string", "integer", "null"]}),
+    }
+}
+
+/// `mesg` as an object that [`json_schema`] describes, with
+/// numbers at full precision.
+///
+/// [`json_schema`]: fn.json_schema.html
+pub fn to_json(mesg: &Data) -> Json {
+    to_json_with(mesg, Precision::Full)
+}
+
+/// Like [`to_json`], with scaled numbers rounded by
+/// `precision` for the scale the profile gives each field.
+///
+/// [`to_json`]: fn.to_json.html
+pub fn to_json_with(mesg: &Data, precision: Precision) -> Json {
+    let mesg_num = mesg.0.first().map_or(u16::MAX, Message::mesg_num);
+    let mesg_name = message_name(mesg_num)
+        .map_or_else(|| format!("unknown_{}", mesg_num), str::to_owned);
+    let mut fields = Map::new();
+    for field in mesg.0.iter() {
+        let num = field.field_def_num();
+        let name = field_name(mesg_num, num)
+            .map_or_else(|| format!("unknown_{}", num), str::to_owned);
+        fields.insert(name, field_json(field, precision));
+    }
+    json!({"message": mesg_name, "fields": fields})
+}
+
+fn field_json(field: &Message, precision: Precision) -> Json {
+    let (mesg_num, num) = (field.mesg_num(), field.field_def_num());
+    let (scale, _) = field_scale_offset(mesg_num, num);
+    match field.value() {
+        Value::Number(value) => {
+            match kind(mesg_num, num) {
+                Some(Kind::Degrees) => {
+                    json!(semicircles_to_degrees(value as i32))
+                },
+                Some(Kind::Integer {
+                    signed: false, ..
+                }) => json!(value as u64),
+                Some(Kind::Integer {
+                    signed: true, ..
+                }) => json!(value as i64),
+                _ => json!(precision.round(value, scale)),
+            }
+        },
+        Value::Numbers(values) => {
+            let values: Vec<f64> = values
+                .into_iter()
+                .map(|value| precision.round(value, scale))
+                .collect();
+            json!(values)
+        },
+        Value::Bool(value) => json!(value),
+        Value::Text(text) => json!(text),
+        Value::Texts(texts) => json!(texts),
+        Value::Bytes(bytes) => json!(bytes),
+        Value::DateTime(time) => {
+            match time.to_utc() {
+                Some(utc) => json!(utc.to_rfc3339()),
+                None => json!(time.0),
+            }
+        },
+        Value::LocalDateTime(time) => {
+            match time.to_naive_local() {
+                Some(local) => {
+                    json!(local.format("%Y-%m-%dT%H:%M:%S").to_string())
+                },
+                None => json!(time.0),
+            }
+        },
+        Value::Enum(name) => json!(name),
+        Value::Invalid => Json::Null,
+    }
+}
+
+/// An Arrow schema for message `mesg`, one nullable column
+/// per profile field, with the field's units in its
+/// metadata.
+///
+/// Date times are UTC timestamps and local date times are
+/// timestamps without a time zone, both in seconds, so
+/// times since power on don't fit either: leave them null.
+/// `None` for `Unknown` and the numbers without a message.
+#[cfg(feature = "arrow")]
+pub fn arrow_schema(mesg: MesgNum) -> Option<arrow_schema::Schema> {
+    let mesg_num = mesg.value();
+    let mesg_name = message_name(mesg_num)?;
+    let columns: Vec<ArrowField> = fields(mesg_num)
+        .map(|(num, name, kind)| {
+            let column = ArrowField::new(name, arrow_type(kind), true);
+            let units = match kind {
+                Kind::Degrees => Some("degrees"),
+                _ => field_units(mesg_num, num),
+            };
+            match units {
+                Some(units) => {
+                    column.with_metadata(HashMap::from([(
+                        "units".to_owned(),
+                        units.to_owned(),
+                    )]))
+                },
+                None => column,
+            }
+        })
+        .collect();
+    Some(arrow_schema::Schema::new_with_metadata(
+        columns,
+        HashMap::from([("message".to_owned(), mesg_name.to_owned())]),
+    ))
+}
+
+#[cfg(feature = "arrow")]
+fn arrow_type(kind: Kind) -> DataType {
+    let list =
+        |item| DataType::List(ArrowField::new("item", item, true).into());
+    match kind {
+        Kind::Integer {
+            bits,
+            signed: true,
+        } => {
+            match bits {
+                8 => DataType::Int8,
+                16 => DataType::Int16,
+                32 => DataType::Int32,
+                _ => DataType::Int64,
+            }
+        },
+        Kind::Integer {
+            bits,
+            signed: false,
+        } => {
+            match bits {
+                8 => DataType::UInt8,
+                16 => DataType::UInt16,
+                32 => DataType::UInt32,
+                _ => DataType::UInt64,
+            }
+        },
+        Kind::Float | Kind::Degrees => DataType::Float64,
+        Kind::Numbers => list(DataType::Float64),
+        Kind::Bool => DataType::Boolean,
+        Kind::Text | Kind::Enum => DataType::Utf8,
+        Kind::Texts => list(DataType::Utf8),
+        Kind::Bytes => DataType::Binary,
+        Kind::DateTime => {
+            DataType::Timestamp(TimeUnit::Second, Some("UTC".into()))
+        },
+        Kind::LocalDateTime => DataType::Timestamp(TimeUnit::Second, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate jsonschema;
+
+    use super::*;
+    use testutil::{
+        mesg,
+        record_file,
+    };
+    use types::file::File;
+
+    #[test]
+    fn field_kinds() {
+        // record: position_lat, heart_rate, distance,
+        // timestamp, compressed_speed_distance
+        assert_eq!(kind(20, 0), Some(Kind::Degrees));
+        assert_eq!(
+            kind(20, 3),
+            Some(Kind::Integer {
+                bits: 8, signed: false
+            })
+        );
+        assert_eq!(kind(20, 5), Some(Kind::Float));
+        assert_eq!(kind(20, 253), Some(Kind::DateTime));
+        assert_eq!(kind(20, 8), Some(Kind::Bytes));
+        // record: left_pco, sport: sport, event: timer_trigger
+        assert_eq!(
+            kind(20, 67),
+            Some(Kind::Integer {
+                bits: 8, signed: true
+            })
+        );
+        assert_eq!(kind(12, 0), Some(Kind::Enum));
+        assert_eq!(kind(34, 5), Some(Kind::LocalDateTime));
+        assert_eq!(kind(206, 3), Some(Kind::Texts));
+    }
+
+    #[test]
+    fn decoded_messages_match_the_schema() {
+        let schema = json_schema();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let mut messages: Vec<Data> = File::from_bytes(&record_file(5))
+            .unwrap()
+            .messages()
+            .cloned()
+            .collect();
+        // An activity saved at 01:46:40 UTC, 03:46:40 local
+        messages.push(
+            mesg(34)
+                .u32(253, 1_000_000_000)
+                .u32(5, 1_000_007_200)
+                .u8(2, 0)
+                .build(),
+        );
+        for mesg in &messages {
+            let json = to_json(mesg);
+            let errors: Vec<String> =
+                validator.iter_errors(&json).map(|e| e.to_string()).collect();
+            assert!(errors.is_empty(), "{}: {:?}", json, errors);
+        }
+
+        let record = to_json(&messages[1]);
+        assert_eq!(record["message"], "record");
+        assert_eq!(record["fields"]["heart_rate"], 100);
+        assert_eq!(record["fields"]["timestamp"], 1_000_000);
+        let activity = to_json(&messages[6]);
+        assert_eq!(activity["message"], "activity");
+        assert_eq!(
+            activity["fields"]["timestamp"],
+            "2021-09-08T01:46:40+00:00"
+        );
+        assert_eq!(
+            activity["fields"]["local_timestamp"],
+            "2021-09-08T03:46:40"
+        );
+        assert_eq!(activity["fields"]["type"], "Manual");
+
+        // Speed has a scale of 1000
+        let speed = mesg(20).u16(6, 3520).build();
+        let rounded = to_json_with(&speed, Precision::SpecImplied);
+        assert_eq!(rounded["fields"]["speed"], 3.52);
+        assert_eq!(
+            to_json_with(&speed, Precision::Fixed(1))["fields"]["speed"],
+            3.5
+        );
+
+        // What doesn't match the profile doesn't validate
+        let mut wrong = record.clone();
+        wrong["fields"]["heart_rate"] = json!("fast");
+        assert!(!validator.is_valid(&wrong));
+        wrong["fields"] = json!({"heart_rates": 100});
+        assert!(!validator.is_valid(&wrong));
+    }
+
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn arrow_schemas() {
+        let schema = arrow_schema(MesgNum::Record).unwrap();
+        assert_eq!(schema.metadata["message"], "record");
+        let lat = schema.field_with_name("position_lat").unwrap();
+        assert_eq!(lat.data_type(), &DataType::Float64);
+        assert_eq!(lat.metadata()["units"], "degrees");
+        assert!(lat.is_nullable());
+        assert_eq!(
+            schema.field_with_name("heart_rate").unwrap().data_type(),
+            &DataType::UInt8
+        );
+        assert_eq!(
+            schema.field_with_name("timestamp").unwrap().data_type(),
+            &DataType::Timestamp(TimeUnit::Second, Some("UTC".into()))
+        );
+        assert!(arrow_schema(MesgNum::Unknown).is_none());
+    }
+}
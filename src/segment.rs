@@ -0,0 +1,594 @@
+//! Attempts at segments ridden during an activity, and how
+//! they compare with the segment's leaderboard.
+//!
+//! A segment file also carries the leaderboard along the
+//! way: each `SegmentPoint` has a `leader_time` for every
+//! entry, which [`virtual_partner`] turns into a curve to
+//! race against.
+//!
+//! [`virtual_partner`]: fn.virtual_partner.html
+use profile::{
+    base::Valid,
+    messages::{
+        Message,
+        SegmentId,
+        SegmentLap,
+        SegmentLeaderboardEntry,
+        SegmentPoint,
+    },
+    types::{
+        SegmentLapStatus,
+        SegmentLeaderboardType,
+    },
+};
+use types::{
+    field::{
+        scale_value,
+        Field,
+    },
+    record::Data,
+};
+
+/// How an attempt at a segment ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptStatus {
+    /// The segment was ridden start to finish.
+    Completed,
+    /// The device marked the attempt as failed, which is
+    /// also how ineligible attempts are recorded.
+    Failed,
+    /// The segment was entered but never finished, so the
+    /// attempt has no end.
+    Unfinished,
+}
+
+/// How an attempt compares with one leaderboard entry.
+#[derive(Debug, Clone)]
+pub struct LeaderboardDelta {
+    pub name:          Option<String>,
+    pub board_type:    SegmentLeaderboardType,
+    /// Seconds.
+    pub leader_time_s: f64,
+    /// Seconds behind the leader, negative when ahead.
+    pub delta_s:       f64,
+}
+
+/// A single `SegmentLeaderboardEntry` message: somebody's
+/// time on a segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    /// The entry's `message_index`, or 0xFFFF if missing.
+    pub message_index:  u16,
+    pub name:           Option<String>,
+    /// Seconds.
+    pub elapsed_time_s: f64,
+    /// The raw `SegmentLeaderboardType`. Values the profile
+    /// doesn't know all read as 11.
+    pub entry_type:     u8,
+}
+
+impl LeaderboardEntry {
+    /// Flatten a decoded `SegmentLeaderboardEntry` data
+    /// message.
+    ///
+    /// Returns `None` if `mesg` isn't a
+    /// `SegmentLeaderboardEntry` message or has no valid
+    /// segment time.
+    pub fn from_message(mesg: &Data) -> Option<LeaderboardEntry> {
+        let (name, board_type, elapsed_time_s) = leaderboard_entry(mesg)?;
+        let message_index = mesg
+            .0
+            .iter()
+            .find_map(Message::message_index)
+            .map(|index| index.index())
+            .unwrap_or(u16::MAX);
+        Some(LeaderboardEntry {
+            message_index,
+            name,
+            elapsed_time_s,
+            entry_type: board_type as u8,
+        })
+    }
+}
+
+/// Every leaderboard entry with a time among `messages`, in
+/// file order.
+pub fn extract_leaderboard(messages: &[Data]) -> Vec<LeaderboardEntry> {
+    messages.iter().filter_map(LeaderboardEntry::from_message).collect()
+}
+
+/// Where `my_time_s` would place on `leaderboard`, counting
+/// from 1 for the fastest. Ties share the better rank.
+pub fn rank_in_leaderboard(
+    my_time_s: f64,
+    leaderboard: &[LeaderboardEntry],
+) -> usize {
+    1 + leaderboard
+        .iter()
+        .filter(|entry| entry.elapsed_time_s < my_time_s)
+        .count()
+}
+
+/// Seconds `my_time_s` is behind the fastest entry on
+/// `leaderboard`, negative when ahead, or NaN if the
+/// leaderboard is empty.
+pub fn time_gap_to_leader_s(
+    my_time_s: f64,
+    leaderboard: &[LeaderboardEntry],
+) -> f64 {
+    leaderboard
+        .iter()
+        .map(|entry| entry.elapsed_time_s)
+        .fold(None, |leader: Option<f64>, time| {
+            Some(leader.map_or(time, |leader| leader.min(time)))
+        })
+        .map_or(f64::NAN, |leader| my_time_s - leader)
+}
+
+/// A single `SegmentLap` message: one go at a segment.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub uuid:        Option<String>,
+    /// From the `SegmentId` message with the same UUID if
+    /// there is one, otherwise from the lap itself.
+    pub name:        Option<String>,
+    /// Counts from 1 for each segment, in file order.
+    pub attempt:     u32,
+    /// Seconds since the FIT epoch.
+    pub start:       Option<u32>,
+    /// Seconds since the FIT epoch.
+    pub end:         Option<u32>,
+    /// Seconds, pauses included.
+    pub elapsed_s:   Option<f64>,
+    pub status:      AttemptStatus,
+    /// Empty unless the attempt was completed and the
+    /// segment file was supplied.
+    pub leaderboard: Vec<LeaderboardDelta>,
+}
+
+impl Attempt {
+    /// Flatten a decoded `SegmentLap` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `SegmentLap`
+    /// message. The attempt number is left at 1 and the
+    /// leaderboard empty.
+    pub fn from_message(mesg: &Data) -> Option<Attempt> {
+        let mut attempt = Attempt {
+            uuid:        None,
+            name:        None,
+            attempt:     1,
+            start:       None,
+            end:         None,
+            elapsed_s:   None,
+            status:      AttemptStatus::Unfinished,
+            leaderboard: Vec::new(),
+        };
+        let mut status = None;
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::SegmentLap(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                SegmentLap::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    attempt.end = Some(f.raw_value.0)
+                },
+                SegmentLap::StartTime(f) if f.raw_value.0 != u32::MAX => {
+                    attempt.start = Some(f.raw_value.0)
+                },
+                SegmentLap::TotalElapsedTime(f) if f.is_valid() => {
+                    attempt.elapsed_s = Some(f.value())
+                },
+                SegmentLap::Name(f) => attempt.name = text(&f.raw_value.0),
+                SegmentLap::Uuid(f) => attempt.uuid = text(&f.raw_value.0),
+                SegmentLap::Status(f) => status = Some(f.raw_value.clone()),
+                _ => (),
+            }
+        }
+
+        attempt.status = match status {
+            Some(SegmentLapStatus::Fail) => AttemptStatus::Failed,
+            _ if attempt.end.is_none() => AttemptStatus::Unfinished,
+            _ => AttemptStatus::Completed,
+        };
+        if attempt.elapsed_s.is_none() {
+            if let (Some(start), Some(end)) = (attempt.start, attempt.end) {
+                attempt.elapsed_s = Some(f64::from(end.saturating_sub(start)));
+            }
+        }
+        Some(attempt)
+    }
+}
+
+/// Every segment attempt among `messages`, in file order.
+pub fn attempts(messages: &[Data]) -> Vec<Attempt> {
+    attempts_with_segments(messages, &[])
+}
+
+/// Every segment attempt among `messages`, with completed
+/// attempts compared against the leaderboard of the
+/// matching file in `segments`.
+///
+/// A segment file matches an attempt when its `SegmentId`
+/// has the attempt's UUID. Names are taken from `SegmentId`
+/// messages in the activity first, then in the segment
+/// files, then from the attempt itself.
+pub fn attempts_with_segments(
+    messages: &[Data],
+    segments: &[&[Data]],
+) -> Vec<Attempt> {
+    let mut attempts: Vec<Attempt> =
+        messages.iter().filter_map(Attempt::from_message).collect();
+
+    let embedded: Vec<Ident> = messages.iter().filter_map(ident).collect();
+    let files: Vec<(Ident, &[Data])> = segments
+        .iter()
+        .filter_map(|segment| Some((segment.iter().find_map(ident)?, *segment)))
+        .collect();
+
+    for i in 0..attempts.len() {
+        let uuid = match attempts[i].uuid.clone() {
+            Some(uuid) => uuid,
+            None => continue,
+        };
+        let same = |other: &Option<String>| other.as_ref() == Some(&uuid);
+
+        attempts[i].attempt =
+            1 + attempts[..i].iter().filter(|a| same(&a.uuid)).count() as u32;
+
+        let name = embedded
+            .iter()
+            .chain(files.iter().map(|file| &file.0))
+            .filter(|id| same(&id.uuid))
+            .find_map(|id| id.name.clone());
+        let attempt = &mut attempts[i];
+        if name.is_some() {
+            attempt.name = name;
+        }
+
+        let elapsed = match (attempt.status, attempt.elapsed_s) {
+            (AttemptStatus::Completed, Some(elapsed)) => elapsed,
+            _ => continue,
+        };
+        if let Some(&(_, file)) = files.iter().find(|file| same(&file.0.uuid)) {
+            attempt.leaderboard = file
+                .iter()
+                .filter_map(leaderboard_entry)
+                .map(|(name, board_type, leader_time_s)| {
+                    LeaderboardDelta {
+                        name,
+                        board_type,
+                        leader_time_s,
+                        delta_s: elapsed - leader_time_s,
+                    }
+                })
+                .collect();
+        }
+    }
+    attempts
+}
+
+/// How far along the segment and how long into it one
+/// leaderboard entry was at each `SegmentPoint` of
+/// `segment`, in file order: `(distance_m, elapsed_s)`.
+///
+/// `leaderboard_index` picks the entry, as the
+/// `leader_time` arrays are indexed, which is the entry's
+/// `message_index`. Points without a distance or without a
+/// valid time for that entry are left out, so the curve
+/// may be sparse but never has made up zeros in it.
+pub fn virtual_partner(
+    segment: &[Data],
+    leaderboard_index: usize,
+) -> Vec<(f64, f64)> {
+    segment
+        .iter()
+        .filter_map(|mesg| {
+            let mut distance = None;
+            let mut time = None;
+            for field in mesg.0.iter() {
+                match field {
+                    Message::SegmentPoint(SegmentPoint::Distance(f))
+                        if f.is_valid() =>
+                    {
+                        distance = Some(f.value())
+                    },
+                    Message::SegmentPoint(SegmentPoint::LeaderTime(f)) => {
+                        time = f
+                            .raw_value
+                            .get(leaderboard_index)
+                            .filter(|raw| raw.is_valid())
+                            .map(|raw| {
+                                scale_value(f64::from(raw.0), f.scale, None)
+                            })
+                    },
+                    Message::SegmentPoint(_) => (),
+                    _ => return None,
+                }
+            }
+            Some((distance?, time?))
+        })
+        .collect()
+}
+
+/// Seconds into the segment the partner of `curve` was
+/// `distance_m` along it, interpolating linearly between
+/// points, or `None` off either end of the curve.
+pub fn partner_time_at(curve: &[(f64, f64)], distance_m: f64) -> Option<f64> {
+    interpolate(curve.iter().cloned(), distance_m)
+}
+
+/// Metres along the segment the partner of `curve` was
+/// `elapsed_s` into it, interpolating linearly between
+/// points, or `None` off either end of the curve. This is
+/// where to draw the partner while racing it.
+pub fn partner_distance_at(
+    curve: &[(f64, f64)],
+    elapsed_s: f64,
+) -> Option<f64> {
+    interpolate(curve.iter().map(|&(d, t)| (t, d)), elapsed_s)
+}
+
+/// The `y` at `x` on the line through `points`, which are
+/// in increasing `x`.
+fn interpolate<I>(points: I, x: f64) -> Option<f64>
+where
+    I: Iterator<Item = (f64, f64)>,
+{
+    let mut previous: Option<(f64, f64)> = None;
+    for (x1, y1) in points {
+        if x1 == x {
+            return Some(y1);
+        }
+        if x1 > x {
+            let (x0, y0) = previous?;
+            return Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0));
+        }
+        previous = Some((x1, y1));
+    }
+    None
+}
+
+/// The UUID and name of a segment.
+struct Ident {
+    uuid: Option<String>,
+    name: Option<String>,
+}
+
+/// The identity of the segment in a `SegmentId` message.
+fn ident(mesg: &Data) -> Option<Ident> {
+    let mut id = Ident {
+        uuid: None, name: None
+    };
+    for field in mesg.0.iter() {
+        match field {
+            Message::SegmentId(SegmentId::Uuid(f)) => {
+                id.uuid = text(&f.raw_value.0)
+            },
+            Message::SegmentId(SegmentId::Name(f)) => {
+                id.name = text(&f.raw_value.0)
+            },
+            Message::SegmentId(_) => (),
+            _ => return None,
+        }
+    }
+    Some(id)
+}
+
+/// The name, type and segment time in seconds of a
+/// `SegmentLeaderboardEntry` message with a time.
+fn leaderboard_entry(
+    mesg: &Data,
+) -> Option<(Option<String>, SegmentLeaderboardType, f64)> {
+    let mut name = None;
+    let mut board_type = SegmentLeaderboardType::Unknown;
+    let mut time = None;
+    for field in mesg.0.iter() {
+        let field = match field {
+            Message::SegmentLeaderboardEntry(field) => field,
+            _ => return None,
+        };
+
+        match field {
+            SegmentLeaderboardEntry::Name(f) => name = text(&f.raw_value.0),
+            SegmentLeaderboardEntry::Type(f) => {
+                board_type = f.raw_value.clone()
+            },
+            SegmentLeaderboardEntry::SegmentTime(f) if f.is_valid() => {
+                time = Some(f.value())
+            },
+            _ => (),
+        }
+    }
+    Some((name, board_type, time?))
+}
+
+/// Strings are null padded to their field size.
+fn text(s: &str) -> Option<String> {
+    let s = s.trim_end_matches('\0');
+    if s.is_empty() {
+        None
+    }
+    else {
+        Some(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn segment_id(uuid: &str, name: &str) -> Data {
+        mesg(148).bytes(1, uuid.as_bytes()).bytes(0, name.as_bytes()).build()
+    }
+
+    fn segment_lap(
+        uuid: &str,
+        start: u32,
+        end: Option<u32>,
+        status: u8,
+    ) -> Data {
+        let mut lap = mesg(142).bytes(65, uuid.as_bytes()).u32(2, start);
+        if let Some(end) = end {
+            lap = lap.u32(253, end).u32(7, (end - start) * 1000);
+        }
+        lap.u8(64, status).build()
+    }
+
+    fn leader(board_type: u8, time_ms: u32) -> Data {
+        mesg(149)
+            .bytes(0, b"Leader\0\0")
+            .u8(1, board_type)
+            .u32(4, time_ms)
+            .build()
+    }
+
+    #[test]
+    fn attempts_against_leaderboard() {
+        let activity = vec![
+            segment_id("col-du-test", "Col du Test"),
+            segment_lap("col-du-test", 1000, Some(1300), 0),
+            segment_lap("sprint", 1500, Some(1530), 0),
+            // Cut the corner
+            segment_lap("col-du-test", 2000, Some(2250), 1),
+            segment_lap("col-du-test", 3000, Some(3280), 0),
+            // Ran out of daylight
+            segment_lap("sprint", 3500, None, 0),
+        ];
+        let segment = vec![
+            segment_id("col-du-test", ""),
+            leader(5, 250_000),
+            leader(1, 320_000),
+            leader(7, 0xFFFF_FFFF),
+        ];
+
+        let attempts = attempts_with_segments(&activity, &[&segment]);
+        assert_eq!(attempts.len(), 5);
+
+        let statuses: Vec<(u32, AttemptStatus)> =
+            attempts.iter().map(|a| (a.attempt, a.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (1, AttemptStatus::Completed),
+                (1, AttemptStatus::Completed),
+                (2, AttemptStatus::Failed),
+                (3, AttemptStatus::Completed),
+                (2, AttemptStatus::Unfinished),
+            ]
+        );
+
+        let first = &attempts[0];
+        assert_eq!(first.name.as_deref(), Some("Col du Test"));
+        assert_eq!((first.start, first.end), (Some(1000), Some(1300)));
+        assert_eq!(first.elapsed_s, Some(300.0));
+        let board = &first.leaderboard;
+        assert_eq!(board.len(), 2);
+        assert!(matches!(board[0].board_type, SegmentLeaderboardType::Kom));
+        assert_eq!((board[0].leader_time_s, board[0].delta_s), (250.0, 50.0));
+        assert!(matches!(
+            board[1].board_type,
+            SegmentLeaderboardType::PersonalBest
+        ));
+        assert_eq!(board[1].delta_s, -20.0);
+        assert_eq!(first.leaderboard[0].name.as_deref(), Some("Leader"));
+
+        assert_eq!(attempts[3].leaderboard[1].delta_s, -40.0);
+        assert!(attempts[2].leaderboard.is_empty());
+        assert!(attempts[1].leaderboard.is_empty());
+        assert_eq!(attempts[1].name, None);
+        assert_eq!(attempts[4].end, None);
+        assert_eq!(attempts[4].elapsed_s, None);
+    }
+
+    #[test]
+    fn rank_against_leaderboard() {
+        let segment: Vec<Data> = [312_000, 298_500, 340_000, 355_250, 305_000]
+            .iter()
+            .enumerate()
+            .map(|(i, &time_ms)| {
+                mesg(149).u16(254, i as u16).u8(1, 0).u32(4, time_ms).build()
+            })
+            .chain(vec![leader(5, 0xFFFF_FFFF), segment_id("climb", "Climb")])
+            .collect();
+
+        let board = extract_leaderboard(&segment);
+        assert_eq!(board.len(), 5);
+        assert_eq!(board[1].message_index, 1);
+        assert_eq!(board[1].elapsed_time_s, 298.5);
+        assert_eq!((board[1].entry_type, board[1].name.clone()), (0, None));
+
+        assert_eq!(rank_in_leaderboard(310.0, &board), 3);
+        assert_eq!(time_gap_to_leader_s(310.0, &board), 11.5);
+        assert_eq!(rank_in_leaderboard(298.5, &board), 1);
+        assert_eq!(rank_in_leaderboard(400.0, &board), 6);
+        assert_eq!(time_gap_to_leader_s(290.0, &board), -8.5);
+        assert!(time_gap_to_leader_s(290.0, &[]).is_nan());
+
+        let entry = LeaderboardEntry::from_message(&leader(9, 60_000)).unwrap();
+        assert_eq!(entry.message_index, 0xFFFF);
+        assert_eq!(entry.entry_type, 9);
+    }
+
+    fn point(distance_cm: u32, leader_times_ms: &[u32]) -> Data {
+        let times: Vec<u8> = leader_times_ms
+            .iter()
+            .flat_map(|time| time.to_le_bytes().to_vec())
+            .collect();
+        mesg(150).u32(3, distance_cm).bytes(5, &times).build()
+    }
+
+    #[test]
+    fn virtual_partner_curve() {
+        const NONE: u32 = 0xFFFF_FFFF;
+        let segment = vec![
+            segment_id("climb", "Climb"),
+            leader(5, 100_000),
+            leader(1, 120_000),
+            point(0, &[0, 0]),
+            point(25_000, &[25_000, NONE]),
+            point(50_000, &[50_000, 55_000]),
+            // The second entry's time is missing altogether
+            point(75_000, &[80_000]),
+            point(NONE, &[85_000, 90_000]),
+            point(100_000, &[100_000, 120_000]),
+        ];
+
+        let leader = virtual_partner(&segment, 0);
+        assert_eq!(
+            leader,
+            vec![
+                (0.0, 0.0),
+                (250.0, 25.0),
+                (500.0, 50.0),
+                (750.0, 80.0),
+                (1000.0, 100.0),
+            ]
+        );
+        let second = virtual_partner(&segment, 1);
+        assert_eq!(second, vec![(0.0, 0.0), (500.0, 55.0), (1000.0, 120.0)]);
+        assert!(virtual_partner(&segment, 2).is_empty());
+
+        assert_eq!(partner_time_at(&leader, 625.0), Some(65.0));
+        assert_eq!(partner_time_at(&leader, 1000.0), Some(100.0));
+        assert_eq!(partner_time_at(&second, 250.0), Some(27.5));
+        assert_eq!(partner_time_at(&second, 1000.5), None);
+        assert_eq!(partner_time_at(&[], 0.0), None);
+        assert_eq!(partner_distance_at(&second, 87.5), Some(750.0));
+        assert_eq!(partner_distance_at(&leader, 0.0), Some(0.0));
+        assert_eq!(partner_distance_at(&leader, -1.0), None);
+
+        // Read back through the array value
+        let values: Vec<_> = segment[3..4]
+            .iter()
+            .flat_map(|mesg| mesg.0.iter())
+            .map(Message::value)
+            .collect();
+        assert!(
+            values.contains(&::types::field::Value::Numbers(vec![0.0, 0.0]))
+        );
+    }
+}
@@ -0,0 +1,558 @@
+//! Flattened views over the `Record` message stream.
+use altitude::AltitudeSeries;
+use error::{
+    Error,
+    Result,
+};
+use geo::{
+    self,
+    GeoPoint,
+    Position,
+};
+use profile::messages::{
+    Message,
+    Record,
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+use validate::{
+    Bounds,
+    Quantity,
+};
+
+/// The commonly used fields of a single `Record` message,
+/// scaled into their natural units.
+///
+/// Fields that were absent from the message, or held the
+/// invalid sentinel, are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordPoint {
+    /// Seconds since the FIT epoch (UTC 00:00 Dec 31 1989).
+    pub timestamp:         u32,
+    /// Latitude in semicircles.
+    pub position_lat:      Option<i32>,
+    /// Longitude in semicircles.
+    pub position_long:     Option<i32>,
+    /// Metres.
+    pub altitude:          Option<f64>,
+    /// Metres.
+    pub enhanced_altitude: Option<f64>,
+    /// Beats per minute.
+    pub heart_rate:        Option<u8>,
+    /// Revolutions per minute.
+    pub cadence:           Option<u8>,
+    /// Metres.
+    pub distance:          Option<f64>,
+    /// Metres per second.
+    pub speed:             Option<f64>,
+    /// Metres per second.
+    pub enhanced_speed:    Option<f64>,
+    /// Watts.
+    pub power:             Option<u16>,
+    /// Degrees celsius.
+    pub temperature:       Option<i8>,
+    /// Seconds, negative when behind the course.
+    pub time_from_course:  Option<f64>,
+    /// Kilocalories burnt since the start.
+    pub calories:          Option<u16>,
+}
+
+impl RecordPoint {
+    /// Flatten a decoded `Record` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Record` message or
+    /// doesn't carry a valid timestamp.
+    pub fn from_message(mesg: &Data) -> Option<RecordPoint> {
+        let mut point = RecordPoint::default();
+        let mut timestamp = None;
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Record(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                Record::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                Record::PositionLat(f) if f.is_valid() => {
+                    point.position_lat = Some(f.raw_value.0)
+                },
+                Record::PositionLong(f) if f.is_valid() => {
+                    point.position_long = Some(f.raw_value.0)
+                },
+                Record::Altitude(f) if f.is_valid() => {
+                    point.altitude = Some(f.value())
+                },
+                Record::EnhancedAltitude(f) if f.is_valid() => {
+                    point.enhanced_altitude = Some(f.value())
+                },
+                Record::HeartRate(f) if f.is_valid() => {
+                    point.heart_rate = Some(f.raw_value.0)
+                },
+                Record::Cadence(f) if f.is_valid() => {
+                    point.cadence = Some(f.raw_value.0)
+                },
+                Record::Distance(f) if f.is_valid() => {
+                    point.distance = Some(f.value())
+                },
+                Record::Speed(f) if f.is_valid() => {
+                    point.speed = Some(f.value())
+                },
+                Record::EnhancedSpeed(f) if f.is_valid() => {
+                    point.enhanced_speed = Some(f.value())
+                },
+                Record::Power(f) if f.is_valid() => {
+                    point.power = Some(f.raw_value.0)
+                },
+                Record::Temperature(f) if f.is_valid() => {
+                    point.temperature = Some(f.raw_value.0)
+                },
+                Record::TimeFromCourse(f) if f.is_valid() => {
+                    point.time_from_course = Some(f.value())
+                },
+                Record::Calories(f) if f.is_valid() => {
+                    point.calories = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        timestamp.map(|timestamp| {
+            RecordPoint {
+                timestamp,
+                ..point
+            }
+        })
+    }
+
+    /// The position in semicircles, if the record has both
+    /// a latitude and a longitude.
+    pub fn position_semicircles(&self) -> Option<Position> {
+        match (self.position_lat, self.position_long) {
+            (Some(lat), Some(long)) => Some(Position::new(lat, long)),
+            _ => None,
+        }
+    }
+
+    /// The position in degrees, if the record has both a
+    /// latitude and a longitude.
+    pub fn position(&self) -> Option<GeoPoint> {
+        self.position_semicircles().map(|position| position.to_degrees())
+    }
+
+    /// A copy with the fields that are implausible under
+    /// `bounds` set to `None`, so they don't skew
+    /// aggregates.
+    pub fn plausible(&self, bounds: &Bounds) -> RecordPoint {
+        let ok = |quantity, value: f64| bounds.is_plausible(quantity, value);
+
+        RecordPoint {
+            altitude: self.altitude.filter(|&v| ok(Quantity::Altitude, v)),
+            enhanced_altitude: self
+                .enhanced_altitude
+                .filter(|&v| ok(Quantity::Altitude, v)),
+            heart_rate: self
+                .heart_rate
+                .filter(|&v| ok(Quantity::HeartRate, f64::from(v))),
+            cadence: self
+                .cadence
+                .filter(|&v| ok(Quantity::Cadence, f64::from(v))),
+            distance: self.distance.filter(|&v| ok(Quantity::Distance, v)),
+            speed: self.speed.filter(|&v| ok(Quantity::Speed, v)),
+            enhanced_speed: self
+                .enhanced_speed
+                .filter(|&v| ok(Quantity::Speed, v)),
+            power: self.power.filter(|&v| ok(Quantity::Power, f64::from(v))),
+            ..self.clone()
+        }
+    }
+}
+
+/// Every `Record` message of an activity, in file order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordTimeSeries {
+    pub points: Vec<RecordPoint>,
+}
+
+impl RecordTimeSeries {
+    /// Collect the `Record` messages in `messages`,
+    /// skipping anything else.
+    pub fn from_messages(messages: &[Data]) -> Self {
+        RecordTimeSeries {
+            points: messages
+                .iter()
+                .filter_map(RecordPoint::from_message)
+                .collect(),
+        }
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Iterate over the records in file order.
+    pub fn iter(&self) -> impl Iterator<Item = &RecordPoint> {
+        self.points.iter()
+    }
+
+    /// Length of the GPS track in metres, summing the
+    /// haversine distance between consecutive positions.
+    ///
+    /// Unlike the `distance` field, which comes from the
+    /// device's own odometer (often a wheel or foot pod),
+    /// this only depends on the recorded positions.
+    pub fn total_distance_haversine_m(&self) -> f64 {
+        let track: Vec<GeoPoint> = self.into();
+        track.windows(2).map(|pair| pair[0].haversine_m(&pair[1])).sum()
+    }
+
+    /// The GPS track as a GeoJSON `LineString` geometry.
+    pub fn to_geojson_line_string(&self) -> String {
+        let track: Vec<GeoPoint> = self.into();
+        geo::line_string(&track)
+    }
+
+    /// The GPS track as a GeoJSON `LineString` geometry,
+    /// with altitudes from `altitude` at each position.
+    pub fn to_geojson_line_string_3d(
+        &self,
+        altitude: &AltitudeSeries,
+    ) -> String {
+        let (track, altitudes): (Vec<GeoPoint>, Vec<f64>) = self
+            .iter()
+            .filter_map(|point| {
+                Some((point.position()?, altitude.at(point.timestamp)))
+            })
+            .unzip();
+        geo::line_string_with_altitude(&track, &altitudes)
+    }
+
+    /// Thin the records out to at most one every
+    /// `target_interval_s` seconds.
+    ///
+    /// A record is kept once its timestamp is at least
+    /// `target_interval_s` past the last one kept. The first
+    /// and last records are always kept, so the final gap may
+    /// be shorter.
+    pub fn subsample(&self, target_interval_s: u64) -> RecordTimeSeries {
+        let mut kept: Vec<usize> = Vec::new();
+        let mut due = 0;
+        for (i, point) in self.points.iter().enumerate() {
+            let timestamp = u64::from(point.timestamp);
+            if kept.is_empty() || timestamp >= due {
+                kept.push(i);
+                due = timestamp + target_interval_s;
+            }
+        }
+        if let Some(last) = self.points.len().checked_sub(1) {
+            if kept.last() != Some(&last) {
+                kept.push(last);
+            }
+        }
+        self.pick(&kept)
+    }
+
+    /// Exactly `n` records spread evenly by position, the
+    /// first and last among them, or every record if there
+    /// are no more than `n`.
+    pub fn downsample_to_count(&self, n: usize) -> RecordTimeSeries {
+        let len = self.points.len();
+        if n >= len {
+            return self.clone();
+        }
+        let kept: Vec<usize> = match n {
+            0 => Vec::new(),
+            1 => vec![0],
+            _ => {
+                (0..n)
+                    .map(|i| (i * (len - 1) + (n - 1) / 2) / (n - 1))
+                    .collect()
+            },
+        };
+        self.pick(&kept)
+    }
+
+    fn pick(&self, indices: &[usize]) -> RecordTimeSeries {
+        RecordTimeSeries {
+            points: indices.iter().map(|&i| self.points[i].clone()).collect(),
+        }
+    }
+}
+
+/// Correct the systematic error of GPS altitude, given the
+/// true altitude of a point the activity passes through.
+///
+/// The record nearest to the reference point, among those
+/// with a position and an altitude, has its altitude moved
+/// to `reference_alt_m`. The same offset is then added to
+/// the altitude and enhanced altitude of every record.
+/// Returns the offset in metres, or an error if no record
+/// is within `search_radius_m` of the reference point.
+pub fn apply_altitude_offset(
+    series: &mut RecordTimeSeries,
+    reference_lat: f64,
+    reference_lon: f64,
+    reference_alt_m: f64,
+    search_radius_m: f64,
+) -> Result<f64> {
+    let reference = GeoPoint::new(reference_lat, reference_lon);
+    let nearest = series
+        .iter()
+        .filter_map(|point| {
+            let altitude = point.enhanced_altitude.or(point.altitude)?;
+            let distance = point.position()?.haversine_m(&reference);
+            Some((distance, altitude))
+        })
+        .filter(|&(distance, _)| distance <= search_radius_m)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+
+    let offset = match nearest {
+        Some((_, altitude)) => reference_alt_m - altitude,
+        None => return Err(Error::no_record_nearby(search_radius_m)),
+    };
+
+    for point in series.points.iter_mut() {
+        if let Some(ref mut altitude) = point.altitude {
+            *altitude += offset;
+        }
+        if let Some(ref mut altitude) = point.enhanced_altitude {
+            *altitude += offset;
+        }
+    }
+    Ok(offset)
+}
+
+/// Linearly interpolate `samples`, `(x, y)` pairs sorted by
+/// `x`, at each of the ascending positions in `at`.
+///
+/// Positions outside the samples, or between two samples
+/// more than `max_gap` apart, are NaN rather than
+/// interpolated.
+pub fn resample(samples: &[(f64, f64)], at: &[f64], max_gap: f64) -> Vec<f64> {
+    let mut next = 0;
+    at.iter()
+        .map(|&x| {
+            while next < samples.len() && samples[next].0 < x {
+                next += 1;
+            }
+            let (x1, y1) = match samples.get(next) {
+                Some(&sample) => sample,
+                None => return f64::NAN,
+            };
+            if x1 == x {
+                return y1;
+            }
+            let (x0, y0) = match next.checked_sub(1) {
+                Some(prev) => samples[prev],
+                None => return f64::NAN,
+            };
+            if x1 - x0 > max_gap {
+                return f64::NAN;
+            }
+            y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+        })
+        .collect()
+}
+
+impl<'a> From<&'a RecordTimeSeries> for Vec<GeoPoint> {
+    /// The positions of the records that have one.
+    fn from(series: &'a RecordTimeSeries) -> Self {
+        series.iter().filter_map(RecordPoint::position).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use testutil::mesg;
+
+    #[test]
+    fn record_point_scales_fields() {
+        let record = mesg(20)
+            .u32(253, 1000)
+            .u16(2, 2600) // (2600 / 5) - 500 = 20 m
+            .u8(3, 150)
+            .u32(5, 12345) // 123.45 m
+            .u16(6, 3520) // 3.52 m/s
+            .u8(4, 0xFF) // invalid cadence
+            .build();
+
+        let point = RecordPoint::from_message(&record).unwrap();
+        assert_eq!(point.timestamp, 1000);
+        assert_eq!(point.altitude, Some(20.0));
+        assert_eq!(point.heart_rate, Some(150));
+        assert_eq!(point.distance, Some(123.45));
+        assert_eq!(point.speed, Some(3.52));
+        assert_eq!(point.cadence, None);
+    }
+
+    #[test]
+    fn plausible_drops_absurd_fields() {
+        let record = mesg(20)
+            .u32(253, 1000)
+            .u16(6, 65_000) // 65 m/s
+            .u8(3, 150)
+            .build();
+        let point = RecordPoint::from_message(&record).unwrap();
+        assert_eq!(point.speed, Some(65.0));
+
+        let plausible = point.plausible(&Bounds::default());
+        assert_eq!(plausible.speed, None);
+        assert_eq!(plausible.heart_rate, Some(150));
+    }
+
+    #[test]
+    fn record_point_requires_timestamp() {
+        let record = mesg(20).u8(3, 150).build();
+        assert!(RecordPoint::from_message(&record).is_none());
+
+        let lap = mesg(19).u32(253, 1000).build();
+        assert!(RecordPoint::from_message(&lap).is_none());
+    }
+
+    #[test]
+    fn haversine_matches_odometer_on_straight_track() {
+        // Due north along the prime meridian, ~11.1 m per step,
+        // with a missing fix every tenth record.
+        let step = 0.0001f64;
+        let metres_per_step = geo::EARTH_RADIUS_M * step.to_radians();
+
+        let messages: Vec<Data> = (0..500u32)
+            .map(|i| {
+                let lat = 51.0 + f64::from(i) * step;
+                let record = mesg(20)
+                    .u32(253, 1000 + i)
+                    .u32(5, (f64::from(i) * metres_per_step * 100.0) as u32);
+                if i % 10 == 5 {
+                    record.build()
+                }
+                else {
+                    record
+                        .i32(0, geo::degrees_to_semicircles(lat))
+                        .i32(1, 0)
+                        .build()
+                }
+            })
+            .collect();
+
+        let series = RecordTimeSeries::from_messages(&messages);
+        let track: Vec<GeoPoint> = (&series).into();
+        assert_eq!(series.len(), 500);
+        assert_eq!(track.len(), 450);
+
+        let odometer = series.points.last().unwrap().distance.unwrap();
+        let haversine = series.total_distance_haversine_m();
+        assert!(
+            (haversine - odometer).abs() / odometer < 0.01,
+            "haversine {} vs odometer {}",
+            haversine,
+            odometer
+        );
+
+        let json = series.to_geojson_line_string();
+        assert!(json.starts_with(r#"{"type":"LineString","coordinates":[[0,"#));
+        assert_eq!(json.matches("],[").count(), 449);
+    }
+
+    #[test]
+    fn subsample_keeps_ends() {
+        // An hour at 1 Hz, with a dropout from 600 s to 630 s
+        let series = RecordTimeSeries {
+            points: (0..=3600u32)
+                .filter(|t| !(600..630).contains(t))
+                .map(|t| {
+                    RecordPoint {
+                        timestamp: 1000 + t,
+                        ..RecordPoint::default()
+                    }
+                })
+                .collect(),
+        };
+
+        let thinned = series.subsample(10);
+        assert_eq!(thinned.len(), 3600 / 10 + 1 - 3);
+        assert_eq!(thinned.points[0], series.points[0]);
+        assert_eq!(thinned.points.last(), series.points.last());
+        let gaps = thinned
+            .points
+            .windows(2)
+            .map(|pair| pair[1].timestamp - pair[0].timestamp);
+        assert!(gaps.clone().all(|gap| gap >= 10));
+        assert_eq!(gaps.max(), Some(40));
+
+        // The last record is kept even when it's too close
+        assert_eq!(series.subsample(7).points.last(), series.points.last());
+        assert_eq!(series.subsample(0), series);
+        assert!(RecordTimeSeries::default().subsample(10).is_empty());
+
+        let hundred = series.downsample_to_count(100);
+        assert_eq!(hundred.len(), 100);
+        assert_eq!(hundred.points[0], series.points[0]);
+        assert_eq!(hundred.points.last(), series.points.last());
+        assert_eq!(series.downsample_to_count(1).points, &series.points[..1]);
+        assert!(series.downsample_to_count(0).is_empty());
+        assert_eq!(series.downsample_to_count(10_000), series);
+    }
+
+    #[test]
+    fn altitude_offset_from_reference_point() {
+        // A climb north from 51.0, reading 12 m too low
+        let mut series = RecordTimeSeries {
+            points: (0..10)
+                .map(|i| {
+                    RecordPoint {
+                        timestamp: 1000 + i,
+                        position_lat: Some(geo::degrees_to_semicircles(
+                            51.0 + f64::from(i) * 0.001,
+                        )),
+                        position_long: Some(0),
+                        altitude: Some(100.0 + f64::from(i)),
+                        enhanced_altitude: Some(100.0 + f64::from(i)),
+                        ..RecordPoint::default()
+                    }
+                })
+                .collect(),
+        };
+        series.points[9].enhanced_altitude = None;
+
+        // A summit marker near the fifth record
+        let offset =
+            apply_altitude_offset(&mut series, 51.00402, 0.0, 116.0, 50.0)
+                .unwrap();
+        assert_eq!(offset, 12.0);
+        for (i, point) in series.iter().enumerate() {
+            assert_eq!(point.altitude, Some(112.0 + i as f64));
+        }
+        assert_eq!(series.points[0].enhanced_altitude, Some(112.0));
+        assert_eq!(series.points[9].enhanced_altitude, None);
+
+        let err = apply_altitude_offset(&mut series, 52.0, 0.0, 0.0, 50.0)
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::NoRecordNearby { .. }));
+        assert_eq!(series.points[0].altitude, Some(112.0));
+    }
+
+    #[test]
+    fn resample_leaves_gaps() {
+        let samples = [(0.0, 0.0), (2.0, 20.0), (3.0, 30.0), (10.0, 100.0)];
+        let at = [-1.0, 0.0, 1.0, 2.5, 3.0, 5.0, 10.0, 11.0];
+        let resampled = resample(&samples, &at, 5.0);
+
+        assert!(resampled[0].is_nan());
+        assert_eq!(&resampled[1..5], &[0.0, 10.0, 25.0, 30.0]);
+        assert!(resampled[5].is_nan());
+        assert_eq!(resampled[6], 100.0);
+        assert!(resampled[7].is_nan());
+    }
+}
@@ -0,0 +1,467 @@
+//! Session summaries that insist on the fields any summary
+//! needs.
+//!
+//! [`Lap`] and the other flattened messages keep whatever a
+//! device wrote and leave the rest `None`. A
+//! [`SessionSummary`] instead fails to build without a
+//! start time, sport, elapsed time and distance, so code
+//! built on it doesn't have to check for them again.
+//!
+//! [`Lap`]: ../laps/struct.Lap.html
+//! [`SessionSummary`]: struct.SessionSummary.html
+use duration::FitDuration;
+use error::{
+    Error,
+    Result,
+};
+use geo::{
+    self,
+    Bounds,
+    Position,
+};
+use profile::{
+    messages::{
+        Message,
+        Session,
+    },
+    types::{
+        Sport,
+        SubSport,
+    },
+};
+use series::RecordPoint;
+use sport::{
+    self,
+    ActivityKind,
+    Canonical,
+    Encoding,
+};
+use std::convert::TryFrom;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// The fields of a `Session` message, scaled into their
+/// natural units.
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    /// Seconds since the FIT epoch.
+    pub start_time:       u32,
+    pub sport:            Sport,
+    pub sub_sport:        Option<SubSport>,
+    /// What the sport and sub sport stand for, by the
+    /// default [`Canonical`] table.
+    /// [`classify`](#method.classify) takes the device
+    /// into account too.
+    ///
+    /// [`Canonical`]: ../sport/struct.Canonical.html
+    pub kind:             ActivityKind,
+    /// Seconds.
+    pub total_elapsed_s:  f64,
+    /// Metres.
+    pub total_distance_m: f64,
+    /// Seconds.
+    pub total_timer_s:    Option<f64>,
+    /// Metres per second, from the enhanced field if there
+    /// is one.
+    pub avg_speed_ms:     Option<f64>,
+    /// Metres per second, from the enhanced field if there
+    /// is one.
+    pub max_speed_ms:     Option<f64>,
+    /// Beats per minute.
+    pub avg_heart_rate:   Option<u8>,
+    /// Beats per minute.
+    pub max_heart_rate:   Option<u8>,
+    /// Revolutions per minute.
+    pub avg_cadence:      Option<u8>,
+    /// Watts.
+    pub avg_power:        Option<u16>,
+    /// Watts.
+    pub max_power:        Option<u16>,
+    /// Kilocalories.
+    pub total_calories:   Option<u16>,
+    /// Metres.
+    pub total_ascent_m:   Option<u16>,
+    /// Metres.
+    pub total_descent_m:  Option<u16>,
+    pub num_laps:         Option<u16>,
+    /// From the `nec_*` and `swc_*` corners, if all four
+    /// are valid.
+    pub bounds:           Option<Bounds>,
+}
+
+impl SessionSummary {
+    /// Summarise a decoded `Session` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Session` message
+    /// or lacks a field every summary needs; `try_from`
+    /// its fields says which.
+    pub fn from_message(mesg: &Data) -> Option<SessionSummary> {
+        let fields = mesg
+            .0
+            .iter()
+            .map(|field| {
+                match field {
+                    Message::Session(field) => Some(field.clone()),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<Session>>>()?;
+        SessionSummary::try_from(fields).ok()
+    }
+
+    /// [`total_elapsed_s`](#structfield.total_elapsed_s) as
+    /// a duration.
+    pub fn elapsed(&self) -> FitDuration {
+        FitDuration::from_secs_f64(self.total_elapsed_s).unwrap_or_default()
+    }
+
+    /// [`total_timer_s`](#structfield.total_timer_s) as a
+    /// duration.
+    pub fn timer(&self) -> Option<FitDuration> {
+        self.total_timer_s.and_then(FitDuration::from_secs_f64)
+    }
+
+    /// Set [`kind`](#structfield.kind) by `canonical`, for
+    /// the device of the first `FileId` among `messages`,
+    /// and return it.
+    pub fn classify(
+        &mut self,
+        canonical: &Canonical,
+        messages: &[Data],
+    ) -> ActivityKind {
+        let (manufacturer, product) = messages
+            .iter()
+            .find(|mesg| mesg.mesg_num() == Some(0))
+            .map(sport::device)
+            .unwrap_or_default();
+        self.kind = canonical.kind(&Encoding {
+            manufacturer,
+            product,
+            sport: self.sport.clone(),
+            sub_sport: self.sub_sport.clone().unwrap_or(SubSport::Generic),
+        });
+        self.kind
+    }
+
+    /// Set [`bounds`](#structfield.bounds) from the records
+    /// with positions during the session, if the message
+    /// had none or they leave out some of the track.
+    ///
+    /// Returns whether the bounds were changed.
+    pub fn fill_bounds(&mut self, records: &[RecordPoint]) -> bool {
+        let end = f64::from(self.start_time) + self.total_elapsed_s;
+        let during: Vec<RecordPoint> = records
+            .iter()
+            .filter(|point| {
+                point.timestamp >= self.start_time
+                    && f64::from(point.timestamp) <= end
+            })
+            .cloned()
+            .collect();
+        let track = match geo::bounds(&during) {
+            Some(track) => track,
+            None => return false,
+        };
+        match self.bounds {
+            Some(bounds) if bounds.covers(&track) => false,
+            _ => {
+                self.bounds = Some(track);
+                true
+            },
+        }
+    }
+}
+
+/// The box given by the `nec_*` and `swc_*` corners among
+/// the fields of a `Session` message, if all four are
+/// valid.
+pub(crate) fn session_bounds<'a, I>(fields: I) -> Option<Bounds>
+where
+    I: IntoIterator<Item = &'a Session>,
+{
+    let mut corners = [None; 4];
+    for field in fields {
+        match field {
+            Session::NecLat(f) if f.is_valid() => {
+                corners[0] = Some(f.raw_value.0)
+            },
+            Session::NecLong(f) if f.is_valid() => {
+                corners[1] = Some(f.raw_value.0)
+            },
+            Session::SwcLat(f) if f.is_valid() => {
+                corners[2] = Some(f.raw_value.0)
+            },
+            Session::SwcLong(f) if f.is_valid() => {
+                corners[3] = Some(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+    Some(Bounds {
+        north_east: Position::new(corners[0]?, corners[1]?),
+        south_west: Position::new(corners[2]?, corners[3]?),
+    })
+}
+
+/// Build a summary from the fields of one `Session`
+/// message.
+///
+/// Fails with `MissingField`, naming the first one missing,
+/// unless the fields include a valid `start_time`, `sport`,
+/// `total_elapsed_time` and `total_distance`.
+impl TryFrom<Vec<Session>> for SessionSummary {
+    type Error = Error;
+
+    fn try_from(fields: Vec<Session>) -> Result<SessionSummary> {
+        let mut start_time = None;
+        let mut sport = None;
+        let mut total_elapsed_s = None;
+        let mut total_distance_m = None;
+        let (mut avg_speed, mut enhanced_avg_speed) = (None, None);
+        let (mut max_speed, mut enhanced_max_speed) = (None, None);
+        let mut summary = SessionSummary {
+            start_time:       0,
+            sport:            Sport::Unknown,
+            sub_sport:        None,
+            kind:             ActivityKind::Other,
+            total_elapsed_s:  0.0,
+            total_distance_m: 0.0,
+            total_timer_s:    None,
+            avg_speed_ms:     None,
+            max_speed_ms:     None,
+            avg_heart_rate:   None,
+            max_heart_rate:   None,
+            avg_cadence:      None,
+            avg_power:        None,
+            max_power:        None,
+            total_calories:   None,
+            total_ascent_m:   None,
+            total_descent_m:  None,
+            num_laps:         None,
+            bounds:           session_bounds(&fields),
+        };
+
+        for field in fields {
+            match field {
+                Session::StartTime(f) if f.raw_value.0 != u32::MAX => {
+                    start_time = Some(f.raw_value.0)
+                },
+                Session::Sport(f) => sport = Some(f.raw_value),
+                Session::SubSport(f) => summary.sub_sport = Some(f.raw_value),
+                Session::TotalElapsedTime(f) if f.is_valid() => {
+                    total_elapsed_s = Some(f.value())
+                },
+                Session::TotalDistance(f) if f.is_valid() => {
+                    total_distance_m = Some(f.value())
+                },
+                Session::TotalTimerTime(f) if f.is_valid() => {
+                    summary.total_timer_s = Some(f.value())
+                },
+                Session::AvgSpeed(f) if f.is_valid() => {
+                    avg_speed = Some(f.value())
+                },
+                Session::EnhancedAvgSpeed(f) if f.is_valid() => {
+                    enhanced_avg_speed = Some(f.value())
+                },
+                Session::MaxSpeed(f) if f.is_valid() => {
+                    max_speed = Some(f.value())
+                },
+                Session::EnhancedMaxSpeed(f) if f.is_valid() => {
+                    enhanced_max_speed = Some(f.value())
+                },
+                Session::AvgHeartRate(f) if f.is_valid() => {
+                    summary.avg_heart_rate = Some(f.raw_value.0)
+                },
+                Session::MaxHeartRate(f) if f.is_valid() => {
+                    summary.max_heart_rate = Some(f.raw_value.0)
+                },
+                Session::AvgCadence(f) if f.is_valid() => {
+                    summary.avg_cadence = Some(f.raw_value.0)
+                },
+                Session::AvgPower(f) if f.is_valid() => {
+                    summary.avg_power = Some(f.raw_value.0)
+                },
+                Session::MaxPower(f) if f.is_valid() => {
+                    summary.max_power = Some(f.raw_value.0)
+                },
+                Session::TotalCalories(f) if f.is_valid() => {
+                    summary.total_calories = Some(f.raw_value.0)
+                },
+                Session::TotalAscent(f) if f.is_valid() => {
+                    summary.total_ascent_m = Some(f.raw_value.0)
+                },
+                Session::TotalDescent(f) if f.is_valid() => {
+                    summary.total_descent_m = Some(f.raw_value.0)
+                },
+                Session::NumLaps(f) if f.is_valid() => {
+                    summary.num_laps = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        let start_time =
+            start_time.ok_or_else(|| Error::missing_field("start_time"))?;
+        let sport = sport.ok_or_else(|| Error::missing_field("sport"))?;
+        let kind = Canonical::default().kind(&Encoding {
+            manufacturer: None,
+            product:      None,
+            sport:        sport.clone(),
+            sub_sport:    summary
+                .sub_sport
+                .clone()
+                .unwrap_or(SubSport::Generic),
+        });
+        Ok(SessionSummary {
+            start_time,
+            sport,
+            kind,
+            total_elapsed_s: total_elapsed_s
+                .ok_or_else(|| Error::missing_field("total_elapsed_time"))?,
+            total_distance_m: total_distance_m
+                .ok_or_else(|| Error::missing_field("total_distance"))?,
+            avg_speed_ms: enhanced_avg_speed.or(avg_speed),
+            max_speed_ms: enhanced_max_speed.or(max_speed),
+            ..summary
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use testutil::mesg;
+
+    /// The `Session` fields of `mesg`.
+    fn fields(mesg: Data) -> Vec<Session> {
+        mesg.0
+            .into_iter()
+            .filter_map(|field| {
+                match field {
+                    Message::Session(field) => Some(field),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn requires_start_time() {
+        let complete = mesg(18)
+            .u32(2, 1_000_000_000)
+            .u8(5, 2) // cycling
+            .u32(7, 3_600_000) // 3600 s
+            .u32(9, 3_000_000) // 30 km
+            .u16(14, 8333) // 8.333 m/s
+            .u32(124, 8340) // enhanced, 8.34 m/s
+            .u8(16, 140)
+            .u16(26, 0xFFFF) // invalid lap count
+            .build();
+        let mut summary = SessionSummary::from_message(&complete).unwrap();
+        assert_eq!(summary.total_distance_m, 30_000.0);
+        assert_eq!(summary.kind, ActivityKind::Ride);
+        // Recorded by Zwift
+        let file_id = mesg(0).u8(0, 4).u16(1, 260).build();
+        let canonical = Canonical::default();
+        assert_eq!(
+            summary.classify(&canonical, &[file_id]),
+            ActivityKind::VirtualRide
+        );
+        assert_eq!(summary.kind, ActivityKind::VirtualRide);
+        assert!(summary.sub_sport.is_none());
+        let summary = SessionSummary::try_from(fields(complete)).unwrap();
+        assert_eq!(summary.start_time, 1_000_000_000);
+        assert!(matches!(summary.sport, Sport::Cycling));
+        assert_eq!(summary.total_elapsed_s, 3600.0);
+        assert_eq!(summary.elapsed().to_string(), "1:00:00.000");
+        assert_eq!(summary.total_distance_m, 30_000.0);
+        assert_eq!(summary.avg_speed_ms, Some(8.34));
+        assert_eq!(summary.avg_heart_rate, Some(140));
+        assert_eq!(summary.num_laps, None);
+
+        let no_start =
+            mesg(18).u8(5, 2).u32(7, 3_600_000).u32(9, 3_000_000).build();
+        assert!(SessionSummary::from_message(&no_start).is_none());
+        let err = SessionSummary::try_from(fields(no_start)).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ErrorKind::MissingField {
+                field: "start_time"
+            }
+        ));
+        assert_eq!(err.to_string(), "missing required field: start_time");
+
+        // Invalid counts as missing
+        let invalid_distance = mesg(18)
+            .u32(2, 1_000_000_000)
+            .u8(5, 2)
+            .u32(7, 3_600_000)
+            .u32(9, u32::MAX)
+            .build();
+        let err = SessionSummary::try_from(fields(invalid_distance));
+        assert!(matches!(
+            err.unwrap_err().kind(),
+            ErrorKind::MissingField {
+                field: "total_distance"
+            }
+        ));
+    }
+
+    #[test]
+    fn fills_missing_or_wrong_bounds() {
+        let at = |lat: f64, lon: f64, timestamp: u32| {
+            let position = Position::from_degrees(lat, lon);
+            RecordPoint {
+                timestamp,
+                position_lat: Some(position.lat_semicircles),
+                position_long: Some(position.lon_semicircles),
+                ..RecordPoint::default()
+            }
+        };
+        let records = vec![
+            at(45.0, 7.0, 1000),
+            at(45.1, 7.2, 1500),
+            // After the session ends
+            at(46.0, 8.0, 5000),
+        ];
+        let session = |corners: &[(u8, f64)]| {
+            let mut mesg = mesg(18)
+                .u32(2, 1000)
+                .u8(5, 1)
+                .u32(7, 1_000_000) // 1000 s
+                .u32(9, 500_000);
+            for &(num, degrees) in corners {
+                mesg = mesg.i32(num, geo::degrees_to_semicircles(degrees));
+            }
+            SessionSummary::try_from(fields(mesg.build())).unwrap()
+        };
+
+        let mut missing = session(&[]);
+        assert_eq!(missing.bounds, None);
+        assert!(missing.fill_bounds(&records));
+        let track = missing.bounds.unwrap();
+        assert_eq!(track.north_east, Position::from_degrees(45.1, 7.2));
+        assert_eq!(track.south_west, Position::from_degrees(45.0, 7.0));
+
+        // Corners covering the track are kept as they are
+        let mut roomy =
+            session(&[(29, 45.2), (30, 7.3), (31, 44.9), (32, 6.9)]);
+        assert!(!roomy.fill_bounds(&records));
+        assert_eq!(
+            roomy.bounds.unwrap().north_east,
+            Position::from_degrees(45.2, 7.3)
+        );
+
+        // Corners leaving out part of it are replaced
+        let mut wrong =
+            session(&[(29, 45.05), (30, 7.3), (31, 44.9), (32, 6.9)]);
+        assert!(wrong.bounds.is_some());
+        assert!(wrong.fill_bounds(&records));
+        assert_eq!(wrong.bounds, Some(track));
+
+        assert!(!session(&[]).fill_bounds(&[]));
+    }
+}
@@ -0,0 +1,718 @@
+//! Zero-copy decoding of a FIT file held in memory.
+//!
+//! [`FitReader`] decodes every field into the profile's
+//! owning types, which costs a few heap allocations per
+//! message. [`FitSliceReader`] instead hands out views into
+//! the original buffer: definitions and field values are
+//! slices of it, and nothing is allocated unless decoding
+//! fails. The price is that fields come back as raw bytes
+//! to interpret through [`BorrowedField`], rather than as
+//! profile enums.
+//!
+//! [`FitReader`]: ../reader/struct.FitReader.html
+//! [`FitSliceReader`]: struct.FitSliceReader.html
+//! [`BorrowedField`]: struct.BorrowedField.html
+use bits::Bits;
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+    LittleEndian,
+};
+use consts::CRC_SIZE;
+use dyncrc16::CRC16;
+use error::{
+    Error,
+    Result,
+};
+use profile::{
+    self,
+    base::is_invalid_raw,
+};
+use std::{
+    io,
+    str,
+};
+use types::{
+    file::Header,
+    record::{
+        Data,
+        DEVELOPER_FIELD_NUM,
+    },
+};
+
+/// Number of local message types a file can have defined at
+/// once.
+const LOCAL_MESGS: usize = 16;
+
+
+/// The bits of a base type that number it, leaving out the
+/// endian ability flag.
+const BASE_TYPE_NUM_MASK: u8 = 0x1F;
+
+/// Decodes records one at a time from a byte slice without
+/// copying or allocating.
+///
+/// Like [`FitReader`](../reader/struct.FitReader.html), the
+/// iterator checks the file CRC once every record has been
+/// read, and yields an error and then stops if a record
+/// fails to decode or the checksum doesn't match. It also
+/// takes a header data size of zero to mean the file runs
+/// to the end of the slice, less its CRC.
+pub struct FitSliceReader<'a> {
+    header:      Header,
+    data:        &'a [u8],
+    pos:         usize,
+    end:         usize,
+    definitions: [Option<BorrowedDefinition<'a>>; LOCAL_MESGS],
+    count:       u64,
+    done:        bool,
+}
+
+impl<'a> FitSliceReader<'a> {
+    /// Read the file header from the start of `data` and
+    /// prepare to decode records.
+    pub fn new(data: &'a [u8]) -> Result<Self> {
+        let mut r = data;
+        let header =
+            Header::decode(&mut r).map_err(Error::decoding("file header"))?;
+        let pos = usize::from(header.size());
+        // A zero data size leaves the end to the input, as
+        // some writers do
+        let end = match header.data_size() {
+            0 => data.len().saturating_sub(usize::from(CRC_SIZE)).max(pos),
+            size => pos.saturating_add(size as usize),
+        };
+
+        Ok(FitSliceReader {
+            header,
+            data,
+            pos,
+            end,
+            definitions: [None; LOCAL_MESGS],
+            count: 1,
+            done: false,
+        })
+    }
+
+    /// The file header.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Number of bytes consumed so far, including the
+    /// header.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// The next `n` bytes of the data section.
+    fn take(&mut self, n: usize, what: &'static str) -> Result<&'a [u8]> {
+        let end = self.pos.saturating_add(n);
+        let bytes = match self.data.get(self.pos..end) {
+            Some(bytes) if end <= self.end => bytes,
+            _ => {
+                let eof = io::Error::from(io::ErrorKind::UnexpectedEof);
+                return Err(Error::reading(what)(eof))
+            },
+        };
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn decode_record(&mut self) -> Result<BorrowedMessage<'a>> {
+        let byte = self.take(1, "byte")?[0];
+
+        // Normal headers have bit 7 clear, and bit 6 set for
+        // definitions; compressed timestamp headers carry the
+        // local message number in bits 5-6
+        if byte.bit_not_set(7) {
+            let local_mesg_num = byte.bit_range(0, 3);
+            if byte.bit_is_set(6) {
+                let definition = self
+                    .decode_definition(local_mesg_num, byte.bit_is_set(5))
+                    .map_err(Error::decoding("definition message"))?;
+                self.definitions[usize::from(local_mesg_num)] =
+                    Some(definition);
+                Ok(BorrowedMessage::Definition(definition))
+            }
+            else {
+                self.decode_data(local_mesg_num, None)
+            }
+        }
+        else {
+            self.decode_data(byte.bit_range(5, 6), Some(byte.bit_range(0, 4)))
+        }
+    }
+
+    fn decode_definition(
+        &mut self,
+        local_mesg_num: u8,
+        has_dev_fields: bool,
+    ) -> Result<BorrowedDefinition<'a>> {
+        // Reserved byte, architecture, global message number
+        // and number of fields
+        let fixed = self.take(5, "definition")?;
+        let big_endian = match fixed[1] {
+            0 => false,
+            1 => true,
+            arch => return Err(Error::unknown_architecture(arch)),
+        };
+        let global_mesg_num = if big_endian {
+            BigEndian::read_u16(&fixed[2..4])
+        }
+        else {
+            LittleEndian::read_u16(&fixed[2..4])
+        };
+
+        let fields =
+            self.take(3 * usize::from(fixed[4]), "field definitions")?;
+        let developer_fields = if has_dev_fields {
+            let n = self.take(1, "number of fields")?[0];
+            self.take(3 * usize::from(n), "developer field definitions")?
+        }
+        else {
+            &[]
+        };
+
+        let size = fields
+            .chunks(3)
+            .chain(developer_fields.chunks(3))
+            .map(|def| usize::from(def[1]))
+            .sum();
+
+        Ok(BorrowedDefinition {
+            local_mesg_num,
+            big_endian,
+            global_mesg_num,
+            fields,
+            developer_fields,
+            size,
+        })
+    }
+
+    fn decode_data(
+        &mut self,
+        local_mesg_num: u8,
+        time_offset: Option<u8>,
+    ) -> Result<BorrowedMessage<'a>> {
+        let definition = self.definitions[usize::from(local_mesg_num)]
+            .ok_or_else(|| Error::missing_definition(local_mesg_num))?;
+        let bytes = self
+            .take(definition.size, "buffer")
+            .map_err(Error::decoding("data message"))?;
+
+        Ok(BorrowedMessage::Data(BorrowedData {
+            definition,
+            time_offset,
+            bytes,
+        }))
+    }
+
+    fn check_crc(&self) -> Result<()> {
+        let crc_end = self.end.saturating_add(usize::from(CRC_SIZE));
+        let expected = match self.data.get(self.end..crc_end) {
+            Some(bytes) => LittleEndian::read_u16(bytes),
+            None => {
+                let eof = io::Error::from(io::ErrorKind::UnexpectedEof);
+                return Err(Error::reading("crc")(eof))
+            },
+        };
+
+        let mut crc = CRC16::new();
+        crc.update(&self.data[..self.end]);
+        let computed = crc.sum_16();
+        if expected != computed {
+            return Err(Error::crc_mismatch(expected, computed))
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for FitSliceReader<'a> {
+    type Item = Result<BorrowedMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+
+        if self.pos >= self.end {
+            self.done = true;
+            return self.check_crc().err().map(Err)
+        }
+
+        // Only format the context on failure, so the happy
+        // path stays allocation free
+        let count = self.count;
+        let record = self
+            .decode_record()
+            .map_err(|err| Error::decoding(format!("record #{}", count))(err));
+
+        match record {
+            Ok(record) => {
+                self.count += 1;
+                Some(Ok(record))
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// A record borrowed from the buffer being decoded.
+#[derive(Debug, Clone, Copy)]
+pub enum BorrowedMessage<'a> {
+    Definition(BorrowedDefinition<'a>),
+    Data(BorrowedData<'a>),
+}
+
+/// One field of a definition message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDef {
+    pub num:       u8,
+    /// Bytes.
+    pub size:      u8,
+    pub base_type: u8,
+}
+
+/// One developer field of a definition message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeveloperFieldDef {
+    /// The `field_definition_number` of the matching
+    /// `FieldDescription` message.
+    pub num:                  u8,
+    /// Bytes.
+    pub size:                 u8,
+    pub developer_data_index: u8,
+}
+
+/// A definition message, with its field definitions left
+/// in the buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedDefinition<'a> {
+    local_mesg_num:   u8,
+    big_endian:       bool,
+    global_mesg_num:  u16,
+    fields:           &'a [u8],
+    developer_fields: &'a [u8],
+    size:             usize,
+}
+
+impl<'a> BorrowedDefinition<'a> {
+    pub fn local_mesg_num(&self) -> u8 {
+        self.local_mesg_num
+    }
+
+    /// The global message number of the data messages this
+    /// defines.
+    pub fn global_mesg_num(&self) -> u16 {
+        self.global_mesg_num
+    }
+
+    /// Whether the data messages this defines are big
+    /// endian.
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    /// The regular fields, in record order.
+    pub fn fields(&self) -> impl Iterator<Item = FieldDef> + 'a {
+        self.fields.chunks(3).map(|def| {
+            FieldDef {
+                num: def[0], size: def[1], base_type: def[2]
+            }
+        })
+    }
+
+    /// The developer fields, which follow the regular ones
+    /// in each data message.
+    pub fn developer_fields(
+        &self,
+    ) -> impl Iterator<Item = DeveloperFieldDef> + 'a {
+        self.developer_fields.chunks(3).map(|def| {
+            DeveloperFieldDef {
+                num:                  def[0],
+                size:                 def[1],
+                developer_data_index: def[2],
+            }
+        })
+    }
+
+    /// Size in bytes of each data message this defines.
+    pub fn data_size(&self) -> usize {
+        self.size
+    }
+}
+
+/// A data message, with its field values left in the
+/// buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedData<'a> {
+    definition:  BorrowedDefinition<'a>,
+    time_offset: Option<u8>,
+    bytes:       &'a [u8],
+}
+
+impl<'a> BorrowedData<'a> {
+    /// The definition this message was decoded with.
+    pub fn definition(&self) -> &BorrowedDefinition<'a> {
+        &self.definition
+    }
+
+    pub fn global_mesg_num(&self) -> u16 {
+        self.definition.global_mesg_num
+    }
+
+    /// The 5 bit offset from the last full timestamp, if
+    /// the message had a compressed timestamp header.
+    pub fn time_offset(&self) -> Option<u8> {
+        self.time_offset
+    }
+
+    /// Every field value, developer fields included.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The regular fields, in record order.
+    pub fn fields(&self) -> impl Iterator<Item = BorrowedField<'a>> + 'a {
+        let (bytes, big_endian) = (self.bytes, self.definition.big_endian);
+        self.definition.fields().scan(0, move |offset, def| {
+            let start = *offset;
+            *offset += usize::from(def.size);
+            Some(BorrowedField {
+                def,
+                big_endian,
+                bytes: &bytes[start..*offset],
+            })
+        })
+    }
+
+    /// The regular field numbered `num`, if present. If the
+    /// definition lists it more than once this is the last
+    /// occurrence, as [`to_data`](#method.to_data) decodes.
+    pub fn field(&self, num: u8) -> Option<BorrowedField<'a>> {
+        self.fields().filter(|field| field.num() == num).last()
+    }
+
+    /// The developer fields with their raw values.
+    pub fn developer_fields(
+        &self,
+    ) -> impl Iterator<Item = (DeveloperFieldDef, &'a [u8])> + 'a {
+        let start = self
+            .definition
+            .fields()
+            .map(|def| usize::from(def.size))
+            .sum::<usize>();
+        let bytes = &self.bytes[start..];
+        self.definition.developer_fields().scan(0, move |offset, def| {
+            let start = *offset;
+            *offset += usize::from(def.size);
+            Some((def, &bytes[start..*offset]))
+        })
+    }
+
+    /// Decode into the owning profile types, exactly as
+    /// [`FitReader`](../reader/struct.FitReader.html)
+    /// would have.
+    pub fn to_data(&self) -> Result<Data> {
+        if self.definition.big_endian {
+            self.decode::<BigEndian>()
+        }
+        else {
+            self.decode::<LittleEndian>()
+        }
+    }
+
+    fn decode<T: ByteOrder>(&self) -> Result<Data> {
+        let mesg_num = self.definition.global_mesg_num;
+        let mut messages = Vec::new();
+        for (i, field) in self.fields().enumerate() {
+            // Only the last occurrence of a repeated field
+            // is decoded
+            if self.fields().skip(i + 1).any(|later| later.num() == field.num())
+            {
+                continue
+            }
+            messages.push(profile::messages::Message::decode::<T>(
+                field.bytes,
+                mesg_num,
+                field.num(),
+            )?);
+        }
+        for (_, bytes) in self.developer_fields() {
+            messages.push(profile::messages::Message::decode::<T>(
+                bytes,
+                mesg_num,
+                DEVELOPER_FIELD_NUM,
+            )?);
+        }
+        Ok(Data(messages))
+    }
+}
+
+/// The raw value of one field of a data message.
+///
+/// The numeric accessors return `None` when the field has
+/// the wrong size for the type asked for, or holds its base
+/// type's invalid value.
+#[derive(Debug, Clone, Copy)]
+pub struct BorrowedField<'a> {
+    def:        FieldDef,
+    big_endian: bool,
+    bytes:      &'a [u8],
+}
+
+macro_rules! accessor {
+    ($name:ident, $ty:ty, $read:ident) => {
+        pub fn $name(&self) -> Option<$ty> {
+            if self.bytes.len() != ::std::mem::size_of::<$ty>()
+                || !self.is_valid()
+            {
+                return None
+            }
+            if self.big_endian {
+                Some(BigEndian::$read(self.bytes))
+            }
+            else {
+                Some(LittleEndian::$read(self.bytes))
+            }
+        }
+    };
+}
+
+impl<'a> BorrowedField<'a> {
+    accessor!(as_u16, u16, read_u16);
+
+    accessor!(as_i16, i16, read_i16);
+
+    accessor!(as_u32, u32, read_u32);
+
+    accessor!(as_i32, i32, read_i32);
+
+    accessor!(as_u64, u64, read_u64);
+
+    accessor!(as_i64, i64, read_i64);
+
+    accessor!(as_f32, f32, read_f32);
+
+    accessor!(as_f64, f64, read_f64);
+
+    /// A field read out of a data message some other way,
+    /// such as a developer field with the base type its
+    /// `FieldDescription` gives.
+    pub(crate) fn new(
+        def: FieldDef,
+        big_endian: bool,
+        bytes: &'a [u8],
+    ) -> Self {
+        BorrowedField {
+            def,
+            big_endian,
+            bytes,
+        }
+    }
+
+    /// The field definition number.
+    pub fn num(&self) -> u8 {
+        self.def.num
+    }
+
+    /// The base type number from the field definition.
+    pub fn base_type(&self) -> u8 {
+        self.def.base_type
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Whether the value isn't the invalid value for its
+    /// base type.
+    pub fn is_valid(&self) -> bool {
+        !is_invalid_raw(self.def.base_type, self.bytes, self.big_endian)
+    }
+
+    /// The value as a string, up to the first NUL. `None`
+    /// if that's empty or isn't valid UTF-8.
+    pub fn as_str(&self) -> Option<&'a str> {
+        let end =
+            self.bytes.iter().position(|&b| b == 0).unwrap_or(self.bytes.len());
+        str::from_utf8(&self.bytes[..end]).ok().filter(|s| !s.is_empty())
+    }
+
+    pub fn as_u8(&self) -> Option<u8> {
+        match self.bytes {
+            &[byte] if self.is_valid() => Some(byte),
+            _ => None,
+        }
+    }
+
+    pub fn as_i8(&self) -> Option<i8> {
+        self.as_u8().map(|byte| byte as i8)
+    }
+
+    /// The value as a number, whatever its numeric base
+    /// type. `None` for strings, bytes, arrays and the
+    /// invalid value.
+    pub fn number(&self) -> Option<f64> {
+        match self.def.base_type & BASE_TYPE_NUM_MASK {
+            0x00 | 0x02 | 0x0A => self.as_u8().map(f64::from),
+            0x01 => self.as_i8().map(f64::from),
+            0x03 => self.as_i16().map(f64::from),
+            0x04 | 0x0B => self.as_u16().map(f64::from),
+            0x05 => self.as_i32().map(f64::from),
+            0x06 | 0x0C => self.as_u32().map(f64::from),
+            0x08 => self.as_f32().map(f64::from),
+            0x09 => self.as_f64(),
+            0x0E => self.as_i64().map(|value| value as f64),
+            0x0F | 0x10 => self.as_u64().map(|value| value as f64),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use testutil::{
+        count_allocations,
+        duplicate_field_file,
+        record_file,
+        FitBuilder,
+    };
+    use types::file::File;
+
+    fn data(message: Result<BorrowedMessage>) -> Option<BorrowedData> {
+        match message.unwrap() {
+            BorrowedMessage::Data(data) => Some(data),
+            BorrowedMessage::Definition(_) => None,
+        }
+    }
+
+    #[test]
+    fn records_decode_without_allocating() {
+        let bytes = record_file(10_000);
+
+        let (heart_rates, allocations) = count_allocations(|| {
+            FitSliceReader::new(&bytes)
+                .unwrap()
+                .filter_map(data)
+                .filter(|data| data.global_mesg_num() == 20)
+                .filter_map(|data| data.field(3)?.as_u8())
+                .map(u64::from)
+                .sum::<u64>()
+        });
+        assert_eq!(allocations, 0);
+
+        let (file, owned_allocations) =
+            count_allocations(|| File::from_bytes(&bytes).unwrap());
+        assert!(owned_allocations > 10_000, "{}", owned_allocations);
+
+        // Both see the same values
+        let owned: u64 = file
+            .messages()
+            .filter_map(::series::RecordPoint::from_message)
+            .filter_map(|point| point.heart_rate)
+            .map(u64::from)
+            .sum();
+        assert_eq!(heart_rates, owned);
+    }
+
+    #[test]
+    fn duplicate_fields_use_the_last_occurrence() {
+        let bytes = duplicate_field_file();
+        let record = FitSliceReader::new(&bytes)
+            .unwrap()
+            .filter_map(data)
+            .next()
+            .unwrap();
+        assert_eq!(record.field(253).unwrap().as_u32(), Some(1000));
+        assert_eq!(record.field(7).unwrap().as_u16(), Some(250));
+
+        let owned = record.to_data().unwrap();
+        let file = File::from_bytes(&bytes).unwrap();
+        let expected = file.messages().next().unwrap();
+        assert_eq!(format!("{:?}", owned), format!("{:?}", expected));
+    }
+
+    #[test]
+    fn borrowed_strings_and_owned_conversion() {
+        let mut fit = FitBuilder::new();
+        // DeviceInfo: device index, serial, product name, and
+        // a developer field
+        fit.raw(&[0x60, 0, 0, 23, 0, 3]);
+        fit.raw(&[0, 1, 0x02, 3, 4, 0x8C, 27, 8, 0x07]);
+        fit.raw(&[1, 7, 2, 0]);
+        fit.data(0, &[&[0], &1234u32.to_le_bytes(), b"Edge\0\0\0\0", &[9, 9]]);
+        let plain = fit.build();
+
+        // Compressed timestamp header for local message 0,
+        // which the owning reader doesn't decode
+        fit.raw(&[0x80 | 5, 1, 0, 0, 0, 0]);
+        fit.raw(b"\0\0\0\0\0\0\0\0");
+        fit.raw(&[0, 0]);
+        let bytes = fit.build();
+
+        let messages: Vec<BorrowedMessage> = FitSliceReader::new(&bytes)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(messages.len(), 3);
+
+        let device = match messages[1] {
+            BorrowedMessage::Data(data) => data,
+            _ => panic!("expected a data message"),
+        };
+        assert_eq!(device.field(3).unwrap().as_u32(), Some(1234));
+        assert_eq!(device.field(27).unwrap().as_str(), Some("Edge"));
+        assert_eq!(device.field(27).unwrap().bytes().len(), 8);
+        let developer: Vec<_> = device.developer_fields().collect();
+        assert_eq!(developer.len(), 1);
+        assert_eq!(developer[0].0.developer_data_index, 0);
+        assert_eq!(developer[0].1, &[9, 9]);
+
+        let owned = device.to_data().unwrap();
+        let expected = &File::from_bytes(&plain).unwrap();
+        let expected = expected.messages().next().unwrap();
+        assert_eq!(format!("{:?}", owned), format!("{:?}", expected));
+
+        let compressed = match messages[2] {
+            BorrowedMessage::Data(data) => data,
+            _ => panic!("expected a data message"),
+        };
+        assert_eq!(compressed.time_offset(), Some(5));
+        assert_eq!(compressed.field(3).unwrap().as_u32(), None);
+        assert_eq!(compressed.field(27).unwrap().as_str(), None);
+    }
+
+    #[test]
+    fn errors_match_the_owning_reader() {
+        let mut bytes = record_file(10);
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+
+        let err = FitSliceReader::new(&bytes)
+            .unwrap()
+            .find_map(|message| message.err())
+            .unwrap();
+        assert!(matches!(err.kind(), ErrorKind::CrcMismatch { .. }));
+
+        let mut fit = FitBuilder::new();
+        fit.data(3, &[&[1]]);
+        let err = FitSliceReader::new(&fit.build())
+            .unwrap()
+            .find_map(|message| message.err())
+            .unwrap();
+        assert!(matches!(err.kind(), ErrorKind::Decode { .. }));
+
+        let truncated = record_file(10);
+        let truncated = &truncated[..truncated.len() - 20];
+        let mut reader = FitSliceReader::new(truncated).unwrap();
+        assert!(reader.by_ref().any(|message| message.is_err()));
+        assert!(reader.next().is_none());
+    }
+}
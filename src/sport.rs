@@ -0,0 +1,592 @@
+//! Sports: how to present them, and what kind of activity a
+//! sport and sub sport stand for.
+//!
+//! Devices disagree on how to encode the same activity. A
+//! Zwift ride is `cycling` with sub sport
+//! `virtual_activity` from one app and `generic` from
+//! another, and a treadmill run is `running`/`treadmill` on
+//! one watch and `fitness_equipment`/`treadmill` on an
+//! older one. [`Canonical`] maps every such [`Encoding`]
+//! onto one [`ActivityKind`] analytics can rely on, through
+//! a curated table callers can override.
+//!
+//! [`Canonical`]: struct.Canonical.html
+//! [`Encoding`]: struct.Encoding.html
+//! [`ActivityKind`]: enum.ActivityKind.html
+use profile::{
+    messages::{
+        FileId,
+        Message,
+        Session,
+        Sport as SportField,
+    },
+    types::{
+        Manufacturer,
+        Sport,
+        SubSport,
+    },
+};
+use types::record::Data;
+
+/// A single emoji representing `sport`, for display.
+///
+/// Every sport gets its own emoji. Those with an emoji
+/// presentation and a text one (like `⛷`) are returned
+/// without the variation selector, since it wouldn't fit
+/// in a `char`.
+pub fn sport_emoji(sport: &Sport) -> char {
+    match sport {
+        Sport::Generic => '📊',
+        Sport::Running => '🏃',
+        Sport::Cycling => '🚴',
+        Sport::Transition => '🔀',
+        Sport::FitnessEquipment => '🏋',
+        Sport::Swimming => '🏊',
+        Sport::Basketball => '🏀',
+        Sport::Soccer => '⚽',
+        Sport::Tennis => '🎾',
+        Sport::AmericanFootball => '🏈',
+        Sport::Training => '💪',
+        Sport::Walking => '🚶',
+        Sport::CrossCountrySkiing => '🎿',
+        Sport::AlpineSkiing => '⛷',
+        Sport::Snowboarding => '🏂',
+        Sport::Rowing => '🚣',
+        Sport::Mountaineering => '🏔',
+        Sport::Hiking => '🥾',
+        // Triathlons are recorded as multisport activities
+        Sport::Multisport => '🏅',
+        Sport::Paddling => '🏞',
+        Sport::Flying => '✈',
+        Sport::EBiking => '🛵',
+        Sport::Motorcycling => '🏍',
+        Sport::Boating => '🚤',
+        Sport::Driving => '🚗',
+        Sport::Golf => '⛳',
+        Sport::HangGliding => '🦅',
+        Sport::HorsebackRiding => '🏇',
+        Sport::Hunting => '🏹',
+        Sport::Fishing => '🎣',
+        Sport::InlineSkating => '🛼',
+        Sport::RockClimbing => '🧗',
+        Sport::Sailing => '⛵',
+        Sport::IceSkating => '⛸',
+        Sport::SkyDiving => '🪂',
+        Sport::Snowshoeing => '❄',
+        Sport::Snowmobiling => '🛷',
+        Sport::StandUpPaddleboarding => '🧍',
+        Sport::Surfing => '🏄',
+        Sport::Wakeboarding => '🛹',
+        Sport::WaterSkiing => '💦',
+        Sport::Kayaking => '🛶',
+        Sport::Rafting => '🛟',
+        Sport::Windsurfing => '🌬',
+        Sport::Kitesurfing => '🪁',
+        Sport::Tactical => '🎯',
+        Sport::Jumpmaster => '🪖',
+        Sport::Boxing => '🥊',
+        Sport::FloorClimbing => '🪜',
+        Sport::All => '🌐',
+        Sport::Unknown => '❓',
+    }
+}
+
+/// Like [`sport_emoji`](fn.sport_emoji.html), refined by
+/// sub sport where a more specific emoji exists.
+pub fn subsport_emoji(sport: &Sport, subsport: &SubSport) -> char {
+    match (sport, subsport) {
+        (_, SubSport::VirtualActivity) => '🖥',
+        (Sport::Running, SubSport::Track) => '🏟',
+        (Sport::Cycling, SubSport::Mountain) => '⛰',
+        (Sport::Swimming, SubSport::OpenWater) => '🌊',
+        (sport, _) => sport_emoji(sport),
+    }
+}
+
+/// What an activity was, however the device that recorded
+/// it encoded that.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ActivityKind {
+    Run,
+    TrailRun,
+    TrackRun,
+    /// On a treadmill or otherwise indoors.
+    TreadmillRun,
+    /// In an app such as Zwift.
+    VirtualRun,
+    Ride,
+    MountainBikeRide,
+    /// Gravel and cyclocross.
+    GravelRide,
+    /// On a trainer or spin bike.
+    IndoorRide,
+    /// In an app such as Zwift.
+    VirtualRide,
+    EBikeRide,
+    EMountainBikeRide,
+    PoolSwim,
+    OpenWaterSwim,
+    /// A swim the encoding doesn't say more about.
+    Swim,
+    Walk,
+    Hike,
+    Row,
+    IndoorRow,
+    Strength,
+    /// Anything the table doesn't place. The raw sport
+    /// says more.
+    Other,
+}
+
+/// How a device encoded an activity: who made it and what
+/// sport and sub sport it wrote.
+#[derive(Debug, Clone)]
+pub struct Encoding {
+    pub manufacturer: Option<Manufacturer>,
+    pub product:      Option<u16>,
+    pub sport:        Sport,
+    pub sub_sport:    SubSport,
+}
+
+impl Encoding {
+    /// The device from the first `FileId` message, and the
+    /// sport and sub sport of the first `Session` message,
+    /// or failing that `Sport` message, with a valid sport.
+    pub fn from_messages(messages: &[Data]) -> Option<Encoding> {
+        let (manufacturer, product) = messages
+            .iter()
+            .find(|mesg| mesg.mesg_num() == Some(0))
+            .map(device)
+            .unwrap_or_default();
+        let sports = |mesg_num| {
+            messages
+                .iter()
+                .filter(move |mesg| mesg.mesg_num() == Some(mesg_num))
+                .filter_map(sports)
+        };
+        let (sport, sub_sport) = sports(18).chain(sports(12)).next()?;
+        Some(Encoding {
+            manufacturer,
+            product,
+            sport,
+            sub_sport,
+        })
+    }
+}
+
+/// The manufacturer and product of a `FileId` message.
+pub(crate) fn device(mesg: &Data) -> (Option<Manufacturer>, Option<u16>) {
+    let (mut manufacturer, mut product) = (None, None);
+    for field in mesg.0.iter() {
+        match field {
+            Message::FileId(FileId::Manufacturer(f)) => {
+                manufacturer = Some(f.raw_value.clone())
+            },
+            Message::FileId(FileId::Product(f)) if f.is_valid() => {
+                product = Some(f.raw_value.0)
+            },
+            _ => (),
+        }
+    }
+    (manufacturer, product)
+}
+
+/// The sport and sub sport of a `Session` or `Sport`
+/// message, if it has a valid sport. A missing sub sport
+/// is `Generic`.
+fn sports(mesg: &Data) -> Option<(Sport, SubSport)> {
+    let (mut sport, mut sub_sport) = (None, SubSport::Generic);
+    for field in mesg.0.iter() {
+        match field {
+            Message::Session(Session::Sport(f))
+            | Message::Sport(SportField::Sport(f)) => {
+                sport = Some(f.raw_value.clone())
+            },
+            Message::Session(Session::SubSport(f))
+            | Message::Sport(SportField::SubSport(f)) => {
+                sub_sport = f.raw_value.clone()
+            },
+            _ => (),
+        }
+    }
+    match sport {
+        Some(Sport::Unknown) | None => None,
+        Some(sport) => Some((sport, sub_sport)),
+    }
+}
+
+/// Which encodings an entry of a [`Canonical`] table
+/// applies to. `None` matches any value.
+///
+/// [`Canonical`]: struct.Canonical.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rule {
+    pub manufacturer: Option<u16>,
+    pub product:      Option<u16>,
+    pub sport:        Option<u8>,
+    pub sub_sport:    Option<u8>,
+}
+
+impl Rule {
+    /// `sport` with any sub sport, from any device.
+    pub fn sport(sport: Sport) -> Rule {
+        Rule {
+            sport: Some(sport as u8),
+            ..Rule::default()
+        }
+    }
+
+    pub fn sub_sport(mut self, sub_sport: SubSport) -> Rule {
+        self.sub_sport = Some(sub_sport as u8);
+        self
+    }
+
+    pub fn manufacturer(mut self, manufacturer: Manufacturer) -> Rule {
+        self.manufacturer = Some(manufacturer as u16);
+        self
+    }
+
+    /// A product number, which means something different
+    /// for every manufacturer.
+    pub fn product(mut self, product: u16) -> Rule {
+        self.product = Some(product);
+        self
+    }
+
+    pub fn matches(&self, encoding: &Encoding) -> bool {
+        let manufacturer =
+            encoding.manufacturer.as_ref().map(|m| m.clone() as u16);
+        fn matches<T: PartialEq>(rule: Option<T>, value: Option<T>) -> bool {
+            rule.is_none() || rule == value
+        }
+        matches(self.manufacturer, manufacturer)
+            && matches(self.product, encoding.product)
+            && matches(self.sport, Some(encoding.sport.clone() as u8))
+            && matches(self.sub_sport, Some(encoding.sub_sport.clone() as u8))
+    }
+}
+
+/// Maps [`Encoding`]s onto [`ActivityKind`]s: the first
+/// [`Rule`] to match one decides its kind, and anything
+/// none matches is `Other`.
+///
+/// The default table covers the sports and sub sports with
+/// a kind of their own, and the devices known to encode
+/// them their own way. Callers add their own rules ahead of
+/// it:
+///
+/// ```
+/// use garminfit::{
+///     sport::{
+///         ActivityKind,
+///         Canonical,
+///         Encoding,
+///         Rule,
+///     },
+///     Manufacturer,
+///     Sport,
+///     SubSport,
+/// };
+///
+/// // Rides from this bike computer are all on an e-bike
+/// let canonical = Canonical::default().with(
+///     Rule::sport(Sport::Cycling)
+///         .manufacturer(Manufacturer::Garmin)
+///         .product(3570),
+///     ActivityKind::EBikeRide,
+/// );
+/// let encoding = Encoding {
+///     manufacturer: Some(Manufacturer::Garmin),
+///     product:      Some(3570),
+///     sport:        Sport::Cycling,
+///     sub_sport:    SubSport::Road,
+/// };
+/// assert_eq!(canonical.kind(&encoding), ActivityKind::EBikeRide);
+/// ```
+///
+/// [`Encoding`]: struct.Encoding.html
+/// [`ActivityKind`]: enum.ActivityKind.html
+/// [`Rule`]: struct.Rule.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canonical {
+    rules: Vec<(Rule, ActivityKind)>,
+}
+
+impl Default for Canonical {
+    fn default() -> Self {
+        Canonical {
+            rules: default_rules()
+        }
+    }
+}
+
+impl Canonical {
+    /// A table with no rules, mapping everything to `Other`
+    /// until given some.
+    pub fn empty() -> Canonical {
+        Canonical {
+            rules: Vec::new()
+        }
+    }
+
+    /// Map encodings `rule` matches to `kind`, ahead of the
+    /// rules the table already has.
+    pub fn with(mut self, rule: Rule, kind: ActivityKind) -> Canonical {
+        self.rules.insert(0, (rule, kind));
+        self
+    }
+
+    pub fn rules(&self) -> &[(Rule, ActivityKind)] {
+        &self.rules
+    }
+
+    pub fn kind(&self, encoding: &Encoding) -> ActivityKind {
+        self.rules
+            .iter()
+            .find(|(rule, _)| rule.matches(encoding))
+            .map_or(ActivityKind::Other, |&(_, kind)| kind)
+    }
+}
+
+/// Most specific first.
+fn default_rules() -> Vec<(Rule, ActivityKind)> {
+    use self::ActivityKind::*;
+
+    let running = || Rule::sport(Sport::Running);
+    let cycling = || Rule::sport(Sport::Cycling);
+    let equipment = || Rule::sport(Sport::FitnessEquipment);
+    vec![
+        // Zwift writes generic sub sports as well as virtual
+        // activity ones
+        (cycling().manufacturer(Manufacturer::Zwift), VirtualRide),
+        (running().manufacturer(Manufacturer::Zwift), VirtualRun),
+        // Wahoo's trainers and their apps record rides as
+        // fitness equipment, often without a sub sport
+        (equipment().manufacturer(Manufacturer::WahooFitness), IndoorRide),
+        (running().sub_sport(SubSport::VirtualActivity), VirtualRun),
+        (running().sub_sport(SubSport::Treadmill), TreadmillRun),
+        (running().sub_sport(SubSport::IndoorRunning), TreadmillRun),
+        (running().sub_sport(SubSport::Trail), TrailRun),
+        (running().sub_sport(SubSport::Track), TrackRun),
+        (running(), Run),
+        // Older Garmin watches record indoor activities as
+        // fitness equipment
+        (equipment().sub_sport(SubSport::Treadmill), TreadmillRun),
+        (equipment().sub_sport(SubSport::IndoorRunning), TreadmillRun),
+        (equipment().sub_sport(SubSport::IndoorCycling), IndoorRide),
+        (equipment().sub_sport(SubSport::IndoorRowing), IndoorRow),
+        (cycling().sub_sport(SubSport::VirtualActivity), VirtualRide),
+        (cycling().sub_sport(SubSport::IndoorCycling), IndoorRide),
+        (cycling().sub_sport(SubSport::Spin), IndoorRide),
+        (cycling().sub_sport(SubSport::Mountain), MountainBikeRide),
+        (cycling().sub_sport(SubSport::Downhill), MountainBikeRide),
+        (cycling().sub_sport(SubSport::GravelCycling), GravelRide),
+        (cycling().sub_sport(SubSport::Cyclocross), GravelRide),
+        // E-bike rides are cycling sub sports or a sport of
+        // their own
+        (cycling().sub_sport(SubSport::EBikeMountain), EMountainBikeRide),
+        (cycling().sub_sport(SubSport::EBikeFitness), EBikeRide),
+        (
+            Rule::sport(Sport::EBiking).sub_sport(SubSport::Mountain),
+            EMountainBikeRide,
+        ),
+        (
+            Rule::sport(Sport::EBiking).sub_sport(SubSport::EBikeMountain),
+            EMountainBikeRide,
+        ),
+        (Rule::sport(Sport::EBiking), EBikeRide),
+        (cycling(), Ride),
+        (
+            Rule::sport(Sport::Swimming).sub_sport(SubSport::LapSwimming),
+            PoolSwim,
+        ),
+        (
+            Rule::sport(Sport::Swimming).sub_sport(SubSport::OpenWater),
+            OpenWaterSwim,
+        ),
+        (Rule::sport(Sport::Swimming), Swim),
+        (Rule::sport(Sport::Walking), Walk),
+        (Rule::sport(Sport::Hiking), Hike),
+        (
+            Rule::sport(Sport::Rowing).sub_sport(SubSport::IndoorRowing),
+            IndoorRow,
+        ),
+        (Rule::sport(Sport::Rowing), Row),
+        (
+            Rule::sport(Sport::Training).sub_sport(SubSport::StrengthTraining),
+            Strength,
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::LittleEndian;
+    use std::collections::HashMap;
+    use testutil::mesg;
+
+    #[test]
+    fn every_sport_has_its_own_emoji() {
+        // Decode every possible raw value to reach every variant
+        let mut seen: HashMap<char, String> = HashMap::new();
+        for raw in 0..=255u8 {
+            let sport = Sport::decode::<LittleEndian>(&[raw]).unwrap();
+            let name = format!("{:?}", sport);
+            let emoji = sport_emoji(&sport);
+
+            assert!(char::from_u32(emoji as u32).is_some());
+            assert!(!emoji.is_ascii());
+            let previous = seen.entry(emoji).or_insert_with(|| name.clone());
+            assert_eq!(*previous, name, "{} is used twice", emoji);
+        }
+        assert_eq!(seen.len(), 51);
+    }
+
+    #[test]
+    fn subsport_refines_sport() {
+        assert_eq!(
+            subsport_emoji(&Sport::Cycling, &SubSport::VirtualActivity),
+            '🖥'
+        );
+        assert_eq!(subsport_emoji(&Sport::Running, &SubSport::Track), '🏟');
+        assert_eq!(
+            subsport_emoji(&Sport::Swimming, &SubSport::OpenWater),
+            '🌊'
+        );
+        assert_eq!(subsport_emoji(&Sport::Cycling, &SubSport::Mountain), '⛰');
+        assert_eq!(subsport_emoji(&Sport::Cycling, &SubSport::Road), '🚴');
+        assert_eq!(subsport_emoji(&Sport::Running, &SubSport::Mountain), '🏃');
+    }
+
+    fn kind(
+        manufacturer: Manufacturer,
+        sport: Sport,
+        sub_sport: SubSport,
+    ) -> ActivityKind {
+        Canonical::default().kind(&Encoding {
+            manufacturer: Some(manufacturer),
+            product: Some(1),
+            sport,
+            sub_sport,
+        })
+    }
+
+    #[test]
+    fn quirky_encodings() {
+        use self::ActivityKind::*;
+        use profile::types::Manufacturer::*;
+
+        // Zwift, with either sub sport
+        assert_eq!(
+            kind(Zwift, Sport::Cycling, SubSport::VirtualActivity),
+            VirtualRide
+        );
+        assert_eq!(kind(Zwift, Sport::Cycling, SubSport::Generic), VirtualRide);
+        assert_eq!(kind(Zwift, Sport::Running, SubSport::Generic), VirtualRun);
+        assert_eq!(
+            kind(Garmin, Sport::Cycling, SubSport::VirtualActivity),
+            VirtualRide
+        );
+        // Indoor rides: Wahoo's, and Garmin's either way
+        assert_eq!(
+            kind(WahooFitness, Sport::FitnessEquipment, SubSport::Generic),
+            IndoorRide
+        );
+        assert_eq!(
+            kind(Garmin, Sport::FitnessEquipment, SubSport::IndoorCycling),
+            IndoorRide
+        );
+        assert_eq!(
+            kind(Garmin, Sport::Cycling, SubSport::IndoorCycling),
+            IndoorRide
+        );
+        assert_eq!(
+            kind(Garmin, Sport::FitnessEquipment, SubSport::Generic),
+            Other
+        );
+        // Garmin treadmill runs, old and new
+        assert_eq!(
+            kind(Garmin, Sport::FitnessEquipment, SubSport::Treadmill),
+            TreadmillRun
+        );
+        assert_eq!(
+            kind(Garmin, Sport::Running, SubSport::Treadmill),
+            TreadmillRun
+        );
+        assert_eq!(
+            kind(Garmin, Sport::Running, SubSport::IndoorRunning),
+            TreadmillRun
+        );
+        // E-bikes
+        assert_eq!(kind(Garmin, Sport::EBiking, SubSport::Generic), EBikeRide);
+        assert_eq!(
+            kind(Garmin, Sport::Cycling, SubSport::EBikeMountain),
+            EMountainBikeRide
+        );
+        assert_eq!(
+            kind(Garmin, Sport::EBiking, SubSport::Mountain),
+            EMountainBikeRide
+        );
+        assert_eq!(kind(Garmin, Sport::Cycling, SubSport::Road), Ride);
+        assert_eq!(kind(Garmin, Sport::Golf, SubSport::Generic), Other);
+        assert_eq!(kind(Zwift, Sport::Golf, SubSport::Generic), Other);
+    }
+
+    #[test]
+    fn overrides_come_first() {
+        let canonical = Canonical::default()
+            .with(Rule::sport(Sport::Cycling), ActivityKind::EBikeRide)
+            .with(
+                Rule::sport(Sport::Cycling).manufacturer(Manufacturer::Zwift),
+                ActivityKind::IndoorRide,
+            );
+        let encoding = |manufacturer| {
+            Encoding {
+                manufacturer: Some(manufacturer),
+                product:      None,
+                sport:        Sport::Cycling,
+                sub_sport:    SubSport::VirtualActivity,
+            }
+        };
+        assert_eq!(
+            canonical.kind(&encoding(Manufacturer::Zwift)),
+            ActivityKind::IndoorRide
+        );
+        assert_eq!(
+            canonical.kind(&encoding(Manufacturer::Garmin)),
+            ActivityKind::EBikeRide
+        );
+        assert_eq!(canonical.rules().len(), default_rules().len() + 2);
+        assert_eq!(
+            Canonical::empty().kind(&encoding(Manufacturer::Zwift)),
+            ActivityKind::Other
+        );
+    }
+
+    #[test]
+    fn encoding_from_messages() {
+        let messages = vec![
+            mesg(0).u8(0, 4).u16(1, 260).u16(2, 0xFFFF).build(),
+            // A Sport message with an invalid sport
+            mesg(12).u8(0, 0xFF).build(),
+            mesg(12).u8(0, 2).u8(1, 6).build(),
+            mesg(18).u8(5, 2).build(),
+        ];
+        let encoding = Encoding::from_messages(&messages).unwrap();
+        assert!(matches!(encoding.manufacturer, Some(Manufacturer::Zwift)));
+        assert_eq!(encoding.product, None);
+        // The session's, without a sub sport
+        assert!(matches!(encoding.sub_sport, SubSport::Generic));
+        assert_eq!(
+            Canonical::default().kind(&encoding),
+            ActivityKind::VirtualRide
+        );
+
+        let encoding = Encoding::from_messages(&messages[1..3]).unwrap();
+        assert!(encoding.manufacturer.is_none());
+        assert!(matches!(encoding.sub_sport, SubSport::IndoorCycling));
+        assert!(Encoding::from_messages(&messages[..2]).is_none());
+    }
+}
@@ -0,0 +1,52 @@
+//! Counters describing what a decode run came across.
+use std::{
+    collections::BTreeMap,
+    time::Duration,
+};
+
+/// Outcome of the file checksum check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CrcStatus {
+    /// Decoding stopped before the end of the file.
+    #[default]
+    NotChecked,
+    Valid,
+    Mismatch,
+}
+
+/// Statistics gathered by a [`FitReader`] with
+/// [`with_stats`] enabled.
+///
+/// The message histograms are keyed by global message
+/// number, and iterate and serialize in ascending order.
+/// `unknown_messages` is the one to watch for gaps in the
+/// profile.
+///
+/// [`FitReader`]: ../reader/struct.FitReader.html
+/// [`with_stats`]: ../reader/struct.FitReader.html#method.with_stats
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DecodeStats {
+    /// Bytes consumed, header included.
+    pub bytes_read:            u64,
+    /// Time spent decoding records.
+    pub elapsed:               Duration,
+    pub crc:                   CrcStatus,
+    pub definitions:           u64,
+    /// Definitions that replaced an earlier one for the
+    /// same local message number.
+    pub redefinitions:         u64,
+    pub data_messages:         u64,
+    pub compressed_timestamps: u64,
+    /// Data messages decoded, by global message number.
+    pub messages_by_type:      BTreeMap<u16, u64>,
+    /// Data messages missing from the profile, by global
+    /// message number.
+    pub unknown_messages:      BTreeMap<u16, u64>,
+    /// Fields missing from the profile, in otherwise known
+    /// messages.
+    pub unknown_fields:        u64,
+    /// Fields holding their base type's invalid value.
+    pub invalid_fields:        u64,
+}
@@ -0,0 +1,163 @@
+//! Strength training sets, from the `Set` messages of an
+//! activity.
+use profile::{
+    messages::{
+        Message,
+        Set,
+    },
+    types::{
+        ExerciseCategory,
+        SetType,
+    },
+};
+use std::collections::BTreeMap;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// One active set of a strength training activity.
+///
+/// Missing or invalid numeric fields are zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainingSet {
+    /// Seconds since the FIT epoch.
+    pub timestamp:         u32,
+    /// Raw `ExerciseCategory` value, `0xFFFF` when it's
+    /// missing or not in the profile.
+    pub exercise_category: u16,
+    /// Exercise within the category, see the profile's
+    /// `*_exercise_name` types.
+    pub exercise_num:      u16,
+    pub weight_kg:         f64,
+    pub reps:              u16,
+    pub duration_s:        f64,
+    /// The profile's `Set` message has no calories field,
+    /// so this is only set by callers merging in other
+    /// data.
+    pub total_calories:    Option<u16>,
+}
+
+impl TrainingSet {
+    /// Flatten a decoded `Set` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Set` message or is
+    /// a rest between sets.
+    pub fn from_message(mesg: &Data) -> Option<TrainingSet> {
+        let mut set = TrainingSet {
+            exercise_category: 0xFFFF,
+            ..TrainingSet::default()
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Set(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                Set::Timestamp(f) => set.timestamp = f.raw_value.0,
+                Set::SetType(f) => {
+                    if let SetType::Rest = f.raw_value {
+                        return None
+                    }
+                },
+                Set::Category(f) => {
+                    set.exercise_category = match f.raw_value.clone() {
+                        ExerciseCategory::Unknown => 0xFFFF,
+                        category => category as u16,
+                    }
+                },
+                Set::CategorySubtype(f) if f.is_valid() => {
+                    set.exercise_num = f.raw_value.0
+                },
+                Set::Weight(f) if f.is_valid() => set.weight_kg = f.value(),
+                Set::Repetitions(f) if f.is_valid() => set.reps = f.raw_value.0,
+                Set::Duration(f) if f.is_valid() => set.duration_s = f.value(),
+                _ => (),
+            }
+        }
+
+        Some(set)
+    }
+}
+
+/// The active sets in `messages`, in file order.
+pub fn extract_training_sets(messages: &[Data]) -> Vec<TrainingSet> {
+    messages.iter().filter_map(TrainingSet::from_message).collect()
+}
+
+/// Total weight lifted: the sum of weight times reps over
+/// `sets`, in kilograms.
+pub fn volume_load(sets: &[TrainingSet]) -> f64 {
+    sets.iter().map(|set| set.weight_kg * f64::from(set.reps)).sum()
+}
+
+/// Group `sets` by exercise, keyed by
+/// `(exercise_category, exercise_num)`. Groups iterate in
+/// key order and sets keep their order within each group.
+pub fn group_by_exercise(
+    sets: &[TrainingSet],
+) -> BTreeMap<(u16, u16), Vec<&TrainingSet>> {
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for set in sets {
+        groups
+            .entry((set.exercise_category, set.exercise_num))
+            .or_default()
+            .push(set);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn set(category: u16, exercise: u16, weight_kg: u16, reps: u16) -> Data {
+        mesg(225)
+            .u32(254, 1_000_000_000)
+            .u32(0, 30_000)
+            .u16(3, reps)
+            .u16(4, weight_kg * 16)
+            .u8(5, 1)
+            .u16(7, category)
+            .u16(8, exercise)
+            .build()
+    }
+
+    fn rest() -> Data {
+        mesg(225).u32(254, 1_000_000_000).u32(0, 90_000).u8(5, 0).build()
+    }
+
+    #[test]
+    fn volume_and_groups() {
+        // Bench press, squat and deadlift, three sets each
+        let messages: Vec<Data> = [(0, 0, 60), (28, 0, 80), (8, 0, 100)]
+            .iter()
+            .flat_map(|&(category, exercise, weight)| {
+                vec![
+                    set(category, exercise, weight, 10),
+                    rest(),
+                    set(category, exercise, weight, 8),
+                    rest(),
+                    set(category, exercise, weight, 6),
+                ]
+            })
+            .collect();
+
+        let sets = extract_training_sets(&messages);
+        assert_eq!(sets.len(), 9);
+        assert_eq!(sets[0].weight_kg, 60.0);
+        assert_eq!(sets[0].duration_s, 30.0);
+        assert_eq!(sets[0].total_calories, None);
+        assert_eq!(volume_load(&sets), (60.0 + 80.0 + 100.0) * 24.0);
+
+        let groups = group_by_exercise(&sets);
+        assert_eq!(groups.len(), 3);
+        let squats = &groups[&(28, 0)];
+        let reps: Vec<u16> = squats.iter().map(|set| set.reps).collect();
+        assert_eq!(reps, vec![10, 8, 6]);
+        assert_eq!(volume_load(&[]), 0.0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Text helpers for names and other strings decoded from a
+//! FIT file on their way into text formats.
+//!
+//! FIT strings are UTF-8 in fixed size fields, so a device
+//! name or workout name can hold emoji, CJK or right to
+//! left text, and anything written into a field has to be
+//! cut on a character boundary. When exporting them to XML
+//! (GPX, TCX) or CSV, escape them with [`escape_xml`] and
+//! [`escape_csv`].
+//!
+//! [`escape_xml`]: fn.escape_xml.html
+//! [`escape_csv`]: fn.escape_csv.html
+use std::borrow::Cow;
+
+/// The longest prefix of `s` that fits in `n` bytes without
+/// splitting a character.
+///
+/// ```
+/// use garminfit::strings::truncate_to_bytes;
+///
+/// // The runner is four bytes
+/// assert_eq!(truncate_to_bytes("Run 🏃", 6), "Run ");
+/// assert_eq!(truncate_to_bytes("Run 🏃", 8), "Run 🏃");
+/// ```
+pub fn truncate_to_bytes(s: &str, n: usize) -> &str {
+    if s.len() <= n {
+        return s;
+    }
+    let mut end = n;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// `s` with the characters that are special in XML text and
+/// attribute values replaced by entities.
+///
+/// Control characters other than tab, newline and carriage
+/// return can't appear in XML 1.0 at all, so they're
+/// dropped.
+pub fn escape_xml(s: &str) -> Cow<'_, str> {
+    let special = |c: char| {
+        matches!(c, '&' | '<' | '>' | '"' | '\'') || is_xml_illegal(c)
+    };
+    if !s.contains(special) {
+        return Cow::Borrowed(s);
+    }
+    let mut escaped = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c if is_xml_illegal(c) => (),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// `s` as a CSV field, quoted per RFC 4180 if it holds a
+/// comma, quote or line break.
+pub fn escape_csv(s: &str) -> Cow<'_, str> {
+    if !s.contains([',', '"', '\n', '\r']) {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(format!("\"{}\"", s.replace('"', "\"\"")))
+}
+
+fn is_xml_illegal(c: char) -> bool {
+    c < ' ' && !matches!(c, '\t' | '\n' | '\r')
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate csv;
+    extern crate roxmltree;
+
+    use super::*;
+    use testutil::FitBuilder;
+    use types::file::File;
+    use workout::Workout;
+
+    const NAMES: &[&str] = &[
+        "Morning run 🏃‍♀️🔥",
+        "朝のジョギング, 東京",
+        "ריצת בוקר",
+        "Intervals <5 × 400m> & \"strides\"",
+    ];
+
+    /// A file holding a `Workout` message with each name in
+    /// a 32 byte `wkt_name` field.
+    fn workouts() -> Vec<Workout> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 26, &[(8, 32, 7)]);
+        for name in NAMES {
+            let mut field = truncate_to_bytes(name, 31).as_bytes().to_vec();
+            field.resize(32, 0);
+            fit.data(0, &[&field]);
+        }
+        let file = File::from_bytes(&fit.build()).unwrap();
+        file.messages().filter_map(Workout::from_message).collect()
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        for name in NAMES {
+            for n in 0..=name.len() + 1 {
+                let cut = truncate_to_bytes(name, n);
+                assert!(cut.len() <= n && name.starts_with(cut));
+                assert!(n >= name.len() || cut.len() + 4 > n);
+            }
+        }
+        assert_eq!(truncate_to_bytes("跑步", 5), "跑");
+        assert_eq!(truncate_to_bytes("", 0), "");
+    }
+
+    #[test]
+    fn names_round_trip_through_xml() {
+        let names = workouts();
+        assert_eq!(names.len(), NAMES.len());
+
+        let mut gpx = String::from("<gpx>");
+        for workout in &names {
+            let name = workout.name.as_ref().unwrap();
+            gpx.push_str(&format!(
+                "<trk name=\"{0}\"><name>{0}</name></trk>",
+                escape_xml(name)
+            ));
+        }
+        gpx.push_str("</gpx>");
+
+        let doc = roxmltree::Document::parse(&gpx).unwrap();
+        let tracks: Vec<_> =
+            doc.root_element().children().filter(|n| n.is_element()).collect();
+        for (track, (workout, original)) in
+            tracks.iter().zip(names.iter().zip(NAMES))
+        {
+            let name = workout.name.as_ref().unwrap();
+            assert!(original.starts_with(name.as_str()));
+            assert_eq!(track.attribute("name"), Some(name.as_str()));
+            assert_eq!(
+                track.first_child().unwrap().text(),
+                Some(name.as_str())
+            );
+        }
+        assert_eq!(escape_xml("a\u{1}b"), "ab");
+        assert!(matches!(escape_xml("ריצה"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn names_round_trip_through_csv() {
+        let names: Vec<String> =
+            workouts().into_iter().filter_map(|w| w.name).collect();
+
+        let mut csv = String::from("name,count\n");
+        for name in &names {
+            csv.push_str(&format!("{},1\n", escape_csv(name)));
+        }
+        csv.push_str(&format!("{},1\n", escape_csv("two\nlines, \"quoted\"")));
+
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let parsed: Vec<String> = reader
+            .records()
+            .map(|record| record.unwrap()[0].to_owned())
+            .collect();
+        assert_eq!(parsed[..names.len()], names[..]);
+        assert_eq!(parsed[names.len()], "two\nlines, \"quoted\"");
+    }
+}
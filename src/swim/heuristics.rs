@@ -0,0 +1,469 @@
+//! Corrections for the usual ways a watch miscounts a pool
+//! swim.
+//!
+//! A missed turn or a push-off counted twice leaves a
+//! phantom length of a few strokes and seconds next to a
+//! real one, and drills swum with a kickboard get recorded
+//! as whatever stroke the watch guessed. [`fix`] puts these
+//! right and reports each change it made, so the result can
+//! be reviewed before it's written out again.
+//!
+//! [`fix`]: fn.fix.html
+use profile::types::SwimStroke;
+use swim::{
+    Length,
+    SwimLap,
+};
+
+/// Which corrections [`fix`] makes, each independently.
+///
+/// [`fix`]: fn.fix.html
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Merge lengths with too few strokes and too little
+    /// time to be real into a neighbour.
+    pub merge_short_lengths:   bool,
+    /// Mark the active lengths of laps with a `Drill`
+    /// stroke as drills too.
+    pub reclassify_drill_laps: bool,
+    /// Recompute the length counts and totals of each lap
+    /// from its lengths.
+    pub recompute_lap_totals:  bool,
+    /// A length is too short when both its strokes and its
+    /// duration are under this fraction of the median of
+    /// the active lengths in its lap.
+    pub short_fraction:        f64,
+    /// Metres, to recompute lap distances from the number
+    /// of active lengths. Distances are left alone without
+    /// it.
+    pub pool_length_m:         Option<f64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            merge_short_lengths:   true,
+            reclassify_drill_laps: true,
+            recompute_lap_totals:  true,
+            short_fraction:        0.5,
+            pool_length_m:         None,
+        }
+    }
+}
+
+/// A change made by [`fix`]. Lengths and laps are
+/// identified by their index in its input.
+///
+/// [`fix`]: fn.fix.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The length `length` was merged into `into`.
+    MergedLength { length: usize, into: usize },
+    /// The length was in a drill lap, and is now a drill.
+    Reclassified { length: usize },
+    /// Some of the lap's totals were recomputed to
+    /// different values.
+    LapTotals { lap: usize },
+}
+
+/// The corrected lengths and laps, and what was changed.
+#[derive(Debug, Clone)]
+pub struct Fixed {
+    /// Numbered afresh from zero, in order.
+    pub lengths: Vec<Length>,
+    pub laps:    Vec<SwimLap>,
+    pub changes: Vec<Change>,
+}
+
+/// Correct `lengths` and the `laps` grouping them, as
+/// chosen by `opts`.
+///
+/// Lengths belong to the lap their midpoint falls in. Both
+/// are expected in time order, as they're recorded.
+pub fn fix(lengths: &[Length], laps: &[SwimLap], opts: &Options) -> Fixed {
+    let mut changes = Vec::new();
+    // Paired with their index in `lengths`, for the log
+    let mut work: Vec<(usize, Length)> =
+        lengths.iter().cloned().enumerate().collect();
+
+    if opts.merge_short_lengths {
+        merge_short_lengths(&mut work, laps, opts.short_fraction, &mut changes);
+    }
+
+    if opts.reclassify_drill_laps {
+        for (index, length) in work.iter_mut() {
+            let in_drill = lap_of(laps, length)
+                .is_some_and(|lap| is_drill(&laps[lap].swim_stroke));
+            if in_drill && length.active && !is_drill(&length.swim_stroke) {
+                length.swim_stroke = Some(SwimStroke::Drill);
+                changes.push(Change::Reclassified {
+                    length: *index
+                });
+            }
+        }
+    }
+
+    let lengths: Vec<Length> = work
+        .into_iter()
+        .enumerate()
+        .map(|(i, (_, mut length))| {
+            length.message_index = Some(i as u16);
+            length
+        })
+        .collect();
+
+    let mut laps = laps.to_vec();
+    if opts.recompute_lap_totals {
+        for (index, lap) in laps.iter_mut().enumerate() {
+            if recompute_totals(lap, &lengths, opts.pool_length_m) {
+                changes.push(Change::LapTotals {
+                    lap: index
+                });
+            }
+        }
+    }
+
+    Fixed {
+        lengths,
+        laps,
+        changes,
+    }
+}
+
+fn merge_short_lengths(
+    work: &mut Vec<(usize, Length)>,
+    laps: &[SwimLap],
+    fraction: f64,
+    changes: &mut Vec<Change>,
+) {
+    // Judged against the lengths as recorded, so one merge
+    // doesn't move the bar for the next
+    let medians: Vec<Option<(f64, f64)>> = (0..laps.len())
+        .map(|lap| {
+            let active: Vec<&Length> = work
+                .iter()
+                .map(|(_, length)| length)
+                .filter(|l| l.active && lap_of(laps, l) == Some(lap))
+                .collect();
+            // Too few to tell a short length from a normal one
+            if active.len() < 3 {
+                return None;
+            }
+            let strokes = median(
+                active
+                    .iter()
+                    .filter_map(|l| l.total_strokes.map(f64::from))
+                    .collect(),
+            )?;
+            let duration =
+                median(active.iter().map(|l| l.total_timer_s).collect())?;
+            Some((strokes, duration))
+        })
+        .collect();
+
+    let mut i = 0;
+    while i < work.len() {
+        let length = &work[i].1;
+        let lap = lap_of(laps, length);
+        let short = match lap.and_then(|lap| medians[lap]) {
+            Some((strokes, duration)) => {
+                length.active
+                    && length
+                        .total_strokes
+                        .is_some_and(|n| f64::from(n) < fraction * strokes)
+                    && length.total_timer_s < fraction * duration
+            },
+            None => false,
+        };
+        // Into the active length before it in the same lap,
+        // or else the one after
+        let same_lap =
+            |j: usize| work[j].1.active && lap_of(laps, &work[j].1) == lap;
+        let target = if !short {
+            None
+        }
+        else if i > 0 && same_lap(i - 1) {
+            Some(i - 1)
+        }
+        else if i + 1 < work.len() && same_lap(i + 1) {
+            Some(i + 1)
+        }
+        else {
+            None
+        };
+
+        match target {
+            Some(j) => {
+                let (index, short) = work.remove(i);
+                let j = if j > i { j - 1 } else { j };
+                merge(&mut work[j].1, &short);
+                changes.push(Change::MergedLength {
+                    length: index,
+                    into:   work[j].0,
+                });
+            },
+            None => i += 1,
+        }
+    }
+}
+
+/// Fold `short` into `into`, which it's next to.
+fn merge(into: &mut Length, short: &Length) {
+    into.start_time = into.start_time.min(short.start_time);
+    into.timestamp = into.timestamp.max(short.timestamp);
+    into.total_elapsed_s += short.total_elapsed_s;
+    into.total_timer_s += short.total_timer_s;
+    if let Some(strokes) = short.total_strokes {
+        into.total_strokes =
+            Some(into.total_strokes.unwrap_or(0).saturating_add(strokes));
+    }
+}
+
+/// Returns whether any of the totals changed.
+fn recompute_totals(
+    lap: &mut SwimLap,
+    lengths: &[Length],
+    pool_length_m: Option<f64>,
+) -> bool {
+    let first = lengths.iter().position(|l| lap.contains(l));
+    let mine: Vec<&Length> =
+        lengths.iter().filter(|l| lap.contains(l)).collect();
+    let active = mine.iter().filter(|l| l.active).count();
+    let strokes = mine
+        .iter()
+        .filter(|l| l.active)
+        .filter_map(|l| l.total_strokes)
+        .map(u32::from)
+        .sum();
+
+    let before = (
+        lap.first_length_index,
+        lap.num_lengths,
+        lap.num_active_lengths,
+        lap.total_strokes,
+        lap.total_distance_m,
+    );
+    lap.first_length_index = first.map(|i| i as u16);
+    lap.num_lengths = Some(mine.len() as u16);
+    lap.num_active_lengths = Some(active as u16);
+    lap.total_strokes = Some(strokes);
+    if let Some(pool) = pool_length_m {
+        lap.total_distance_m = Some(pool * active as f64);
+    }
+    before
+        != (
+            lap.first_length_index,
+            lap.num_lengths,
+            lap.num_active_lengths,
+            lap.total_strokes,
+            lap.total_distance_m,
+        )
+}
+
+fn lap_of(laps: &[SwimLap], length: &Length) -> Option<usize> {
+    laps.iter().position(|lap| lap.contains(length))
+}
+
+fn is_drill(stroke: &Option<SwimStroke>) -> bool {
+    matches!(stroke, Some(SwimStroke::Drill))
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values
+        .sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    Some(
+        if values.len() % 2 == 1 {
+            values[mid]
+        }
+        else {
+            (values[mid - 1] + values[mid]) / 2.0
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profile::types::MesgNum;
+    use testutil::mesg;
+    use types::record::Data;
+
+    const T0: u32 = 1_000_000_000;
+
+    /// A length of `seconds` from `start`, seconds after
+    /// T0.
+    fn length(start: u32, seconds: u32, strokes: u16, stroke: u8) -> Data {
+        mesg(MesgNum::Length.value())
+            .u32(253, T0 + start + seconds)
+            .u32(2, T0 + start)
+            .u32(3, seconds * 1000)
+            .u32(4, seconds * 1000)
+            .u16(5, strokes)
+            .u8(7, stroke)
+            .u8(12, 1)
+            // Not modelled, so carried through as it is
+            .u16(11, 9)
+            .build()
+    }
+
+    fn lap(start: u32, end: u32, stroke: u8, lengths: u16) -> Data {
+        mesg(MesgNum::Lap.value())
+            .u32(253, T0 + end)
+            .u32(2, T0 + start)
+            .u32(7, (end - start) * 1000)
+            .u32(8, (end - start) * 1000)
+            .u8(38, stroke)
+            .u16(32, lengths)
+            .build()
+    }
+
+    /// A freestyle lap of four lengths with a phantom in
+    /// the middle, then a drill lap recorded as
+    /// breaststroke.
+    fn swim() -> (Vec<Length>, Vec<SwimLap>) {
+        let messages = vec![
+            length(0, 30, 20, 0),
+            length(30, 31, 21, 0),
+            length(61, 3, 2, 0),
+            length(64, 30, 20, 0),
+            length(94, 29, 19, 0),
+            lap(0, 123, 0, 5),
+            length(150, 40, 12, 2),
+            length(190, 40, 12, 2),
+            lap(150, 230, 4, 2),
+        ];
+        (::swim::lengths(&messages), ::swim::laps(&messages))
+    }
+
+    #[test]
+    fn merges_and_reclassifies() {
+        let (lengths, laps) = swim();
+        let opts = Options {
+            pool_length_m: Some(25.0),
+            ..Options::default()
+        };
+        let fixed = fix(&lengths, &laps, &opts);
+
+        assert_eq!(
+            fixed.changes,
+            vec![
+                Change::MergedLength {
+                    length: 2, into: 1
+                },
+                Change::Reclassified {
+                    length: 5
+                },
+                Change::Reclassified {
+                    length: 6
+                },
+                Change::LapTotals {
+                    lap: 0
+                },
+                Change::LapTotals {
+                    lap: 1
+                },
+            ]
+        );
+
+        assert_eq!(fixed.lengths.len(), 6);
+        let merged = &fixed.lengths[1];
+        assert_eq!((merged.start_time, merged.timestamp), (T0 + 30, T0 + 64));
+        assert_eq!(merged.total_strokes, Some(23));
+        assert_eq!(merged.total_timer_s, 34.0);
+        assert!(matches!(
+            fixed.lengths[4].swim_stroke,
+            Some(SwimStroke::Drill)
+        ));
+        assert_eq!(fixed.lengths[5].message_index, Some(5));
+
+        let lap = &fixed.laps[0];
+        assert_eq!(lap.num_lengths, Some(4));
+        assert_eq!(lap.num_active_lengths, Some(4));
+        assert_eq!(lap.total_strokes, Some(82));
+        assert_eq!(lap.total_distance_m, Some(100.0));
+        assert_eq!(fixed.laps[1].first_length_index, Some(4));
+    }
+
+    #[test]
+    fn heuristics_disable_independently() {
+        let (lengths, laps) = swim();
+
+        let no_merge = fix(
+            &lengths,
+            &laps,
+            &Options {
+                merge_short_lengths: false,
+                ..Options::default()
+            },
+        );
+        assert_eq!(no_merge.lengths.len(), 7);
+        assert_eq!(no_merge.laps[0].num_lengths, Some(5));
+        assert!(no_merge
+            .changes
+            .iter()
+            .all(|c| !matches!(c, Change::MergedLength { .. })));
+
+        let no_drill = fix(
+            &lengths,
+            &laps,
+            &Options {
+                reclassify_drill_laps: false,
+                ..Options::default()
+            },
+        );
+        assert_eq!(no_drill.lengths.len(), 6);
+        assert!(matches!(
+            no_drill.lengths[4].swim_stroke,
+            Some(SwimStroke::Breaststroke)
+        ));
+
+        let no_totals = fix(
+            &lengths,
+            &laps,
+            &Options {
+                recompute_lap_totals: false,
+                ..Options::default()
+            },
+        );
+        assert_eq!(no_totals.laps[0].num_lengths, Some(5));
+        assert_eq!(no_totals.changes.len(), 3);
+
+        let nothing = fix(
+            &lengths,
+            &laps,
+            &Options {
+                merge_short_lengths: false,
+                reclassify_drill_laps: false,
+                recompute_lap_totals: false,
+                ..Options::default()
+            },
+        );
+        assert!(nothing.changes.is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_messages() {
+        let (lengths, laps) = swim();
+        let fixed = fix(&lengths, &laps, &Options::default());
+
+        let length = &fixed.lengths[1];
+        let message = length.to_message();
+        let back = Length::from_message(&message).unwrap();
+        assert_eq!(back.total_strokes, Some(23));
+        assert_eq!(back.total_elapsed_s, 34.0);
+        assert_eq!((back.start_time, back.timestamp), (T0 + 30, T0 + 64));
+        assert_eq!(back.message_index, Some(1));
+        // Calories weren't modelled, and are kept
+        assert!(message.0.iter().any(|f| f.field_def_num() == 11));
+        assert_eq!(message.0.len(), length.source.len() + 1);
+
+        let lap = SwimLap::from_message(&fixed.laps[1].to_message()).unwrap();
+        assert!(matches!(lap.swim_stroke, Some(SwimStroke::Drill)));
+        assert_eq!(lap.num_lengths, Some(2));
+        assert_eq!(lap.first_length_index, Some(4));
+    }
+}
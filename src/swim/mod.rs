@@ -0,0 +1,374 @@
+//! Pool swims: the lengths swum, and the laps grouping them
+//! into intervals.
+//!
+//! Watches count lengths by the turn at each wall, which
+//! goes wrong often enough to need [`heuristics`] to put it
+//! right. Both [`Length`] and [`SwimLap`] turn back into
+//! messages for writing out again, keeping whatever fields
+//! they don't model as they were read.
+//!
+//! [`heuristics`]: heuristics/index.html
+//! [`Length`]: struct.Length.html
+//! [`SwimLap`]: struct.SwimLap.html
+use byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+use profile::{
+    messages::{
+        Lap as LapField,
+        Length as LengthField,
+        Message,
+    },
+    types::{
+        LengthType,
+        MesgNum,
+        SwimStroke,
+    },
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+
+pub mod heuristics;
+
+/// A `Length` message: one length of the pool, or a rest
+/// at the wall.
+#[derive(Debug, Clone)]
+pub struct Length {
+    pub message_index:   Option<u16>,
+    /// End of the length, seconds since the FIT epoch.
+    pub timestamp:       u32,
+    /// Seconds since the FIT epoch.
+    pub start_time:      u32,
+    /// Seconds.
+    pub total_elapsed_s: f64,
+    /// Seconds.
+    pub total_timer_s:   f64,
+    pub total_strokes:   Option<u16>,
+    pub swim_stroke:     Option<SwimStroke>,
+    /// Whether the length was swum, rather than spent
+    /// resting.
+    pub active:          bool,
+    /// The fields read, for those not modelled above.
+    source:              Vec<Message>,
+}
+
+/// Field numbers of `Length` that [`Length`] models.
+///
+/// [`Length`]: struct.Length.html
+const LENGTH_FIELDS: [u8; 8] = [254, 253, 2, 3, 4, 5, 7, 12];
+
+impl Length {
+    /// Flatten a decoded `Length` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Length` message or
+    /// lacks a valid start time and timestamp. Lengths
+    /// without a `length_type` are active if they have
+    /// strokes.
+    pub fn from_message(mesg: &Data) -> Option<Length> {
+        let (mut timestamp, mut start_time) = (None, None);
+        let (mut elapsed, mut timer) = (None, None);
+        let mut length_type = None;
+        let mut length = Length {
+            message_index:   None,
+            timestamp:       0,
+            start_time:      0,
+            total_elapsed_s: 0.0,
+            total_timer_s:   0.0,
+            total_strokes:   None,
+            swim_stroke:     None,
+            active:          false,
+            source:          mesg.0.clone(),
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Length(field) => field,
+                _ => return None,
+            };
+            match field {
+                LengthField::MessageIndex(f) if f.raw_value.0 != u16::MAX => {
+                    length.message_index = Some(f.raw_value.0)
+                },
+                LengthField::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                LengthField::StartTime(f) if f.raw_value.0 != u32::MAX => {
+                    start_time = Some(f.raw_value.0)
+                },
+                LengthField::TotalElapsedTime(f) if f.is_valid() => {
+                    elapsed = Some(f.value())
+                },
+                LengthField::TotalTimerTime(f) if f.is_valid() => {
+                    timer = Some(f.value())
+                },
+                LengthField::TotalStrokes(f) if f.is_valid() => {
+                    length.total_strokes = Some(f.raw_value.0)
+                },
+                LengthField::SwimStroke(f) => {
+                    length.swim_stroke = match f.raw_value {
+                        SwimStroke::Unknown => None,
+                        ref stroke => Some(stroke.clone()),
+                    }
+                },
+                LengthField::LengthType(f) => {
+                    length_type = match f.raw_value {
+                        LengthType::Active => Some(true),
+                        LengthType::Idle => Some(false),
+                        LengthType::Unknown => None,
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        length.timestamp = timestamp?;
+        length.start_time = start_time?;
+        let span =
+            f64::from(length.timestamp.saturating_sub(length.start_time));
+        length.total_elapsed_s = elapsed.unwrap_or(span);
+        length.total_timer_s = timer.unwrap_or(length.total_elapsed_s);
+        length.active = length_type
+            .unwrap_or_else(|| length.total_strokes.is_some_and(|n| n > 0));
+        Some(length)
+    }
+
+    /// Seconds since the FIT epoch halfway through the
+    /// length.
+    fn midpoint(&self) -> f64 {
+        (f64::from(self.start_time) + f64::from(self.timestamp)) / 2.0
+    }
+
+    /// The length as a `Length` message, with the fields
+    /// read that aren't modelled here as they were.
+    pub fn to_message(&self) -> Data {
+        let mut fields =
+            Fields::keeping(MesgNum::Length, &self.source, &LENGTH_FIELDS);
+        fields.u16(254, self.message_index);
+        fields.u32(253, Some(self.timestamp));
+        fields.u32(2, Some(self.start_time));
+        fields.u32(3, Some(millis(self.total_elapsed_s)));
+        fields.u32(4, Some(millis(self.total_timer_s)));
+        fields.u16(5, self.total_strokes);
+        fields.u8(7, self.swim_stroke.as_ref().and_then(stroke_value));
+        fields.u8(12, Some(self.active as u8));
+        fields.build()
+    }
+}
+
+/// A `Lap` message of a pool swim, an interval of lengths.
+#[derive(Debug, Clone)]
+pub struct SwimLap {
+    pub message_index:      Option<u16>,
+    /// End of the lap, seconds since the FIT epoch.
+    pub timestamp:          u32,
+    /// Seconds since the FIT epoch.
+    pub start_time:         u32,
+    /// Seconds.
+    pub total_elapsed_s:    f64,
+    /// Seconds.
+    pub total_timer_s:      f64,
+    /// Metres.
+    pub total_distance_m:   Option<f64>,
+    /// Strokes, from `total_cycles`.
+    pub total_strokes:      Option<u32>,
+    /// A lap marked `Drill` holds drill lengths whatever
+    /// they were recorded as.
+    pub swim_stroke:        Option<SwimStroke>,
+    pub first_length_index: Option<u16>,
+    pub num_lengths:        Option<u16>,
+    pub num_active_lengths: Option<u16>,
+    /// The fields read, for those not modelled above.
+    source:                 Vec<Message>,
+}
+
+/// Field numbers of `Lap` that [`SwimLap`] models.
+///
+/// [`SwimLap`]: struct.SwimLap.html
+const LAP_FIELDS: [u8; 11] = [254, 253, 2, 7, 8, 9, 10, 32, 35, 38, 40];
+
+impl SwimLap {
+    /// Flatten a decoded `Lap` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Lap` message or
+    /// lacks a valid start time and timestamp.
+    pub fn from_message(mesg: &Data) -> Option<SwimLap> {
+        let (mut timestamp, mut start_time) = (None, None);
+        let (mut elapsed, mut timer) = (None, None);
+        let mut lap = SwimLap {
+            message_index:      None,
+            timestamp:          0,
+            start_time:         0,
+            total_elapsed_s:    0.0,
+            total_timer_s:      0.0,
+            total_distance_m:   None,
+            total_strokes:      None,
+            swim_stroke:        None,
+            first_length_index: None,
+            num_lengths:        None,
+            num_active_lengths: None,
+            source:             mesg.0.clone(),
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Lap(field) => field,
+                _ => return None,
+            };
+            match field {
+                LapField::MessageIndex(f) if f.raw_value.0 != u16::MAX => {
+                    lap.message_index = Some(f.raw_value.0)
+                },
+                LapField::Timestamp(f) if f.raw_value.0 != u32::MAX => {
+                    timestamp = Some(f.raw_value.0)
+                },
+                LapField::StartTime(f) if f.raw_value.0 != u32::MAX => {
+                    start_time = Some(f.raw_value.0)
+                },
+                LapField::TotalElapsedTime(f) if f.is_valid() => {
+                    elapsed = Some(f.value())
+                },
+                LapField::TotalTimerTime(f) if f.is_valid() => {
+                    timer = Some(f.value())
+                },
+                LapField::TotalDistance(f) if f.is_valid() => {
+                    lap.total_distance_m = Some(f.value())
+                },
+                LapField::TotalCycles(f) if f.is_valid() => {
+                    lap.total_strokes = Some(f.raw_value.0)
+                },
+                LapField::SwimStroke(f) => {
+                    lap.swim_stroke = match f.raw_value {
+                        SwimStroke::Unknown => None,
+                        ref stroke => Some(stroke.clone()),
+                    }
+                },
+                LapField::FirstLengthIndex(f) if f.is_valid() => {
+                    lap.first_length_index = Some(f.raw_value.0)
+                },
+                LapField::NumLengths(f) if f.is_valid() => {
+                    lap.num_lengths = Some(f.raw_value.0)
+                },
+                LapField::NumActiveLengths(f) if f.is_valid() => {
+                    lap.num_active_lengths = Some(f.raw_value.0)
+                },
+                _ => (),
+            }
+        }
+
+        lap.timestamp = timestamp?;
+        lap.start_time = start_time?;
+        let span = f64::from(lap.timestamp.saturating_sub(lap.start_time));
+        lap.total_elapsed_s = elapsed.unwrap_or(span);
+        lap.total_timer_s = timer.unwrap_or(lap.total_elapsed_s);
+        Some(lap)
+    }
+
+    /// Whether `length` falls in the lap, judged by its
+    /// midpoint.
+    fn contains(&self, length: &Length) -> bool {
+        let mid = length.midpoint();
+        f64::from(self.start_time) <= mid && mid <= f64::from(self.timestamp)
+    }
+
+    /// The lap as a `Lap` message, with the fields read
+    /// that aren't modelled here as they were.
+    pub fn to_message(&self) -> Data {
+        let mut fields =
+            Fields::keeping(MesgNum::Lap, &self.source, &LAP_FIELDS);
+        fields.u16(254, self.message_index);
+        fields.u32(253, Some(self.timestamp));
+        fields.u32(2, Some(self.start_time));
+        fields.u32(7, Some(millis(self.total_elapsed_s)));
+        fields.u32(8, Some(millis(self.total_timer_s)));
+        fields
+            .u32(9, self.total_distance_m.map(|m| (m * 100.0).round() as u32));
+        fields.u32(10, self.total_strokes);
+        fields.u8(38, self.swim_stroke.as_ref().and_then(stroke_value));
+        fields.u16(35, self.first_length_index);
+        fields.u16(32, self.num_lengths);
+        fields.u16(40, self.num_active_lengths);
+        fields.build()
+    }
+}
+
+/// The `Length` messages among `messages`, in file order.
+pub fn lengths(messages: &[Data]) -> Vec<Length> {
+    messages.iter().filter_map(Length::from_message).collect()
+}
+
+/// The `Lap` messages among `messages`, in file order.
+pub fn laps(messages: &[Data]) -> Vec<SwimLap> {
+    messages.iter().filter_map(SwimLap::from_message).collect()
+}
+
+fn millis(seconds: f64) -> u32 {
+    (seconds * 1000.0).round() as u32
+}
+
+fn stroke_value(stroke: &SwimStroke) -> Option<u8> {
+    match stroke {
+        SwimStroke::Unknown => None,
+        stroke => Some(stroke.clone() as u8),
+    }
+}
+
+/// Builds up the fields of a message, decoding each through
+/// the profile so they come out as if read from a file.
+struct Fields {
+    mesg_num: u16,
+    fields:   Vec<Message>,
+}
+
+impl Fields {
+    /// Start from the fields of `source` that aren't among
+    /// `replaced`.
+    fn keeping(mesg_num: MesgNum, source: &[Message], replaced: &[u8]) -> Self {
+        let fields = source
+            .iter()
+            .filter(|field| !replaced.contains(&field.field_def_num()))
+            .cloned()
+            .collect();
+        Fields {
+            mesg_num: mesg_num.value(),
+            fields,
+        }
+    }
+
+    fn push(&mut self, num: u8, bytes: &[u8]) {
+        // Every field written here is in the profile
+        if let Ok(field) =
+            Message::decode::<LittleEndian>(bytes, self.mesg_num, num)
+        {
+            self.fields.push(field);
+        }
+    }
+
+    fn u8(&mut self, num: u8, value: Option<u8>) {
+        if let Some(value) = value {
+            self.push(num, &[value]);
+        }
+    }
+
+    fn u16(&mut self, num: u8, value: Option<u16>) {
+        if let Some(value) = value {
+            let mut bytes = [0; 2];
+            LittleEndian::write_u16(&mut bytes, value);
+            self.push(num, &bytes);
+        }
+    }
+
+    fn u32(&mut self, num: u8, value: Option<u32>) {
+        if let Some(value) = value {
+            let mut bytes = [0; 4];
+            LittleEndian::write_u32(&mut bytes, value);
+            self.push(num, &bytes);
+        }
+    }
+
+    fn build(self) -> Data {
+        Data(self.fields)
+    }
+}
@@ -0,0 +1,677 @@
+//! Random but valid activity files, for testing code built
+//! on this crate without collecting hundreds of device
+//! files. Needs the `testgen` feature.
+//!
+//! [`generate`] writes a complete FIT byte stream of the
+//! [`Shape`] asked for: a `FileId`, timer events, records,
+//! laps, a session and an activity, with values that wander
+//! the way a real activity's do. The same seed always gives
+//! the same bytes, so a failure found with one can be
+//! replayed:
+//!
+//! ```
+//! use garminfit::testgen::{
+//!     generate,
+//!     Shape,
+//! };
+//!
+//! let shape = Shape {
+//!     records: 120,
+//!     big_endian: true,
+//!     ..Shape::default()
+//! };
+//! for seed in 0..10 {
+//!     let bytes = generate(&shape, seed);
+//!     let file = garminfit::File::from_bytes(&bytes)
+//!         .unwrap_or_else(|err| panic!("seed {}: {}", seed, err));
+//!     assert_eq!(file.messages().count(), 120 + 6);
+//! }
+//! ```
+//!
+//! [`generate`]: fn.generate.html
+//! [`Shape`]: struct.Shape.html
+use crc::CrcWriter;
+use profile::types::MesgNum;
+use std::io::Write;
+use strings;
+
+/// Base type numbers, as written in field definitions.
+const ENUM: u8 = 0x00;
+const UINT8: u8 = 0x02;
+const STRING: u8 = 0x07;
+const UINT16: u8 = 0x84;
+const SINT32: u8 = 0x85;
+const UINT32: u8 = 0x86;
+const UINT32Z: u8 = 0x8C;
+
+/// Local message numbers of the messages written.
+const FILE_ID: u8 = 0;
+const DEVELOPER: u8 = 1;
+const EVENT: u8 = 2;
+/// Low enough for compressed timestamp headers to refer to.
+const RECORD: u8 = 3;
+const TIMED_RECORD: u8 = 4;
+const LAP: u8 = 5;
+const SESSION: u8 = 6;
+const ACTIVITY: u8 = 7;
+
+/// What the generated files look like.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shape {
+    /// `Record` messages per file.
+    pub records:               usize,
+    pub heart_rate:            bool,
+    pub cadence:               bool,
+    pub power:                 bool,
+    /// Latitude and longitude.
+    pub position:              bool,
+    pub altitude:              bool,
+    /// Record only when something changed, one to eight
+    /// seconds apart, instead of every second.
+    pub smart_recording:       bool,
+    /// `Lap` messages per file, splitting the records
+    /// evenly. None at all if zero.
+    pub laps:                  usize,
+    /// A developer humidity field on every record, with the
+    /// `DeveloperDataId` and `FieldDescription` it needs.
+    pub developer_fields:      bool,
+    /// Big endian definitions and data.
+    pub big_endian:            bool,
+    /// Give records a compressed timestamp header, except
+    /// the first of each lap.
+    ///
+    /// The records carry their data as the FIT protocol
+    /// says they should. The readers of this crate don't
+    /// decode those yet, so this is for testing other
+    /// consumers.
+    pub compressed_timestamps: bool,
+    /// Files written one after the other into the stream,
+    /// as devices sometimes do. At least one is.
+    pub chained_files:         usize,
+}
+
+impl Default for Shape {
+    /// Ten minutes of one second records with every
+    /// optional field, in a single lap.
+    fn default() -> Self {
+        Shape {
+            records:               600,
+            heart_rate:            true,
+            cadence:               true,
+            power:                 true,
+            position:              true,
+            altitude:              true,
+            smart_recording:       false,
+            laps:                  1,
+            developer_fields:      false,
+            big_endian:            false,
+            compressed_timestamps: false,
+            chained_files:         1,
+        }
+    }
+}
+
+/// A FIT byte stream of `shape`, the same for the same
+/// `seed`.
+pub fn generate(shape: &Shape, seed: u64) -> Vec<u8> {
+    let mut rng = Rng(seed);
+    let mut bytes = Vec::new();
+    for _ in 0..shape.chained_files.max(1) {
+        bytes.extend(activity(shape, &mut rng));
+    }
+    bytes
+}
+
+/// One activity file.
+fn activity(shape: &Shape, rng: &mut Rng) -> Vec<u8> {
+    let mut fit = Encoder::new(shape.big_endian);
+    let start = 900_000_000 + rng.below(100_000_000) as u32;
+    let cycling = rng.below(2) == 1;
+
+    fit.definition(
+        FILE_ID,
+        MesgNum::FileId,
+        &[
+            (0, 1, ENUM),
+            (1, 2, UINT16),
+            (2, 2, UINT16),
+            (3, 4, UINT32Z),
+            (4, 4, UINT32),
+        ],
+        &[],
+    );
+    fit.data(FILE_ID);
+    fit.u8(4); // activity
+    fit.u16(1); // Garmin
+    fit.u16(1 + rng.below(4000) as u16);
+    fit.u32(1 + rng.below(u64::from(u32::MAX) - 1) as u32);
+    fit.u32(start);
+
+    if shape.developer_fields {
+        developer_field_description(&mut fit);
+    }
+
+    fit.definition(
+        EVENT,
+        MesgNum::Event,
+        &[(253, 4, UINT32), (0, 1, ENUM), (1, 1, ENUM)],
+        &[],
+    );
+    timer_event(&mut fit, start, 0); // start
+
+    let mut fields = Vec::new();
+    if shape.position {
+        fields.push((0, 4, SINT32));
+        fields.push((1, 4, SINT32));
+    }
+    if shape.altitude {
+        fields.push((2, 2, UINT16));
+    }
+    if shape.heart_rate {
+        fields.push((3, 1, UINT8));
+    }
+    if shape.cadence {
+        fields.push((4, 1, UINT8));
+    }
+    fields.push((5, 4, UINT32));
+    fields.push((6, 2, UINT16));
+    if shape.power {
+        fields.push((7, 2, UINT16));
+    }
+    let developer: &[(u8, u8, u8)] =
+        if shape.developer_fields { &[(0, 2, 0)] } else { &[] };
+    let mut timed = vec![(253, 4, UINT32)];
+    timed.extend_from_slice(&fields);
+    fit.definition(TIMED_RECORD, MesgNum::Record, &timed, developer);
+    if shape.compressed_timestamps {
+        fit.definition(RECORD, MesgNum::Record, &fields, developer);
+    }
+    fit.definition(
+        LAP,
+        MesgNum::Lap,
+        &[
+            (254, 2, UINT16),
+            (253, 4, UINT32),
+            (2, 4, UINT32),
+            (7, 4, UINT32),
+            (8, 4, UINT32),
+            (9, 4, UINT32),
+        ],
+        &[],
+    );
+
+    let mut state = Activity::new(rng, start, cycling);
+    let laps = shape.laps.min(shape.records);
+    let mut lap_start = (start, 0.0);
+    let mut lap_index = 0;
+    let mut first_in_lap = true;
+    for i in 0..shape.records {
+        if i > 0 {
+            let step =
+                if shape.smart_recording { 1 + rng.below(8) as u32 } else { 1 };
+            state.advance(rng, step);
+        }
+
+        if shape.compressed_timestamps && !first_in_lap {
+            fit.compressed(RECORD, (state.timestamp & 0x1F) as u8);
+        }
+        else {
+            fit.data(TIMED_RECORD);
+            fit.u32(state.timestamp);
+        }
+        first_in_lap = false;
+        if shape.position {
+            fit.i32(state.lat);
+            fit.i32(state.lon);
+        }
+        if shape.altitude {
+            fit.u16(((state.altitude_m + 500.0) * 5.0).round() as u16);
+        }
+        if shape.heart_rate {
+            fit.u8(state.heart_rate.round() as u8);
+        }
+        if shape.cadence {
+            fit.u8(state.cadence.round() as u8);
+        }
+        fit.u32((state.distance_m * 100.0).round() as u32);
+        fit.u16((state.speed_ms * 1000.0).round() as u16);
+        if shape.power {
+            fit.u16(state.power_w.round() as u16);
+        }
+        if shape.developer_fields {
+            fit.u16(400 + rng.below(200) as u16); // 0.1 %
+        }
+
+        // Laps end on the last record of each equal share
+        if laps > 0 && (i + 1) * laps / shape.records > lap_index {
+            let (lap_time, lap_distance) = lap_start;
+            let elapsed = state.timestamp - lap_time;
+            fit.data(LAP);
+            fit.u16(lap_index as u16);
+            fit.u32(state.timestamp);
+            fit.u32(lap_time);
+            fit.u32(elapsed * 1000);
+            fit.u32(elapsed * 1000);
+            fit.u32(((state.distance_m - lap_distance) * 100.0).round() as u32);
+            lap_start = (state.timestamp, state.distance_m);
+            lap_index += 1;
+            first_in_lap = true;
+        }
+    }
+
+    let end = state.timestamp;
+    let elapsed_ms = (end - start) * 1000;
+    timer_event(&mut fit, end, 4); // stop all
+
+    fit.definition(
+        SESSION,
+        MesgNum::Session,
+        &[
+            (254, 2, UINT16),
+            (253, 4, UINT32),
+            (2, 4, UINT32),
+            (5, 1, ENUM),
+            (7, 4, UINT32),
+            (8, 4, UINT32),
+            (9, 4, UINT32),
+            (25, 2, UINT16),
+            (26, 2, UINT16),
+        ],
+        &[],
+    );
+    fit.data(SESSION);
+    fit.u16(0);
+    fit.u32(end);
+    fit.u32(start);
+    fit.u8(if cycling { 2 } else { 1 });
+    fit.u32(elapsed_ms);
+    fit.u32(elapsed_ms);
+    fit.u32((state.distance_m * 100.0).round() as u32);
+    fit.u16(0);
+    fit.u16(laps as u16);
+
+    fit.definition(
+        ACTIVITY,
+        MesgNum::Activity,
+        &[
+            (253, 4, UINT32),
+            (0, 4, UINT32),
+            (1, 2, UINT16),
+            (2, 1, ENUM),
+            (3, 1, ENUM),
+            (4, 1, ENUM),
+        ],
+        &[],
+    );
+    fit.data(ACTIVITY);
+    fit.u32(end);
+    fit.u32(elapsed_ms);
+    fit.u16(1);
+    fit.u8(0); // manual
+    fit.u8(26); // activity
+    fit.u8(1); // stop
+
+    fit.finish()
+}
+
+/// Describe developer field 0 of developer 0, a relative
+/// humidity in tenths of a percent.
+fn developer_field_description(fit: &mut Encoder) {
+    fit.definition(DEVELOPER, MesgNum::DeveloperDataId, &[(3, 1, UINT8)], &[]);
+    fit.data(DEVELOPER);
+    fit.u8(0);
+
+    fit.definition(
+        DEVELOPER,
+        MesgNum::FieldDescription,
+        &[
+            (0, 1, UINT8),
+            (1, 1, UINT8),
+            (2, 1, UINT8),
+            (3, 16, STRING),
+            (6, 1, UINT8),
+            (8, 8, STRING),
+        ],
+        &[],
+    );
+    fit.data(DEVELOPER);
+    fit.u8(0);
+    fit.u8(0);
+    fit.u8(UINT16);
+    fit.string("humidity", 16);
+    fit.u8(10);
+    fit.string("%", 8);
+}
+
+fn timer_event(fit: &mut Encoder, timestamp: u32, event_type: u8) {
+    fit.data(EVENT);
+    fit.u32(timestamp);
+    fit.u8(0); // timer
+    fit.u8(event_type);
+}
+
+/// Where the simulated athlete is at.
+struct Activity {
+    timestamp:    u32,
+    lat:          i32,
+    lon:          i32,
+    /// Direction of travel, in semicircles per metre north
+    /// and east.
+    heading:      (f64, f64),
+    altitude_m:   f64,
+    distance_m:   f64,
+    speed_ms:     f64,
+    heart_rate:   f64,
+    cadence:      f64,
+    power_w:      f64,
+    target_speed: f64,
+}
+
+impl Activity {
+    fn new(rng: &mut Rng, start: u32, cycling: bool) -> Self {
+        // About 11.9 metres per 10^5 semicircles of latitude
+        let to_semicircles = 8_388.608;
+        let angle = rng.f64(0.0, 2.0 * ::std::f64::consts::PI);
+        let target_speed =
+            if cycling { rng.f64(6.0, 10.0) } else { rng.f64(2.5, 4.0) };
+        Activity {
+            timestamp: start,
+            lat: rng.f64(-6.0e8, 6.0e8) as i32,
+            lon: rng.f64(-2.0e9, 2.0e9) as i32,
+            heading: (
+                angle.cos() * to_semicircles,
+                angle.sin() * to_semicircles,
+            ),
+            altitude_m: rng.f64(0.0, 2000.0),
+            distance_m: 0.0,
+            speed_ms: target_speed,
+            heart_rate: rng.f64(90.0, 120.0),
+            cadence: if cycling { 85.0 } else { 80.0 },
+            power_w: if cycling { 180.0 } else { 250.0 },
+            target_speed,
+        }
+    }
+
+    /// Move on `seconds`.
+    fn advance(&mut self, rng: &mut Rng, seconds: u32) {
+        let dt = f64::from(seconds);
+        self.timestamp += seconds;
+        self.speed_ms = (self.speed_ms
+            + 0.2 * (self.target_speed - self.speed_ms)
+            + rng.f64(-0.3, 0.3))
+        .clamp(0.5, 20.0);
+        let metres = self.speed_ms * dt;
+        self.distance_m += metres;
+        self.lat = (f64::from(self.lat) + self.heading.0 * metres / 1000.0)
+            .clamp(-1.0e9, 1.0e9) as i32;
+        self.lon =
+            (f64::from(self.lon) + self.heading.1 * metres / 1000.0) as i32;
+        self.altitude_m =
+            (self.altitude_m + rng.f64(-0.5, 0.5) * dt).clamp(-400.0, 8000.0);
+        self.heart_rate =
+            (self.heart_rate + rng.f64(-1.0, 1.5)).clamp(60.0, 200.0);
+        self.cadence = (self.cadence + rng.f64(-2.0, 2.0)).clamp(40.0, 120.0);
+        self.power_w = (self.power_w + rng.f64(-15.0, 15.0)).clamp(0.0, 1500.0);
+    }
+}
+
+/// Writes FIT records into a buffer, and the header and CRC
+/// around them when done.
+struct Encoder {
+    big_endian: bool,
+    data:       Vec<u8>,
+}
+
+impl Encoder {
+    fn new(big_endian: bool) -> Self {
+        Encoder {
+            big_endian,
+            data: Vec::new(),
+        }
+    }
+
+    /// A definition message of `(number, size, base type)`
+    /// fields, and `(number, size, developer index)`
+    /// developer fields.
+    fn definition(
+        &mut self,
+        local: u8,
+        mesg: MesgNum,
+        fields: &[(u8, u8, u8)],
+        developer: &[(u8, u8, u8)],
+    ) {
+        let has_developer = if developer.is_empty() { 0 } else { 0x20 };
+        self.data.push(0x40 | has_developer | local);
+        self.data.push(0);
+        self.data.push(self.big_endian as u8);
+        self.u16(mesg.value());
+        self.data.push(fields.len() as u8);
+        for &(num, size, base_type) in fields {
+            self.data.extend_from_slice(&[num, size, base_type]);
+        }
+        if !developer.is_empty() {
+            self.data.push(developer.len() as u8);
+            for &(num, size, index) in developer {
+                self.data.extend_from_slice(&[num, size, index]);
+            }
+        }
+    }
+
+    /// The header of a data message.
+    fn data(&mut self, local: u8) {
+        self.data.push(local);
+    }
+
+    /// The header of a data message with a compressed
+    /// timestamp, the low five bits of the full one.
+    fn compressed(&mut self, local: u8, time_offset: u8) {
+        self.data.push(0x80 | (local << 5) | time_offset);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        }
+        else {
+            value.to_le_bytes()
+        };
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn u32(&mut self, value: u32) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        }
+        else {
+            value.to_le_bytes()
+        };
+        self.data.extend_from_slice(&bytes);
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.u32(value as u32);
+    }
+
+    /// `value` NUL padded to `size` bytes.
+    fn string(&mut self, value: &str, size: usize) {
+        // Leave room for the NUL, and don't split a character
+        let value = strings::truncate_to_bytes(value, size.saturating_sub(1));
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(size, 0);
+        self.data.extend_from_slice(&bytes);
+    }
+
+    /// The whole file: a 14 byte header, always little
+    /// endian, the records, and the CRC.
+    fn finish(self) -> Vec<u8> {
+        let mut header = vec![14, 0x20];
+        header.extend_from_slice(&2066u16.to_le_bytes());
+        header.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        header.extend_from_slice(b".FIT");
+
+        let mut file = CrcWriter::new(Vec::new());
+        // Writing to a Vec can't fail
+        let _ = file.write_all(&header);
+        let header_crc = file.crc();
+        let _ = file.write_all(&header_crc.to_le_bytes());
+        let _ = file.write_all(&self.data);
+        let crc = file.crc();
+        let mut bytes = file.into_inner();
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+}
+
+/// SplitMix64, so files don't depend on the version of an
+/// outside random number generator.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`, near enough.
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    /// Uniform in `low..high`.
+    fn f64(&mut self, low: f64, high: f64) -> f64 {
+        let unit = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+        low + unit * (high - low)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crc::CrcReader;
+    use health::health_check;
+    use reader::FitReader;
+    use series::RecordTimeSeries;
+    use std::io::{
+        self,
+        Cursor,
+    };
+    use types::file::File;
+
+    fn decode(shape: &Shape, seed: u64) -> File {
+        File::from_bytes(&generate(shape, seed))
+            .unwrap_or_else(|err| panic!("{:?} seed {}: {}", shape, seed, err))
+    }
+
+    #[test]
+    fn same_seed_same_file() {
+        let shape = Shape::default();
+        assert_eq!(generate(&shape, 7), generate(&shape, 7));
+        assert_ne!(generate(&shape, 7), generate(&shape, 8));
+    }
+
+    #[test]
+    fn every_shape_decodes() {
+        let bare = Shape {
+            heart_rate: false,
+            cadence: false,
+            power: false,
+            position: false,
+            altitude: false,
+            laps: 0,
+            ..Shape::default()
+        };
+        let shapes = vec![
+            Shape::default(),
+            bare,
+            Shape {
+                smart_recording: true,
+                laps: 5,
+                ..Shape::default()
+            },
+            Shape {
+                developer_fields: true,
+                big_endian: true,
+                laps: 3,
+                ..Shape::default()
+            },
+            Shape {
+                records: 1,
+                laps: 4,
+                ..Shape::default()
+            },
+        ];
+        for shape in &shapes {
+            for seed in 0..5 {
+                let file = decode(shape, seed);
+                let series = RecordTimeSeries::from_messages(
+                    &file.messages().cloned().collect::<Vec<_>>(),
+                );
+                assert_eq!(series.len(), shape.records);
+                assert!(series
+                    .points
+                    .windows(2)
+                    .all(|pair| { pair[0].timestamp < pair[1].timestamp }));
+                assert_eq!(
+                    series.points[0].heart_rate.is_some(),
+                    shape.heart_rate
+                );
+                assert_eq!(
+                    series.points[0].position().is_some(),
+                    shape.position
+                );
+                let issues = health_check(&file);
+                assert!(issues.is_empty(), "{:?}: {:?}", shape, issues);
+            }
+        }
+    }
+
+    #[test]
+    fn chained_files_read_one_after_another() {
+        let shape = Shape {
+            records: 50,
+            chained_files: 3,
+            ..Shape::default()
+        };
+        let bytes = generate(&shape, 1);
+        let mut cursor = Cursor::new(&bytes);
+        for _ in 0..3 {
+            let mut reader = FitReader::new(&mut cursor).unwrap();
+            assert_eq!(reader.read_messages().unwrap().len(), 50 + 6);
+        }
+        assert_eq!(cursor.position(), bytes.len() as u64);
+    }
+
+    #[test]
+    fn compressed_timestamps_are_well_framed() {
+        let shape = Shape {
+            records: 100,
+            compressed_timestamps: true,
+            ..Shape::default()
+        };
+        let plain = generate(
+            &Shape {
+                compressed_timestamps: false,
+                ..shape.clone()
+            },
+            3,
+        );
+        let bytes = generate(&shape, 3);
+        // Four bytes saved on all but the first record, and
+        // one more definition
+        assert_eq!(bytes.len(), plain.len() - 99 * 4 + 6 + 3 * 8);
+
+        let data_size =
+            u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        assert_eq!(bytes.len(), 14 + data_size as usize + 2);
+        let mut crc = CrcReader::new(&bytes[..]);
+        io::copy(&mut crc, &mut io::sink()).unwrap();
+        assert_eq!(crc.crc(), 0);
+    }
+}
@@ -0,0 +1,324 @@
+//! Helpers for building synthetic messages in unit tests.
+use byteorder::{
+    ByteOrder,
+    LittleEndian,
+};
+use crc::CrcWriter;
+use profile::messages::Message;
+use std::{
+    alloc::{
+        GlobalAlloc,
+        Layout,
+        System,
+    },
+    cell::Cell,
+    io::{
+        self,
+        Write,
+    },
+};
+use types::record::Data;
+
+/// Builds a single data message field by field, decoding
+/// every field through the real profile path so scales,
+/// offsets and units match what the decoder would produce.
+pub(crate) struct MesgBuilder {
+    mesg_num: u16,
+    fields:   Vec<Message>,
+}
+
+/// Start building a data message with the given global
+/// message number.
+pub(crate) fn mesg(mesg_num: u16) -> MesgBuilder {
+    MesgBuilder {
+        mesg_num,
+        fields: Vec::new(),
+    }
+}
+
+impl MesgBuilder {
+    pub(crate) fn bytes(mut self, field_def_num: u8, buffer: &[u8]) -> Self {
+        let field = Message::decode::<LittleEndian>(
+            buffer,
+            self.mesg_num,
+            field_def_num,
+        )
+        .expect("test field should decode");
+        self.fields.push(field);
+        self
+    }
+
+    pub(crate) fn u8(self, field_def_num: u8, value: u8) -> Self {
+        self.bytes(field_def_num, &[value])
+    }
+
+    pub(crate) fn i8(self, field_def_num: u8, value: i8) -> Self {
+        self.bytes(field_def_num, &[value as u8])
+    }
+
+    pub(crate) fn u16(self, field_def_num: u8, value: u16) -> Self {
+        let mut buffer = [0; 2];
+        LittleEndian::write_u16(&mut buffer, value);
+        self.bytes(field_def_num, &buffer)
+    }
+
+    pub(crate) fn i16(self, field_def_num: u8, value: i16) -> Self {
+        let mut buffer = [0; 2];
+        LittleEndian::write_i16(&mut buffer, value);
+        self.bytes(field_def_num, &buffer)
+    }
+
+    pub(crate) fn u32(self, field_def_num: u8, value: u32) -> Self {
+        let mut buffer = [0; 4];
+        LittleEndian::write_u32(&mut buffer, value);
+        self.bytes(field_def_num, &buffer)
+    }
+
+    pub(crate) fn i32(self, field_def_num: u8, value: i32) -> Self {
+        let mut buffer = [0; 4];
+        LittleEndian::write_i32(&mut buffer, value);
+        self.bytes(field_def_num, &buffer)
+    }
+
+    pub(crate) fn build(self) -> Data {
+        Data(self.fields)
+    }
+}
+
+/// Builds a complete FIT byte stream, header and CRCs
+/// included, from raw definition and data records.
+pub(crate) struct FitBuilder {
+    data: Vec<u8>,
+}
+
+impl FitBuilder {
+    pub(crate) fn new() -> Self {
+        FitBuilder {
+            data: Vec::new()
+        }
+    }
+
+    /// Append a little endian definition message for
+    /// `local_mesg_num` with `(field_def_num, size,
+    /// base_type)` triples.
+    pub(crate) fn definition(
+        &mut self,
+        local_mesg_num: u8,
+        mesg_num: u16,
+        fields: &[(u8, u8, u8)],
+    ) -> &mut Self {
+        self.data.push(0x40 | local_mesg_num);
+        self.data.extend_from_slice(&[0, 0]);
+        self.data.extend_from_slice(&mesg_num.to_le_bytes());
+        self.fields(fields)
+    }
+
+    /// Like [`definition`], but big endian, so its data
+    /// messages must be too.
+    ///
+    /// [`definition`]: #method.definition
+    pub(crate) fn big_endian_definition(
+        &mut self,
+        local_mesg_num: u8,
+        mesg_num: u16,
+        fields: &[(u8, u8, u8)],
+    ) -> &mut Self {
+        self.data.push(0x40 | local_mesg_num);
+        self.data.extend_from_slice(&[0, 1]);
+        self.data.extend_from_slice(&mesg_num.to_be_bytes());
+        self.fields(fields)
+    }
+
+    fn fields(&mut self, fields: &[(u8, u8, u8)]) -> &mut Self {
+        self.data.push(fields.len() as u8);
+        for &(num, size, base_type) in fields {
+            self.data.extend_from_slice(&[num, size, base_type]);
+        }
+        self
+    }
+
+    /// Append a data message for `local_mesg_num` whose
+    /// field bytes are the concatenation of `fields`.
+    pub(crate) fn data(
+        &mut self,
+        local_mesg_num: u8,
+        fields: &[&[u8]],
+    ) -> &mut Self {
+        self.data.push(local_mesg_num);
+        for field in fields {
+            self.data.extend_from_slice(field);
+        }
+        self
+    }
+
+    /// Append raw bytes to the data section.
+    pub(crate) fn raw(&mut self, bytes: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(bytes);
+        self
+    }
+
+    /// The finished file with a 14 byte header.
+    pub(crate) fn build(&self) -> Vec<u8> {
+        self.build_with_header(14, true)
+    }
+
+    /// The finished file with a `size` byte header. A 14
+    /// byte header ends in its CRC, or zero unless
+    /// `header_crc`; other headers are padded with zeros.
+    pub(crate) fn build_with_header(
+        &self,
+        size: u8,
+        header_crc: bool,
+    ) -> Vec<u8> {
+        let mut bytes = vec![size, 0x20];
+        bytes.extend_from_slice(&2066u16.to_le_bytes());
+        bytes.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b".FIT");
+        if size == 14 {
+            let header_crc = if header_crc { crc(&bytes) } else { 0 };
+            bytes.extend_from_slice(&header_crc.to_le_bytes());
+        }
+        else {
+            bytes.resize(usize::from(size), 0);
+        }
+        let header_crc = crc(&bytes);
+        let mut file = CrcWriter::with_crc(bytes, header_crc);
+        // Writing to a Vec can't fail
+        let _ = file.write_all(&self.data);
+        let file_crc = file.crc();
+        let mut bytes = file.into_inner();
+        bytes.extend_from_slice(&file_crc.to_le_bytes());
+        bytes
+    }
+}
+
+/// The FIT checksum of `bytes`.
+pub(crate) fn crc(bytes: &[u8]) -> u16 {
+    let mut w = CrcWriter::new(io::sink());
+    // Writing to a sink can't fail
+    let _ = w.write_all(bytes);
+    w.crc()
+}
+
+/// `bytes` with the header's data size zeroed, as some
+/// writers leave it, and both CRCs to match.
+pub(crate) fn without_data_size(bytes: &[u8]) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    bytes[4..8].copy_from_slice(&[0; 4]);
+    let header_crc = crc(&bytes[..12]);
+    bytes[12..14].copy_from_slice(&header_crc.to_le_bytes());
+    let end = bytes.len() - 2;
+    let file_crc = crc(&bytes[..end]);
+    bytes[end..].copy_from_slice(&file_crc.to_le_bytes());
+    bytes
+}
+
+/// A 14 byte header FIT file with a `FileId` message and
+/// `n` `Record` messages holding a timestamp, position,
+/// heart rate, distance and power.
+pub(crate) fn record_file(n: u32) -> Vec<u8> {
+    let mut fit = FitBuilder::new();
+    fit.definition(0, 0, &[(0, 1, 0x00), (1, 2, 0x84), (4, 4, 0x86)]);
+    fit.data(0, &[&[4], &1u16.to_le_bytes(), &1_000_000u32.to_le_bytes()]);
+
+    fit.definition(
+        1,
+        20,
+        &[
+            (253, 4, 0x86),
+            (0, 4, 0x85),
+            (1, 4, 0x85),
+            (3, 1, 0x02),
+            (5, 4, 0x86),
+            (7, 2, 0x84),
+        ],
+    );
+    for i in 0..n {
+        fit.data(
+            1,
+            &[
+                &(1_000_000 + i).to_le_bytes(),
+                &(600_000_000 + i as i32 * 100).to_le_bytes(),
+                &(-1_000_000 + i as i32 * 50).to_le_bytes(),
+                &[(100 + i % 80) as u8],
+                &(i * 500).to_le_bytes(),
+                &(150 + (i % 200) as u16).to_le_bytes(),
+            ],
+        );
+    }
+    fit.build()
+}
+
+/// A file whose `Record` definition lists the timestamp and
+/// heart rate twice each, with different sizes, followed by
+/// speed and power, as a buggy encoder wrote it. The last
+/// occurrences hold a timestamp of 1000, a heart rate of
+/// 150, 5.25 m/s and 250 W; the two byte timestamp would be
+/// too short to decode.
+pub(crate) fn duplicate_field_file() -> Vec<u8> {
+    let mut fit = FitBuilder::new();
+    fit.definition(
+        0,
+        20,
+        &[
+            (253, 2, 0x84),
+            (3, 1, 0x02),
+            (253, 4, 0x86),
+            (3, 2, 0x84),
+            (6, 2, 0x84),
+            (7, 2, 0x84),
+        ],
+    );
+    fit.data(
+        0,
+        &[
+            &[0xAA, 0xBB],
+            &[100],
+            &1000u32.to_le_bytes(),
+            &[150, 0],
+            &5250u16.to_le_bytes(),
+            &250u16.to_le_bytes(),
+        ],
+    );
+    fit.build()
+}
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The system allocator, counting allocations per thread so
+/// tests can check a code path doesn't allocate.
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning its result and the number of heap
+/// allocations it made on this thread.
+pub(crate) fn count_allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
@@ -0,0 +1,310 @@
+//! Converting FIT date times into calendar times.
+//!
+//! FIT has two kinds of date time, both counting seconds
+//! from the FIT epoch (00:00 Dec 31 1989):
+//!
+//! * [`DateTime`] counts from the epoch in UTC. Most
+//!   timestamps are these.
+//! * [`LocalDateTime`] counts from the epoch in the
+//!   device's time zone, so it already has the UTC offset
+//!   applied.
+//!
+//! Treating a `LocalDateTime` as a `DateTime` and then
+//! converting it to local time applies the offset twice,
+//! which is how activities end up showing a start time
+//! shifted by the user's time zone. The two are distinct
+//! types with no conversion between them, and each has its
+//! own conversions here: a `DateTime` becomes a UTC time, a
+//! `LocalDateTime` only ever becomes a naive local time.
+//!
+//! [`DateTime`]: ../profile/types/struct.DateTime.html
+//! [`LocalDateTime`]: ../profile/types/struct.LocalDateTime.html
+use chrono::{
+    self,
+    FixedOffset,
+    NaiveDateTime,
+    TimeZone,
+    Utc,
+};
+use profile::{
+    messages::{
+        Activity,
+        DeviceSettings,
+        Message,
+        TimestampCorrelation,
+    },
+    types::{
+        DateTime,
+        LocalDateTime,
+    },
+};
+use std::convert::TryFrom;
+use types::{
+    field::Value,
+    record::Data,
+};
+
+/// The FIT epoch, 00:00 Dec 31 1989 UTC, as a Unix
+/// timestamp.
+pub const FIT_EPOCH: i64 = 631_065_600;
+
+/// Date times below this are seconds since the device was
+/// powered on rather than since the FIT epoch.
+pub const MIN_DATE_TIME: u32 = 0x1000_0000;
+
+/// Seconds since the FIT epoch, or `None` for the invalid
+/// value and system times.
+fn since_epoch(raw: u32) -> Option<i64> {
+    if raw < MIN_DATE_TIME || raw == u32::MAX {
+        None
+    }
+    else {
+        Some(FIT_EPOCH + i64::from(raw))
+    }
+}
+
+impl DateTime {
+    /// Whether this counts seconds since the device was
+    /// powered on rather than since the FIT epoch.
+    pub fn is_system_time(&self) -> bool {
+        self.0 < MIN_DATE_TIME
+    }
+
+    /// The time in UTC, or `None` for system times and the
+    /// invalid value.
+    pub fn to_utc(&self) -> Option<chrono::DateTime<Utc>> {
+        Utc.timestamp_opt(since_epoch(self.0)?, 0).single()
+    }
+
+    /// The time in the time zone `offset` from UTC.
+    pub fn to_local(
+        &self,
+        offset: FixedOffset,
+    ) -> Option<chrono::DateTime<FixedOffset>> {
+        Some(self.to_utc()?.with_timezone(&offset))
+    }
+}
+
+impl LocalDateTime {
+    /// Whether this counts seconds since the device was
+    /// powered on rather than since the FIT epoch.
+    pub fn is_system_time(&self) -> bool {
+        self.0 < MIN_DATE_TIME
+    }
+
+    /// The wall clock time on the device, or `None` for
+    /// system times and the invalid value.
+    ///
+    /// There's no time zone to attach: pair it with the
+    /// matching UTC time through [`utc_offset`] for that.
+    ///
+    /// [`utc_offset`]: fn.utc_offset.html
+    pub fn to_naive_local(&self) -> Option<NaiveDateTime> {
+        Some(Utc.timestamp_opt(since_epoch(self.0)?, 0).single()?.naive_utc())
+    }
+}
+
+/// The device's offset from UTC, given the same instant as
+/// both a UTC and a local date time, rounded to the nearest
+/// quarter hour.
+pub fn utc_offset(
+    utc: &DateTime,
+    local: &LocalDateTime,
+) -> Option<FixedOffset> {
+    since_epoch(utc.0)?;
+    since_epoch(local.0)?;
+
+    let seconds = i64::from(local.0) - i64::from(utc.0);
+    let quarters = (seconds as f64 / 900.0).round() as i32;
+    FixedOffset::east_opt(quarters * 900)
+}
+
+/// The device's offset from UTC when the activity was
+/// recorded, from the `Activity` message, falling back to
+/// `TimestampCorrelation`.
+pub fn local_offset(messages: &[Data]) -> Option<FixedOffset> {
+    messages.iter().find_map(|mesg| {
+        let mut utc = None;
+        let mut local = None;
+        for field in mesg.0.iter() {
+            match field {
+                Message::Activity(Activity::Timestamp(f))
+                | Message::TimestampCorrelation(
+                    TimestampCorrelation::Timestamp(f),
+                ) => utc = Some(f.raw_value),
+                Message::Activity(Activity::LocalTimestamp(f))
+                | Message::TimestampCorrelation(
+                    TimestampCorrelation::LocalTimestamp(f),
+                ) => local = Some(f.raw_value),
+                _ => (),
+            }
+        }
+        utc_offset(&utc?, &local?)
+    })
+}
+
+/// Seconds in one step of `DeviceSettings::TimeZoneOffset`.
+const QUARTER_HOUR_S: i64 = 15 * 60;
+
+/// The Unix timestamp of the wall clock time `utc_offset_s`
+/// seconds from UTC at the FIT `timestamp`.
+///
+/// This is plain arithmetic: system times and the invalid
+/// value aren't singled out.
+pub fn fit_timestamp_to_local_unix(timestamp: u32, utc_offset_s: i64) -> i64 {
+    FIT_EPOCH + i64::from(timestamp) + utc_offset_s
+}
+
+/// The device's configured offset from UTC in seconds,
+/// from the first `DeviceSettings` message that has one.
+///
+/// `TimeZoneOffset`, in quarter hours, is preferred when
+/// valid; otherwise `UtcOffset` is read as a signed number
+/// of seconds. Either way this is the offset the device was
+/// set to when the file was written, so it doesn't follow
+/// daylight saving changes during the activity.
+pub fn extract_utc_offset(messages: &[Data]) -> Option<i64> {
+    messages.iter().find_map(|mesg| {
+        let mut seconds = None;
+        let mut quarters = None;
+        for field in mesg.0.iter() {
+            match field {
+                Message::DeviceSettings(DeviceSettings::UtcOffset(f))
+                    if f.is_valid() =>
+                {
+                    seconds = Some(i64::from(f.raw_value.0 as i32))
+                },
+                Message::DeviceSettings(DeviceSettings::TimeZoneOffset(f))
+                    if f.is_valid() =>
+                {
+                    quarters = Some(i64::from(f.raw_value.0))
+                },
+                _ => (),
+            }
+        }
+        quarters.map(|quarters| quarters * QUARTER_HOUR_S).or(seconds)
+    })
+}
+
+/// The FIT `timestamp` as a local time `utc_offset_s`
+/// seconds from UTC, like `2023-07-15 09:32:00+02:00`.
+///
+/// Offsets of a day or more can't be represented and are
+/// ignored, leaving the time in UTC.
+pub fn format_local_time(timestamp: u32, utc_offset_s: i64) -> String {
+    let offset = i32::try_from(utc_offset_s)
+        .ok()
+        .and_then(FixedOffset::east_opt)
+        .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    let unix = FIT_EPOCH + i64::from(timestamp);
+    match offset.timestamp_opt(unix, 0).single() {
+        Some(local) => local.format("%Y-%m-%d %H:%M:%S%:z").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+/// The `timestamp` field of any message.
+pub(crate) fn message_timestamp(mesg: &Data) -> Option<u32> {
+    mesg.0.iter().find_map(|field| {
+        match (field.field_def_num(), field.value()) {
+            (253, Value::DateTime(timestamp)) => Some(timestamp.0),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Timelike;
+    use testutil::mesg;
+
+    // 2018-07-01 12:00:00 UTC
+    const NOON_UTC: u32 = 899_380_800;
+
+    #[test]
+    fn epoch() {
+        let utc = DateTime(NOON_UTC).to_utc().unwrap();
+        assert_eq!(utc.to_rfc3339(), "2018-07-01T12:00:00+00:00");
+        assert!(DateTime(1234).to_utc().is_none());
+        assert!(DateTime(u32::MAX).to_utc().is_none());
+        assert!(LocalDateTime(1234).is_system_time());
+    }
+
+    #[test]
+    fn start_time_is_not_shifted_twice() {
+        // Recorded in New York, five hours behind UTC
+        let local = NOON_UTC - 5 * 3600;
+        let messages = vec![mesg(34).u32(253, NOON_UTC).u32(5, local).build()];
+
+        let offset = local_offset(&messages).unwrap();
+        assert_eq!(offset.local_minus_utc(), -5 * 3600);
+
+        let start = DateTime(NOON_UTC).to_local(offset).unwrap();
+        assert_eq!(start.to_rfc3339(), "2018-07-01T07:00:00-05:00");
+        assert_eq!(
+            LocalDateTime(local).to_naive_local(),
+            Some(start.naive_local())
+        );
+
+        // The bug: reading the local timestamp as UTC and
+        // converting it to local time again
+        let shifted = DateTime(local).to_local(offset).unwrap();
+        assert_eq!(shifted.hour(), 2);
+    }
+
+    #[test]
+    fn offset_from_timestamp_correlation() {
+        // Kathmandu, UTC+05:45, with a second of skew
+        let messages = vec![mesg(162)
+            .u32(253, NOON_UTC)
+            .u32(3, NOON_UTC + 5 * 3600 + 45 * 60 + 1)
+            .build()];
+        let offset = local_offset(&messages).unwrap();
+        assert_eq!(offset.local_minus_utc(), 5 * 3600 + 45 * 60);
+        assert!(local_offset(&[mesg(34).u32(253, NOON_UTC).build()]).is_none());
+    }
+
+    #[test]
+    fn offset_from_device_settings() {
+        assert_eq!(fit_timestamp_to_local_unix(0, 0), FIT_EPOCH);
+        assert_eq!(fit_timestamp_to_local_unix(0, -3600), FIT_EPOCH - 3600);
+
+        // 2023-07-15 07:32:00 UTC, in Berlin summer time
+        let summer = 1_058_340_720;
+        let messages = vec![mesg(2).u32(1, 7200).build()];
+        let offset = extract_utc_offset(&messages).unwrap();
+        assert_eq!(offset, 7200);
+        assert_eq!(
+            fit_timestamp_to_local_unix(summer, offset),
+            1_689_406_320 + 7200
+        );
+        assert_eq!(
+            format_local_time(summer, offset),
+            "2023-07-15 09:32:00+02:00"
+        );
+
+        // The same device in winter, with the quarter hour
+        // offset taking precedence
+        let winter = summer - 182 * 86_400;
+        let messages = vec![
+            mesg(0).u8(0, 4).build(),
+            mesg(2).u32(1, 7200).i8(5, 4).build(),
+        ];
+        let offset = extract_utc_offset(&messages).unwrap();
+        assert_eq!(offset, 3600);
+        assert_eq!(
+            format_local_time(winter, offset),
+            "2023-01-14 08:32:00+01:00"
+        );
+
+        // West of UTC, stored as two's complement
+        let messages = vec![mesg(2).u32(1, (-4 * 3600i32) as u32).build()];
+        assert_eq!(extract_utc_offset(&messages), Some(-4 * 3600));
+        assert!(extract_utc_offset(&[mesg(2).i8(5, 0x7F).build()]).is_none());
+        assert_eq!(
+            format_local_time(summer, 2 * 86_400),
+            "2023-07-15 07:32:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Lifetime statistics, from the `Totals` messages devices
+//! keep one of per sport.
+use profile::{
+    messages::{
+        Message,
+        Totals,
+    },
+    types::Sport,
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// A single `Totals` message.
+///
+/// Counters missing from the message are zero.
+#[derive(Debug, Clone)]
+pub struct SportTotals {
+    pub sport:                Sport,
+    /// Including pauses.
+    pub total_elapsed_time_s: f64,
+    pub total_distance_m:     f64,
+    pub total_sessions:       u16,
+    /// Kilocalories.
+    pub total_calories:       u32,
+    /// Always `None`: the FIT profile has no ascent field
+    /// in `Totals`.
+    pub total_ascent_m:       Option<u32>,
+}
+
+impl SportTotals {
+    /// Flatten a decoded `Totals` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Totals` message or
+    /// doesn't say which sport it totals.
+    pub fn from_message(mesg: &Data) -> Option<SportTotals> {
+        let mut sport = None;
+        let mut totals = SportTotals {
+            sport:                Sport::Unknown,
+            total_elapsed_time_s: 0.0,
+            total_distance_m:     0.0,
+            total_sessions:       0,
+            total_calories:       0,
+            total_ascent_m:       None,
+        };
+
+        for field in mesg.0.iter() {
+            let field = match field {
+                Message::Totals(field) => field,
+                _ => return None,
+            };
+
+            match field {
+                Totals::Sport(f) => sport = Some(f.raw_value.clone()),
+                Totals::ElapsedTime(f) if f.is_valid() => {
+                    totals.total_elapsed_time_s = f.value()
+                },
+                Totals::Distance(f) if f.is_valid() => {
+                    totals.total_distance_m = f.value()
+                },
+                Totals::Sessions(f) if f.is_valid() => {
+                    totals.total_sessions = f.raw_value.0
+                },
+                Totals::Calories(f) if f.is_valid() => {
+                    totals.total_calories = f.raw_value.0
+                },
+                _ => (),
+            }
+        }
+
+        Some(SportTotals {
+            sport: sport?,
+            ..totals
+        })
+    }
+}
+
+/// Every `Totals` message naming its sport, in file order.
+pub fn extract_sport_totals(messages: &[Data]) -> Vec<SportTotals> {
+    messages.iter().filter_map(SportTotals::from_message).collect()
+}
+
+/// The distance covered in every sport, in metres.
+pub fn lifetime_distance_m(totals: &[SportTotals]) -> f64 {
+    totals.iter().map(|totals| totals.total_distance_m).sum()
+}
+
+/// The sport with the most sessions, the one with the
+/// greater elapsed time among those tied. `None` without
+/// any totals.
+pub fn most_practiced_sport(totals: &[SportTotals]) -> Option<Sport> {
+    let mut most: Option<&SportTotals> = None;
+    for totals in totals {
+        let more = match most {
+            Some(most) => {
+                (totals.total_sessions, totals.total_elapsed_time_s)
+                    > (most.total_sessions, most.total_elapsed_time_s)
+            },
+            None => true,
+        };
+        if more {
+            most = Some(totals);
+        }
+    }
+    most.map(|most| most.sport.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn totals(sport: u8, sessions: u16, elapsed_time_s: u32) -> Data {
+        mesg(33)
+            .u8(3, sport)
+            .u16(5, sessions)
+            .u32(4, elapsed_time_s)
+            .u32(1, 10_000)
+            .build()
+    }
+
+    #[test]
+    fn lifetime_totals() {
+        let messages = vec![
+            mesg(0).u8(0, 10).build(),
+            totals(1, 120, 400_000),
+            totals(2, 120, 500_000),
+            totals(5, 30, 60_000),
+            // No sport
+            mesg(33).u16(5, 500).build(),
+        ];
+
+        let totals = extract_sport_totals(&messages);
+        assert_eq!(totals.len(), 3);
+        assert!(matches!(totals[0].sport, Sport::Running));
+        assert_eq!(totals[0].total_sessions, 120);
+        assert_eq!(totals[0].total_elapsed_time_s, 400_000.0);
+        assert_eq!(totals[0].total_calories, 0);
+        assert_eq!(totals[0].total_ascent_m, None);
+        assert_eq!(lifetime_distance_m(&totals), 30_000.0);
+
+        // Tied on sessions, cycling took longer
+        let most = most_practiced_sport(&totals);
+        assert!(matches!(most, Some(Sport::Cycling)));
+        let most = most_practiced_sport(&totals[2..]);
+        assert!(matches!(most, Some(Sport::Swimming)));
+        assert!(most_practiced_sport(&[]).is_none());
+    }
+}
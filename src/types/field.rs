@@ -1,5 +1,316 @@
+use profile::types::{
+    DateTime,
+    LocalDateTime,
+};
+
 /// Trait for getting the value from a message field.
 pub trait Field {
     type Value;
     fn value(&self) -> Self::Value;
 }
+
+/// The value of any field, for code that treats every field
+/// alike, such as exporters.
+///
+/// Returned by [`Message::value`].
+///
+/// [`Message::value`]: ../../profile/messages/enum.Message.html#method.value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// Scaled into the field's units.
+    Number(f64),
+    /// An array, each element scaled.
+    Numbers(Vec<f64>),
+    Bool(bool),
+    Text(String),
+    Texts(Vec<String>),
+    /// Byte fields, and fields the profile doesn't know.
+    Bytes(Vec<u8>),
+    DateTime(DateTime),
+    LocalDateTime(LocalDateTime),
+    /// The name of a value of one of the profile's enum
+    /// types, `Unknown` for values it doesn't name.
+    Enum(String),
+    /// The field held its base type's invalid value.
+    Invalid,
+}
+
+/// How many decimal places to keep when presenting scaled
+/// field values.
+///
+/// Scaling integers by floating point divisors produces
+/// values like `3.5200000000000002`, so the JSON export
+/// ([`schema::to_json_with`]), [`Field::rounded_value`],
+/// the `Display` of scaled fields and the [`inspect`] tree
+/// round through this instead. The Arrow and Python exports
+/// hand over numbers rather than text, and keep the full
+/// `f64`.
+///
+/// [`schema::to_json_with`]: ../../schema/fn.to_json_with.html
+/// [`Field::rounded_value`]: ../../profile/messages/struct.Field.html#method.rounded_value
+/// [`inspect`]: ../../inspect/index.html
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// Keep the full `f64`.
+    #[default]
+    Full,
+    /// As many decimals as the field's scale can produce,
+    /// e.g. three for a scale of 1000. Fields whose scale
+    /// doesn't map onto a number of decimals are left at
+    /// full precision.
+    SpecImplied,
+    /// Always this many decimals.
+    Fixed(u8),
+}
+
+impl Precision {
+    /// The number of decimals to keep for a field with the
+    /// given scale, or `None` for full precision.
+    pub fn decimals(&self, scale: Option<f64>) -> Option<u8> {
+        match *self {
+            Precision::Full => None,
+            Precision::SpecImplied => decimals_for_scale(scale),
+            Precision::Fixed(decimals) => Some(decimals),
+        }
+    }
+
+    /// Round `value`, from a field with the given scale.
+    pub fn round(&self, value: f64, scale: Option<f64>) -> f64 {
+        match self.decimals(scale) {
+            Some(decimals) => {
+                let factor = 10f64.powi(i32::from(decimals));
+                (value * factor).round() / factor
+            },
+            None => value,
+        }
+    }
+
+    /// Format `value`, from a field with the given scale.
+    /// Trailing zeros are kept so columns line up.
+    pub fn format(&self, value: f64, scale: Option<f64>) -> String {
+        match self.decimals(scale) {
+            Some(decimals) => format!("{:.*}", decimals as usize, value),
+            None => format!("{}", value),
+        }
+    }
+}
+
+/// `raw` in a field's units: divided by `scale` less
+/// `offset`, as the profile and `FieldDescription` messages
+/// say to scale it.
+///
+/// A scale of zero, as a buggy Connect IQ app has written
+/// into a `FieldDescription`, or one that isn't finite
+/// would make the value infinite or NaN, so it's taken as
+/// no scale and `raw` is left as it is. An offset that isn't
+/// finite is likewise left out.
+///
+/// ```
+/// use garminfit::types::field::scale_value;
+///
+/// assert_eq!(scale_value(3520.0, Some(1000.0), None), 3.52);
+/// assert_eq!(scale_value(3520.0, Some(0.0), None), 3520.0);
+/// assert_eq!(scale_value(60.0, Some(f64::NAN), Some(f64::INFINITY)), 60.0);
+/// ```
+pub fn scale_value(raw: f64, scale: Option<f64>, offset: Option<f64>) -> f64 {
+    raw / usable_scale(scale).unwrap_or(1.0)
+        - offset.filter(|offset| offset.is_finite()).unwrap_or(0.0)
+}
+
+/// `scale`, unless it's zero or isn't finite, which
+/// [`scale_value`] leaves out.
+///
+/// [`scale_value`]: fn.scale_value.html
+pub fn usable_scale(scale: Option<f64>) -> Option<f64> {
+    scale.filter(|scale| scale.is_finite() && *scale != 0.0)
+}
+
+/// Decimals needed to represent every value of a field with
+/// this scale exactly: 0 for unscaled fields, 1 for a scale
+/// of 5 or 10, 2 for 4 or 100 and so on.
+///
+/// Non-integer scales (there are a few odd ones like
+/// `0.7111111`) have no sensible answer and give `None`.
+pub fn decimals_for_scale(scale: Option<f64>) -> Option<u8> {
+    // Beyond this we may as well keep the whole f64
+    const MAX_DECIMALS: u8 = 9;
+
+    let scale = match usable_scale(scale) {
+        None => return Some(0),
+        Some(scale) => scale,
+    };
+    if scale < 1.0 || scale.fract() != 0.0 {
+        return None
+    }
+
+    let scale = scale as u64;
+    let mut power = 1u64;
+    for decimals in 0..=MAX_DECIMALS {
+        if power % scale == 0 {
+            return Some(decimals)
+        }
+        power *= 10;
+    }
+
+    // Scales like 1024 never divide a power of ten, so fall
+    // back to the decimals of their resolution
+    Some((scale as f64).log10().ceil().min(f64::from(MAX_DECIMALS)) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_from_scale() {
+        assert_eq!(decimals_for_scale(None), Some(0));
+        assert_eq!(decimals_for_scale(Some(1.0)), Some(0));
+        assert_eq!(decimals_for_scale(Some(10.0)), Some(1));
+        assert_eq!(decimals_for_scale(Some(100.0)), Some(2));
+        assert_eq!(decimals_for_scale(Some(1000.0)), Some(3));
+        assert_eq!(decimals_for_scale(Some(5.0)), Some(1));
+        assert_eq!(decimals_for_scale(Some(4.0)), Some(2));
+        assert_eq!(decimals_for_scale(Some(1024.0)), Some(4));
+        assert_eq!(decimals_for_scale(Some(0.7111111)), None);
+        // Unusable scales leave the value unscaled
+        assert_eq!(decimals_for_scale(Some(0.0)), Some(0));
+        assert_eq!(decimals_for_scale(Some(f64::INFINITY)), Some(0));
+    }
+
+    #[test]
+    fn rounding_policies() {
+        let speed = 3520.0 / 1000.0 + 2e-16;
+        let scale = Some(1000.0);
+
+        assert_eq!(Precision::Full.round(speed, scale), speed);
+        assert_eq!(Precision::SpecImplied.round(speed, scale), 3.52);
+        assert_eq!(Precision::SpecImplied.format(speed, scale), "3.520");
+        assert_eq!(Precision::Fixed(1).format(speed, scale), "3.5");
+        assert_eq!(Precision::SpecImplied.format(12.34, Some(100.0)), "12.34");
+        assert_eq!(Precision::SpecImplied.format(1.5, Some(10.0)), "1.5");
+
+        let odd = 100.0 / 0.7111111;
+        assert_eq!(Precision::SpecImplied.round(odd, Some(0.7111111)), odd);
+        assert_eq!(Precision::Fixed(2).round(odd, Some(0.7111111)), 140.63);
+    }
+
+    #[test]
+    fn message_field_helpers() {
+        use profile::{
+            base::Uint16,
+            messages,
+        };
+
+        let speed = messages::Field {
+            raw_value: Uint16(3520),
+            scale:     Some(1000.0),
+            offset:    None,
+            units:     Some("m/s"),
+        };
+        assert_eq!(speed.decimals_hint(), Some(3));
+        assert_eq!(speed.rounded_value(Precision::SpecImplied), 3.52);
+        assert_eq!(speed.rounded_value(Precision::Fixed(0)), 4.0);
+        assert_eq!(speed.checked_value(), Some(3.52));
+    }
+
+    #[test]
+    fn unusable_scales() {
+        use profile::{
+            base::{
+                Float32,
+                Sint16,
+                Uint16,
+            },
+            messages,
+        };
+
+        // As if the profile gave a scale of zero
+        let zero = messages::Field {
+            raw_value: Uint16(3520),
+            scale:     Some(0.0),
+            offset:    None,
+            units:     Some("m/s"),
+        };
+        assert_eq!(zero.value(), 3520.0);
+        assert_eq!(zero.checked_value(), Some(3520.0));
+        assert_eq!(zero.rounded_value(Precision::SpecImplied), 3520.0);
+
+        let nan = messages::Field {
+            raw_value: Sint16(-40),
+            scale:     Some(f64::NAN),
+            offset:    Some(f64::NEG_INFINITY),
+            units:     None,
+        };
+        assert_eq!(nan.value(), -40.0);
+        let array = messages::Field {
+            raw_value: vec![Sint16(1), Sint16(-2)],
+            scale:     Some(f64::INFINITY),
+            offset:    Some(f64::NAN),
+            units:     None,
+        };
+        assert_eq!(array.value(), vec![1.0, -2.0]);
+
+        let invalid = messages::Field {
+            raw_value: Uint16(u16::MAX),
+            ..zero
+        };
+        assert_eq!(invalid.checked_value(), None);
+        // A finite raw float overflowing its scale
+        let huge = messages::Field {
+            raw_value: Float32(1e30),
+            scale:     Some(1e-300),
+            offset:    None,
+            units:     None,
+        };
+        assert!(huge.value().is_infinite());
+        assert_eq!(huge.checked_value(), None);
+    }
+
+    #[test]
+    fn exact_integer_conversions() {
+        use error::ErrorKind;
+        use profile::{
+            base::{
+                Sint64,
+                Uint16,
+                Uint64,
+            },
+            messages,
+        };
+        use std::convert::TryFrom;
+
+        fn field<T>(raw_value: T) -> messages::Field<T> {
+            messages::Field {
+                raw_value,
+                scale: None,
+                offset: None,
+                units: None,
+            }
+        }
+
+        // The first integer an f64 can't hold
+        let big = (1u64 << 53) + 1;
+        let energy = field(Uint64(big));
+        assert_eq!(energy.value(), (1u64 << 53) as f64);
+        assert_ne!(energy.value() as u64, big);
+        assert_eq!(energy.raw_u64(), Some(big));
+        assert_eq!(u64::try_from(&energy).unwrap(), big);
+        assert_eq!(energy.raw_value.get(), big);
+
+        let offset = field(Sint64(-(1i64 << 53) - 1));
+        assert_eq!(offset.raw_i64(), Some(-(1i64 << 53) - 1));
+        assert_eq!(field(Sint64(i64::MAX)).raw_i64(), None);
+
+        // The raw integer, unscaled
+        let speed = messages::Field {
+            scale: Some(1000.0),
+            ..field(Uint16(3520))
+        };
+        assert_eq!(u16::try_from(&speed).unwrap(), 3520);
+        assert_eq!(usize::from(u16::try_from(&speed).unwrap()), 3520);
+
+        let invalid = u64::try_from(&field(Uint64(u64::MAX))).unwrap_err();
+        assert!(matches!(invalid.kind(), ErrorKind::InvalidValue));
+        assert_eq!(field(Uint64(u64::MAX)).raw_u64(), None);
+    }
+}
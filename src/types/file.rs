@@ -2,24 +2,41 @@ use byteorder::{
     LittleEndian,
     ReadBytesExt,
 };
-use dyncrc16::{
-    CRC16,
-    CRC_SIZE,
+use consts::{
+    ProfileVersion,
+    ProtocolVersion,
+    DATA_TYPE,
+    HEADER_SIZE_MAX,
+    HEADER_SIZE_NO_CRC,
+    HEADER_SIZE_WITH_CRC,
 };
+use devices::DeviceInfo;
+use dyncrc16::CRC16;
 use error::{
     Error,
     Result,
 };
+use events;
+use laps::Lap;
+use profile::{
+    messages::{
+        FileId,
+        Message as Field,
+    },
+    types::File as FileType,
+};
+use reader::FitReader;
+use series::RecordPoint;
 use std::{
-    collections::HashMap,
     io::{
-        Seek,
-        SeekFrom,
+        Cursor,
+        Read,
     },
+    slice,
 };
 use types::record::{
-    self,
-    Definition,
+    Data,
+    Message,
     Record,
 };
 
@@ -29,52 +46,245 @@ pub struct File {
 }
 
 impl File {
-    pub fn decode<R: Seek + ReadBytesExt>(r: &mut R) -> Result<Self> {
-        let mut _crc = CRC16::new(); // TODO
+    /// Decode a whole FIT file, verifying its checksum.
+    ///
+    /// This is a convenience over [`FitReader`] that
+    /// collects every record.
+    ///
+    /// [`FitReader`]: ../../reader/struct.FitReader.html
+    pub fn decode<R: Read>(r: &mut R) -> Result<Self> {
+        let mut reader = FitReader::new(r)?;
+        let records = reader.by_ref().collect::<Result<Vec<_>>>()?;
 
-        let header =
-            Header::decode(r).map_err(Error::decoding("file header"))?;
+        Ok(File {
+            header: reader.header().clone(),
+            records,
+        })
+    }
 
-        // TODO: check crc here
+    /// Decode a whole FIT file held in memory.
+    ///
+    /// Never panics, whatever `bytes` contains.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        File::decode(&mut Cursor::new(bytes))
+    }
 
-        // Initialise loop variables
-        let mut records = Vec::new(); // what we want from the loop.
-        let mut local_mesgs: HashMap<u8, Definition> = HashMap::new();
-        let mut bytes_left = header.data_size as u64;
-        let mut count = 1;
+    /// The `Record` messages belonging to the `lap_index`th
+    /// `Lap` message, or `None` if there aren't that many
+    /// laps.
+    ///
+    /// A lap spans `[start_time, timestamp)`. Laps without
+    /// a start time begin where the previous one ended.
+    /// Laps shouldn't overlap, but in case they do a
+    /// lap is cut short where the next one starts, so
+    /// no record ends up in two laps.
+    ///
+    /// Unlike [`laps::assign`], which closes both ends and
+    /// gives a record on a boundary to the earlier lap,
+    /// this puts a record at a lap's `timestamp` in the
+    /// next lap.
+    ///
+    /// [`laps::assign`]: ../../laps/fn.assign.html
+    pub fn records_in_lap(
+        &self,
+        lap_index: usize,
+    ) -> Option<impl Iterator<Item = &Data>> {
+        let laps: Vec<Lap> =
+            self.messages().filter_map(Lap::from_message).collect();
+        let lap = laps.get(lap_index)?;
 
-        while bytes_left > 0 {
-            let position_before = current_position(r)?;
-            //*DEBUG*/dbg!(position_before);
+        let previous_end = match lap_index {
+            0 => 0,
+            i => laps[i - 1].timestamp,
+        };
+        let start = lap.start_time.unwrap_or(previous_end);
+        let end = match laps.get(lap_index + 1).and_then(|next| next.start_time)
+        {
+            Some(next_start) => lap.timestamp.min(next_start),
+            None => lap.timestamp,
+        };
+
+        Some(self.messages().filter(move |mesg| {
+            RecordPoint::from_message(mesg)
+                .map(|point| start <= point.timestamp && point.timestamp < end)
+                .unwrap_or(false)
+        }))
+    }
+
+    /// The `FileId` message identifying the file: the first
+    /// one if there are several. See [`file_id_warnings`].
+    ///
+    /// [`file_id_warnings`]: fn.file_id_warnings.html
+    pub fn file_id(&self) -> Option<&Data> {
+        first_file_id(self.messages())
+    }
+
+    /// The type of the file, from its [`file_id`].
+    ///
+    /// [`file_id`]: #method.file_id
+    pub fn file_type(&self) -> Option<FileType> {
+        self.file_id().and_then(file_type)
+    }
 
-            let record = Record::decode(r, &mut local_mesgs)
-                .map_err(Error::decoding(format!("record #{}", count)))?;
+    /// Anything odd about the file that decoding got past.
+    pub fn warnings(&self) -> Vec<FileWarning> {
+        file_id_warnings(self.messages())
+    }
 
-            //*DEBUG*/dbg!(record.clone());
+    /// The `DeviceInfo` messages with a valid device index.
+    pub fn contributing_devices(&self) -> Vec<DeviceInfo> {
+        self.messages()
+            .filter_map(DeviceInfo::from_message)
+            .filter(DeviceInfo::is_valid)
+            .collect()
+    }
 
-            // If we got a definition message we need
-            // to add it to the `local_mesgs` map
-            if let record::Message::Definition(ref mesg) = record.content {
-                // TODO: cloning here seems hacky...
-                local_mesgs
-                    .insert(record.header.local_mesg_num(), mesg.clone());
+    /// The data messages, in decode order.
+    ///
+    /// Definition and compressed timestamp records carry no
+    /// data of their own and are skipped; iterate over the
+    /// file itself for every record.
+    pub fn messages(&self) -> impl Iterator<Item = &Data> {
+        self.records.iter().filter_map(|record| {
+            match record.content {
+                Message::Data(ref data) => Some(data),
+                _ => None,
             }
+        })
+    }
 
-            // TODO: check crc after every record
-            records.push(record);
+    /// The `Event` messages, in decode order.
+    pub fn events(&self) -> impl Iterator<Item = &Data> {
+        self.messages().filter(|mesg| events::is_event(mesg))
+    }
 
-            let position_after = current_position(r)?;
-            bytes_left -= position_after - position_before;
-            count += 1;
-        }
+    /// The `Event` messages starting or stopping the timer.
+    pub fn timer_events(&self) -> impl Iterator<Item = &Data> {
+        self.messages().filter(|mesg| events::is_timer_event(mesg))
+    }
 
-        Ok(File {
-            header,
-            records,
+    /// The `(pause_start, pause_end)` of every pause, in
+    /// seconds since the FIT epoch. See
+    /// [`events::pause_intervals`].
+    ///
+    /// [`events::pause_intervals`]: ../../events/fn.pause_intervals.html
+    pub fn pause_intervals(&self) -> Vec<(u32, u32)> {
+        events::pause_intervals(self.timer_events())
+    }
+
+    /// The data messages, in decode order, for editing in
+    /// place.
+    pub fn messages_mut(&mut self) -> impl Iterator<Item = &mut Data> {
+        self.records.iter_mut().filter_map(|record| {
+            match record.content {
+                Message::Data(ref mut data) => Some(data),
+                _ => None,
+            }
         })
     }
 }
 
+impl<'a> IntoIterator for &'a File {
+    type IntoIter = slice::Iter<'a, Record>;
+    type Item = &'a Record;
+
+    /// Every record, definitions included, in decode order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
+    }
+}
+
+/// Something odd about a file that doesn't stop it being
+/// decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileWarning {
+    /// There are `count` `FileId` messages, as in merged or
+    /// hand edited files. Only the first is used.
+    MultipleFileIds {
+        count:             usize,
+        /// Whether they disagree on the file type, which
+        /// makes the file's type doubtful.
+        conflicting_types: bool,
+    },
+    /// The first `FileId` message comes after `preceding`
+    /// other data messages instead of first. It's used all
+    /// the same.
+    FileIdNotFirst { preceding: usize },
+}
+
+/// The first `FileId` message among `messages`.
+///
+/// Readers yield every `FileId` message a file has, but a
+/// file has exactly one identity: the first `FileId` wins,
+/// wherever it is, and the rest are ignored.
+pub fn first_file_id<'a, I>(messages: I) -> Option<&'a Data>
+where
+    I: IntoIterator<Item = &'a Data>,
+{
+    messages.into_iter().find(|mesg| is_file_id(mesg))
+}
+
+/// How `messages` stray from having a single `FileId`
+/// message at the start.
+pub fn file_id_warnings<'a, I>(messages: I) -> Vec<FileWarning>
+where
+    I: IntoIterator<Item = &'a Data>,
+{
+    let mut first = None;
+    let mut types = Vec::new();
+    for (i, mesg) in messages.into_iter().enumerate() {
+        if is_file_id(mesg) {
+            first = first.or(Some(i));
+            types.push(file_type(mesg).map(|file_type| file_type as u8));
+        }
+    }
+
+    let mut warnings = Vec::new();
+    if types.len() > 1 {
+        warnings.push(FileWarning::MultipleFileIds {
+            count:             types.len(),
+            conflicting_types: types.iter().any(|t| *t != types[0]),
+        });
+    }
+    match first {
+        Some(preceding) if preceding > 0 => {
+            warnings.push(FileWarning::FileIdNotFirst {
+                preceding,
+            })
+        },
+        _ => (),
+    }
+    warnings
+}
+
+fn is_file_id(mesg: &Data) -> bool {
+    matches!(mesg.0.first(), Some(Field::FileId(_)))
+}
+
+/// The type in a `FileId` message.
+fn file_type(mesg: &Data) -> Option<FileType> {
+    mesg.0.iter().find_map(|field| {
+        match field {
+            Field::FileId(FileId::Type(f)) => Some(f.raw_value.clone()),
+            _ => None,
+        }
+    })
+}
+
+/// How a file header was laid out, as told by its size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderLayout {
+    /// The original 12 byte header, without a CRC.
+    NoCrc,
+    /// The usual 14 byte header ending in a CRC.
+    WithCrc,
+    /// A header of some other size, which a few writers
+    /// produce. Only the first 12 bytes are understood; the
+    /// rest were skipped.
+    Nonstandard(u8),
+}
+
+#[derive(Debug, Clone)]
 pub struct Header {
     size:             u8,
     protocol_version: u8,
@@ -84,28 +294,26 @@ pub struct Header {
     crc:              Option<u16>,
 }
 
-const SIZE_NO_CRC: u8 = 12;
-const SIZE_HAS_CRC: u8 = SIZE_NO_CRC + CRC_SIZE;
-const MAX_SUPPORTED_PROTOCOL: u8 = 0x20; // v20.0.0
-
 impl Header {
+    /// Decode a header of any size from 12 to 100 bytes,
+    /// leaving `r` positioned right after it.
     pub(crate) fn decode<R: ReadBytesExt>(r: &mut R) -> Result<Self> {
         let size = r.read_u8().map_err(Error::reading("size"))?;
 
-        // Check that the header size is valid
-        match size {
-            SIZE_HAS_CRC | SIZE_NO_CRC => (),
-            _ => return Err(Error::unknown_file_header_size(size)),
+        // Anything outside these bounds isn't a FIT header
+        if !(HEADER_SIZE_NO_CRC..=HEADER_SIZE_MAX).contains(&size) {
+            return Err(Error::not_fit())
         }
 
         let protocol_version =
             r.read_u8().map_err(Error::reading("protocol version"))?;
 
         // Check that we support this protocol version
-        if protocol_version.major() > MAX_SUPPORTED_PROTOCOL.major() {
+        let version = ProtocolVersion::from(protocol_version);
+        if !version.supported() {
             return Err(Error::unsupported_protocol_version(
-                MAX_SUPPORTED_PROTOCOL.major(),
-                protocol_version.major(),
+                ProtocolVersion::MAX_SUPPORTED.major,
+                version.major,
             ))
         }
 
@@ -119,60 +327,354 @@ impl Header {
 
         // Check magic number
         let mut data_type = [0; 4];
-        r.read(&mut data_type).map_err(Error::reading("data type"))?;
-        if &data_type != b".FIT" {
+        r.read_exact(&mut data_type).map_err(Error::reading("data type"))?;
+        if &data_type != DATA_TYPE {
             return Err(Error::not_fit())
         }
 
-        // fcrc.write(&[size as u8])
-        //    .expect("dyncrc16 write implementation can't fail");
-        // fcrc.write(&buf[..size as usize])
-        //    .expect("dyncrc16 write implementation can't fail");
-
         let crc = match size {
-            SIZE_NO_CRC => None,
-            SIZE_HAS_CRC => {
+            HEADER_SIZE_WITH_CRC => {
                 Some(
                     r.read_u16::<LittleEndian>()
                         .map_err(Error::reading("crc"))?,
                 )
             },
-            _ => panic!("invalid size should have been caught earlier"),
+            _ => {
+                // Skip whatever a nonstandard header carries
+                let mut extra = vec![0; usize::from(size - HEADER_SIZE_NO_CRC)];
+                r.read_exact(&mut extra)
+                    .map_err(Error::reading("extra header bytes"))?;
+                None
+            },
         };
 
-        Ok(Header {
+        let header = Header {
             size,
             protocol_version,
             profile_version,
             data_size,
             data_type,
             crc,
-        })
+        };
+
+        // A zero header CRC means it wasn't computed
+        match header.crc {
+            Some(expected) if expected != 0 => {
+                let computed = header.computed_crc();
+                if expected != computed {
+                    return Err(Error::crc_mismatch(expected, computed))
+                }
+            },
+            _ => (),
+        }
+
+        Ok(header)
     }
-}
 
-/// Small utility trait for working with version bytes
-/// above.
-trait Version {
-    fn major(&self) -> u8;
-    fn minor(&self) -> u8;
-}
+    /// Size of the header in bytes.
+    pub fn size(&self) -> u8 {
+        self.size
+    }
 
-impl Version for u8 {
-    fn major(&self) -> u8 {
-        const MAJOR_SHIFT: u8 = 4;
-        const MAJOR_MASK: u8 = 0x0F << MAJOR_SHIFT;
+    /// Which layout the header had.
+    pub fn layout(&self) -> HeaderLayout {
+        match self.size {
+            HEADER_SIZE_NO_CRC => HeaderLayout::NoCrc,
+            HEADER_SIZE_WITH_CRC => HeaderLayout::WithCrc,
+            size => HeaderLayout::Nonstandard(size),
+        }
+    }
+
+    /// The protocol version the file was written with.
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        ProtocolVersion::from(self.protocol_version)
+    }
 
-        (self & MAJOR_MASK) >> MAJOR_SHIFT
+    /// The profile version the file was written with.
+    pub fn profile_version(&self) -> ProfileVersion {
+        ProfileVersion::from(self.profile_version)
     }
 
-    fn minor(&self) -> u8 {
-        const MINOR_MASK: u8 = 0x0f;
+    /// Size in bytes of the records following the header,
+    /// not counting the trailing CRC.
+    pub fn data_size(&self) -> u32 {
+        self.data_size
+    }
+
+    /// The header CRC, if the header has one.
+    pub fn crc(&self) -> Option<u16> {
+        self.crc
+    }
+
+    /// The header for the same file with `data_size` bytes
+    /// of records. A nonstandard size is written as the
+    /// usual 14 bytes, since only the first 12 were kept.
+    pub(crate) fn encode(&self, data_size: u32) -> Vec<u8> {
+        let header = Header {
+            size: match self.layout() {
+                HeaderLayout::NoCrc => HEADER_SIZE_NO_CRC,
+                _ => HEADER_SIZE_WITH_CRC,
+            },
+            data_size,
+            crc: None,
+            ..self.clone()
+        };
+        let mut bytes = vec![header.size, header.protocol_version];
+        bytes.extend_from_slice(&header.profile_version.to_le_bytes());
+        bytes.extend_from_slice(&data_size.to_le_bytes());
+        bytes.extend_from_slice(&header.data_type);
+        if header.size == HEADER_SIZE_WITH_CRC {
+            bytes.extend_from_slice(&header.computed_crc().to_le_bytes());
+        }
+        bytes
+    }
 
-        self & MINOR_MASK
+    /// CRC of the first 12 header bytes.
+    fn computed_crc(&self) -> u16 {
+        let mut crc = CRC16::new();
+        crc.update(&[self.size, self.protocol_version]);
+        crc.update(&self.profile_version.to_le_bytes());
+        crc.update(&self.data_size.to_le_bytes());
+        crc.update(&self.data_type);
+        crc.sum_16()
     }
 }
 
-fn current_position<R: Seek>(r: &mut R) -> Result<u64> {
-    r.seek(SeekFrom::Current(0)).map_err(Error::seek)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use error::ErrorKind;
+    use profile::messages;
+    use series::RecordTimeSeries;
+    use testutil::{
+        record_file,
+        FitBuilder,
+    };
+
+    /// 300 one second records split into three laps, with
+    /// the given `(start_time, timestamp)` lap bounds.
+    fn three_laps(laps: &[(u32, u32)]) -> File {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        fit.definition(1, 19, &[(253, 4, 0x86), (2, 4, 0x86)]);
+
+        let mut laps = laps.iter();
+        let mut next_lap = laps.next();
+        for i in 0..300u32 {
+            let ts = 1000 + i;
+            fit.data(0, &[&ts.to_le_bytes()[..], &[140]]);
+            if let Some(&(start, end)) = next_lap {
+                if end == ts + 1 {
+                    fit.data(
+                        1,
+                        &[&end.to_le_bytes()[..], &start.to_le_bytes()],
+                    );
+                    next_lap = laps.next();
+                }
+            }
+        }
+        File::from_bytes(&fit.build()).unwrap()
+    }
+
+    fn timestamps<'a>(records: impl Iterator<Item = &'a Data>) -> Vec<u32> {
+        records
+            .filter_map(RecordPoint::from_message)
+            .map(|point| point.timestamp)
+            .collect()
+    }
+
+    #[test]
+    fn records_in_first_and_last_lap() {
+        let file = three_laps(&[(1000, 1100), (1100, 1200), (1200, 1300)]);
+
+        let first = timestamps(file.records_in_lap(0).unwrap());
+        assert_eq!(first.len(), 100);
+        assert_eq!((first[0], first[99]), (1000, 1099));
+
+        let last = timestamps(file.records_in_lap(2).unwrap());
+        assert_eq!(last.len(), 100);
+        assert_eq!((last[0], last[99]), (1200, 1299));
+
+        assert!(file.records_in_lap(3).is_none());
+    }
+
+    #[test]
+    fn contributing_devices() {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 23, &[(0, 1, 0x02), (3, 4, 0x8C)]);
+        fit.data(0, &[&[0], &1234u32.to_le_bytes()]);
+        fit.data(0, &[&[0xFF], &5678u32.to_le_bytes()]);
+        fit.data(0, &[&[2], &9012u32.to_le_bytes()]);
+        let file = File::from_bytes(&fit.build()).unwrap();
+
+        let serials: Vec<Option<u32>> = file
+            .contributing_devices()
+            .iter()
+            .map(|device| device.serial_number)
+            .collect();
+        assert_eq!(serials, vec![Some(1234), Some(9012)]);
+    }
+
+    #[test]
+    fn overlapping_laps_do_not_share_records() {
+        let file = three_laps(&[(1000, 1100), (1090, 1200), (1200, 1300)]);
+
+        let first = timestamps(file.records_in_lap(0).unwrap());
+        let second = timestamps(file.records_in_lap(1).unwrap());
+        assert_eq!(first.last(), Some(&1089));
+        assert_eq!(second.first(), Some(&1090));
+        assert_eq!(first.len() + second.len(), 200);
+    }
+
+    #[test]
+    fn iterates_messages_in_decode_order() {
+        let mut file = three_laps(&[(1000, 1100), (1100, 1200), (1200, 1300)]);
+
+        let records = file
+            .messages()
+            .filter(|mesg| {
+                matches!(mesg.0.first(), Some(messages::Message::Record(_)))
+            })
+            .count();
+        let series = RecordTimeSeries::from_messages(
+            &file.messages().cloned().collect::<Vec<_>>(),
+        );
+        assert_eq!(records, series.len());
+        assert_eq!(file.messages().count(), 303);
+        assert_eq!((&file).into_iter().count(), 305);
+
+        // Strip heart rates in place
+        for mesg in file.messages_mut() {
+            mesg.0.retain(|field| {
+                !matches!(
+                    field,
+                    messages::Message::Record(messages::Record::HeartRate(_))
+                )
+            });
+        }
+        let points: Vec<RecordPoint> =
+            file.messages().filter_map(RecordPoint::from_message).collect();
+        assert_eq!(points.len(), 300);
+        assert!(points.iter().all(|point| point.heart_rate.is_none()));
+    }
+
+    fn one_record() -> FitBuilder {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 20, &[(253, 4, 0x86), (3, 1, 0x02)]);
+        fit.data(0, &[&1000u32.to_le_bytes(), &[140]]);
+        fit
+    }
+
+    #[test]
+    fn accepts_header_sizes() {
+        let fit = one_record();
+        for &(size, header_crc, layout) in &[
+            (12, false, HeaderLayout::NoCrc),
+            (13, false, HeaderLayout::Nonstandard(13)),
+            (14, true, HeaderLayout::WithCrc),
+            // A zero CRC wasn't computed rather than wrong
+            (14, false, HeaderLayout::WithCrc),
+            (20, false, HeaderLayout::Nonstandard(20)),
+        ] {
+            let bytes = fit.build_with_header(size, header_crc);
+            let file = File::from_bytes(&bytes).unwrap();
+            assert_eq!(file.header.layout(), layout);
+            assert_eq!(file.header.size(), size);
+            assert_eq!(file.header.crc().is_some(), size == 14);
+            let points: Vec<RecordPoint> =
+                file.messages().filter_map(RecordPoint::from_message).collect();
+            assert_eq!(points.len(), 1);
+            assert_eq!(points[0].heart_rate, Some(140));
+        }
+    }
+
+    #[test]
+    fn rejects_header_sizes() {
+        let mut bytes = one_record().build_with_header(14, true);
+        bytes[12] ^= 0xFF;
+        assert!(File::from_bytes(&bytes).is_err());
+
+        for &size in &[0, 11, 101, 0xFF] {
+            let mut bytes = one_record().build_with_header(12, false);
+            bytes[0] = size;
+            let err = Header::decode(&mut Cursor::new(&bytes)).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::NotFit), "{}", size);
+        }
+    }
+
+    #[test]
+    fn conflicting_file_ids() {
+        let file_id = |file_type: u8| -> Vec<u8> { vec![file_type, 1, 0] };
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 0, &[(0, 1, 0x00), (1, 2, 0x84)]);
+        fit.definition(1, 20, &[(253, 4, 0x86)]);
+        fit.data(1, &[&1000u32.to_le_bytes()]);
+        // An activity merged with a course
+        fit.data(0, &[&file_id(4)]);
+        fit.data(1, &[&1001u32.to_le_bytes()]);
+        fit.data(0, &[&file_id(6)]);
+        let file = File::from_bytes(&fit.build()).unwrap();
+
+        // The reader keeps both
+        let file_ids = file
+            .messages()
+            .filter(|mesg| matches!(mesg.0.first(), Some(Field::FileId(_))))
+            .count();
+        assert_eq!(file_ids, 2);
+
+        assert!(matches!(file.file_type(), Some(FileType::Activity)));
+        assert!(std::ptr::eq(
+            file.file_id().unwrap(),
+            file.messages().nth(1).unwrap()
+        ));
+        assert_eq!(
+            file.warnings(),
+            vec![
+                FileWarning::MultipleFileIds {
+                    count:             2,
+                    conflicting_types: true,
+                },
+                FileWarning::FileIdNotFirst {
+                    preceding: 1
+                },
+            ]
+        );
+
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 0, &[(0, 1, 0x00), (1, 2, 0x84)]);
+        fit.data(0, &[&file_id(4)]).data(0, &[&file_id(4)]);
+        let file = File::from_bytes(&fit.build()).unwrap();
+        assert_eq!(
+            file.warnings(),
+            vec![FileWarning::MultipleFileIds {
+                count:             2,
+                conflicting_types: false,
+            }]
+        );
+        let file = File::from_bytes(&record_file(3)).unwrap();
+        assert!(file.warnings().is_empty());
+    }
+
+    #[test]
+    fn largest_developer_fields_are_skipped() {
+        // The most a definition can declare: 255 developer
+        // fields of 255 bytes each
+        let mut definition = vec![0x60, 0, 0, 20, 0, 1, 253, 4, 0x86, 255];
+        for num in 0..255u8 {
+            definition.extend_from_slice(&[num, 255, 0]);
+        }
+        let mut fit = FitBuilder::new();
+        fit.raw(&definition);
+        fit.raw(&[0x00])
+            .raw(&1000u32.to_le_bytes())
+            .raw(&[0xAB; 255 * 255]);
+        fit.definition(1, 20, &[(253, 4, 0x86)]);
+        fit.data(1, &[&1001u32.to_le_bytes()]);
+
+        let file = File::from_bytes(&fit.build()).unwrap();
+        let messages: Vec<&Data> = file.messages().collect();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0.len(), 1 + 255);
+        assert_eq!(timestamps(messages.into_iter()), vec![1000, 1001]);
+    }
 }
@@ -5,30 +5,43 @@ use byteorder::{
     LittleEndian,
     ReadBytesExt,
 };
+use consts::ProtocolVersion;
 use error::{
     Error,
     Result,
 };
+use presence::MessagePresence;
 use profile;
+use stats::DecodeStats;
 use std::{
     collections::HashMap,
     convert::TryFrom,
 };
 
+/// Field definition number the owning decoder gives
+/// developer fields, which no profile field has.
+pub(crate) const DEVELOPER_FIELD_NUM: u8 = 255;
+
 #[derive(Debug,Clone)]
 pub struct Record {
-    pub header:  Header,
-    pub content: Message,
+    pub header:   Header,
+    pub content:  Message,
+    /// Which fields the definition declared, and which of
+    /// them held the invalid value. Empty unless `content`
+    /// is a data message.
+    pub presence: MessagePresence,
 }
 
 impl Record {
     pub(crate) fn decode<R: ReadBytesExt>(
         r: &mut R,
         local_mesgs: &HashMap<u8, Definition>,
+        stats: Option<&mut DecodeStats>,
     ) -> Result<Self> {
         let header = Header::decode(r).map_err(Error::decoding("header"))?;
 
         // Decode record content according to the header we got
+        let mut presence = MessagePresence::default();
         let content = match header {
             Header::Definition {
                 local_mesg_num: _,
@@ -47,18 +60,18 @@ impl Record {
                 // message
                 let definition = local_mesgs
                     .get(&local_mesg_num)
-                    .ok_or(Error::missing_definition(local_mesg_num))?;
+                    .ok_or_else(|| Error::missing_definition(local_mesg_num))?;
 
                 match definition.arch {
                     Architecture::LittleEndian => {
                         Message::Data(
-                            Data::decode::<R, LittleEndian>(r, definition)
+                            Data::decode::<R, LittleEndian>(r, definition, &mut presence, stats)
                                 .map_err(Error::decoding("data message"))?,
                         )
                     },
                     Architecture::BigEndian => {
                         Message::Data(
-                            Data::decode::<R, BigEndian>(r, definition)
+                            Data::decode::<R, BigEndian>(r, definition, &mut presence, stats)
                                 .map_err(Error::decoding("data message"))?,
                         )
                     },
@@ -73,6 +86,7 @@ impl Record {
         Ok(Record {
             header,
             content,
+            presence,
         })
     }
 }
@@ -95,7 +109,14 @@ pub enum Header {
 impl Header {
     fn decode<R: ReadBytesExt>(r: &mut R) -> Result<Self> {
         let byte = r.read_u8().map_err(Error::reading("byte"))?;
+        Ok(Header::from_byte(byte))
+    }
 
+    /// Parse a record header byte.
+    ///
+    /// Reserved bits are ignored here; see
+    /// [`reserved_bits`](#method.reserved_bits).
+    pub(crate) fn from_byte(byte: u8) -> Self {
         // "A value of 0 in Bit 7 of the record header indicates
         // that this is a Normal Header."
         if byte.bit_not_set(7) {
@@ -103,25 +124,44 @@ impl Header {
             // 1: Definition Message
             // 0: Data Message
             if byte.bit_is_set(6) {
-                Ok(Header::Definition {
+                Header::Definition {
                     local_mesg_num: byte.bit_range(0, 3),
                     has_dev_fields: byte.bit_is_set(5),
-                })
+                }
             }
             else {
-                Ok(Header::Data {
+                Header::Data {
                     local_mesg_num: byte.bit_range(0, 3)
-                })
+                }
             }
         }
         else {
-            Ok(Header::CompressedTimestamp {
+            Header::CompressedTimestamp {
                 local_mesg_num: byte.bit_range(5, 6),
                 time_offset:    byte.bit_range(0, 4),
-            })
+            }
         }
     }
 
+    /// The bits of record header `byte` that a file of
+    /// `protocol` version reserves, which should be zero.
+    ///
+    /// Bit 4 of a normal header is reserved, and so is bit
+    /// 5 of a data message header. In a definition header
+    /// bit 5 flags developer fields, which protocol 2.0
+    /// added, so 1.x files reserve it there too. Compressed
+    /// timestamp headers use all their bits.
+    pub(crate) fn reserved_bits(byte: u8, protocol: ProtocolVersion) -> u8 {
+        if byte.bit_is_set(7) {
+            return 0
+        }
+        let mut reserved = 1 << 4;
+        if byte.bit_not_set(6) || protocol.major < 2 {
+            reserved |= 1 << 5;
+        }
+        byte & reserved
+    }
+
     /// Convenience method to access the `local_mesg_num`
     /// field common to all `Header` types.
     pub fn local_mesg_num(&self) -> u8 {
@@ -160,6 +200,55 @@ pub struct Definition {
 }
 
 impl Definition {
+    /// The global message number of the data messages this
+    /// defines.
+    pub(crate) fn global_mesg_num(&self) -> u16 {
+        self.global_mesg_num
+    }
+
+    /// Whether the data messages this defines are big
+    /// endian.
+    pub(crate) fn is_big_endian(&self) -> bool {
+        match self.arch {
+            Architecture::LittleEndian => false,
+            Architecture::BigEndian => true,
+        }
+    }
+
+    /// The field definition numbers of the regular fields
+    /// this defines, in record order.
+    pub(crate) fn field_def_nums<'a>(&'a self) -> impl Iterator<Item = u8> + 'a {
+        self.field_defs.iter().map(|field_def| field_def.num)
+    }
+
+    /// The regular fields this defines, in record order.
+    pub(crate) fn field_defs(&self) -> &[FieldDefinition] {
+        &self.field_defs
+    }
+
+    /// The numbers of the regular fields this lists more
+    /// than once, once for each occurrence but the last.
+    pub(crate) fn duplicate_field_nums<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = u8> + 'a {
+        self.field_defs
+            .iter()
+            .filter(|field_def| field_def.superseded)
+            .map(|field_def| field_def.num)
+    }
+
+    /// The developer fields this defines, in record order,
+    /// or `None` if the definition record didn't flag any.
+    pub(crate) fn developer_field_defs(&self) -> Option<&[FieldDefinition]> {
+        self.devfield_defs.as_deref()
+    }
+
+    /// The number of fields in each data message this
+    /// defines, developer fields included.
+    pub(crate) fn total_fields(&self) -> usize {
+        self.field_defs.len() + self.devfield_defs.as_ref().map_or(0, Vec::len)
+    }
+
     pub(super) fn decode<R: ReadBytesExt>(r: &mut R, has_dev_fields: bool) -> Result<Self> {
         // NOTE: Discarding the reserved byte
         r.read_u8().map_err(Error::reading("reserved byte"))?;
@@ -192,6 +281,16 @@ impl Definition {
             field_defs.push(field_def);
         }
 
+        // Buggy encoders sometimes list a field twice, maybe
+        // with different sizes. The last occurrence is the
+        // one decoded; the others are read past so the
+        // fields after them stay aligned.
+        let mut seen = [false; 256];
+        for field_def in field_defs.iter_mut().rev() {
+            field_def.superseded = seen[usize::from(field_def.num)];
+            seen[usize::from(field_def.num)] = true;
+        }
+
 
         // Reading developer felds, which were added in FIT 1.9
         if has_dev_fields {
@@ -230,10 +329,39 @@ impl Definition {
 pub struct FieldDefinition {
     num:            u8,
     size:           u8,
-    _base_type_num: u8,
+    base_type_num: u8,
+    /// Whether the same field comes again later in the
+    /// definition, so this occurrence isn't decoded.
+    superseded:     bool,
+    /// The developer data index of a developer field, whose
+    /// `num` is its field definition number.
+    developer_data_index: Option<u8>,
 }
 
 impl FieldDefinition {
+    pub(crate) fn num(&self) -> u8 {
+        self.num
+    }
+
+    /// Bytes.
+    pub(crate) fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub(crate) fn base_type_num(&self) -> u8 {
+        self.base_type_num
+    }
+
+    /// Whether a later occurrence of the same field in the
+    /// definition is the one decoded.
+    pub(crate) fn is_superseded(&self) -> bool {
+        self.superseded
+    }
+
+    /// The developer data index, for a developer field.
+    pub(crate) fn developer_data_index(&self) -> Option<u8> {
+        self.developer_data_index
+    }
 
     // TODO: Pass full Field Description message instead of `is_developer_field: bool`
     pub(super) fn decode<R: ReadBytesExt>(reader: &mut R, is_developer_field: bool) -> Result<Self> {
@@ -250,27 +378,31 @@ impl FieldDefinition {
                 Ok(FieldDefinition {
                     num: field_number,
                     size: field_size,
-                    _base_type_num: base_type_id,
+                    base_type_num: base_type_id,
+                    superseded: false,
+                    developer_data_index: None,
                 })
             }
 
             // Developer field
             true => {
                 // Maps to field_definition_number of Field Description Message
-                let _field_number = reader.read_u8().map_err(Error::reading("developer field number"))?;
+                let field_number = reader.read_u8().map_err(Error::reading("developer field number"))?;
 
                 let field_size = reader.read_u8().map_err(Error::reading("developer field size"))?;
 
                 // Index of Field Descripion Message
-                let _developer_data_index =
+                let developer_data_index =
                     reader.read_u8().map_err(Error::reading("developer field data index"))?;
 
                 Ok(FieldDefinition {
-                    // TODO: FIXME: Update FieldDefiniton to support developer fields.
-                    num: 255,
+                    num: field_number,
                     size: field_size,
-                    // TODO: FIXME: Update FieldDefiniton to support developer fields.
-                    _base_type_num: 13, // Array of bytes, to just skip value, to be able to parse file.
+                    // The base type is in the FieldDescription, so
+                    // read the value as bytes
+                    base_type_num: 13,
+                    superseded: false,
+                    developer_data_index: Some(developer_data_index),
                 })
             }
         }
@@ -283,17 +415,35 @@ impl FieldDefinition {
 pub struct Data(pub Vec<profile::messages::Message>);
 
 impl Data {
+    /// The global message number, or `None` for a message
+    /// without any fields.
+    pub fn mesg_num(&self) -> Option<u16> {
+        self.0.first().map(|field| field.mesg_num())
+    }
+
     pub(super) fn decode<R: ReadBytesExt, T: ByteOrder>(
         reader: &mut R,
         definition: &Definition,
+        presence: &mut MessagePresence,
+        mut stats: Option<&mut DecodeStats>,
     ) -> Result<Self> {
         let mut messages = Vec::with_capacity(definition.field_defs.len());
+        let big_endian = definition.is_big_endian();
 
         for field_def in definition.field_defs.iter() {
 
             // Read required number of bytes, as required by field
             let mut buffer = vec![0; field_def.size as usize];
-            reader.read(&mut buffer).map_err(Error::reading("buffer"))?;
+            reader.read_exact(&mut buffer).map_err(Error::reading("buffer"))?;
+
+            let invalid = profile::base::is_invalid_raw(field_def.base_type_num, &buffer, big_endian);
+            presence.push(field_def.num, !invalid);
+
+            // Only the last occurrence of a repeated field
+            // counts, whatever the size of the others
+            if field_def.superseded {
+                continue;
+            }
 
             // Decode field from buffer
             let message = profile::messages::Message::decode::<T>(
@@ -302,6 +452,10 @@ impl Data {
                 field_def.num,
             )?;
 
+            if let Some(ref mut stats) = stats {
+                Data::count_field(stats, invalid, &message);
+            }
+
             // Append message to 
             messages.push(message);
         }
@@ -312,13 +466,14 @@ impl Data {
 
                 // Read required number of bytes, as required by field
                 let mut buffer = vec![0; field_def.size as usize];
-                reader.read(&mut buffer).map_err(Error::reading("buffer"))?;
+                reader.read_exact(&mut buffer).map_err(Error::reading("buffer"))?;
 
-                // Decode field from buffer
+                // Decode field from buffer, as bytes under the
+                // field number no profile field has
                 let message = profile::messages::Message::decode::<T>(
                     &buffer,
                     definition.global_mesg_num,
-                    field_def.num,
+                    DEVELOPER_FIELD_NUM,
                 )?;
 
                 messages.push(message);
@@ -327,6 +482,24 @@ impl Data {
 
         Ok(Data(messages))
     }
+
+    fn count_field(
+        stats: &mut DecodeStats,
+        invalid: bool,
+        message: &profile::messages::Message,
+    ) {
+        if invalid {
+            stats.invalid_fields += 1;
+        }
+        match message {
+            // Whole unknown messages are counted by the reader
+            profile::messages::Message::Unknown {
+                ..
+            } => (),
+            message if message.is_unknown() => stats.unknown_fields += 1,
+            _ => (),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -346,3 +519,84 @@ impl TryFrom<u8> for Architecture {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_mesg_num(byte: u8) -> u8 {
+        Header::from_byte(byte).local_mesg_num()
+    }
+
+    #[test]
+    fn header_bytes() {
+        let v1 = ProtocolVersion::V1_0;
+        let v2 = ProtocolVersion::V2_0;
+
+        // Data message, local type 5
+        assert!(matches!(Header::from_byte(0x05), Header::Data { .. }));
+        assert_eq!(local_mesg_num(0x05), 5);
+        assert_eq!(Header::reserved_bits(0x05, v2), 0);
+        // Data message, local type 15
+        assert_eq!(local_mesg_num(0x0F), 15);
+        assert_eq!(Header::reserved_bits(0x0F, v2), 0);
+
+        // Definition, local type 5
+        assert!(matches!(
+            Header::from_byte(0x45),
+            Header::Definition {
+                local_mesg_num: 5,
+                has_dev_fields: false,
+            }
+        ));
+        assert_eq!(Header::reserved_bits(0x45, v1), 0);
+
+        // Definition with developer fields, which 1.x
+        // reserves
+        assert!(matches!(
+            Header::from_byte(0x65),
+            Header::Definition {
+                local_mesg_num: 5,
+                has_dev_fields: true,
+            }
+        ));
+        assert_eq!(Header::reserved_bits(0x65, v2), 0);
+        assert_eq!(Header::reserved_bits(0x65, v1), 0x20);
+
+        // Reserved bit 5 on a data message is no developer
+        // flag and no part of the local type
+        assert!(matches!(Header::from_byte(0x25), Header::Data { .. }));
+        assert_eq!(local_mesg_num(0x25), 5);
+        assert_eq!(Header::reserved_bits(0x25, v2), 0x20);
+
+        // Reserved bit 4, on a data message and a definition
+        assert_eq!(local_mesg_num(0x15), 5);
+        assert_eq!(Header::reserved_bits(0x15, v2), 0x10);
+        assert!(matches!(
+            Header::from_byte(0x75),
+            Header::Definition {
+                local_mesg_num: 5,
+                has_dev_fields: true,
+            }
+        ));
+        assert_eq!(Header::reserved_bits(0x75, v2), 0x10);
+
+        // Compressed timestamps: local type in bits 5-6,
+        // offset in bits 0-4, nothing reserved
+        assert!(matches!(
+            Header::from_byte(0x9F),
+            Header::CompressedTimestamp {
+                local_mesg_num: 0,
+                time_offset:    31,
+            }
+        ));
+        assert!(matches!(
+            Header::from_byte(0xE0),
+            Header::CompressedTimestamp {
+                local_mesg_num: 3,
+                time_offset:    0,
+            }
+        ));
+        assert_eq!(Header::reserved_bits(0xFF, v1), 0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Universal Transverse Mercator grid coordinates, for
+//! positions in metres rather than degrees.
+//!
+//! This is a simplified implementation: the WGS84 ellipsoid
+//! only, Karney's sixth order series for the transverse
+//! Mercator projection, and the standard zones, including
+//! the exceptions around Norway and Svalbard. The series is
+//! good to a few nanometres within the zones, but there's
+//! no handling of the polar UPS zones or of other datums.
+//! Use a dedicated geodesy crate in production.
+use geo::GeoPoint;
+
+/// WGS84 semi-major axis in metres.
+const A: f64 = 6_378_137.0;
+/// WGS84 flattening.
+const F: f64 = 1.0 / 298.257_223_563;
+/// Scale on the central meridian of each zone.
+const K0: f64 = 0.9996;
+const FALSE_EASTING: f64 = 500_000.0;
+/// Added to northings in the southern hemisphere.
+const FALSE_NORTHING_SOUTH: f64 = 10_000_000.0;
+
+/// Latitude bands from 80°S, 8° each, with X stretched to
+/// 84°N.
+const BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWXX";
+
+/// A position on the UTM grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UtmCoord {
+    /// Metres, 500 km at the zone's central meridian.
+    pub easting_m:   f64,
+    /// Metres from the equator, plus 10 000 km in the
+    /// southern hemisphere.
+    pub northing_m:  f64,
+    /// 1 to 60, eastwards from 180°.
+    pub zone_number: u8,
+    /// The latitude band, `C` to `X`. Bands from `N` up are
+    /// in the northern hemisphere.
+    pub zone_letter: char,
+}
+
+/// Project a WGS84 position onto the UTM grid, in the zone
+/// it belongs to.
+pub fn wgs84_to_utm(lat_deg: f64, lon_deg: f64) -> UtmCoord {
+    let zone_number = zone_number(lat_deg, lon_deg);
+    let zone_letter = zone_letter(lat_deg);
+
+    let e = eccentricity();
+    let phi = lat_deg.to_radians();
+    let lambda = (lon_deg - central_meridian(zone_number)).to_radians();
+
+    // Conformal latitude, as its tangent
+    let tau = phi.tan();
+    let sigma = (e * (e * tau / (1.0 + tau * tau).sqrt()).atanh()).sinh();
+    let tau_c =
+        tau * (1.0 + sigma * sigma).sqrt() - sigma * (1.0 + tau * tau).sqrt();
+
+    // Spherical transverse Mercator
+    let xi_c = tau_c.atan2(lambda.cos());
+    let eta_c =
+        (lambda.sin() / (tau_c * tau_c + lambda.cos().powi(2)).sqrt()).asinh();
+
+    // And on to the ellipsoid
+    let (mut xi, mut eta) = (xi_c, eta_c);
+    for (j, alpha) in alpha().iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi += alpha * (k * xi_c).sin() * (k * eta_c).cosh();
+        eta += alpha * (k * xi_c).cos() * (k * eta_c).sinh();
+    }
+
+    let scale = K0 * rectifying_radius();
+    let mut northing_m = scale * xi;
+    if lat_deg < 0.0 {
+        northing_m += FALSE_NORTHING_SOUTH;
+    }
+    UtmCoord {
+        easting_m: FALSE_EASTING + scale * eta,
+        northing_m,
+        zone_number,
+        zone_letter,
+    }
+}
+
+/// The WGS84 position of a point on the UTM grid.
+pub fn utm_to_wgs84(coord: &UtmCoord) -> GeoPoint {
+    let e = eccentricity();
+    let scale = K0 * rectifying_radius();
+    let mut northing_m = coord.northing_m;
+    if coord.zone_letter.to_ascii_uppercase() < 'N' {
+        northing_m -= FALSE_NORTHING_SOUTH;
+    }
+    let xi = northing_m / scale;
+    let eta = (coord.easting_m - FALSE_EASTING) / scale;
+
+    // Back to the sphere
+    let (mut xi_c, mut eta_c) = (xi, eta);
+    for (j, beta) in beta().iter().enumerate() {
+        let k = 2.0 * (j + 1) as f64;
+        xi_c -= beta * (k * xi).sin() * (k * eta).cosh();
+        eta_c -= beta * (k * xi).cos() * (k * eta).sinh();
+    }
+    let tau_c = xi_c.sin() / (eta_c.sinh().powi(2) + xi_c.cos().powi(2)).sqrt();
+    let lambda = eta_c.sinh().atan2(xi_c.cos());
+
+    // Invert the conformal latitude with Newton's method,
+    // which converges in two or three steps
+    let e2 = e * e;
+    let mut tau = tau_c;
+    for _ in 0..5 {
+        let sigma = (e * (e * tau / (1.0 + tau * tau).sqrt()).atanh()).sinh();
+        let tau_i = tau * (1.0 + sigma * sigma).sqrt()
+            - sigma * (1.0 + tau * tau).sqrt();
+        let step = (tau_c - tau_i) / (1.0 + tau_i * tau_i).sqrt()
+            * (1.0 + (1.0 - e2) * tau * tau)
+            / ((1.0 - e2) * (1.0 + tau * tau).sqrt());
+        tau += step;
+        if step.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    GeoPoint::new(
+        tau.atan().to_degrees(),
+        central_meridian(coord.zone_number) + lambda.to_degrees(),
+    )
+}
+
+/// The zone of a position, with the wider zones 32V over
+/// Norway and 31X to 37X over Svalbard.
+fn zone_number(lat: f64, lon: f64) -> u8 {
+    // Longitude 180° belongs to zone 1
+    let lon = if lon >= 180.0 { lon - 360.0 } else { lon };
+    let zone = ((lon + 180.0) / 6.0).floor() as u8 + 1;
+
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        return 32;
+    }
+    if (72.0..84.0).contains(&lat) && (0.0..42.0).contains(&lon) {
+        return match lon {
+            lon if lon < 9.0 => 31,
+            lon if lon < 21.0 => 33,
+            lon if lon < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    zone
+}
+
+fn zone_letter(lat: f64) -> char {
+    let band = ((lat + 80.0) / 8.0).floor().max(0.0) as usize;
+    char::from(BANDS[band.min(BANDS.len() - 1)])
+}
+
+/// Longitude in degrees of the middle of `zone`.
+fn central_meridian(zone: u8) -> f64 {
+    f64::from(zone) * 6.0 - 183.0
+}
+
+fn eccentricity() -> f64 {
+    (F * (2.0 - F)).sqrt()
+}
+
+/// Third flattening, the small parameter of the series.
+fn third_flattening() -> f64 {
+    F / (2.0 - F)
+}
+
+/// Radius of the circle with the circumference of a
+/// meridian.
+fn rectifying_radius() -> f64 {
+    let n = third_flattening();
+    let n2 = n * n;
+    A / (1.0 + n) * (1.0 + n2 / 4.0 + n2 * n2 / 64.0 + n2 * n2 * n2 / 256.0)
+}
+
+/// Coefficients of the series from the sphere to the
+/// ellipsoid.
+fn alpha() -> [f64; 6] {
+    let n = third_flattening();
+    let (n2, n3, n4, n5, n6) =
+        (n.powi(2), n.powi(3), n.powi(4), n.powi(5), n.powi(6));
+    [
+        n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0
+            - 127.0 * n5 / 288.0
+            + 7891.0 * n6 / 37800.0,
+        13.0 * n2 / 48.0 - 3.0 * n3 / 5.0
+            + 557.0 * n4 / 1440.0
+            + 281.0 * n5 / 630.0
+            - 1_983_433.0 * n6 / 1_935_360.0,
+        61.0 * n3 / 240.0 - 103.0 * n4 / 140.0
+            + 15061.0 * n5 / 26880.0
+            + 167_603.0 * n6 / 181_440.0,
+        49561.0 * n4 / 161_280.0 - 179.0 * n5 / 168.0
+            + 6_601_661.0 * n6 / 7_257_600.0,
+        34729.0 * n5 / 80640.0 - 3_418_889.0 * n6 / 1_995_840.0,
+        212_378_941.0 * n6 / 319_334_400.0,
+    ]
+}
+
+/// Coefficients of the series from the ellipsoid back to
+/// the sphere.
+fn beta() -> [f64; 6] {
+    let n = third_flattening();
+    let (n2, n3, n4, n5, n6) =
+        (n.powi(2), n.powi(3), n.powi(4), n.powi(5), n.powi(6));
+    [
+        n / 2.0 - 2.0 * n2 / 3.0 + 37.0 * n3 / 96.0
+            - n4 / 360.0
+            - 81.0 * n5 / 512.0
+            + 96199.0 * n6 / 604_800.0,
+        n2 / 48.0 + n3 / 15.0 - 437.0 * n4 / 1440.0 + 46.0 * n5 / 105.0
+            - 1_118_711.0 * n6 / 3_870_720.0,
+        17.0 * n3 / 480.0 - 37.0 * n4 / 840.0 - 209.0 * n5 / 4480.0
+            + 5569.0 * n6 / 90720.0,
+        4397.0 * n4 / 161_280.0
+            - 11.0 * n5 / 504.0
+            - 830_251.0 * n6 / 7_257_600.0,
+        4583.0 * n5 / 161_280.0 - 108_847.0 * n6 / 3_991_680.0,
+        20_648_693.0 * n6 / 638_668_800.0,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Within a millimetre.
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 0.001
+    }
+
+    #[test]
+    fn zone_32_western_boundary() {
+        // References from numerically integrating the exact
+        // projection, independent of the series
+        let cases = [
+            ((52.0, 6.0), 'U', 294_071.081_053, 5_765_288.254_857),
+            ((0.5, 6.0), 'N', 166_034.098_267, 55_341.388_216),
+            // Zone 32 is wider over Norway
+            ((60.0, 5.5), 'V', 304_838.827_269, 6_656_575.859_116),
+        ];
+        for &((lat, lon), letter, easting, northing) in &cases {
+            let utm = wgs84_to_utm(lat, lon);
+            assert_eq!((utm.zone_number, utm.zone_letter), (32, letter));
+            assert!(close(utm.easting_m, easting), "{:?}", utm);
+            assert!(close(utm.northing_m, northing), "{:?}", utm);
+
+            let back = utm_to_wgs84(&utm);
+            // About a millimetre, in degrees
+            assert!((back.lat - lat).abs() < 1e-8, "{:?}", back);
+            assert!((back.lon - lon).abs() < 1e-8, "{:?}", back);
+        }
+    }
+
+    #[test]
+    fn southern_hemisphere_and_zones() {
+        // On the central meridian of zone 34, south
+        let utm = wgs84_to_utm(-10.0, 21.0);
+        assert_eq!((utm.zone_number, utm.zone_letter), (34, 'L'));
+        assert!(close(utm.easting_m, 500_000.0));
+        assert!(utm.northing_m > 8_800_000.0 && utm.northing_m < 9_000_000.0);
+        let back = utm_to_wgs84(&utm);
+        assert!(
+            (back.lat + 10.0).abs() < 1e-9 && (back.lon - 21.0).abs() < 1e-9
+        );
+
+        assert_eq!(wgs84_to_utm(0.0, 180.0).zone_number, 1);
+        assert_eq!(wgs84_to_utm(78.0, 10.0).zone_number, 33);
+        assert_eq!(wgs84_to_utm(83.9, 0.0).zone_letter, 'X');
+    }
+}
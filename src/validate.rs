@@ -0,0 +1,305 @@
+//! Sanity checks for decoded values.
+//!
+//! A field can hold a perfectly valid raw value that still
+//! makes no physical sense once scaled, typically in
+//! corrupt files or from misbehaving sensors. Left alone, a
+//! single such value poisons every aggregate computed from
+//! it.
+//!
+//! The profile doesn't define plausible ranges for fields,
+//! so the checks here use a table of [`Bounds`] per
+//! [`Quantity`] covering the common fields. The defaults
+//! are meant for everyday sports; callers with special
+//! cases, like aviation speeds or dive depths, override
+//! them.
+use profile::messages::{
+    Lap,
+    Message,
+    Record,
+    Session,
+};
+use std::collections::HashMap;
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// What a checked field measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Quantity {
+    /// Metres above sea level.
+    Altitude,
+    /// Metres per second.
+    Speed,
+    /// Beats per minute.
+    HeartRate,
+    /// Revolutions or strides per minute.
+    Cadence,
+    /// Watts.
+    Power,
+    /// Metres travelled.
+    Distance,
+}
+
+/// An inclusive range of plausible values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Range {
+    pub fn new(min: f64, max: f64) -> Range {
+        Range {
+            min,
+            max,
+        }
+    }
+
+    /// Whether `value` is finite and within the range.
+    pub fn contains(&self, value: f64) -> bool {
+        value.is_finite() && value >= self.min && value <= self.max
+    }
+}
+
+/// Plausible ranges by quantity.
+///
+/// Quantities without a range are never implausible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bounds {
+    ranges: HashMap<Quantity, Range>,
+}
+
+impl Default for Bounds {
+    /// Ranges for everyday sports, on foot, bike or in the
+    /// water.
+    fn default() -> Self {
+        let mut ranges = HashMap::new();
+        ranges.insert(Quantity::Altitude, Range::new(-500.0, 9000.0));
+        ranges.insert(Quantity::Speed, Range::new(0.0, 50.0));
+        ranges.insert(Quantity::HeartRate, Range::new(20.0, 250.0));
+        ranges.insert(Quantity::Cadence, Range::new(0.0, 250.0));
+        ranges.insert(Quantity::Power, Range::new(0.0, 3000.0));
+        ranges.insert(Quantity::Distance, Range::new(0.0, 10_000_000.0));
+        Bounds {
+            ranges,
+        }
+    }
+}
+
+impl Bounds {
+    /// Bounds that accept everything, to build on with
+    /// [`with`](#method.with).
+    pub fn none() -> Bounds {
+        Bounds {
+            ranges: HashMap::new()
+        }
+    }
+
+    /// Replace the range for `quantity`.
+    pub fn with(mut self, quantity: Quantity, range: Range) -> Bounds {
+        self.ranges.insert(quantity, range);
+        self
+    }
+
+    /// Stop checking `quantity`.
+    pub fn without(mut self, quantity: Quantity) -> Bounds {
+        self.ranges.remove(&quantity);
+        self
+    }
+
+    pub fn get(&self, quantity: Quantity) -> Option<Range> {
+        self.ranges.get(&quantity).cloned()
+    }
+
+    /// Whether `value` is plausible for `quantity`.
+    pub fn is_plausible(&self, quantity: Quantity, value: f64) -> bool {
+        self.get(quantity).map(|range| range.contains(value)).unwrap_or(true)
+    }
+}
+
+/// A field whose scaled value is outside its plausible
+/// range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Implausible {
+    /// Position of the data message among `messages`.
+    pub index:    usize,
+    /// Profile name of the field, e.g.
+    /// `"enhanced_altitude"`.
+    pub field:    &'static str,
+    pub quantity: Quantity,
+    pub value:    f64,
+    pub range:    Range,
+}
+
+/// Check the `Record`, `Lap` and `Session` messages in
+/// `messages` against the default bounds.
+pub fn plausibility(messages: &[Data]) -> Vec<Implausible> {
+    plausibility_with(messages, &Bounds::default())
+}
+
+/// Check the `Record`, `Lap` and `Session` messages in
+/// `messages` against `bounds`.
+///
+/// Invalid fields are skipped: they are already flagged as
+/// missing by the decoder.
+pub fn plausibility_with(
+    messages: &[Data],
+    bounds: &Bounds,
+) -> Vec<Implausible> {
+    let mut implausible = Vec::new();
+    for (index, mesg) in messages.iter().enumerate() {
+        for field in mesg.0.iter() {
+            let (name, quantity, value) = match checked_value(field) {
+                Some(checked) => checked,
+                None => continue,
+            };
+            if let Some(range) = bounds.get(quantity) {
+                if !range.contains(value) {
+                    implausible.push(Implausible {
+                        index,
+                        field: name,
+                        quantity,
+                        value,
+                        range,
+                    });
+                }
+            }
+        }
+    }
+    implausible
+}
+
+/// The name, quantity and scaled value of a checked field,
+/// if it's one and it's valid.
+fn checked_value(field: &Message) -> Option<(&'static str, Quantity, f64)> {
+    macro_rules! checked {
+        ($f:expr, $name:expr, $quantity:ident) => {
+            if $f.is_valid() {
+                Some(($name, Quantity::$quantity, $f.value()))
+            }
+            else {
+                None
+            }
+        };
+    }
+
+    // Lap and Session share field names and types
+    macro_rules! summary {
+        ($mesg:ident, $field:expr) => {
+            match $field {
+                $mesg::TotalDistance(f) => {
+                    checked!(f, "total_distance", Distance)
+                },
+                $mesg::AvgSpeed(f) => checked!(f, "avg_speed", Speed),
+                $mesg::MaxSpeed(f) => checked!(f, "max_speed", Speed),
+                $mesg::EnhancedAvgSpeed(f) => {
+                    checked!(f, "enhanced_avg_speed", Speed)
+                },
+                $mesg::EnhancedMaxSpeed(f) => {
+                    checked!(f, "enhanced_max_speed", Speed)
+                },
+                $mesg::AvgHeartRate(f) => {
+                    checked!(f, "avg_heart_rate", HeartRate)
+                },
+                $mesg::MaxHeartRate(f) => {
+                    checked!(f, "max_heart_rate", HeartRate)
+                },
+                $mesg::AvgCadence(f) => checked!(f, "avg_cadence", Cadence),
+                $mesg::MaxCadence(f) => checked!(f, "max_cadence", Cadence),
+                $mesg::AvgPower(f) => checked!(f, "avg_power", Power),
+                $mesg::MaxPower(f) => checked!(f, "max_power", Power),
+                $mesg::AvgAltitude(f) => {
+                    checked!(f, "avg_altitude", Altitude)
+                },
+                $mesg::MaxAltitude(f) => {
+                    checked!(f, "max_altitude", Altitude)
+                },
+                $mesg::MinAltitude(f) => {
+                    checked!(f, "min_altitude", Altitude)
+                },
+                $mesg::EnhancedAvgAltitude(f) => {
+                    checked!(f, "enhanced_avg_altitude", Altitude)
+                },
+                $mesg::EnhancedMaxAltitude(f) => {
+                    checked!(f, "enhanced_max_altitude", Altitude)
+                },
+                $mesg::EnhancedMinAltitude(f) => {
+                    checked!(f, "enhanced_min_altitude", Altitude)
+                },
+                _ => None,
+            }
+        };
+    }
+
+    match field {
+        Message::Record(field) => {
+            match field {
+                Record::Altitude(f) => checked!(f, "altitude", Altitude),
+                Record::EnhancedAltitude(f) => {
+                    checked!(f, "enhanced_altitude", Altitude)
+                },
+                Record::Speed(f) => checked!(f, "speed", Speed),
+                Record::EnhancedSpeed(f) => {
+                    checked!(f, "enhanced_speed", Speed)
+                },
+                Record::HeartRate(f) => checked!(f, "heart_rate", HeartRate),
+                Record::Cadence(f) => checked!(f, "cadence", Cadence),
+                Record::Power(f) => checked!(f, "power", Power),
+                Record::Distance(f) => checked!(f, "distance", Distance),
+                _ => None,
+            }
+        },
+        Message::Lap(field) => summary!(Lap, field),
+        Message::Session(field) => summary!(Session, field),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    #[test]
+    fn flags_implausible_values() {
+        let messages = vec![
+            mesg(20)
+                .u32(253, 1_000_000_000)
+                .u16(6, 3500) // 3.5 m/s
+                .u8(3, 150)
+                .u32(78, 3500) // 200 m
+                .build(),
+            mesg(20)
+                .u32(253, 1_000_000_001)
+                .u16(6, 65_000) // 65 m/s
+                .u8(3, 10)
+                .u32(78, 0xFFFF_FFFE) // 858,993 km up
+                .u32(5, 0xFFFF_FFFF) // invalid, skipped
+                .build(),
+        ];
+
+        let implausible = plausibility(&messages);
+        let fields: Vec<_> = implausible.iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["speed", "heart_rate", "enhanced_altitude"]);
+        assert!(implausible.iter().all(|i| i.index == 1));
+        assert_eq!(implausible[0].value, 65.0);
+        assert_eq!(implausible[0].quantity, Quantity::Speed);
+    }
+
+    #[test]
+    fn bounds_can_be_overridden() {
+        let lap = vec![mesg(19).u16(13, 30_000).u16(14, 55_000).build()];
+        assert_eq!(plausibility(&lap).len(), 1);
+
+        let aviation =
+            Bounds::default().with(Quantity::Speed, Range::new(0.0, 300.0));
+        assert!(plausibility_with(&lap, &aviation).is_empty());
+
+        let unchecked = Bounds::default().without(Quantity::Speed);
+        assert!(!unchecked.is_plausible(Quantity::HeartRate, 300.0));
+        assert!(unchecked.is_plausible(Quantity::Speed, 1e9));
+        assert!(Bounds::none().is_plausible(Quantity::Altitude, -1e9));
+    }
+}
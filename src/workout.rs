@@ -0,0 +1,394 @@
+//! Structured workouts, as found in workout files.
+//!
+//! A workout file holds one `Workout` message followed by
+//! its `WorkoutStep`s. Multisport workouts add a
+//! `WorkoutSession` per leg, each owning a run of steps and
+//! carrying its own sport and pool length.
+use profile::{
+    messages::{
+        Message,
+        Workout as WorkoutField,
+        WorkoutSession as WorkoutSessionField,
+        WorkoutStep as WorkoutStepField,
+    },
+    types::{
+        DisplayMeasure,
+        Intensity,
+        Sport,
+        SubSport,
+        WktStepDuration,
+    },
+};
+use types::{
+    field::Field,
+    record::Data,
+};
+
+/// Metres per yard.
+const YARD_M: f64 = 0.9144;
+/// Metres per mile.
+const MILE_M: f64 = 1609.344;
+
+/// The fields of a `Workout` message.
+#[derive(Debug, Clone, Default)]
+pub struct Workout {
+    pub name:             Option<String>,
+    pub sport:            Option<Sport>,
+    pub sub_sport:        Option<SubSport>,
+    pub num_valid_steps:  Option<u16>,
+    /// Metres.
+    pub pool_length:      Option<f64>,
+    /// The unit pool lengths should be displayed in.
+    pub pool_length_unit: Option<DisplayMeasure>,
+}
+
+impl Workout {
+    /// Flatten a decoded `Workout` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `Workout` message.
+    pub fn from_message(mesg: &Data) -> Option<Workout> {
+        let mut workout = Workout::default();
+        for field in mesg.0.iter() {
+            match field {
+                Message::Workout(field) => {
+                    match field {
+                        WorkoutField::WktName(f) => {
+                            workout.name = non_empty(&f.raw_value.0)
+                        },
+                        WorkoutField::Sport(f) => {
+                            workout.sport = Some(f.raw_value.clone())
+                        },
+                        WorkoutField::SubSport(f) => {
+                            workout.sub_sport = Some(f.raw_value.clone())
+                        },
+                        WorkoutField::NumValidSteps(f) if f.is_valid() => {
+                            workout.num_valid_steps = Some(f.raw_value.0)
+                        },
+                        WorkoutField::PoolLength(f) if f.is_valid() => {
+                            workout.pool_length = Some(f.value())
+                        },
+                        WorkoutField::PoolLengthUnit(f) => {
+                            workout.pool_length_unit = Some(f.raw_value.clone())
+                        },
+                        _ => (),
+                    }
+                },
+                _ => return None,
+            }
+        }
+        Some(workout)
+    }
+}
+
+/// The fields of a `WorkoutSession` message: one leg of a
+/// multisport workout.
+#[derive(Debug, Clone, Default)]
+pub struct WorkoutSession {
+    pub sport:            Option<Sport>,
+    pub sub_sport:        Option<SubSport>,
+    pub num_valid_steps:  Option<u16>,
+    /// Index of the first step of this session among the
+    /// workout's steps.
+    pub first_step_index: Option<u16>,
+    /// Metres.
+    pub pool_length:      Option<f64>,
+    /// The unit pool lengths should be displayed in.
+    pub pool_length_unit: Option<DisplayMeasure>,
+}
+
+impl WorkoutSession {
+    /// Flatten a decoded `WorkoutSession` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `WorkoutSession`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<WorkoutSession> {
+        let mut session = WorkoutSession::default();
+        for field in mesg.0.iter() {
+            match field {
+                Message::WorkoutSession(field) => {
+                    match field {
+                        WorkoutSessionField::Sport(f) => {
+                            session.sport = Some(f.raw_value.clone())
+                        },
+                        WorkoutSessionField::SubSport(f) => {
+                            session.sub_sport = Some(f.raw_value.clone())
+                        },
+                        WorkoutSessionField::NumValidSteps(f)
+                            if f.is_valid() =>
+                        {
+                            session.num_valid_steps = Some(f.raw_value.0)
+                        },
+                        WorkoutSessionField::FirstStepIndex(f)
+                            if f.is_valid() =>
+                        {
+                            session.first_step_index = Some(f.raw_value.0)
+                        },
+                        WorkoutSessionField::PoolLength(f) if f.is_valid() => {
+                            session.pool_length = Some(f.value())
+                        },
+                        WorkoutSessionField::PoolLengthUnit(f) => {
+                            session.pool_length_unit = Some(f.raw_value.clone())
+                        },
+                        _ => (),
+                    }
+                },
+                _ => return None,
+            }
+        }
+        Some(session)
+    }
+}
+
+/// How long a step lasts, with the duration value scaled
+/// according to the duration type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepDuration {
+    /// Seconds.
+    Time(f64),
+    /// Metres.
+    Distance(f64),
+    /// Until the lap button is pressed.
+    Open,
+    /// Any other duration type, with its raw value.
+    Other(Option<u32>),
+}
+
+impl Default for StepDuration {
+    fn default() -> Self {
+        StepDuration::Other(None)
+    }
+}
+
+/// The fields of a `WorkoutStep` message.
+#[derive(Debug, Clone, Default)]
+pub struct WorkoutStep {
+    pub name:      Option<String>,
+    pub duration:  StepDuration,
+    pub intensity: Option<Intensity>,
+    pub notes:     Option<String>,
+}
+
+impl WorkoutStep {
+    /// Flatten a decoded `WorkoutStep` data message.
+    ///
+    /// Returns `None` if `mesg` isn't a `WorkoutStep`
+    /// message.
+    pub fn from_message(mesg: &Data) -> Option<WorkoutStep> {
+        let mut step = WorkoutStep::default();
+        let mut duration_type = None;
+        let mut duration_value = None;
+
+        for field in mesg.0.iter() {
+            match field {
+                Message::WorkoutStep(field) => {
+                    match field {
+                        WorkoutStepField::WktStepName(f) => {
+                            step.name = non_empty(&f.raw_value.0)
+                        },
+                        WorkoutStepField::DurationType(f) => {
+                            duration_type = Some(f.raw_value.clone())
+                        },
+                        WorkoutStepField::DurationValue(f) if f.is_valid() => {
+                            duration_value = Some(f.raw_value.0)
+                        },
+                        WorkoutStepField::Intensity(f) => {
+                            step.intensity = Some(f.raw_value.clone())
+                        },
+                        WorkoutStepField::Notes(f) => {
+                            step.notes = non_empty(&f.raw_value.0)
+                        },
+                        _ => (),
+                    }
+                },
+                _ => return None,
+            }
+        }
+
+        // Scaled like the duration_time and duration_distance
+        // subfields
+        step.duration = match (duration_type, duration_value) {
+            (Some(WktStepDuration::Time), Some(value)) => {
+                StepDuration::Time(f64::from(value) / 1000.0)
+            },
+            (Some(WktStepDuration::Distance), Some(value)) => {
+                StepDuration::Distance(f64::from(value) / 100.0)
+            },
+            (Some(WktStepDuration::Open), _) => StepDuration::Open,
+            (_, value) => StepDuration::Other(value),
+        };
+        Some(step)
+    }
+}
+
+/// One leg of a workout and the steps belonging to it.
+#[derive(Debug, Clone)]
+pub struct WorkoutLeg {
+    pub session: WorkoutSession,
+    pub steps:   Vec<WorkoutStep>,
+}
+
+impl WorkoutLeg {
+    /// Format a distance in the units this leg should be
+    /// displayed in: whole metres or yards for pool legs,
+    /// kilometres or miles otherwise.
+    pub fn format_distance(&self, metres: f64) -> String {
+        let statute = matches!(
+            self.session.pool_length_unit,
+            Some(DisplayMeasure::Statute)
+        );
+        match (self.session.pool_length.is_some(), statute) {
+            (true, false) => format!("{:.0} m", metres),
+            (true, true) => format!("{:.0} yd", metres / YARD_M),
+            (false, false) => format!("{:.2} km", metres / 1000.0),
+            (false, true) => format!("{:.2} mi", metres / MILE_M),
+        }
+    }
+}
+
+/// A whole workout, split into legs.
+#[derive(Debug, Clone)]
+pub struct WorkoutPlan {
+    pub workout: Workout,
+    pub legs:    Vec<WorkoutLeg>,
+}
+
+impl WorkoutPlan {
+    /// Assemble the workout in `messages`, or `None` if
+    /// there's no `Workout` message.
+    ///
+    /// Each `WorkoutSession` becomes a leg owning the steps
+    /// from its `first_step_index`, counted in file order.
+    /// A workout without sessions is a single leg with
+    /// the workout's own sport and pool length.
+    pub fn from_messages(messages: &[Data]) -> Option<WorkoutPlan> {
+        let workout = messages.iter().find_map(Workout::from_message)?;
+        let steps: Vec<WorkoutStep> =
+            messages.iter().filter_map(WorkoutStep::from_message).collect();
+        let mut sessions: Vec<WorkoutSession> =
+            messages.iter().filter_map(WorkoutSession::from_message).collect();
+
+        if sessions.is_empty() {
+            sessions.push(WorkoutSession {
+                sport:            workout.sport.clone(),
+                sub_sport:        workout.sub_sport.clone(),
+                num_valid_steps:  Some(steps.len() as u16),
+                first_step_index: Some(0),
+                pool_length:      workout.pool_length,
+                pool_length_unit: workout.pool_length_unit.clone(),
+            });
+        }
+
+        let legs = sessions
+            .into_iter()
+            .map(|session| {
+                let first = usize::from(session.first_step_index.unwrap_or(0));
+                let count = usize::from(session.num_valid_steps.unwrap_or(0));
+                let steps =
+                    steps.iter().skip(first).take(count).cloned().collect();
+                WorkoutLeg {
+                    session,
+                    steps,
+                }
+            })
+            .collect();
+
+        Some(WorkoutPlan {
+            workout,
+            legs,
+        })
+    }
+}
+
+/// Strings are null padded to their field size.
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim_end_matches('\0');
+    if s.is_empty() {
+        None
+    }
+    else {
+        Some(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testutil::mesg;
+
+    fn step(duration_type: u8, value: u32) -> Data {
+        mesg(27).u8(1, duration_type).u32(2, value).u8(7, 0).build()
+    }
+
+    /// A sprint triathlon brick as exported by Garmin
+    /// Connect: 750 yd swim, 20 km bike, 5 km run.
+    fn triathlon() -> Vec<Data> {
+        vec![
+            mesg(0).u8(0, 5).build(),
+            mesg(26)
+                .bytes(8, b"Sprint tri\0\0\0\0\0\0")
+                .u8(4, 18)
+                .u16(6, 5)
+                .build(),
+            mesg(158)
+                .u16(254, 0)
+                .u8(0, 5)
+                .u8(1, 17)
+                .u16(2, 2)
+                .u16(3, 0)
+                .u16(4, 2286) // 25 yd
+                .u8(5, 1)
+                .build(),
+            mesg(158).u16(254, 1).u8(0, 2).u16(2, 2).u16(3, 2).build(),
+            mesg(158).u16(254, 2).u8(0, 1).u16(2, 1).u16(3, 4).build(),
+            step(1, 34_290),  // 375 yd
+            step(1, 34_290),  // 375 yd
+            step(0, 600_000), // 10 min warm up
+            step(1, 2_000_000),
+            step(1, 500_000),
+        ]
+    }
+
+    #[test]
+    fn multisport_legs() {
+        let plan = WorkoutPlan::from_messages(&triathlon()).unwrap();
+        assert_eq!(plan.workout.name, Some(String::from("Sprint tri")));
+        assert_eq!(plan.legs.len(), 3);
+
+        let swim = &plan.legs[0];
+        assert!(matches!(swim.session.sport, Some(Sport::Swimming)));
+        assert!(matches!(swim.session.sub_sport, Some(SubSport::LapSwimming)));
+        assert_eq!(swim.session.pool_length, Some(22.86));
+        assert_eq!(swim.steps.len(), 2);
+        assert_eq!(swim.steps[0].duration, StepDuration::Distance(342.9));
+        assert_eq!(swim.format_distance(342.9), "375 yd");
+
+        let bike = &plan.legs[1];
+        assert!(matches!(bike.session.sport, Some(Sport::Cycling)));
+        assert_eq!(bike.steps[0].duration, StepDuration::Time(600.0));
+        assert_eq!(bike.steps[1].duration, StepDuration::Distance(20_000.0));
+        assert_eq!(bike.format_distance(20_000.0), "20.00 km");
+
+        let run = &plan.legs[2];
+        assert!(matches!(run.session.sport, Some(Sport::Running)));
+        assert_eq!(run.steps.len(), 1);
+        assert_eq!(run.steps[0].duration, StepDuration::Distance(5000.0));
+    }
+
+    #[test]
+    fn single_sport_workout() {
+        let messages = vec![
+            mesg(26).u8(4, 5).u16(14, 2500).u8(15, 0).build(),
+            step(1, 10_000),
+            step(5, 0),
+        ];
+        let plan = WorkoutPlan::from_messages(&messages).unwrap();
+        assert_eq!(plan.legs.len(), 1);
+
+        let leg = &plan.legs[0];
+        assert_eq!(leg.session.pool_length, Some(25.0));
+        assert_eq!(leg.steps[1].duration, StepDuration::Open);
+        assert_eq!(leg.format_distance(100.0), "100 m");
+
+        assert!(WorkoutPlan::from_messages(&messages[1..]).is_none());
+    }
+}
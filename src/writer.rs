@@ -0,0 +1,375 @@
+//! Writing decoded files back out as FIT bytes.
+//!
+//! [`encode`] writes a [`File`] record for record as it was
+//! decoded, so a batch of files can be decoded, changed
+//! through [`File::messages_mut`] and written out again.
+//! Definitions keep the layout they were read with: the
+//! same local message numbers, byte order, fields, sizes
+//! and base types. Fields the profile knows are written
+//! from their values. Everything else is written as the
+//! bytes it was read from: the fields of messages the
+//! profile doesn't know, such as vendor messages, fields it
+//! doesn't know in messages it does, and developer fields.
+//! Decoding the result gives the same messages as decoding
+//! the original, and a file from a well behaved encoder
+//! comes back byte for byte.
+//!
+//! ```no_run
+//! use garminfit::{
+//!     types::file::File,
+//!     writer,
+//! };
+//!
+//! # fn main() -> garminfit::Result<()> {
+//! let bytes = std::fs::read("activity.fit").expect("a readable file");
+//! let mut file = File::from_bytes(&bytes)?;
+//! for _mesg in file.messages_mut() {
+//!     // Change what needs changing
+//! }
+//! let written = writer::encode(&file)?;
+//! std::fs::write("rewritten.fit", written).expect("a writable file");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Some things don't survive the trip:
+//!
+//! - Header bytes past the first 12 of a nonstandard size
+//!   header, which is written as the usual 14 bytes.
+//! - Earlier occurrences of a field a definition lists more
+//!   than once. Only the last was decoded, so the others
+//!   are left out of the definition.
+//! - Values of profile enums that the profile doesn't name,
+//!   which decode as `Unknown` and are written as a value
+//!   it doesn't name either.
+//! - Strings that aren't valid UTF-8, and the elements of a
+//!   field the profile gives one value that hold more.
+//!   Whatever a value doesn't fill of its field is written
+//!   as the base type's invalid value.
+//!
+//! [`encode`]: fn.encode.html
+//! [`File`]: ../types/file/struct.File.html
+//! [`File::messages_mut`]: ../types/file/struct.File.html#method.messages_mut
+use crc::CrcWriter;
+use error::{
+    Error,
+    Result,
+};
+use profile::{
+    base::{
+        base_type_size,
+        invalid_raw,
+    },
+    messages::Message as Field,
+};
+use std::{
+    collections::HashMap,
+    io::Write,
+};
+use types::{
+    file::File,
+    record::{
+        Data,
+        Definition,
+        FieldDefinition,
+        Header,
+        Message,
+        DEVELOPER_FIELD_NUM,
+    },
+};
+
+/// Encode `file` as FIT bytes, header and CRCs included.
+///
+/// Fails with a missing definition error if a data message
+/// comes before any definition of its local message
+/// number, which only happens to records put together by
+/// hand.
+pub fn encode(file: &File) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut definitions: HashMap<u8, &Definition> = HashMap::new();
+    for record in &file.records {
+        let local = record.header.local_mesg_num();
+        match record.content {
+            Message::Definition(ref definition) => {
+                write_definition(&mut data, local, definition);
+                definitions.insert(local, definition);
+            },
+            Message::Data(ref mesg) => {
+                let definition = definitions
+                    .get(&local)
+                    .ok_or_else(|| Error::missing_definition(local))?;
+                data.push(local);
+                write_data(&mut data, definition, mesg);
+            },
+            // Only the header byte was read
+            Message::CompressedTimestamp => {
+                if let Header::CompressedTimestamp {
+                    local_mesg_num,
+                    time_offset,
+                } = record.header
+                {
+                    data.push(0x80 | local_mesg_num << 5 | time_offset);
+                }
+            },
+        }
+    }
+
+    let header = file.header.encode(data.len() as u32);
+    let mut out =
+        CrcWriter::new(Vec::with_capacity(header.len() + data.len() + 2));
+    // Writing to a Vec can't fail
+    let _ = out.write_all(&header);
+    let _ = out.write_all(&data);
+    let crc = out.crc();
+    let mut bytes = out.into_inner();
+    bytes.extend_from_slice(&crc.to_le_bytes());
+    Ok(bytes)
+}
+
+/// Decode the FIT file in `bytes` and encode it again, as
+/// [`encode`] does.
+///
+/// [`encode`]: fn.encode.html
+pub fn reencode(bytes: &[u8]) -> Result<Vec<u8>> {
+    encode(&File::from_bytes(bytes)?)
+}
+
+/// The fields of `definition` that data messages hold
+/// values for.
+fn decoded_fields(
+    definition: &Definition,
+) -> impl Iterator<Item = &FieldDefinition> {
+    definition.field_defs().iter().filter(|def| !def.is_superseded())
+}
+
+fn write_definition(out: &mut Vec<u8>, local: u8, definition: &Definition) {
+    let developer = definition.developer_field_defs();
+    let flag = if developer.is_some() { 0x20 } else { 0 };
+    out.extend_from_slice(&[0x40 | flag | local, 0]);
+    let mesg_num = definition.global_mesg_num();
+    if definition.is_big_endian() {
+        out.push(1);
+        out.extend_from_slice(&mesg_num.to_be_bytes());
+    }
+    else {
+        out.push(0);
+        out.extend_from_slice(&mesg_num.to_le_bytes());
+    }
+
+    out.push(decoded_fields(definition).count() as u8);
+    for def in decoded_fields(definition) {
+        out.extend_from_slice(&[def.num(), def.size(), def.base_type_num()]);
+    }
+    if let Some(developer) = developer {
+        out.push(developer.len() as u8);
+        for def in developer {
+            let index = def.developer_data_index().unwrap_or(0);
+            out.extend_from_slice(&[def.num(), def.size(), index]);
+        }
+    }
+}
+
+/// The fields of `mesg` laid out as `definition` says.
+/// Fields are found by number, so a message whose fields
+/// were added to or taken from still lines up.
+fn write_data(out: &mut Vec<u8>, definition: &Definition, mesg: &Data) {
+    let big_endian = definition.is_big_endian();
+    for def in decoded_fields(definition) {
+        let field = mesg.0.iter().find(|field| {
+            field.field_def_num() == def.num()
+                && def.num() != DEVELOPER_FIELD_NUM
+        });
+        write_field(out, def, field, big_endian);
+    }
+
+    let mut developer = mesg
+        .0
+        .iter()
+        .filter(|field| field.field_def_num() == DEVELOPER_FIELD_NUM);
+    for def in definition.developer_field_defs().unwrap_or(&[]) {
+        write_field(out, def, developer.next(), big_endian);
+    }
+}
+
+/// `field` in exactly the `def.size()` bytes its definition
+/// gives it.
+fn write_field(
+    out: &mut Vec<u8>,
+    def: &FieldDefinition,
+    field: Option<&Field>,
+    big_endian: bool,
+) {
+    let start = out.len();
+    if let Some(field) = field {
+        field.encode(out);
+        // Profile fields encode little endian, while the
+        // rest are the bytes they were read from
+        if big_endian && !field.is_unknown() {
+            let element = base_type_size(def.base_type_num());
+            for value in out[start..].chunks_mut(element) {
+                value.reverse();
+            }
+        }
+    }
+
+    let size = usize::from(def.size());
+    let written = out.len() - start;
+    if written > size {
+        out.truncate(start + size);
+    }
+    else {
+        let invalid = invalid_raw(def.base_type_num(), size, big_endian);
+        out.extend_from_slice(&invalid[written..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use profile::messages::Record as RecordField;
+    use testutil::{
+        duplicate_field_file,
+        record_file,
+        FitBuilder,
+    };
+    use types::field::Value;
+
+    /// Each field of each data message, as the SDK's CSV
+    /// tool lists them: message, field and value.
+    fn dump(bytes: &[u8]) -> Vec<(u16, u8, Value)> {
+        File::from_bytes(bytes)
+            .unwrap()
+            .messages()
+            .flat_map(|mesg| mesg.0.iter())
+            .map(|field| {
+                (field.mesg_num(), field.field_def_num(), field.value())
+            })
+            .collect()
+    }
+
+    /// A vendor message the profile doesn't know, big
+    /// endian with a string, then a record with
+    /// developer fields.
+    fn vendor_file() -> Vec<u8> {
+        let mut fit = FitBuilder::new();
+        fit.definition(0, 0, &[(0, 1, 0x00), (1, 2, 0x84)]);
+        fit.data(0, &[&[4], &255u16.to_le_bytes()]);
+        fit.big_endian_definition(
+            1,
+            0xFF10,
+            &[(0, 2, 0x84), (1, 4, 0x85), (2, 6, 0x07)],
+        );
+        fit.data(
+            1,
+            &[&300u16.to_be_bytes(), &(-7i32).to_be_bytes(), b"wax\0\0\0"],
+        );
+        // A big endian record with a field the profile
+        // doesn't know and two developer fields
+        fit.raw(&[
+            0x62, 0, 1, 0, 20, 3, 253, 4, 0x86, 3, 1, 0x02, 200, 2, 0x84,
+        ]);
+        fit.raw(&[2, 0, 2, 0, 1, 1, 1]);
+        fit.data(
+            2,
+            &[
+                &1000u32.to_be_bytes(),
+                &[150],
+                &0xABCDu16.to_be_bytes(),
+                &[1, 2],
+                &[3],
+            ],
+        );
+        fit.build()
+    }
+
+    #[test]
+    fn files_come_back_byte_for_byte() {
+        for bytes in [record_file(20), vendor_file()] {
+            assert_eq!(reencode(&bytes).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn changes_are_written() {
+        let bytes = vendor_file();
+        let mut file = File::from_bytes(&bytes).unwrap();
+        for mesg in file.messages_mut() {
+            for field in mesg.0.iter_mut() {
+                if let Field::Record(RecordField::HeartRate(f)) = field {
+                    f.raw_value.0 = 160;
+                }
+            }
+        }
+        let written = encode(&file).unwrap();
+        assert_eq!(written.len(), bytes.len());
+
+        let (before, after) = (dump(&bytes), dump(&written));
+        assert_eq!(before.len(), after.len());
+        let changed: Vec<_> = before
+            .iter()
+            .zip(&after)
+            .filter(|(before, after)| before != after)
+            .map(|(_, after)| after.clone())
+            .collect();
+        assert_eq!(changed, vec![(20, 3, Value::Number(160.0))]);
+    }
+
+    #[test]
+    fn duplicate_fields_are_dropped() {
+        let bytes = duplicate_field_file();
+        let written = reencode(&bytes).unwrap();
+        assert!(written.len() < bytes.len());
+        assert_eq!(dump(&written), dump(&bytes));
+        assert_eq!(reencode(&written).unwrap(), written);
+    }
+
+    #[test]
+    fn missing_definition() {
+        let mut file = File::from_bytes(&record_file(1)).unwrap();
+        file.records.remove(0);
+        assert!(encode(&file).is_err());
+    }
+
+    /// Every file in `testdata`, from `make testdata`, and
+    /// generated ones decode the same after re-encoding,
+    /// and re-encoding again changes nothing.
+    #[test]
+    fn corpus() {
+        let dir =
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let mut corpus: Vec<Vec<u8>> = ::std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "fit"))
+            .filter_map(|path| ::std::fs::read(path).ok())
+            .filter(|bytes| File::from_bytes(bytes).is_ok())
+            .collect();
+        corpus.extend(vec![
+            record_file(100),
+            vendor_file(),
+            duplicate_field_file(),
+        ]);
+        #[cfg(feature = "testgen")]
+        {
+            use testgen::{
+                generate,
+                Shape,
+            };
+            let big_endian = Shape {
+                big_endian: true,
+                ..Shape::default()
+            };
+            for seed in 0..5 {
+                corpus.push(generate(&Shape::default(), seed));
+                corpus.push(generate(&big_endian, seed));
+            }
+        }
+
+        for bytes in corpus {
+            let written = reencode(&bytes).unwrap();
+            assert_eq!(dump(&written), dump(&bytes));
+            assert_eq!(reencode(&written).unwrap(), written);
+        }
+    }
+}